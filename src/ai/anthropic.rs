@@ -2,7 +2,9 @@
 //!
 //! Access to Claude models via Anthropic's API.
 
-use super::provider::AiProvider;
+use std::sync::mpsc::Sender;
+
+use super::provider::{read_event_stream, AiProvider};
 use anyhow::{anyhow, Result};
 
 const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
@@ -98,4 +100,51 @@ impl AiProvider for AnthropicProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response content from Anthropic"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let api_key = self
+            .get_or_prompt_api_key()
+            .ok_or_else(|| anyhow!("{}", self.get_setup_instructions()))?;
+
+        let model = model.unwrap_or(DEFAULT_MODEL);
+
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .post(ANTHROPIC_API_URL)
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", API_VERSION)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_tokens": 2048,
+                "stream": true,
+                "messages": [
+                    {"role": "user", "content": prompt}
+                ]
+            }))
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .map_err(|e| anyhow!("Failed to connect to Anthropic: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(anyhow!("Anthropic API error ({}): {}", status, body));
+        }
+
+        // Text arrives in content_block_delta events; errors can too
+        read_event_stream(response, chunks, |json| match json["type"].as_str() {
+            Some("content_block_delta") => Ok(json["delta"]["text"].as_str()),
+            Some("error") => Err(anyhow!(
+                "Anthropic API error: {}",
+                json["error"]["message"].as_str().unwrap_or("unknown error")
+            )),
+            _ => Ok(None),
+        })
+    }
 }
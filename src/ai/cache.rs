@@ -0,0 +1,227 @@
+//! AI response cache
+//!
+//! Remembers recent answers keyed by provider, persona, normalized prompt,
+//! and a hash of the terminal context. Repeated questions are answered
+//! instantly, and stale answers are still served when the provider is
+//! unreachable.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of cached answers kept on disk
+const MAX_ENTRIES: usize = 200;
+
+/// A cached answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The (normalized) question, for `ai cache` listings
+    pub prompt: String,
+    /// Provider display name that produced the answer
+    pub provider: String,
+    /// The answer text
+    pub response: String,
+    /// Unix timestamp when the answer was stored
+    pub created_at: u64,
+}
+
+impl CacheEntry {
+    /// Age of this entry in seconds
+    pub fn age_secs(&self) -> u64 {
+        now_secs().saturating_sub(self.created_at)
+    }
+}
+
+/// Result of a cache lookup
+pub enum CacheLookup {
+    /// Entry exists and is within the TTL
+    Fresh(CacheEntry),
+    /// Entry exists but has expired (still useful when offline)
+    Stale(CacheEntry),
+    /// Nothing cached for this key
+    Miss,
+}
+
+/// On-disk AI response cache
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AiCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl AiCache {
+    /// Load the cache from disk (empty if missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::cache_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the cache to disk
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.save_to(&Self::cache_path())
+    }
+
+    /// Save the cache to `path`
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Path of the cache file
+    pub fn cache_path() -> PathBuf {
+        crate::config::paths::data_dir().join("ai_cache.json")
+    }
+
+    /// Build a cache key from everything that influences the answer
+    pub fn key(provider: &str, persona: &str, prompt: &str, context: &str) -> String {
+        let mut hasher = blake3::Hasher::new();
+        for part in [provider, persona, &normalize_prompt(prompt), context] {
+            hasher.update(part.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Look up an entry, classifying it against the TTL
+    pub fn lookup(&self, key: &str, ttl_secs: u64) -> CacheLookup {
+        match self.entries.get(key) {
+            Some(entry) if entry.age_secs() <= ttl_secs => CacheLookup::Fresh(entry.clone()),
+            Some(entry) => CacheLookup::Stale(entry.clone()),
+            None => CacheLookup::Miss,
+        }
+    }
+
+    /// Store an answer, evicting the oldest entries beyond the size limit
+    pub fn insert(&mut self, key: String, prompt: &str, provider: &str, response: &str) {
+        self.entries.insert(
+            key,
+            CacheEntry {
+                prompt: normalize_prompt(prompt),
+                provider: provider.to_string(),
+                response: response.to_string(),
+                created_at: now_secs(),
+            },
+        );
+
+        while self.entries.len() > MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.created_at)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(k) => self.entries.remove(&k),
+                None => break,
+            };
+        }
+    }
+
+    /// Remove all entries, returning how many were removed
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+
+    /// Number of cached answers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries sorted newest first
+    pub fn recent(&self) -> Vec<&CacheEntry> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+        entries
+    }
+}
+
+/// Normalize a prompt so trivially different phrasings share a cache slot
+pub fn normalize_prompt(prompt: &str) -> String {
+    prompt
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(['?', '!', '.'])
+        .to_string()
+}
+
+/// Human-readable age, e.g. "5m ago"
+pub fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86_400)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_prompt() {
+        assert_eq!(
+            normalize_prompt("  What   is a HashMap?  "),
+            "what is a hashmap"
+        );
+    }
+
+    #[test]
+    fn test_key_ignores_formatting_but_not_context() {
+        let a = AiCache::key("groq", "assistant", "what is dns?", "cwd: /a");
+        let b = AiCache::key("groq", "assistant", "What is  DNS", "cwd: /a");
+        let c = AiCache::key("groq", "assistant", "what is dns", "cwd: /b");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_lookup_respects_ttl() {
+        let mut cache = AiCache::default();
+        cache.insert("k".to_string(), "q", "Groq", "answer");
+        assert!(matches!(cache.lookup("k", 60), CacheLookup::Fresh(_)));
+        assert!(matches!(cache.lookup("missing", 60), CacheLookup::Miss));
+
+        cache.entries.get_mut("k").unwrap().created_at = 0;
+        assert!(matches!(cache.lookup("k", 60), CacheLookup::Stale(_)));
+    }
+
+    #[test]
+    fn test_insert_evicts_oldest() {
+        let mut cache = AiCache::default();
+        for i in 0..MAX_ENTRIES + 5 {
+            cache.insert(format!("k{}", i), "q", "Groq", "a");
+            cache
+                .entries
+                .get_mut(&format!("k{}", i))
+                .unwrap()
+                .created_at = i as u64;
+        }
+        assert_eq!(cache.len(), MAX_ENTRIES);
+        assert!(!cache.entries.contains_key("k0"));
+    }
+}
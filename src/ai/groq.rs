@@ -3,7 +3,9 @@
 //! Fast inference with free tier (30 requests/minute).
 //! Uses OpenAI-compatible API format.
 
-use super::provider::AiProvider;
+use std::sync::mpsc::Sender;
+
+use super::provider::{stream_chat_completion, AiProvider};
 use anyhow::{anyhow, Result};
 
 const GROQ_API_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
@@ -97,4 +99,17 @@ impl AiProvider for GroqProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response content from Groq"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let api_key = self
+            .get_or_prompt_api_key()
+            .ok_or_else(|| anyhow!("{}", self.get_setup_instructions()))?;
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        stream_chat_completion("Groq", GROQ_API_URL, &api_key, model, prompt, chunks)
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! Access to Mistral models via their API.
 
-use super::provider::AiProvider;
+use std::sync::mpsc::Sender;
+
+use super::provider::{stream_chat_completion, AiProvider};
 use anyhow::{anyhow, Result};
 
 const MISTRAL_API_URL: &str = "https://api.mistral.ai/v1/chat/completions";
@@ -96,4 +98,17 @@ impl AiProvider for MistralProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response content from Mistral"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let api_key = self
+            .get_or_prompt_api_key()
+            .ok_or_else(|| anyhow!("{}", self.get_setup_instructions()))?;
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        stream_chat_completion("Mistral", MISTRAL_API_URL, &api_key, model, prompt, chunks)
+    }
 }
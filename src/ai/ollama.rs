@@ -7,6 +7,7 @@ use super::provider::AiProvider;
 use anyhow::{anyhow, Result};
 use std::io::{BufRead, BufReader};
 use std::process::Command;
+use std::sync::mpsc::Sender;
 
 const OLLAMA_API: &str = "http://localhost:11434";
 const DEFAULT_MODEL: &str = "llama3.2";
@@ -137,9 +138,8 @@ impl OllamaProvider {
         }
     }
 
-    /// Chat with streaming (for future use)
-    #[allow(dead_code)]
-    pub fn chat_stream<F>(prompt: &str, model: Option<&str>, mut on_chunk: F) -> Result<()>
+    /// Generate a response, passing each piece to `on_chunk` as it arrives
+    pub fn generate_stream<F>(prompt: &str, model: Option<&str>, mut on_chunk: F) -> Result<()>
     where
         F: FnMut(&str),
     {
@@ -173,6 +173,9 @@ impl OllamaProvider {
             let json: serde_json::Value =
                 serde_json::from_str(&line).map_err(|e| anyhow!("Failed to parse JSON: {}", e))?;
 
+            if let Some(error) = json["error"].as_str() {
+                return Err(anyhow!("Ollama error: {}", error));
+            }
             if let Some(text) = json["response"].as_str() {
                 on_chunk(text);
             }
@@ -259,4 +262,18 @@ impl AiProvider for OllamaProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response from Ollama"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let mut response = String::new();
+        Self::generate_stream(prompt, model, |text| {
+            response.push_str(text);
+            let _ = chunks.send(text.to_string());
+        })?;
+        Ok(response)
+    }
 }
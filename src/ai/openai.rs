@@ -2,7 +2,9 @@
 //!
 //! Access to GPT models via OpenAI's API.
 
-use super::provider::AiProvider;
+use std::sync::mpsc::Sender;
+
+use super::provider::{stream_chat_completion, AiProvider};
 use anyhow::{anyhow, Result};
 
 const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
@@ -98,4 +100,17 @@ impl AiProvider for OpenAIProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response content from OpenAI"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let api_key = self
+            .get_or_prompt_api_key()
+            .ok_or_else(|| anyhow!("{}", self.get_setup_instructions()))?;
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        stream_chat_completion("OpenAI", OPENAI_API_URL, &api_key, model, prompt, chunks)
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! Search-augmented AI with real-time information.
 
-use super::provider::AiProvider;
+use std::sync::mpsc::Sender;
+
+use super::provider::{stream_chat_completion, AiProvider};
 use anyhow::{anyhow, Result};
 
 const PERPLEXITY_API_URL: &str = "https://api.perplexity.ai/chat/completions";
@@ -94,4 +96,24 @@ impl AiProvider for PerplexityProvider {
             .map(|s| s.to_string())
             .ok_or_else(|| anyhow!("No response content from Perplexity"))
     }
+
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let api_key = self
+            .get_or_prompt_api_key()
+            .ok_or_else(|| anyhow!("{}", self.get_setup_instructions()))?;
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        stream_chat_completion(
+            "Perplexity",
+            PERPLEXITY_API_URL,
+            &api_key,
+            model,
+            prompt,
+            chunks,
+        )
+    }
 }
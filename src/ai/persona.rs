@@ -0,0 +1,230 @@
+//! AI personas
+//!
+//! System-prompt templates that shape how the assistant answers.
+//! Selected per-prompt with `# --persona <name> ...` or via `ai.default_persona`.
+//!
+//! Templates interpolate `{context}` (terminal context) and `{question}`
+//! (the user's prompt).
+
+use crate::config::settings::Config;
+
+/// Name of the persona used when nothing else is selected
+pub const DEFAULT_PERSONA: &str = "assistant";
+
+/// A named prompt template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Persona {
+    /// Persona name (used with --persona)
+    pub name: String,
+    /// Short description for `ai personas`
+    pub description: String,
+    /// Prompt template with {context} and {question} placeholders
+    pub template: String,
+    /// Whether this persona comes from the user's config
+    pub custom: bool,
+}
+
+impl Persona {
+    fn builtin(name: &str, description: &str, template: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            template: template.to_string(),
+            custom: false,
+        }
+    }
+
+    /// Render the template with terminal context and the user's question
+    pub fn render(&self, context: &str, question: &str) -> String {
+        let rendered = self
+            .template
+            .replace("{context}", context)
+            .replace("{question}", question);
+
+        // Templates that forget {question} still get the question appended
+        if self.template.contains("{question}") {
+            rendered
+        } else {
+            format!("{}\n\nUser question: {}", rendered, question)
+        }
+    }
+}
+
+/// Built-in personas shipped with Zaxiom
+pub fn builtin_personas() -> Vec<Persona> {
+    vec![
+        Persona::builtin(
+            DEFAULT_PERSONA,
+            "Helpful terminal assistant (default)",
+            "You are a helpful terminal assistant. Here's the user's terminal context:\n\n{context}\n\nUser question: {question}\n\nProvide a concise, helpful response. If the user asks about commands, show examples. Keep responses brief but useful.",
+        ),
+        Persona::builtin(
+            "sysadmin",
+            "Terse sysadmin - commands first, no fluff",
+            "You are a terse, senior systems administrator. Terminal context:\n\n{context}\n\nQuestion: {question}\n\nAnswer with the exact command(s) first, then at most one short sentence of explanation. No greetings, no filler.",
+        ),
+        Persona::builtin(
+            "eli5",
+            "Explain like I'm five",
+            "You are a patient teacher explaining things to a complete beginner. Terminal context:\n\n{context}\n\nQuestion: {question}\n\nExplain it like I'm five: use simple words, a friendly analogy, and a tiny example. Avoid jargon or define it when you must use it.",
+        ),
+        Persona::builtin(
+            "reviewer",
+            "Code reviewer - bugs, risks, and improvements",
+            "You are a meticulous code reviewer. Terminal context:\n\n{context}\n\nRequest: {question}\n\nPoint out bugs, edge cases, security risks, and readability issues as a short bulleted list, most important first. Suggest concrete fixes.",
+        ),
+    ]
+}
+
+/// All personas: built-ins plus user-defined ones from config.
+/// A custom persona with the same name as a built-in replaces it.
+pub fn all_personas(config: &Config) -> Vec<Persona> {
+    let mut personas = builtin_personas();
+
+    let mut custom: Vec<_> = config.ai.personas.iter().collect();
+    custom.sort_by_key(|(name, _)| name.to_lowercase());
+
+    for (name, persona_config) in custom {
+        let persona = Persona {
+            name: name.to_lowercase(),
+            description: persona_config
+                .description
+                .clone()
+                .unwrap_or_else(|| "Custom persona".to_string()),
+            template: persona_config.template.clone(),
+            custom: true,
+        };
+
+        match personas.iter_mut().find(|p| p.name == persona.name) {
+            Some(existing) => *existing = persona,
+            None => personas.push(persona),
+        }
+    }
+
+    personas
+}
+
+/// Look up a persona by name (case-insensitive)
+pub fn find_persona(config: &Config, name: &str) -> Option<Persona> {
+    let name = name.to_lowercase();
+    all_personas(config).into_iter().find(|p| p.name == name)
+}
+
+/// Resolve the persona to use: explicit choice, then config default, then built-in default
+pub fn resolve_persona(config: &Config, requested: Option<&str>) -> Result<Persona, String> {
+    if let Some(name) = requested {
+        return find_persona(config, name).ok_or_else(|| {
+            format!(
+                "Unknown persona '{}'. Run 'ai personas' to see what's available.",
+                name
+            )
+        });
+    }
+
+    if let Some(name) = &config.ai.default_persona {
+        if let Some(persona) = find_persona(config, name) {
+            return Ok(persona);
+        }
+    }
+
+    Ok(builtin_personas().remove(0))
+}
+
+/// Extract a `--persona <name>` (or `--persona=<name>`) flag from the leading
+/// flags of an AI prompt. Returns (persona_name, remaining_input).
+pub fn parse_persona_flag(input: &str) -> (Option<String>, String) {
+    super::take_leading_flag(input, "--persona", Some("-p"))
+}
+
+/// Formatted list of personas for `ai personas`
+pub fn format_persona_list(config: &Config) -> String {
+    let default_name = config
+        .ai
+        .default_persona
+        .as_deref()
+        .map(|n| n.to_lowercase())
+        .unwrap_or_else(|| DEFAULT_PERSONA.to_string());
+
+    let mut output = String::from("\n🎭 AI Personas\n");
+    output.push_str("═══════════════════════════════════════\n\n");
+
+    for p in all_personas(config) {
+        let marker = if p.name == default_name { "★" } else { " " };
+        let origin = if p.custom { " (custom)" } else { "" };
+        output.push_str(&format!(
+            "  {} {:<12} {}{}\n",
+            marker, p.name, p.description, origin
+        ));
+    }
+
+    output.push_str("\nUsage: # --persona <name> <prompt>\n");
+    output.push_str("Example: # --persona sysadmin free up disk space\n");
+    output.push_str("\nAdd your own in config.toml:\n");
+    output.push_str("  [ai.personas.pirate]\n");
+    output.push_str("  description = \"Talks like a pirate\"\n");
+    output.push_str("  template = \"Answer like a pirate.\\n{context}\\n{question}\"\n");
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::PersonaConfig;
+
+    #[test]
+    fn test_parse_persona_flag() {
+        let (persona, rest) = parse_persona_flag("--persona sysadmin free disk space");
+        assert_eq!(persona.as_deref(), Some("sysadmin"));
+        assert_eq!(rest, "free disk space");
+
+        let (persona, rest) = parse_persona_flag("--groq --persona=eli5 what is dns");
+        assert_eq!(persona.as_deref(), Some("eli5"));
+        assert_eq!(rest, "--groq what is dns");
+
+        let (persona, rest) = parse_persona_flag("explain --persona flags");
+        assert_eq!(persona, None);
+        assert_eq!(rest, "explain --persona flags");
+    }
+
+    #[test]
+    fn test_render_interpolates() {
+        let persona = Persona::builtin("t", "test", "ctx={context} q={question}");
+        assert_eq!(persona.render("cwd", "why"), "ctx=cwd q=why");
+
+        let persona = Persona::builtin("t", "test", "Be brief. {context}");
+        assert_eq!(
+            persona.render("cwd", "why"),
+            "Be brief. cwd\n\nUser question: why"
+        );
+    }
+
+    #[test]
+    fn test_custom_persona_overrides_builtin() {
+        let mut config = Config::default();
+        config.ai.personas.insert(
+            "Sysadmin".to_string(),
+            PersonaConfig {
+                description: None,
+                template: "custom {question}".to_string(),
+            },
+        );
+
+        let persona = resolve_persona(&config, Some("sysadmin")).unwrap();
+        assert!(persona.custom);
+        assert_eq!(persona.template, "custom {question}");
+        assert!(resolve_persona(&config, Some("nope")).is_err());
+    }
+
+    #[test]
+    fn test_default_persona_from_config() {
+        let mut config = Config::default();
+        assert_eq!(
+            resolve_persona(&config, None).unwrap().name,
+            DEFAULT_PERSONA
+        );
+
+        config.ai.default_persona = Some("eli5".to_string());
+        assert_eq!(resolve_persona(&config, None).unwrap().name, "eli5");
+    }
+}
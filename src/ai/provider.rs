@@ -1,10 +1,14 @@
 //! AI Provider trait definition
 //!
-//! Defines the common interface for all AI providers.
+//! Defines the common interface for all AI providers, and the streaming
+//! helpers shared by the providers that speak the same wire format.
 
 #![allow(dead_code)]
 
-use anyhow::Result;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::mpsc::Sender;
+
+use anyhow::{anyhow, Result};
 
 /// Common trait for all AI providers
 pub trait AiProvider: Send + Sync {
@@ -32,6 +36,20 @@ pub trait AiProvider: Send + Sync {
     /// Send a chat message and get response (blocking)
     fn chat(&self, prompt: &str, model: Option<&str>) -> Result<String>;
 
+    /// Send a chat message, sending each piece of the response to `chunks`
+    /// as it arrives, and return the whole response once it is complete.
+    /// Providers without a streaming API send their answer as one chunk.
+    fn chat_stream(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        chunks: &Sender<String>,
+    ) -> Result<String> {
+        let response = self.chat(prompt, model)?;
+        let _ = chunks.send(response.clone());
+        Ok(response)
+    }
+
     /// Get API key from environment
     fn get_api_key(&self) -> Option<String> {
         std::env::var(self.api_key_env()).ok()
@@ -56,6 +74,74 @@ pub trait AiProvider: Send + Sync {
     }
 }
 
+/// Read a server-sent events body, sending the text `text_of` finds in each
+/// `data:` payload to `chunks`, and return all of it. Stops early at
+/// `data: [DONE]` or once nobody is listening.
+pub fn read_event_stream(
+    body: impl Read,
+    chunks: &Sender<String>,
+    text_of: impl Fn(&serde_json::Value) -> Result<Option<&str>>,
+) -> Result<String> {
+    let mut response = String::new();
+    for line in BufReader::new(body).lines() {
+        let line = line.map_err(|e| anyhow!("Failed to read response: {}", e))?;
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        if data == "[DONE]" {
+            break;
+        }
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(data) else {
+            continue;
+        };
+        if let Some(text) = text_of(&json)?.filter(|text| !text.is_empty()) {
+            response.push_str(text);
+            if chunks.send(text.to_string()).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(response)
+}
+
+/// Stream an OpenAI-style chat completion, the format OpenAI, Groq, Mistral,
+/// DeepSeek, xAI, and Perplexity all serve. `service` names the provider in
+/// errors.
+pub fn stream_chat_completion(
+    service: &str,
+    url: &str,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    chunks: &Sender<String>,
+) -> Result<String> {
+    let response = reqwest::blocking::Client::new()
+        .post(url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({
+            "model": model,
+            "messages": [
+                {"role": "user", "content": prompt}
+            ],
+            "max_tokens": 2048,
+            "stream": true
+        }))
+        .timeout(std::time::Duration::from_secs(120))
+        .send()
+        .map_err(|e| anyhow!("Failed to connect to {}: {}", service, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(anyhow!("{} API error ({}): {}", service, status, body));
+    }
+
+    read_event_stream(response, chunks, |json| {
+        Ok(json["choices"][0]["delta"]["content"].as_str())
+    })
+}
+
 /// Provider selection from command flags or config
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProviderChoice {
@@ -107,3 +193,37 @@ impl ProviderChoice {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_read_event_stream() {
+        let body = "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n\
+                    : keep-alive\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n\
+                    data: [DONE]\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"!\"}}]}\n";
+        let (tx, rx) = mpsc::channel();
+        let response = read_event_stream(body.as_bytes(), &tx, |json| {
+            Ok(json["choices"][0]["delta"]["content"].as_str())
+        })
+        .unwrap();
+
+        assert_eq!(response, "Hello");
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), ["Hel", "lo"]);
+
+        let error = read_event_stream(
+            "data: {\"error\":\"overloaded\"}\n".as_bytes(),
+            &tx,
+            |json| match json["error"].as_str() {
+                Some(message) => Err(anyhow!("{}", message)),
+                None => Ok(None),
+            },
+        );
+        assert_eq!(error.unwrap_err().to_string(), "overloaded");
+    }
+}
@@ -0,0 +1,166 @@
+//! Output summarization
+//!
+//! Condenses long command output (build logs, `git log`, test runs) with the
+//! configured AI provider. Large inputs are split into chunks that are
+//! summarized separately and then merged.
+
+use anyhow::{anyhow, Result};
+
+use super::{get_provider_from_choice, redact, ProviderChoice};
+use crate::config::settings::Config;
+
+/// Roughly how many characters go into a single request
+const CHUNK_CHARS: usize = 12_000;
+
+/// Upper bound on chunks so a runaway log can't fire off hundreds of requests
+const MAX_CHUNKS: usize = 8;
+
+/// Split text into chunks of at most `max_chars`, breaking on line boundaries
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        // Hard-wrap pathological single lines (minified JSON, etc.)
+        let mut line = line;
+        while line.len() > max_chars {
+            let mut split = max_chars;
+            while !line.is_char_boundary(split) {
+                split -= 1;
+            }
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(line[..split].to_string());
+            line = &line[split..];
+        }
+
+        if current.len() + line.len() + 1 > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Keep the first and last chunks when there are too many to send
+fn limit_chunks(mut chunks: Vec<String>, max: usize) -> (Vec<String>, usize) {
+    if chunks.len() <= max {
+        return (chunks, 0);
+    }
+    let omitted = chunks.len() - max;
+    let tail = chunks.split_off(chunks.len() - max / 2);
+    chunks.truncate(max - tail.len());
+    chunks.extend(tail);
+    (chunks, omitted)
+}
+
+/// Build the prompt for one chunk
+fn chunk_prompt(chunk: &str, focus: Option<&str>, part: Option<(usize, usize)>) -> String {
+    let mut prompt = String::from(
+        "Summarize the following terminal output concisely. Lead with the outcome \
+        (success, failure, key result), then list the most important details: errors, \
+        warnings, changed items, or numbers worth knowing. Use short bullet points and \
+        skip noise like progress bars and repeated lines.",
+    );
+    if let Some(focus) = focus {
+        prompt.push_str(&format!("\nFocus on: {}", focus));
+    }
+    if let Some((i, n)) = part {
+        prompt.push_str(&format!("\nThis is part {} of {} of the output.", i, n));
+    }
+    prompt.push_str("\n\n--- OUTPUT ---\n");
+    prompt.push_str(chunk);
+    prompt
+}
+
+/// Summarize text with the chosen provider. Returns (provider display name, summary).
+pub fn summarize(
+    text: &str,
+    choice: &ProviderChoice,
+    focus: Option<&str>,
+) -> Result<(String, String)> {
+    if text.trim().is_empty() {
+        return Err(anyhow!("nothing to summarize (pipe some output into it)"));
+    }
+
+    let config = Config::load();
+    let scrub = |s: String| {
+        if config.ai.privacy.redact_secrets {
+            redact::redact_secrets(&s)
+        } else {
+            s
+        }
+    };
+
+    let provider = get_provider_from_choice(choice);
+    let (chunks, omitted) = limit_chunks(chunk_text(text, CHUNK_CHARS), MAX_CHUNKS);
+
+    if chunks.len() == 1 {
+        let summary = provider.chat(&scrub(chunk_prompt(&chunks[0], focus, None)), None)?;
+        return Ok((provider.display_name().to_string(), summary));
+    }
+
+    // Map: summarize each chunk. Reduce: merge the partial summaries.
+    let total = chunks.len();
+    let mut partials = Vec::with_capacity(total);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let prompt = scrub(chunk_prompt(chunk, focus, Some((i + 1, total))));
+        partials.push(provider.chat(&prompt, None)?);
+    }
+
+    let mut merge = String::from(
+        "Combine these partial summaries of one long terminal output into a single \
+        concise summary. Lead with the overall outcome, then the key details as short \
+        bullet points. Remove duplicates.",
+    );
+    if let Some(focus) = focus {
+        merge.push_str(&format!("\nFocus on: {}", focus));
+    }
+    if omitted > 0 {
+        merge.push_str(&format!(
+            "\nNote: {} middle section(s) of the output were skipped for length.",
+            omitted
+        ));
+    }
+    for (i, partial) in partials.iter().enumerate() {
+        merge.push_str(&format!("\n\n--- PART {} ---\n{}", i + 1, partial));
+    }
+
+    let summary = provider.chat(&scrub(merge), None)?;
+    Ok((provider.display_name().to_string(), summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_respects_line_boundaries() {
+        let text = "aaaa\nbbbb\ncccc\n";
+        let chunks = chunk_text(text, 10);
+        assert_eq!(chunks, vec!["aaaa\nbbbb\n", "cccc\n"]);
+    }
+
+    #[test]
+    fn test_chunk_text_splits_long_lines() {
+        let text = "x".repeat(25);
+        let chunks = chunk_text(&text, 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.trim_end().len() <= 10));
+    }
+
+    #[test]
+    fn test_limit_chunks_keeps_head_and_tail() {
+        let chunks: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let (kept, omitted) = limit_chunks(chunks, 4);
+        assert_eq!(kept, vec!["0", "1", "8", "9"]);
+        assert_eq!(omitted, 6);
+    }
+}
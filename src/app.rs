@@ -3,33 +3,81 @@
 use arboard::Clipboard;
 use eframe::egui;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::LazyLock;
 
+use crate::ai::cache::format_age;
+use crate::ai::{prepare_ai_chat, AiChat, AiReply, AiStream};
 use crate::commands::files::EditorState;
 
 // Pre-compiled regexes for history expansion (performance optimization)
 static HISTORY_NEG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!-(\d+)").unwrap());
 static HISTORY_POS_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!(\d+)").unwrap());
-use crate::config::settings::Config;
-use crate::config::theme::{Theme, ThemeName};
-use crate::mascot::Mascot;
+use crate::config::keymap::{self, Action};
+use crate::config::settings::{
+    Config, KawaiiFeatures, KawaiiLevel, MenuStyle, TerminalConfig, TimestampMode,
+};
+use crate::config::theme::{ColorVision, Theme, ThemeName, UserTheme};
+use crate::git::branches;
+use crate::git::conflicts::{Region as ConflictRegion, Side as ConflictSide};
+use crate::git::panel::{Action as GitAction, GitPanel, Section as GitSection};
+use crate::git::watcher::{GitSnapshot, GitStatusWatcher};
+use crate::mascot::{Mascot, MascotMood};
 use crate::pty::{
-    input::char_to_bytes, input::key_to_bytes, InputMode, PtyBuffer, PtyOutput, PtySession,
-    TerminalGrid,
+    grid::Row as GridRow, input::char_to_bytes, input::key_to_bytes, InputMode, PtyBuffer,
+    PtyOutput, PtySession, TerminalGrid,
 };
+use crate::shell::alias::expand_aliases;
 use crate::shell::executor::{ExecutionTarget, Executor};
+use crate::shell::job::{ForegroundJob, Job, JobEvent, JobStatus};
+use crate::shell::parser::{
+    incomplete, new_pane_command, pending_heredoc, Incomplete, SyntaxError,
+};
+use crate::shell::restricted::{current_hostname, RestrictedMode};
+use crate::shell::script::{is_compound, is_unfinished};
 use crate::terminal::ansi;
-use crate::terminal::autocomplete::{Autocomplete, Suggestion, SuggestionKind};
-use crate::terminal::buffer::{LineType, OutputBuffer};
+use crate::terminal::audit::{AuditLog, AuditRecord};
+use crate::terminal::autocomplete::{usage_hint, Autocomplete, Suggestion, SuggestionKind};
+use crate::terminal::buffer::{CommandBlock, LineType, OutputBuffer, WARN_PERCENT};
+use crate::terminal::digest::Digest;
+use crate::terminal::export::{self, ExportFormat};
+use crate::terminal::filter::OutputFilter;
+use crate::terminal::format::{format_duration, format_size, truncate};
 use crate::terminal::fuzzy::{FuzzyAction, FuzzyFinder, FuzzyMode};
-use crate::terminal::hints::{HintType, HintsExtractor, HintsMode};
-use crate::terminal::palette::CommandPalette;
-use crate::terminal::session::{SavedSession, SavedTab, SessionManager};
-use crate::terminal::smart_history::SmartHistory;
-use crate::terminal::split::{SplitDirection, SplitManager};
-use crate::terminal::state::TerminalState;
-use crate::terminal::vi_mode::{ViAction, ViMode, ViState};
+use crate::terminal::hints::{is_git_hash, HintType, HintsExtractor, HintsMode};
+use crate::terminal::input::{self, EditAction, KillRing};
+use crate::terminal::keybindings::{self, CtrlHold};
+use crate::terminal::layout::Layout;
+use crate::terminal::minimap::{self, MarkerKind};
+use crate::terminal::output_view::{OutputView, Row};
+use crate::terminal::pager::Pager;
+use crate::terminal::palette::{self, CommandPalette};
+use crate::terminal::preview::{self, Preview, PreviewCache, PreviewStatus};
+use crate::terminal::render::{self, GridColors};
+use crate::terminal::session::{
+    saved_time, SavedPane, SavedSession, SavedTab, SessionManager, Workspace, WorkspacePane,
+    WorkspaceTab, LAST_SESSION,
+};
+use crate::terminal::smart_history::{self, SmartHistory};
+use crate::terminal::split::{SplitDirection, SplitManager, RESIZE_STEP};
+use crate::terminal::state::{
+    HistoryRequest, LayoutRequest, PaneContext, SessionRequest, StatsRequest, TerminalState,
+    TipsRequest, WorkspaceRequest, ZoomRequest,
+};
+use crate::terminal::syntax;
+use crate::terminal::tips::{Tip, TipEvent, TipsEngine};
+use crate::terminal::usage::{self, UsageEntry, UsageStats};
+use crate::terminal::vi_mode::{self, ViAction, ViMode, ViState};
+
+/// How long a pane must keep its size before the PTY is resized, so dragging
+/// a divider doesn't flood the running program with redraws
+const PTY_RESIZE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(80);
+
+/// How long the end-of-day digest toast stays up
+const DIGEST_TOAST_DURATION: std::time::Duration = std::time::Duration::from_secs(12);
+
+/// Rows in each of the `history stats` charts
+const HISTORY_STATS_ROWS: usize = 5;
 
 /// ASCII art logo only (shown after clear)
 const ASCII_LOGO: &str = r#"
@@ -70,6 +118,35 @@ const STARTUP_BANNER: &str = r#"
     ♪(´ε` ) Let's have fun together~
 "#;
 
+/// An AI answer arriving piece by piece
+struct StreamingAnswer {
+    stream: AiStream,
+    /// Header already shown above the answer
+    header: String,
+    /// Lines the raw streamed text takes up (0 until the first piece)
+    lines: usize,
+}
+
+/// A command whose block is still open, for `finish_command`
+struct CommandRun {
+    /// The command line as entered (after history expansion)
+    command: String,
+    cwd: std::path::PathBuf,
+    started: chrono::DateTime<chrono::Local>,
+    target: ExecutionTarget,
+}
+
+/// A built-in running on a worker thread, writing into the last block
+struct RunningJob {
+    job: Job,
+    run: CommandRun,
+    /// Latest progress message, shown beside the spinner
+    progress: Option<String>,
+}
+
+/// Spinner frames for a block whose job is still running
+const JOB_SPINNER: [&str; 4] = ["◐", "◓", "◑", "◒"];
+
 /// A single terminal pane within a tab
 pub struct PaneSession {
     /// Terminal state
@@ -82,8 +159,21 @@ pub struct PaneSession {
     pub input: String,
     /// Saved input when navigating history
     pub saved_input: String,
+    /// Text cut from the input line (Ctrl+W, Ctrl+K, ...) for Ctrl+Y
+    pub kill_ring: KillRing,
+    /// Lines entered so far of a command that isn't finished: one waiting
+    /// for its here-document to end, an `if`/`for`/`while`/function waiting
+    /// for its `fi`, `done`, or `}`, or a line left inside a quoted string
+    /// or ending in `\` or `|`
+    pub continuation: Option<String>,
     /// Whether this pane needs to scroll to bottom
     pub scroll_to_bottom: bool,
+    /// Scroll position (0.0 - 1.0) requested by a minimap click
+    pub minimap_jump: Option<f32>,
+    /// Largest scroll offset of the output last frame (for minimap jumps)
+    pub max_scroll_offset: f32,
+    /// Output line heights and galleys, so only the lines in view are laid out
+    pub output_view: OutputView,
     /// Search mode (Ctrl+F)
     pub search_mode: bool,
     /// Search query
@@ -92,6 +182,12 @@ pub struct PaneSession {
     pub search_matches: Vec<usize>,
     /// Current search match index
     pub current_match: usize,
+    /// Keep the scroll position while searching instead of following new output
+    pub search_pause_scroll: bool,
+    /// Scroll to the current match next frame
+    pub search_jump: bool,
+    /// Output filter (Ctrl+Shift+L)
+    pub filter: OutputFilter,
     /// Autocomplete suggestions
     pub suggestions: Vec<Suggestion>,
     /// Selected suggestion index
@@ -108,12 +204,16 @@ pub struct PaneSession {
     pub hints_mode: HintsMode,
     /// Vi mode for terminal navigation
     pub vi_mode: ViMode,
+    /// Pager over long output (`less`)
+    pub pager: Pager,
     /// Scroll to selected hint on next frame (after Tab cycling)
     pub hints_scroll_to_selected: bool,
-    /// Fuzzy finder (Ctrl+R/Ctrl+Shift+F/Ctrl+G)
+    /// Fuzzy finder (Ctrl+R/Ctrl+Shift+F/Ctrl+Shift+O/Ctrl+Shift+J/Ctrl+G)
     pub fuzzy_finder: FuzzyFinder,
     /// PTY session for this pane (for external/interactive commands)
     pub pty_session: Option<PtySession>,
+    /// When the PTY's program started, for its history entry's duration
+    pty_started: Option<std::time::Instant>,
     /// PTY output buffer (for streaming output - legacy)
     pub pty_buffer: PtyBuffer,
     /// Terminal grid for proper ANSI/cursor handling (used for interactive apps)
@@ -122,19 +222,35 @@ pub struct PaneSession {
     pub input_mode: InputMode,
     /// Accumulator for incomplete PTY output lines
     pub pty_line_buffer: String,
+    /// AI answer streaming into the scrollback
+    ai_answer: Option<StreamingAnswer>,
+    /// Background job running in this pane
+    job: Option<RunningJob>,
+    /// Size (rows, cols) the pane wants and when it first asked for it
+    pty_pending_size: Option<((u16, u16), std::time::Instant)>,
+    /// Line put back in the input after a syntax error, and the byte range
+    /// to underline while the input still holds it
+    pub syntax_error: Option<(String, std::ops::Range<usize>)>,
+    /// The startup script hasn't run in this pane yet
+    startup_pending: bool,
+    /// Commands from a workspace still to run, each once the one before it
+    /// has finished
+    pub startup_commands: VecDeque<String>,
 }
 
 impl PaneSession {
     /// Create a new pane session
     pub fn new(show_banner: bool) -> Self {
         let state = TerminalState::new();
-        let mut buffer = OutputBuffer::new(10_000);
+        let terminal = Config::load().terminal;
+        let mut buffer = OutputBuffer::new(terminal.scrollback_lines)
+            .with_spill(terminal.scrollback_disk_mb * 1024 * 1024);
 
         if show_banner {
             for line in STARTUP_BANNER.lines() {
                 buffer.push_line(line);
             }
-        } else {
+        } else if state.kawaii.greetings {
             // Random kawaii greeting for new panes!
             let greetings = [
                 "✨ New pane spawned~ (◕‿◕)✧",
@@ -152,19 +268,29 @@ impl PaneSession {
                 .unwrap_or(0);
             buffer.push_line(greetings[idx]);
             buffer.push_line(&format!("📂 {}", state.cwd().display()));
+        } else {
+            buffer.push_line(&format!("📂 {}", state.cwd().display()));
         }
 
         Self {
             state,
             buffer,
-            history: SmartHistory::new(10_000),
+            history: SmartHistory::persistent(smart_history::default_path(), 10_000),
             input: String::new(),
             saved_input: String::new(),
+            kill_ring: KillRing::default(),
+            continuation: None,
             scroll_to_bottom: false,
+            minimap_jump: None,
+            max_scroll_offset: 0.0,
+            output_view: OutputView::default(),
             search_mode: false,
             search_query: String::new(),
             search_matches: Vec::new(),
             current_match: 0,
+            search_pause_scroll: false,
+            search_jump: false,
+            filter: OutputFilter::default(),
             suggestions: Vec::new(),
             selected_suggestion: 0,
             show_suggestions: false,
@@ -173,27 +299,124 @@ impl PaneSession {
             cursor_to_end: false,
             hints_mode: HintsMode::new(),
             vi_mode: ViMode::new(),
+            pager: Pager::new(),
             hints_scroll_to_selected: false,
             fuzzy_finder: FuzzyFinder::new(),
             pty_session: None, // PTY spawned on-demand for external commands
+            pty_started: None,
             pty_buffer: PtyBuffer::new(24, 80),
             pty_grid: TerminalGrid::new(24, 80),
             input_mode: InputMode::Normal,
             pty_line_buffer: String::new(),
+            ai_answer: None,
+            job: None,
+            pty_pending_size: None,
+            syntax_error: None,
+            startup_pending: true,
+            startup_commands: VecDeque::new(),
+        }
+    }
+
+    /// Start in a saved directory, or say why the pane didn't
+    pub fn start_in(&mut self, cwd: &std::path::Path) {
+        if cwd.is_dir() {
+            self.state.set_cwd(cwd.to_path_buf());
+        } else {
+            self.buffer
+                .push_error(&format!("Directory {} no longer exists", cwd.display()));
+        }
+    }
+
+    /// Add an entered line to a pending command (see `continuation`).
+    /// Returns the full command once every here-document has its closing
+    /// delimiter, every block and quote is closed, and it doesn't end in
+    /// `\` or `|`; None while more lines are needed.
+    pub fn continue_line(&mut self, line: &str) -> Option<String> {
+        let command = match self.continuation.take() {
+            // `\` + Enter joins the lines, as in the shell
+            Some(pending) if incomplete(&pending) == Some(Incomplete::Backslash) => {
+                format!("{}{}", &pending[..pending.len() - 1], line)
+            }
+            Some(pending) => format!("{}\n{}", pending, line),
+            None => line.to_string(),
+        };
+        if pending_heredoc(&command).is_some()
+            || (is_compound(&command) && is_unfinished(&command))
+            || incomplete(&command).is_some()
+        {
+            self.continuation = Some(command);
+            return None;
+        }
+        Some(command)
+    }
+
+    /// Open the fuzzy finder on files used by recent commands
+    pub fn open_recent_files(&mut self) {
+        let cwd = self.state.cwd().clone();
+        self.fuzzy_finder.activate(FuzzyMode::RecentFiles, &cwd);
+        let now = std::time::SystemTime::now();
+        let files = self
+            .history
+            .recent_files(200)
+            .into_iter()
+            .map(|(path, entry)| {
+                let age = now.duration_since(entry.timestamp).unwrap_or_default();
+                let program = entry.command.split_whitespace().next().unwrap_or_default();
+                (path, format!("{} · {}", format_age(age.as_secs()), program))
+            })
+            .collect();
+        self.fuzzy_finder.set_recent_files(files);
+    }
+
+    /// Open the fuzzy finder on the directory stack, then directories recent
+    /// commands ran in
+    pub fn open_directories(&mut self) {
+        let cwd = self.state.cwd().clone();
+        self.fuzzy_finder.activate(FuzzyMode::Directories, &cwd);
+        let mut dirs: Vec<(std::path::PathBuf, String)> = self
+            .state
+            .dirs()
+            .into_iter()
+            .enumerate()
+            .skip(1)
+            .map(|(n, dir)| (dir, format!("+{} · stack", n)))
+            .collect();
+        for dir in self.history.recent_dirs(100) {
+            if dir != cwd && !dirs.iter().any(|(seen, _)| *seen == dir) {
+                dirs.push((dir, "recent".to_string()));
+            }
+        }
+        self.fuzzy_finder.set_directories(dirs);
+    }
+
+    /// Open the fuzzy finder on grep/find hits in `output`
+    pub fn open_results(&mut self, output: &str) {
+        let cwd = self.state.cwd().clone();
+        self.fuzzy_finder.activate(FuzzyMode::Results, &cwd);
+        self.fuzzy_finder.set_results(output);
+    }
+
+    /// Echo a command after the prompt, with `> ` before here-document lines
+    pub fn echo_command(&mut self, command: &str) {
+        let prompt = self.state.format_prompt();
+        for (index, line) in command.split('\n').enumerate() {
+            let lead = if index == 0 { prompt.as_str() } else { "> " };
+            self.buffer.push_line(&format!("{}{}", lead, line));
         }
     }
 
-    /// Spawn a PTY session for a specific command
-    pub fn spawn_pty_command(&mut self, program: &str, args: &[String]) -> anyhow::Result<()> {
+    /// Start an external command line in a PTY sized to the pane
+    pub fn spawn_pty_command(&mut self, executor: &Executor, command: &str) -> anyhow::Result<()> {
         // Close any existing PTY session
         self.pty_session = None;
 
         // Clear the terminal grid for fresh output
         self.pty_grid.clear();
 
-        // Spawn new PTY with the command directly
-        let pty = PtySession::new_with_command(program, args, 24, 80, self.state.cwd())?;
+        let (rows, cols) = self.pty_grid.dimensions();
+        let pty = executor.spawn_pty(command, &self.state, rows as u16, cols as u16)?;
         self.pty_session = Some(pty);
+        self.pty_started = Some(std::time::Instant::now());
         Ok(())
     }
 
@@ -211,21 +434,28 @@ impl PaneSession {
         };
 
         // Process collected outputs
-        let mut should_close_pty = false;
+        let mut exit_message = None;
+        let mut received = false;
         for output in outputs {
             match output {
                 PtyOutput::Data(data) => {
                     // Feed raw data to the terminal grid - it handles all ANSI sequences
                     self.pty_grid.process(&data);
                     self.scroll_to_bottom = true;
+                    received = true;
                 }
                 PtyOutput::Exited(code) => {
+                    // The history entry was completed as a success when the
+                    // program started; record how it really ended
+                    if let (Some(code), Some(started)) = (code, self.pty_started.take()) {
+                        self.history
+                            .complete_last(code as i32, started.elapsed(), None);
+                    }
                     let msg = match code {
                         Some(c) => format!("[Process exited with code {}]", c),
                         None => "[Process exited]".to_string(),
                     };
-                    self.buffer.push_line(&msg);
-                    should_close_pty = true;
+                    exit_message = Some(msg);
                 }
                 PtyOutput::Error(e) => {
                     self.buffer.push_error(&format!("[PTY Error: {}]", e));
@@ -233,43 +463,366 @@ impl PaneSession {
             }
         }
 
-        if should_close_pty {
+        let should_close_pty = exit_message.is_some();
+        if let Some(msg) = exit_message {
             self.pty_session = None;
             self.input_mode = InputMode::Normal;
-            // Clear the grid when PTY exits
+            // Keep what the program printed to the main screen (never its
+            // alternate-screen redraws) in the pane's history
+            for line in self.pty_grid.finish() {
+                self.buffer.push_line(&line);
+            }
+            self.buffer.push_line(&msg);
+            self.warn_if_buffer_full();
             self.pty_grid.clear();
         }
+        // Matches follow the output as it streams in
+        if received || should_close_pty {
+            self.refresh_search();
+        }
     }
 
-    /// Run a command via PTY (spawns the command directly)
-    pub fn run_via_pty(&mut self, program: &str, args: &[String]) -> anyhow::Result<()> {
-        // Spawn the command directly attached to PTY
-        self.spawn_pty_command(program, args)?;
-        Ok(())
+    /// Show requests logged by this pane's mock server
+    pub fn poll_mock_log(&mut self) {
+        let Some(server) = &self.state.mock_server else {
+            return;
+        };
+        let lines = server.drain_log();
+        if lines.is_empty() {
+            return;
+        }
+        for line in lines {
+            self.buffer.push_line(&line);
+        }
+        self.warn_if_buffer_full();
+        self.refresh_search();
+        self.scroll_to_bottom = true;
     }
 
-    /// Resize the PTY if active and size has changed
-    pub fn resize_pty(&mut self, width: f32, height: f32, font_size: f32, line_height: f32) {
-        if let Some(ref pty) = self.pty_session {
-            // Skip resize if dimensions are invalid
-            if width <= 0.0 || height <= 0.0 || font_size <= 0.0 || line_height <= 0.0 {
-                return;
+    /// Append newly streamed AI text; once the answer is complete, replace
+    /// the raw text with the rendered reply. Returns whether the provider
+    /// answered, once it is done.
+    fn poll_ai_answer(&mut self) -> Option<bool> {
+        let answer = self.ai_answer.as_mut()?;
+        let mut received = false;
+        while let Ok(chunk) = answer.stream.chunks.try_recv() {
+            if answer.lines == 0 {
+                // A blank line under the header, then the answer
+                self.buffer.push_line("");
+                self.buffer.push_line("");
+                answer.lines = 2;
+            }
+            self.buffer.append_text(&chunk);
+            answer.lines += chunk.matches('\n').count();
+            received = true;
+        }
+        if received {
+            self.scroll_to_bottom = true;
+        }
+
+        let reply: AiReply = match answer.stream.done.try_recv() {
+            Ok(reply) => reply,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => AiReply {
+                header: String::new(),
+                body: "Error: the AI request stopped unexpectedly".to_string(),
+                ok: false,
+            },
+        };
+        let answer = self.ai_answer.take()?;
+        self.buffer.pop_lines(answer.lines);
+        if reply.ok {
+            if reply.header != answer.header {
+                // An old cached answer, shown because the provider is offline
+                self.buffer.push_line(&reply.header);
+            }
+            self.buffer.push_line("");
+            for line in reply.body.lines() {
+                self.buffer.push_line(line);
+            }
+        } else {
+            self.buffer.push_error(&reply.body);
+        }
+        self.warn_if_buffer_full();
+        self.refresh_search();
+        self.scroll_to_bottom = true;
+        Some(reply.ok)
+    }
+
+    /// Stop an AI answer that is still streaming in, keeping what arrived
+    fn stop_ai_answer(&mut self) -> bool {
+        if self.ai_answer.take().is_none() {
+            return false;
+        }
+        self.buffer.push_line("(answer stopped)");
+        true
+    }
+
+    /// Run `expanded` as a background job writing into the open block
+    fn start_job(
+        &mut self,
+        executor: &Executor,
+        expanded: &str,
+        history: Vec<String>,
+        run: CommandRun,
+    ) {
+        let job = executor.spawn_job(expanded, self.state.snapshot(), history);
+        self.job = Some(RunningJob {
+            job,
+            run,
+            progress: None,
+        });
+    }
+
+    /// Show what the background job printed since last frame. Once it is
+    /// done, shows the rest of its output (or its error) and returns the
+    /// command with whether it succeeded.
+    fn poll_job(&mut self, error_faces: bool) -> Option<(CommandRun, bool)> {
+        let running = self.job.as_mut()?;
+        let mut received = false;
+        let done = loop {
+            match running.job.events.try_recv() {
+                Ok(JobEvent::Line(line)) => {
+                    self.buffer.push_line(&line);
+                    received = true;
+                }
+                Ok(JobEvent::Progress(message)) => running.progress = Some(message),
+                Ok(JobEvent::Redraw(screen)) => {
+                    self.buffer.set_live(&screen);
+                    received = true;
+                }
+                Ok(JobEvent::Done(result, state)) => break Some((result, Some(state))),
+                Err(std::sync::mpsc::TryRecvError::Empty) => break None,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    break Some((
+                        Err(anyhow::anyhow!("the command stopped unexpectedly")),
+                        None,
+                    ))
+                }
+            }
+        };
+        if received {
+            self.warn_if_buffer_full();
+            self.refresh_search();
+            self.scroll_to_bottom = true;
+        }
+
+        let (result, state) = done?;
+        let running = self.job.take()?;
+        self.buffer.end_live();
+        if let Some(mut state) = state {
+            if state.requested_pick.is_some() {
+                self.state.requested_pick = state.requested_pick.take();
+            }
+            if state.requested_pager.is_some() {
+                self.state.requested_pager = state.requested_pager.take();
+            }
+        }
+        let success = match result {
+            Ok(output) => {
+                for line in output.lines() {
+                    self.buffer.push_line(line);
+                }
+                true
+            }
+            Err(e) => {
+                self.buffer.push_error(&error_text(&e, error_faces));
+                false
+            }
+        };
+        self.refresh_search();
+        Some((running.run, success))
+    }
+
+    /// Stop the background job (Ctrl+C), keeping what it printed
+    fn stop_job(&mut self) -> Option<CommandRun> {
+        let running = self.job.take()?;
+        running.job.cancel();
+        self.buffer.end_live();
+        self.buffer.push_line("^C");
+        Some(running.run)
+    }
+
+    /// Suspend the background job (Ctrl+Z): it waits in the job table for
+    /// `fg` or `bg`, and the pane takes commands again
+    fn suspend_job(&mut self) -> Option<CommandRun> {
+        let running = self.job.take()?;
+        running.job.pause();
+        self.buffer.end_live();
+        let jobs = self.state.jobs();
+        let id = jobs.add(&running.run.command, running.job, JobStatus::Stopped);
+        self.buffer.push_line("^Z");
+        self.buffer
+            .push_line(&format!("[{}]+  Stopped  {}", id, running.run.command));
+        Some(running.run)
+    }
+
+    /// Wait for a job taken from the job table (`fg`) as the command `run`,
+    /// showing what it printed in the background
+    fn foreground_job(&mut self, foreground: ForegroundJob, run: CommandRun) {
+        self.buffer.push_line(&foreground.command);
+        for line in &foreground.output {
+            self.buffer.push_line(line);
+        }
+        self.job = Some(RunningJob {
+            job: foreground.job,
+            run,
+            progress: None,
+        });
+        self.scroll_to_bottom = true;
+    }
+
+    /// Report numbered jobs that finished, with what they printed. Waits
+    /// while a command runs in the pane, as a shell waits for the prompt.
+    fn report_jobs(&mut self, error_faces: bool) {
+        if self.job.is_some() {
+            return;
+        }
+        for finished in self.state.jobs().reap() {
+            for line in &finished.output {
+                self.buffer.push_line(line);
+            }
+            match finished.result {
+                Ok(rest) => {
+                    for line in rest.lines() {
+                        self.buffer.push_line(line);
+                    }
+                    self.buffer
+                        .push_line(&format!("[{}]  Done  {}", finished.id, finished.command));
+                }
+                Err(e) => {
+                    self.buffer.push_error(&error_text(&e, error_faces));
+                    self.buffer
+                        .push_error(&format!("[{}]  Exit  {}", finished.id, finished.command));
+                }
             }
+            self.scroll_to_bottom = true;
+        }
+    }
+
+    /// Spinner, running time, and latest progress of the background job,
+    /// shown under its output
+    fn job_status(&self) -> Option<String> {
+        let running = self.job.as_ref()?;
+        let elapsed = std::time::Duration::from_millis(elapsed_ms(running.run.started));
+        let frame = JOB_SPINNER[(elapsed.as_millis() / 120) as usize % JOB_SPINNER.len()];
+        let mut status = format!(
+            "{} {}",
+            frame,
+            crate::terminal::buffer::format_duration(elapsed)
+        );
+        if let Some(progress) = &running.progress {
+            status.push_str(&format!(" · {}", progress));
+        }
+        Some(status)
+    }
+
+    /// Share this pane's history with the `history` and `fc` commands
+    /// (skipped for other commands to avoid copying it on every run)
+    fn share_history(&mut self, command: &str) {
+        if command.contains("history") || command.contains("fc") {
+            self.state.history = self.history.all().map(|e| e.command.clone()).collect();
+        }
+        if command.contains("history") && command.contains("stats") {
+            self.state.history_stats = Some(self.history.stats(HISTORY_STATS_ROWS));
+        }
+    }
+
+    /// Share this pane's scrollback size with `buffer stats`
+    fn share_buffer_stats(&mut self, command: &str) {
+        if command.contains("buffer") {
+            self.state.buffer_stats = Some(self.buffer.stats());
+        }
+    }
+
+    /// Warn once when the scrollback nears its cap and old output is about to be dropped
+    fn warn_if_buffer_full(&mut self) {
+        if self.buffer.cap_warning_due() {
+            self.buffer.push_line(&format!(
+                "\x1b[33m⚠ Scrollback is {}% full ({} lines max) - the oldest output will be dropped. \
+                 'buffer stats' shows what uses it, 'buffer trim <n>' or 'clear' frees it.\x1b[0m",
+                WARN_PERCENT,
+                self.buffer.max_lines()
+            ));
+        }
+    }
 
-            // Calculate character dimensions (approximate for monospace)
-            let char_width = font_size * 0.6; // Approximate monospace ratio
-            let char_height = font_size * line_height;
+    /// Apply a deletion or clear requested by the `history` command
+    fn apply_history_request(&mut self) {
+        match self.state.requested_history.take() {
+            Some(HistoryRequest::Delete { start, end }) => self.history.remove(start - 1..end),
+            Some(HistoryRequest::Clear) => self.history.clear(),
+            None => {}
+        }
+        self.state.history.clear();
+        self.state.history_stats = None;
+    }
+
+    /// Run an external command line via PTY, streaming its output into the pane
+    pub fn run_via_pty(&mut self, executor: &Executor, command: &str) -> anyhow::Result<()> {
+        self.spawn_pty_command(executor, command)
+    }
 
-            let cols = (width / char_width).max(1.0) as u16;
-            let rows = (height / char_height).max(1.0) as u16;
+    /// Fit the PTY and its grid to an area of `width` x `height` pixels. A
+    /// new size is applied once it has held for `PTY_RESIZE_DEBOUNCE`; returns
+    /// true while one is waiting, so the caller can schedule a repaint.
+    pub fn resize_pty(
+        &mut self,
+        width: f32,
+        height: f32,
+        char_width: f32,
+        char_height: f32,
+    ) -> bool {
+        // Skip resize if dimensions are invalid
+        if width <= 0.0 || height <= 0.0 || char_width <= 0.0 || char_height <= 0.0 {
+            return false;
+        }
+
+        let cols = (width / char_width).max(1.0) as u16;
+        let rows = (height / char_height).max(1.0) as u16;
+
+        let (current_rows, current_cols) = self.pty_grid.dimensions();
+        if (rows as usize, cols as usize) == (current_rows, current_cols) {
+            self.pty_pending_size = None;
+            return false;
+        }
 
-            let (current_rows, current_cols) = pty.size();
-            if rows != current_rows || cols != current_cols {
-                let _ = pty.resize(rows, cols);
-                self.pty_buffer.resize(rows as usize, cols as usize);
-                self.pty_grid.resize(rows as usize, cols as usize);
+        let now = std::time::Instant::now();
+        match self.pty_pending_size {
+            Some((size, since)) if size == (rows, cols) => {
+                if now.duration_since(since) < PTY_RESIZE_DEBOUNCE {
+                    return true;
+                }
+            }
+            _ => {
+                self.pty_pending_size = Some(((rows, cols), now));
+                return true;
             }
         }
+
+        self.pty_pending_size = None;
+        if let Some(ref mut pty) = self.pty_session {
+            let _ = pty.resize(rows, cols);
+        }
+        self.pty_buffer.resize(rows as usize, cols as usize);
+        self.pty_grid.resize(rows as usize, cols as usize);
+        false
+    }
+
+    /// After a syntax error, put `command` back in the input so it can be
+    /// fixed, underlining the offending part if the parser saw the line as
+    /// typed (not rewritten by an alias)
+    fn restore_after_syntax_error(&mut self, command: &str, parsed: &str, error: &anyhow::Error) {
+        let Some(syntax) = error.downcast_ref::<SyntaxError>() else {
+            return;
+        };
+        let offset = command.len() - command.trim_start().len();
+        self.syntax_error = (parsed.trim() == command.trim()).then(|| {
+            let span = offset + syntax.span.start..offset + syntax.span.end;
+            (command.to_string(), span)
+        });
+        self.input = command.to_string();
+        self.cursor_to_end = true;
     }
 
     /// Toggle search mode
@@ -282,23 +835,58 @@ impl PaneSession {
         }
     }
 
-    /// Update search results
+    /// Whether a running program's screen is shown instead of the output buffer
+    pub fn shows_pty(&self) -> bool {
+        self.input_mode == InputMode::Raw && self.pty_session.is_some()
+    }
+
+    /// Update search results (in the program's screen and scrollback while
+    /// one is running, otherwise in the output buffer)
     pub fn update_search(&mut self) {
         if self.search_query.is_empty() {
             self.search_matches.clear();
             self.current_match = 0;
         } else {
-            self.search_matches = self.buffer.search(&self.search_query);
+            self.search_matches = if self.shows_pty() {
+                let query = self.search_query.to_lowercase();
+                self.pty_grid
+                    .get_lines()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| ansi::strip_ansi(line).to_lowercase().contains(&query))
+                    .map(|(idx, _)| idx)
+                    .collect()
+            } else {
+                self.buffer.search(&self.search_query)
+            };
             if self.current_match >= self.search_matches.len() {
                 self.current_match = 0;
             }
         }
+        self.search_jump = true;
+    }
+
+    /// Re-run the search after new output arrived. While following the
+    /// output, the newest match becomes current; paused, the current one stays.
+    fn refresh_search(&mut self) {
+        if !self.search_mode || self.search_query.is_empty() {
+            return;
+        }
+        let current = self.current_match;
+        self.update_search();
+        if self.search_pause_scroll {
+            self.current_match = current.min(self.search_matches.len().saturating_sub(1));
+            self.search_jump = false;
+        } else {
+            self.current_match = self.search_matches.len().saturating_sub(1);
+        }
     }
 
     /// Go to next search match
     pub fn next_match(&mut self) {
         if !self.search_matches.is_empty() {
             self.current_match = (self.current_match + 1) % self.search_matches.len();
+            self.search_jump = true;
         }
     }
 
@@ -310,8 +898,22 @@ impl PaneSession {
             } else {
                 self.current_match -= 1;
             }
+            self.search_jump = true;
         }
     }
+
+    /// Whether the output sticks to the bottom as it grows (not while a
+    /// search pauses it)
+    pub fn follows_output(&self) -> bool {
+        !(self.search_mode && self.search_pause_scroll)
+    }
+
+    /// Line of the current search match, while the search bar is open
+    pub fn current_match_line(&self) -> Option<usize> {
+        self.search_mode
+            .then(|| self.search_matches.get(self.current_match).copied())
+            .flatten()
+    }
 }
 
 /// A single terminal tab containing one or more panes
@@ -367,6 +969,31 @@ impl TabSession {
         self.panes.insert(new_pane_id, new_pane);
     }
 
+    /// Split the focused pane for a command run with `|> pane`: the new
+    /// pane starts in the same directory, without the banner, and the
+    /// focus stays where it was
+    pub fn split_for_command(&mut self) -> usize {
+        let cwd = self.focused_pane().map(|pane| pane.state.cwd().clone());
+        let new_pane_id = self.splits.split(SplitDirection::Vertical);
+        let mut new_pane = PaneSession::new(false);
+        if let Some(cwd) = cwd {
+            new_pane.state.set_cwd(cwd);
+        }
+        self.panes.insert(new_pane_id, new_pane);
+        new_pane_id
+    }
+
+    /// Rearrange this tab's panes, opening or closing panes as needed
+    pub fn apply_layout(&mut self, layout: &Layout) {
+        let (added, removed) = self.splits.apply_layout(layout);
+        for pane_id in added {
+            self.panes.insert(pane_id, PaneSession::new(true));
+        }
+        for pane_id in removed {
+            self.panes.remove(&pane_id);
+        }
+    }
+
     /// Close the focused pane
     pub fn close_focused_pane(&mut self) -> bool {
         let pane_id = self.splits.focused_pane_id();
@@ -386,12 +1013,16 @@ impl TabSession {
     /// Update title based on focused pane's directory
     pub fn update_title(&mut self) {
         if let Some(pane) = self.focused_pane() {
-            self.title = pane
-                .state
-                .cwd()
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "~".to_string());
+            self.title = match pane.pty_session.as_ref().and_then(|pty| pty.remote()) {
+                // An SSH session is titled by where it is logged in
+                Some(label) => label.to_string(),
+                None => pane
+                    .state
+                    .cwd()
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "~".to_string()),
+            };
         }
     }
 
@@ -399,6 +1030,33 @@ impl TabSession {
     pub fn pane_count(&self) -> usize {
         self.panes.len()
     }
+
+    /// Output of the pane referenced by `# --pane N`, if the command asks for one
+    pub fn referenced_pane_context(&self, command: &str) -> Option<PaneContext> {
+        if !command.trim_start().starts_with('#') {
+            return None;
+        }
+        let label = crate::ai::parse_pane_flag(command).0?;
+        // Pane labels are 1-based, matching the pane headers
+        let pane = self.panes.get(&label.checked_sub(1)?)?;
+        let buffer = &pane.buffer;
+
+        // Most recent finished command that wasn't itself an AI question
+        buffer
+            .blocks()
+            .iter()
+            .rev()
+            .filter(|block| block.duration.is_some())
+            .find(|block| !block.command.trim_start().starts_with('#'))
+            .and_then(|block| {
+                Some(PaneContext {
+                    label,
+                    command: block.command.clone(),
+                    output: buffer.get_block_content(block.id)?,
+                    success: block.success,
+                })
+            })
+    }
 }
 
 /// Main Zaxiom application
@@ -415,6 +1073,10 @@ pub struct ZaxiomApp {
     theme: Theme,
     /// Current theme name (for saving)
     theme_name: ThemeName,
+    /// Theme file in use instead of a built-in (its base is `theme_name`)
+    user_theme: Option<UserTheme>,
+    /// Last time the theme file was checked for changes
+    user_theme_checked: std::time::Instant,
     /// App configuration
     config: Config,
     /// Whether to exit the app
@@ -424,6 +1086,38 @@ pub struct ZaxiomApp {
     /// Editor state (Some when editing a file)
     #[allow(dead_code)]
     editor: Option<EditorState>,
+    /// Syntax colors for the file in the editor
+    editor_colors: syntax::HighlightCache,
+    /// Temp file opened by `fc`; its commands run when the editor closes
+    fc_file: Option<std::path::PathBuf>,
+    /// Message file opened by `gc`; it is committed when the editor closes
+    commit_file: Option<std::path::PathBuf>,
+    /// Command audit log, when enabled
+    audit: Option<AuditLog>,
+    /// Record local usage stats for the `stats` dashboard
+    record_usage: bool,
+    /// Usage dashboard, while open
+    usage_dashboard: Option<UsageStats>,
+    /// Saved sessions offered for restoring, while the picker is open
+    session_picker: Option<Vec<SavedSession>>,
+    /// End-of-day digest toast and when it appeared
+    digest_toast: Option<(String, std::time::Instant)>,
+    /// Day the digest toast was last shown
+    digest_toast_day: Option<chrono::NaiveDate>,
+    /// Folder dropped on the window, waiting to be cd'd into or inserted
+    dropped_folder: Option<std::path::PathBuf>,
+    /// Shortcut overlay pinned open with F1
+    shortcuts_pinned: bool,
+    /// Ctrl held on its own, for the shortcut overlay
+    ctrl_hold: CtrlHold,
+    /// Palette action or command picked from the menu, run next frame
+    menu_action: Option<String>,
+    /// Hover previews for paths in output
+    previews: PreviewCache,
+    /// Background git status for the status bar
+    git_status: GitStatusWatcher,
+    /// Git sidebar (Ctrl+Shift+G)
+    git_panel: GitPanel,
     /// Autocomplete engine
     autocomplete: Autocomplete,
     /// Session manager for persistence
@@ -436,13 +1130,21 @@ pub struct ZaxiomApp {
     clipboard_feedback: Option<(String, std::time::Instant)>,
     /// Command palette (Ctrl+P)
     command_palette: CommandPalette,
-    /// Kawaii mode - cuter UI elements
-    kawaii_mode: bool,
+    /// Contextual tips
+    tips: TipsEngine,
+    /// Consecutive ↑ presses (for the history search tip)
+    history_up_streak: usize,
+    /// Kawaii level - how much cute flair to show
+    kawaii_level: KawaiiLevel,
+    /// Kawaii features in effect
+    kawaii: KawaiiFeatures,
+    /// Whether egui style needs rebuilding (theme or kawaii change)
+    style_dirty: bool,
 }
 
 impl ZaxiomApp {
-    /// Create a new Zaxiom application
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    /// Create a new Zaxiom application (`restricted` forces restricted mode on)
+    pub fn new(cc: &eframe::CreationContext<'_>, restricted: bool) -> Self {
         // Configure custom fonts
         let mut fonts = egui::FontDefinitions::default();
 
@@ -478,89 +1180,161 @@ impl ZaxiomApp {
 
         // Load configuration
         let config = Config::load();
-
-        // Load theme from config or use default
-        let theme_name = config
+        keymap::set_current(keymap::Keymap::load(&keymap::saved_path()));
+
+        // Load theme from config or use default (auto mode picks light or dark)
+        let system_dark = cc
+            .egui_ctx
+            .system_theme()
+            .map(|theme| theme == egui::Theme::Dark);
+        let auto_theme = config
             .theme
-            .name
-            .as_ref()
-            .and_then(|name| ThemeName::from_string(name))
-            .unwrap_or_default();
-        let kawaii_mode = config.kawaii_mode;
-        let theme = if kawaii_mode {
-            Theme::from_name(theme_name).apply_kawaii()
-        } else {
-            Theme::from_name(theme_name)
+            .auto_theme(system_dark, chrono::Local::now().time());
+        let user_theme = match (&auto_theme, &config.theme.name) {
+            (None, Some(name)) => UserTheme::find(name).and_then(|user| match user {
+                Ok(user) => Some(user),
+                Err(e) => {
+                    eprintln!("Failed to load theme: {:#}", e);
+                    None
+                }
+            }),
+            _ => None,
         };
-        let mut style = (*cc.egui_ctx.style()).clone();
-
-        // Set base font sizes using theme settings
-        style.text_styles.insert(
-            egui::TextStyle::Monospace,
-            egui::FontId::new(theme.font_size, egui::FontFamily::Monospace),
-        );
-        style.text_styles.insert(
-            egui::TextStyle::Body,
-            egui::FontId::new(theme.font_size, egui::FontFamily::Monospace),
-        );
-        style.text_styles.insert(
-            egui::TextStyle::Small,
-            egui::FontId::new(theme.font_size * 0.85, egui::FontFamily::Monospace),
-        );
-        style.text_styles.insert(
-            egui::TextStyle::Button,
-            egui::FontId::new(theme.font_size, egui::FontFamily::Monospace),
-        );
-        style.text_styles.insert(
-            egui::TextStyle::Heading,
-            egui::FontId::new(theme.font_size * 1.25, egui::FontFamily::Monospace),
+        let theme_name = auto_theme
+            .or(user_theme.as_ref().map(|user| user.base))
+            .or_else(|| {
+                config
+                    .theme
+                    .name
+                    .as_ref()
+                    .and_then(|name| ThemeName::from_string(name))
+            })
+            .unwrap_or_default();
+        let kawaii_level = config.kawaii_level();
+        let kawaii = config.kawaii_features();
+        let theme = build_theme(
+            theme_name,
+            user_theme.as_ref(),
+            kawaii.palette,
+            config.theme.color_vision,
         );
-
-        // Set comfortable spacing
-        style.spacing.item_spacing = egui::vec2(8.0, theme.font_size * (theme.line_height - 1.0));
-
-        // Apply kawaii mode visual adjustments
-        if kawaii_mode {
-            // More rounded corners for a cuter look
-            style.visuals.window_corner_radius = egui::CornerRadius::same(12);
-            style.visuals.menu_corner_radius = egui::CornerRadius::same(10);
-            // Softer widget styling
-            style.visuals.widgets.noninteractive.corner_radius = egui::CornerRadius::same(8);
-            style.visuals.widgets.inactive.corner_radius = egui::CornerRadius::same(8);
-            style.visuals.widgets.hovered.corner_radius = egui::CornerRadius::same(8);
-            style.visuals.widgets.active.corner_radius = egui::CornerRadius::same(8);
-        }
-
-        cc.egui_ctx.set_style(style);
-
-        // Always start fresh (no session restore - like a normal terminal)
+        Self::apply_style(&cc.egui_ctx, &theme, kawaii.rounded_corners);
+        // Ctrl+= and Ctrl+- zoom one pane's text instead of the whole window
+        cc.egui_ctx
+            .options_mut(|options| options.zoom_with_keyboard = false);
+
+        // Start fresh like a normal terminal, unless `[session] restore` asks
+        // to offer the saved sessions. The previous window's autosave is kept
+        // as the "last" session either way.
         let session_manager = SessionManager::new();
+        if let Err(e) = session_manager.keep_last_session() {
+            eprintln!("Failed to keep the last session: {}", e);
+        }
+        let session_picker = config
+            .session
+            .restore
+            .then(|| session_manager.restorable_sessions())
+            .filter(|sessions| !sessions.is_empty());
         let (tabs, active_tab, next_tab_id) = (vec![TabSession::new(0, true)], 0, 1);
 
         // Try to create clipboard (may fail on some systems)
         let clipboard = Clipboard::new().ok();
 
+        let mut mascot = Mascot::new();
+        mascot.confetti = kawaii.confetti;
+
+        let audit = config
+            .audit
+            .enabled
+            .then(|| open_audit_log(&config))
+            .flatten();
+
+        let record_usage = config.stats.enabled;
+
+        let mut executor = Executor::new();
+        executor.set_restricted(RestrictedMode::from_config(
+            &config.restricted,
+            restricted,
+            &current_hostname(),
+        ));
+        executor.set_startup_script(config.startup_script());
+
+        let mut command_palette = CommandPalette::new();
+        for warning in command_palette.set_custom(&config.palette) {
+            eprintln!("config.toml: {}", warning);
+        }
+
         Self {
             tabs,
             active_tab,
             next_tab_id,
-            executor: Executor::new(),
+            executor,
             theme,
             theme_name,
+            user_theme,
+            user_theme_checked: std::time::Instant::now(),
             config,
             should_exit: false,
-            mascot: Mascot::new(),
+            mascot,
             editor: None,
+            editor_colors: syntax::HighlightCache::default(),
+            fc_file: None,
+            commit_file: None,
+            audit,
+            record_usage,
+            usage_dashboard: None,
+            session_picker,
+            digest_toast: None,
+            digest_toast_day: None,
+            dropped_folder: None,
+            shortcuts_pinned: false,
+            ctrl_hold: CtrlHold::default(),
+            menu_action: None,
+            previews: PreviewCache::new(),
+            git_status: GitStatusWatcher::new(),
+            git_panel: GitPanel::new(),
             autocomplete: Autocomplete::new(),
             session_manager,
             frame_count: 0,
             clipboard,
             clipboard_feedback: None,
-            command_palette: CommandPalette::new(),
-            kawaii_mode,
+            command_palette,
+            tips: TipsEngine::load(),
+            history_up_streak: 0,
+            kawaii_level,
+            kawaii,
+            style_dirty: false,
         }
     }
 
+    /// Set egui fonts, spacing, and corner rounding from the theme
+    fn apply_style(ctx: &egui::Context, theme: &Theme, rounded_corners: bool) {
+        let mut style = (*ctx.style()).clone();
+
+        // Set base font sizes using theme settings
+        set_font_sizes(&mut style, theme.font_size, theme.line_height);
+
+        // Kawaii: more rounded corners for a cuter look
+        let (window, menu, widget) = if rounded_corners {
+            (12, 10, 8)
+        } else {
+            let defaults = egui::Visuals::dark();
+            (
+                defaults.window_corner_radius.nw,
+                defaults.menu_corner_radius.nw,
+                defaults.widgets.inactive.corner_radius.nw,
+            )
+        };
+        style.visuals.window_corner_radius = egui::CornerRadius::same(window);
+        style.visuals.menu_corner_radius = egui::CornerRadius::same(menu);
+        style.visuals.widgets.noninteractive.corner_radius = egui::CornerRadius::same(widget);
+        style.visuals.widgets.inactive.corner_radius = egui::CornerRadius::same(widget);
+        style.visuals.widgets.hovered.corner_radius = egui::CornerRadius::same(widget);
+        style.visuals.widgets.active.corner_radius = egui::CornerRadius::same(widget);
+
+        ctx.set_style(style);
+    }
+
     /// Restore a tab from saved data
     #[allow(dead_code)]
     fn restore_tab(id: usize, saved: &SavedTab) -> Option<TabSession> {
@@ -598,93 +1372,1001 @@ impl ZaxiomApp {
         let mut session = SavedSession::new("autosave");
 
         for tab in &self.tabs {
-            // Get the focused pane's data (or first pane if none focused)
-            if let Some(pane) = tab.focused_pane() {
-                // Get commands from SmartHistory entries
-                let history: Vec<String> = pane
-                    .history
-                    .all()
-                    .take(100) // Limit to last 100 commands
-                    .map(|entry| entry.command.clone())
-                    .collect();
+            let pane_ids = tab.splits.all_pane_ids();
+            let panes: Vec<SavedPane> = pane_ids
+                .iter()
+                .filter_map(|id| tab.panes.get(id))
+                .map(|pane| {
+                    // The pane's own commands, limited to the last 100
+                    let commands = pane.history.current_session();
+                    let skip = commands.len().saturating_sub(100);
+                    SavedPane {
+                        cwd: pane.state.cwd().to_path_buf(),
+                        name: pane.state.pane_name.clone(),
+                        history: commands[skip..]
+                            .iter()
+                            .map(|entry| entry.command.clone())
+                            .collect(),
+                    }
+                })
+                .collect();
+            let focused = pane_ids
+                .iter()
+                .position(|id| *id == tab.splits.focused_pane_id())
+                .unwrap_or(0);
+            let Some(focused_pane) = panes.get(focused) else {
+                continue;
+            };
 
-                let saved_tab = SavedTab {
-                    title: tab.title.clone(),
-                    cwd: pane.state.cwd().to_path_buf(),
-                    history,
-                    scroll_position: 0,
-                };
-                session.add_tab(saved_tab);
-            }
+            session.add_tab(SavedTab {
+                title: tab.title.clone(),
+                cwd: focused_pane.cwd.clone(),
+                history: focused_pane.history.clone(),
+                scroll_position: 0,
+                layout: tab.splits.layout().to_spec(),
+                focused,
+                panes,
+            });
         }
 
         session.active_tab = self.active_tab;
         session
     }
 
-    /// Autosave the current session
-    fn autosave(&self) {
-        let session = self.create_saved_session();
-        if let Err(e) = self.session_manager.autosave(&session) {
-            eprintln!("Failed to autosave session: {}", e);
+    /// Capture the window's tabs and panes as a workspace. The command still
+    /// running in a pane becomes its startup command.
+    fn create_workspace(&self, name: &str) -> Workspace {
+        let mut workspace = Workspace::new(name);
+        for tab in &self.tabs {
+            let pane_ids = tab.splits.all_pane_ids();
+            let panes = pane_ids
+                .iter()
+                .filter_map(|id| tab.panes.get(id))
+                .map(|pane| WorkspacePane {
+                    cwd: pane.state.cwd().clone(),
+                    name: pane.state.pane_name.clone(),
+                    commands: pane
+                        .buffer
+                        .blocks()
+                        .last()
+                        .filter(|block| block.duration.is_none())
+                        .filter(|_| pane.job.is_some() || pane.pty_session.is_some())
+                        .map(|block| block.command.clone())
+                        .into_iter()
+                        .collect(),
+                })
+                .collect();
+            workspace.tabs.push(WorkspaceTab {
+                title: tab.title.clone(),
+                layout: tab.splits.layout().to_spec(),
+                panes,
+                focused: pane_ids
+                    .iter()
+                    .position(|id| *id == tab.splits.focused_pane_id())
+                    .unwrap_or(0),
+            });
         }
+        workspace.active_tab = self.active_tab;
+        workspace
     }
 
-    /// Create a new tab
-    fn new_tab(&mut self) {
-        let tab = TabSession::new(self.next_tab_id, true); // Show banner in new tabs
+    /// A new tab split by a layout spec, with `setup` called for each pane
+    /// (by its position in the layout) and the `focused`th pane focused
+    fn build_tab(
+        &mut self,
+        layout: &str,
+        focused: usize,
+        mut setup: impl FnMut(usize, &mut PaneSession),
+    ) -> TabSession {
+        let mut tab = TabSession::new(self.next_tab_id, true);
         self.next_tab_id += 1;
-        self.tabs.push(tab);
-        self.active_tab = self.tabs.len() - 1;
-    }
+        tab.apply_layout(&Layout::parse(layout).unwrap_or(Layout::Pane));
 
-    /// Close current tab
-    fn close_current_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            self.tabs.remove(self.active_tab);
-            if self.active_tab >= self.tabs.len() {
-                self.active_tab = self.tabs.len() - 1;
+        let pane_ids = tab.splits.all_pane_ids();
+        for (index, pane_id) in pane_ids.iter().enumerate() {
+            if let Some(pane) = tab.panes.get_mut(pane_id) {
+                setup(index, pane);
             }
         }
+        if let Some(id) = pane_ids.get(focused) {
+            tab.splits.focus_pane(*id);
+        }
+        tab.update_title();
+        tab
     }
 
-    /// Switch to next tab
-    fn next_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            self.active_tab = (self.active_tab + 1) % self.tabs.len();
+    /// Open a workspace's tabs after the current ones
+    fn open_workspace(&mut self, workspace: &Workspace) {
+        let first = self.tabs.len();
+        for saved in &workspace.tabs {
+            let tab = self.build_tab(&saved.layout, saved.focused, |index, pane| {
+                if let Some(saved_pane) = saved.panes.get(index) {
+                    pane.start_in(&saved_pane.cwd);
+                    pane.state.pane_name = saved_pane.name.clone();
+                    pane.startup_commands = saved_pane.commands.iter().cloned().collect();
+                }
+            });
+            self.tabs.push(tab);
+        }
+        if self.tabs.len() > first {
+            self.active_tab = (first + workspace.active_tab).min(self.tabs.len() - 1);
         }
     }
 
-    /// Switch to previous tab
-    fn prev_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            if self.active_tab == 0 {
-                self.active_tab = self.tabs.len() - 1;
-            } else {
-                self.active_tab -= 1;
-            }
+    /// Reopen a saved session's tabs with their splits, directories, and
+    /// history. A window that hasn't run anything yet is replaced; otherwise
+    /// the tabs open after the current ones.
+    fn restore_session(&mut self, session: &SavedSession) {
+        let mut tabs = Vec::new();
+        for saved in &session.tabs {
+            let panes = saved.saved_panes();
+            let tab = self.build_tab(&saved.layout, saved.focused, |index, pane| {
+                if let Some(saved_pane) = panes.get(index) {
+                    pane.start_in(&saved_pane.cwd);
+                    pane.state.pane_name = saved_pane.name.clone();
+                    pane.history.restore(&saved_pane.history, &saved_pane.cwd);
+                }
+            });
+            tabs.push(tab);
+        }
+        if tabs.is_empty() {
+            return;
+        }
+
+        let unused = self.tabs.len() == 1
+            && self.tabs[0]
+                .panes
+                .values()
+                .all(|pane| pane.buffer.blocks().is_empty() && pane.job.is_none());
+        let first = if unused { 0 } else { self.tabs.len() };
+        if unused {
+            self.tabs = tabs;
+        } else {
+            self.tabs.extend(tabs);
         }
+        self.active_tab = (first + session.active_tab).min(self.tabs.len() - 1);
     }
 
-    /// Copy text to clipboard
-    fn copy_to_clipboard(&mut self, text: &str) {
-        if let Some(ref mut clipboard) = self.clipboard {
-            if clipboard.set_text(text.to_string()).is_ok() {
-                self.clipboard_feedback = Some((
-                    format!(
-                        "✨ Copied~ (◕‿◕) {}",
-                        if text.len() > 30 {
-                            format!("{}...", &text[..30])
-                        } else {
-                            text.to_string()
+    /// Save, restore, or pick sessions as requested by the `session` command
+    fn handle_session_requests(&mut self) {
+        let requests: Vec<SessionRequest> = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_session.take())
+            .collect();
+
+        for request in requests {
+            match request {
+                SessionRequest::Save(name) => {
+                    let mut session = self.create_saved_session();
+                    session.name = name;
+                    if let Err(e) = self.session_manager.save_session(&session) {
+                        if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                            pane.buffer
+                                .push_error(&format!("Failed to save session: {}", e));
                         }
-                    ),
+                    }
+                }
+                SessionRequest::Restore(session) => self.restore_session(&session),
+                SessionRequest::Pick => {
+                    self.session_picker = Some(self.session_manager.restorable_sessions());
+                }
+            }
+        }
+    }
+
+    /// Saved sessions to restore (on launch with `[session] restore`, or
+    /// `session restore`)
+    fn render_session_picker(&mut self, ctx: &egui::Context) {
+        let Some(sessions) = &self.session_picker else {
+            return;
+        };
+        let (bg, accent, fg, comment) = (
+            self.theme.background_secondary,
+            self.theme.accent,
+            self.theme.foreground,
+            self.theme.comment_color,
+        );
+        let mut close = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        let mut restore = None;
+
+        egui::Area::new(egui::Id::new("session_picker"))
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::default()
+                    .fill(bg)
+                    .stroke(egui::Stroke::new(2.0, accent))
+                    .corner_radius(egui::CornerRadius::same(8))
+                    .inner_margin(egui::Margin::same(12))
+                    .shadow(egui::epaint::Shadow {
+                        spread: 8,
+                        blur: 16,
+                        color: egui::Color32::from_black_alpha(120),
+                        offset: [0, 4],
+                    })
+                    .show(ui, |ui| {
+                        ui.set_width(480.0);
+                        ui.label(
+                            egui::RichText::new("🗂 Restore a session")
+                                .color(accent)
+                                .size(16.0),
+                        );
+                        ui.label(
+                            egui::RichText::new("Tabs, splits, directories, and history")
+                                .color(comment)
+                                .size(10.0),
+                        );
+                        ui.add_space(8.0);
+
+                        if sessions.is_empty() {
+                            ui.label(egui::RichText::new("No saved sessions yet.").color(fg));
+                        }
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for (index, session) in sessions.iter().enumerate() {
+                                    let name = if session.name == LAST_SESSION {
+                                        "Last session"
+                                    } else {
+                                        session.name.as_str()
+                                    };
+                                    ui.horizontal(|ui| {
+                                        if ui.button("Restore").clicked() {
+                                            restore = Some(index);
+                                        }
+                                        ui.label(egui::RichText::new(name).color(fg).strong());
+                                        ui.label(
+                                            egui::RichText::new(saved_time(session.saved_at))
+                                                .color(comment)
+                                                .size(11.0),
+                                        );
+                                    });
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{} tab{}: {}",
+                                            session.tabs.len(),
+                                            if session.tabs.len() == 1 { "" } else { "s" },
+                                            session.tab_titles()
+                                        ))
+                                        .color(comment)
+                                        .size(11.0),
+                                    );
+                                    ui.add_space(4.0);
+                                }
+                            });
+
+                        ui.add_space(4.0);
+                        ui.horizontal(|ui| {
+                            if ui.button("Start fresh").clicked() {
+                                close = true;
+                            }
+                            ui.label(egui::RichText::new("esc closes").color(comment).size(10.0));
+                        });
+                    });
+            });
+
+        if let Some(index) = restore {
+            if let Some(session) = self
+                .session_picker
+                .take()
+                .and_then(|mut s| (index < s.len()).then(|| s.swap_remove(index)))
+            {
+                self.restore_session(&session);
+            }
+        } else if close {
+            self.session_picker = None;
+        }
+    }
+
+    /// Save or open workspaces requested by the `workspace` command
+    fn handle_workspace_requests(&mut self) {
+        let requests: Vec<WorkspaceRequest> = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_workspace.take())
+            .collect();
+
+        for request in requests {
+            match request {
+                WorkspaceRequest::Save(name) => {
+                    let workspace = self.create_workspace(&name);
+                    if let Err(e) = self.session_manager.save_workspace(&workspace) {
+                        if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                            pane.buffer
+                                .push_error(&format!("Failed to save workspace: {}", e));
+                        }
+                    }
+                }
+                WorkspaceRequest::Open(workspace) => self.open_workspace(&workspace),
+            }
+        }
+    }
+
+    /// Run the next startup command of each idle pane opened from a workspace
+    /// (after its startup script)
+    fn run_startup_commands(&mut self) {
+        let mut next = Vec::new();
+        for (tab_index, tab) in self.tabs.iter_mut().enumerate() {
+            for (pane_id, pane) in tab.panes.iter_mut() {
+                if pane.startup_pending || pane.job.is_some() || pane.pty_session.is_some() {
+                    continue;
+                }
+                if let Some(command) = pane.startup_commands.pop_front() {
+                    next.push((tab_index, *pane_id, command));
+                }
+            }
+        }
+
+        let active_tab = self.active_tab;
+        for (tab_index, pane_id, command) in next {
+            if tab_index >= self.tabs.len() {
+                continue;
+            }
+            // Run it as if typed in that pane, then put the focus back
+            self.active_tab = tab_index;
+            let focused = self.tabs[tab_index].splits.focused_pane_id();
+            self.tabs[tab_index].splits.focus_pane(pane_id);
+            self.execute_command(&command);
+            if let Some(tab) = self.tabs.get_mut(tab_index) {
+                tab.splits.focus_pane(focused);
+            }
+        }
+        self.active_tab = active_tab.min(self.tabs.len() - 1);
+    }
+
+    /// Autosave the current session
+    fn autosave(&self) {
+        let session = self.create_saved_session();
+        if let Err(e) = self.session_manager.autosave(&session) {
+            eprintln!("Failed to autosave session: {}", e);
+        }
+    }
+
+    /// Create a new tab
+    fn new_tab(&mut self) {
+        let tab = TabSession::new(self.next_tab_id, true); // Show banner in new tabs
+        self.next_tab_id += 1;
+        self.tabs.push(tab);
+        self.active_tab = self.tabs.len() - 1;
+        self.tip_event(TipEvent::TabOpened);
+    }
+
+    /// Feed a non-command event to the tips engine, showing any tip in the focused pane
+    fn tip_event(&mut self, event: TipEvent) {
+        self.tips.enabled = self.kawaii.tips;
+        if let Some(tip) = self.tips.on_event(event) {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.buffer.push_line(&format_tip(tip));
+                pane.scroll_to_bottom = true;
+            }
+        }
+    }
+
+    /// Dismiss or reset tips as requested by the `tips` command
+    fn handle_tips_requests(&mut self) {
+        let requests: Vec<TipsRequest> = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_tips.take())
+            .collect();
+
+        for request in requests {
+            match request {
+                TipsRequest::Dismiss => {
+                    self.tips.dismiss_last();
+                }
+                TipsRequest::Reset => self.tips.reset(),
+            }
+        }
+    }
+
+    /// Open grep/find output in the fuzzy finder as requested by `--pick`
+    fn handle_pick_requests(&mut self) {
+        for pane in self.tabs[self.active_tab].panes.values_mut() {
+            if let Some(output) = pane.state.requested_pick.take() {
+                pane.open_results(&output);
+            }
+        }
+    }
+
+    /// Show text in the pager as requested by `less`
+    fn handle_pager_requests(&mut self) {
+        for pane in self.tabs[self.active_tab].panes.values_mut() {
+            if let Some(request) = pane.state.requested_pager.take() {
+                pane.vi_mode.exit();
+                pane.pager.open(&request.title, &request.text);
+            }
+        }
+    }
+
+    /// Trim, resize, or export scrollback as requested by `buffer`
+    fn handle_buffer_requests(&mut self) {
+        let mut scrollback = None;
+        for pane in self.tabs[self.active_tab].panes.values_mut() {
+            if let Some(keep) = pane.state.requested_buffer_trim.take() {
+                pane.buffer.trim(keep);
+            }
+            if let Some(path) = pane.state.requested_buffer_export.take() {
+                let exported = std::fs::File::create(&path)
+                    .and_then(|file| pane.buffer.export(&mut std::io::BufWriter::new(file)));
+                match exported {
+                    Ok(lines) => pane.buffer.push_success(&format!(
+                        "Saved {} lines to {}",
+                        lines,
+                        path.display()
+                    )),
+                    Err(e) => pane.buffer.push_error(&format!(
+                        "Could not export to {}: {}",
+                        path.display(),
+                        e
+                    )),
+                }
+            }
+            scrollback = pane.state.requested_scrollback.take().or(scrollback);
+            pane.state.buffer_stats = None;
+        }
+
+        // The in-memory size is a setting for every pane
+        if let Some(lines) = scrollback {
+            for tab in &mut self.tabs {
+                for pane in tab.panes.values_mut() {
+                    pane.buffer.set_max_lines(lines);
+                }
+            }
+            self.config.terminal.scrollback_lines = lines;
+            if let Err(e) = self.config.save() {
+                eprintln!("Failed to save scrollback size: {}", e);
+            }
+        }
+    }
+
+    /// Delete or clear history as requested by the `history` command
+    fn handle_history_requests(&mut self) {
+        for pane in self.tabs[self.active_tab].panes.values_mut() {
+            if pane.state.requested_history.is_some() {
+                pane.apply_history_request();
+            }
+        }
+    }
+
+    /// Start or stop audit logging as requested by the `audit` command
+    fn handle_audit_requests(&mut self) {
+        let requests: Vec<bool> = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_audit.take())
+            .collect();
+
+        if let Some(&enabled) = requests.last() {
+            // The command already saved the config; pick up its path too
+            self.config.audit = Config::load().audit;
+            self.audit = if enabled {
+                open_audit_log(&self.config)
+            } else {
+                None
+            };
+        }
+    }
+
+    /// Open the usage dashboard or start/stop recording as requested by the `stats` command
+    fn handle_stats_requests(&mut self) {
+        let requests: Vec<StatsRequest> = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_stats.take())
+            .collect();
+
+        for request in requests {
+            match request {
+                StatsRequest::Show => {
+                    self.usage_dashboard = Some(UsageStats::load(&usage::default_path()));
+                }
+                StatsRequest::Record(enabled) => {
+                    self.config.stats.enabled = enabled;
+                    self.record_usage = enabled;
+                }
+            }
+        }
+    }
+
+    /// Activity on `day` across every open pane
+    fn digest(&self, day: chrono::NaiveDate) -> Digest {
+        // Each pane loaded the saved history, so count every command once
+        let mut seen = std::collections::HashSet::new();
+        let entries = self
+            .tabs
+            .iter()
+            .flat_map(|tab| tab.panes.values())
+            .flat_map(|pane| pane.history.all())
+            .filter(|entry| seen.insert((entry.session_id, entry.timestamp)));
+        Digest::from_entries(entries, day)
+    }
+
+    /// Print the report card asked for by the `digest` command
+    fn handle_digest_requests(&mut self) {
+        let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+        let requests: Vec<(usize, chrono::NaiveDate)> = self.tabs[self.active_tab]
+            .panes
+            .iter_mut()
+            .filter_map(|(&id, pane)| pane.state.requested_digest.take().map(|day| (id, day)))
+            .collect();
+
+        for (id, day) in requests {
+            let digest = self.digest(day);
+            let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&id) else {
+                continue;
+            };
+            let card = digest.render(day, pane.state.home(), self.kawaii.greetings);
+            for line in card.lines() {
+                pane.buffer.push_line(line);
+            }
+            pane.scroll_to_bottom = true;
+            if id == pane_id && self.kawaii.greetings && !digest.is_empty() {
+                self.mascot.set_mood(MascotMood::Proud);
+            }
+        }
+    }
+
+    /// Show the end-of-day toast once a day, after `[digest] toast`
+    fn update_digest_toast(&mut self, ctx: &egui::Context) {
+        let Some(at) = self.config.digest.toast.as_deref() else {
+            return;
+        };
+        let Ok(at) = chrono::NaiveTime::parse_from_str(at.trim(), "%H:%M") else {
+            return;
+        };
+        if let Some((_, shown)) = &self.digest_toast {
+            if shown.elapsed() >= DIGEST_TOAST_DURATION {
+                self.digest_toast = None;
+            }
+        }
+
+        let now = chrono::Local::now();
+        let today = now.date_naive();
+        if self.digest_toast_day == Some(today) {
+            return;
+        }
+        if now.time() < at {
+            // Nothing else wakes the UI when the time comes round
+            ctx.request_repaint_after(std::time::Duration::from_secs(30));
+            return;
+        }
+        self.digest_toast_day = Some(today);
+        let digest = self.digest(today);
+        if digest.is_empty() {
+            return;
+        }
+        self.digest_toast = Some((
+            format!("Today: {} - grade {}", digest.summary(), digest.grade()),
+            std::time::Instant::now(),
+        ));
+        if self.kawaii.greetings {
+            self.mascot.set_mood(MascotMood::Proud);
+        }
+        ctx.request_repaint_after(DIGEST_TOAST_DURATION);
+    }
+
+    /// Show or hide the timestamp gutter as requested by the `timestamps` command
+    fn handle_timestamps_requests(&mut self) {
+        let requested = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_timestamps.take())
+            .last();
+
+        if let Some(mode) = requested {
+            // The command already saved the config; pick up the format too
+            self.config.terminal.timestamps = mode;
+            self.config.terminal.timestamp_format = Config::load().terminal.timestamp_format;
+        }
+    }
+
+    /// Pick up suffix/global aliases saved by `alias -s|-g` and `unalias`
+    fn handle_alias_requests(&mut self) {
+        let requested = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_aliases.take())
+            .last();
+
+        if let Some(aliases) = requested {
+            self.config.aliases = aliases;
+        }
+    }
+
+    /// Use shortcuts rebound by `keys`
+    fn handle_keymap_requests(&mut self) {
+        let requested = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_keymap.take())
+            .last();
+
+        if let Some(keymap) = requested {
+            keymap::set_current(keymap);
+            self.command_palette.refresh_shortcuts();
+        }
+    }
+
+    /// Run the commands saved by `fc` once its editor closes
+    fn run_fc_file(&mut self) {
+        let Some(path) = self.fc_file.take() else {
+            return;
+        };
+        let contents = std::fs::read_to_string(&path).unwrap_or_default();
+        let _ = std::fs::remove_file(&path);
+
+        for line in contents.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            self.execute_command(line);
+        }
+    }
+
+    /// Check out (or create and switch to) a branch picked in the branch finder
+    fn switch_branch(&mut self, name: &str, create: bool) {
+        let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() else {
+            return;
+        };
+        let cwd = pane.state.cwd().to_path_buf();
+        let result = if create {
+            branches::create(&cwd, name)
+        } else {
+            branches::checkout(&cwd, name)
+        };
+        match result {
+            Ok(message) => pane.buffer.push_line(&format!("🌿 {}", message)),
+            Err(e) => pane.buffer.push_error(&format!("Error: {}", e)),
+        }
+        pane.scroll_to_bottom = true;
+        if self.git_panel.open {
+            self.git_panel.refresh(&cwd);
+        }
+    }
+
+    /// Commit with the message written for `gc` once its editor closes
+    fn run_commit_file(&mut self) {
+        let Some(path) = self.commit_file.take() else {
+            return;
+        };
+        self.execute_command(&format!("gc -F '{}'", path.display()));
+    }
+
+    /// Run the startup script in panes that just opened. What it prints and
+    /// where it failed go to the pane; a theme it picks is applied without
+    /// being saved.
+    fn run_startup_scripts(&mut self) {
+        let mut theme = None;
+        let mut user_theme = None;
+        let user_theme_name = self.user_theme.as_ref().map(|user| user.name.clone());
+        for tab in &mut self.tabs {
+            for pane in tab.panes.values_mut() {
+                if !std::mem::take(&mut pane.startup_pending) {
+                    continue;
+                }
+                pane.state.current_theme = self.theme_name;
+                pane.state.current_user_theme = user_theme_name.clone();
+                match self.executor.run_startup_script(&mut pane.state) {
+                    Some(Ok(output)) => {
+                        for line in output.lines() {
+                            pane.buffer.push_line(line);
+                        }
+                    }
+                    Some(Err(e)) => pane.buffer.push_error(&format!("⚠️ {:#}", e)),
+                    None => {}
+                }
+                theme = pane.state.requested_theme.take().or(theme);
+                user_theme = pane.state.requested_user_theme.take().or(user_theme);
+            }
+        }
+        if let Some(user) = user_theme {
+            self.switch_user_theme(user);
+        } else if let Some(theme) = theme {
+            self.switch_theme(theme);
+        }
+    }
+
+    /// Switch every pane to a theme (without saving it)
+    fn switch_theme(&mut self, name: ThemeName) {
+        self.user_theme = None;
+        self.apply_theme(name);
+    }
+
+    /// Switch every pane to a theme file (without saving the choice)
+    fn switch_user_theme(&mut self, user: UserTheme) {
+        let base = user.base;
+        self.user_theme = Some(user);
+        self.apply_theme(base);
+    }
+
+    /// Rebuild the theme from `name` or the theme file in use
+    fn apply_theme(&mut self, name: ThemeName) {
+        self.theme = build_theme(
+            name,
+            self.user_theme.as_ref(),
+            self.kawaii.palette,
+            self.config.theme.color_vision,
+        );
+        self.theme_name = name;
+        let user_name = self.user_theme.as_ref().map(|user| user.name.clone());
+        // Update current_theme on all panes
+        for tab in &mut self.tabs {
+            for pane in tab.panes.values_mut() {
+                pane.state.current_theme = name;
+                pane.state.current_user_theme = user_name.clone();
+            }
+        }
+    }
+
+    /// Resize a pane's text and remember the size for new panes and the
+    /// next launch
+    fn zoom_pane(&mut self, tab: usize, pane_id: usize, request: ZoomRequest) {
+        let Some(pane) = self
+            .tabs
+            .get_mut(tab)
+            .and_then(|tab| tab.panes.get_mut(&pane_id))
+        else {
+            return;
+        };
+        let size = request.apply(pane.state.font_size);
+        pane.state.font_size = size;
+        self.config.font.size = size;
+        if let Err(e) = self.config.save() {
+            eprintln!("Failed to save font size: {}", e);
+        }
+    }
+
+    fn zoom_focused_pane(&mut self, request: ZoomRequest) {
+        let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+        self.zoom_pane(self.active_tab, pane_id, request);
+    }
+
+    /// Pick up saved changes to the theme file in use
+    fn reload_user_theme(&mut self, ctx: &egui::Context) {
+        let Some(user) = &mut self.user_theme else {
+            return;
+        };
+        // Edits from outside the app don't wake the UI by themselves
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        if self.user_theme_checked.elapsed() < std::time::Duration::from_secs(1) {
+            return;
+        }
+        self.user_theme_checked = std::time::Instant::now();
+        match user.reload_if_changed() {
+            Some(Ok(user)) => self.switch_user_theme(user),
+            // Keep the colors it had until the file parses again
+            Some(Err(e)) => {
+                if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                    pane.buffer.push_error(&format!("⚠️ {:#}", e));
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Follow the system light/dark setting (or the clock) in `theme auto`
+    /// mode, picking up changes saved by the `theme auto` command
+    fn update_auto_theme(&mut self, ctx: &egui::Context) {
+        let requested = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_auto_theme.take())
+            .last();
+        if let Some(auto) = requested {
+            self.config.theme = Config::load().theme;
+            // Turning auto off keeps whichever theme it last picked
+            if !auto {
+                if let Err(e) = self.config.set_theme(self.theme_name.config_key()) {
+                    eprintln!("Failed to save theme config: {}", e);
+                }
+            }
+        }
+
+        let system_dark = ctx.system_theme().map(|theme| theme == egui::Theme::Dark);
+        let Some(name) = self
+            .config
+            .theme
+            .auto_theme(system_dark, chrono::Local::now().time())
+        else {
+            return;
+        };
+        if self.config.theme.light_hours.is_some() {
+            // Nothing else wakes the UI when the hour comes round
+            ctx.request_repaint_after(std::time::Duration::from_secs(30));
+        }
+        if name != self.theme_name {
+            self.switch_theme(name);
+        }
+    }
+
+    /// Re-color status colors as requested by `theme --vision`
+    fn handle_color_vision_requests(&mut self) {
+        let requested = self.tabs[self.active_tab]
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_color_vision.take())
+            .last();
+        if let Some(vision) = requested {
+            self.config.theme.color_vision = vision;
+            self.apply_theme(self.theme_name);
+        }
+    }
+
+    /// Switch themes as requested by `theme` in any pane. Picked by hand, so
+    /// auto mode ends and the choice is saved.
+    fn handle_theme_requests(&mut self) {
+        let mut theme = None;
+        let mut user_theme = None;
+        for tab in &mut self.tabs {
+            for pane in tab.panes.values_mut() {
+                theme = pane.state.requested_theme.take().or(theme);
+                user_theme = pane.state.requested_user_theme.take().or(user_theme);
+            }
+        }
+        if let Some(name) = theme {
+            self.switch_theme(name);
+            self.config.theme.auto = false;
+            if let Err(e) = self.config.set_theme(name.config_key()) {
+                eprintln!("Failed to save theme config: {}", e);
+            }
+        }
+        if let Some(user) = user_theme {
+            let name = user.name.clone();
+            self.switch_user_theme(user);
+            self.config.theme.auto = false;
+            if let Err(e) = self.config.set_theme(&name) {
+                eprintln!("Failed to save theme config: {}", e);
+            }
+        }
+    }
+
+    /// Change a pane's text size as requested by `zoom` in it, in any tab
+    fn handle_zoom_requests(&mut self) {
+        let mut requests = Vec::new();
+        for (tab_index, tab) in self.tabs.iter_mut().enumerate() {
+            for (pane_id, pane) in tab.panes.iter_mut() {
+                if let Some(request) = pane.state.requested_zoom.take() {
+                    requests.push((tab_index, *pane_id, request));
+                }
+            }
+        }
+        for (tab, pane_id, request) in requests {
+            self.zoom_pane(tab, pane_id, request);
+        }
+    }
+
+    /// Apply or save pane layouts requested by the `layout` command
+    fn handle_layout_requests(&mut self) {
+        let tab = &mut self.tabs[self.active_tab];
+        let requests: Vec<LayoutRequest> = tab
+            .panes
+            .values_mut()
+            .filter_map(|pane| pane.state.requested_layout.take())
+            .collect();
+
+        for request in requests {
+            match request {
+                LayoutRequest::Apply(layout) => tab.apply_layout(&layout),
+                LayoutRequest::Save(name) => {
+                    // Reload so layouts edited by other commands aren't clobbered
+                    let mut config = Config::load();
+                    config.layouts.insert(name, tab.splits.layout().to_spec());
+                    if let Err(e) = config.save() {
+                        eprintln!("Failed to save layout: {}", e);
+                    }
+                    self.config.layouts = config.layouts;
+                }
+            }
+        }
+    }
+
+    /// Close current tab
+    fn close_current_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active_tab);
+            if self.active_tab >= self.tabs.len() {
+                self.active_tab = self.tabs.len() - 1;
+            }
+        }
+    }
+
+    /// Switch to next tab
+    fn next_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.active_tab = (self.active_tab + 1) % self.tabs.len();
+        }
+    }
+
+    /// Switch to previous tab
+    fn prev_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            if self.active_tab == 0 {
+                self.active_tab = self.tabs.len() - 1;
+            } else {
+                self.active_tab -= 1;
+            }
+        }
+    }
+
+    /// Copy text to clipboard
+    fn copy_to_clipboard(&mut self, text: &str) {
+        if let Some(ref mut clipboard) = self.clipboard {
+            if clipboard.set_text(text.to_string()).is_ok() {
+                self.clipboard_feedback = Some((
+                    format!(
+                        "✨ Copied~ (◕‿◕) {}",
+                        if text.len() > 30 {
+                            format!("{}...", &text[..30])
+                        } else {
+                            text.to_string()
+                        }
+                    ),
                     std::time::Instant::now(),
                 ));
             }
         }
     }
 
+    /// Copy lines `start..=end` of the focused pane keeping their colors
+    fn copy_styled(&mut self, start: usize, end: usize, format: ExportFormat) {
+        let Some(pane) = self.tabs[self.active_tab].focused_pane() else {
+            return;
+        };
+        let lines: Vec<_> = (start..=end)
+            .filter_map(|index| pane.buffer.get_line(index))
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        let styled = export::export(lines.iter().copied(), &self.theme, format);
+        let Some(ref mut clipboard) = self.clipboard else {
+            return;
+        };
+        let copied = match format {
+            // Rich text targets get the HTML, plain text ones the bare lines
+            ExportFormat::Html => {
+                clipboard.set_html(styled, Some(export::plain_text(lines.iter().copied())))
+            }
+            ExportFormat::Ansi => clipboard.set_text(styled),
+        };
+        if copied.is_ok() {
+            self.clipboard_feedback = Some((
+                format!(
+                    "📋 Copied {} line{} as {}~",
+                    lines.len(),
+                    if lines.len() == 1 { "" } else { "s" },
+                    format.name()
+                ),
+                std::time::Instant::now(),
+            ));
+        }
+    }
+
+    /// Copy the block under the vi cursor (or the last block) keeping its colors
+    fn copy_block_styled(&mut self, format: ExportFormat) {
+        let Some(pane) = self.tabs[self.active_tab].focused_pane() else {
+            return;
+        };
+        let block = if pane.vi_mode.active {
+            pane.buffer
+                .get_line(pane.vi_mode.cursor.line)
+                .and_then(|line| line.block_id)
+                .and_then(|id| pane.buffer.get_block(id))
+        } else {
+            pane.buffer.blocks().last()
+        };
+        let Some(block) = block else {
+            return;
+        };
+        // A running block has no end yet
+        let end = if block.end_line > block.start_line {
+            block.end_line
+        } else {
+            pane.buffer.len()
+        };
+        let start = block.start_line;
+        self.copy_styled(start, end.saturating_sub(1), format);
+    }
+
     /// Paste from clipboard
     fn paste_from_clipboard(&mut self) -> Option<String> {
         if let Some(ref mut clipboard) = self.clipboard {
@@ -770,16 +2452,66 @@ impl ZaxiomApp {
         result
     }
 
+    /// Run `inner` in a new split pane, leaving the focused pane free.
+    /// `command` (with its `|> pane`) goes into the focused pane's history.
+    fn run_in_new_pane(&mut self, command: &str, inner: &str) {
+        let tab = &mut self.tabs[self.active_tab];
+        let pane_id = tab.splits.focused_pane_id();
+        let Some(pane) = tab.panes.get_mut(&pane_id) else {
+            return;
+        };
+        let history_commands: Vec<String> = pane.history.all().map(|e| e.command.clone()).collect();
+        let inner = Self::expand_history(inner, &history_commands);
+        pane.history.add(command, pane.state.cwd().clone(), None);
+        pane.history.reset_position();
+
+        if tab.pane_count() >= 4 {
+            if let Some(pane) = tab.panes.get_mut(&pane_id) {
+                pane.buffer
+                    .push_error("Can't open another pane (4 max) - close one with Ctrl+W");
+                pane.scroll_to_bottom = true;
+            }
+            return;
+        }
+
+        let new_pane_id = tab.split_for_command();
+        tab.splits.focus_pane(new_pane_id);
+        self.execute_command(&inner);
+        self.tabs[self.active_tab].splits.focus_pane(pane_id);
+        self.tip_event(TipEvent::FeatureUsed("split"));
+    }
+
     /// Process a command in the focused pane of the current tab
     fn execute_command(&mut self, command: &str) {
+        let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+        if self.refuse_while_busy(pane_id, command) {
+            return;
+        }
+        let tab = &mut self.tabs[self.active_tab];
+
+        // A here-document keeps collecting lines until its delimiter
+        let command = match tab.panes.get_mut(&pane_id) {
+            Some(pane) => match pane.continue_line(command) {
+                Some(command) => command,
+                None => return,
+            },
+            None => command.to_string(),
+        };
+        let command = command.as_str();
         if command.trim().is_empty() {
             return;
         }
+        self.history_up_streak = 0;
+
+        if let Some(inner) = new_pane_command(command) {
+            self.run_in_new_pane(command, inner);
+            return;
+        }
 
         let tab = &mut self.tabs[self.active_tab];
-        let pane_id = tab.splits.focused_pane_id();
+        let pane_context = tab.referenced_pane_context(command);
 
-        let theme_to_apply = if let Some(pane) = tab.panes.get_mut(&pane_id) {
+        let finished = if let Some(pane) = tab.panes.get_mut(&pane_id) {
             // History expansion: !! = last command, !n = nth command
             let history_commands: Vec<String> =
                 pane.history.all().map(|e| e.command.clone()).collect();
@@ -788,53 +2520,87 @@ impl ZaxiomApp {
 
             // Add command to smart history with context
             let cwd = pane.state.cwd().clone();
-            pane.history.add(command, cwd, None);
+            pane.history.add(command, cwd.clone(), None);
+            pane.share_history(command);
+            pane.share_buffer_stats(command);
+            let started = chrono::Local::now();
 
             // Reset history navigation position
             pane.history.reset_position();
             pane.saved_input.clear();
 
+            pane.state.pane_context = pane_context;
+
             // Start a new command block
             pane.buffer.start_block(command);
 
+            // A new command ends an answer still streaming in above it
+            pane.stop_ai_answer();
+
             // Show the prompt + command in output
-            let prompt = pane.state.format_prompt();
-            pane.buffer.push_line(&format!("{}{}", prompt, command));
+            pane.echo_command(command);
 
-            // Route the command to determine execution target
-            let target = self.executor.route_command(command);
+            // Expand aliases, then route the command to determine execution target
+            let expanded = expand_aliases(command, &self.config.aliases, |name| {
+                pane.state.get_alias(name)
+            });
+            let target = self.executor.route_command_in(&expanded, &pane.state);
 
             // Execute based on routing
             let history = pane.history.recent_commands(10);
             let success = match target {
                 ExecutionTarget::PtyRaw => {
-                    // Parse command to get program and args
-                    let parts: Vec<&str> = command.split_whitespace().collect();
-                    if parts.is_empty() {
-                        pane.buffer.push_error("Empty command");
-                        false
-                    } else {
-                        let program = parts[0];
-                        let args: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
-
-                        // Spawn command directly via PTY (no shell intermediary)
-                        match pane.run_via_pty(program, &args) {
-                            Ok(()) => {
-                                pane.input_mode = InputMode::Raw;
-                                pane.buffer.push_line(&format!("[PTY: {}]", command));
-                                true
-                            }
-                            Err(e) => {
-                                pane.buffer.push_error(&format!("PTY error: {}", e));
-                                false
+                    // Spawn via PTY: quoted arguments survive, and pipes or
+                    // `&&` go to the system shell
+                    match pane.run_via_pty(&self.executor, &expanded) {
+                        Ok(()) => {
+                            pane.input_mode = InputMode::Raw;
+                            pane.buffer.push_line(&format!("[PTY: {}]", expanded));
+                            true
+                        }
+                        Err(e) => {
+                            pane.buffer.push_error(&format!("PTY error: {}", e));
+                            false
+                        }
+                    }
+                }
+                ExecutionTarget::Special if expanded.trim_start().starts_with('#') => {
+                    // AI chat: answered on the spot, or streamed in on a worker thread
+                    match prepare_ai_chat(&expanded, &pane.state, Some(&history)) {
+                        AiChat::Reply(reply) => {
+                            for line in reply.lines() {
+                                pane.buffer.push_line(line);
                             }
                         }
+                        AiChat::Ask(question) => {
+                            let header = question.header();
+                            pane.buffer.push_line(&header);
+                            pane.ai_answer = Some(StreamingAnswer {
+                                stream: question.stream(),
+                                header,
+                                lines: 0,
+                            });
+                        }
                     }
+                    true
+                }
+                ExecutionTarget::Background => {
+                    // Slow built-ins run on a worker thread; the block stays
+                    // open until the job is done (see `poll_job`)
+                    let run = CommandRun {
+                        command: command.to_string(),
+                        cwd,
+                        started,
+                        target,
+                    };
+                    pane.start_job(&self.executor, &expanded, history, run);
+                    pane.scroll_to_bottom = true;
+                    return;
                 }
                 ExecutionTarget::Native | ExecutionTarget::Special => {
                     // Execute as native command (instant!)
                     match self.executor.execute_with_history(
-                        command,
+                        &expanded,
                         &mut pane.state,
                         Some(&history),
                     ) {
@@ -848,22 +2614,22 @@ impl ZaxiomApp {
                                 }
                             } else if output.starts_with("\x1b[EXIT") {
                                 self.should_exit = true;
-                            } else if output.starts_with("\x1b[EDIT]") {
+                            } else if let Some(file_path) = output.strip_prefix("\x1b[EDIT]") {
                                 // Open the editor with the specified file
-                                let file_path = output.trim_start_matches("\x1b[EDIT]");
-                                let path = std::path::PathBuf::from(file_path);
-                                match EditorState::new(path.clone()) {
-                                    Ok(editor_state) => {
-                                        self.editor = Some(editor_state);
-                                        pane.buffer.push_line(&format!(
-                                            "📝 Opening {} ...",
-                                            path.display()
-                                        ));
-                                    }
-                                    Err(e) => {
-                                        pane.buffer
-                                            .push_error(&format!("Failed to open file: {}", e));
-                                    }
+                                self.editor = open_editor(file_path, &mut pane.buffer);
+                            } else if let Some(file_path) = output.strip_prefix("\x1b[FC]") {
+                                // fc: edit a previous command, run it when the editor closes
+                                self.editor = open_editor(file_path, &mut pane.buffer);
+                                if self.editor.is_some() {
+                                    self.fc_file = Some(std::path::PathBuf::from(file_path));
+                                    pane.buffer.push_line(FC_NOTE);
+                                }
+                            } else if let Some(file_path) = output.strip_prefix("\x1b[COMMIT]") {
+                                // gc: write the message, commit when the editor closes
+                                self.editor = open_editor(file_path, &mut pane.buffer);
+                                if self.editor.is_some() {
+                                    self.commit_file = Some(std::path::PathBuf::from(file_path));
+                                    pane.buffer.push_line(COMMIT_NOTE);
                                 }
                             } else if !output.is_empty() {
                                 for line in output.lines() {
@@ -873,121 +2639,204 @@ impl ZaxiomApp {
                             true
                         }
                         Err(e) => {
-                            // Kawaii error messages!
-                            let sad_faces =
-                                ["(´;ω;`)", "(◞‸◟)", "(´・ω・`)", "(｡•́︿•̀｡)", "(っ◞‸◟c)"];
-                            let idx = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .map(|d| d.as_nanos() as usize % sad_faces.len())
-                                .unwrap_or(0);
+                            pane.restore_after_syntax_error(command, &expanded, &e);
                             pane.buffer
-                                .push_error(&format!("{} Oopsie~ {}", sad_faces[idx], e));
+                                .push_error(&error_text(&e, self.kawaii.error_faces));
                             false
                         }
                     }
                 }
             };
 
-            // End the command block
-            pane.buffer.end_block(success);
+            let run = CommandRun {
+                command: command.to_string(),
+                cwd,
+                started,
+                target,
+            };
+            // `fg`: the block stays open until the job is done (see `poll_job`)
+            let foreground = pane.state.requested_foreground.take();
+            if let Some(job) = foreground.and_then(|id| pane.state.jobs().take(id)) {
+                pane.foreground_job(job, run);
+                return;
+            }
+            Some((run, success))
+        } else {
+            None
+        };
 
-            // Update smart history with command result
-            if let Some(block) = pane.buffer.blocks().last() {
-                let exit_code = if success { 0 } else { 1 };
-                let duration = block.duration.unwrap_or_default();
-                pane.history.complete_last(exit_code, duration, None);
-            }
-
-            // Show command duration for commands that took significant time
-            if let Some(duration_str) = pane.buffer.last_block_duration() {
-                // Only show for commands that took more than 100ms
-                if let Some(block) = pane.buffer.blocks().last() {
-                    if let Some(dur) = block.duration {
-                        if dur.as_millis() >= 100 {
-                            let status_icon = if success { "✓" } else { "✗" };
-                            pane.buffer.push_line(&format!(
-                                " {} completed in {}",
-                                status_icon, duration_str
-                            ));
-                        }
+        if let Some((run, success)) = finished {
+            self.finish_command(self.active_tab, pane_id, run, success);
+        }
+        // Check for kawaii mode change from any pane
+        let focused_tab = &self.tabs[self.active_tab];
+        let focused_pane_id = focused_tab.splits.focused_pane_id();
+        if let Some(pane) = focused_tab.panes.get(&focused_pane_id) {
+            if pane.state.kawaii_level != self.kawaii_level || pane.state.kawaii != self.kawaii {
+                self.kawaii_level = pane.state.kawaii_level;
+                self.kawaii = pane.state.kawaii;
+                self.mascot.confetti = self.kawaii.confetti;
+                self.style_dirty = true;
+                // Keep other panes in sync
+                let (level, features) = (self.kawaii_level, self.kawaii);
+                for tab in &mut self.tabs {
+                    for pane in tab.panes.values_mut() {
+                        pane.state.kawaii_level = level;
+                        pane.state.kawaii = features;
                     }
                 }
+                // Re-apply theme with kawaii mode
+                self.theme = build_theme(
+                    self.theme_name,
+                    self.user_theme.as_ref(),
+                    self.kawaii.palette,
+                    self.config.theme.color_vision,
+                );
+            }
+        }
+
+        // Update tab title based on focused pane's cwd (or SSH host)
+        self.tabs[self.active_tab].update_title();
+    }
+
+    /// Close a command's block and record how it went: audit log, usage
+    /// stats, history, duration line, mascot, and tips. Background jobs get
+    /// here when they finish, possibly in a pane that is no longer focused.
+    fn finish_command(&mut self, tab: usize, pane_id: usize, run: CommandRun, success: bool) {
+        let focused = tab == self.active_tab
+            && self
+                .tabs
+                .get(tab)
+                .is_some_and(|tab| tab.splits.focused_pane_id() == pane_id);
+        let Some(pane) = self
+            .tabs
+            .get_mut(tab)
+            .and_then(|tab| tab.panes.get_mut(&pane_id))
+        else {
+            return;
+        };
+        let command = run.command.as_str();
+
+        // End the command block
+        pane.buffer.end_block(success);
+
+        // Append to the audit log (PTY programs are still running, so no exit code yet)
+        if let Some(audit) = &mut self.audit {
+            let exit_code = match run.target {
+                ExecutionTarget::PtyRaw if success => None,
+                _ => Some(if success { 0 } else { 1 }),
+            };
+            let record = AuditRecord {
+                command,
+                cwd: &run.cwd,
+                started: run.started,
+                exit_code,
+            };
+            if let Err(e) = audit.record(record) {
+                pane.buffer
+                    .push_error(&format!("⚠️ Failed to write audit log: {}", e));
             }
+        }
+        if self.record_usage {
+            let duration_ms = match run.target {
+                ExecutionTarget::PtyRaw if success => None,
+                _ => Some(elapsed_ms(run.started)),
+            };
+            record_usage(command, &run.cwd, run.started, duration_ms, success);
+        }
 
-            // Let mascot react to command
-            self.mascot.on_command(command, success);
+        // Remember the output for AI context (but not AI answers themselves)
+        if !command.trim_start().starts_with('#') {
+            let last_block_id = pane.buffer.blocks().last().map(|b| b.id);
+            pane.state.last_output = last_block_id.and_then(|id| pane.buffer.get_block_content(id));
+        }
 
-            // Occasionally show kawaii tips (roughly 1 in 20 commands)
-            if success {
-                let tip_chance = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map(|d| d.as_nanos() as usize % 20)
-                    .unwrap_or(1);
+        // Update smart history with command result
+        if let Some(block) = pane.buffer.blocks().last() {
+            let exit_code = if success { 0 } else { 1 };
+            let duration = block.duration.unwrap_or_default();
+            pane.history.complete_last(exit_code, duration, None);
+        }
 
-                if tip_chance == 0 {
-                    let tips = [
-                        "💡 Tip: Try 'pet' to interact with your robot companion! (◕‿◕)",
-                        "✨ Tip: Use Ctrl+Shift+D to split panes horizontally~",
-                        "🎀 Tip: 'fortune | cowsay' for extra fun! ♪(´ε` )",
-                        "💜 Tip: Your mascot reacts differently to various commands!",
-                        "⭐ Tip: 'theme list' shows all 20 beautiful themes~",
-                        "🌸 Tip: Ctrl+C interrupts, Ctrl+Shift+C copies! (◕ᴗ◕✿)",
-                        "🎵 Tip: Try 'neofetch' to see system info with style!",
-                        "💫 Tip: 'cat -s file.rs' shows syntax highlighting!",
-                    ];
-                    let tip_idx = (tip_chance + tips.len()) % tips.len();
-                    pane.buffer.push_line("");
-                    pane.buffer.push_line(tips[tip_idx]);
+        // Show command duration for commands that took significant time
+        if let Some(duration_str) = pane.buffer.last_block_duration() {
+            // Only show for commands that took more than 100ms
+            if let Some(block) = pane.buffer.blocks().last() {
+                if let Some(dur) = block.duration {
+                    if dur.as_millis() >= 100 {
+                        let status_icon = if success { "✓" } else { "✗" };
+                        pane.buffer
+                            .push_line(&format!(" {} completed in {}", status_icon, duration_str));
+                    }
                 }
             }
+        }
 
-            // Check for theme change request and store it
-            let theme_change = pane.state.requested_theme.take();
-            pane.scroll_to_bottom = true;
-            theme_change
-        } else {
-            None
-        };
-
-        // Handle theme change after pane borrow ends
-        if let Some(new_theme_name) = theme_to_apply {
-            let base_theme = Theme::from_name(new_theme_name);
-            self.theme = if self.kawaii_mode {
-                base_theme.apply_kawaii()
-            } else {
-                base_theme
-            };
-            self.theme_name = new_theme_name;
-            // Update current_theme on all panes
-            for tab in &mut self.tabs {
-                for pane in tab.panes.values_mut() {
-                    pane.state.current_theme = new_theme_name;
-                }
-            }
-            // Save to config file
-            if let Err(e) = self.config.set_theme(new_theme_name.config_key()) {
-                eprintln!("Failed to save theme config: {}", e);
+        // Let mascot react to command
+        self.mascot.on_command(command, success);
+
+        // Suggest an unused feature if this command makes one relevant
+        let output_lines = pane
+            .buffer
+            .blocks()
+            .last()
+            .map(|b| b.end_line.saturating_sub(b.start_line + 1))
+            .unwrap_or(0);
+
+        // Built-in output taller than the pane opens in the pager too
+        let rows = pane.pty_grid.dimensions().0;
+        if self.config.terminal.pager
+            && focused
+            && success
+            && matches!(
+                run.target,
+                ExecutionTarget::Native | ExecutionTarget::Background
+            )
+            && output_lines > rows
+            && pane.state.requested_pick.is_none()
+            && pane.state.requested_pager.is_none()
+        {
+            let last_block = pane.buffer.blocks().last().map(|b| b.id);
+            if let Some(text) = last_block.and_then(|id| pane.buffer.get_block_content(id)) {
+                pane.pager.open(command, &text);
             }
         }
 
-        // Check for kawaii mode change from any pane
-        let focused_tab = &self.tabs[self.active_tab];
-        let focused_pane_id = focused_tab.splits.focused_pane_id();
-        if let Some(pane) = focused_tab.panes.get(&focused_pane_id) {
-            if pane.state.kawaii_mode != self.kawaii_mode {
-                self.kawaii_mode = pane.state.kawaii_mode;
-                // Re-apply theme with kawaii mode
-                let base_theme = Theme::from_name(self.theme_name);
-                self.theme = if self.kawaii_mode {
-                    base_theme.apply_kawaii()
-                } else {
-                    base_theme
-                };
-            }
+        self.tips.enabled = self.kawaii.tips;
+        if let Some(tip) = self.tips.on_event(TipEvent::Command {
+            command,
+            success,
+            output_lines,
+        }) {
+            pane.buffer.push_line("");
+            pane.buffer.push_line(&format_tip(tip));
         }
+        pane.warn_if_buffer_full();
+        pane.scroll_to_bottom = true;
+    }
 
-        // Update tab title based on focused pane's cwd
-        self.tabs[self.active_tab].update_title();
+    /// One command at a time per pane: while a background job runs, keep
+    /// the entered line in the input and say what is still running
+    fn refuse_while_busy(&mut self, pane_id: usize, command: &str) -> bool {
+        let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) else {
+            return false;
+        };
+        let Some(running) = &pane.job else {
+            return false;
+        };
+        if command.trim().is_empty() {
+            return true;
+        }
+        let message = format!(
+            "⏳ '{}' is still running - Ctrl+C stops it",
+            running.run.command
+        );
+        if pane.input.is_empty() {
+            pane.input = command.to_string();
+            pane.cursor_to_end = true;
+        }
+        self.clipboard_feedback = Some((message, std::time::Instant::now()));
+        true
     }
 
     /// Update autocomplete suggestions for the focused pane
@@ -1022,6 +2871,7 @@ impl ZaxiomApp {
                 pane.input.len(),
                 pane.state.cwd(),
                 &history,
+                &pane.state.dirs(),
             );
 
             // Show suggestions if we have any and input is not empty
@@ -1056,11 +2906,753 @@ impl ZaxiomApp {
         }
     }
 
+    /// Draw the scrollback minimap along the right edge of `viewport`.
+    /// Returns the scroll fraction (0.0 - 1.0) if the gutter was clicked.
+    fn render_minimap(
+        &self,
+        ui: &mut egui::Ui,
+        viewport: egui::Rect,
+        markers: &[minimap::Marker],
+        total_lines: usize,
+    ) -> Option<f32> {
+        const GUTTER_WIDTH: f32 = 6.0;
+        // Sit just left of the scrollbar
+        let gutter = egui::Rect::from_min_max(
+            egui::pos2(viewport.right() - GUTTER_WIDTH - 2.0, viewport.top()),
+            egui::pos2(viewport.right() - 2.0, viewport.bottom()),
+        );
+
+        let response = ui.interact(
+            gutter,
+            ui.id().with("minimap"),
+            egui::Sense::click_and_drag(),
+        );
+        let painter = ui.painter_at(gutter);
+        painter.rect_filled(
+            gutter,
+            egui::CornerRadius::same(2),
+            self.theme.background_secondary.gamma_multiply(0.6),
+        );
+
+        for (y, kind) in minimap::dedupe_rows(markers, total_lines, gutter.height()) {
+            let (color, height) = match kind {
+                MarkerKind::Block => (self.theme.comment_color, 1.0),
+                MarkerKind::Error => (self.theme.error_color, 2.0),
+                MarkerKind::Match => (self.theme.warning_color, 2.0),
+                MarkerKind::CurrentMatch => (self.theme.accent, 3.0),
+            };
+            let top =
+                (gutter.top() + y - height / 2.0).clamp(gutter.top(), gutter.bottom() - height);
+            painter.rect_filled(
+                egui::Rect::from_min_size(
+                    egui::pos2(gutter.left(), top),
+                    egui::vec2(gutter.width(), height),
+                ),
+                egui::CornerRadius::ZERO,
+                color,
+            );
+        }
+
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if response.clicked() || response.dragged() {
+            response
+                .interact_pointer_pos()
+                .map(|pos| ((pos.y - gutter.top()) / gutter.height()).clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    /// Draw the focused pane's pager over the window, as many lines as fit
+    fn render_pager(&mut self, ctx: &egui::Context) {
+        let (bg, accent, fg, comment) = (
+            self.theme.background_secondary,
+            self.theme.accent,
+            self.theme.foreground,
+            self.theme.comment_color,
+        );
+        let line_height = self.theme.line_height;
+        let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() else {
+            return;
+        };
+        if !pane.pager.active {
+            return;
+        }
+
+        // Fill the window below the tab bar with as many lines as fit
+        let font = egui::FontId::monospace(pane.state.font_size);
+        let row_height = font.size * line_height;
+        let screen = ctx.screen_rect();
+        let rect = egui::Rect::from_min_max(
+            screen.min + egui::vec2(24.0, 48.0),
+            screen.max - egui::vec2(24.0, 24.0),
+        );
+        let margin = 12.0;
+        let status_height = row_height + 12.0;
+        let text_width = rect.width() - 2.0 * margin;
+        let rows = ((rect.height() - 2.0 * margin - status_height) / row_height).max(1.0);
+        pane.pager.set_height(rows as usize);
+
+        let wheel = ctx.input(|i| i.raw_scroll_delta.y);
+        if wheel != 0.0 {
+            let lines = (-wheel / row_height).round() as isize;
+            pane.pager.scroll(if lines == 0 {
+                -wheel.signum() as isize
+            } else {
+                lines
+            });
+        }
+
+        let pager = &pane.pager;
+        egui::Area::new(egui::Id::new("pager"))
+            .fixed_pos(rect.min)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::default()
+                    .fill(bg)
+                    .stroke(egui::Stroke::new(2.0, accent))
+                    .corner_radius(egui::CornerRadius::same(8))
+                    .inner_margin(egui::Margin::same(margin as i8))
+                    .shadow(egui::epaint::Shadow {
+                        spread: 8,
+                        blur: 16,
+                        color: egui::Color32::from_black_alpha(120),
+                        offset: [0, 4],
+                    })
+                    .show(ui, |ui| {
+                        let (text_rect, _) = ui.allocate_exact_size(
+                            egui::vec2(text_width, rows.floor() * row_height),
+                            egui::Sense::hover(),
+                        );
+                        for (row, (_, line)) in pager.visible().enumerate() {
+                            let segments = ansi::parse_ansi(line);
+                            let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+                            let matches = pager.highlights(&text);
+                            let mut job = render::pager_line_job(
+                                &segments,
+                                &matches,
+                                &font,
+                                fg,
+                                (accent, bg),
+                            );
+                            job.wrap = egui::text::TextWrapping::truncate_at_width(text_width);
+                            let galley = ui.fonts(|fonts| fonts.layout_job(job));
+                            let pos = text_rect.min + egui::vec2(0.0, row as f32 * row_height);
+                            ui.painter().galley(pos, galley, fg);
+                        }
+
+                        ui.add_space(6.0);
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.set_width(text_width);
+                            ui.label(
+                                egui::RichText::new(pager.status_text())
+                                    .color(accent)
+                                    .monospace(),
+                            );
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "j/k line · space/b page · g/G top/end · / search · n/N next/prev · q quit",
+                                        )
+                                        .color(comment)
+                                        .size(10.0),
+                                    );
+                                },
+                            );
+                        });
+                    });
+            });
+    }
+
+    /// Draw the `stats` dashboard; closes on Escape or ✕
+    fn render_usage_dashboard(&mut self, ctx: &egui::Context) {
+        let Some(stats) = &self.usage_dashboard else {
+            return;
+        };
+        let (bg, accent, fg, comment) = (
+            self.theme.background_secondary,
+            self.theme.accent,
+            self.theme.foreground,
+            self.theme.comment_color,
+        );
+        let mut close = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+
+        egui::Area::new(egui::Id::new("usage_dashboard"))
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::default()
+                    .fill(bg)
+                    .stroke(egui::Stroke::new(2.0, accent))
+                    .corner_radius(egui::CornerRadius::same(8))
+                    .inner_margin(egui::Margin::same(12))
+                    .shadow(egui::epaint::Shadow {
+                        spread: 8,
+                        blur: 16,
+                        color: egui::Color32::from_black_alpha(120),
+                        offset: [0, 4],
+                    })
+                    .show(ui, |ui| {
+                        ui.set_width(480.0);
+                        let heading = |ui: &mut egui::Ui, text: &str| {
+                            ui.add_space(10.0);
+                            ui.label(egui::RichText::new(text).color(accent).size(12.0));
+                        };
+
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("📊 Usage").color(accent).size(16.0));
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("✕").clicked() {
+                                        close = true;
+                                    }
+                                },
+                            );
+                        });
+                        ui.label(
+                            egui::RichText::new(
+                                "Recorded on this machine only · stats off to stop",
+                            )
+                            .color(comment)
+                            .size(10.0),
+                        );
+
+                        if stats.total == 0 {
+                            ui.add_space(10.0);
+                            ui.label(egui::RichText::new("Nothing recorded yet.").color(fg));
+                            return;
+                        }
+
+                        let average = match stats.average_ms {
+                            Some(ms) if ms < 1_000 => format!("{}ms", ms),
+                            Some(ms) if ms < 60_000 => format!("{:.1}s", ms as f64 / 1000.0),
+                            Some(ms) => format_duration(ms / 1000),
+                            None => "-".to_string(),
+                        };
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} commands   {}% failed   avg {}   {} AI prompts",
+                                stats.total,
+                                stats.failed * 100 / stats.total,
+                                average,
+                                stats.ai_prompts
+                            ))
+                            .color(fg)
+                            .monospace(),
+                        );
+
+                        heading(
+                            ui,
+                            &format!("Commands per day (last {} days)", usage::DAYS_SHOWN),
+                        );
+                        let per_day: Vec<usize> = stats.per_day.iter().map(|(_, n)| *n).collect();
+                        usage_bars(ui, &per_day, accent, comment);
+                        if let (Some((first, _)), Some((last, _))) =
+                            (stats.per_day.first(), stats.per_day.last())
+                        {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(first.format("%b %d").to_string())
+                                        .color(comment)
+                                        .size(10.0),
+                                );
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        ui.label(
+                                            egui::RichText::new(last.format("%b %d").to_string())
+                                                .color(comment)
+                                                .size(10.0),
+                                        );
+                                    },
+                                );
+                            });
+                        }
+
+                        let peak = stats
+                            .busiest_hour()
+                            .map(|hour| format!(" - busiest {:02}:00", hour))
+                            .unwrap_or_default();
+                        heading(ui, &format!("Hours of the day{}", peak));
+                        usage_bars(ui, &stats.by_hour, accent, comment);
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("00:00").color(comment).size(10.0));
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(
+                                        egui::RichText::new("23:00").color(comment).size(10.0),
+                                    );
+                                },
+                            );
+                        });
+
+                        heading(ui, "Top directories");
+                        let most = stats.top_directories.first().map_or(1, |(_, n)| *n);
+                        for (dir, count) in &stats.top_directories {
+                            let bar = "█".repeat((count * 20).div_ceil(most));
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{:<20} {:>5}  {}",
+                                    bar,
+                                    count,
+                                    truncate(dir, 40)
+                                ))
+                                .color(fg)
+                                .monospace()
+                                .size(11.0),
+                            );
+                        }
+                    });
+            });
+
+        if close {
+            self.usage_dashboard = None;
+        }
+    }
+
+    /// Open or close the git panel on the focused pane's repository
+    fn toggle_git_panel(&mut self) {
+        if let Some(pane) = self.tabs[self.active_tab].focused_pane() {
+            let cwd = pane.state.cwd().clone();
+            self.git_panel.toggle(&cwd);
+        }
+    }
+
+    /// Select a file in the git panel and show its diff in the focused pane
+    fn select_git_file(&mut self, index: usize) {
+        let Some(lines) = self.git_panel.select(index) else {
+            return;
+        };
+        let Some(command) = self.git_panel.selected_change().map(|c| c.diff_command()) else {
+            return;
+        };
+        let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() else {
+            return;
+        };
+        // A running command owns the buffer; the file stays selected for staging
+        if pane.job.is_some() || pane.pty_session.is_some() {
+            return;
+        }
+        pane.buffer.start_block(&command);
+        pane.echo_command(&command);
+        for line in &lines {
+            pane.buffer.push_line(line);
+        }
+        pane.buffer.end_block(true);
+    }
+
+    /// Stage, unstage, or discard the selected file (or its current hunk)
+    fn run_git_action(&mut self, action: GitAction, whole_file: bool) {
+        if let Err(e) = self.git_panel.run(action, whole_file) {
+            self.git_panel.error = Some(e.to_string());
+        }
+    }
+
+    /// Draw the git panel (Ctrl+Shift+G) along the right edge
+    fn render_git_panel(&mut self, ctx: &egui::Context) {
+        if !self.git_panel.open {
+            return;
+        }
+        if let Some(pane) = self.tabs[self.active_tab].focused_pane() {
+            let cwd = pane.state.cwd().clone();
+            self.git_panel.refresh_if_stale(&cwd);
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs(2));
+
+        let theme = &self.theme;
+        let (bg, accent, fg, comment) = (
+            theme.background_secondary,
+            theme.accent,
+            theme.foreground,
+            theme.comment_color,
+        );
+        let (added, removed, changed) =
+            (theme.success_color, theme.error_color, theme.warning_color);
+        let panel = &self.git_panel;
+        let mut close = false;
+        let mut select = None;
+        let mut select_hunk = None;
+        let mut action: Option<(usize, GitAction, bool)> = None;
+
+        egui::SidePanel::right("git_panel")
+            .resizable(true)
+            .default_width(280.0)
+            .frame(
+                egui::Frame::default()
+                    .fill(bg)
+                    .inner_margin(egui::Margin::same(8)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("Git").strong().color(accent));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").on_hover_text("Ctrl+Shift+G").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+                ui.separator();
+                if let Some(error) = &panel.error {
+                    ui.colored_label(removed, error);
+                }
+                if panel.changes.is_empty() && panel.error.is_none() {
+                    ui.label(egui::RichText::new("Nothing to commit").color(comment));
+                }
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(ui.available_height() - 24.0)
+                    .show(ui, |ui| {
+                        for section in GitSection::ALL {
+                            let files: Vec<_> = panel
+                                .changes
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, change)| change.section == section)
+                                .collect();
+                            if files.is_empty() {
+                                continue;
+                            }
+                            ui.add_space(4.0);
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} ({})",
+                                    section.title(),
+                                    files.len()
+                                ))
+                                .small()
+                                .color(comment),
+                            );
+                            for (index, change) in files {
+                                let selected = panel.selected == Some(index);
+                                let status_color = match change.status {
+                                    'A' | '?' => added,
+                                    'D' | 'U' => removed,
+                                    _ => changed,
+                                };
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(change.status.to_string())
+                                            .monospace()
+                                            .color(status_color),
+                                    );
+                                    let label = egui::RichText::new(&change.path).color(fg);
+                                    if ui.selectable_label(selected, label).clicked() {
+                                        select = Some(index);
+                                    }
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            if section == GitSection::Staged {
+                                                if ui
+                                                    .small_button("−")
+                                                    .on_hover_text("Unstage file")
+                                                    .clicked()
+                                                {
+                                                    action =
+                                                        Some((index, GitAction::Unstage, true));
+                                                }
+                                                return;
+                                            }
+                                            let armed = selected && panel.is_armed(true);
+                                            let discard = if armed { "Discard?" } else { "↺" };
+                                            if ui
+                                                .small_button(discard)
+                                                .on_hover_text("Discard changes (click twice)")
+                                                .clicked()
+                                            {
+                                                action = Some((index, GitAction::Discard, true));
+                                            }
+                                            if ui
+                                                .small_button("+")
+                                                .on_hover_text("Stage file")
+                                                .clicked()
+                                            {
+                                                action = Some((index, GitAction::Stage, true));
+                                            }
+                                        },
+                                    );
+                                });
+                                if !selected || section == GitSection::Untracked {
+                                    continue;
+                                }
+                                for (number, hunk) in panel.hunks.iter().enumerate() {
+                                    let current = panel.hunk == number;
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(16.0);
+                                        let header = egui::RichText::new(&hunk.header)
+                                            .monospace()
+                                            .small()
+                                            .color(comment);
+                                        if ui.selectable_label(current, header).clicked() {
+                                            select_hunk = Some(number);
+                                        }
+                                        if !current {
+                                            return;
+                                        }
+                                        let mut button = |ui: &mut egui::Ui, text, hover, what| {
+                                            if ui.small_button(text).on_hover_text(hover).clicked()
+                                            {
+                                                action = Some((index, what, false));
+                                            }
+                                        };
+                                        if section == GitSection::Staged {
+                                            button(
+                                                ui,
+                                                "−",
+                                                "Unstage hunk (Alt+U)",
+                                                GitAction::Unstage,
+                                            );
+                                        } else {
+                                            button(ui, "+", "Stage hunk (Alt+S)", GitAction::Stage);
+                                            let discard = if panel.is_armed(false) {
+                                                "Discard?"
+                                            } else {
+                                                "↺"
+                                            };
+                                            button(
+                                                ui,
+                                                discard,
+                                                "Discard hunk (Alt+D twice)",
+                                                GitAction::Discard,
+                                            );
+                                        }
+                                    });
+                                }
+                            }
+                        }
+                    });
+
+                ui.separator();
+                ui.label(
+                    egui::RichText::new(
+                        "Alt+N/P hunk · Alt+S stage · Alt+U unstage · Alt+D discard",
+                    )
+                    .small()
+                    .color(comment),
+                );
+            });
+
+        if close {
+            self.git_panel.open = false;
+        }
+        if let Some(index) = select {
+            self.select_git_file(index);
+        }
+        if let Some(hunk) = select_hunk {
+            self.git_panel.select_hunk(hunk);
+        }
+        if let Some((index, what, whole_file)) = action {
+            // Buttons act on their own row; keep the selection (and an armed
+            // discard) when it is already that file
+            if self.git_panel.selected != Some(index) {
+                self.git_panel.select(index);
+            }
+            self.run_git_action(what, whole_file);
+        }
+    }
+
+    /// Which-key overlay listing the shortcuts that apply right now. Holding
+    /// Ctrl on its own shows the Ctrl shortcuts until it is released; F1 (or
+    /// the palette) pins the full list until F1 or Escape.
+    fn render_shortcut_overlay(&mut self, ctx: &egui::Context) {
+        let pane = self.tabs[self.active_tab].focused_pane();
+        // Raw PTY mode forwards F1 to the program
+        let raw = pane.is_some_and(|p| p.input_mode == InputMode::Raw);
+        let busy = self.editor.is_some() || self.command_palette.is_open;
+        let (ctrl, other_input, f1, escape) = ctx.input(|i| {
+            (
+                i.modifiers.ctrl && !i.modifiers.alt,
+                !i.keys_down.is_empty()
+                    || i.pointer.any_down()
+                    || i.raw_scroll_delta != egui::Vec2::ZERO,
+                keymap::current().pressed(i, Action::Shortcuts),
+                i.key_pressed(egui::Key::Escape),
+            )
+        });
+        if f1 && !raw && !busy {
+            self.shortcuts_pinned = !self.shortcuts_pinned;
+        } else if escape {
+            self.shortcuts_pinned = false;
+        }
+
+        let now = std::time::Instant::now();
+        let held = self.ctrl_hold.update(ctrl, other_input, now) && !busy;
+        if let Some(wait) = self.ctrl_hold.remaining(now).filter(|w| !w.is_zero()) {
+            ctx.request_repaint_after(wait);
+        }
+        if !self.shortcuts_pinned && !held {
+            return;
+        }
+
+        let context = keybindings::Context {
+            tabs: self.tabs.len(),
+            panes: self.tabs[self.active_tab].pane_count(),
+        };
+        let groups = keybindings::available(context, !self.shortcuts_pinned);
+        let (bg, accent, fg, comment, key_color) = (
+            self.theme.background_secondary,
+            self.theme.accent,
+            self.theme.foreground,
+            self.theme.comment_color,
+            self.theme.flag_color,
+        );
+
+        egui::Area::new(egui::Id::new("shortcut_overlay"))
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -60.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::default()
+                    .fill(bg)
+                    .stroke(egui::Stroke::new(2.0, accent))
+                    .corner_radius(egui::CornerRadius::same(8))
+                    .inner_margin(egui::Margin::same(12))
+                    .shadow(egui::epaint::Shadow {
+                        spread: 8,
+                        blur: 16,
+                        color: egui::Color32::from_black_alpha(120),
+                        offset: [0, 4],
+                    })
+                    .show(ui, |ui| {
+                        let title = if self.shortcuts_pinned {
+                            "⌨ Shortcuts · F1 or esc to close"
+                        } else {
+                            "⌨ Ctrl shortcuts · F1 for all"
+                        };
+                        ui.label(egui::RichText::new(title).color(accent).size(14.0));
+                        ui.add_space(6.0);
+                        for row in groups.chunks(3) {
+                            ui.horizontal_top(|ui| {
+                                for (category, bindings) in row {
+                                    ui.vertical(|ui| {
+                                        ui.set_width(230.0);
+                                        ui.label(
+                                            egui::RichText::new(category.title())
+                                                .color(comment)
+                                                .size(11.0),
+                                        );
+                                        for (keys, binding) in bindings {
+                                            ui.horizontal(|ui| {
+                                                ui.label(
+                                                    egui::RichText::new(format!("{:<14}", keys))
+                                                        .color(key_color)
+                                                        .monospace()
+                                                        .size(11.0),
+                                                );
+                                                ui.label(
+                                                    egui::RichText::new(binding.action)
+                                                        .color(fg)
+                                                        .size(11.0),
+                                                );
+                                            });
+                                        }
+                                    });
+                                }
+                            });
+                            ui.add_space(6.0);
+                        }
+                    });
+            });
+    }
+
+    /// File/Edit/View/AI/Help menus, as a menu bar or inside the ☰ button.
+    /// Items name a palette action or a command, and the palette runs them
+    /// next frame, so a menu item does exactly what its shortcut does.
+    fn render_menus(&mut self, ui: &mut egui::Ui) {
+        let mut chosen: Option<String> = None;
+
+        ui.menu_button("File", |ui| {
+            menu_action(ui, "New Tab", &mut chosen);
+            menu_action(ui, "New Window", &mut chosen);
+            menu_action(ui, "Close Tab", &mut chosen);
+            ui.separator();
+            menu_action(ui, "Quit", &mut chosen);
+        });
+        ui.menu_button("Edit", |ui| {
+            menu_action(ui, "Copy Input", &mut chosen);
+            menu_action(ui, "Paste", &mut chosen);
+            menu_action(ui, "Paste with Newlines", &mut chosen);
+            menu_action(ui, "Copy Block as HTML", &mut chosen);
+            menu_action(ui, "Copy Block with ANSI", &mut chosen);
+            ui.separator();
+            menu_action(ui, "Search", &mut chosen);
+            menu_action(ui, "Filter Output", &mut chosen);
+            menu_action(ui, "Collapse Previous Blocks", &mut chosen);
+            menu_action(ui, "Expand All Blocks", &mut chosen);
+            menu_action(ui, "History Search", &mut chosen);
+            ui.separator();
+            menu_action(ui, "Clear", &mut chosen);
+        });
+        ui.menu_button("View", |ui| {
+            ui.menu_button("Theme", |ui| {
+                for theme in ThemeName::all() {
+                    let name = theme.display_name().to_lowercase().replace(' ', "-");
+                    let command = format!("theme {}", name);
+                    menu_command(ui, theme.display_name(), &command, &mut chosen);
+                }
+                ui.separator();
+                menu_command(ui, "Follow System", "theme auto", &mut chosen);
+            });
+            menu_action(ui, "Zoom In", &mut chosen);
+            menu_action(ui, "Zoom Out", &mut chosen);
+            menu_action(ui, "Reset Zoom", &mut chosen);
+            ui.separator();
+            menu_action(ui, "Split Horizontal", &mut chosen);
+            menu_action(ui, "Split Vertical", &mut chosen);
+            ui.menu_button("Layout", |ui| {
+                for (name, description, _) in crate::terminal::layout::PRESETS {
+                    menu_command(ui, description, &format!("layout {}", name), &mut chosen);
+                }
+            });
+            ui.separator();
+            menu_action(ui, "Vi Mode", &mut chosen);
+            menu_action(ui, "Hints Mode", &mut chosen);
+        });
+        ui.menu_button("AI", |ui| {
+            menu_action(ui, "Ask AI", &mut chosen);
+            ui.separator();
+            menu_command(ui, "Provider Status", "ai status", &mut chosen);
+            menu_command(ui, "Providers", "ai providers", &mut chosen);
+            menu_command(ui, "Personas", "ai personas", &mut chosen);
+            ui.separator();
+            menu_command(ui, "Ollama Models", "ollama list", &mut chosen);
+            menu_command(ui, "Ollama Status", "ollama status", &mut chosen);
+        });
+        ui.menu_button("Help", |ui| {
+            menu_action(ui, "Keyboard Shortcuts", &mut chosen);
+            menu_action(ui, "Command Palette", &mut chosen);
+            ui.separator();
+            menu_command(ui, "Commands", "help", &mut chosen);
+            menu_command(ui, "Tips", "tips", &mut chosen);
+        });
+
+        if let Some(action) = chosen {
+            self.menu_action = Some(action);
+            ui.ctx().request_repaint();
+        }
+    }
+
     /// Render the tab bar
     fn render_tab_bar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing.x = 2.0;
 
+            if self.config.terminal.menu == MenuStyle::Hamburger {
+                ui.menu_button("☰", |ui| self.render_menus(ui))
+                    .response
+                    .on_hover_text("Menu");
+            }
+
             let mut tab_to_close: Option<usize> = None;
             let mut tab_to_switch: Option<usize> = None;
 
@@ -1165,20 +3757,674 @@ impl ZaxiomApp {
     }
 }
 
+/// Shown under the editor notice when `fc` opens a command
+const FC_NOTE: &str = "   Runs when you close the editor (save first; empty the file to cancel)";
+
+/// Shown when `gc` opens the editor on a commit message
+const COMMIT_NOTE: &str =
+    "   Commits when you close the editor (save first; an empty message cancels)";
+
+/// Size every text style from `size` (panes use their own zoomed size) and
+/// space lines for `line_height`
+fn set_font_sizes(style: &mut egui::Style, size: f32, line_height: f32) {
+    let font = |scale: f32| egui::FontId::new(size * scale, egui::FontFamily::Monospace);
+    style
+        .text_styles
+        .insert(egui::TextStyle::Monospace, font(1.0));
+    style.text_styles.insert(egui::TextStyle::Body, font(1.0));
+    style.text_styles.insert(egui::TextStyle::Small, font(0.85));
+    style.text_styles.insert(egui::TextStyle::Button, font(1.0));
+    style
+        .text_styles
+        .insert(egui::TextStyle::Heading, font(1.25));
+
+    // Set comfortable spacing
+    style.spacing.item_spacing = egui::vec2(8.0, size * (line_height - 1.0));
+}
+
+/// A theme with the kawaii palette and color vision adjustments applied
+fn build_theme(
+    name: ThemeName,
+    user_theme: Option<&UserTheme>,
+    kawaii_palette: bool,
+    vision: ColorVision,
+) -> Theme {
+    let theme = match user_theme {
+        Some(user) => user.theme.clone(),
+        None => Theme::from_name(name),
+    };
+    let theme = if kawaii_palette {
+        theme.apply_kawaii()
+    } else {
+        theme
+    };
+    let theme = theme.apply_color_vision(vision);
+    // Code in cat, previews, the editor, and diffs follows the theme
+    syntax::set_theme(&theme);
+    theme
+}
+
+/// Byte offset of the input line's cursor (the end if it has none)
+fn input_cursor(ctx: &egui::Context, id: egui::Id, line: &str) -> usize {
+    egui::TextEdit::load_state(ctx, id)
+        .and_then(|state| state.cursor.char_range())
+        .map_or(line.len(), |range| {
+            input::char_to_byte(line, range.primary.index)
+        })
+}
+
+/// Put the input line's cursor at character `index`
+fn set_input_cursor(ctx: &egui::Context, id: egui::Id, index: usize) {
+    let mut state = egui::TextEdit::load_state(ctx, id).unwrap_or_default();
+    let ccursor = egui::text::CCursor::new(index);
+    state
+        .cursor
+        .set_char_range(Some(egui::text::CCursorRange::one(ccursor)));
+    state.store(ctx, id);
+}
+
+/// Dimmed text on an empty `> ` line: what the command is waiting for
+fn continuation_hint(pending: &str) -> String {
+    let waiting = match (pending_heredoc(pending), incomplete(pending)) {
+        (Some(delimiter), _) => format!("here-document until {}", delimiter),
+        (None, Some(Incomplete::Quote(quote))) => {
+            format!("inside a string until the closing {}", quote)
+        }
+        (None, Some(Incomplete::Backslash)) => "the rest of the line".to_string(),
+        (None, Some(Incomplete::Pipe)) => "the command to pipe into".to_string(),
+        (None, None) => "the rest of the block".to_string(),
+    };
+    format!(
+        "{} · Backspace edits the line above · Ctrl+C cancels",
+        waiting
+    )
+}
+
+/// `line[range]` for the editor, in its syntax colors when it has them
+fn code_text(
+    line: &str,
+    colors: Option<&syntax::HighlightedLine>,
+    range: std::ops::Range<usize>,
+    fg: egui::Color32,
+    background: egui::Color32,
+) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let mut append = |text: &str, color: egui::Color32, italics: bool| {
+        job.append(
+            text,
+            0.0,
+            egui::TextFormat {
+                font_id: egui::FontId::monospace(13.0),
+                color,
+                background,
+                italics,
+                ..Default::default()
+            },
+        );
+    };
+    let mut start = 0;
+    for segment in colors.map(Vec::as_slice).unwrap_or_default() {
+        let end = start + segment.text.len();
+        let (from, to) = (start.max(range.start), end.min(range.end));
+        if from < to {
+            let (r, g, b, _) = segment.fg;
+            append(
+                &line[from..to],
+                egui::Color32::from_rgb(r, g, b),
+                segment.italic,
+            );
+        }
+        start = end;
+    }
+    // Text the colors don't cover (none, for a file without a grammar)
+    if start.max(range.start) < range.end {
+        append(&line[start.max(range.start)..range.end], fg, false);
+    }
+    job
+}
+
+/// Timestamp gutter text for line `index` (blank when the line has no
+/// stamp), or `None` when the gutter is off
+fn gutter_text(buffer: &OutputBuffer, index: usize, terminal: &TerminalConfig) -> Option<String> {
+    if terminal.timestamps == TimestampMode::Off {
+        return None;
+    }
+    let format = terminal.timestamp_format.as_str();
+    Some(match buffer.gutter_time(index, terminal.timestamps) {
+        Some(time) => time.format(format).to_string(),
+        None => " ".repeat(
+            chrono::Local::now()
+                .format(format)
+                .to_string()
+                .chars()
+                .count(),
+        ),
+    })
+}
+
+/// Add an output line with its timestamp gutter cell (if any) to the left
+fn with_gutter(
+    ui: &mut egui::Ui,
+    gutter: Option<&str>,
+    color: egui::Color32,
+    add_line: impl FnOnce(&mut egui::Ui),
+) {
+    let Some(gutter) = gutter else {
+        add_line(ui);
+        return;
+    };
+    ui.horizontal_top(|ui| {
+        ui.add(egui::Label::new(
+            egui::RichText::new(format!("{} ", gutter))
+                .monospace()
+                .color(color),
+        ));
+        ui.vertical(|ui| add_line(ui));
+    });
+}
+
+/// One-line summary of a collapsed block (command, exit status, duration,
+/// and line count); a click on it expands the block
+fn block_summary(ui: &mut egui::Ui, block: &CommandBlock, theme: &Theme) -> egui::Response {
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let mut job = egui::text::LayoutJob::default();
+    let mut append = |text: &str, color: egui::Color32| {
+        job.append(
+            text,
+            0.0,
+            egui::TextFormat {
+                font_id: font_id.clone(),
+                color,
+                ..Default::default()
+            },
+        );
+    };
+
+    append("▸ ", theme.comment_color);
+    let mut commands = block.command.lines();
+    append(commands.next().unwrap_or_default(), theme.command_color);
+    if commands.next().is_some() {
+        append(" …", theme.command_color);
+    }
+    let (status, color) = match block.duration {
+        None => ("  running", theme.accent),
+        Some(_) if block.success => ("  ✓", theme.success_color),
+        Some(_) => ("  ✗", theme.error_color),
+    };
+    append(status, color);
+    if let Some(duration) = block.duration {
+        append(
+            &format!("  {}", crate::terminal::buffer::format_duration(duration)),
+            theme.accent,
+        );
+    }
+    let lines = block.output_len();
+    append(
+        &format!("  {} line{}", lines, if lines == 1 { "" } else { "s" }),
+        theme.comment_color,
+    );
+
+    let response = ui
+        .add(egui::Label::new(job).truncate().sense(egui::Sense::click()))
+        .on_hover_text("Click to expand");
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+    }
+    response
+}
+
+/// What a command block's header buttons or context menu asked for
+enum BlockAction {
+    Toggle(usize),
+    CopyOutput(usize),
+    CopyCommand(String),
+    Rerun(String),
+    Edit(String),
+    Delete(usize),
+}
+
+/// Right-click menu of a command block
+fn block_menu(ui: &mut egui::Ui, block: &CommandBlock, action: &mut Option<BlockAction>) {
+    let mut item = |ui: &mut egui::Ui, enabled: bool, label: &str, chosen: BlockAction| {
+        if ui.add_enabled(enabled, egui::Button::new(label)).clicked() {
+            *action = Some(chosen);
+            ui.close_menu();
+        }
+    };
+    let finished = block.duration.is_some();
+    item(ui, true, "Copy Output", BlockAction::CopyOutput(block.id));
+    item(
+        ui,
+        true,
+        "Copy Command",
+        BlockAction::CopyCommand(block.command.clone()),
+    );
+    ui.separator();
+    item(
+        ui,
+        true,
+        "Re-run",
+        BlockAction::Rerun(block.command.clone()),
+    );
+    item(ui, true, "Edit", BlockAction::Edit(block.command.clone()));
+    ui.separator();
+    // A running block is still being written to
+    item(ui, finished, "Delete Block", BlockAction::Delete(block.id));
+}
+
+/// Add an output label, returning the path-like word under the pointer
+fn output_label(ui: &mut egui::Ui, label: egui::Label) -> Option<String> {
+    let (galley_pos, galley, response) = label.layout_in_ui(ui);
+    let hovered = response.hover_pos().and_then(|pointer| {
+        let index = galley.cursor_from_pos(pointer - galley_pos).ccursor.index;
+        preview::path_at(galley.text(), index)
+    });
+
+    if ui.is_rect_visible(response.rect) {
+        let color = ui.style().visuals.text_color();
+        if ui.style().interaction.selectable_labels {
+            egui::text_selection::LabelSelectionState::label_text_selection(
+                ui,
+                &response,
+                galley_pos,
+                galley,
+                color,
+                egui::Stroke::NONE,
+            );
+        } else {
+            ui.painter()
+                .add(egui::epaint::TextShape::new(galley_pos, galley, color));
+        }
+    }
+    hovered
+}
+
+/// Menu item for a palette action, with its shortcut from the registry
+fn menu_action(ui: &mut egui::Ui, action: &str, chosen: &mut Option<String>) {
+    let mut button = egui::Button::new(action);
+    if let Some(keys) = keybindings::shortcut_for(action) {
+        button = button.shortcut_text(keys);
+    }
+    if ui.add(button).clicked() {
+        *chosen = Some(action.to_string());
+        ui.close_menu();
+    }
+}
+
+/// Menu item that runs a command as if typed
+fn menu_command(ui: &mut egui::Ui, label: &str, command: &str, chosen: &mut Option<String>) {
+    if ui.button(label).clicked() {
+        *chosen = Some(command.to_string());
+        ui.close_menu();
+    }
+}
+
+/// Tooltip preview of a file or directory hovered in the output
+fn show_path_preview(
+    ui: &egui::Ui,
+    previews: &mut PreviewCache,
+    theme: &Theme,
+    path: &std::path::Path,
+) {
+    let status = previews.get(ui.ctx(), path);
+    if matches!(status, PreviewStatus::Missing) {
+        return;
+    }
+
+    egui::show_tooltip_at_pointer(
+        ui.ctx(),
+        ui.layer_id(),
+        egui::Id::new("path_preview"),
+        |ui| {
+            ui.set_max_width(560.0);
+            ui.label(
+                egui::RichText::new(path.display().to_string())
+                    .monospace()
+                    .color(theme.accent)
+                    .strong(),
+            );
+            path_preview_contents(ui, previews, theme, path);
+        },
+    );
+}
+
+/// Preview of a file or folder (text, image, or summary) drawn into `ui`
+fn path_preview_contents(
+    ui: &mut egui::Ui,
+    previews: &mut PreviewCache,
+    theme: &Theme,
+    path: &std::path::Path,
+) {
+    let dim = theme.foreground_dim;
+    let (preview, texture) = match previews.get(ui.ctx(), path) {
+        PreviewStatus::Ready(preview, texture) => (preview, texture),
+        PreviewStatus::Missing => return,
+        _ => {
+            ui.label(egui::RichText::new("Loading…").color(dim));
+            return;
+        }
+    };
+    match preview {
+        Preview::Text {
+            lines,
+            size,
+            truncated,
+        } => {
+            let mut job = egui::text::LayoutJob::default();
+            let font = egui::FontId::monospace(12.0);
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    job.append(
+                        "\n",
+                        0.0,
+                        egui::TextFormat::simple(font.clone(), theme.foreground),
+                    );
+                }
+                for (text, color) in line {
+                    let color = color.unwrap_or(theme.foreground);
+                    job.append(text, 0.0, egui::TextFormat::simple(font.clone(), color));
+                }
+            }
+            ui.label(job);
+            let more = if *truncated { " · more below" } else { "" };
+            ui.label(
+                egui::RichText::new(format!("{}{}", format_size(*size), more))
+                    .small()
+                    .color(dim),
+            );
+        }
+        Preview::Image {
+            width,
+            height,
+            size,
+            ..
+        } => {
+            if let Some(texture) = texture {
+                ui.image((texture.id(), texture.size_vec2()));
+            }
+            ui.label(
+                egui::RichText::new(format!("{}×{} · {}", width, height, format_size(*size)))
+                    .color(dim),
+            );
+        }
+        Preview::Binary { size, kind } => {
+            ui.label(egui::RichText::new(format!("{} · {}", kind, format_size(*size))).color(dim));
+        }
+        Preview::Directory { entries, total } => {
+            for entry in entries {
+                ui.label(
+                    egui::RichText::new(entry)
+                        .monospace()
+                        .color(theme.foreground),
+                );
+            }
+            let summary = if *total > entries.len() {
+                format!("{} entries ({} more)", total, total - entries.len())
+            } else {
+                format!("{} entries", total)
+            };
+            ui.label(egui::RichText::new(summary).small().color(dim));
+        }
+    }
+}
+
+/// Bar chart of `values` for the usage dashboard (empty slots shown as a baseline)
+fn usage_bars(ui: &mut egui::Ui, values: &[usize], color: egui::Color32, empty: egui::Color32) {
+    const HEIGHT: f32 = 48.0;
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let slot = rect.width() / values.len().max(1) as f32;
+
+    for (i, &value) in values.iter().enumerate() {
+        let height = (value as f32 / max * HEIGHT).max(1.0);
+        let left = rect.left() + i as f32 * slot + 1.0;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, rect.bottom() - height),
+            egui::pos2(left + (slot - 2.0).max(1.0), rect.bottom()),
+        );
+        let fill = if value == 0 {
+            empty.gamma_multiply(0.5)
+        } else {
+            color
+        };
+        painter.rect_filled(bar, egui::CornerRadius::same(2), fill);
+    }
+}
+
+/// Append a command to the local usage stats (time, directory, and duration only)
+fn record_usage(
+    command: &str,
+    cwd: &std::path::Path,
+    started: chrono::DateTime<chrono::Local>,
+    duration_ms: Option<u64>,
+    success: bool,
+) {
+    let entry = UsageEntry {
+        started: started.to_rfc3339(),
+        cwd: cwd.display().to_string(),
+        duration_ms,
+        success,
+        ai: command.trim_start().starts_with('#'),
+    };
+    if let Err(e) = usage::record(&usage::default_path(), &entry) {
+        eprintln!("Failed to write usage stats: {}", e);
+    }
+}
+
+/// Milliseconds since `started`
+fn elapsed_ms(started: chrono::DateTime<chrono::Local>) -> u64 {
+    (chrono::Local::now() - started).num_milliseconds().max(0) as u64
+}
+
+/// An error as shown under its command, with a sad face when kawaii error
+/// faces are on
+fn error_text(error: &anyhow::Error, faces: bool) -> String {
+    if !faces {
+        return format!("Error: {}", error);
+    }
+    let sad_faces = ["(´;ω;`)", "(◞‸◟)", "(´・ω・`)", "(｡•́︿•̀｡)", "(っ◞‸◟c)"];
+    let idx = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as usize % sad_faces.len())
+        .unwrap_or(0);
+    format!("{} Oopsie~ {}", sad_faces[idx], error)
+}
+
+/// Open the audit log, continuing its hash chain
+fn open_audit_log(config: &Config) -> Option<AuditLog> {
+    match AuditLog::open(config.audit_path()) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            eprintln!("Failed to open audit log: {}", e);
+            None
+        }
+    }
+}
+
+/// Tint an output row that matches the search (stronger for the current
+/// match), and scroll to the current match when the pane asks for it
+fn mark_search_row(
+    ui: &mut egui::Ui,
+    background: egui::layers::ShapeIdx,
+    top: f32,
+    pane: &PaneSession,
+    row: usize,
+    color: egui::Color32,
+) {
+    if !pane.search_mode || pane.search_matches.binary_search(&row).is_err() {
+        return;
+    }
+    let rect = egui::Rect::from_min_max(
+        egui::pos2(ui.max_rect().left(), top),
+        egui::pos2(ui.max_rect().right(), ui.cursor().top()),
+    );
+    let current = pane.current_match_line() == Some(row);
+    let tint = color.gamma_multiply(if current { 0.3 } else { 0.12 });
+    ui.painter()
+        .set(background, egui::Shape::rect_filled(rect, 2.0, tint));
+    if current && pane.search_jump {
+        ui.scroll_to_rect(rect, Some(egui::Align::Center));
+    }
+}
+
+/// Open a file in the built-in editor, reporting the result in the pane
+fn open_editor(file_path: &str, buffer: &mut OutputBuffer) -> Option<EditorState> {
+    let path = std::path::PathBuf::from(file_path);
+    match EditorState::new(path.clone()) {
+        Ok(editor_state) => {
+            buffer.push_line(&format!("📝 Opening {} ...", path.display()));
+            Some(editor_state)
+        }
+        Err(e) => {
+            buffer.push_error(&format!("Failed to open file: {}", e));
+            None
+        }
+    }
+}
+
+/// Render a tip line for the output buffer
+fn format_tip(tip: &Tip) -> String {
+    format!("💡 Tip: {}  ('tips dismiss' to hide)", tip.text)
+}
+
 impl eframe::App for ZaxiomApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.update_frame(ctx, frame);
+
+        // A URL copied by hand (text selection) makes the hints mode tip relevant
+        let copied_url = ctx.output(|o| {
+            o.commands.iter().any(|command| {
+                matches!(command, egui::OutputCommand::CopyText(text) if text.contains("://"))
+            })
+        });
+        if copied_url {
+            self.tip_event(TipEvent::UrlCopied);
+        }
+    }
+}
+
+impl ZaxiomApp {
+    /// Handle input and render one frame
+    fn update_frame(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Check if we should exit
         if self.should_exit {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
             return;
         }
 
-        // Poll PTY output for all panes (non-blocking)
-        for tab in &mut self.tabs {
-            for pane in tab.panes.values_mut() {
+        self.run_startup_scripts();
+        self.run_startup_commands();
+
+        // Layout, tips, history, buffer, pick, audit, stats, digest, timestamp, theme, and zoom changes requested by commands run last frame
+        self.handle_layout_requests();
+        self.handle_tips_requests();
+        self.handle_history_requests();
+        self.handle_buffer_requests();
+        self.handle_pick_requests();
+        self.handle_pager_requests();
+        self.handle_audit_requests();
+        self.handle_stats_requests();
+        self.handle_digest_requests();
+        self.update_digest_toast(ctx);
+        self.handle_timestamps_requests();
+        self.handle_alias_requests();
+        self.handle_keymap_requests();
+        self.handle_workspace_requests();
+        self.handle_session_requests();
+        self.update_auto_theme(ctx);
+        self.reload_user_theme(ctx);
+        self.handle_color_vision_requests();
+        self.handle_theme_requests();
+        self.handle_zoom_requests();
+
+        // Paths of files dropped on the window go in the input line; a
+        // lone folder offers a cd first
+        let mut dropped_text = None;
+        let dropped: Vec<std::path::PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect()
+        });
+        match dropped.as_slice() {
+            [] => {}
+            [folder] if folder.is_dir() => self.dropped_folder = Some(folder.clone()),
+            paths => {
+                let quoted: Vec<String> =
+                    paths.iter().map(|path| input::quote_path(path)).collect();
+                dropped_text = Some(quoted.join(" "));
+            }
+        }
+
+        if self.style_dirty {
+            Self::apply_style(ctx, &self.theme, self.kawaii.rounded_corners);
+            self.style_dirty = false;
+        }
+
+        // Poll PTY output, streaming AI answers, and background jobs for all panes (non-blocking)
+        let mut answered = None;
+        let mut finished_jobs = Vec::new();
+        for (tab_index, tab) in self.tabs.iter_mut().enumerate() {
+            let mut pty_closed = false;
+            for (&pane_id, pane) in tab.panes.iter_mut() {
+                let had_pty = pane.pty_session.is_some();
                 pane.poll_pty_output();
+                pty_closed |= had_pty && pane.pty_session.is_none();
+                pane.poll_mock_log();
+                if let Some(ok) = pane.poll_ai_answer() {
+                    answered = Some(ok);
+                }
+                if let Some((run, success)) = pane.poll_job(self.kawaii.error_faces) {
+                    finished_jobs.push((tab_index, pane_id, run, success));
+                }
+                pane.report_jobs(self.kawaii.error_faces);
+            }
+            if pty_closed {
+                // Drop an SSH session's user@host title
+                tab.update_title();
             }
         }
+        for (tab, pane_id, run, success) in finished_jobs {
+            self.finish_command(tab, pane_id, run, success);
+            ctx.request_repaint();
+        }
+        if self
+            .tabs
+            .iter()
+            .any(|tab| tab.panes.values().any(|pane| pane.job.is_some()))
+        {
+            // Keep the spinners turning and the output flowing
+            ctx.request_repaint_after(std::time::Duration::from_millis(30));
+        } else if self
+            .tabs
+            .iter()
+            .any(|tab| tab.panes.values().any(|pane| !pane.state.jobs().is_empty()))
+        {
+            // Notice numbered jobs finishing
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+
+        // The mascot thinks until every answer is in
+        let streaming = self
+            .tabs
+            .iter()
+            .any(|tab| tab.panes.values().any(|pane| pane.ai_answer.is_some()));
+        self.mascot.waiting = streaming;
+        if let Some(ok) = answered.filter(|_| !streaming) {
+            self.mascot.set_mood(if ok {
+                MascotMood::Happy
+            } else {
+                MascotMood::Sad
+            });
+        }
+        if streaming {
+            ctx.request_repaint_after(std::time::Duration::from_millis(30));
+        }
 
         // Request repaint if any PTY is active (for streaming output)
         let has_active_pty = self
@@ -1189,8 +4435,20 @@ impl eframe::App for ZaxiomApp {
             ctx.request_repaint();
         }
 
+        // Keep checking for mock server requests while one is running
+        let has_mock_server = self.tabs.iter().any(|tab| {
+            tab.panes
+                .values()
+                .any(|pane| pane.state.mock_server.is_some())
+        });
+        if has_mock_server {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
         // Track search toggle
         let mut toggle_search = false;
+        let mut toggle_filter = false;
+        let mut collapse_blocks = false;
         let mut search_next = false;
         let mut search_prev = false;
         let mut split_horizontal = false;
@@ -1199,12 +4457,13 @@ impl eframe::App for ZaxiomApp {
         let mut paste_input = false;
         let mut paste_raw = false;
         let mut interrupt_input = false;
+        let mut suspend_input = false;
         let mut clear_screen = false;
-        let mut clear_line_to_start = false;
         let mut insert_last_arg = false;
         let mut close_pane = false;
         let mut focus_next_pane = false;
         let mut focus_prev_pane = false;
+        let mut resize_pane: Option<(SplitDirection, f32)> = None;
         let mut toggle_hints = false;
         let mut toggle_vi_mode = false;
         let mut hints_filter_char: Option<char> = None;
@@ -1212,7 +4471,7 @@ impl eframe::App for ZaxiomApp {
         let mut hints_tab = false;
         let mut hints_enter = false;
         let mut vi_key_char: Option<char> = None;
-        let mut vi_ctrl_key: Option<char> = None;
+        let mut pager_key: Option<char> = None;
         let mut palette_up = false;
         let mut palette_down = false;
         let mut palette_enter = false;
@@ -1235,21 +4494,35 @@ impl eframe::App for ZaxiomApp {
         let mut editor_end = false;
         let mut editor_ctrl_home = false;
         let mut editor_ctrl_end = false;
+        let mut editor_next_conflict = false;
+        let mut editor_prev_conflict = false;
+        let mut editor_take: Option<ConflictSide> = None;
         let editor_is_open = self.editor.is_some();
         let palette_was_open = self.command_palette.is_open;
         let mut fuzzy_history = false;
         let mut fuzzy_files = false;
         let mut fuzzy_branches = false;
+        let mut fuzzy_recent_files = false;
+        let mut fuzzy_directories = false;
+        let mut fuzzy_results = false;
+        let mut toggle_git_panel = false;
+        let mut git_hunk_step: isize = 0;
+        let mut git_action: Option<GitAction> = None;
+        let git_panel_open = self.git_panel.open;
         let mut fuzzy_up = false;
         let mut fuzzy_down = false;
         let mut fuzzy_enter = false;
         let mut fuzzy_ctrl_enter = false;
+        let mut fuzzy_shift_enter = false;
+        let mut fuzzy_ctrl_n = false;
         let mut fuzzy_escape = false;
         let mut fuzzy_char: Option<char> = None;
         let mut fuzzy_backspace = false;
         let mut pty_raw_key: Option<egui::Key> = None;
         let mut pty_raw_char: Option<char> = None;
         let mut pty_raw_modifiers = egui::Modifiers::NONE;
+        let mut zoom: Option<ZoomRequest> = None;
+        let mut custom_shortcut: Option<String> = None;
 
         // Check focused pane's mode states
         let focused_in_search = self.tabs[self.active_tab]
@@ -1268,12 +4541,24 @@ impl eframe::App for ZaxiomApp {
             .focused_pane()
             .map(|p| p.fuzzy_finder.active)
             .unwrap_or(false);
+        let focused_in_pager = self.tabs[self.active_tab]
+            .focused_pane()
+            .is_some_and(|p| p.pager.active);
         let focused_in_pty_raw = self.tabs[self.active_tab]
             .focused_pane()
             .map(|p| p.input_mode == InputMode::Raw)
             .unwrap_or(false);
+        // Full-screen programs (vim, less) keep Ctrl+F for themselves
+        let focused_in_pty_fullscreen = self.tabs[self.active_tab]
+            .focused_pane()
+            .is_some_and(|p| p.pty_grid.is_alternate_screen());
+        // Ctrl+W deletes a word while there is one to delete
+        let input_has_text = self.tabs[self.active_tab]
+            .focused_pane()
+            .is_some_and(|p| !p.input.is_empty() && p.input_mode != InputMode::Raw);
 
         // Handle keyboard shortcuts
+        let keymap = keymap::current();
         ctx.input(|i| {
             // Handle editor keyboard when open - editor consumes all input FIRST
             if editor_is_open {
@@ -1286,6 +4571,22 @@ impl eframe::App for ZaxiomApp {
                 if i.modifiers.ctrl && i.key_pressed(egui::Key::S) {
                     editor_save = true;
                 }
+                // Merge conflict navigation and resolution
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::N) {
+                    editor_next_conflict = true;
+                }
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::P) {
+                    editor_prev_conflict = true;
+                }
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::O) {
+                    editor_take = Some(ConflictSide::Ours);
+                }
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::T) {
+                    editor_take = Some(ConflictSide::Theirs);
+                }
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::B) {
+                    editor_take = Some(ConflictSide::Both);
+                }
                 if i.key_pressed(egui::Key::Backspace) {
                     editor_backspace = true;
                 }
@@ -1336,34 +4637,47 @@ impl eframe::App for ZaxiomApp {
                 return;
             }
 
+            let pressed = |action| keymap.pressed(i, action);
+
             // Zaxiom shortcuts that always work (even in raw PTY mode)
-            let is_zaxiom_shortcut = (i.modifiers.ctrl
-                && !i.modifiers.shift
-                && (
-                    i.key_pressed(egui::Key::T) ||  // New tab
-                    i.key_pressed(egui::Key::P) ||  // Command palette
-                    i.key_pressed(egui::Key::W) ||  // Close pane/tab
-                    i.key_pressed(egui::Key::Tab)
-                    // Switch focus
-                ))
-                || (i.modifiers.ctrl
-                    && i.modifiers.shift
-                    && (
-                        i.key_pressed(egui::Key::E) ||  // Split horizontal
-                    i.key_pressed(egui::Key::D)
-                        // Split vertical
-                    ));
+            let is_zaxiom_shortcut = [
+                Action::NewTab,
+                Action::CommandPalette,
+                Action::NextTab,
+                Action::PrevTab,
+                Action::SplitHorizontal,
+                Action::SplitVertical,
+                Action::ZoomIn,
+                Action::ZoomOut,
+                Action::ResetZoom,
+            ]
+            .into_iter()
+            .any(pressed)
+                // Close pane/tab
+                || (i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::W))
+                // Search streaming output
+                || (pressed(Action::Search) && !focused_in_pty_fullscreen);
 
             // Handle these shortcuts FIRST, before raw PTY mode
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::T) {
+            if pressed(Action::NewTab) {
                 self.new_tab();
             }
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::P) {
+            if pressed(Action::CommandPalette) {
                 self.command_palette.toggle();
+                self.tips.mark_used("palette");
+            }
+            // Ctrl+= / Ctrl+-: Zoom the focused pane's text, Ctrl+0: Reset it
+            if pressed(Action::ZoomIn) {
+                zoom = Some(ZoomRequest::In);
+            } else if pressed(Action::ZoomOut) {
+                zoom = Some(ZoomRequest::Out);
+            } else if pressed(Action::ResetZoom) {
+                zoom = Some(ZoomRequest::Reset);
             }
 
             // Handle raw PTY mode - pass non-Zaxiom input directly to PTY
-            if focused_in_pty_raw && !is_zaxiom_shortcut {
+            // (the search bar takes the keyboard while it is open)
+            if focused_in_pty_raw && !is_zaxiom_shortcut && !focused_in_search {
                 // Escape exits raw mode
                 if i.key_pressed(egui::Key::Escape) {
                     // Will be handled below to exit raw mode
@@ -1440,6 +4754,10 @@ impl eframe::App for ZaxiomApp {
                 // Raw mode consumes remaining input (Zaxiom shortcuts already handled above)
                 return;
             }
+            // Shortcuts of custom palette entries from config.toml
+            if !self.command_palette.is_open {
+                custom_shortcut = self.command_palette.custom_pressed(i);
+            }
             // Handle command palette keyboard when open - palette consumes all input
             if self.command_palette.is_open {
                 if i.key_pressed(egui::Key::Escape) {
@@ -1514,8 +4832,8 @@ impl eframe::App for ZaxiomApp {
                 // Early return - palette consumes all keyboard input
                 return;
             }
-            // Ctrl+W: Close current tab (or pane if multiple)
-            if i.modifiers.ctrl && i.key_pressed(egui::Key::W) {
+            // Ctrl+W: Close current tab (or pane if multiple) from an empty line
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::W) && !input_has_text {
                 if self.tabs[self.active_tab].pane_count() > 1 {
                     close_pane = true;
                 } else {
@@ -1523,7 +4841,7 @@ impl eframe::App for ZaxiomApp {
                 }
             }
             // Ctrl+F: Toggle search
-            if i.modifiers.ctrl && i.key_pressed(egui::Key::F) {
+            if pressed(Action::Search) {
                 toggle_search = true;
             }
             // Escape: Close search if open
@@ -1539,59 +4857,79 @@ impl eframe::App for ZaxiomApp {
                 }
             }
             // Ctrl+Tab: Next tab
-            if i.modifiers.ctrl && i.key_pressed(egui::Key::Tab) {
-                if i.modifiers.shift {
-                    self.prev_tab();
-                } else {
-                    self.next_tab();
-                }
+            if pressed(Action::NextTab) {
+                self.next_tab();
+            }
+            if pressed(Action::PrevTab) {
+                self.prev_tab();
             }
 
             // Ctrl+Shift+D: Split horizontal
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::D) {
+            if pressed(Action::SplitHorizontal) {
                 split_horizontal = true;
             }
             // Ctrl+Shift+E: Split vertical
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::E) {
+            if pressed(Action::SplitVertical) {
                 split_vertical = true;
             }
             // Alt+Arrow: Navigate between panes
-            if i.modifiers.alt && i.key_pressed(egui::Key::ArrowRight) {
+            if pressed(Action::NextPane) {
                 focus_next_pane = true;
             }
-            if i.modifiers.alt && i.key_pressed(egui::Key::ArrowLeft) {
+            if pressed(Action::PrevPane) {
                 focus_prev_pane = true;
             }
+            // Ctrl+Shift+Arrow: Move the divider beside the focused pane
+            if pressed(Action::ResizeLeft) {
+                resize_pane = Some((SplitDirection::Vertical, -RESIZE_STEP));
+            }
+            if pressed(Action::ResizeRight) {
+                resize_pane = Some((SplitDirection::Vertical, RESIZE_STEP));
+            }
+            if pressed(Action::ResizeUp) {
+                resize_pane = Some((SplitDirection::Horizontal, -RESIZE_STEP));
+            }
+            if pressed(Action::ResizeDown) {
+                resize_pane = Some((SplitDirection::Horizontal, RESIZE_STEP));
+            }
             // Ctrl+Shift+C: Copy current input (terminal style)
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::C) {
+            if pressed(Action::CopyInput) {
                 copy_input = true;
             }
             // Ctrl+C: Interrupt (clear current line, like real terminal)
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::C) {
+            if pressed(Action::Interrupt) {
                 interrupt_input = true;
             }
+            // Ctrl+Z: Suspend the running job (see `jobs`)
+            if pressed(Action::Suspend) {
+                suspend_input = true;
+            }
             // Ctrl+V: Paste (clean - strip newlines)
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::V) {
+            if pressed(Action::Paste) {
                 paste_input = true;
             }
             // Ctrl+Shift+V: Paste raw (keep newlines)
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::V) {
+            if pressed(Action::PasteRaw) {
                 paste_raw = true;
             }
             // Ctrl+L: Clear screen (like real terminal)
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::L) {
+            if pressed(Action::Clear) {
                 clear_screen = true;
             }
-            // Ctrl+U: Clear line from cursor to beginning
-            if i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::U) {
-                clear_line_to_start = true;
+            // Ctrl+Shift+L: Toggle output filter bar
+            if pressed(Action::FilterOutput) {
+                toggle_filter = true;
+            }
+            // Ctrl+Shift+K: Collapse every block before the last
+            if pressed(Action::CollapseBlocks) {
+                collapse_blocks = true;
             }
             // Alt+.: Insert last argument from previous command
-            if i.modifiers.alt && i.key_pressed(egui::Key::Period) {
+            if pressed(Action::InsertLastArg) {
                 insert_last_arg = true;
             }
             // Ctrl+Shift+H: Toggle hints mode
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::H) {
+            if pressed(Action::HintsMode) {
                 toggle_hints = true;
             }
             // Escape: Exit hints/vi/fuzzy mode
@@ -1605,27 +4943,53 @@ impl eframe::App for ZaxiomApp {
                 }
             }
             // Ctrl+Shift+M: Toggle vi mode (like Alacritty)
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::M) {
+            if pressed(Action::ViMode) {
                 toggle_vi_mode = true;
             }
             // Ctrl+R: Fuzzy history search
-            if i.modifiers.ctrl
-                && !i.modifiers.shift
-                && i.key_pressed(egui::Key::R)
-                && !focused_in_fuzzy
-            {
+            if pressed(Action::HistorySearch) && !focused_in_fuzzy {
                 fuzzy_history = true;
             }
             // Ctrl+Shift+F: Fuzzy file search (Ctrl+F is for search in buffer)
-            if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::F) {
+            if pressed(Action::FindFile) {
                 fuzzy_files = true;
             }
+            // Ctrl+Shift+O: Fuzzy search files used by recent commands
+            if pressed(Action::RecentFiles) {
+                fuzzy_recent_files = true;
+            }
+            // Ctrl+Shift+J: Jump to a stacked or recently used directory
+            if pressed(Action::JumpToDirectory) {
+                fuzzy_directories = true;
+            }
+            // Ctrl+Shift+S: Fuzzy search grep/find hits in the last output
+            if pressed(Action::PickSearchResult) && !focused_in_fuzzy {
+                fuzzy_results = true;
+            }
+            // Ctrl+Shift+G: Toggle the git panel
+            if pressed(Action::GitPanel) {
+                toggle_git_panel = true;
+            }
+            // Alt+N/P, Alt+S/U/D: Move between and act on hunks in the git panel
+            if git_panel_open && i.modifiers.alt && !i.modifiers.ctrl && !focused_in_pty_raw {
+                if i.key_pressed(egui::Key::N) {
+                    git_hunk_step = 1;
+                }
+                if i.key_pressed(egui::Key::P) {
+                    git_hunk_step = -1;
+                }
+                if i.key_pressed(egui::Key::S) {
+                    git_action = Some(GitAction::Stage);
+                }
+                if i.key_pressed(egui::Key::U) {
+                    git_action = Some(GitAction::Unstage);
+                }
+                if i.key_pressed(egui::Key::D) {
+                    git_action = Some(GitAction::Discard);
+                }
+            }
             // Ctrl+G: Fuzzy git branches
-            if i.modifiers.ctrl
-                && !i.modifiers.shift
-                && i.key_pressed(egui::Key::G)
-                && !focused_in_fuzzy
-            {
+            if pressed(Action::SwitchBranch) && !focused_in_fuzzy {
                 fuzzy_branches = true;
             }
             // Handle fuzzy finder keyboard input - fuzzy finder consumes all input
@@ -1639,10 +5003,16 @@ impl eframe::App for ZaxiomApp {
                 if i.key_pressed(egui::Key::Enter) {
                     if i.modifiers.ctrl {
                         fuzzy_ctrl_enter = true;
+                    } else if i.modifiers.shift {
+                        fuzzy_shift_enter = true;
                     } else {
                         fuzzy_enter = true;
                     }
                 }
+                // Ctrl+N: create a branch named by the query (branches mode)
+                if i.modifiers.ctrl && i.key_pressed(egui::Key::N) {
+                    fuzzy_ctrl_n = true;
+                }
                 if i.key_pressed(egui::Key::Backspace) {
                     fuzzy_backspace = true;
                 }
@@ -1709,120 +5079,23 @@ impl eframe::App for ZaxiomApp {
                 }
             }
             // Handle vi mode keyboard input
-            if focused_in_vi && !i.modifiers.alt {
-                // Ctrl key combinations
-                if i.modifiers.ctrl {
-                    if i.key_pressed(egui::Key::F) {
-                        vi_ctrl_key = Some('\x06');
-                    }
-                    // Ctrl+F page down
-                    else if i.key_pressed(egui::Key::B) {
-                        vi_ctrl_key = Some('\x02');
-                    }
-                    // Ctrl+B page up
-                    else if i.key_pressed(egui::Key::D) {
-                        vi_ctrl_key = Some('\x04');
-                    }
-                    // Ctrl+D half page down
-                    else if i.key_pressed(egui::Key::U) {
-                        vi_ctrl_key = Some('\x15');
-                    }
-                    // Ctrl+U half page up
-                    else if i.key_pressed(egui::Key::O) {
-                        vi_ctrl_key = Some('\x0f');
-                    }
-                    // Ctrl+O jump back
-                    else if i.key_pressed(egui::Key::I) {
-                        vi_ctrl_key = Some('\x09');
-                    } // Ctrl+I jump forward
-                } else {
-                    // Regular keys
-                    for (key, ch) in [
-                        (egui::Key::A, 'a'),
-                        (egui::Key::B, 'b'),
-                        (egui::Key::C, 'c'),
-                        (egui::Key::D, 'd'),
-                        (egui::Key::E, 'e'),
-                        (egui::Key::F, 'f'),
-                        (egui::Key::G, 'g'),
-                        (egui::Key::H, 'h'),
-                        (egui::Key::I, 'i'),
-                        (egui::Key::J, 'j'),
-                        (egui::Key::K, 'k'),
-                        (egui::Key::L, 'l'),
-                        (egui::Key::M, 'm'),
-                        (egui::Key::N, 'n'),
-                        (egui::Key::O, 'o'),
-                        (egui::Key::P, 'p'),
-                        (egui::Key::Q, 'q'),
-                        (egui::Key::R, 'r'),
-                        (egui::Key::S, 's'),
-                        (egui::Key::T, 't'),
-                        (egui::Key::U, 'u'),
-                        (egui::Key::V, 'v'),
-                        (egui::Key::W, 'w'),
-                        (egui::Key::X, 'x'),
-                        (egui::Key::Y, 'y'),
-                        (egui::Key::Z, 'z'),
-                    ] {
-                        if i.key_pressed(key) {
-                            vi_key_char = Some(if i.modifiers.shift {
-                                ch.to_ascii_uppercase()
-                            } else {
-                                ch
-                            });
-                            break;
-                        }
-                    }
-                    // Number keys
-                    for (key, ch) in [
-                        (egui::Key::Num0, '0'),
-                        (egui::Key::Num1, '1'),
-                        (egui::Key::Num2, '2'),
-                        (egui::Key::Num3, '3'),
-                        (egui::Key::Num4, '4'),
-                        (egui::Key::Num5, '5'),
-                        (egui::Key::Num6, '6'),
-                        (egui::Key::Num7, '7'),
-                        (egui::Key::Num8, '8'),
-                        (egui::Key::Num9, '9'),
-                    ] {
-                        if i.key_pressed(key) {
-                            vi_key_char = Some(ch);
-                            break;
-                        }
-                    }
-                    // Special keys
-                    if i.key_pressed(egui::Key::Escape) {
-                        vi_key_char = Some('\x1b');
-                    }
-                    if i.key_pressed(egui::Key::Enter) {
-                        vi_key_char = Some('\n');
-                    }
-                    if i.key_pressed(egui::Key::Backspace) {
-                        vi_key_char = Some('\x08');
-                    }
-                    if i.key_pressed(egui::Key::Minus) {
-                        vi_key_char = Some('-');
-                    }
-                    if i.key_pressed(egui::Key::Space) {
-                        vi_key_char = Some(' ');
-                    }
-                    // Shift+4 = $, Shift+6 = ^, Shift+/ = ?
-                    if i.modifiers.shift {
-                        if i.key_pressed(egui::Key::Num4) {
-                            vi_key_char = Some('$');
-                        }
-                        if i.key_pressed(egui::Key::Num6) {
-                            vi_key_char = Some('^');
-                        }
-                        if i.key_pressed(egui::Key::Slash) {
-                            vi_key_char = Some('?');
-                        }
-                    } else if i.key_pressed(egui::Key::Slash) {
-                        vi_key_char = Some('/');
-                    }
-                }
+            if focused_in_vi {
+                vi_key_char = vi_mode::key_char(i);
+            }
+            // The pager reads keys as vi mode does, with the arrow and page
+            // keys as Ctrl+N/P/F/B
+            if focused_in_pager {
+                pager_key = vi_mode::key_char(i).or_else(|| {
+                    [
+                        (egui::Key::ArrowDown, '\x0e'),
+                        (egui::Key::ArrowUp, '\x10'),
+                        (egui::Key::PageDown, '\x06'),
+                        (egui::Key::PageUp, '\x02'),
+                    ]
+                    .into_iter()
+                    .find(|&(key, _)| i.key_pressed(key))
+                    .map(|(_, ch)| ch)
+                });
             }
             // Ctrl+1-9: Switch to specific tab
             for (key, idx) in [
@@ -1844,10 +5117,21 @@ impl eframe::App for ZaxiomApp {
 
         // Handle search toggle/navigation
         if toggle_search {
+            self.tips.mark_used("search");
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 pane.toggle_search();
             }
         }
+        if toggle_filter {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.filter.toggle();
+            }
+        }
+        if collapse_blocks {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.buffer.collapse_previous();
+            }
+        }
         if search_next {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 pane.next_match();
@@ -1862,9 +5146,11 @@ impl eframe::App for ZaxiomApp {
         // Handle split pane actions (max 4 panes)
         if split_horizontal && self.tabs[self.active_tab].pane_count() < 4 {
             self.tabs[self.active_tab].split(SplitDirection::Horizontal);
+            self.tip_event(TipEvent::FeatureUsed("split"));
         }
         if split_vertical && self.tabs[self.active_tab].pane_count() < 4 {
             self.tabs[self.active_tab].split(SplitDirection::Vertical);
+            self.tip_event(TipEvent::FeatureUsed("split"));
         }
         if close_pane {
             self.tabs[self.active_tab].close_focused_pane();
@@ -1875,6 +5161,13 @@ impl eframe::App for ZaxiomApp {
         if focus_prev_pane {
             self.tabs[self.active_tab].splits.focus_prev();
         }
+        if let Some((direction, delta)) = resize_pane {
+            if self.tabs[self.active_tab].pane_count() > 1 {
+                self.tabs[self.active_tab]
+                    .splits
+                    .resize_toward(direction, delta);
+            }
+        }
 
         // Handle clipboard actions
         if copy_input {
@@ -1888,9 +5181,49 @@ impl eframe::App for ZaxiomApp {
         }
 
         // Handle Ctrl+C interrupt (clear line like real terminal)
-        if interrupt_input {
+        let stopped_job = if interrupt_input {
+            self.tabs[self.active_tab]
+                .focused_pane_mut()
+                .and_then(|pane| pane.stop_job())
+        } else {
+            None
+        };
+        let suspended_job = if suspend_input {
+            self.tabs[self.active_tab]
+                .focused_pane_mut()
+                .and_then(|pane| pane.suspend_job())
+        } else {
+            None
+        };
+        if let Some(run) = suspended_job {
+            let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+            self.finish_command(self.active_tab, pane_id, run, false);
+            self.clipboard_feedback = Some((
+                "^Z (job suspended - fg resumes it)".to_string(),
+                std::time::Instant::now(),
+            ));
+        }
+        if let Some(run) = stopped_job {
+            let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+            self.finish_command(self.active_tab, pane_id, run, false);
+            self.clipboard_feedback =
+                Some(("^C (job stopped)".to_string(), std::time::Instant::now()));
+        } else if interrupt_input {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                if !pane.input.is_empty() {
+                if pane.stop_ai_answer() {
+                    self.clipboard_feedback = Some((
+                        "^C (AI answer stopped)".to_string(),
+                        std::time::Instant::now(),
+                    ));
+                } else if let Some(pending) = pane.continuation.take() {
+                    // Abandon the unfinished command
+                    pane.echo_command(&format!("{}\n{}^C", pending, pane.input));
+                    pane.input.clear();
+                    self.clipboard_feedback = Some((
+                        "^C (multi-line input cancelled)".to_string(),
+                        std::time::Instant::now(),
+                    ));
+                } else if !pane.input.is_empty() {
                     // Show ^C and the interrupted input
                     let interrupted = format!("{}^C", pane.input);
                     pane.buffer.push_line(&interrupted);
@@ -1908,6 +5241,7 @@ impl eframe::App for ZaxiomApp {
         // Handle Ctrl+L clear screen (same as clear command - show logo after)
         if clear_screen {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.ai_answer = None;
                 pane.buffer.clear();
                 // Show just the ASCII logo after clear (like the clear command)
                 for line in ASCII_LOGO.lines() {
@@ -1916,13 +5250,6 @@ impl eframe::App for ZaxiomApp {
             }
         }
 
-        // Handle Ctrl+U clear line to beginning
-        if clear_line_to_start {
-            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                pane.input.clear();
-            }
-        }
-
         // Handle Alt+. insert last argument
         if insert_last_arg {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
@@ -1938,6 +5265,7 @@ impl eframe::App for ZaxiomApp {
 
         // Handle hints mode toggle
         if toggle_hints {
+            self.tips.mark_used("hints");
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 if pane.hints_mode.active {
                     pane.hints_mode.deactivate();
@@ -2055,7 +5383,7 @@ impl eframe::App for ZaxiomApp {
         }
 
         // Handle vi mode key input
-        let vi_action = if let Some(ch) = vi_key_char.or(vi_ctrl_key) {
+        let vi_action = if let Some(ch) = vi_key_char {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 if pane.vi_mode.active {
                     // Update max_col based on current line
@@ -2168,19 +5496,29 @@ impl eframe::App for ZaxiomApp {
                         }
                     }
                 }
+                ViAction::YankStyled { start, end, format } => {
+                    self.copy_styled(start, end, format);
+                }
                 ViAction::Paste => {
                     // Paste not applicable in read-only vi mode
                 }
                 _ => {}
             }
         }
+        if let Some(ch) = pager_key {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.pager.handle_key(ch);
+            }
+        }
 
         // Handle fuzzy finder activation
         if fuzzy_history {
+            self.tips.mark_used("history_search");
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 let cwd = pane.state.cwd().to_path_buf();
                 pane.fuzzy_finder.activate(FuzzyMode::History, &cwd);
-                // Populate with history items
+                // Populate with history items, including other panes' latest
+                pane.history.sync();
                 let history_items: Vec<(String, Option<String>)> = pane
                     .history
                     .all()
@@ -2195,12 +5533,42 @@ impl eframe::App for ZaxiomApp {
                 pane.fuzzy_finder.activate(FuzzyMode::Files, &cwd);
             }
         }
-        if fuzzy_branches {
+        if fuzzy_branches {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                let cwd = pane.state.cwd().to_path_buf();
+                pane.fuzzy_finder.activate(FuzzyMode::GitBranches, &cwd);
+            }
+        }
+        if fuzzy_recent_files {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.open_recent_files();
+            }
+        }
+        if fuzzy_directories {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                pane.open_directories();
+            }
+        }
+        if fuzzy_results {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                let cwd = pane.state.cwd().to_path_buf();
-                pane.fuzzy_finder.activate(FuzzyMode::GitBranches, &cwd);
+                let output = pane.state.last_output.clone().unwrap_or_default();
+                pane.open_results(&output);
             }
         }
+        if toggle_git_panel {
+            self.toggle_git_panel();
+        }
+        if let Some(request) = zoom {
+            self.zoom_focused_pane(request);
+        }
+        match git_hunk_step {
+            1 => self.git_panel.next_hunk(),
+            -1 => self.git_panel.prev_hunk(),
+            _ => {}
+        }
+        if let Some(action) = git_action {
+            self.run_git_action(action, false);
+        }
 
         // Handle fuzzy finder input
         if fuzzy_escape {
@@ -2231,10 +5599,40 @@ impl eframe::App for ZaxiomApp {
 
         // Handle fuzzy finder selection
         let mut fuzzy_action: Option<FuzzyAction> = None;
-        if fuzzy_enter || fuzzy_ctrl_enter {
+        if fuzzy_enter || fuzzy_ctrl_enter || fuzzy_shift_enter {
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                let picked_path = pane.fuzzy_finder.selected_path();
                 if let Some(item) = pane.fuzzy_finder.get_selected() {
-                    if fuzzy_ctrl_enter {
+                    // A grep/find hit: Ctrl+Enter edits it, Shift+Enter cds to its directory
+                    let hit = (pane.fuzzy_finder.mode == FuzzyMode::Results)
+                        .then_some(picked_path)
+                        .flatten();
+                    if let (Some(path), true) = (&hit, fuzzy_ctrl_enter) {
+                        fuzzy_action = Some(FuzzyAction::Edit(path.clone(), item.line));
+                    } else if let (Some(path), true) = (&hit, fuzzy_shift_enter) {
+                        let dir = if path.is_dir() {
+                            path.as_path()
+                        } else {
+                            path.parent().unwrap_or(path)
+                        };
+                        let dir = dir.display().to_string();
+                        fuzzy_action =
+                            Some(FuzzyAction::Execute(if dir.contains(char::is_whitespace) {
+                                format!("cd \"{}\"", dir)
+                            } else {
+                                format!("cd {}", dir)
+                            }));
+                    } else if pane.fuzzy_finder.mode == FuzzyMode::GitBranches {
+                        // Enter switches to the branch; Shift+Enter only inserts its name
+                        if item.value.is_empty() {
+                            // The "not a git repository" placeholder
+                        } else if fuzzy_shift_enter {
+                            fuzzy_action = Some(FuzzyAction::Insert(item.value.clone()));
+                        } else {
+                            fuzzy_action = Some(FuzzyAction::Checkout(item.value.clone()));
+                        }
+                    } else if fuzzy_ctrl_enter || pane.fuzzy_finder.mode == FuzzyMode::Directories {
+                        // A directory jump is its `cd`, run straight away
                         fuzzy_action = Some(FuzzyAction::Execute(item.value.clone()));
                     } else {
                         fuzzy_action = Some(FuzzyAction::Insert(item.value.clone()));
@@ -2244,41 +5642,54 @@ impl eframe::App for ZaxiomApp {
             }
         }
 
+        if fuzzy_ctrl_n {
+            if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                let name = pane.fuzzy_finder.query.trim().to_string();
+                if pane.fuzzy_finder.mode == FuzzyMode::GitBranches && !name.is_empty() {
+                    fuzzy_action = Some(FuzzyAction::CreateBranch(name));
+                    pane.fuzzy_finder.deactivate();
+                }
+            }
+        }
+
         // Process fuzzy finder action
         if let Some(action) = fuzzy_action {
             match action {
                 FuzzyAction::Insert(value) => {
                     if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                        pane.input = value;
+                        // A file completes what was typed (`nano ` + file)
+                        if matches!(
+                            pane.fuzzy_finder.mode,
+                            FuzzyMode::RecentFiles | FuzzyMode::Results
+                        ) && !pane.input.trim().is_empty()
+                        {
+                            if !pane.input.ends_with(' ') {
+                                pane.input.push(' ');
+                            }
+                            pane.input.push_str(&value);
+                        } else {
+                            pane.input = value;
+                        }
                         pane.cursor_to_end = true;
                     }
                 }
                 FuzzyAction::Execute(value) => {
                     if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                        pane.input = value.clone();
-                        // Execute immediately
-                        let cwd_display = pane.state.cwd().display().to_string();
-                        let prompt = format!("{} ❯ {}", cwd_display, value);
-                        pane.buffer.push_line(&prompt);
-                        let history_cmds = pane.history.recent_commands(10);
-                        match self.executor.execute_with_history(
-                            &value,
-                            &mut pane.state,
-                            Some(&history_cmds),
-                        ) {
-                            Ok(output) => {
-                                for line in output.lines() {
-                                    pane.buffer.push_line(line);
-                                }
-                            }
-                            Err(e) => {
-                                pane.buffer.push_error(&format!("Error: {}", e));
-                            }
-                        }
-                        pane.history
-                            .add(&value, pane.state.cwd().to_path_buf(), None);
                         pane.input.clear();
-                        pane.scroll_to_bottom = true;
+                    }
+                    self.execute_command(&value);
+                }
+                FuzzyAction::Checkout(name) => self.switch_branch(&name, false),
+                FuzzyAction::CreateBranch(name) => self.switch_branch(&name, true),
+                FuzzyAction::Edit(path, line) => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        self.editor = open_editor(&path.to_string_lossy(), &mut pane.buffer);
+                        if let (Some(editor), Some(line)) = (&mut self.editor, line) {
+                            editor.cursor_line =
+                                line.saturating_sub(1).min(editor.line_count() - 1);
+                            editor.cursor_col = 0;
+                            editor.ensure_cursor_visible();
+                        }
                     }
                 }
                 _ => {}
@@ -2286,7 +5697,7 @@ impl eframe::App for ZaxiomApp {
         }
 
         // Handle command palette actions
-        let mut palette_command: Option<String> = None;
+        let mut palette_command: Option<String> = self.menu_action.take().or(custom_shortcut);
         if palette_escape {
             self.command_palette.close();
         }
@@ -2307,9 +5718,20 @@ impl eframe::App for ZaxiomApp {
         // Enter: copy command to input (don't execute)
         if palette_enter && self.command_palette.is_open {
             if let Some(cmd) = self.command_palette.get_selected_command() {
-                // Just copy to input, don't execute
-                if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                    pane.input = cmd;
+                self.command_palette.record_use(&cmd);
+                let custom = self.command_palette.custom_command(&cmd);
+                if cmd == "Run in New Pane" {
+                    // Acts on the typed command, which copying would replace
+                    palette_command = Some(cmd);
+                } else if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                    // Just copy to input, don't execute
+                    pane.input = match custom {
+                        Some(command) => {
+                            palette::expand_command(command, &pane.input, pane.state.cwd())
+                        }
+                        None => cmd,
+                    };
+                    pane.cursor_to_end = true;
                 }
             }
             self.command_palette.close();
@@ -2318,13 +5740,26 @@ impl eframe::App for ZaxiomApp {
         // Ctrl+Enter: execute command immediately
         if palette_ctrl_enter && self.command_palette.is_open {
             if let Some(cmd) = self.command_palette.get_selected_command() {
+                self.command_palette.record_use(&cmd);
                 palette_command = Some(cmd);
             }
             self.command_palette.close();
         }
 
         // Execute palette command (only on Ctrl+Enter)
-        if let Some(cmd) = palette_command {
+        if let Some(mut cmd) = palette_command {
+            // Custom entries run their command line, using up the typed input
+            if let Some(command) = self.command_palette.custom_command(&cmd) {
+                let command = command.to_string();
+                if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                    let input = if command.contains("{input}") {
+                        std::mem::take(&mut pane.input)
+                    } else {
+                        String::new()
+                    };
+                    cmd = palette::expand_command(&command, &input, pane.state.cwd());
+                }
+            }
             // Handle special actions
             match cmd.as_str() {
                 "New Tab" => self.new_tab(),
@@ -2334,6 +5769,23 @@ impl eframe::App for ZaxiomApp {
                         pane.toggle_search();
                     }
                 }
+                "Copy Block as HTML" => self.copy_block_styled(ExportFormat::Html),
+                "Copy Block with ANSI" => self.copy_block_styled(ExportFormat::Ansi),
+                "Filter Output" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        pane.filter.toggle();
+                    }
+                }
+                "Collapse Previous Blocks" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        pane.buffer.collapse_previous();
+                    }
+                }
+                "Expand All Blocks" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        pane.buffer.expand_all();
+                    }
+                }
                 "Clear" => {
                     if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                         pane.buffer.clear();
@@ -2344,9 +5796,21 @@ impl eframe::App for ZaxiomApp {
                 }
                 "Split Horizontal" => {
                     self.tabs[self.active_tab].split(SplitDirection::Horizontal);
+                    self.tip_event(TipEvent::FeatureUsed("split"));
                 }
                 "Split Vertical" => {
                     self.tabs[self.active_tab].split(SplitDirection::Vertical);
+                    self.tip_event(TipEvent::FeatureUsed("split"));
+                }
+                "Run in New Pane" => {
+                    let input = self.tabs[self.active_tab]
+                        .focused_pane_mut()
+                        .map(|pane| std::mem::take(&mut pane.input))
+                        .unwrap_or_default();
+                    let input = input.trim();
+                    if !input.is_empty() {
+                        self.run_in_new_pane(&format!("{} |> pane", input), input);
+                    }
                 }
                 "Vi Mode" => {
                     if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
@@ -2369,6 +5833,65 @@ impl eframe::App for ZaxiomApp {
                         }
                     }
                 }
+                "Keyboard Shortcuts" => {
+                    self.shortcuts_pinned = true;
+                }
+                "Restore Session" => {
+                    self.session_picker = Some(self.session_manager.restorable_sessions());
+                }
+                "Command Palette" => self.command_palette.toggle(),
+                "Quit" => self.should_exit = true,
+                "New Window" => {
+                    let spawned = std::env::current_exe()
+                        .and_then(|exe| std::process::Command::new(exe).spawn());
+                    if let Err(e) = spawned {
+                        if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                            pane.buffer
+                                .push_error(&format!("Could not open a new window: {}", e));
+                        }
+                    }
+                }
+                "Copy Input" => self.copy_current_input(),
+                "Paste" => self.paste_to_input(),
+                "Paste with Newlines" => self.paste_raw_to_input(),
+                "Zoom In" => self.zoom_focused_pane(ZoomRequest::In),
+                "Zoom Out" => self.zoom_focused_pane(ZoomRequest::Out),
+                "Reset Zoom" => self.zoom_focused_pane(ZoomRequest::Reset),
+                "Ask AI" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        pane.input = "# ".to_string();
+                        pane.cursor_to_end = true;
+                    }
+                }
+                "Recent Files" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        if pane.fuzzy_finder.active {
+                            pane.fuzzy_finder.deactivate();
+                        } else {
+                            pane.open_recent_files();
+                        }
+                    }
+                }
+                "Jump to Directory" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        if pane.fuzzy_finder.active {
+                            pane.fuzzy_finder.deactivate();
+                        } else {
+                            pane.open_directories();
+                        }
+                    }
+                }
+                "Git Panel" => self.toggle_git_panel(),
+                "Pick Search Result" => {
+                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
+                        if pane.fuzzy_finder.active {
+                            pane.fuzzy_finder.deactivate();
+                        } else {
+                            let output = pane.state.last_output.clone().unwrap_or_default();
+                            pane.open_results(&output);
+                        }
+                    }
+                }
                 "History Search" => {
                     if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                         if pane.fuzzy_finder.active {
@@ -2387,35 +5910,8 @@ impl eframe::App for ZaxiomApp {
                         }
                     }
                 }
-                _ => {
-                    // Execute as terminal command
-                    if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                        let cwd = pane.state.cwd().clone();
-                        pane.history.add(&cmd, cwd, None);
-                        pane.history.reset_position();
-                        pane.saved_input.clear();
-                        pane.buffer.start_block(&cmd);
-                        let prompt = pane.state.format_prompt();
-                        pane.buffer.push_line(&format!("{}{}", prompt, cmd));
-                        let history = pane.history.recent_commands(10);
-                        match self.executor.execute_with_history(
-                            &cmd,
-                            &mut pane.state,
-                            Some(&history),
-                        ) {
-                            Ok(output) => {
-                                if !output.is_empty() && !output.starts_with("\x1b[") {
-                                    for line in output.lines() {
-                                        pane.buffer.push_line(line);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                pane.buffer.push_error(&e.to_string());
-                            }
-                        }
-                    }
-                }
+                // Anything else is a command line, run as if typed
+                _ => self.execute_command(&cmd),
             }
         }
 
@@ -2460,10 +5956,17 @@ impl eframe::App for ZaxiomApp {
         if let Some(ref mut editor) = self.editor {
             if editor_exit {
                 // Close editor without saving
+                let closed_path = editor.file_path.clone();
                 self.editor = None;
                 if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                     pane.buffer.push_line("📝 Editor closed.");
                 }
+                if self.fc_file.as_ref() == Some(&closed_path) {
+                    self.run_fc_file();
+                }
+                if self.commit_file.as_ref() == Some(&closed_path) {
+                    self.run_commit_file();
+                }
             } else if editor_save {
                 // Save file
                 match editor.save() {
@@ -2479,6 +5982,12 @@ impl eframe::App for ZaxiomApp {
                         }
                     }
                 }
+            } else if editor_next_conflict {
+                editor.next_conflict();
+            } else if editor_prev_conflict {
+                editor.prev_conflict();
+            } else if let Some(side) = editor_take {
+                editor.take(side);
             } else if editor_enter {
                 // Insert newline
                 let lines: Vec<&str> = editor.content.lines().collect();
@@ -2665,6 +6174,17 @@ impl eframe::App for ZaxiomApp {
                                     for (i, entry) in
                                         self.command_palette.entries.iter().enumerate()
                                     {
+                                        if let Some(section) = self.command_palette.section_at(i) {
+                                            if i > 0 {
+                                                ui.add_space(6.0);
+                                            }
+                                            ui.add(egui::Label::new(
+                                                egui::RichText::new(section)
+                                                    .color(palette_comment)
+                                                    .size(10.0)
+                                                    .strong(),
+                                            ));
+                                        }
                                         let is_selected = i == self.command_palette.selected;
                                         let bg = if is_selected {
                                             palette_accent.linear_multiply(0.3)
@@ -2750,6 +6270,18 @@ impl eframe::App for ZaxiomApp {
                 });
         }
 
+        // Usage dashboard overlay (`stats`)
+        self.render_usage_dashboard(ctx);
+
+        // Saved sessions to restore (`session restore`)
+        self.render_session_picker(ctx);
+
+        // Which-key shortcut overlay (hold Ctrl, or F1)
+        self.render_shortcut_overlay(ctx);
+
+        // Pager over long output (`less`)
+        self.render_pager(ctx);
+
         // Fuzzy finder overlay (bottom-anchored, like fzf)
         if let Some(pane) = self.tabs[self.active_tab].focused_pane() {
             if pane.fuzzy_finder.active {
@@ -2894,6 +6426,23 @@ impl eframe::App for ZaxiomApp {
                                         }
                                     });
 
+                                // Preview of the selected recent file
+                                if let Some(path) = pane.fuzzy_finder.selected_path() {
+                                    ui.add_space(4.0);
+                                    ui.separator();
+                                    egui::ScrollArea::vertical()
+                                        .id_salt("fuzzy_preview")
+                                        .max_height(180.0)
+                                        .show(ui, |ui| {
+                                            path_preview_contents(
+                                                ui,
+                                                &mut self.previews,
+                                                &self.theme,
+                                                &path,
+                                            );
+                                        });
+                                }
+
                                 ui.add_space(8.0);
                                 ui.separator();
                                 ui.add_space(4.0);
@@ -2965,6 +6514,7 @@ impl eframe::App for ZaxiomApp {
 
         // Editor overlay (full screen when editing a file)
         if let Some(ref editor) = self.editor {
+            let code_colors = self.editor_colors.lines(&editor.file_path, &editor.content);
             let editor_bg = self.theme.background;
             let editor_fg = self.theme.foreground;
             let editor_accent = self.theme.accent;
@@ -2972,6 +6522,14 @@ impl eframe::App for ZaxiomApp {
             let line_num_color = self.theme.comment_color;
             let cursor_color = self.theme.accent;
             let modified_indicator = if editor.modified { " [+]" } else { "" };
+            let conflicts = editor.conflicts();
+            // Bring the cursor into view after jumping between conflicts
+            let scroll_to_cursor =
+                editor_next_conflict || editor_prev_conflict || editor_take.is_some();
+            let ours_bg = self.theme.success_color.gamma_multiply(0.15);
+            let base_bg = self.theme.comment_color.gamma_multiply(0.15);
+            let theirs_bg = self.theme.info_color.gamma_multiply(0.15);
+            let marker_bg = self.theme.warning_color.gamma_multiply(0.3);
 
             egui::Area::new(egui::Id::new("editor_overlay"))
                 .anchor(egui::Align2::LEFT_TOP, [0.0, 0.0])
@@ -3002,6 +6560,17 @@ impl eframe::App for ZaxiomApp {
                                         .color(editor_fg)
                                         .size(13.0),
                                     ));
+                                    if !conflicts.is_empty() {
+                                        ui.add(egui::Label::new(
+                                            egui::RichText::new(format!(
+                                                "  ⚔ {} conflict{}",
+                                                conflicts.len(),
+                                                if conflicts.len() == 1 { "" } else { "s" }
+                                            ))
+                                            .color(self.theme.warning_color)
+                                            .size(13.0),
+                                        ));
+                                    }
                                     ui.with_layout(
                                         egui::Layout::right_to_left(egui::Align::Center),
                                         |ui| {
@@ -3027,7 +6596,18 @@ impl eframe::App for ZaxiomApp {
                                     (line_count as f32).log10().floor() as usize + 1;
 
                                 for (i, line) in lines.iter().enumerate() {
-                                    ui.horizontal(|ui| {
+                                    // Conflict regions get a tinted background
+                                    let line_bg = conflicts
+                                        .iter()
+                                        .find_map(|c| c.region(i))
+                                        .map(|region| match region {
+                                            ConflictRegion::Marker => marker_bg,
+                                            ConflictRegion::Ours => ours_bg,
+                                            ConflictRegion::Base => base_bg,
+                                            ConflictRegion::Theirs => theirs_bg,
+                                        })
+                                        .unwrap_or(egui::Color32::TRANSPARENT);
+                                    let row = ui.horizontal(|ui| {
                                         // Line number
                                         ui.add(egui::Label::new(
                                             egui::RichText::new(format!(
@@ -3059,12 +6639,14 @@ impl eframe::App for ZaxiomApp {
                                                 ""
                                             };
 
-                                            ui.add(egui::Label::new(
-                                                egui::RichText::new(before)
-                                                    .color(editor_fg)
-                                                    .size(13.0)
-                                                    .monospace(),
-                                            ));
+                                            let colors = code_colors.get(i);
+                                            ui.add(egui::Label::new(code_text(
+                                                line,
+                                                colors,
+                                                0..before.len(),
+                                                editor_fg,
+                                                line_bg,
+                                            )));
                                             ui.add(egui::Label::new(
                                                 egui::RichText::new(cursor_char)
                                                     .color(editor_bg)
@@ -3072,21 +6654,26 @@ impl eframe::App for ZaxiomApp {
                                                     .size(13.0)
                                                     .monospace(),
                                             ));
-                                            ui.add(egui::Label::new(
-                                                egui::RichText::new(after)
-                                                    .color(editor_fg)
-                                                    .size(13.0)
-                                                    .monospace(),
-                                            ));
+                                            ui.add(egui::Label::new(code_text(
+                                                line,
+                                                colors,
+                                                line.len() - after.len()..line.len(),
+                                                editor_fg,
+                                                line_bg,
+                                            )));
                                         } else {
-                                            ui.add(egui::Label::new(
-                                                egui::RichText::new(*line)
-                                                    .color(editor_fg)
-                                                    .size(13.0)
-                                                    .monospace(),
-                                            ));
+                                            ui.add(egui::Label::new(code_text(
+                                                line,
+                                                code_colors.get(i),
+                                                0..line.len(),
+                                                editor_fg,
+                                                line_bg,
+                                            )));
                                         }
                                     });
+                                    if scroll_to_cursor && i == editor.cursor_line {
+                                        row.response.scroll_to_me(Some(egui::Align::Center));
+                                    }
                                 }
 
                                 // Show cursor on empty file or past last line
@@ -3147,16 +6734,26 @@ impl eframe::App for ZaxiomApp {
                                     ui.with_layout(
                                         egui::Layout::right_to_left(egui::Align::Center),
                                         |ui| {
-                                            for (keys, desc) in [
-                                                ("Esc/^X", "exit"),
-                                                ("^S", "save"),
-                                                ("PgUp/Dn", "scroll"),
-                                                ("Home/End", "line"),
-                                                ("^Home/End", "file"),
-                                            ]
-                                            .iter()
-                                            .rev()
+                                            let shortcuts: &[(&str, &str)] = if conflicts.is_empty()
                                             {
+                                                &[
+                                                    ("Esc/^X", "exit"),
+                                                    ("^S", "save"),
+                                                    ("PgUp/Dn", "scroll"),
+                                                    ("Home/End", "line"),
+                                                    ("^Home/End", "file"),
+                                                ]
+                                            } else {
+                                                &[
+                                                    ("Esc/^X", "exit"),
+                                                    ("^S", "save"),
+                                                    ("^N/^P", "next/prev conflict"),
+                                                    ("^O", "ours"),
+                                                    ("^T", "theirs"),
+                                                    ("^B", "both"),
+                                                ]
+                                            };
+                                            for (keys, desc) in shortcuts.iter().rev() {
                                                 ui.add(egui::Label::new(
                                                     egui::RichText::new(*desc)
                                                         .color(editor_comment)
@@ -3188,6 +6785,29 @@ impl eframe::App for ZaxiomApp {
                 });
         }
 
+        // Restricted mode watermark - faint, and painted without taking input
+        if self.executor.is_restricted() {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("restricted_watermark"),
+            ));
+            let screen = ctx.screen_rect();
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "RESTRICTED",
+                egui::FontId::monospace(72.0),
+                self.theme.warning_color.gamma_multiply(0.08),
+            );
+            painter.text(
+                screen.right_bottom() + egui::vec2(-12.0, -36.0),
+                egui::Align2::RIGHT_BOTTOM,
+                "🔒 restricted mode",
+                egui::FontId::monospace(12.0),
+                self.theme.warning_color.gamma_multiply(0.7),
+            );
+        }
+
         // Clipboard feedback toast (bottom-center)
         if let Some((message, _)) = &self.clipboard_feedback {
             let feedback_accent = self.theme.accent;
@@ -3211,6 +6831,123 @@ impl eframe::App for ZaxiomApp {
                 });
         }
 
+        // End-of-day digest toast (bottom-right)
+        if let Some((message, _)) = &self.digest_toast {
+            let accent = self.theme.accent;
+            let mut open_card = false;
+            let mut dismiss = false;
+            egui::Area::new(egui::Id::new("digest_toast"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -60.0))
+                .order(egui::Order::Foreground)
+                .show(ctx, |ui| {
+                    egui::Frame::default()
+                        .fill(self.theme.background_secondary)
+                        .stroke(egui::Stroke::new(1.0, accent))
+                        .corner_radius(egui::CornerRadius::same(8))
+                        .inner_margin(egui::Margin::symmetric(16, 10))
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new("📋 Daily digest")
+                                    .color(accent)
+                                    .strong()
+                                    .size(14.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(message)
+                                    .color(self.theme.foreground)
+                                    .size(13.0),
+                            );
+                            ui.horizontal(|ui| {
+                                open_card = ui.button("Report card").clicked();
+                                dismiss = ui.button("Dismiss").clicked();
+                            });
+                        });
+                });
+            if open_card {
+                self.menu_action = Some("digest".to_string());
+            }
+            if open_card || dismiss {
+                self.digest_toast = None;
+            }
+        }
+
+        // Offer for a folder dropped on the window (bottom-right)
+        if let Some(folder) = self.dropped_folder.clone() {
+            let accent = self.theme.accent;
+            let (mut cd, mut insert, mut dismiss) = (false, false, false);
+            egui::Area::new(egui::Id::new("dropped_folder"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -60.0))
+                .order(egui::Order::Foreground)
+                .show(ctx, |ui| {
+                    egui::Frame::default()
+                        .fill(self.theme.background_secondary)
+                        .stroke(egui::Stroke::new(1.0, accent))
+                        .corner_radius(egui::CornerRadius::same(8))
+                        .inner_margin(egui::Margin::symmetric(16, 10))
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new("📂 Dropped folder")
+                                    .color(accent)
+                                    .strong()
+                                    .size(14.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(folder.display().to_string())
+                                    .color(self.theme.foreground)
+                                    .monospace(),
+                            );
+                            ui.horizontal(|ui| {
+                                cd = ui.button("cd into it").clicked();
+                                insert = ui.button("Insert path").clicked();
+                                dismiss = ui.button("Dismiss").clicked();
+                            });
+                        });
+                });
+            if cd {
+                self.execute_command(&format!("cd {}", input::quote_path(&folder)));
+            }
+            if insert {
+                dropped_text = Some(input::quote_path(&folder));
+            }
+            if cd || insert || dismiss {
+                self.dropped_folder = None;
+            }
+        }
+
+        // Show where dragged files will go
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("file_drop_target"),
+            ));
+            let rect = ctx.screen_rect();
+            painter.rect_filled(
+                rect,
+                egui::CornerRadius::ZERO,
+                self.theme.background.gamma_multiply(0.6),
+            );
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop to insert the path (a folder can be cd'd into)",
+                egui::FontId::proportional(18.0),
+                self.theme.accent,
+            );
+        }
+
+        // Optional menu bar above the tabs
+        if self.config.terminal.menu == MenuStyle::Bar {
+            egui::TopBottomPanel::top("menu_bar")
+                .frame(
+                    egui::Frame::default()
+                        .fill(self.theme.background_secondary)
+                        .inner_margin(egui::Margin::symmetric(4, 0)),
+                )
+                .show(ctx, |ui| {
+                    egui::menu::bar(ui, |ui| self.render_menus(ui));
+                });
+        }
+
         // Top panel for tab bar
         egui::TopBottomPanel::top("tab_bar")
             .frame(
@@ -3224,7 +6961,7 @@ impl eframe::App for ZaxiomApp {
 
         // Bottom status bar with git info
         let status_bar_height = 22.0;
-        let (git_branch, cwd_display, block_count, pane_count, vi_status, hints_count) = {
+        let (git, cwd_display, block_count, pane_count, vi_status, hints_count) = {
             let tab = &self.tabs[self.active_tab];
             if let Some(pane) = tab.focused_pane() {
                 let vi_status = if pane.vi_mode.active {
@@ -3238,7 +6975,7 @@ impl eframe::App for ZaxiomApp {
                     0
                 };
                 (
-                    crate::git::prompt::get_git_branch(pane.state.cwd()),
+                    self.git_status.status(ctx, pane.state.cwd()),
                     pane.state.cwd().display().to_string(),
                     pane.buffer.blocks().len(),
                     tab.pane_count(),
@@ -3253,6 +6990,7 @@ impl eframe::App for ZaxiomApp {
         let status_fg = self.theme.foreground;
         let status_comment = self.theme.comment_color;
         let status_bg = self.theme.background_tertiary;
+        let status_dirty = self.theme.warning_color;
 
         egui::TopBottomPanel::bottom("status_bar")
             .frame(
@@ -3264,13 +7002,38 @@ impl eframe::App for ZaxiomApp {
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     // Git branch (if in a git repo)
-                    if let Some(branch) = &git_branch {
+                    if let Some(GitSnapshot {
+                        branch,
+                        ahead,
+                        behind,
+                        dirty,
+                    }) = &git
+                    {
                         ui.add(egui::Label::new(
                             egui::RichText::new(" ").color(status_accent).size(12.0),
                         ));
                         ui.add(egui::Label::new(
                             egui::RichText::new(branch).color(status_fg).size(12.0),
                         ));
+                        if *dirty {
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(" ●").color(status_dirty).size(12.0),
+                            ))
+                            .on_hover_text("Uncommitted changes");
+                        }
+                        if *ahead > 0 || *behind > 0 {
+                            let mut sync = String::new();
+                            if *ahead > 0 {
+                                sync.push_str(&format!(" ↑{}", ahead));
+                            }
+                            if *behind > 0 {
+                                sync.push_str(&format!(" ↓{}", behind));
+                            }
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(sync).color(status_comment).size(12.0),
+                            ))
+                            .on_hover_text(format!("{} to push, {} to pull", ahead, behind));
+                        }
                         ui.add(egui::Label::new(
                             egui::RichText::new(" │ ").color(status_comment).size(12.0),
                         ));
@@ -3401,6 +7164,24 @@ impl eframe::App for ZaxiomApp {
                                 ));
                             }
 
+                            // New output moves the view unless scrolling is paused
+                            let (label, hint) = if pane.search_pause_scroll {
+                                ("▶ Follow", "Scroll with new output again")
+                            } else {
+                                ("⏸ Pause", "Keep the view still while output streams in")
+                            };
+                            let pause = ui
+                                .add(
+                                    egui::Button::new(
+                                        egui::RichText::new(label).color(theme_accent).size(11.0),
+                                    )
+                                    .frame(false),
+                                )
+                                .on_hover_text(hint);
+                            if pause.clicked() {
+                                pane.search_pause_scroll = !pane.search_pause_scroll;
+                            }
+
                             // Navigation hint
                             ui.add(egui::Label::new(
                                 egui::RichText::new(" Enter: next, Shift+Enter: prev, Esc: close")
@@ -3419,6 +7200,113 @@ impl eframe::App for ZaxiomApp {
             }
         }
 
+        // Filter bar (Ctrl+Shift+L) - hides output lines without touching the buffer
+        let mut filter_focused = false;
+        let filter_active = self.tabs[self.active_tab]
+            .focused_pane()
+            .is_some_and(|pane| pane.filter.active);
+        if filter_active {
+            let pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+            let theme_accent = self.theme.accent;
+            let theme_fg = self.theme.foreground;
+            let theme_err = self.theme.error_color;
+            let theme_comment = self.theme.comment_color;
+
+            egui::TopBottomPanel::bottom("filter_bar")
+                .frame(
+                    egui::Frame::default()
+                        .fill(self.theme.background_secondary)
+                        .inner_margin(egui::Margin::symmetric(8, 4)),
+                )
+                .show(ctx, |ui| {
+                    if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Label::new(
+                                egui::RichText::new("⏷ ").color(theme_accent).size(14.0),
+                            ));
+
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut pane.filter.pattern)
+                                    .font(egui::TextStyle::Monospace)
+                                    .desired_width(200.0)
+                                    .hint_text("text or /regex/"),
+                            );
+                            if toggle_filter {
+                                response.request_focus();
+                            }
+                            if response.has_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Escape))
+                            {
+                                pane.filter.toggle();
+                            } else {
+                                filter_focused = response.has_focus();
+                            }
+
+                            ui.checkbox(&mut pane.filter.errors_only, "Errors only");
+
+                            let block_label = |id: usize| {
+                                let command = pane.buffer.get_block_command(id).unwrap_or("");
+                                let command: String = command.chars().take(40).collect();
+                                format!("#{} {}", id + 1, command)
+                            };
+                            let selected = match pane.filter.block {
+                                Some(id) => block_label(id),
+                                None => "All blocks".to_string(),
+                            };
+                            let blocks: Vec<(usize, String)> = pane
+                                .buffer
+                                .blocks()
+                                .iter()
+                                .rev()
+                                .map(|block| (block.id, block_label(block.id)))
+                                .collect();
+                            egui::ComboBox::from_id_salt("filter_block")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut pane.filter.block, None, "All blocks");
+                                    for (id, label) in blocks {
+                                        ui.selectable_value(
+                                            &mut pane.filter.block,
+                                            Some(id),
+                                            label,
+                                        );
+                                    }
+                                });
+
+                            if pane.filter.pattern_error() {
+                                ui.add(egui::Label::new(
+                                    egui::RichText::new(" Invalid regex ")
+                                        .color(theme_err)
+                                        .size(12.0),
+                                ));
+                            } else if pane.filter.is_filtering() {
+                                let shown = pane.filter.count(&pane.buffer);
+                                let color = if shown == 0 { theme_err } else { theme_fg };
+                                ui.add(egui::Label::new(
+                                    egui::RichText::new(format!(
+                                        " {} of {} lines ",
+                                        shown,
+                                        pane.buffer.len()
+                                    ))
+                                    .color(color)
+                                    .size(12.0),
+                                ));
+                            }
+
+                            if pane.filter.is_filtering() && ui.small_button("Clear").clicked() {
+                                pane.filter.clear();
+                            }
+
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(" Esc / Ctrl+Shift+L: close")
+                                    .color(theme_comment)
+                                    .size(11.0),
+                            ));
+                        });
+                    }
+                });
+        }
+
         // Track if we need to execute a command after rendering
         let mut command_to_execute: Option<String> = None;
         let mut history_up = false;
@@ -3452,6 +7340,13 @@ impl eframe::App for ZaxiomApp {
 
         // Get focused pane ID and calculate split layout
         let focused_pane_id = self.tabs[self.active_tab].splits.focused_pane_id();
+        // Lines hidden by the filter bar (focused pane, single-pane view)
+        let filter_mask = self.tabs[self.active_tab]
+            .panes
+            .get_mut(&focused_pane_id)
+            .and_then(|p| p.filter.visible(&p.buffer));
+
+        self.render_git_panel(ctx);
 
         egui::CentralPanel::default()
             .frame(egui::Frame::default().fill(bg_color))
@@ -3479,8 +7374,20 @@ impl eframe::App for ZaxiomApp {
                     let mut pane_to_focus: Option<usize> = None;
                     let mut pane_to_close: Option<usize> = None;
 
+                    // Dropped files go to the pane under the pointer, and a
+                    // folder's inserted path to the focused one
+                    let pointer = ui.input(|i| i.pointer.hover_pos()).filter(|_| !dropped.is_empty());
+                    let drop_target = (dropped_text.is_some() || !dropped.is_empty()).then(|| {
+                        pointer
+                            .and_then(|pos| pane_layouts.iter().find(|(_, rect)| rect.contains(pos)))
+                            .map_or(focused_pane_id, |(pane_id, _)| *pane_id)
+                    });
+
                     for (pane_id, rect) in pane_layouts.iter().copied() {
                         let is_focused = pane_id == focused_pane_id;
+                        if drop_target == Some(pane_id) && !is_focused {
+                            pane_to_focus = Some(pane_id);
+                        }
 
                         // Draw border around each pane
                         let stroke = if is_focused {
@@ -3495,14 +7402,36 @@ impl eframe::App for ZaxiomApp {
                             egui::StrokeKind::Inside,
                         );
 
+                        // Header label: number (for `# --pane N`) plus the user-given name
+                        let pane_label = match self.tabs[self.active_tab]
+                            .panes
+                            .get(&pane_id)
+                            .and_then(|p| p.state.pane_name.as_deref())
+                        {
+                            Some(name) => format!("{} · {}", pane_id + 1, name),
+                            None => format!("Pane {}", pane_id + 1),
+                        };
+
+                        // Each pane's text has its own size (Ctrl+= / Ctrl+-)
+                        let font_size = self.tabs[self.active_tab]
+                            .panes
+                            .get(&pane_id)
+                            .map_or(self.theme.font_size, |p| p.state.font_size);
+
                         // Use push_id to create unique ID scope for each pane
                         let inner_rect = rect.shrink(4.0);
-                        let header_height = 20.0;
-                        let input_height = 25.0;
+                        let header_height = 18.0;
+                        let input_height = (font_size + 9.0).max(25.0);
                         let header_rect = egui::Rect::from_min_max(
                             inner_rect.min,
                             egui::pos2(inner_rect.max.x, inner_rect.min.y + header_height),
                         );
+                        // Thin divider under the header, tinted like the border
+                        ui.painter().hline(
+                            header_rect.x_range(),
+                            header_rect.bottom(),
+                            egui::Stroke::new(1.0, stroke.color.gamma_multiply(0.5)),
+                        );
                         let scroll_rect = egui::Rect::from_min_max(
                             egui::pos2(inner_rect.min.x, inner_rect.min.y + header_height),
                             egui::pos2(inner_rect.max.x, inner_rect.max.y - input_height),
@@ -3512,14 +7441,28 @@ impl eframe::App for ZaxiomApp {
                             inner_rect.max,
                         );
 
+                        // Keep the pane's PTY sized to its own area
+                        let font = egui::FontId::monospace(font_size);
+                        let char_width = ui.fonts(|fonts| fonts.glyph_width(&font, 'M'));
+                        let row_height = font.size * self.theme.line_height;
+                        if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) {
+                            if pane.resize_pty(
+                                scroll_rect.width() - 10.0 - ui.spacing().scroll.bar_width,
+                                scroll_rect.height(),
+                                char_width,
+                                row_height,
+                            ) {
+                                ui.ctx().request_repaint_after(PTY_RESIZE_DEBOUNCE);
+                            }
+                        }
+
                         ui.push_id(pane_id, |ui| {
                             // Render header with close button
                             ui.push_id("header", |ui| {
                                 let mut header_ui = ui.new_child(egui::UiBuilder::new().max_rect(header_rect));
                                 header_ui.horizontal(|ui| {
                                     // Pane label
-                                    let label_color = if is_focused { foreground } else { border_color };
-                                    ui.label(egui::RichText::new(format!("Pane {}", pane_id + 1)).color(label_color).small());
+                                    ui.label(egui::RichText::new(&pane_label).color(stroke.color).small());
 
                                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                         // Close button (only show if more than 1 pane)
@@ -3555,46 +7498,130 @@ impl eframe::App for ZaxiomApp {
                                 });
                             });
                             // Get pane data for reading
-                            let buffer_lines: Vec<_> = self.tabs[self.active_tab]
+                            let filter_mask = self.tabs[self.active_tab]
                                 .panes
-                                .get(&pane_id)
-                                .map(|p| p.buffer.output_lines().collect())
+                                .get_mut(&pane_id)
+                                .and_then(|p| p.filter.visible(&p.buffer));
+                            let mut output_view = self.tabs[self.active_tab]
+                                .panes
+                                .get_mut(&pane_id)
+                                .map(|p| std::mem::take(&mut p.output_view))
                                 .unwrap_or_default();
+                            let mut block_to_toggle = None;
                             let prompt = self.tabs[self.active_tab]
                                 .panes
                                 .get(&pane_id)
-                                .map(|p| p.state.format_prompt())
+                                .map(|p| if p.continuation.is_some() { "> ".to_string() } else { p.state.format_prompt() })
                                 .unwrap_or_default();
 
+                            // Output and input at the pane's text size (the header keeps the app's)
+                            set_font_sizes(ui.style_mut(), font_size, self.theme.line_height);
+
                             // Render scroll area with buffer content
                             ui.push_id("scroll", |ui| {
                                 let mut scroll_ui = ui.new_child(egui::UiBuilder::new().max_rect(scroll_rect).sense(egui::Sense::click()));
                                 let scroll_response = scroll_ui.response();
-
-                                egui::ScrollArea::vertical()
-                                    .max_height(scroll_rect.height())
-                                    .stick_to_bottom(true)
-                                    .auto_shrink([false; 2])
-                                    .show(&mut scroll_ui, |ui| {
-                                        ui.set_max_width(scroll_rect.width() - 10.0);
-                                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Wrap);
-
-                                        for line in buffer_lines {
+                                let mut hovered_path = None;
+                                let running_pty = self.tabs[self.active_tab]
+                                    .panes
+                                    .get(&pane_id)
+                                    .filter(|p| p.input_mode == InputMode::Raw && p.pty_session.is_some());
+
+                                if let Some(pane) = running_pty {
+                                    // A program running in the pane: its cell grid
+                                    let grid = &pane.pty_grid;
+                                    let rows: Vec<&GridRow> = grid.all_rows().collect();
+                                    let (cursor_row, cursor_col) = grid.cursor_position();
+                                    let cursor_col = cursor_col.min(grid.dimensions().1.saturating_sub(1));
+                                    let cursor_row = grid
+                                        .cursor_visible()
+                                        .then_some(grid.scrollback_len() + cursor_row);
+                                    let colors = GridColors {
+                                        foreground,
+                                        background: self.theme.background,
+                                        cursor: self.theme.cursor,
+                                    };
+                                    scroll_ui.spacing_mut().item_spacing.y = 0.0;
+                                    egui::ScrollArea::vertical()
+                                        .max_height(scroll_rect.height())
+                                        .stick_to_bottom(pane.follows_output())
+                                        .auto_shrink([false; 2])
+                                        .show_rows(&mut scroll_ui, row_height, rows.len(), |ui, range| {
+                                            for row_idx in range {
+                                                let cursor = (cursor_row == Some(row_idx)).then_some(cursor_col);
+                                                let job = render::grid_row_job(rows[row_idx], &font, row_height, &colors, cursor);
+                                                ui.add(egui::Label::new(job).wrap_mode(egui::TextWrapMode::Extend));
+                                            }
+                                        });
+                                } else if let Some(pane) = self.tabs[self.active_tab].panes.get(&pane_id) {
+                                    let scroll_area = egui::ScrollArea::vertical()
+                                        .max_height(scroll_rect.height())
+                                        .stick_to_bottom(true)
+                                        .auto_shrink([false; 2]);
+                                    output_view.show(
+                                        &mut scroll_ui,
+                                        scroll_area,
+                                        &pane.buffer,
+                                        filter_mask.as_deref(),
+                                        10.0,
+                                        |ui, row, view| {
+                                            let index = match row {
+                                                Row::Line(index) => index,
+                                                Row::Header(_) => return,
+                                                Row::Collapsed(index) => {
+                                                    let block = pane.buffer.get_line(index).and_then(|line| line.block_id).and_then(|id| pane.buffer.get_block(id));
+                                                    if let Some(block) = block {
+                                                        if block_summary(ui, block, &self.theme).clicked() {
+                                                            block_to_toggle = Some(block.id);
+                                                        }
+                                                    }
+                                                    return;
+                                                }
+                                                Row::End => {
+                                                    if let Some(status) = pane.job_status() {
+                                                        ui.label(egui::RichText::new(status).color(self.theme.accent).monospace());
+                                                    }
+                                                    return;
+                                                }
+                                            };
+                                            let Some(line) = pane.buffer.get_line(index) else {
+                                                return;
+                                            };
                                             let color = match line.line_type {
                                                 LineType::Normal => foreground,
                                                 LineType::Error => error_color,
                                                 LineType::Command => command_color,
                                                 LineType::Success => success_color,
                                             };
-                                            ui.label(egui::RichText::new(&line.text).color(color).monospace());
-                                        }
-                                    });
+                                            let gutter = gutter_text(&pane.buffer, index, &self.config.terminal);
+                                            with_gutter(ui, gutter.as_deref(), self.theme.comment_color, |ui| {
+                                                let galley = view.galley(ui, index, &line.text, color);
+                                                if let Some(path) = output_label(ui, egui::Label::new(galley)) {
+                                                    hovered_path = Some(path);
+                                                }
+                                            });
+                                        },
+                                    );
+                                }
+
+                                if let Some(word) = hovered_path {
+                                    if let Some(pane) = self.tabs[self.active_tab].panes.get(&pane_id) {
+                                        let path = pane.state.resolve_path(&word);
+                                        show_path_preview(ui, &mut self.previews, &self.theme, &path);
+                                    }
+                                }
 
                                 // Click on scroll area to focus this pane
                                 if scroll_response.clicked() && !is_focused {
                                     pane_to_focus = Some(pane_id);
                                 }
                             });
+                            if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) {
+                                pane.output_view = output_view;
+                                if let Some(block_id) = block_to_toggle {
+                                    pane.buffer.toggle_collapsed(block_id);
+                                }
+                            }
 
                             // Render input area at fixed position
                             ui.push_id("input", |ui| {
@@ -3603,7 +7630,14 @@ impl eframe::App for ZaxiomApp {
                                     let prompt_color = if is_focused { path_color } else { border_color };
                                     ui.label(egui::RichText::new(&prompt).color(prompt_color).monospace());
 
-                                    if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) {
+                                    let raw = self.tabs[self.active_tab]
+                                        .panes
+                                        .get(&pane_id)
+                                        .is_some_and(|p| p.input_mode == InputMode::Raw);
+                                    if raw {
+                                        // Keys go to the running program (see `focused_in_pty_raw`)
+                                        ui.label(egui::RichText::new("📡 PTY - Esc to exit").color(self.theme.comment_color).monospace());
+                                    } else if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&pane_id) {
                                         let text_color = if is_focused { foreground } else { border_color };
 
                                         let response = ui.add(
@@ -3614,20 +7648,29 @@ impl eframe::App for ZaxiomApp {
                                                 .frame(false)
                                         );
 
+                                        if drop_target == Some(pane_id) {
+                                            if let Some(text) = dropped_text.take() {
+                                                let cursor = input_cursor(ui.ctx(), response.id, &pane.input);
+                                                let cursor = input::insert_word(&mut pane.input, cursor, &text);
+                                                set_input_cursor(ui.ctx(), response.id, input::byte_to_char(&pane.input, cursor));
+                                                ui.ctx().request_repaint();
+                                            }
+                                        }
+
                                         // Click/focus on input switches pane focus
                                         if (response.clicked() || response.gained_focus()) && !is_focused {
                                             pane_to_focus = Some(pane_id);
                                         }
 
                                         // Auto-focus the focused pane's input
-                                        if is_focused && !response.has_focus() {
+                                        if is_focused && !response.has_focus() && !filter_focused {
                                             response.request_focus();
                                         }
 
                                         // Handle Enter key directly
                                         if response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                                             let cmd = std::mem::take(&mut pane.input);
-                                            if !cmd.is_empty() {
+                                            if !cmd.is_empty() || pane.continuation.is_some() {
                                                 split_command_to_execute = Some((pane_id, cmd));
                                             }
                                         }
@@ -3637,6 +7680,29 @@ impl eframe::App for ZaxiomApp {
                         });
                     }
 
+                    // Drag the dividers between panes to resize them
+                    for divider in self.tabs[self.active_tab].splits.dividers(content_rect) {
+                        let response = ui.interact(
+                            divider.line.expand(3.0),
+                            ui.id().with(("pane_divider", divider.index)),
+                            egui::Sense::drag(),
+                        );
+                        if response.hovered() || response.dragged() {
+                            ui.ctx().set_cursor_icon(match divider.direction {
+                                SplitDirection::Horizontal => egui::CursorIcon::ResizeVertical,
+                                SplitDirection::Vertical => egui::CursorIcon::ResizeHorizontal,
+                            });
+                            ui.painter().rect_filled(divider.line.expand(1.0), egui::CornerRadius::ZERO, self.theme.accent);
+                        }
+                        if response.dragged() {
+                            if let Some(pos) = response.interact_pointer_pos() {
+                                self.tabs[self.active_tab]
+                                    .splits
+                                    .set_ratio(divider.index, divider.ratio_at(pos));
+                            }
+                        }
+                    }
+
                     // Switch focus if a pane was clicked
                     if let Some(new_focus) = pane_to_focus {
                         self.tabs[self.active_tab].splits.focus_pane(new_focus);
@@ -3647,58 +7713,43 @@ impl eframe::App for ZaxiomApp {
                         self.tabs[self.active_tab].close_pane(close_id);
                     }
 
-                    // Execute command if entered in split mode
+                    // Execute command if entered in split mode: the same path as
+                    // a single pane, run in the pane it was typed in
                     if let Some((exec_pane_id, cmd)) = split_command_to_execute {
                         self.tabs[self.active_tab].splits.focus_pane(exec_pane_id);
-
-                        // Handle special commands
-                        match cmd.as_str() {
-                            "clear" => {
-                                if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&exec_pane_id) {
-                                    pane.buffer.clear();
-                                    for line in ASCII_LOGO.lines() {
-                                        pane.buffer.push_line(line);
-                                    }
-                                }
-                            }
-                            _ => {
-                                // Execute as regular command
-                                if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&exec_pane_id) {
-                                    let cwd = pane.state.cwd().clone();
-                                    pane.history.add(&cmd, cwd, None);
-                                    pane.history.reset_position();
-                                    pane.buffer.start_block(&cmd);
-                                    let prompt = pane.state.format_prompt();
-                                    pane.buffer.push_line(&format!("{}{}", prompt, cmd));
-                                    let history = pane.history.recent_commands(10);
-                                    match self.executor.execute_with_history(&cmd, &mut pane.state, Some(&history)) {
-                                        Ok(output) => {
-                                            if !output.is_empty() {
-                                                for line in output.lines() {
-                                                    pane.buffer.push_line(line);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            pane.buffer.push_error(&e.to_string());
-                                        }
-                                    }
-                                }
-                            }
+                        if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&exec_pane_id) {
+                            pane.suggestions.clear();
+                            pane.show_suggestions = false;
                         }
+                        self.execute_command(&cmd);
                     }
                     return; // Skip single-pane rendering
                 }
 
-                // Terminal output area (scrollable) - single pane mode
-                let available_height = ui.available_height() - 30.0;
+                // Terminal output area (scrollable) - single pane mode, at
+                // the pane's own text size
+                let font_size = self.tabs[self.active_tab]
+                    .panes
+                    .get(&focused_pane_id)
+                    .map_or(self.theme.font_size, |p| p.state.font_size);
+                set_font_sizes(ui.style_mut(), font_size, self.theme.line_height);
+                let available_height = ui.available_height() - (font_size + 14.0).max(30.0);
                 let available_width = ui.available_width();
 
-                // Resize PTY if needed
-                let font_size = self.theme.font_size;
-                let line_height = self.theme.line_height;
+                // Resize PTY if needed (to the cells that fit beside the mascot margin)
+                let grid_font = egui::TextStyle::Monospace.resolve(ui.style());
+                let grid_row_height = grid_font.size * self.theme.line_height;
+                let grid_char_width = ui.fonts(|fonts| fonts.glyph_width(&grid_font, 'M'));
+                let grid_margin = if show_mascot { 90.0 } else { 10.0 };
                 if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
-                    pane.resize_pty(available_width, available_height, font_size, line_height);
+                    if pane.resize_pty(
+                        available_width - grid_margin - ui.spacing().scroll.bar_width,
+                        available_height,
+                        grid_char_width,
+                        grid_row_height,
+                    ) {
+                        ui.ctx().request_repaint_after(PTY_RESIZE_DEBOUNCE);
+                    }
                 }
 
                 // Get theme colors for output
@@ -3708,8 +7759,20 @@ impl eframe::App for ZaxiomApp {
 
                 let link_color = self.theme.link_color;
 
-                // Track block copy request
-                let mut block_to_copy: Option<(usize, String)> = None;
+                // Track block button and menu clicks
+                let mut block_action: Option<BlockAction> = None;
+                let mut minimap_click: Option<f32> = None;
+                let mut minimap_max_offset: Option<f32> = None;
+                let (jump_offset, mut output_view) = self.tabs[self.active_tab]
+                    .panes
+                    .get_mut(&focused_pane_id)
+                    .map(|p| {
+                        (
+                            p.minimap_jump.take().map(|f| f * p.max_scroll_offset),
+                            std::mem::take(&mut p.output_view),
+                        )
+                    })
+                    .unwrap_or_default();
 
                 // Check if we're in PTY Raw mode - render terminal grid instead of normal buffer
                 let is_pty_mode = self.tabs[self.active_tab]
@@ -3719,200 +7782,169 @@ impl eframe::App for ZaxiomApp {
                     .unwrap_or(false);
 
                 if is_pty_mode {
-                    // Render PTY terminal grid
+                    // Render the PTY cell grid (only the rows in view are laid out)
                     if let Some(pane) = self.tabs[self.active_tab].panes.get(&focused_pane_id) {
-                        let grid_lines = pane.pty_grid.get_lines();
-                        let (cursor_row, cursor_col) = pane.pty_grid.cursor_position();
+                        let grid = &pane.pty_grid;
+                        let rows: Vec<&GridRow> = grid.all_rows().collect();
+                        let (cursor_row, cursor_col) = grid.cursor_position();
+                        // A cursor parked past the last column (pending wrap) is drawn on it
+                        let cursor_col = cursor_col.min(grid.dimensions().1.saturating_sub(1));
+                        let cursor_row = grid
+                            .cursor_visible()
+                            .then_some(grid.scrollback_len() + cursor_row);
+                        let colors = GridColors {
+                            foreground,
+                            background: bg_color,
+                            cursor: self.theme.cursor,
+                        };
 
-                        egui::ScrollArea::vertical()
+                        let mut scroll_area = egui::ScrollArea::vertical()
                             .max_height(available_height)
-                            .stick_to_bottom(true)
+                            .stick_to_bottom(pane.follows_output())
                             .auto_shrink([false; 2])
-                            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible)
-                            .show(ui, |ui| {
-                                let margin = if show_mascot { 90.0 } else { 10.0 };
-                                ui.set_max_width(ui.available_width() - margin);
-
-                                // Render each line from the terminal grid
-                                for (row_idx, line) in grid_lines.iter().enumerate() {
-                                    // Check if line has ANSI codes
-                                    let has_ansi = ansi::has_ansi(line);
-
-                                    if has_ansi {
-                                        // Parse and render ANSI-styled segments
-                                        ui.horizontal(|ui| {
-                                            let segments = ansi::parse_ansi(line);
-                                            let mut col = 0;
-                                            for segment in segments {
-                                                let color = segment.fg_color
-                                                    .map(|(r, g, b)| egui::Color32::from_rgb(r, g, b))
-                                                    .unwrap_or(foreground);
-
-                                                let mut rich_text = egui::RichText::new(&segment.text)
-                                                    .monospace()
-                                                    .color(color);
-
-                                                if segment.bold {
-                                                    rich_text = rich_text.strong();
-                                                }
-                                                if segment.italic {
-                                                    rich_text = rich_text.italics();
-                                                }
-                                                if segment.underline {
-                                                    rich_text = rich_text.underline();
-                                                }
-
-                                                ui.add(egui::Label::new(rich_text));
-                                                col += segment.text.chars().count();
-                                            }
-
-                                            // Show cursor on current line
-                                            if row_idx == cursor_row && col <= cursor_col {
-                                                // Pad to cursor position if needed
-                                                let padding = cursor_col.saturating_sub(col);
-                                                if padding > 0 {
-                                                    ui.add(egui::Label::new(
-                                                        egui::RichText::new(" ".repeat(padding))
-                                                            .monospace()
-                                                    ));
-                                                }
-                                                // Show blinking cursor
-                                                ui.add(egui::Label::new(
-                                                    egui::RichText::new("▏")
-                                                        .monospace()
-                                                        .color(self.theme.accent)
-                                                ));
-                                            }
-                                        });
-                                    } else if !line.is_empty() {
-                                        // Simple text without ANSI
-                                        ui.horizontal(|ui| {
-                                            ui.add(egui::Label::new(
-                                                egui::RichText::new(line)
-                                                    .monospace()
-                                                    .color(foreground)
-                                            ));
+                            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible);
+                        if pane.search_jump {
+                            if let Some(line) = pane.current_match_line() {
+                                let offset = line as f32 * grid_row_height - available_height / 2.0;
+                                scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+                            }
+                        }
 
-                                            // Show cursor on current line
-                                            if row_idx == cursor_row {
-                                                let line_len = line.chars().count();
-                                                let padding = cursor_col.saturating_sub(line_len);
-                                                if padding > 0 {
-                                                    ui.add(egui::Label::new(
-                                                        egui::RichText::new(" ".repeat(padding))
-                                                            .monospace()
-                                                    ));
-                                                }
-                                                ui.add(egui::Label::new(
-                                                    egui::RichText::new("▏")
-                                                        .monospace()
-                                                        .color(self.theme.accent)
-                                                ));
-                                            }
-                                        });
-                                    } else {
-                                        // Empty line - just show cursor if applicable
-                                        if row_idx == cursor_row {
-                                            ui.horizontal(|ui| {
-                                                let padding = cursor_col;
-                                                if padding > 0 {
-                                                    ui.add(egui::Label::new(
-                                                        egui::RichText::new(" ".repeat(padding))
-                                                            .monospace()
-                                                    ));
-                                                }
-                                                ui.add(egui::Label::new(
-                                                    egui::RichText::new("▏")
-                                                        .monospace()
-                                                        .color(self.theme.accent)
-                                                ));
-                                            });
-                                        } else {
-                                            ui.add(egui::Label::new(egui::RichText::new(" ").monospace()));
-                                        }
-                                    }
+                        ui.scope(|ui| {
+                            // Rows touch so background colors and box drawing join up
+                            ui.spacing_mut().item_spacing.y = 0.0;
+                            scroll_area.show_rows(ui, grid_row_height, rows.len(), |ui, range| {
+                                ui.set_max_width(ui.available_width() - grid_margin);
+                                for row_idx in range {
+                                    let match_bg = ui.painter().add(egui::Shape::Noop);
+                                    let row_top = ui.cursor().top();
+                                    let cursor = (cursor_row == Some(row_idx)).then_some(cursor_col);
+                                    let job = render::grid_row_job(rows[row_idx], &grid_font, grid_row_height, &colors, cursor);
+                                    ui.add(egui::Label::new(job).wrap_mode(egui::TextWrapMode::Extend));
+                                    mark_search_row(ui, match_bg, row_top, pane, row_idx, self.theme.accent);
                                 }
                             });
+                        });
                     }
                 } else if let Some(pane) = self.tabs[self.active_tab].panes.get(&focused_pane_id) {
                     // Normal mode: render output buffer
-                    // Get block content map for copy buttons
-                    let block_contents: std::collections::HashMap<usize, String> = pane.buffer.blocks()
-                        .iter()
-                        .filter_map(|b| {
-                            pane.buffer.get_block_content(b.id)
-                                .map(|content| (b.id, content))
-                        })
-                        .collect();
-
-                    egui::ScrollArea::vertical()
+                    let mut scroll_area = egui::ScrollArea::vertical()
                         .max_height(available_height)
-                        .stick_to_bottom(true)
+                        .stick_to_bottom(pane.follows_output())
                         .auto_shrink([false; 2])
-                        .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible)
-                        .show(ui, |ui| {
-                            // Leave margin on the right for mascot (only when visible)
-                            let margin = if show_mascot { 90.0 } else { 10.0 };
-                            ui.set_max_width(ui.available_width() - margin);
-
-                            // Track which blocks have had their header rendered
-                            let mut rendered_blocks = std::collections::HashSet::new();
-                            let comment_color = self.theme.comment_color;
-                            let accent_color = self.theme.accent;
-
-                            // Render output buffer with proper colors based on line type
-                            for line in pane.buffer.output_lines() {
-                                let base_color = match line.line_type {
-                                    LineType::Normal => foreground,
-                                    LineType::Error => error_color,
-                                    LineType::Command => command_color,
-                                    LineType::Success => success_color,
-                                };
-
-                                // Check if this is a command line with a new block
-                                if line.line_type == LineType::Command {
-                                    if let Some(block_id) = line.block_id {
-                                        if !rendered_blocks.contains(&block_id) {
-                                            rendered_blocks.insert(block_id);
-
-                                            // Render block header with copy button
-                                            ui.horizontal(|ui| {
-                                                // Copy button
-                                                let copy_btn = ui.add(
-                                                    egui::Button::new(
-                                                        egui::RichText::new(" Copy")
-                                                            .color(comment_color)
-                                                            .size(11.0)
-                                                    )
-                                                    .frame(false)
-                                                );
-
-                                                if copy_btn.clicked() {
-                                                    if let Some(content) = block_contents.get(&block_id) {
-                                                        block_to_copy = Some((block_id, content.clone()));
-                                                    }
-                                                }
-
-                                                if copy_btn.hovered() {
-                                                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
-                                                }
+                        .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible);
+                    if let Some(offset) = jump_offset {
+                        scroll_area = scroll_area.vertical_scroll_offset(offset);
+                    }
+                    if pane.search_jump {
+                        if let Some(top) = pane.current_match_line().and_then(|line| output_view.line_top(line)) {
+                            scroll_area = scroll_area.vertical_scroll_offset((top - available_height / 2.0).max(0.0));
+                        }
+                    }
+                    let mut hovered_path = None;
+                    let mut clicked_hash = None;
+                    let comment_color = self.theme.comment_color;
+                    let accent_color = self.theme.accent;
+                    // Leave margin on the right for mascot (only when visible)
+                    let margin = if show_mascot { 90.0 } else { 10.0 };
+
+                    // Only the rows in view are laid out
+                    let scroll_output = output_view.show(
+                        ui,
+                        scroll_area,
+                        &pane.buffer,
+                        filter_mask.as_deref(),
+                        margin,
+                        |ui, row, view| {
+                            let index = match row {
+                                Row::Line(index) => index,
+                                Row::Header(index) => {
+                                    let Some(block_id) = pane.buffer.get_line(index).and_then(|line| line.block_id) else {
+                                        return;
+                                    };
+                                    let Some(block) = pane.buffer.get_block(block_id) else {
+                                        return;
+                                    };
+                                    let button = |ui: &mut egui::Ui, label: &str| {
+                                        let response = ui.add(
+                                            egui::Button::new(egui::RichText::new(label).color(comment_color).size(11.0))
+                                                .frame(false),
+                                        );
+                                        if response.hovered() {
+                                            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                                        }
+                                        response
+                                    };
+                                    // Render block header with collapse, copy, re-run and edit buttons
+                                    let header = ui.scope_builder(
+                                        egui::UiBuilder::new()
+                                            .sense(egui::Sense::click())
+                                            .layout(egui::Layout::left_to_right(egui::Align::Center)),
+                                        |ui| {
+                                            let collapse = ui
+                                                .add(egui::Button::new(egui::RichText::new("▾").color(comment_color).size(11.0)).frame(false))
+                                                .on_hover_text("Collapse (Ctrl+Shift+K collapses all previous blocks)");
+                                            if collapse.clicked() {
+                                                block_action = Some(BlockAction::Toggle(block_id));
+                                            }
+                                            if button(ui, " Copy").clicked() {
+                                                block_action = Some(BlockAction::CopyOutput(block_id));
+                                            }
+                                            if button(ui, " Re-run").on_hover_text(&block.command).clicked() {
+                                                block_action = Some(BlockAction::Rerun(block.command.clone()));
+                                            }
+                                            if button(ui, " Edit").on_hover_text("Put the command in the input line").clicked() {
+                                                block_action = Some(BlockAction::Edit(block.command.clone()));
+                                            }
 
-                                                // Show duration if available
-                                                if let Some(block) = pane.buffer.get_block(block_id) {
-                                                    if let Some(duration) = block.duration {
-                                                        let dur_str = crate::terminal::buffer::format_duration(duration);
-                                                        ui.add(egui::Label::new(
-                                                            egui::RichText::new(format!(" {}", dur_str))
-                                                                .color(accent_color)
-                                                                .size(11.0)
-                                                        ));
-                                                    }
-                                                }
-                                            });
+                                            // Show duration if available
+                                            if let Some(duration) = block.duration {
+                                                let dur_str = crate::terminal::buffer::format_duration(duration);
+                                                ui.add(egui::Label::new(
+                                                    egui::RichText::new(format!(" {}", dur_str))
+                                                        .color(accent_color)
+                                                        .size(11.0)
+                                                ));
+                                            }
+                                        },
+                                    );
+                                    header.response.context_menu(|ui| block_menu(ui, block, &mut block_action));
+                                    return;
+                                }
+                                Row::Collapsed(index) => {
+                                    let block = pane.buffer.get_line(index).and_then(|line| line.block_id).and_then(|id| pane.buffer.get_block(id));
+                                    if let Some(block) = block {
+                                        let summary = block_summary(ui, block, &self.theme);
+                                        if summary.clicked() {
+                                            block_action = Some(BlockAction::Toggle(block.id));
                                         }
+                                        summary.context_menu(|ui| block_menu(ui, block, &mut block_action));
+                                    }
+                                    return;
+                                }
+                                Row::End => {
+                                    // Spinner under the output of a job that is still running
+                                    if let Some(status) = pane.job_status() {
+                                        ui.label(egui::RichText::new(status).color(accent_color).monospace());
                                     }
+                                    return;
                                 }
+                            };
+                            let Some(line) = pane.buffer.get_line(index) else {
+                                return;
+                            };
+                            let base_color = match line.line_type {
+                                LineType::Normal => foreground,
+                                LineType::Error => error_color,
+                                LineType::Command => command_color,
+                                LineType::Success => success_color,
+                            };
 
+                            // Timestamp gutter, then the line itself
+                            let match_bg = ui.painter().add(egui::Shape::Noop);
+                            let row_top = ui.cursor().top();
+                            let gutter = gutter_text(&pane.buffer, index, &self.config.terminal);
+                            with_gutter(ui, gutter.as_deref(), comment_color, |ui| {
                                 // Check if line contains ANSI codes
                                 let has_ansi = ansi::has_ansi(&line.text);
 
@@ -3943,7 +7975,31 @@ impl eframe::App for ZaxiomApp {
                                                 rich_text = rich_text.underline();
                                             }
 
-                                            ui.add(egui::Label::new(rich_text));
+                                            // A segment that is just a URL (e.g. a rendered markdown link) is clickable
+                                            if line.urls.iter().any(|u| u.url == segment.text) {
+                                                let url_response = ui.add(
+                                                    egui::Label::new(rich_text).sense(egui::Sense::click()),
+                                                );
+                                                if url_response.clicked() {
+                                                    let _ = open::that(&segment.text);
+                                                }
+                                                if url_response.hovered() {
+                                                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                                                }
+                                            } else if is_git_hash(&segment.text) {
+                                                // A colored commit hash (gl, gg) copies itself
+                                                let hash_response = ui.add(
+                                                    egui::Label::new(rich_text).sense(egui::Sense::click()),
+                                                );
+                                                if hash_response.clicked() {
+                                                    clicked_hash = Some(segment.text.clone());
+                                                }
+                                                if hash_response.hovered() {
+                                                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                                                }
+                                            } else if let Some(path) = output_label(ui, egui::Label::new(rich_text)) {
+                                                hovered_path = Some(path);
+                                            }
                                         }
                                     });
                                 } else if has_urls {
@@ -3955,11 +8011,13 @@ impl eframe::App for ZaxiomApp {
                                         for url_span in &line.urls {
                                             // Text before URL
                                             if url_span.start > last_end {
-                                                ui.add(egui::Label::new(
+                                                if let Some(path) = output_label(ui, egui::Label::new(
                                                     egui::RichText::new(&text[last_end..url_span.start])
                                                         .monospace()
                                                         .color(base_color),
-                                                ));
+                                                )) {
+                                                    hovered_path = Some(path);
+                                                }
                                             }
 
                                             // Clickable URL
@@ -3986,40 +8044,132 @@ impl eframe::App for ZaxiomApp {
 
                                         // Text after last URL
                                         if last_end < text.len() {
-                                            ui.add(egui::Label::new(
+                                            if let Some(path) = output_label(ui, egui::Label::new(
                                                 egui::RichText::new(&text[last_end..])
                                                     .monospace()
                                                     .color(base_color),
-                                            ));
+                                            )) {
+                                                hovered_path = Some(path);
+                                            }
                                         }
                                     });
                                 } else {
-                                    // Simple rendering for plain lines (with word wrap)
-                                    ui.add(egui::Label::new(
-                                        egui::RichText::new(&line.text)
-                                            .monospace()
-                                            .color(base_color),
-                                    ).wrap_mode(egui::TextWrapMode::Wrap));
+                                    // Plain lines wrap, and keep their galley while unchanged
+                                    let galley = view.galley(ui, index, &line.text, base_color);
+                                    if let Some(path) = output_label(ui, egui::Label::new(galley)) {
+                                        hovered_path = Some(path);
+                                    }
                                 }
+                            });
+                            mark_search_row(ui, match_bg, row_top, pane, index, accent_color);
+                        },
+                    );
+
+                    // Preview the file under the pointer
+                    if let Some(word) = hovered_path {
+                        let path = pane.state.resolve_path(&word);
+                        show_path_preview(ui, &mut self.previews, &self.theme, &path);
+                    }
+                    if let Some(hash) = clicked_hash {
+                        if let Some(ref mut clipboard) = self.clipboard {
+                            if clipboard.set_text(hash.clone()).is_ok() {
+                                self.clipboard_feedback =
+                                    Some((format!("🔗 Copied~ {}", hash), std::time::Instant::now()));
                             }
-                        });
+                        }
+                    }
+
+                    let max_offset =
+                        (scroll_output.content_size.y - scroll_output.inner_rect.height()).max(0.0);
+                    if self.config.terminal.minimap {
+                        let matches: Vec<usize> = pane
+                            .search_matches
+                            .iter()
+                            .copied()
+                            .chain(pane.vi_mode.search_matches.iter().map(|&(line, _, _)| line))
+                            .collect();
+                        let current = if pane.search_mode {
+                            pane.search_matches.get(pane.current_match).copied()
+                        } else {
+                            pane.vi_mode
+                                .search_matches
+                                .get(pane.vi_mode.current_match)
+                                .map(|&(line, _, _)| line)
+                        };
+                        minimap_click = self.render_minimap(
+                            ui,
+                            scroll_output.inner_rect,
+                            &minimap::collect_markers(&pane.buffer, &matches, current),
+                            pane.buffer.len(),
+                        );
+                    }
+                    minimap_max_offset = Some(max_offset);
                 }
 
-                // Handle block copy request
-                if let Some((_, content)) = block_to_copy {
-                    if let Some(ref mut clipboard) = self.clipboard {
-                        if clipboard.set_text(content.clone()).is_ok() {
-                            let preview = if content.len() > 30 {
-                                format!("{}...", &content[..30])
-                            } else {
-                                content.clone()
-                            };
-                            self.clipboard_feedback = Some((
-                                format!("📦 Block copied~ ♪(´ε` ) {}", preview.replace('\n', " ")),
-                                std::time::Instant::now(),
-                            ));
+                // Remember scroll extent and apply minimap clicks for the next frame
+                if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
+                    pane.output_view = output_view;
+                    if let Some(max_offset) = minimap_max_offset {
+                        pane.max_scroll_offset = max_offset;
+                    }
+                    if minimap_click.is_some() {
+                        pane.minimap_jump = minimap_click;
+                        ui.ctx().request_repaint();
+                    }
+                    pane.search_jump = false;
+                }
+
+                // Handle block button and menu requests
+                match block_action {
+                    Some(BlockAction::Toggle(block_id)) => {
+                        if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
+                            pane.buffer.toggle_collapsed(block_id);
+                        }
+                    }
+                    Some(BlockAction::CopyOutput(block_id)) => {
+                        let content = self.tabs[self.active_tab]
+                            .panes
+                            .get(&focused_pane_id)
+                            .and_then(|pane| pane.buffer.get_block_content(block_id));
+                        if let (Some(content), Some(clipboard)) = (content, self.clipboard.as_mut()) {
+                            if clipboard.set_text(content.clone()).is_ok() {
+                                let preview = if content.len() > 30 {
+                                    format!("{}...", &content[..30])
+                                } else {
+                                    content.clone()
+                                };
+                                self.clipboard_feedback = Some((
+                                    format!("📦 Block copied~ ♪(´ε` ) {}", preview.replace('\n', " ")),
+                                    std::time::Instant::now(),
+                                ));
+                            }
+                        }
+                    }
+                    Some(BlockAction::CopyCommand(command)) => {
+                        if let Some(clipboard) = self.clipboard.as_mut() {
+                            if clipboard.set_text(command.clone()).is_ok() {
+                                self.clipboard_feedback = Some((
+                                    format!("📋 Command copied~ {}", command.replace('\n', " ")),
+                                    std::time::Instant::now(),
+                                ));
+                            }
+                        }
+                    }
+                    Some(BlockAction::Rerun(command)) => self.execute_command(&command),
+                    Some(BlockAction::Edit(command)) => {
+                        if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
+                            pane.input = command;
+                            pane.cursor_to_end = true;
                         }
                     }
+                    Some(BlockAction::Delete(block_id)) => {
+                        if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
+                            if pane.buffer.remove_block(block_id) && pane.filter.block == Some(block_id) {
+                                pane.filter.block = None;
+                            }
+                        }
+                    }
+                    None => {}
                 }
 
                 // Hints overlay (when hints mode is active) - floating window
@@ -4378,6 +8528,19 @@ impl eframe::App for ZaxiomApp {
                 if let Some(pane) = self.tabs[self.active_tab].panes.get_mut(&focused_pane_id) {
                     let is_raw_mode = pane.input_mode == InputMode::Raw;
 
+                    // Lines entered so far of an unfinished command
+                    if let Some(pending) = &pane.continuation {
+                        let prompt = pane.state.format_prompt();
+                        for (index, line) in pending.lines().enumerate() {
+                            let lead = if index == 0 { prompt.as_str() } else { "> " };
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(format!("{}{}", lead, line))
+                                    .monospace()
+                                    .color(self.theme.comment_color),
+                            ));
+                        }
+                    }
+
                     ui.horizontal(|ui| {
                         // In raw PTY mode, show minimal prompt
                         if is_raw_mode {
@@ -4387,7 +8550,11 @@ impl eframe::App for ZaxiomApp {
                                     .color(self.theme.comment_color),
                             ));
                         } else {
-                            let prompt = pane.state.format_prompt();
+                            let prompt = if pane.continuation.is_some() {
+                                "> ".to_string()
+                            } else {
+                                pane.state.format_prompt()
+                            };
                             ui.add(egui::Label::new(
                                 egui::RichText::new(&prompt)
                                     .monospace()
@@ -4405,13 +8572,118 @@ impl eframe::App for ZaxiomApp {
                             }
 
                             let text_edit_id = ui.make_persistent_id("input_field");
-                            let modal_active = pane.hints_mode.active || pane.vi_mode.active || palette_was_open || editor_is_open;
-                            let text_edit = egui::TextEdit::singleline(&mut pane.input)
+                            let modal_active = pane.hints_mode.active || pane.vi_mode.active || pane.pager.active || palette_was_open || editor_is_open;
+
+                            // Readline keys act on the line before the text field sees them
+                            if !modal_active
+                                && !pane.fuzzy_finder.active
+                                && ui.memory(|m| m.has_focus(text_edit_id))
+                            {
+                                let line_empty = pane.input.is_empty();
+                                let action = ui.input_mut(|i| {
+                                    let (index, action, alt) = i.events.iter().enumerate().find_map(|(index, event)| match event {
+                                        egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                                            EditAction::from_key(*key, *modifiers, line_empty)
+                                                // Alt+D discards a hunk while the git panel is open
+                                                .filter(|action| !(git_panel_open && *action == EditAction::KillWordForward))
+                                                .map(|action| (index, action, modifiers.alt))
+                                        }
+                                        _ => None,
+                                    })?;
+                                    i.events.remove(index);
+                                    // Alt+letter can also arrive as typed text
+                                    if alt {
+                                        i.events.retain(|event| !matches!(event, egui::Event::Text(_)));
+                                    }
+                                    Some(action)
+                                });
+                                if let Some(action) = action {
+                                    let cursor = input_cursor(ui.ctx(), text_edit_id, &pane.input);
+                                    let cursor = input::apply(action, &mut pane.input, cursor, &mut pane.kill_ring);
+                                    set_input_cursor(ui.ctx(), text_edit_id, input::byte_to_char(&pane.input, cursor));
+                                    input_changed = true;
+                                }
+
+                                // Backspace on an empty `> ` line goes back to the line above
+                                if line_empty
+                                    && pane.continuation.is_some()
+                                    && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace))
+                                {
+                                    let pending = pane.continuation.take().unwrap_or_default();
+                                    pane.input = match pending.rsplit_once('\n') {
+                                        Some((above, last)) => {
+                                            pane.continuation = Some(above.to_string());
+                                            last.to_string()
+                                        }
+                                        None => pending,
+                                    };
+                                    set_input_cursor(ui.ctx(), text_edit_id, pane.input.chars().count());
+                                    input_changed = true;
+                                }
+                            }
+
+                            // Dropped paths go in at the cursor
+                            if let Some(text) = dropped_text.take().filter(|_| !modal_active) {
+                                let cursor = input_cursor(ui.ctx(), text_edit_id, &pane.input);
+                                let cursor = input::insert_word(&mut pane.input, cursor, &text);
+                                set_input_cursor(ui.ctx(), text_edit_id, input::byte_to_char(&pane.input, cursor));
+                                input_changed = true;
+                            }
+
+                            // On an empty prompt, offer this directory's frecent commands
+                            let quick_picks = if pane.input.is_empty() && pane.continuation.is_none() {
+                                pane.history.frecent(pane.state.cwd(), 3)
+                            } else {
+                                Vec::new()
+                            };
+
+                            // Highlight the prefix Up is searching history for,
+                            // and underline a syntax error in a restored line
+                            let prefix_len = pane
+                                .history
+                                .navigation_prefix()
+                                .filter(|prefix| pane.input.starts_with(prefix))
+                                .map(str::len);
+                            let error_span = pane
+                                .syntax_error
+                                .as_ref()
+                                .filter(|(line, _)| *line == pane.input)
+                                .map(|(_, span)| (span.clone(), self.theme.error_color));
+                            let custom_layout = prefix_len.is_some() || error_span.is_some();
+                            let prefix = (prefix_len.unwrap_or(0), self.theme.accent);
+                            let mut input_layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| {
+                                let font = egui::TextStyle::Monospace.resolve(ui.style());
+                                let input_color = ui
+                                    .visuals()
+                                    .override_text_color
+                                    .unwrap_or_else(|| ui.visuals().widgets.inactive.text_color());
+                                let job = render::input_job(text, &font, input_color, prefix, error_span.clone());
+                                ui.fonts(|fonts| fonts.layout_job(job))
+                            };
+
+                            let mut text_edit = egui::TextEdit::singleline(&mut pane.input)
                                 .id(text_edit_id)
                                 .font(egui::TextStyle::Monospace)
                                 .desired_width(input_width.max(200.0))
                             .frame(false)
                             .interactive(!modal_active);  // Disable input when modal/overlay is active
+                        if custom_layout {
+                            text_edit = text_edit.layouter(&mut input_layouter);
+                        }
+
+                        if !quick_picks.is_empty() {
+                            let hint = quick_picks
+                                .iter()
+                                .enumerate()
+                                .map(|(index, cmd)| format!("Alt+{} {}", index + 1, cmd))
+                                .collect::<Vec<_>>()
+                                .join("   ");
+                            text_edit = text_edit.hint_text(hint);
+                        }
+
+                        if let Some(pending) = &pane.continuation {
+                            text_edit = text_edit.hint_text(continuation_hint(pending));
+                        }
 
                         let response = ui.add(text_edit);
 
@@ -4431,7 +8703,7 @@ impl eframe::App for ZaxiomApp {
 
                         // Handle keyboard input (only when no overlays are active)
                         // Use palette_was_open to prevent Enter from executing after palette closes
-                        let overlay_active = editor_is_open || palette_was_open || pane.fuzzy_finder.active;
+                        let overlay_active = editor_is_open || palette_was_open || pane.fuzzy_finder.active || pane.pager.active;
 
                         // Auto-focus the input and execute on Enter (only if no overlay)
                         if !overlay_active && response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
@@ -4447,9 +8719,10 @@ impl eframe::App for ZaxiomApp {
                                     apply_suggestion = true;
                                 }
 
-                            // Up/Down: navigate suggestions or history
+                            // Up/Down: navigate suggestions or history (Up searches
+                            // history by prefix until Down has moved into the popup)
                             if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-                                if has_suggestions && pane.show_suggestions {
+                                if has_suggestions && pane.show_suggestions && pane.selected_suggestion > 0 {
                                     suggestion_up = true;
                                 } else {
                                     history_up = true;
@@ -4463,6 +8736,17 @@ impl eframe::App for ZaxiomApp {
                                 }
                             }
 
+                            // Alt+1..3: take a suggested command from the empty prompt
+                            for (key, cmd) in [egui::Key::Num1, egui::Key::Num2, egui::Key::Num3]
+                                .into_iter()
+                                .zip(&quick_picks)
+                            {
+                                if ui.input_mut(|i| i.consume_key(egui::Modifiers::ALT, key)) {
+                                    pane.input = cmd.clone();
+                                    pane.cursor_to_end = true;
+                                }
+                            }
+
                             // Escape: close suggestions
                             if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
                                 && pane.show_suggestions {
@@ -4470,26 +8754,46 @@ impl eframe::App for ZaxiomApp {
                                 }
                         }
 
-                            // Keep focus on input
-                            response.request_focus();
+                            // Keep focus on input (unless the filter bar is being typed in)
+                            if !filter_focused {
+                                response.request_focus();
+                            }
                         } // End of !is_raw_mode block
                     });
+
+                    // Signature help: the command's usage with the current argument marked
+                    if !is_raw_mode && pane.continuation.is_none() {
+                        if let Some(hint) = usage_hint(&pane.input, |name| self.executor.usage(name)) {
+                            let font = egui::FontId::monospace(11.0);
+                            let job = render::usage_hint_job(hint.usage, hint.active, &font, self.theme.comment_color, self.theme.accent);
+                            ui.add(egui::Label::new(job).selectable(false));
+                        }
+                    }
                 }
             });
 
         // Handle history navigation outside the closure
         if history_up {
+            self.history_up_streak += 1;
+            if self.history_up_streak == 5 {
+                self.tip_event(TipEvent::HistoryScrolled(self.history_up_streak));
+            }
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
-                // Save current input when starting to navigate history
-                if pane.saved_input.is_empty() && !pane.input.is_empty() {
+                // Save current input when starting to navigate history; typed
+                // text limits Up to commands starting with it
+                if !pane.history.is_navigating() {
                     pane.saved_input = pane.input.clone();
                 }
-                if let Some(cmd) = pane.history.previous() {
+                let prefix = pane.saved_input.clone();
+                if let Some(cmd) = pane.history.previous_with_prefix(&prefix) {
                     pane.input = cmd.to_string();
+                    pane.cursor_to_end = true;
+                    pane.show_suggestions = false;
                 }
             }
         }
         if history_down {
+            self.history_up_streak = 0;
             if let Some(pane) = self.tabs[self.active_tab].focused_pane_mut() {
                 if let Some(cmd) = pane.history.next() {
                     pane.input = cmd.to_string();
@@ -4598,6 +8902,7 @@ impl eframe::App for ZaxiomApp {
                                     SuggestionKind::Flag => ("", self.theme.flag_color),
                                     SuggestionKind::Command => ("", self.theme.success_color),
                                     SuggestionKind::EnvVar => ("$", self.theme.number_color),
+                                    SuggestionKind::StackEntry => ("+", self.theme.folder_color),
                                 };
 
                                 egui::Frame::new()
@@ -6,7 +6,12 @@
 use anyhow::Result;
 
 use super::traits::Command as CommandTrait;
-use crate::ai::{get_help, list_providers, OllamaProvider};
+use crate::ai::cache::{format_age, AiCache};
+use crate::ai::persona::format_persona_list;
+use crate::ai::summarize::summarize;
+use crate::ai::{get_help, list_providers, OllamaProvider, ProviderChoice};
+use crate::config::settings::Config;
+use crate::terminal::markdown;
 use crate::terminal::state::TerminalState;
 
 /// AI command - manage providers and settings
@@ -22,7 +27,11 @@ impl CommandTrait for AiCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "ai [status|providers|help] [args...]"
+        "ai [status|providers|personas|privacy|cache|help] [args...]"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
     }
 
     fn extended_help(&self) -> String {
@@ -37,6 +46,9 @@ impl CommandTrait for AiCommand {
         match args[0].as_str() {
             "status" => Ok(get_status()),
             "providers" | "list" => Ok(get_providers_list()),
+            "personas" | "persona" => Ok(format_persona_list(&Config::load())),
+            "privacy" => Ok(get_privacy_settings()),
+            "cache" => handle_cache(&args[1..]),
             "help" | "--help" | "-h" => Ok(get_help()),
             _ => Ok(get_help()),
         }
@@ -74,6 +86,88 @@ fn get_status() -> String {
     output
 }
 
+/// Inspect or clear the AI response cache
+fn handle_cache(args: &[String]) -> Result<String> {
+    let mut cache = AiCache::load();
+
+    match args.first().map(|s| s.as_str()) {
+        Some("clear") => {
+            let removed = cache.clear();
+            cache.save()?;
+            Ok(format!("🧹 Cleared {} cached AI answer(s)", removed))
+        }
+        Some("path") => Ok(AiCache::cache_path().display().to_string()),
+        None | Some("list") | Some("status") => {
+            let config = Config::load().ai.cache;
+            let mut output = String::from("\n🗃️  AI Response Cache\n");
+            output.push_str("═══════════════════════════════════════\n\n");
+            output.push_str(&format!(
+                "  Enabled: {}\n  TTL:     {} min\n  Entries: {}\n",
+                if config.enabled { "yes" } else { "no" },
+                config.ttl_minutes,
+                cache.len()
+            ));
+
+            if !cache.is_empty() {
+                output.push_str("\nMost recent:\n");
+                for entry in cache.recent().into_iter().take(10) {
+                    let prompt: String = entry.prompt.chars().take(50).collect();
+                    output.push_str(&format!(
+                        "  {:<9} {:<12} {}\n",
+                        format_age(entry.age_secs()),
+                        entry.provider,
+                        prompt
+                    ));
+                }
+            }
+
+            output.push_str("\nUsage: ai cache [list|clear|path]\n");
+            output.push_str("Settings: [ai.cache] enabled, ttl_minutes\n");
+            Ok(output)
+        }
+        Some(other) => Ok(format!(
+            "Unknown cache action '{}'. Usage: ai cache [list|clear|path]",
+            other
+        )),
+    }
+}
+
+/// Show what context is shared with AI providers
+fn get_privacy_settings() -> String {
+    let privacy = Config::load().ai.privacy;
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+
+    let mut output = String::from("\n🔒 AI Privacy Settings\n");
+    output.push_str("═══════════════════════════════════════\n\n");
+    output.push_str(&format!(
+        "  Include cwd:          {}\n",
+        yes_no(privacy.include_cwd)
+    ));
+    output.push_str(&format!(
+        "  History commands:     {}\n",
+        privacy.history_commands
+    ));
+    output.push_str(&format!(
+        "  Include last output:  {} (max {} lines)\n",
+        yes_no(privacy.include_output),
+        privacy.max_output_lines
+    ));
+    output.push_str(&format!(
+        "  Redact secrets:       {}\n",
+        yes_no(privacy.redact_secrets)
+    ));
+
+    output.push_str("\nChange these in config.toml under [ai.privacy]:\n");
+    output.push_str("  include_cwd = true\n");
+    output.push_str("  history_commands = 5\n");
+    output.push_str("  include_output = false\n");
+    output.push_str("  max_output_lines = 50\n");
+    output.push_str("  redact_secrets = true\n");
+    output.push_str(&format!("\nConfig: {}\n", Config::config_path().display()));
+
+    output
+}
+
 /// Get formatted providers list
 fn get_providers_list() -> String {
     let providers = list_providers();
@@ -115,6 +209,10 @@ impl CommandTrait for OllamaCommand {
         "ollama [list|pull|serve|status|models|--help] [args...]"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn extended_help(&self) -> String {
         get_ollama_help()
     }
@@ -196,6 +294,117 @@ impl CommandTrait for OllamaCommand {
     }
 }
 
+/// Summarize command - condense piped output with AI
+pub struct SummarizeCommand;
+
+impl CommandTrait for SummarizeCommand {
+    fn name(&self) -> &'static str {
+        "summarize"
+    }
+
+    fn description(&self) -> &'static str {
+        "Summarize piped output with AI"
+    }
+
+    fn usage(&self) -> &'static str {
+        "command | summarize [--<provider>] [focus...]"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"summarize - Condense long output with AI
+
+USAGE:
+  command | summarize [--<provider>] [focus...]
+  summarize [--<provider>] <file> [focus...]
+
+DESCRIPTION:
+  Sends the piped text to your AI provider and prints a short summary:
+  the outcome first, then the details that matter. Large inputs are
+  split into chunks, summarized separately, then merged.
+
+  Anything after the flags is treated as a focus hint.
+  Secrets are redacted first unless [ai.privacy] redact_secrets = false.
+
+EXAMPLES:
+  cargo build 2>&1 | summarize            What happened in the build?
+  git log -50 | summarize                 Recent history at a glance
+  cat app.log | summarize errors only     Focus the summary
+  summarize --groq build.log              Use a specific provider
+
+RELATED COMMANDS:
+  ai       Manage AI providers
+  #        Ask the AI a question
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut choice = ProviderChoice::Default;
+        let mut words: Vec<&str> = Vec::new();
+
+        for arg in args {
+            match ProviderChoice::from_flag(arg) {
+                Some(c) => choice = c,
+                None => words.push(arg),
+            }
+        }
+
+        // Without piped input, the first word may name a file to summarize
+        let file_content;
+        let text = match stdin {
+            Some(input) => input,
+            None => {
+                let path = words.first().map(|w| state.resolve_path(w));
+                match path.filter(|p| p.is_file()) {
+                    Some(path) => {
+                        file_content = std::fs::read_to_string(&path)
+                            .map_err(|e| anyhow::anyhow!("summarize: {}: {}", words[0], e))?;
+                        words.remove(0);
+                        file_content.as_str()
+                    }
+                    None => {
+                        return Ok(format!(
+                            "Usage: {}\n\nTry: git log -20 | summarize",
+                            self.usage()
+                        ))
+                    }
+                }
+            }
+        };
+
+        let focus = if words.is_empty() {
+            None
+        } else {
+            Some(words.join(" "))
+        };
+
+        let (provider, summary) = summarize(text, &choice, focus.as_deref())?;
+        Ok(format!(
+            "📝 Summary ({} lines via {}):\n\n{}",
+            text.lines().count(),
+            provider,
+            markdown::render(summary.trim())
+        ))
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
 fn get_ollama_help() -> String {
     r#"
 ┌─────────────────────────────────────────────────────────────┐
@@ -127,11 +127,9 @@ RELATED COMMANDS:
                         't' => list = true,
                         'v' => verbose = true,
                         'z' => gzip = true,
-                        'f' => {
-                            if i + 1 < args.len() {
-                                i += 1;
-                                archive_file = Some(args[i].clone());
-                            }
+                        'f' if i + 1 < args.len() => {
+                            i += 1;
+                            archive_file = Some(args[i].clone());
                         }
                         _ => {}
                     }
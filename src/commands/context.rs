@@ -0,0 +1,259 @@
+//! Cancellation and progress for long-running commands
+//!
+//! A `CommandContext` is handed to `Command::execute_with_context`. Commands
+//! that can take a while (walking a directory tree, hashing a large file)
+//! call `check()` between units of work and return its error as soon as the
+//! job is cancelled, and may `report()` how far they got. Whoever runs the
+//! command keeps a clone of the `CancelToken` and cancels it on Ctrl+C, or
+//! pauses it on Ctrl+Z: `check()` then waits until the job is resumed.
+//!
+//! Commands that produce output over time (`ping`, `find`) may `print()`
+//! lines as they go when the context is streaming; the background jobs in
+//! `shell::job` show those lines right away. A command that shows a whole
+//! screen over and over (`watch`) may `redraw()` it instead, replacing what
+//! it drew last.
+
+#![allow(dead_code)]
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often a paused `check()` looks for resume or cancel
+const PAUSE_POLL: Duration = Duration::from_millis(50);
+
+/// Shared flags set when a job should stop or pause (cheap to clone)
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<Flags>);
+
+#[derive(Debug, Default)]
+struct Flags {
+    cancelled: AtomicBool,
+    paused: AtomicBool,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every holder of this token to stop
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Ask the job to wait at its next `check()` until resumed
+    pub fn pause(&self) {
+        self.0.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.0.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.paused.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned by a command that stopped because it was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Receives progress messages ("1200 files", "45%")
+pub type ProgressFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Receives output lines printed while a command runs
+pub type OutputFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// What a running command can ask of whoever started it
+#[derive(Clone, Default)]
+pub struct CommandContext {
+    cancel: CancelToken,
+    progress: Option<ProgressFn>,
+    output: Option<OutputFn>,
+    redraw: Option<OutputFn>,
+}
+
+impl CommandContext {
+    /// A context cancelled through `cancel`
+    pub fn new(cancel: CancelToken) -> Self {
+        Self {
+            cancel,
+            progress: None,
+            output: None,
+            redraw: None,
+        }
+    }
+
+    /// Send progress messages to `progress`
+    pub fn with_progress(mut self, progress: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Send lines passed to `print` to `output`
+    pub fn with_output(mut self, output: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.output = Some(Arc::new(output));
+        self
+    }
+
+    /// Send screens passed to `redraw` to `redraw`
+    pub fn with_redraw(mut self, redraw: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.redraw = Some(Arc::new(redraw));
+        self
+    }
+
+    /// The same context without its output sinks, for a command whose output
+    /// is piped or redirected rather than shown
+    pub fn without_output(&self) -> Self {
+        Self {
+            output: None,
+            redraw: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Whether the job was paused (commands that run a child process stop
+    /// and continue it to match)
+    pub fn is_paused(&self) -> bool {
+        self.cancel.is_paused()
+    }
+
+    /// Whether printed lines are shown as they come. A command that prints
+    /// leaves those lines out of the output it returns.
+    pub fn is_streaming(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// `Err(Interrupted)` once the job has been cancelled; while it is
+    /// paused, waits to be resumed first
+    pub fn check(&self) -> anyhow::Result<()> {
+        while self.is_paused() && !self.is_cancelled() {
+            thread::sleep(PAUSE_POLL);
+        }
+        if self.is_cancelled() {
+            Err(Interrupted.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Report progress (ignored when nobody is listening)
+    pub fn report(&self, message: &str) {
+        if let Some(progress) = &self.progress {
+            progress(message);
+        }
+    }
+
+    /// Show a line of output right away (only while streaming)
+    pub fn print(&self, line: &str) {
+        if let Some(output) = &self.output {
+            output(line);
+        }
+    }
+
+    /// Whether `redraw` can replace what was drawn last
+    pub fn can_redraw(&self) -> bool {
+        self.redraw.is_some()
+    }
+
+    /// Show `screen` in place of the previous one (only while redrawing)
+    pub fn redraw(&self, screen: &str) {
+        if let Some(redraw) = &self.redraw {
+            redraw(screen);
+        }
+    }
+}
+
+impl fmt::Debug for CommandContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandContext")
+            .field("cancel", &self.cancel)
+            .field("progress", &self.progress.is_some())
+            .field("output", &self.output.is_some())
+            .field("redraw", &self.redraw.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_cancel_and_progress() {
+        let token = CancelToken::new();
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&messages);
+        let ctx = CommandContext::new(token.clone())
+            .with_progress(move |m| sink.lock().unwrap().push(m.to_string()));
+
+        assert!(ctx.check().is_ok());
+        ctx.report("10 files");
+        token.cancel();
+        let err = ctx.check().unwrap_err();
+        assert_eq!(err.downcast_ref::<Interrupted>(), Some(&Interrupted));
+        assert_eq!(*messages.lock().unwrap(), ["10 files"]);
+
+        // Without a listener, reporting is a no-op
+        CommandContext::default().report("ignored");
+    }
+
+    #[test]
+    fn test_check_waits_while_paused() {
+        let token = CancelToken::new();
+        let ctx = CommandContext::new(token.clone());
+        token.pause();
+        let resumer = {
+            let token = token.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                token.resume();
+            })
+        };
+        let started = std::time::Instant::now();
+        assert!(ctx.check().is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        resumer.join().unwrap();
+
+        // Cancelling a paused job stops it without resuming
+        token.pause();
+        token.cancel();
+        assert!(ctx.check().is_err());
+    }
+
+    #[test]
+    fn test_print_streams_until_output_is_taken_away() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&lines);
+        let ctx = CommandContext::default()
+            .with_output(move |line| sink.lock().unwrap().push(line.to_string()));
+
+        assert!(ctx.is_streaming());
+        ctx.print("first");
+        let piped = ctx.without_output();
+        assert!(!piped.is_streaming());
+        piped.print("second");
+        assert_eq!(*lines.lock().unwrap(), ["first"]);
+    }
+}
@@ -38,12 +38,15 @@ OPTIONS:
 
 DESCRIPTION:
   Concatenate and display files. Automatically detects file type
-  and applies syntax highlighting for 40+ languages.
+  and applies syntax highlighting for 100+ languages in the
+  current theme's colors. Files without an extension are matched
+  by name (Makefile, Dockerfile) or by their #! line.
   Can also display images as ASCII art!
 
 SUPPORTED LANGUAGES:
   Rust, Python, JavaScript, TypeScript, Go, C, C++, Java,
-  Ruby, PHP, Swift, Kotlin, Scala, Haskell, Elixir, and more...
+  Ruby, PHP, Swift, Kotlin, Scala, Haskell, Elixir, TOML, YAML,
+  Dockerfile, SQL, and more...
 
 EXAMPLES:
   cat file.txt           Display file contents
@@ -60,7 +63,7 @@ IMAGE SUPPORT:
 RELATED COMMANDS:
   head     Show first lines
   tail     Show last lines
-  less     Page through file (coming soon)
+  less     Page through file
 "#
         .to_string()
     }
@@ -150,69 +153,36 @@ RELATED COMMANDS:
             let contents =
                 fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("cat: {}: {}", file, e))?;
 
-            // Check for syntax highlighting
-            let should_highlight = !no_syntax
-                && (force_syntax || {
-                    path.extension()
-                        .and_then(|e| e.to_str())
-                        .map(syntax::is_supported)
-                        .unwrap_or(false)
-                });
-
-            if should_highlight {
-                // Get the file extension for syntax detection
-                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                    if let Some(highlighted) = syntax::highlight_code(&contents, ext) {
-                        // Show syntax name in header
-                        if let Some(syntax_name) = syntax::get_syntax_name(ext) {
-                            output.push_str(&format!(" {} [{}]\n", path.display(), syntax_name));
-                            output.push_str(&"─".repeat(60));
-                            output.push('\n');
-                        }
-
-                        // Output highlighted lines with color markers
-                        for (line_idx, line_segments) in highlighted.iter().enumerate() {
-                            if show_line_numbers {
-                                output.push_str(&format!("{:6} │ ", line_number));
-                                line_number += 1;
-                            }
-
-                            for segment in line_segments {
-                                // Use ANSI color codes for terminal display
-                                let r = segment.fg.0;
-                                let g = segment.fg.1;
-                                let b = segment.fg.2;
-
-                                // Clean up the text (remove trailing newline as we add our own)
-                                let text = segment.text.trim_end_matches('\n');
-
-                                if segment.bold {
-                                    output.push_str(&format!(
-                                        "\x1b[1;38;2;{};{};{}m{}\x1b[0m",
-                                        r, g, b, text
-                                    ));
-                                } else if segment.italic {
-                                    output.push_str(&format!(
-                                        "\x1b[3;38;2;{};{};{}m{}\x1b[0m",
-                                        r, g, b, text
-                                    ));
-                                } else {
-                                    output.push_str(&format!(
-                                        "\x1b[38;2;{};{};{}m{}\x1b[0m",
-                                        r, g, b, text
-                                    ));
-                                }
-                            }
-
-                            // Only add newline if it's not the last line or if original had newline
-                            if line_idx < highlighted.len() - 1 {
-                                output.push('\n');
-                            }
-                        }
-                        output.push('\n');
-                        continue;
+            // Check for syntax highlighting: by extension, file name, or
+            // shebang (not for output headed into a pipe or file, unless forced)
+            let found = if no_syntax || (state.output_piped && !force_syntax) {
+                None
+            } else {
+                syntax::syntax_for_path(&path, contents.lines().next().unwrap_or_default())
+            };
+
+            if let Some(found) = found {
+                // Show syntax name in header
+                let syntax_name = path
+                    .extension()
+                    .or_else(|| path.file_name())
+                    .and_then(|token| token.to_str())
+                    .and_then(syntax::get_syntax_name)
+                    .unwrap_or_else(|| found.name.clone());
+                output.push_str(&format!(" {} [{}]\n", path.display(), syntax_name));
+                output.push_str(&"─".repeat(60));
+                output.push('\n');
+
+                let mut highlighter = syntax::LineHighlighter::new(found);
+                for line in contents.lines() {
+                    if show_line_numbers {
+                        output.push_str(&format!("{:6} │ ", line_number));
+                        line_number += 1;
                     }
+                    output.push_str(&highlighter.ansi(line));
+                    output.push('\n');
                 }
+                continue;
             }
 
             // Fall back to plain text
@@ -238,3 +208,32 @@ RELATED COMMANDS:
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_cat_golden() {
+        let fixture = Fixture::new("cat")
+            .file("a.txt", "first\n\nthird\n")
+            .file("b.txt", "fourth\n")
+            .file("Dockerfile", "FROM rust\n")
+            .file("deploy", "#!/bin/sh\necho hi\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(&CatCommand, &["-n", "a.txt", "b.txt"], &mut state).unwrap();
+        assert_golden("cat_numbered", &fixture.scrub(&output));
+
+        assert!(run(&CatCommand, &["missing.txt"], &mut state).is_err());
+
+        // Files without an extension are matched by name or #! line
+        let output = run(&CatCommand, &["Dockerfile"], &mut state).unwrap();
+        assert!(output.contains("Dockerfile [Dockerfile]"));
+        assert!(output.contains("\x1b[38;2;"));
+        let output = run(&CatCommand, &["deploy"], &mut state).unwrap();
+        assert!(output.contains("[Bourne Again Shell (bash)]"));
+        let plain = run(&CatCommand, &["-p", "deploy"], &mut state).unwrap();
+        assert_eq!(plain, "#!/bin/sh\necho hi");
+    }
+}
@@ -0,0 +1,182 @@
+//! less command - page through files or piped output
+
+use std::fs;
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::{PagerRequest, TerminalState};
+
+pub struct LessCommand;
+
+impl Command for LessCommand {
+    fn name(&self) -> &'static str {
+        "less"
+    }
+
+    fn description(&self) -> &'static str {
+        "Page through files or piped output"
+    }
+
+    fn usage(&self) -> &'static str {
+        "less [-N] [file...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"less - Page through files or piped output
+
+USAGE:
+  less [OPTIONS] <file...>
+  command | less
+
+OPTIONS:
+  -N, --LINE-NUMBERS  Show line numbers
+
+DESCRIPTION:
+  Opens the text in the pager over the pane. Several files are shown
+  one after another, each under its name. When the output goes to a
+  pipe or a file instead, less passes the text through like cat.
+
+  Output of any built-in that is taller than the pane opens in the
+  pager on its own; set pager = false under [terminal] in config.toml
+  to turn that off. The output stays in the scrollback either way.
+
+KEYS:
+  j / k, ↓ / ↑        Line down / up (with a count: 5j)
+  Space / b           Page down / up (also Ctrl+F / Ctrl+B)
+  d / u               Half page down / up (also Ctrl+D / Ctrl+U)
+  g / G               Top / bottom (with a count: 42g goes to line 42)
+  /pattern            Search (case-insensitive unless it has capitals)
+  n / N               Next / previous match
+  q / Esc             Close
+
+EXAMPLES:
+  less notes.txt          Page through a file
+  less -N src/main.rs     With line numbers
+  grep -r TODO | less     Page through search results
+  more README.md          Same as less
+
+RELATED COMMANDS:
+  cat      Print file contents
+  head     Show first lines
+  tail     Show last lines
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut line_numbers = false;
+        let mut files = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "-N" | "--LINE-NUMBERS" | "--line-numbers" => line_numbers = true,
+                _ if arg.starts_with('-') && arg.len() > 1 => {
+                    return Err(anyhow!("less: unknown option '{}'", arg));
+                }
+                _ => files.push(arg.as_str()),
+            }
+        }
+
+        let (title, text) = match (files.as_slice(), stdin) {
+            ([], Some(input)) => ("(standard input)".to_string(), input.to_string()),
+            ([], None) => {
+                return Err(anyhow!(
+                    "less: missing filename (less <file> or ... | less)"
+                ))
+            }
+            ([file], _) => (file.to_string(), read(file, state)?),
+            (files, _) => {
+                let mut text = Vec::new();
+                for file in files {
+                    let contents = read(file, state)?;
+                    text.push(format!(
+                        "==> {} <==\n{}",
+                        file,
+                        contents.trim_end_matches('\n')
+                    ));
+                }
+                (files.join(" "), text.join("\n\n"))
+            }
+        };
+        let text = if line_numbers {
+            text.lines()
+                .enumerate()
+                .map(|(i, line)| format!("{:6}  {}", i + 1, line))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            text
+        };
+
+        // Not on screen: nothing to page
+        if state.output_piped {
+            return Ok(text);
+        }
+        state.requested_pager = Some(PagerRequest { title, text });
+        Ok(String::new())
+    }
+}
+
+/// Contents of `file`, invalid UTF-8 replaced
+fn read(file: &str, state: &TerminalState) -> Result<String> {
+    let path = state.resolve_path(file);
+    if path.is_dir() {
+        return Err(anyhow!("less: {}: Is a directory", file));
+    }
+    let bytes =
+        fs::read(&path).map_err(|_| anyhow!("less: {}: No such file or directory", file))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, run_stdin, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_less() {
+        let fixture = Fixture::new("less")
+            .file("notes.txt", "one\ntwo\n")
+            .file("todo.txt", "three");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+
+        assert_eq!(run(&LessCommand, &["notes.txt"], &mut state).unwrap(), "");
+        let request = state.requested_pager.take().unwrap();
+        assert_eq!(
+            (request.title.as_str(), request.text.as_str()),
+            ("notes.txt", "one\ntwo\n")
+        );
+
+        run(&LessCommand, &["-N", "notes.txt", "todo.txt"], &mut state).unwrap();
+        assert_eq!(
+            state.requested_pager.take().unwrap().text,
+            "     1  ==> notes.txt <==\n     2  one\n     3  two\n     4  \n     5  ==> todo.txt <==\n     6  three"
+        );
+
+        run_stdin(&LessCommand, &[], "piped", &mut state).unwrap();
+        assert_eq!(
+            state.requested_pager.take().unwrap().title,
+            "(standard input)"
+        );
+
+        // Into a pipe, the text passes through
+        state.output_piped = true;
+        assert_eq!(
+            run(&LessCommand, &["todo.txt"], &mut state).unwrap(),
+            "three"
+        );
+        assert!(state.requested_pager.is_none());
+
+        assert!(run(&LessCommand, &["missing.txt"], &mut state).is_err());
+        assert!(run(&LessCommand, &[], &mut state).is_err());
+    }
+}
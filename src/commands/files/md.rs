@@ -0,0 +1,100 @@
+//! md command - render markdown files
+
+use std::fs;
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::terminal::markdown;
+use crate::terminal::state::TerminalState;
+
+pub struct MdCommand;
+
+impl Command for MdCommand {
+    fn name(&self) -> &'static str {
+        "md"
+    }
+
+    fn description(&self) -> &'static str {
+        "Render markdown in the terminal"
+    }
+
+    fn usage(&self) -> &'static str {
+        "md [file...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"md - Render markdown in the terminal
+
+USAGE:
+  md [file...]
+  command | md
+
+DESCRIPTION:
+  Renders markdown instead of printing the raw markup: headings,
+  bold/italic, lists and task lists, quotes, tables, fenced code
+  blocks with syntax highlighting, and clickable links.
+  Without a file, renders piped input or the README.md in the
+  current directory.
+
+EXAMPLES:
+  md                     Render ./README.md
+  md CHANGELOG.md        Render a file
+  md docs/*.md           Render several files
+  curl -s URL | md       Render piped markdown
+
+RELATED COMMANDS:
+  cat      Print file contents
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(self.extended_help());
+        }
+
+        if args.is_empty() {
+            if let Some(input) = stdin {
+                return Ok(markdown::render(input));
+            }
+        }
+
+        let files: Vec<&str> = if args.is_empty() {
+            vec!["README.md"]
+        } else {
+            args.iter().map(String::as_str).collect()
+        };
+
+        let mut rendered = Vec::new();
+        for file in files {
+            let path = state.resolve_path(file);
+            if path.is_dir() {
+                return Err(anyhow::anyhow!("md: {}: Is a directory", file));
+            }
+            let contents = fs::read_to_string(&path).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    anyhow::anyhow!("md: {}: No such file or directory", file)
+                } else {
+                    anyhow::anyhow!("md: {}: {}", file, e)
+                }
+            })?;
+            rendered.push(markdown::render(&contents));
+        }
+
+        Ok(rendered.join("\n\n"))
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
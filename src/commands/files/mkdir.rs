@@ -98,3 +98,19 @@ RELATED COMMANDS:
         Ok(String::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_mkdir_existing() {
+        let fixture = Fixture::new("mkdir").dir("existing");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        assert!(run(&MkdirCommand, &["existing"], &mut state).is_err());
+        run(&MkdirCommand, &["-p", "existing", "new"], &mut state).unwrap();
+        assert!(fixture.path().join("new").is_dir());
+        assert!(run(&MkdirCommand, &[], &mut state).is_err());
+    }
+}
@@ -32,11 +32,9 @@ impl Command for MktempCommand {
         while i < args.len() {
             match args[i].as_str() {
                 "-d" | "--directory" => make_dir = true,
-                "-p" | "--tmpdir" => {
-                    if i + 1 < args.len() {
-                        base_dir = Some(&args[i + 1]);
-                        i += 1;
-                    }
+                "-p" | "--tmpdir" if i + 1 < args.len() => {
+                    base_dir = Some(&args[i + 1]);
+                    i += 1;
                 }
                 arg if !arg.starts_with('-') => template = Some(arg),
                 _ => {}
@@ -1,7 +1,7 @@
 //! File operation commands
 //!
-//! cat, touch, rm, mkdir, cp, mv, ln, stat, file, basename, dirname, realpath
-//! chmod, readlink, mktemp, nano, vim, vi, edit
+//! cat, less, more, md, touch, rm, mkdir, cp, mv, ln, stat, file, basename, dirname, realpath
+//! chmod, readlink, mktemp, nano, vim, vi, edit, open, start
 
 mod basename;
 mod cat;
@@ -9,11 +9,14 @@ mod chmod;
 mod cp;
 mod dirname;
 mod file_type;
+mod less;
 mod ln;
+mod md;
 mod mkdir;
 mod mktemp;
 mod mv;
 pub mod nano;
+mod open;
 mod readlink;
 mod realpath;
 mod rm;
@@ -26,11 +29,14 @@ pub use chmod::ChmodCommand;
 pub use cp::CpCommand;
 pub use dirname::DirnameCommand;
 pub use file_type::FileCommand;
+pub use less::LessCommand;
 pub use ln::LnCommand;
+pub use md::MdCommand;
 pub use mkdir::MkdirCommand;
 pub use mktemp::MktempCommand;
 pub use mv::MvCommand;
 pub use nano::{EditCommand, EditorState, NanoCommand, ViCommand, VimCommand};
+pub use open::OpenCommand;
 pub use readlink::ReadlinkCommand;
 pub use realpath::RealpathCommand;
 pub use rm::RmCommand;
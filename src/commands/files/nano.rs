@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use anyhow::Result;
 
 use crate::commands::traits::Command;
+use crate::git::conflicts::{self, Conflict, Side};
 use crate::terminal::state::TerminalState;
 
 /// Editor state for nano command
@@ -44,7 +45,7 @@ impl EditorState {
             String::new()
         };
 
-        Ok(Self {
+        let mut editor = Self {
             file_path: path,
             content,
             modified: false,
@@ -54,7 +55,12 @@ impl EditorState {
             status: String::new(),
             scroll_offset: 0,
             visible_lines: 30,
-        })
+        };
+        // Start at the first merge conflict, if there is one
+        if !editor.conflicts().is_empty() {
+            editor.next_conflict();
+        }
+        Ok(editor)
     }
 
     /// Save the file
@@ -161,6 +167,78 @@ impl EditorState {
             self.cursor_col = line.len();
         }
     }
+
+    /// Merge conflict regions in the file
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        conflicts::find_conflicts(&self.content)
+    }
+
+    /// Jump to the next merge conflict (wrapping around)
+    pub fn next_conflict(&mut self) {
+        let conflicts = self.conflicts();
+        let index = conflicts
+            .iter()
+            .position(|c| c.start > self.cursor_line)
+            .unwrap_or(0);
+        self.jump_to_conflict(&conflicts, index);
+    }
+
+    /// Jump to the previous merge conflict (wrapping around)
+    pub fn prev_conflict(&mut self) {
+        let conflicts = self.conflicts();
+        let index = conflicts
+            .iter()
+            .rposition(|c| c.start < self.cursor_line)
+            .unwrap_or(conflicts.len().saturating_sub(1));
+        self.jump_to_conflict(&conflicts, index);
+    }
+
+    fn jump_to_conflict(&mut self, conflicts: &[Conflict], index: usize) {
+        let Some(conflict) = conflicts.get(index) else {
+            self.status = "No conflicts".to_string();
+            return;
+        };
+        self.cursor_line = conflict.start;
+        self.cursor_col = 0;
+        // Keep the whole region in view when it fits
+        self.scroll_offset = conflict.start.saturating_sub(2);
+        self.ensure_cursor_visible();
+        self.status = format!("Conflict {} of {}", index + 1, conflicts.len());
+    }
+
+    /// Resolve the conflict under the cursor (or the next one) by keeping
+    /// one side, then move to the next conflict
+    pub fn take(&mut self, side: Side) {
+        let conflicts = self.conflicts();
+        let Some(conflict) = conflicts
+            .iter()
+            .find(|c| c.contains(self.cursor_line) || c.start > self.cursor_line)
+            .or(conflicts.first())
+        else {
+            self.status = "No conflicts".to_string();
+            return;
+        };
+
+        self.content = conflicts::resolve(&self.content, conflict, side);
+        self.modified = true;
+        self.cursor_line = conflict.start.min(self.line_count().saturating_sub(1));
+        self.cursor_col = 0;
+
+        let remaining = self.conflicts();
+        let kept = match side {
+            Side::Ours => "ours",
+            Side::Theirs => "theirs",
+            Side::Both => "both",
+        };
+        if remaining.is_empty() {
+            self.ensure_cursor_visible();
+            self.status = format!("Kept {} - all conflicts resolved, ^S to save", kept);
+        } else {
+            self.cursor_line = self.cursor_line.saturating_sub(1);
+            self.next_conflict();
+            self.status = format!("Kept {} - {} left. {}", kept, remaining.len(), self.status);
+        }
+    }
 }
 
 pub struct NanoCommand;
@@ -0,0 +1,174 @@
+//! open command - open files, folders, and URLs with their default app
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct OpenCommand;
+
+impl Command for OpenCommand {
+    fn name(&self) -> &'static str {
+        "open"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open files, folders, or URLs in their default app"
+    }
+
+    fn usage(&self) -> &'static str {
+        "open [--with <app>] [--reveal] <path|url>...\n\n\
+         Options:\n  \
+         -w, --with <app>  Open with a specific application\n  \
+         -R, --reveal      Show the item selected in Explorer instead of opening it\n\n\
+         Examples:\n  \
+         open .                      - Open the current folder in Explorer\n  \
+         open report.pdf             - Open in the default PDF viewer\n  \
+         open https://example.com    - Open in the browser\n  \
+         open --with code src        - Open a folder in VS Code\n  \
+         open --reveal Cargo.toml    - Show the file in its folder\n  \
+         start notes.txt             - Same as open"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut app: Option<&str> = None;
+        let mut reveal = false;
+        let mut targets = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-w" | "--with" => {
+                    app = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow!("open: --with needs an application"))?,
+                    );
+                }
+                "-R" | "--reveal" => reveal = true,
+                _ => targets.push(arg.as_str()),
+            }
+        }
+
+        if targets.is_empty() {
+            return Err(anyhow!("open: missing file, folder, or URL"));
+        }
+
+        let mut output = Vec::new();
+        for target in targets {
+            if is_url(target) {
+                if reveal {
+                    return Err(anyhow!("open: can't reveal a URL ({})", target));
+                }
+                let url = if target.starts_with("www.") {
+                    format!("https://{}", target)
+                } else {
+                    target.to_string()
+                };
+                launch(url.as_ref(), app).map_err(|e| anyhow!("open: {}: {}", target, e))?;
+                output.push(format!("🌐 Opened {}", url));
+                continue;
+            }
+
+            let path = state.resolve_path(target);
+            if !path.exists() {
+                return Err(anyhow!("open: {}: No such file or directory", target));
+            }
+
+            if reveal {
+                reveal_in_file_manager(&path).map_err(|e| anyhow!("open: {}: {}", target, e))?;
+                output.push(format!("📂 Revealed {}", path.display()));
+            } else {
+                launch(path.as_os_str(), app).map_err(|e| anyhow!("open: {}: {}", target, e))?;
+                match app {
+                    Some(app) => output.push(format!("📂 Opened {} with {}", path.display(), app)),
+                    None => output.push(format!("📂 Opened {}", path.display())),
+                }
+            }
+        }
+
+        Ok(output.join("\n"))
+    }
+}
+
+/// Whether the argument is a URL rather than a path
+fn is_url(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:") || target.starts_with("www.")
+}
+
+/// Launch without waiting for the application to exit
+fn launch(target: &std::ffi::OsStr, app: Option<&str>) -> std::io::Result<()> {
+    match app {
+        Some(app) => open::with_detached(target, app),
+        None => open::that_detached(target),
+    }
+}
+
+/// Show an item selected in the system file manager
+fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    let path = explorer_path(path);
+
+    #[cfg(windows)]
+    {
+        // explorer.exe exits with 1 even on success, so only spawn errors count
+        std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path.display()))
+            .spawn()
+            .map(|_| ())
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(&path)
+            .spawn()
+            .map(|_| ())
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        // No portable "select" on Linux - open the containing folder
+        let folder = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+        open::that_detached(folder)
+    }
+}
+
+/// Absolute path without the `\\?\` prefix Explorer doesn't understand
+fn explorer_path(path: &Path) -> std::path::PathBuf {
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let display = canonical.to_string_lossy();
+    match display.strip_prefix(r"\\?\") {
+        Some(stripped) => std::path::PathBuf::from(stripped),
+        None => canonical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com"));
+        assert!(is_url("mailto:me@example.com"));
+        assert!(is_url("www.rust-lang.org"));
+        assert!(!is_url("src/main.rs"));
+        assert!(!is_url(r"C:\Users\me\notes.txt"));
+    }
+
+    #[test]
+    fn test_missing_target() {
+        let mut state = TerminalState::new();
+        assert!(OpenCommand.execute(&[], &mut state).is_err());
+        let args = [
+            "--reveal".to_string(),
+            "definitely-not-here.txt".to_string(),
+        ];
+        assert!(OpenCommand.execute(&args, &mut state).is_err());
+    }
+}
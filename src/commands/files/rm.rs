@@ -122,3 +122,25 @@ RELATED COMMANDS:
         Ok(String::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_rm() {
+        let fixture = Fixture::new("rm")
+            .file("keep.txt", "")
+            .file("gone.txt", "")
+            .file("dir/nested.txt", "");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        run(&RmCommand, &["gone.txt"], &mut state).unwrap();
+        assert!(run(&RmCommand, &["dir"], &mut state).is_err());
+        run(&RmCommand, &["-r", "dir"], &mut state).unwrap();
+        assert!(!fixture.path().join("gone.txt").exists());
+        assert!(!fixture.path().join("dir").exists());
+        assert_eq!(fixture.read("keep.txt"), "");
+        assert!(fixture.path().join("keep.txt").exists());
+    }
+}
@@ -0,0 +1,133 @@
+//! ga command - stage files
+
+use anyhow::{anyhow, Result};
+use git2::{IndexAddOption, Repository};
+
+use super::{open_repo, repo_pathspecs, GREEN, RED, RESET};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GaCommand;
+
+impl Command for GaCommand {
+    fn name(&self) -> &'static str {
+        "ga"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stage files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "ga [path...]\n\n\
+         Stages new, changed, and deleted files under the given paths, or\n\
+         under the current directory when none are given.\n\n\
+         Examples:\n  \
+         ga                - Stage everything under the current directory\n  \
+         ga src/main.rs    - Stage one file\n  \
+         ga src docs       - Stage two directories"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("ga", state)?;
+        let specs = repo_pathspecs(&repo, args, state);
+        let (added, removed) = stage(&repo, &specs)?;
+
+        if added.is_empty() && removed.is_empty() {
+            return Ok("Nothing to stage".to_string());
+        }
+        let lines = added
+            .iter()
+            .map(|path| format!("{}+{} {}", GREEN, RESET, path))
+            .chain(
+                removed
+                    .iter()
+                    .map(|path| format!("{}-{} {}", RED, RESET, path)),
+            );
+        Ok(lines.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// Stage everything matching `specs`: returns the paths added or updated
+/// and the deleted paths removed from the index
+pub(super) fn stage(repo: &Repository, specs: &[String]) -> Result<(Vec<String>, Vec<String>)> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow!("ga: a bare repository has no working tree"))?
+        .to_path_buf();
+    let mut index = repo.index()?;
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+
+    index.update_all(
+        specs,
+        Some(&mut |path: &std::path::Path, _: &[u8]| {
+            let name = path.to_string_lossy().to_string();
+            if workdir.join(path).exists() {
+                added.push(name);
+            } else {
+                removed.push(name);
+            }
+            0
+        }),
+    )?;
+    index.add_all(
+        specs,
+        IndexAddOption::DEFAULT,
+        Some(&mut |path: &std::path::Path, _: &[u8]| {
+            let name = path.to_string_lossy().to_string();
+            if !added.contains(&name) {
+                added.push(name);
+            }
+            0
+        }),
+    )?;
+    index.write()?;
+
+    added.sort();
+    removed.sort();
+    Ok((added, removed))
+}
+
+/// Stage changes to tracked files anywhere in the repository (`gc -a`)
+pub(super) fn stage_tracked(repo: &Repository) -> Result<()> {
+    let mut index = repo.index()?;
+    index.update_all(["*"], None)?;
+    Ok(index.write()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+    use std::path::Path;
+
+    #[test]
+    fn test_stage() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-ga-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        std::fs::create_dir(dir.join("src")).unwrap();
+        std::fs::write(dir.join("gone.txt"), "x").unwrap();
+        std::fs::write(dir.join("top.txt"), "x").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("gone.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::remove_file(dir.join("gone.txt")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "fn main() {}").unwrap();
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.join("src"));
+        let output = GaCommand.execute(&[], &mut state).unwrap();
+        assert_eq!(strip_ansi(&output), "+ src/lib.rs");
+
+        state.set_cwd(dir.clone());
+        let output = GaCommand.execute(&[], &mut state).unwrap();
+        assert_eq!(strip_ansi(&output), "+ top.txt\n- gone.txt");
+        assert_eq!(
+            GaCommand.execute(&[], &mut state).unwrap(),
+            "Nothing to stage"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,264 @@
+//! gc command - commit staged changes
+//!
+//! With a message (`gc -m "..."` or just `gc fix the build`) it commits right
+//! away. Without one it writes a message template to `.git/COMMIT_EDITMSG`
+//! and the app opens it in the editor; closing the editor runs
+//! `gc -F <file>` to commit with what was written there.
+
+use anyhow::{anyhow, Result};
+use git2::{DiffStatsFormat, Repository};
+
+use super::{open_repo, short_id, GREEN, RESET};
+use crate::commands::traits::Command;
+use crate::git::panel::{changes, Section};
+use crate::terminal::state::TerminalState;
+
+pub struct GcCommand;
+
+impl Command for GcCommand {
+    fn name(&self) -> &'static str {
+        "gc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Commit staged changes"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gc [-a] [--amend] [-m message | -F file | message...]\n\n\
+         Commits what is staged. Without a message the editor opens on a\n\
+         template listing the staged files; the commit is made when the\n\
+         editor closes (save first; an empty message cancels it).\n\n\
+         Options:\n  \
+         -m <message>   Commit message\n  \
+         -F <file>      Read the message from a file ('#' lines are skipped)\n  \
+         -a             Stage changes to tracked files first\n  \
+         --amend        Replace the last commit (keeps its message unless given)\n\n\
+         Examples:\n  \
+         gc                       - Write the message in the editor\n  \
+         gc -m \"Fix the build\"\n  \
+         gc fix typo in readme    - Words after gc are the message\n  \
+         gc -a -m \"WIP\"\n  \
+         gc --amend               - Add staged changes to the last commit"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut message: Option<String> = None;
+        let mut file: Option<String> = None;
+        let mut all = false;
+        let mut amend = false;
+        let mut words = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-m" | "--message" => {
+                    message = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow!("gc: option -m needs a message"))?
+                            .clone(),
+                    );
+                }
+                "-F" | "--file" => {
+                    file = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow!("gc: option -F needs a file"))?
+                            .clone(),
+                    );
+                }
+                "-a" | "--all" => all = true,
+                "--amend" => amend = true,
+                option if option.starts_with('-') && words.is_empty() => {
+                    return Err(anyhow!("gc: unknown option '{}'", option))
+                }
+                word => words.push(word.to_string()),
+            }
+        }
+        if message.is_none() && !words.is_empty() {
+            message = Some(words.join(" "));
+        }
+
+        let repo = open_repo("gc", state)?;
+        if all {
+            super::ga::stage_tracked(&repo)?;
+        }
+
+        if let Some(file) = file {
+            // Joined rather than resolved: the app passes an absolute path
+            let path = state.cwd().join(&file);
+            let text =
+                std::fs::read_to_string(&path).map_err(|e| anyhow!("gc: {}: {}", file, e))?;
+            message = Some(strip_comments(&text));
+        }
+        let message = match message {
+            Some(message) if message.trim().is_empty() => {
+                return Ok("Commit cancelled (empty message)".to_string())
+            }
+            Some(message) => Some(message),
+            None if amend => None,
+            None => {
+                let template = template(&repo)?;
+                let path = repo.path().join("COMMIT_EDITMSG");
+                std::fs::write(&path, template)?;
+                // The app opens the editor and runs `gc -F` when it closes
+                return Ok(format!("\x1b[COMMIT]{}", path.display()));
+            }
+        };
+        commit(&repo, message.as_deref(), amend)
+    }
+}
+
+/// Message lines without `#` comments, trimmed
+fn strip_comments(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Editor contents for a new commit: a blank line for the message, then
+/// what is about to be committed as comments
+fn template(repo: &Repository) -> Result<String> {
+    let staged: Vec<_> = changes(repo)?
+        .into_iter()
+        .filter(|c| c.section == Section::Staged)
+        .collect();
+    if staged.is_empty() {
+        return Err(anyhow!("gc: nothing staged (ga stages files)"));
+    }
+    let branch = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(str::to_string))
+        .unwrap_or_else(|| "HEAD".to_string());
+    let mut text = String::from(
+        "\n\
+         # Write the commit message above. Lines starting with '#' are\n\
+         # skipped, and an empty message cancels the commit.\n\
+         #\n",
+    );
+    text.push_str(&format!(
+        "# On branch {}\n# Changes to be committed:\n",
+        branch
+    ));
+    for change in staged {
+        text.push_str(&format!("#   {}  {}\n", change.status, change.path));
+    }
+    Ok(text)
+}
+
+/// Commit the index (or amend HEAD with it) and describe the result
+fn commit(repo: &Repository, message: Option<&str>, amend: bool) -> Result<String> {
+    let signature = repo.signature().map_err(|_| {
+        anyhow!(
+            "gc: who are you? Set user.name and user.email, e.g.\n  \
+             git config --global user.name \"Your Name\"\n  \
+             git config --global user.email you@example.com"
+        )
+    })?;
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+
+    let id = if amend {
+        let head = head.ok_or_else(|| anyhow!("gc: nothing to amend yet"))?;
+        head.amend(
+            Some("HEAD"),
+            None,
+            Some(&signature),
+            None,
+            message,
+            Some(&tree),
+        )?
+    } else {
+        if head.as_ref().map(|h| h.tree_id()) == Some(tree.id())
+            || (head.is_none() && tree.is_empty())
+        {
+            return Err(anyhow!("gc: nothing staged (ga stages files)"));
+        }
+        let parents: Vec<_> = head.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message.unwrap_or_default(),
+            &tree,
+            &parents,
+        )?
+    };
+
+    let commit = repo.find_commit(id)?;
+    let parent_tree = commit.parent(0).ok().map(|p| p.tree()).transpose()?;
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    let stats = diff.stats()?.to_buf(DiffStatsFormat::SHORT, 80)?;
+    let branch = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(str::to_string))
+        .unwrap_or_else(|| "HEAD".to_string());
+    Ok(format!(
+        "[{} {}{}{}] {}\n{}",
+        branch,
+        GREEN,
+        short_id(id),
+        RESET,
+        commit.summary().unwrap_or_default(),
+        stats.as_str().unwrap_or_default().trim_end()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+
+    fn run(args: &[&str], state: &mut TerminalState) -> Result<String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        GcCommand.execute(&args, state)
+    }
+
+    #[test]
+    fn test_commit() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gc-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+
+        assert!(run(&[], &mut state).is_err());
+        std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+        super::super::ga::stage(&repo, &["*".to_string()]).unwrap();
+
+        // No message: the template is written for the editor
+        let marker = run(&[], &mut state).unwrap();
+        let path = marker.strip_prefix("\x1b[COMMIT]").unwrap().to_string();
+        let template = std::fs::read_to_string(&path).unwrap();
+        assert!(template.contains("#   A  a.txt"));
+        assert_eq!(
+            run(&["-F", &path], &mut state).unwrap(),
+            "Commit cancelled (empty message)"
+        );
+
+        std::fs::write(&path, format!("Add a\n\nBody text\n{}", template)).unwrap();
+        let output = strip_ansi(&run(&["-F", &path], &mut state).unwrap());
+        assert!(output.ends_with("] Add a\n 1 file changed, 1 insertion(+)"));
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("Add a\n\nBody text"));
+        assert!(run(&["-m", "again"], &mut state).is_err());
+
+        // -a stages tracked changes; bare words are the message
+        std::fs::write(dir.join("a.txt"), "b\n").unwrap();
+        run(&["-a", "change", "a"], &mut state).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("change a"));
+
+        run(&["--amend", "-m", "Change a"], &mut state).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("Change a"));
+        assert_eq!(head.parent_count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+//! gconflicts command - list conflicted files and open them for resolving
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use git2::RepositoryState;
+
+use super::{open_repo, DIM, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::git::conflicts::find_conflicts;
+use crate::terminal::state::TerminalState;
+
+pub struct GconflictsCommand;
+
+impl Command for GconflictsCommand {
+    fn name(&self) -> &'static str {
+        "gconflicts"
+    }
+
+    fn description(&self) -> &'static str {
+        "List merge conflicts and resolve them in the editor"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gconflicts [n|file]\n\n\
+         Lists files with unresolved merge/rebase conflicts. Opening one\n\
+         highlights each conflict in the editor:\n  \
+         ^N / ^P   next / previous conflict\n  \
+         ^O ^T ^B  keep ours / theirs / both for the conflict at the cursor\n\n\
+         Examples:\n  \
+         gconflicts            - List conflicted files\n  \
+         gconflicts 1          - Open the first one\n  \
+         gconflicts src/app.rs - Open a file by name"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("gconflicts", state)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("gconflicts: bare repository"))?
+            .to_path_buf();
+
+        let mut files: Vec<String> = Vec::new();
+        for conflict in repo.index()?.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict
+                .our
+                .or(conflict.their)
+                .or(conflict.ancestor)
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string());
+            if let Some(path) = entry {
+                files.push(path);
+            }
+        }
+
+        if files.is_empty() {
+            return Ok("✓ No conflicts.".to_string());
+        }
+
+        if let Some(query) = args.first() {
+            let file = pick(&files, query)
+                .ok_or_else(|| anyhow!("gconflicts: no conflicted file matches '{}'", query))?;
+            let path: PathBuf = workdir.join(file);
+            return Ok(format!("\x1b[EDIT]{}", path.display()));
+        }
+
+        let operation = match repo.state() {
+            RepositoryState::Merge => "Merge",
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => "Rebase",
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => "Cherry-pick",
+            RepositoryState::Revert | RepositoryState::RevertSequence => "Revert",
+            _ => "Operation",
+        };
+        let mut lines = vec![format!(
+            "⚔ {} in progress - {} conflicted file{}",
+            operation,
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        )];
+
+        for (i, file) in files.iter().enumerate() {
+            let detail = match std::fs::read_to_string(workdir.join(file)) {
+                Ok(content) => match find_conflicts(&content).len() {
+                    0 => "no markers left - git add to mark resolved".to_string(),
+                    1 => "1 conflict".to_string(),
+                    n => format!("{} conflicts", n),
+                },
+                Err(_) => "deleted on one side".to_string(),
+            };
+            lines.push(format!(
+                "  {:>2}  {}{}{}  {}{}{}",
+                i + 1,
+                YELLOW,
+                file,
+                RESET,
+                DIM,
+                detail,
+                RESET
+            ));
+        }
+        lines.push(format!(
+            "{}gconflicts <n|file> opens one in the editor{}",
+            DIM, RESET
+        ));
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Pick a file by its 1-based number, exact path, or part of its path
+fn pick<'a>(files: &'a [String], query: &str) -> Option<&'a str> {
+    if let Ok(n) = query.parse::<usize>() {
+        return n
+            .checked_sub(1)
+            .and_then(|i| files.get(i))
+            .map(|f| f.as_str());
+    }
+    let query = query.replace('\\', "/");
+    files
+        .iter()
+        .find(|f| **f == query)
+        .or_else(|| files.iter().find(|f| f.contains(&query)))
+        .map(|f| f.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick() {
+        let files = vec!["src/app.rs".to_string(), "src/main.rs".to_string()];
+        assert_eq!(pick(&files, "2"), Some("src/main.rs"));
+        assert_eq!(pick(&files, "0"), None);
+        assert_eq!(pick(&files, "src\\app.rs"), Some("src/app.rs"));
+        assert_eq!(pick(&files, "main"), Some("src/main.rs"));
+        assert_eq!(pick(&files, "lib.rs"), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! gd command - colored git diff
+
+use anyhow::{anyhow, Result};
+use git2::{Diff, DiffFormat, DiffOptions, DiffStatsFormat};
+
+use super::{open_repo, repo_pathspecs, BOLD, CYAN, DIM, GREEN, RED, RESET};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+use crate::terminal::syntax::DiffHighlighter;
+
+pub struct GdCommand;
+
+impl Command for GdCommand {
+    fn name(&self) -> &'static str {
+        "gd"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show a colored git diff"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gd [--staged] [--stat] [path...]\n\n\
+         Shows unstaged changes (working tree against the index), or with\n\
+         --staged the changes that the next commit will contain.\n\n\
+         Options:\n  \
+         --staged, --cached   Diff the index against HEAD\n  \
+         --stat               Files changed and lines added/removed only\n\n\
+         Examples:\n  \
+         gd                   - Everything not yet staged\n  \
+         gd --staged          - What gc would commit\n  \
+         gd src/main.rs       - One file\n  \
+         gd --stat            - Summary per file"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut staged = false;
+        let mut stat = false;
+        let mut paths = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "--staged" | "--cached" => staged = true,
+                "--stat" => stat = true,
+                option if option.starts_with('-') => {
+                    return Err(anyhow!("gd: unknown option '{}'", option))
+                }
+                path => paths.push(path.to_string()),
+            }
+        }
+
+        let repo = open_repo("gd", state)?;
+        let mut options = DiffOptions::new();
+        if !paths.is_empty() {
+            for spec in repo_pathspecs(&repo, &paths, state) {
+                options.pathspec(spec);
+            }
+        }
+        let diff = if staged {
+            let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+            repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?
+        } else {
+            repo.diff_index_to_workdir(None, Some(&mut options))?
+        };
+
+        if diff.deltas().len() == 0 {
+            return Ok(if staged {
+                "No staged changes".to_string()
+            } else {
+                "No unstaged changes (gd --staged shows staged ones)".to_string()
+            });
+        }
+        if stat {
+            let stats = diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?;
+            return Ok(color_stat(stats.as_str().unwrap_or_default()));
+        }
+        render(&diff)
+    }
+}
+
+/// The patch, colored like `git diff`, with the code in each file's
+/// language colors when it has a grammar
+fn render(diff: &Diff) -> Result<String> {
+    let mut lines = Vec::new();
+    let mut code: Option<DiffHighlighter> = None;
+    diff.print(DiffFormat::Patch, |delta, _, line| {
+        let content = String::from_utf8_lossy(line.content());
+        let content = content.trim_end_matches(['\n', '\r']);
+        match (line.origin(), code.as_mut()) {
+            // File headers come as one block of lines
+            ('F', _) => {
+                code = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .and_then(DiffHighlighter::for_path);
+                lines.extend(content.lines().map(|l| format!("{}{}{}", BOLD, l, RESET)))
+            }
+            ('H', _) => lines.push(format!("{}{}{}", CYAN, content, RESET)),
+            ('+', Some(code)) => {
+                lines.push(format!("{}+{}{}", GREEN, RESET, code.line('+', content)))
+            }
+            ('-', Some(code)) => {
+                lines.push(format!("{}-{}{}", RED, RESET, code.line('-', content)))
+            }
+            (' ', Some(code)) => lines.push(format!(" {}", code.line(' ', content))),
+            ('+', None) => lines.push(format!("{}+{}{}", GREEN, content, RESET)),
+            ('-', None) => lines.push(format!("{}-{}{}", RED, content, RESET)),
+            (' ', None) => lines.push(format!(" {}", content)),
+            // "\ No newline at end of file" and binary notices
+            _ => lines.push(format!("{}{}{}", DIM, content.trim_start(), RESET)),
+        }
+        true
+    })?;
+    Ok(lines.join("\n"))
+}
+
+/// Color the `+` and `-` bars of a diffstat
+fn color_stat(stat: &str) -> String {
+    stat.trim_end()
+        .lines()
+        .map(|line| match line.split_once('|') {
+            Some((file, bars)) => {
+                let bars = bars
+                    .replace('+', &format!("{}+{}", GREEN, RESET))
+                    .replace('-', &format!("{}-{}", RED, RESET));
+                format!("{}|{}", file, bars)
+            }
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+    use std::path::Path;
+
+    #[test]
+    fn test_diff() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gd-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = git2::Repository::init(&dir).unwrap();
+        std::fs::create_dir(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "b\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("src/a.txt")).unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.join("src/a.txt"), "one\n2\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "b!\n").unwrap();
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.join("src"));
+        let diff = GdCommand.execute(&["a.txt".into()], &mut state).unwrap();
+        assert!(diff.contains("\x1b[1mdiff --git a/src/a.txt b/src/a.txt\x1b[0m"));
+        assert!(diff.contains("\x1b[31m-two\x1b[0m\n\x1b[32m+2\x1b[0m"));
+        assert!(!diff.contains("b.txt"));
+
+        // Both files were added to the index, so all of them is staged
+        let staged = GdCommand.execute(&["--staged".into()], &mut state).unwrap();
+        assert!(strip_ansi(&staged).contains("+b\n"));
+        let stat = GdCommand.execute(&["--stat".into()], &mut state).unwrap();
+        assert!(strip_ansi(&stat).contains("2 files changed, 2 insertions(+), 2 deletions(-)"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
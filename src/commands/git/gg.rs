@@ -0,0 +1,379 @@
+//! gg command - commit graph in the theme's colors
+//!
+//! Like gl, but drawn with box-drawing characters and each lane in its own
+//! color from the active theme. Short hashes are plain hex, so clicking one
+//! in the output, or picking it in hints mode (Ctrl+Shift+H, Enter), copies
+//! it.
+
+use anyhow::{anyhow, Result};
+use egui::Color32;
+use git2::{Oid, Repository};
+
+use super::graph::{ascii_edges, ascii_row, ref_labels, revwalk, Edges, Graph};
+use super::{open_repo, short_id, RESET};
+use crate::ai::cache::format_age;
+use crate::commands::traits::Command;
+use crate::config::theme::Theme;
+use crate::terminal::state::TerminalState;
+
+pub struct GgCommand;
+
+/// Commits shown without `-n`
+const DEFAULT_COUNT: usize = 30;
+
+impl Command for GgCommand {
+    fn name(&self) -> &'static str {
+        "gg"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the commit graph"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gg [-n count] [--all] [--ascii]\n\n\
+         Draws the commit graph with each branch line in a color from the\n\
+         current theme, then each commit's hash, branches and tags, message,\n\
+         author, and age. Click a hash (or pick it in hints mode) to copy it.\n\
+         Also available as `git graph`.\n\n\
+         Options:\n  \
+         -n <count>   Number of commits (default: 30)\n  \
+         --all        Include every branch, not just HEAD\n  \
+         --ascii      Draw with | / \\ * instead of box-drawing characters\n\n\
+         Examples:\n  \
+         gg             - The last 30 commits on this branch\n  \
+         gg --all       - How all branches fork and merge\n  \
+         git graph -n 100"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut count = DEFAULT_COUNT;
+        let mut all = false;
+        let mut ascii = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-n" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| anyhow!("gg: option -n needs a value"))?;
+                    count = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| anyhow!("gg: invalid count '{}'", value))?;
+                }
+                "--all" => all = true,
+                "--ascii" => ascii = true,
+                other => {
+                    return Err(anyhow!(
+                        "gg: unknown option '{}'. Usage: gg [-n count] [--all] [--ascii]",
+                        other
+                    ))
+                }
+            }
+        }
+
+        let repo = open_repo("gg", state)?;
+        let theme = Theme::from_name(state.current_theme);
+        graph(&repo, count, all, ascii, &theme)
+    }
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+/// Lane colors, cycled left to right
+fn lane_colors(theme: &Theme) -> [Color32; 8] {
+    [
+        theme.branch_color,
+        theme.accent,
+        theme.success_color,
+        theme.warning_color,
+        theme.info_color,
+        theme.accent_secondary,
+        theme.link_color,
+        theme.string_color,
+    ]
+}
+
+fn graph(repo: &Repository, count: usize, all: bool, ascii: bool, theme: &Theme) -> Result<String> {
+    let Some(walk) = revwalk(repo, all)? else {
+        return Ok("No commits yet".to_string());
+    };
+
+    let colors = lane_colors(theme);
+    let labels = ref_labels(repo)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut graph = Graph::default();
+    let mut lines = Vec::new();
+    // Edge rows after the last commit shown lead nowhere
+    let mut end = 0;
+    for oid in walk.take(count) {
+        let commit = repo.find_commit(oid?)?;
+        let parents: Vec<Oid> = commit.parent_ids().collect();
+        let step = graph.add(commit.id(), &parents);
+        if let Some(joins) = &step.joins {
+            lines.push(if ascii {
+                color_ascii(&ascii_edges(joins, '/'), &colors)
+            } else {
+                box_edges(joins, true, &colors)
+            });
+        }
+
+        let row = if ascii {
+            color_ascii(&ascii_row(step.column, &step.busy), &colors)
+        } else {
+            box_row(step.column, &step.busy, &colors)
+        };
+        let mut line = format!(
+            "{} {}{}{} ",
+            row.trim_end(),
+            fg(theme.warning_color),
+            short_id(commit.id()),
+            RESET
+        );
+        if let Some(labels) = labels.get(&commit.id()) {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|label| match label.strip_prefix("HEAD -> ") {
+                    Some(branch) => format!(
+                        "{}HEAD -> {}{}{}",
+                        fg(theme.accent),
+                        fg(theme.success_color),
+                        branch,
+                        RESET
+                    ),
+                    None if label == "HEAD" || label.starts_with("tag: ") => {
+                        format!("{}{}{}", fg(theme.accent), label, RESET)
+                    }
+                    None => format!("{}{}{}", fg(theme.branch_color), label, RESET),
+                })
+                .collect();
+            line.push_str(&format!("({}) ", labels.join(", ")));
+        }
+        let age = (now - commit.time().seconds()).max(0) as u64;
+        line.push_str(&format!(
+            "{} {}{}{} {}{}{}",
+            commit.summary().unwrap_or_default(),
+            fg(theme.info_color),
+            commit.author().name().unwrap_or("unknown"),
+            RESET,
+            fg(theme.comment_color),
+            format_age(age),
+            RESET
+        ));
+        lines.push(line);
+        end = lines.len();
+        if let Some(forks) = &step.forks {
+            lines.push(if ascii {
+                color_ascii(&ascii_edges(forks, '\\'), &colors)
+            } else {
+                box_edges(forks, false, &colors)
+            });
+        }
+    }
+    lines.truncate(end);
+    Ok(lines.join("\n"))
+}
+
+/// Color an ASCII graph row: each lane takes two characters, and a `/` or
+/// `\` just left of a lane belongs to it
+fn color_ascii(row: &str, colors: &[Color32]) -> String {
+    let cells: Vec<(char, Option<Color32>)> = row
+        .chars()
+        .enumerate()
+        .map(|(i, c)| (c, (c != ' ').then(|| colors[i.div_ceil(2) % colors.len()])))
+        .collect();
+    paint(&cells)
+}
+
+/// The commit's row: `●` in its column, `│` in every other busy lane
+fn box_row(column: usize, busy: &[bool], colors: &[Color32]) -> String {
+    let mut cells = Vec::new();
+    for (i, busy) in busy.iter().enumerate() {
+        let lane = (i == column || *busy).then(|| colors[i % colors.len()]);
+        cells.push((
+            match (i == column, busy) {
+                (true, _) => '●',
+                (false, true) => '│',
+                (false, false) => ' ',
+            },
+            lane,
+        ));
+        cells.push((' ', None));
+    }
+    paint(&cells)
+}
+
+/// A connecting row: a horizontal line from the commit's column to each
+/// lane in `edges.lanes`, curving up into it for lanes that `join` the
+/// commit and down for lanes it opens
+fn box_edges(edges: &Edges, join: bool, colors: &[Color32]) -> String {
+    let color = |lane: usize| colors[lane % colors.len()];
+    let column = edges.column;
+    let low = edges
+        .lanes
+        .iter()
+        .copied()
+        .chain([column])
+        .min()
+        .unwrap_or(column);
+    let high = edges
+        .lanes
+        .iter()
+        .copied()
+        .chain([column])
+        .max()
+        .unwrap_or(column);
+    // The horizontal line at `i` takes the color of the lane it leads to
+    let target = |i: usize| {
+        let toward = if i > column {
+            edges.lanes.iter().filter(|&&lane| lane >= i).min()
+        } else {
+            edges.lanes.iter().filter(|&&lane| lane <= i).max()
+        };
+        color(toward.copied().unwrap_or(column))
+    };
+
+    let mut cells = Vec::new();
+    for (i, busy) in edges.busy.iter().enumerate() {
+        let cell = if i == column {
+            let (left, right) = (low < column, high > column);
+            let c = match (left, right) {
+                (true, true) => '┼',
+                (true, false) => '┤',
+                _ => '├',
+            };
+            (c, Some(color(column)))
+        } else if edges.lanes.contains(&i) {
+            let c = match (join, i > column) {
+                (true, true) => '╯',
+                (true, false) => '╰',
+                (false, true) => '╮',
+                (false, false) => '╭',
+            };
+            (c, Some(color(i)))
+        } else if low < i && i < high {
+            if *busy {
+                ('┼', Some(color(i)))
+            } else {
+                ('─', Some(target(i)))
+            }
+        } else if *busy {
+            ('│', Some(color(i)))
+        } else {
+            (' ', None)
+        };
+        cells.push(cell);
+        // The gap to the next lane is part of the line when it spans it
+        if low <= i && i < high {
+            let toward = if i >= column { i + 1 } else { i };
+            cells.push(('─', Some(target(toward))));
+        } else {
+            cells.push((' ', None));
+        }
+    }
+    paint(&cells)
+}
+
+/// Characters with their colors, trailing blanks dropped
+fn paint(cells: &[(char, Option<Color32>)]) -> String {
+    let end = cells
+        .iter()
+        .rposition(|(c, _)| *c != ' ')
+        .map_or(0, |i| i + 1);
+    let mut out = String::new();
+    let mut current = None;
+    for &(c, color) in &cells[..end] {
+        if color != current {
+            out.push_str(&match color {
+                Some(color) => fg(color),
+                None => RESET.to_string(),
+            });
+            current = color;
+        }
+        out.push(c);
+    }
+    if current.is_some() {
+        out.push_str(RESET);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+
+    #[test]
+    fn test_commit_graph() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gg-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let start = chrono::Utc::now().timestamp() - 3600;
+        let commit = |message: &str, minute: i64, parents: &[Oid], update: Option<&str>| {
+            let time = git2::Time::new(start + minute * 60, 0);
+            let signature = git2::Signature::new("Ada", "ada@example.com", &time).unwrap();
+            let parents: Vec<_> = parents
+                .iter()
+                .map(|p| repo.find_commit(*p).unwrap())
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(update, &signature, &signature, message, &tree, &parents)
+                .unwrap()
+        };
+        let root = commit("root", 0, &[], Some("HEAD"));
+        let main = commit("on main", 1, &[root], Some("HEAD"));
+        let side = commit("on side", 2, &[root], None);
+        repo.branch("side", &repo.find_commit(side).unwrap(), false)
+            .unwrap();
+        let merge = commit("merge side", 3, &[main, side], Some("HEAD"));
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+        let output = GgCommand.execute(&[], &mut state).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let first = strip_ansi(output.lines().next().unwrap());
+        assert_eq!(
+            first,
+            format!(
+                "● {} (HEAD -> {}) merge side Ada 57m ago",
+                short_id(merge),
+                branch
+            )
+        );
+        let graph: Vec<String> = strip_ansi(&output)
+            .lines()
+            .map(|line| {
+                line.split(|c: char| c.is_ascii_hexdigit())
+                    .next()
+                    .unwrap()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(graph, ["●", "├─╮", "│ ●", "● │", "├─╯", "●"]);
+
+        let ascii = GgCommand
+            .execute(&["--ascii".to_string()], &mut state)
+            .unwrap();
+        let graph: Vec<String> = strip_ansi(&ascii)
+            .lines()
+            .map(|line| {
+                line.split(|c: char| c.is_ascii_hexdigit())
+                    .next()
+                    .unwrap()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(graph, ["*", "|\\", "| *", "* |", "|/", "*"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
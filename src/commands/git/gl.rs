@@ -0,0 +1,190 @@
+//! gl command - git log as a graph
+
+use anyhow::{anyhow, Result};
+use git2::{Oid, Repository};
+
+use super::graph::{ascii_edges, ascii_row, ref_labels, revwalk, Graph};
+use super::{open_repo, short_id, CYAN, DIM, GREEN, RESET, YELLOW};
+use crate::ai::cache::format_age;
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GlCommand;
+
+/// Commits shown without `-n`
+const DEFAULT_COUNT: usize = 20;
+
+impl Command for GlCommand {
+    fn name(&self) -> &'static str {
+        "gl"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the git log as a graph"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gl [-n count] [--all]\n\n\
+         Shows recent commits with a branch graph, the branches and tags\n\
+         pointing at them, their author, and how long ago they were made.\n\n\
+         Options:\n  \
+         -n <count>   Number of commits (default: 20)\n  \
+         --all        Include every branch, not just HEAD\n\n\
+         Examples:\n  \
+         gl           - The last 20 commits on this branch\n  \
+         gl -n 50 --all"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut count = DEFAULT_COUNT;
+        let mut all = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-n" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| anyhow!("gl: option -n needs a value"))?;
+                    count = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| anyhow!("gl: invalid count '{}'", value))?;
+                }
+                "--all" => all = true,
+                other => {
+                    return Err(anyhow!(
+                        "gl: unknown option '{}'. Usage: gl [-n count] [--all]",
+                        other
+                    ))
+                }
+            }
+        }
+
+        let repo = open_repo("gl", state)?;
+        log(&repo, count, all)
+    }
+}
+
+fn log(repo: &Repository, count: usize, all: bool) -> Result<String> {
+    let Some(walk) = revwalk(repo, all)? else {
+        return Ok("No commits yet".to_string());
+    };
+
+    let labels = ref_labels(repo)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut graph = Graph::default();
+    let mut lines = Vec::new();
+    // Edge rows after the last commit shown lead nowhere
+    let mut end = 0;
+    for oid in walk.take(count) {
+        let commit = repo.find_commit(oid?)?;
+        let parents: Vec<Oid> = commit.parent_ids().collect();
+        let step = graph.add(commit.id(), &parents);
+        if let Some(joins) = &step.joins {
+            lines.push(ascii_edges(joins, '/'));
+        }
+
+        let mut line = format!(
+            "{}{}{}{} ",
+            ascii_row(step.column, &step.busy),
+            YELLOW,
+            short_id(commit.id()),
+            RESET
+        );
+        if let Some(labels) = labels.get(&commit.id()) {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|label| match label.strip_prefix("HEAD -> ") {
+                    Some(branch) => format!("HEAD -> {}{}{}", GREEN, branch, CYAN),
+                    None => label.clone(),
+                })
+                .collect();
+            line.push_str(&format!("{}({}){} ", CYAN, labels.join(", "), RESET));
+        }
+        let age = (now - commit.time().seconds()).max(0) as u64;
+        line.push_str(&format!(
+            "{} {}({}, {}){}",
+            commit.summary().unwrap_or_default(),
+            DIM,
+            commit.author().name().unwrap_or("unknown"),
+            format_age(age),
+            RESET
+        ));
+        lines.push(line);
+        end = lines.len();
+        if let Some(forks) = &step.forks {
+            lines.push(ascii_edges(forks, '\\'));
+        }
+    }
+    lines.truncate(end);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+
+    #[test]
+    fn test_graph_log() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gl-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let start = chrono::Utc::now().timestamp() - 3600;
+        let commit = |message: &str, minute: i64, parents: &[Oid], update: Option<&str>| {
+            let time = git2::Time::new(start + minute * 60, 0);
+            let signature = git2::Signature::new("Ada", "ada@example.com", &time).unwrap();
+            let parents: Vec<_> = parents
+                .iter()
+                .map(|p| repo.find_commit(*p).unwrap())
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(update, &signature, &signature, message, &tree, &parents)
+                .unwrap()
+        };
+        let root = commit("root", 0, &[], Some("HEAD"));
+        let main = commit("on main", 1, &[root], Some("HEAD"));
+        let side = commit("on side", 2, &[root], None);
+        repo.branch("side", &repo.find_commit(side).unwrap(), false)
+            .unwrap();
+        commit("merge side", 3, &[main, side], Some("HEAD"));
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+        let log = strip_ansi(&GlCommand.execute(&[], &mut state).unwrap());
+        let graph: Vec<String> = log
+            .lines()
+            .map(|line| match line.find(|c: char| c.is_ascii_hexdigit()) {
+                // Drop the hash and the author
+                Some(at) => {
+                    let (graph, rest) = line.split_at(at);
+                    let message = rest.split_once(' ').unwrap().1;
+                    format!("{}{}", graph, message.split(" (Ada").next().unwrap())
+                }
+                None => line.to_string(),
+            })
+            .collect();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        assert_eq!(
+            graph,
+            [
+                format!("* (HEAD -> {}) merge side", branch),
+                "|\\".to_string(),
+                "| * (side) on side".to_string(),
+                "* | on main".to_string(),
+                "|/".to_string(),
+                "* root".to_string(),
+            ]
+        );
+
+        let one = GlCommand
+            .execute(&["-n".into(), "1".into()], &mut state)
+            .unwrap();
+        assert_eq!(one.lines().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
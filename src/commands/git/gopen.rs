@@ -0,0 +1,120 @@
+//! gopen command - open the repository, a file, or a line on GitHub/GitLab
+
+use anyhow::{anyhow, Result};
+
+use super::{open_repo, repo_forge};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GopenCommand;
+
+impl Command for GopenCommand {
+    fn name(&self) -> &'static str {
+        "gopen"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open the repo, a file, or a line on GitHub/GitLab"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gopen [file[:line[-end]]]\n\n\
+         Links point at the current branch when it has been pushed, and at\n\
+         the current commit otherwise.\n\n\
+         Examples:\n  \
+         gopen                  - Open the repository page\n  \
+         gopen src/app.rs       - Open a file\n  \
+         gopen src/app.rs:120   - Open a file at a line\n  \
+         gopen Cargo.toml:10-20 - Highlight a range of lines"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("gopen", state)?;
+        let (_, forge) = repo_forge("gopen", &repo)?;
+
+        let Some(target) = args.first() else {
+            let url = forge.web_url();
+            open::that_detached(&url).map_err(|e| anyhow!("gopen: {}", e))?;
+            return Ok(format!("🌐 Opened {}", url));
+        };
+
+        let (file, lines) = split_lines(target);
+        let path = state.resolve_path(file);
+        if !path.exists() {
+            return Err(anyhow!("gopen: {}: No such file or directory", file));
+        }
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("gopen: bare repository"))?;
+        let path = std::fs::canonicalize(&path)?;
+        let workdir = std::fs::canonicalize(workdir)?;
+        let relative = path
+            .strip_prefix(&workdir)
+            .map_err(|_| anyhow!("gopen: {} is outside the repository", file))?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let url = forge.file_url(&web_ref(&repo)?, &relative, lines);
+        open::that_detached(&url).map_err(|e| anyhow!("gopen: {}", e))?;
+        Ok(format!("🌐 Opened {}", url))
+    }
+}
+
+/// Split `file:line` or `file:start-end` (anything else is all file name)
+fn split_lines(target: &str) -> (&str, Option<(usize, Option<usize>)>) {
+    let Some((file, spec)) = target.rsplit_once(':') else {
+        return (target, None);
+    };
+    let lines = match spec.split_once('-') {
+        Some((start, end)) => start
+            .parse()
+            .ok()
+            .zip(end.parse().ok())
+            .map(|(start, end)| (start, Some(end))),
+        None => spec.parse().ok().map(|line| (line, None)),
+    };
+    match lines {
+        Some(lines) if !file.is_empty() => (file, Some(lines)),
+        _ => (target, None),
+    }
+}
+
+/// Branch name if it exists on the remote, otherwise the HEAD commit
+fn web_ref(repo: &git2::Repository) -> Result<String> {
+    let head = repo.head()?;
+    if head.is_branch() {
+        let branch = git2::Branch::wrap(head);
+        if branch.upstream().is_ok() {
+            if let Some(name) = branch.name()? {
+                return Ok(name.to_string());
+            }
+        }
+        return branch
+            .get()
+            .target()
+            .map(|oid| oid.to_string())
+            .ok_or_else(|| anyhow!("gopen: HEAD has no commit"));
+    }
+    head.target()
+        .map(|oid| oid.to_string())
+        .ok_or_else(|| anyhow!("gopen: HEAD has no commit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines() {
+        assert_eq!(split_lines("src/app.rs"), ("src/app.rs", None));
+        assert_eq!(
+            split_lines("src/app.rs:12"),
+            ("src/app.rs", Some((12, None)))
+        );
+        assert_eq!(
+            split_lines("Cargo.toml:10-20"),
+            ("Cargo.toml", Some((10, Some(20))))
+        );
+        assert_eq!(split_lines(r"C:\repo\main.rs"), (r"C:\repo\main.rs", None));
+    }
+}
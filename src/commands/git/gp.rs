@@ -0,0 +1,465 @@
+//! gp and gpl commands - push and pull the current branch
+//!
+//! Both talk to the branch's upstream remote (origin, else the only remote,
+//! when there is none yet), authenticate with the ssh agent, default ssh
+//! keys, or git's credential helper, and report transfer progress while
+//! they run. `gpl` only fast-forwards; diverged branches are left for a
+//! merge or rebase.
+
+use std::cell::RefCell;
+
+use anyhow::{anyhow, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    Cred, CredentialType, DiffStatsFormat, FetchOptions, Oid, PushOptions, RemoteCallbacks,
+    Repository,
+};
+
+use super::{open_repo, short_id, DIM, GREEN, RESET, YELLOW};
+use crate::commands::context::{CommandContext, Interrupted};
+use crate::commands::traits::Command;
+use crate::terminal::format::format_size;
+use crate::terminal::state::TerminalState;
+
+pub struct GpCommand;
+pub struct GplCommand;
+
+/// Key files tried after the ssh agent, in `~/.ssh`
+const DEFAULT_KEYS: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+impl Command for GpCommand {
+    fn name(&self) -> &'static str {
+        "gp"
+    }
+
+    fn description(&self) -> &'static str {
+        "Push the current branch"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gp [-f] [remote] [branch]\n\n\
+         Pushes the current branch to its upstream. A branch without one is\n\
+         pushed to origin (or the only remote) under the same name, and\n\
+         that becomes its upstream.\n\n\
+         Options:\n  \
+         -f, --force   Overwrite the remote branch even if it has other commits\n\n\
+         Examples:\n  \
+         gp                  - Push to the upstream\n  \
+         gp upstream         - Push to another remote\n  \
+         gp origin release   - Push to origin/release\n  \
+         gp -f               - Force-push after a rebase"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let mut force = false;
+        let mut names = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "-f" | "--force" => force = true,
+                option if option.starts_with('-') => {
+                    return Err(anyhow!("gp: unknown option '{}'", option))
+                }
+                name => names.push(name),
+            }
+        }
+        if names.len() > 2 {
+            return Err(anyhow!(
+                "gp: too many arguments. Usage: gp [-f] [remote] [branch]"
+            ));
+        }
+
+        let repo = open_repo("gp", state)?;
+        let branch = current_branch("gp", &repo)?;
+        let upstream = Upstream::of(&repo, &branch);
+        let remote_name = match names.first() {
+            Some(name) => name.to_string(),
+            None => match &upstream {
+                Some(upstream) => upstream.remote.clone(),
+                None => default_remote("gp", &repo)?,
+            },
+        };
+        let target = match (names.get(1), &upstream) {
+            (Some(name), _) => format!("refs/heads/{}", name),
+            (None, Some(upstream)) if upstream.remote == remote_name => upstream.merge.clone(),
+            _ => format!("refs/heads/{}", branch),
+        };
+        let target_name = target.trim_start_matches("refs/heads/").to_string();
+
+        let mut remote = repo.find_remote(&remote_name)?;
+        let url = remote.url().unwrap_or_default().to_string();
+        let tracking = format!("refs/remotes/{}/{}", remote_name, target_name);
+        let before = repo.refname_to_id(&tracking).ok();
+        let local = repo.refname_to_id(&format!("refs/heads/{}", branch))?;
+
+        let rejected = RefCell::new(None);
+        let mut callbacks = callbacks(&repo, ctx);
+        callbacks.push_transfer_progress(|current, total, bytes| {
+            ctx.report(&format!(
+                "Writing objects: {}/{}, {}",
+                current,
+                total,
+                format_size(bytes as u64)
+            ));
+        });
+        callbacks.push_update_reference(|_, status| {
+            if let Some(status) = status {
+                *rejected.borrow_mut() = Some(status.to_string());
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        let refspec = format!(
+            "{}refs/heads/{}:{}",
+            if force { "+" } else { "" },
+            branch,
+            target
+        );
+        remote
+            .push(&[refspec.as_str()], Some(&mut options))
+            .map_err(|e| remote_error("gp", ctx, e))?;
+        drop(options);
+        if let Some(status) = rejected.into_inner() {
+            return Err(anyhow!(
+                "gp: {} rejected by {}: {}{}",
+                target_name,
+                remote_name,
+                status,
+                if force { "" } else { " (gpl first, or gp -f)" }
+            ));
+        }
+        // Keep the remote-tracking branch in step (the status bar counts from it)
+        repo.reference(&tracking, local, true, "gp: update tracking branch")?;
+
+        let mut lines = vec![format!("To {}", url)];
+        let range = match before {
+            Some(before) if before == local => "(up to date)".to_string(),
+            Some(before) => format!("{}..{}", short_id(before), short_id(local)),
+            None => "[new branch]".to_string(),
+        };
+        lines.push(format!(
+            "   {}{}{}  {} -> {}",
+            GREEN, range, RESET, branch, target_name
+        ));
+        if upstream.is_none() {
+            let mut config = repo.config()?;
+            config.set_str(&format!("branch.{}.remote", branch), &remote_name)?;
+            config.set_str(&format!("branch.{}.merge", branch), &target)?;
+            lines.push(format!(
+                "{}{} now tracks {}/{}{}",
+                DIM, branch, remote_name, target_name, RESET
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+impl Command for GplCommand {
+    fn name(&self) -> &'static str {
+        "gpl"
+    }
+
+    fn description(&self) -> &'static str {
+        "Pull (fast-forward) the current branch"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gpl [remote] [branch]\n\n\
+         Fetches the remote and fast-forwards the current branch to its\n\
+         upstream (or remote/branch). Local edits are kept unless the pull\n\
+         would overwrite them. When both sides have new commits nothing is\n\
+         changed: merge or rebase with git.\n\n\
+         Examples:\n  \
+         gpl                  - Pull the upstream\n  \
+         gpl origin main      - Pull origin/main into the current branch"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if args.len() > 2 || args.iter().any(|arg| arg.starts_with('-')) {
+            return Err(anyhow!("gpl: usage: gpl [remote] [branch]"));
+        }
+        let repo = open_repo("gpl", state)?;
+        let branch = current_branch("gpl", &repo)?;
+        let upstream = Upstream::of(&repo, &branch);
+        let remote_name = match args.first() {
+            Some(name) => name.clone(),
+            None => match &upstream {
+                Some(upstream) => upstream.remote.clone(),
+                None => default_remote("gpl", &repo)?,
+            },
+        };
+        let source = match (args.get(1), &upstream) {
+            (Some(name), _) => name.clone(),
+            (None, Some(upstream)) if upstream.remote == remote_name => {
+                upstream.merge.trim_start_matches("refs/heads/").to_string()
+            }
+            _ => branch.clone(),
+        };
+
+        let mut remote = repo.find_remote(&remote_name)?;
+        let mut callbacks = callbacks(&repo, ctx);
+        callbacks.transfer_progress(|progress| {
+            ctx.report(&format!(
+                "Receiving objects: {}/{}, {}",
+                progress.received_objects(),
+                progress.total_objects(),
+                format_size(progress.received_bytes() as u64)
+            ));
+            !ctx.is_cancelled()
+        });
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        let refspec = format!(
+            "+refs/heads/{}:refs/remotes/{}/{}",
+            source, remote_name, source
+        );
+        remote
+            .fetch(&[refspec.as_str()], Some(&mut options), None)
+            .map_err(|e| remote_error("gpl", ctx, e))?;
+
+        let fetched = repo
+            .refname_to_id(&format!("refs/remotes/{}/{}", remote_name, source))
+            .map_err(|_| anyhow!("gpl: {} has no branch '{}'", remote_name, source))?;
+        fast_forward(&repo, &branch, fetched)
+    }
+}
+
+/// Where a branch pushes and pulls by default (`branch.<name>.remote` and
+/// `branch.<name>.merge`)
+struct Upstream {
+    remote: String,
+    /// `refs/heads/main` on the remote
+    merge: String,
+}
+
+impl Upstream {
+    fn of(repo: &Repository, branch: &str) -> Option<Self> {
+        let config = repo.config().ok()?;
+        Some(Self {
+            remote: config
+                .get_string(&format!("branch.{}.remote", branch))
+                .ok()?,
+            merge: config
+                .get_string(&format!("branch.{}.merge", branch))
+                .ok()?,
+        })
+    }
+}
+
+/// Name of the checked-out branch
+fn current_branch(command: &str, repo: &Repository) -> Result<String> {
+    let head = repo
+        .head()
+        .map_err(|_| anyhow!("{}: no commits yet", command))?;
+    if !head.is_branch() {
+        return Err(anyhow!(
+            "{}: HEAD is detached; check out a branch first",
+            command
+        ));
+    }
+    Ok(head.shorthand().unwrap_or_default().to_string())
+}
+
+/// origin, else the only remote
+fn default_remote(command: &str, repo: &Repository) -> Result<String> {
+    let remotes = repo.remotes()?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+    match names.as_slice() {
+        [] => Err(anyhow!("{}: this repository has no remotes", command)),
+        names if names.contains(&"origin") => Ok("origin".to_string()),
+        [only] => Ok(only.to_string()),
+        _ => Err(anyhow!(
+            "{}: no upstream and no origin; name a remote ({})",
+            command,
+            names.join(", ")
+        )),
+    }
+}
+
+/// Callbacks answering authentication: the ssh agent, then the default
+/// keys, for ssh remotes; git's credential helper for https
+fn callbacks<'a>(repo: &Repository, ctx: &'a CommandContext) -> RemoteCallbacks<'a> {
+    let config = repo.config().ok();
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if ctx.is_cancelled() {
+            return Err(git2::Error::from_str("interrupted"));
+        }
+        let user = username.unwrap_or("git");
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if attempts == 1 {
+                return Cred::ssh_key_from_agent(user);
+            }
+            let home = dirs::home_dir().unwrap_or_default();
+            let key = DEFAULT_KEYS
+                .iter()
+                .map(|name| home.join(".ssh").join(name))
+                .filter(|path| path.exists())
+                .nth(attempts - 2);
+            if let Some(key) = key {
+                return Cred::ssh_key(user, None, &key, None);
+            }
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && attempts == 1 {
+            if let Some(config) = &config {
+                return Cred::credential_helper(config, url, username);
+            }
+        } else if allowed.contains(CredentialType::DEFAULT) && attempts == 1 {
+            return Cred::default();
+        }
+        Err(git2::Error::from_str(
+            "authentication failed (no ssh agent key, default key, or stored credentials worked)",
+        ))
+    });
+    callbacks
+}
+
+/// A transfer error, or `Interrupted` when Ctrl+C stopped the transfer
+fn remote_error(command: &str, ctx: &CommandContext, error: git2::Error) -> anyhow::Error {
+    if ctx.is_cancelled() {
+        Interrupted.into()
+    } else {
+        anyhow!("{}: {}", command, error.message())
+    }
+}
+
+/// Move `branch` (checked out) forward to `target`, updating the working tree
+fn fast_forward(repo: &Repository, branch: &str, target: Oid) -> Result<String> {
+    let annotated = repo.find_annotated_commit(target)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+    if analysis.is_up_to_date() {
+        return Ok("Already up to date.".to_string());
+    }
+    if !analysis.is_fast_forward() {
+        return Err(anyhow!(
+            "gpl: {} and the remote have both moved on; merge or rebase with git",
+            branch
+        ));
+    }
+
+    let mut reference = repo.find_reference(&format!("refs/heads/{}", branch))?;
+    let before = reference
+        .target()
+        .ok_or_else(|| anyhow!("gpl: {} isn't a direct reference", branch))?;
+    let old_tree = repo.find_commit(before)?.tree()?;
+    let new_tree = repo.find_commit(target)?.tree()?;
+
+    // Safe checkout refuses to overwrite local edits
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(new_tree.as_object(), Some(&mut checkout))
+        .map_err(|e| anyhow!("gpl: {} (commit or stash your changes)", e.message()))?;
+    reference.set_target(target, "gpl: fast-forward")?;
+
+    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+    let stats = diff.stats()?.to_buf(DiffStatsFormat::SHORT, 80)?;
+    let mut walk = repo.revwalk()?;
+    walk.push(target)?;
+    walk.hide(before)?;
+    let count = walk.count();
+    Ok(format!(
+        "Updating {}..{}\n{}Fast-forward{} ({} commit{})\n{}",
+        short_id(before),
+        short_id(target),
+        YELLOW,
+        RESET,
+        count,
+        if count == 1 { "" } else { "s" },
+        stats.as_str().unwrap_or_default().trim_end()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, name: &str, text: &str) {
+        let dir = repo.workdir().unwrap();
+        std::fs::write(dir.join(name), text).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, name, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_push_and_pull() {
+        let root = std::env::temp_dir().join(format!("zaxiom-gp-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let bare = Repository::init_bare(root.join("remote.git")).unwrap();
+        let ours = Repository::init(root.join("ours")).unwrap();
+        ours.remote("origin", root.join("remote.git").to_str().unwrap())
+            .unwrap();
+        commit_file(&ours, "a.txt", "a");
+        let branch = ours.head().unwrap().shorthand().unwrap().to_string();
+
+        let mut state = TerminalState::new();
+        state.set_cwd(root.join("ours"));
+        let output = GpCommand.execute(&[], &mut state).unwrap();
+        assert!(output.contains("[new branch]"));
+        assert!(output.contains(&format!("now tracks origin/{}", branch)));
+        let head = ours.head().unwrap().target().unwrap();
+        assert_eq!(
+            bare.refname_to_id(&format!("refs/heads/{}", branch))
+                .unwrap(),
+            head
+        );
+
+        // A clone pulls the next commit
+        let theirs = Repository::clone(
+            root.join("remote.git").to_str().unwrap(),
+            root.join("theirs"),
+        )
+        .unwrap();
+        commit_file(&ours, "b.txt", "b");
+        GpCommand.execute(&[], &mut state).unwrap();
+        state.set_cwd(root.join("theirs"));
+        let output = GplCommand.execute(&[], &mut state).unwrap();
+        assert!(output.contains("Fast-forward"));
+        assert_eq!(
+            theirs.head().unwrap().target(),
+            ours.head().unwrap().target()
+        );
+        assert!(root.join("theirs/b.txt").exists());
+        assert_eq!(
+            GplCommand.execute(&[], &mut state).unwrap(),
+            "Already up to date."
+        );
+
+        // Diverged: pushing is rejected, pulling refuses to merge
+        commit_file(&theirs, "c.txt", "c");
+        commit_file(&ours, "d.txt", "d");
+        GpCommand.execute(&[], &mut state).unwrap();
+        state.set_cwd(root.join("ours"));
+        assert!(GpCommand.execute(&[], &mut state).is_err());
+        assert!(GplCommand.execute(&[], &mut state).is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+//! gpr command - list, check out, and open GitHub pull requests and
+//! GitLab merge requests
+
+use std::path::Path;
+use std::process::Command as Process;
+
+use anyhow::{anyhow, Result};
+
+use super::{fuzzy_pick, open_repo, repo_forge, DIM, GREEN, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::git::forge::{Forge, PullRequest};
+use crate::terminal::state::TerminalState;
+
+pub struct GprCommand;
+
+impl Command for GprCommand {
+    fn name(&self) -> &'static str {
+        "gpr"
+    }
+
+    fn description(&self) -> &'static str {
+        "List, check out, and open pull/merge requests"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gpr [list] | gpr checkout <pr> | gpr open [pr] | gpr login <token>\n\n\
+         A PR is its number or any part of its title, branch, or author.\n\
+         Works with GitHub and GitLab remotes (origin, else the first one).\n\
+         Private repos need a token: gpr login saves it as GITHUB_TOKEN or\n\
+         GITLAB_TOKEN in your .env file.\n\n\
+         Examples:\n  \
+         gpr                  - List open pull requests\n  \
+         gpr checkout 42      - Fetch PR #42 into branch pr/42 and switch to it\n  \
+         gpr checkout login   - Check out the PR whose title matches \"login\"\n  \
+         gpr open             - Open the pull request list in the browser\n  \
+         gpr open 42          - Open PR #42 in the browser"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("gpr", state)?;
+        let (remote, forge) = repo_forge("gpr", &repo)?;
+        let query = args.get(1).map(|s| s.as_str());
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list") => {
+                let requests = forge.list_pull_requests()?;
+                Ok(list(&forge, &requests))
+            }
+            Some("checkout" | "co") => {
+                let query = query.ok_or_else(|| anyhow!("gpr: which pull request?"))?;
+                let number = select(&forge, query)?.0;
+                let branch = format!("pr/{}", number);
+
+                run_git(state.cwd(), &["fetch", &remote, &forge.head_ref(number)])?;
+                run_git(state.cwd(), &["checkout", "-B", &branch, "FETCH_HEAD"])?;
+                Ok(format!(
+                    "Checked out {}#{}{} into {}{}{}",
+                    YELLOW, number, RESET, GREEN, branch, RESET
+                ))
+            }
+            Some("open") => {
+                let url = match query {
+                    Some(query) => {
+                        let (number, url) = select(&forge, query)?;
+                        url.unwrap_or_else(|| forge.request_url(Some(number)))
+                    }
+                    None => forge.request_url(None),
+                };
+                open::that_detached(&url).map_err(|e| anyhow!("gpr: {}", e))?;
+                Ok(format!("🌐 Opened {}", url))
+            }
+            Some("login") => {
+                let token = query.ok_or_else(|| anyhow!("gpr: usage: gpr login <token>"))?;
+                let var = forge.kind.token_envs()[0];
+                std::env::set_var(var, token);
+                if !crate::config::env::save_key_to_env(var, token) {
+                    return Err(anyhow!("gpr: couldn't save {} to the .env file", var));
+                }
+                Ok(format!(
+                    "🔑 Saved {} to {}",
+                    var,
+                    crate::config::env::get_env_file_path().display()
+                ))
+            }
+            Some(other) => Err(anyhow!(
+                "gpr: unknown subcommand '{}'. Usage: gpr [list|checkout|open|login]",
+                other
+            )),
+        }
+    }
+}
+
+fn list(forge: &Forge, requests: &[PullRequest]) -> String {
+    if requests.is_empty() {
+        return format!("No open {}s.", forge.kind.request_name());
+    }
+    requests
+        .iter()
+        .map(|pr| {
+            format!(
+                "{}#{:<5}{} {}{} {}({} by {}){}",
+                YELLOW,
+                pr.number,
+                RESET,
+                if pr.draft { "[draft] " } else { "" },
+                pr.title,
+                DIM,
+                pr.branch,
+                pr.author,
+                RESET
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Resolve a PR number or fuzzy query to its number (and URL, when the
+/// API was asked)
+fn select(forge: &Forge, query: &str) -> Result<(u64, Option<String>)> {
+    if let Ok(number) = query.trim_start_matches(['#', '!']).parse::<u64>() {
+        return Ok((number, None));
+    }
+
+    let requests = forge.list_pull_requests()?;
+    let index = pick(&requests, query).ok_or_else(|| {
+        anyhow!(
+            "gpr: no open {} matches '{}'",
+            forge.kind.request_name(),
+            query
+        )
+    })?;
+    let pr = &requests[index];
+    Ok((pr.number, Some(pr.url.clone())))
+}
+
+/// Best match against title, branch, and author
+fn pick(requests: &[PullRequest], query: &str) -> Option<usize> {
+    let haystacks: Vec<String> = requests
+        .iter()
+        .map(|pr| format!("{} {} {}", pr.title, pr.branch, pr.author))
+        .collect();
+    fuzzy_pick(haystacks.iter().map(|s| s.as_str()), query)
+}
+
+/// Run git in `cwd` (uses the user's credentials for private remotes)
+fn run_git(cwd: &Path, args: &[&str]) -> Result<()> {
+    let output = Process::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| anyhow!("gpr: git: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("gpr: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(number: u64, title: &str, branch: &str, author: &str) -> PullRequest {
+        PullRequest {
+            number,
+            title: title.to_string(),
+            author: author.to_string(),
+            branch: branch.to_string(),
+            url: String::new(),
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn test_pick() {
+        let requests = [
+            pr(12, "Fix login redirect", "fix/login", "sam"),
+            pr(9, "Add dark theme", "feature/theme", "kai"),
+            pr(3, "Login with SSO", "sso", "kai"),
+        ];
+        assert_eq!(pick(&requests, "login"), Some(0));
+        assert_eq!(pick(&requests, "feature/theme"), Some(1));
+        assert_eq!(pick(&requests, "kai"), Some(1));
+        assert_eq!(pick(&requests, "lgnsso"), Some(2));
+        assert_eq!(pick(&requests, "zzz"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Commit graph layout shared by gl and gg
+//!
+//! `Graph` places commits (newest first) in lanes and describes each step
+//! as the rows git log --graph would draw: lanes joining the commit, the
+//! commit's own row, and lanes opened for a merge's other parents. gl draws
+//! them with ASCII; gg with box-drawing characters and lane colors.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use git2::{BranchType, Oid, Repository, Revwalk, Sort};
+
+/// Lanes of the commit graph: each holds the commit expected next in it
+#[derive(Default)]
+pub(super) struct Graph {
+    lanes: Vec<Option<Oid>>,
+}
+
+/// A connecting row between `column` and `lanes`
+pub(super) struct Edges {
+    /// The commit's lane
+    pub column: usize,
+    /// Lanes joining the commit (above it) or opened by it (below it)
+    pub lanes: Vec<usize>,
+    /// Which lanes have a line through this row
+    pub busy: Vec<bool>,
+}
+
+/// What placing one commit draws
+pub(super) struct Step {
+    /// Lanes that were waiting for this commit merging into its lane
+    pub joins: Option<Edges>,
+    /// The commit's lane
+    pub column: usize,
+    /// Which lanes have a line through the commit's row
+    pub busy: Vec<bool>,
+    /// Lanes opened for a merge's other parents
+    pub forks: Option<Edges>,
+}
+
+impl Graph {
+    /// Place the next commit
+    pub fn add(&mut self, oid: Oid, parents: &[Oid]) -> Step {
+        let column = match self.lanes.iter().position(|lane| *lane == Some(oid)) {
+            Some(column) => column,
+            None => self.free_lane(),
+        };
+        self.lanes[column] = Some(oid);
+
+        // Other lanes waiting for this commit merge into it
+        let joining: Vec<usize> = (0..self.lanes.len())
+            .filter(|&i| i != column && self.lanes[i] == Some(oid))
+            .collect();
+        let joins = (!joining.is_empty()).then(|| self.edges(column, joining));
+        if let Some(joins) = &joins {
+            for &lane in &joins.lanes {
+                self.lanes[lane] = None;
+            }
+            self.trim();
+        }
+        let busy = self.busy();
+
+        // The first parent continues this lane; others open new ones
+        self.lanes[column] = parents.first().copied();
+        let mut opened = Vec::new();
+        for parent in parents.iter().skip(1) {
+            if self.lanes.contains(&Some(*parent)) {
+                continue;
+            }
+            let lane = self.free_lane();
+            self.lanes[lane] = Some(*parent);
+            opened.push(lane);
+        }
+        let forks = (!opened.is_empty()).then(|| self.edges(column, opened));
+        self.trim();
+        Step {
+            joins,
+            column,
+            busy,
+            forks,
+        }
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    /// Drop empty lanes on the right
+    fn trim(&mut self) {
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+    }
+
+    fn busy(&self) -> Vec<bool> {
+        self.lanes.iter().map(Option::is_some).collect()
+    }
+
+    fn edges(&self, column: usize, lanes: Vec<usize>) -> Edges {
+        Edges {
+            column,
+            lanes,
+            busy: self.busy(),
+        }
+    }
+}
+
+/// The commit's row in ASCII: `*` in its column, `|` in every other busy lane
+pub(super) fn ascii_row(column: usize, busy: &[bool]) -> String {
+    let mut row = String::new();
+    for (i, busy) in busy.iter().enumerate() {
+        row.push(match (i == column, busy) {
+            (true, _) => '*',
+            (false, true) => '|',
+            (false, false) => ' ',
+        });
+        row.push(' ');
+    }
+    row
+}
+
+/// A connecting row in ASCII: `mark` (`/` or `\\`) leading into each lane
+/// in `edges.lanes` from the one on its left, `|` in the other busy lanes
+pub(super) fn ascii_edges(edges: &Edges, mark: char) -> String {
+    let mut row = vec![' '; edges.busy.len() * 2];
+    for (i, busy) in edges.busy.iter().enumerate() {
+        if edges.lanes.contains(&i) {
+            row[(i * 2).saturating_sub(1)] = mark;
+        } else if *busy {
+            row[i * 2] = '|';
+        }
+    }
+    row.into_iter().collect::<String>().trim_end().to_string()
+}
+
+/// Commits from HEAD (and every branch with `all`), newest first with
+/// parents after their children; `None` when there is nothing to walk
+pub(super) fn revwalk(repo: &Repository, all: bool) -> Result<Option<Revwalk<'_>>> {
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    if all {
+        walk.push_glob("refs/heads")?;
+        walk.push_glob("refs/remotes")?;
+    }
+    if walk.push_head().is_err() && !all {
+        return Ok(None);
+    }
+    Ok(Some(walk))
+}
+
+/// Branch and tag names by the commit they point at, `HEAD -> main` first
+pub(super) fn ref_labels(repo: &Repository) -> Result<HashMap<Oid, Vec<String>>> {
+    let mut labels: HashMap<Oid, Vec<String>> = HashMap::new();
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string));
+    if let Some(target) = head.as_ref().and_then(|h| h.target()) {
+        let label = match &head_branch {
+            Some(branch) => format!("HEAD -> {}", branch),
+            None => "HEAD".to_string(),
+        };
+        labels.entry(target).or_default().push(label);
+    }
+    for branch in repo.branches(None)? {
+        let (branch, kind) = branch?;
+        let (Some(name), Some(target)) = (branch.name()?, branch.get().target()) else {
+            continue;
+        };
+        if kind == BranchType::Local && head_branch.as_deref() == Some(name) {
+            continue;
+        }
+        labels.entry(target).or_default().push(name.to_string());
+    }
+    repo.tag_foreach(|oid, name| {
+        let name = String::from_utf8_lossy(name);
+        let target = repo
+            .find_object(oid, None)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .unwrap_or(oid);
+        let name = name.trim_start_matches("refs/tags/");
+        labels
+            .entry(target)
+            .or_default()
+            .push(format!("tag: {}", name));
+        true
+    })?;
+    Ok(labels)
+}
@@ -0,0 +1,111 @@
+//! gremote command - list, add, and remove remotes
+
+use anyhow::{anyhow, Result};
+
+use super::{open_repo, CYAN, DIM, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GremoteCommand;
+
+impl Command for GremoteCommand {
+    fn name(&self) -> &'static str {
+        "gremote"
+    }
+
+    fn description(&self) -> &'static str {
+        "List, add, and remove git remotes"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gremote [list] | gremote add <name> <url> | gremote remove <name>\n\n\
+         Examples:\n  \
+         gremote                                  - Show remotes with fetch/push URLs\n  \
+         gremote add upstream https://x.io/a.git  - Add a remote\n  \
+         gremote rm upstream                      - Remove a remote"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("gremote", state)?;
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list" | "-v") => list(&repo),
+            Some("add") => {
+                let (Some(name), Some(url)) = (args.get(1), args.get(2)) else {
+                    return Err(anyhow!("gremote: usage: gremote add <name> <url>"));
+                };
+                repo.remote(name, url)
+                    .map_err(|e| anyhow!("gremote: {}", e.message()))?;
+                Ok(format!(
+                    "Added {}{}{} → {}{}{}",
+                    YELLOW, name, RESET, CYAN, url, RESET
+                ))
+            }
+            Some("remove" | "rm") => {
+                let name = args
+                    .get(1)
+                    .ok_or_else(|| anyhow!("gremote: usage: gremote remove <name>"))?;
+                repo.remote_delete(name)
+                    .map_err(|e| anyhow!("gremote: {}", e.message()))?;
+                Ok(format!("Removed {}{}{}", YELLOW, name, RESET))
+            }
+            Some(other) => Err(anyhow!(
+                "gremote: unknown subcommand '{}'. Usage: gremote [list|add|remove]",
+                other
+            )),
+        }
+    }
+}
+
+fn list(repo: &git2::Repository) -> Result<String> {
+    let names = repo.remotes()?;
+    let mut lines = Vec::new();
+
+    for name in names.iter().flatten() {
+        let remote = repo.find_remote(name)?;
+        let fetch = remote.url().unwrap_or_default();
+        let push = remote.pushurl().unwrap_or(fetch);
+        lines.push(format!(
+            "{}{:<12}{} {}{}{} {}(fetch){}",
+            YELLOW, name, RESET, CYAN, fetch, RESET, DIM, RESET
+        ));
+        lines.push(format!(
+            "{:<12} {}{}{} {}(push){}",
+            "", CYAN, push, RESET, DIM, RESET
+        ));
+    }
+
+    if lines.is_empty() {
+        return Ok("No remotes.".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_list_remove() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gremote-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        git2::Repository::init(&dir).unwrap();
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+        let run = |args: &[&str], state: &mut TerminalState| {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            GremoteCommand.execute(&args, state)
+        };
+
+        run(&["add", "origin", "https://example.com/a.git"], &mut state).unwrap();
+        let listing = run(&[], &mut state).unwrap();
+        assert!(listing.contains("https://example.com/a.git"));
+        assert!(listing.contains("(push)"));
+
+        run(&["rm", "origin"], &mut state).unwrap();
+        assert_eq!(run(&[], &mut state).unwrap(), "No remotes.");
+        assert!(run(&["rm", "origin"], &mut state).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
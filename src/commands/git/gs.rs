@@ -0,0 +1,196 @@
+//! gs command - git status with icons
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use super::{open_repo, CYAN, DIM, GREEN, RED, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::config::theme::icons;
+use crate::git::panel::{changes, FileChange, Section};
+use crate::git::watcher::{read_status, GitSnapshot};
+use crate::terminal::state::TerminalState;
+
+pub struct GsCommand;
+
+impl Command for GsCommand {
+    fn name(&self) -> &'static str {
+        "gs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show git status"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gs [-s]\n\n\
+         Shows the branch, how far it is ahead of or behind its upstream, and\n\
+         the staged, unstaged, and untracked files (paths are relative to the\n\
+         repository root).\n\n\
+         Options:\n  \
+         -s, --short   One line per file: staged and unstaged status, then the path\n\n\
+         Examples:\n  \
+         gs      - Status grouped by staged, changed, and untracked\n  \
+         gs -s   - Compact status, like git status -s"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut short = false;
+        for arg in args {
+            match arg.as_str() {
+                "-s" | "--short" => short = true,
+                other => return Err(anyhow!("gs: unknown option '{}'. Usage: gs [-s]", other)),
+            }
+        }
+
+        let repo = open_repo("gs", state)?;
+        if repo.is_bare() {
+            return Err(anyhow!("gs: a bare repository has no working tree"));
+        }
+        let snapshot =
+            read_status(&repo).ok_or_else(|| anyhow!("gs: could not read the current branch"))?;
+        let changes = changes(&repo)?;
+        Ok(if short {
+            short_status(&changes)
+        } else {
+            long_status(&snapshot, &changes)
+        })
+    }
+}
+
+/// Color for a status letter
+fn letter_color(status: char) -> &'static str {
+    match status {
+        'A' | '?' => GREEN,
+        'D' | 'U' => RED,
+        _ => YELLOW,
+    }
+}
+
+/// The branch line, then each non-empty section
+fn long_status(snapshot: &GitSnapshot, changes: &[FileChange]) -> String {
+    let mut branch = format!("{}{} {}{}", CYAN, icons::GIT_BRANCH, snapshot.branch, RESET);
+    if snapshot.ahead > 0 {
+        branch.push_str(&format!(" {}↑{}{}", GREEN, snapshot.ahead, RESET));
+    }
+    if snapshot.behind > 0 {
+        branch.push_str(&format!(" {}↓{}{}", YELLOW, snapshot.behind, RESET));
+    }
+    let mut lines = vec![branch];
+
+    if changes.is_empty() {
+        lines.push(format!(
+            "{}{} Nothing to commit, working tree clean{}",
+            GREEN,
+            icons::GIT_CLEAN,
+            RESET
+        ));
+        return lines.join("\n");
+    }
+
+    for section in Section::ALL {
+        let files: Vec<_> = changes.iter().filter(|c| c.section == section).collect();
+        if files.is_empty() {
+            continue;
+        }
+        let (icon, color) = match section {
+            Section::Staged => (icons::GIT_STAGED, GREEN),
+            Section::Unstaged => (icons::GIT_MODIFIED, YELLOW),
+            Section::Untracked => (icons::GIT_UNTRACKED, DIM),
+        };
+        lines.push(String::new());
+        lines.push(format!(
+            "{}{} {} ({}){}",
+            color,
+            icon,
+            section.title(),
+            files.len(),
+            RESET
+        ));
+        for file in files {
+            let letter = if section == Section::Untracked {
+                ' '
+            } else {
+                file.status
+            };
+            lines.push(format!(
+                "  {}{}{}  {}",
+                letter_color(file.status),
+                letter,
+                RESET,
+                file.path
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// `XY path` per file: X is the staged status, Y the unstaged one
+fn short_status(changes: &[FileChange]) -> String {
+    let mut files: BTreeMap<&str, (char, char)> = BTreeMap::new();
+    for change in changes {
+        let entry = files.entry(&change.path).or_insert((' ', ' '));
+        match change.section {
+            Section::Staged => entry.0 = change.status,
+            Section::Unstaged => entry.1 = change.status,
+            Section::Untracked => *entry = ('?', '?'),
+        }
+    }
+    files
+        .into_iter()
+        .map(|(path, (x, y))| {
+            let (x_color, y_color) = if x == '?' { (RED, RED) } else { (GREEN, RED) };
+            format!(
+                "{}{}{}{}{}{} {}",
+                x_color, x, RESET, y_color, y, RESET, path
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ansi::strip_ansi;
+    use std::path::Path;
+
+    #[test]
+    fn test_status() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = git2::Repository::init(&dir).unwrap();
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+        let clean = strip_ansi(&GsCommand.execute(&[], &mut state).unwrap());
+        assert!(clean.ends_with("Nothing to commit, working tree clean"));
+
+        std::fs::write(dir.join("staged.txt"), "a").unwrap();
+        std::fs::write(dir.join("new.txt"), "b").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.join("staged.txt"), "ab").unwrap();
+
+        let status = strip_ansi(&GsCommand.execute(&[], &mut state).unwrap());
+        let lines: Vec<&str> = status.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            [
+                "",
+                &format!("{} Staged (1)", icons::GIT_STAGED),
+                "  A  staged.txt",
+                "",
+                &format!("{} Changes (1)", icons::GIT_MODIFIED),
+                "  M  staged.txt",
+                "",
+                &format!("{} Untracked (1)", icons::GIT_UNTRACKED),
+                "     new.txt",
+            ]
+        );
+
+        let short = GsCommand.execute(&["-s".to_string()], &mut state).unwrap();
+        assert_eq!(strip_ansi(&short), "?? new.txt\nAM staged.txt");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
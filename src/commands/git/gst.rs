@@ -0,0 +1,135 @@
+//! gst command - list, apply, and pop stashes
+
+use anyhow::{anyhow, Result};
+use git2::{Oid, StashApplyOptions};
+
+use super::{fuzzy_pick, open_repo, short_id, DIM, GREEN, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GstCommand;
+
+impl Command for GstCommand {
+    fn name(&self) -> &'static str {
+        "gst"
+    }
+
+    fn description(&self) -> &'static str {
+        "List, apply, and pop git stashes"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gst [list|apply|pop] [stash]\n\n\
+         A stash can be its index, stash@{n}, or any part of its message -\n\
+         the newest stash that matches best is used.\n\n\
+         Examples:\n  \
+         gst                  - List stashes\n  \
+         gst apply            - Apply the newest stash and keep it\n  \
+         gst pop 2            - Apply stash@{2} and drop it\n  \
+         gst pop login        - Pop the stash whose message matches \"login\""
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut repo = open_repo("gst", state)?;
+
+        let mut stashes: Vec<(usize, String, Oid)> = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
+            true
+        })?;
+
+        let (action, query) = match args.first().map(|s| s.as_str()) {
+            None | Some("list") => return Ok(list(&stashes)),
+            Some(action @ ("apply" | "pop")) => (action, args.get(1).map(|s| s.as_str())),
+            Some(other) => {
+                return Err(anyhow!(
+                    "gst: unknown subcommand '{}'. Usage: gst [list|apply|pop] [stash]",
+                    other
+                ))
+            }
+        };
+
+        if stashes.is_empty() {
+            return Err(anyhow!("gst: no stashes"));
+        }
+        let messages: Vec<&str> = stashes.iter().map(|(_, m, _)| m.as_str()).collect();
+        let index = match query {
+            Some(query) => select_stash(&messages, query)
+                .ok_or_else(|| anyhow!("gst: no stash matches '{}'", query))?,
+            None => 0,
+        };
+        let message = &stashes[index].1;
+
+        let mut options = StashApplyOptions::new();
+        if action == "pop" {
+            repo.stash_pop(index, Some(&mut options))?;
+            Ok(format!(
+                "Popped {}stash@{{{}}}{}: {}",
+                YELLOW, index, RESET, message
+            ))
+        } else {
+            repo.stash_apply(index, Some(&mut options))?;
+            Ok(format!(
+                "Applied {}stash@{{{}}}{}: {} {}(kept){}",
+                YELLOW, index, RESET, message, DIM, RESET
+            ))
+        }
+    }
+}
+
+fn list(stashes: &[(usize, String, Oid)]) -> String {
+    if stashes.is_empty() {
+        return "No stashes.".to_string();
+    }
+    stashes
+        .iter()
+        .map(|(index, message, oid)| {
+            format!(
+                "{}stash@{{{}}}{}  {}{}{}  {}",
+                YELLOW,
+                index,
+                RESET,
+                GREEN,
+                short_id(*oid),
+                RESET,
+                message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Pick a stash by index, `stash@{n}`, or the best match against its
+/// message (substring beats subsequence; newer stashes win ties)
+fn select_stash(messages: &[&str], query: &str) -> Option<usize> {
+    let index = query
+        .strip_prefix("stash@{")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .unwrap_or(query);
+    if let Ok(index) = index.parse::<usize>() {
+        return (index < messages.len()).then_some(index);
+    }
+
+    fuzzy_pick(messages.iter().copied(), query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_stash() {
+        let messages = [
+            "On main: fix login form",
+            "WIP on main: 1a2b3c4 refactor parser",
+            "On main: login redirect",
+        ];
+        assert_eq!(select_stash(&messages, "1"), Some(1));
+        assert_eq!(select_stash(&messages, "stash@{2}"), Some(2));
+        assert_eq!(select_stash(&messages, "7"), None);
+        assert_eq!(select_stash(&messages, "login"), Some(0));
+        assert_eq!(select_stash(&messages, "Redirect"), Some(2));
+        assert_eq!(select_stash(&messages, "rfprs"), Some(1));
+        assert_eq!(select_stash(&messages, "xyz"), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! gtag command - list and create annotated tags
+
+use anyhow::{anyhow, Result};
+
+use super::{open_repo, short_id, DIM, GREEN, RESET, YELLOW};
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct GtagCommand;
+
+impl Command for GtagCommand {
+    fn name(&self) -> &'static str {
+        "gtag"
+    }
+
+    fn description(&self) -> &'static str {
+        "List and create annotated git tags"
+    }
+
+    fn usage(&self) -> &'static str {
+        "gtag [-l [pattern]] | gtag <name> [-m <message>] [commit]\n\n\
+         New tags are annotated; the message defaults to the tag name.\n\n\
+         Examples:\n  \
+         gtag                         - List tags with their commits\n  \
+         gtag -l \"v1.*\"               - List tags matching a pattern\n  \
+         gtag v1.2.0 -m \"Release 1.2\" - Tag HEAD\n  \
+         gtag v1.1.1 a1b2c3d          - Tag an earlier commit"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let repo = open_repo("gtag", state)?;
+
+        let mut name: Option<&str> = None;
+        let mut message: Option<&str> = None;
+        let mut target: Option<&str> = None;
+        let mut pattern: Option<&str> = None;
+        let mut listing = args.is_empty();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-l" | "--list" => listing = true,
+                "-m" | "--message" => {
+                    message = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow!("gtag: -m needs a message"))?,
+                    );
+                }
+                _ if listing => pattern = Some(arg),
+                _ if name.is_none() => name = Some(arg),
+                _ if target.is_none() => target = Some(arg),
+                _ => return Err(anyhow!("gtag: unexpected argument '{}'", arg)),
+            }
+        }
+
+        if listing {
+            return list(&repo, pattern);
+        }
+
+        let name = name.ok_or_else(|| anyhow!("gtag: missing tag name"))?;
+        let object = repo
+            .revparse_single(target.unwrap_or("HEAD"))
+            .map_err(|_| anyhow!("gtag: unknown commit '{}'", target.unwrap_or("HEAD")))?;
+        let signature = repo.signature().map_err(|_| {
+            anyhow!("gtag: set user.name and user.email in your git config to create tags")
+        })?;
+        repo.tag(name, &object, &signature, message.unwrap_or(name), false)
+            .map_err(|e| anyhow!("gtag: {}", e.message()))?;
+
+        Ok(format!(
+            "Tagged {}{}{} as {}{}{}",
+            GREEN,
+            short_id(object.id()),
+            RESET,
+            YELLOW,
+            name,
+            RESET
+        ))
+    }
+}
+
+fn list(repo: &git2::Repository, pattern: Option<&str>) -> Result<String> {
+    let names = repo.tag_names(pattern)?;
+    let mut lines = Vec::new();
+
+    for name in names.iter().flatten() {
+        let object = repo.revparse_single(&format!("refs/tags/{}", name))?;
+        let commit = object.peel_to_commit().map(|c| short_id(c.id()));
+        let note = match object.as_tag() {
+            Some(tag) => tag
+                .message()
+                .and_then(|m| m.lines().next())
+                .unwrap_or_default()
+                .to_string(),
+            None => "(lightweight)".to_string(),
+        };
+        lines.push(format!(
+            "{}{:<20}{} {}{}{}  {}{}{}",
+            YELLOW,
+            name,
+            RESET,
+            GREEN,
+            commit.unwrap_or_else(|_| "-------".to_string()),
+            RESET,
+            DIM,
+            note,
+            RESET
+        ));
+    }
+
+    if lines.is_empty() {
+        return Ok("No tags.".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_list() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gtag-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = git2::Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = repo.signature().unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        let mut state = TerminalState::new();
+        state.set_cwd(dir.clone());
+        let args: Vec<String> = ["v1.0.0", "-m", "First release"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        GtagCommand.execute(&args, &mut state).unwrap();
+
+        let listing = GtagCommand.execute(&[], &mut state).unwrap();
+        assert!(listing.contains("v1.0.0"));
+        assert!(listing.contains("First release"));
+        assert!(GtagCommand.execute(&args, &mut state).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+//! Git management commands
+//!
+//! gs, gd, gl, gg, ga, gc, gp, gpl - status, diff, log, commit graph,
+//! staging, commits, push and pull - and gst, gtag, gremote, gconflicts -
+//! stash, tag, remote, and merge conflict management - go through the
+//! native git backend (libgit2), so they work without git on PATH. gpr and
+//! gopen talk to GitHub and GitLab.
+
+mod ga;
+mod gc;
+mod gconflicts;
+mod gd;
+mod gg;
+mod gl;
+mod gopen;
+mod gp;
+mod gpr;
+mod graph;
+mod gremote;
+mod gs;
+mod gst;
+mod gtag;
+
+pub use ga::GaCommand;
+pub use gc::GcCommand;
+pub use gconflicts::GconflictsCommand;
+pub use gd::GdCommand;
+pub use gg::GgCommand;
+pub use gl::GlCommand;
+pub use gopen::GopenCommand;
+pub use gp::{GpCommand, GplCommand};
+pub use gpr::GprCommand;
+pub use gremote::GremoteCommand;
+pub use gs::GsCommand;
+pub use gst::GstCommand;
+pub use gtag::GtagCommand;
+
+use anyhow::{anyhow, Result};
+use git2::Repository;
+
+use crate::git::forge::Forge;
+use crate::terminal::state::TerminalState;
+
+/// Refs and names (stash@{0}, tag and remote names)
+const YELLOW: &str = "\x1b[33m";
+/// Commit hashes
+const GREEN: &str = "\x1b[32m";
+/// URLs
+const CYAN: &str = "\x1b[36m";
+/// Removed lines and deleted files
+const RED: &str = "\x1b[31m";
+/// Secondary details
+const DIM: &str = "\x1b[2m";
+/// File headers
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Open the repository containing the current directory
+fn open_repo(command: &str, state: &TerminalState) -> Result<Repository> {
+    Repository::discover(state.cwd())
+        .map_err(|_| anyhow!("{}: not a git repository (or any parent)", command))
+}
+
+/// Pathspecs for `args` (paths relative to the current directory) as
+/// paths relative to the repository root; no arguments means the current
+/// directory
+fn repo_pathspecs(repo: &Repository, args: &[String], state: &TerminalState) -> Vec<String> {
+    let Some(workdir) = repo.workdir() else {
+        return args.to_vec();
+    };
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    let relative = |path: std::path::PathBuf| {
+        // A deleted file can't be canonicalized, but its directory can
+        let path = path.canonicalize().unwrap_or_else(|_| {
+            match (
+                path.parent().and_then(|p| p.canonicalize().ok()),
+                path.file_name(),
+            ) {
+                (Some(parent), Some(name)) => parent.join(name),
+                _ => path.clone(),
+            }
+        });
+        match path.strip_prefix(&workdir) {
+            Ok(inside) if inside.as_os_str().is_empty() => "*".to_string(),
+            Ok(inside) => inside.to_string_lossy().replace('\\', "/"),
+            Err(_) => path.to_string_lossy().to_string(),
+        }
+    };
+    if args.is_empty() {
+        return vec![relative(state.cwd().clone())];
+    }
+    args.iter()
+        .map(|arg| relative(state.resolve_path(arg)))
+        .collect()
+}
+
+/// Remote to talk to (origin, else the first one) and its forge
+fn repo_forge(command: &str, repo: &Repository) -> Result<(String, Forge)> {
+    let remotes = repo.remotes()?;
+    let name = remotes
+        .iter()
+        .flatten()
+        .find(|name| *name == "origin")
+        .or_else(|| remotes.iter().flatten().next())
+        .ok_or_else(|| anyhow!("{}: this repository has no remotes", command))?
+        .to_string();
+
+    let remote = repo.find_remote(&name)?;
+    let url = remote.url().unwrap_or_default();
+    let forge = Forge::from_remote_url(url).ok_or_else(|| {
+        anyhow!(
+            "{}: {} ({}) isn't a GitHub or GitLab remote",
+            command,
+            name,
+            url
+        )
+    })?;
+    Ok((name, forge))
+}
+
+/// First 7 characters of an object id
+fn short_id(oid: git2::Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+/// Index of the candidate that best matches `query` (case-insensitive
+/// substring beats subsequence; earlier candidates win ties)
+fn fuzzy_pick<'a>(candidates: impl IntoIterator<Item = &'a str>, query: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let score = |candidate: &str| {
+        let candidate = candidate.to_lowercase();
+        if candidate.contains(&query) {
+            return 2;
+        }
+        let mut chars = query.chars().peekable();
+        for c in candidate.chars() {
+            if chars.peek() == Some(&c) {
+                chars.next();
+            }
+        }
+        if chars.peek().is_none() {
+            1
+        } else {
+            0
+        }
+    };
+
+    let mut best: Option<(usize, i32)> = None;
+    for (i, candidate) in candidates.into_iter().enumerate() {
+        let score = score(candidate);
+        if score > 0 && best.is_none_or(|(_, top)| score > top) {
+            best = Some((i, score));
+        }
+    }
+    best.map(|(i, _)| i)
+}
@@ -19,14 +19,14 @@ impl Command for Base64Command {
     }
 
     fn usage(&self) -> &'static str {
-        "base64 [-d] <file>"
+        "base64 [-d] [file]"
     }
 
     fn extended_help(&self) -> String {
         r#"base64 - Encode or decode base64 data
 
 USAGE:
-  base64 [OPTIONS] <file>
+  base64 [OPTIONS] [file]
   command | base64 [OPTIONS]
 
 OPTIONS:
@@ -42,6 +42,7 @@ EXAMPLES:
   base64 -d encoded.txt          Decode base64 to binary
   echo "hello" | base64          Encode string: aGVsbG8K
   echo "aGVsbG8K" | base64 -d    Decode string: hello
+  echo hi | base64 | base64 -d   Round trip in a pipeline
 
 HOW BASE64 WORKS:
   • Converts 3 bytes to 4 ASCII characters
@@ -69,12 +70,23 @@ NOTE:
 
 RELATED COMMANDS:
   xxd        Hex encoding
+  hex        Plain hex encoding
+  urlencode  Percent-encoding for URLs
   uuencode   Unix-to-Unix encoding
 "#
         .to_string()
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
         let mut decode = false;
         let mut file: Option<&String> = None;
 
@@ -82,7 +94,7 @@ RELATED COMMANDS:
             match arg.as_str() {
                 "-d" | "--decode" => decode = true,
                 "-h" | "--help" => {
-                    return Ok("Usage: base64 [OPTIONS] <file>\n\
+                    return Ok("Usage: base64 [OPTIONS] [FILE]\n\
                         Options:\n  \
                         -d    Decode base64 input"
                         .to_string());
@@ -92,23 +104,32 @@ RELATED COMMANDS:
             }
         }
 
-        let file = file.ok_or_else(|| anyhow::anyhow!("base64: missing file operand"))?;
-        let path = state.resolve_path(file);
+        let content = match (file, stdin) {
+            (Some(file), _) => {
+                let path = state.resolve_path(file);
+                fs::read(&path).map_err(|e| anyhow::anyhow!("base64: {}: {}", file, e))?
+            }
+            (None, Some(input)) => input.as_bytes().to_vec(),
+            (None, None) => return Err(anyhow::anyhow!("base64: missing file operand")),
+        };
 
         if decode {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| anyhow::anyhow!("base64: {}: {}", file, e))?;
+            // Wrapped input (e.g. 76-column MIME) decodes the same as one line
+            let text: String = String::from_utf8_lossy(&content)
+                .split_whitespace()
+                .collect();
 
             let decoded = general_purpose::STANDARD
-                .decode(content.trim())
+                .decode(text)
                 .map_err(|e| anyhow::anyhow!("base64: invalid input: {}", e))?;
 
             Ok(String::from_utf8_lossy(&decoded).to_string())
         } else {
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("base64: {}: {}", file, e))?;
-
             Ok(general_purpose::STANDARD.encode(&content))
         }
     }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
 }
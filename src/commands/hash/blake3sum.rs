@@ -4,8 +4,9 @@
 //! MD5, SHA-1, SHA-2, and SHA-3, while being more secure.
 
 use anyhow::Result;
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -72,6 +73,21 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if let Some(input) = stdin {
+            let hash = blake3::hash(input.as_bytes());
+            return Ok(format!("{}  -", hash.to_hex()));
+        }
+
         if args.is_empty() {
             return Err(anyhow::anyhow!("blake3sum: missing file operand"));
         }
@@ -91,10 +107,11 @@ RELATED COMMANDS:
             }
 
             let path = state.resolve_path(arg);
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("blake3sum: {}: {}", arg, e))?;
-
-            let hash = blake3::hash(&content);
+            let mut hasher = blake3::Hasher::new();
+            read_chunks(&path, "blake3sum", arg, ctx, |chunk| {
+                hasher.update(chunk);
+            })?;
+            let hash = hasher.finalize();
             output.push(format!("{}  {}", hash.to_hex(), arg));
         }
 
@@ -107,11 +124,6 @@ RELATED COMMANDS:
         stdin: Option<&str>,
         state: &mut TerminalState,
     ) -> Result<String> {
-        if let Some(input) = stdin {
-            let hash = blake3::hash(input.as_bytes());
-            Ok(format!("{}  -", hash.to_hex()))
-        } else {
-            self.execute(args, state)
-        }
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
     }
 }
@@ -1,8 +1,9 @@
 //! crc32 command - compute CRC32 checksum
 
 use anyhow::Result;
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -76,6 +77,21 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if let Some(input) = stdin {
+            let checksum = crc32_compute(input.as_bytes());
+            return Ok(format!("{:08x}  -", checksum));
+        }
+
         if args.is_empty() {
             return Err(anyhow::anyhow!("crc32: missing file operand"));
         }
@@ -94,9 +110,11 @@ RELATED COMMANDS:
             }
 
             let path = state.resolve_path(arg);
-            let content = fs::read(&path).map_err(|e| anyhow::anyhow!("crc32: {}: {}", arg, e))?;
-
-            let checksum = crc32_compute(&content);
+            let mut crc = CRC32_INIT;
+            read_chunks(&path, "crc32", arg, ctx, |chunk| {
+                crc = crc32_update(crc, chunk)
+            })?;
+            let checksum = crc ^ CRC32_INIT;
             output.push(format!("{:08x}  {}", checksum, arg));
         }
 
@@ -109,42 +127,44 @@ RELATED COMMANDS:
         stdin: Option<&str>,
         state: &mut TerminalState,
     ) -> Result<String> {
-        if let Some(input) = stdin {
-            let checksum = crc32_compute(input.as_bytes());
-            Ok(format!("{:08x}  -", checksum))
-        } else {
-            self.execute(args, state)
-        }
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
     }
 }
 
-/// Compute CRC32 using the standard polynomial
-fn crc32_compute(data: &[u8]) -> u32 {
-    // CRC32 lookup table (IEEE polynomial)
-    const CRC32_TABLE: [u32; 256] = {
-        let mut table = [0u32; 256];
-        let mut i = 0;
-        while i < 256 {
-            let mut crc = i as u32;
-            let mut j = 0;
-            while j < 8 {
-                if crc & 1 != 0 {
-                    crc = (crc >> 1) ^ 0xEDB88320;
-                } else {
-                    crc >>= 1;
-                }
-                j += 1;
+/// CRC32 lookup table (IEEE polynomial)
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xEDB88320;
+            } else {
+                crc >>= 1;
             }
-            table[i] = crc;
-            i += 1;
+            j += 1;
         }
-        table
-    };
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Register value before the first byte (and the final XOR mask)
+const CRC32_INIT: u32 = 0xFFFFFFFF;
+
+/// Compute CRC32 using the standard polynomial
+fn crc32_compute(data: &[u8]) -> u32 {
+    crc32_update(CRC32_INIT, data) ^ CRC32_INIT
+}
 
-    let mut crc = 0xFFFFFFFF_u32;
+/// Feed more bytes into a running CRC32 register
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
     for &byte in data {
         let index = ((crc ^ byte as u32) & 0xFF) as usize;
         crc = (crc >> 8) ^ CRC32_TABLE[index];
     }
-    crc ^ 0xFFFFFFFF
+    crc
 }
@@ -0,0 +1,160 @@
+//! hex / unhex commands - plain hex encoding
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct HexCommand;
+
+impl Command for HexCommand {
+    fn name(&self) -> &'static str {
+        "hex"
+    }
+
+    fn description(&self) -> &'static str {
+        "Encode text as hex"
+    }
+
+    fn usage(&self) -> &'static str {
+        "hex [-u] [text...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"hex - Encode text as hex
+
+USAGE:
+  hex [OPTIONS] <text...>
+  command | hex [OPTIONS]
+
+OPTIONS:
+  -u, --upper    Use uppercase digits
+
+DESCRIPTION:
+  Prints the bytes of the input as one string of hex digits,
+  without the offsets and ASCII column of xxd.
+
+EXAMPLES:
+  hex "hi!"                 686921
+  hex -u "hi!"              686921 in uppercase
+  echo secret | hex         Encode piped text
+  hex abc | unhex           Round trip
+
+RELATED COMMANDS:
+  unhex      Decode hex
+  xxd        Full hexdump
+  base64     Base64 encoding
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut upper = false;
+        let mut words: Vec<&str> = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "-u" | "--upper" => upper = true,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ => words.push(arg),
+            }
+        }
+
+        let input = match (words.is_empty(), stdin) {
+            (false, _) => words.join(" "),
+            (true, Some(input)) => input.to_string(),
+            (true, None) => return Err(anyhow::anyhow!("hex: no input")),
+        };
+
+        if upper {
+            Ok(hex::encode_upper(input))
+        } else {
+            Ok(hex::encode(input))
+        }
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+pub struct UnhexCommand;
+
+impl Command for UnhexCommand {
+    fn name(&self) -> &'static str {
+        "unhex"
+    }
+
+    fn description(&self) -> &'static str {
+        "Decode hex to text"
+    }
+
+    fn usage(&self) -> &'static str {
+        "unhex [hex...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"unhex - Decode hex to text
+
+USAGE:
+  unhex <hex...>
+  command | unhex
+
+DESCRIPTION:
+  Turns hex digits back into bytes. Whitespace, ':' separators
+  and 0x prefixes are ignored, so "0x68 0x69" and "68:69" work.
+
+EXAMPLES:
+  unhex 686921              hi!
+  unhex "de:ad:be:ef"       Colon-separated bytes
+  hex abc | unhex           Round trip
+
+RELATED COMMANDS:
+  hex        Encode text as hex
+  xxd -r     Reverse a full hexdump
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(self.extended_help());
+        }
+
+        let input = match (args.is_empty(), stdin) {
+            (false, _) => args.join(" "),
+            (true, Some(input)) => input.to_string(),
+            (true, None) => return Err(anyhow::anyhow!("unhex: no input")),
+        };
+
+        let digits: String = input
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .map(|word| word.trim_start_matches("0x").trim_start_matches("0X"))
+            .collect();
+
+        let bytes = hex::decode(&digits).map_err(|e| anyhow::anyhow!("unhex: {}", e))?;
+        Ok(String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
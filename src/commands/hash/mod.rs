@@ -1,25 +1,77 @@
 //! Hash and encoding commands
 //!
-//! md5sum, sha1sum, sha224sum, sha256sum, sha384sum, sha512sum, blake3sum, crc32, base64, xxd
+//! md5sum, sha1sum, sha224sum, sha256sum, sha384sum, sha512sum, blake3sum, crc32, base64, xxd,
+//! hex, unhex, urlencode, urldecode
 
 mod base64cmd;
 mod blake3sum;
 mod crc32;
+mod hexcmd;
 mod md5sum;
 mod sha1sum;
 mod sha224sum;
 mod sha256sum;
 mod sha384sum;
 mod sha512sum;
+mod urlencode;
 mod xxd;
 
 pub use base64cmd::Base64Command;
 pub use blake3sum::Blake3sumCommand;
 pub use crc32::Crc32Command;
+pub use hexcmd::{HexCommand, UnhexCommand};
 pub use md5sum::Md5sumCommand;
 pub use sha1sum::Sha1sumCommand;
 pub use sha224sum::Sha224sumCommand;
 pub use sha256sum::Sha256sumCommand;
 pub use sha384sum::Sha384sumCommand;
 pub use sha512sum::Sha512sumCommand;
+pub use urlencode::{UrldecodeCommand, UrlencodeCommand};
 pub use xxd::XxdCommand;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::context::CommandContext;
+
+/// Bytes read at a time when hashing a file
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Files at least this big report progress while being hashed
+const PROGRESS_MIN_SIZE: u64 = 64 << 20;
+
+/// Feed the file at `path` to `update` a chunk at a time, so hashing a large
+/// file can be cancelled part way and reports how far it got. `command` and
+/// `name` prefix I/O errors (`sha256sum: big.iso: ...`).
+fn read_chunks(
+    path: &Path,
+    command: &str,
+    name: &str,
+    ctx: &CommandContext,
+    mut update: impl FnMut(&[u8]),
+) -> Result<()> {
+    let io_error = |e: std::io::Error| anyhow::anyhow!("{}: {}: {}", command, name, e);
+    let mut file = File::open(path).map_err(io_error)?;
+    let size = file.metadata().map_err(io_error)?.len();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let (mut done, mut reported) = (0u64, 0u64);
+    loop {
+        ctx.check()?;
+        let read = file.read(&mut buffer).map_err(io_error)?;
+        if read == 0 {
+            return Ok(());
+        }
+        update(&buffer[..read]);
+        done += read as u64;
+        if size >= PROGRESS_MIN_SIZE {
+            let percent = done * 100 / size;
+            if percent >= reported + 10 {
+                reported = percent - percent % 10;
+                ctx.report(&format!("{}: {}%", name, reported));
+            }
+        }
+    }
+}
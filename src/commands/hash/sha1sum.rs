@@ -2,8 +2,9 @@
 
 use anyhow::Result;
 use sha1::{Digest, Sha1};
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -69,6 +70,16 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("sha1sum: missing file operand"));
         }
@@ -87,11 +98,8 @@ RELATED COMMANDS:
             }
 
             let path = state.resolve_path(arg);
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("sha1sum: {}: {}", arg, e))?;
-
             let mut hasher = Sha1::new();
-            hasher.update(&content);
+            read_chunks(&path, "sha1sum", arg, ctx, |chunk| hasher.update(chunk))?;
             let result = hasher.finalize();
 
             output.push(format!("{}  {}", hex::encode(result), arg));
@@ -2,8 +2,9 @@
 
 use anyhow::Result;
 use sha2::{Digest, Sha224};
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -27,6 +28,23 @@ impl Command for Sha224sumCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if let Some(input) = stdin {
+            let mut hasher = Sha224::new();
+            hasher.update(input.as_bytes());
+            let result = hasher.finalize();
+            return Ok(format!("{}  -", hex::encode(result)));
+        }
+
         if args.is_empty() {
             return Err(anyhow::anyhow!("sha224sum: missing file operand"));
         }
@@ -45,11 +63,8 @@ impl Command for Sha224sumCommand {
             }
 
             let path = state.resolve_path(arg);
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("sha224sum: {}: {}", arg, e))?;
-
             let mut hasher = Sha224::new();
-            hasher.update(&content);
+            read_chunks(&path, "sha224sum", arg, ctx, |chunk| hasher.update(chunk))?;
             let result = hasher.finalize();
 
             output.push(format!("{}  {}", hex::encode(result), arg));
@@ -64,13 +79,6 @@ impl Command for Sha224sumCommand {
         stdin: Option<&str>,
         state: &mut TerminalState,
     ) -> Result<String> {
-        if let Some(input) = stdin {
-            let mut hasher = Sha224::new();
-            hasher.update(input.as_bytes());
-            let result = hasher.finalize();
-            Ok(format!("{}  -", hex::encode(result)))
-        } else {
-            self.execute(args, state)
-        }
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
     }
 }
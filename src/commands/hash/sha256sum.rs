@@ -2,8 +2,9 @@
 
 use anyhow::Result;
 use sha2::{Digest, Sha256};
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -65,6 +66,16 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("sha256sum: missing file operand"));
         }
@@ -83,11 +94,8 @@ RELATED COMMANDS:
             }
 
             let path = state.resolve_path(arg);
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("sha256sum: {}: {}", arg, e))?;
-
             let mut hasher = Sha256::new();
-            hasher.update(&content);
+            read_chunks(&path, "sha256sum", arg, ctx, |chunk| hasher.update(chunk))?;
             let result = hasher.finalize();
 
             output.push(format!("{}  {}", hex::encode(result), arg));
@@ -96,3 +104,17 @@ RELATED COMMANDS:
         Ok(output.join("\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_sha256sum_golden() {
+        let fixture = Fixture::new("sha256sum").file("hello.txt", "hello\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(&Sha256sumCommand, &["hello.txt"], &mut state).unwrap();
+        assert_golden("sha256sum", &fixture.scrub(&output));
+    }
+}
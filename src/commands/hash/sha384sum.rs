@@ -2,8 +2,9 @@
 
 use anyhow::Result;
 use sha2::{Digest, Sha384};
-use std::fs;
 
+use super::read_chunks;
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -72,6 +73,23 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if let Some(input) = stdin {
+            let mut hasher = Sha384::new();
+            hasher.update(input.as_bytes());
+            let result = hasher.finalize();
+            return Ok(format!("{}  -", hex::encode(result)));
+        }
+
         if args.is_empty() {
             return Err(anyhow::anyhow!("sha384sum: missing file operand"));
         }
@@ -90,11 +108,8 @@ RELATED COMMANDS:
             }
 
             let path = state.resolve_path(arg);
-            let content =
-                fs::read(&path).map_err(|e| anyhow::anyhow!("sha384sum: {}: {}", arg, e))?;
-
             let mut hasher = Sha384::new();
-            hasher.update(&content);
+            read_chunks(&path, "sha384sum", arg, ctx, |chunk| hasher.update(chunk))?;
             let result = hasher.finalize();
 
             output.push(format!("{}  {}", hex::encode(result), arg));
@@ -109,13 +124,6 @@ RELATED COMMANDS:
         stdin: Option<&str>,
         state: &mut TerminalState,
     ) -> Result<String> {
-        if let Some(input) = stdin {
-            let mut hasher = Sha384::new();
-            hasher.update(input.as_bytes());
-            let result = hasher.finalize();
-            Ok(format!("{}  -", hex::encode(result)))
-        } else {
-            self.execute(args, state)
-        }
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
     }
 }
@@ -0,0 +1,208 @@
+//! urlencode / urldecode commands - percent-encoding for URLs
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct UrlencodeCommand;
+
+impl Command for UrlencodeCommand {
+    fn name(&self) -> &'static str {
+        "urlencode"
+    }
+
+    fn description(&self) -> &'static str {
+        "Percent-encode text for URLs"
+    }
+
+    fn usage(&self) -> &'static str {
+        "urlencode [-p] [text...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"urlencode - Percent-encode text for URLs
+
+USAGE:
+  urlencode [OPTIONS] <text...>
+  command | urlencode [OPTIONS]
+
+OPTIONS:
+  -p, --plus    Encode spaces as + (HTML form style)
+
+DESCRIPTION:
+  Encodes every byte except letters, digits and - _ . ~ as %XX.
+  Piped input is encoded line by line.
+
+EXAMPLES:
+  urlencode "a b&c"                  a%20b%26c
+  urlencode -p "rust lang"           rust+lang
+  cat terms.txt | urlencode          One encoded term per line
+  echo "q=1" | urlencode | urldecode Round trip
+
+RELATED COMMANDS:
+  urldecode  Decode percent-encoding
+  base64     Base64 encoding
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut plus = false;
+        let mut words: Vec<&str> = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "-p" | "--plus" => plus = true,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ => words.push(arg),
+            }
+        }
+
+        let input = match (words.is_empty(), stdin) {
+            (false, _) => words.join(" "),
+            (true, Some(input)) => input.to_string(),
+            (true, None) => return Err(anyhow::anyhow!("urlencode: no input")),
+        };
+
+        Ok(input
+            .lines()
+            .map(|line| encode(line, plus))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+pub struct UrldecodeCommand;
+
+impl Command for UrldecodeCommand {
+    fn name(&self) -> &'static str {
+        "urldecode"
+    }
+
+    fn description(&self) -> &'static str {
+        "Decode percent-encoded text"
+    }
+
+    fn usage(&self) -> &'static str {
+        "urldecode [text...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"urldecode - Decode percent-encoded text
+
+USAGE:
+  urldecode <text...>
+  command | urldecode
+
+DESCRIPTION:
+  Turns %XX sequences back into bytes and + into spaces.
+  Malformed sequences are left as they are.
+
+EXAMPLES:
+  urldecode "a%20b%26c"              a b&c
+  urldecode "q=rust+lang"            q=rust lang
+  grep GET access.log | urldecode    Readable request paths
+
+RELATED COMMANDS:
+  urlencode  Percent-encode text
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(self.extended_help());
+        }
+
+        let input = match (args.is_empty(), stdin) {
+            (false, _) => args.join(" "),
+            (true, Some(input)) => input.to_string(),
+            (true, None) => return Err(anyhow::anyhow!("urldecode: no input")),
+        };
+
+        Ok(decode(&input))
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+fn encode(text: &str, plus: bool) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' if plus => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(encode("a b&c/ü", false), "a%20b%26c%2F%C3%BC");
+        assert_eq!(encode("rust lang", true), "rust+lang");
+        assert_eq!(decode("a%20b%26c%2F%C3%BC"), "a b&c/ü");
+        assert_eq!(decode("q=rust+lang&p=100%"), "q=rust lang&p=100%");
+    }
+}
@@ -18,14 +18,15 @@ impl Command for XxdCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "xxd [-r] [-c cols] <file>"
+        "xxd [-r] [-c cols] [file]"
     }
 
     fn extended_help(&self) -> String {
         r#"xxd - Make a hexdump or do the reverse
 
 USAGE:
-  xxd [OPTIONS] <file>
+  xxd [OPTIONS] [file]
+  command | xxd [OPTIONS]
 
 OPTIONS:
   -r, --reverse    Reverse operation: convert hex dump to binary
@@ -40,6 +41,7 @@ EXAMPLES:
   xxd -c 8 file.bin            8 bytes per line
   xxd -r dump.txt              Convert hex back to binary
   xxd image.png | head         First lines of hexdump
+  echo hi | xxd | xxd -r       Round trip in a pipeline
 
 OUTPUT FORMAT:
   <offset>: <hex bytes>  <ASCII>
@@ -63,6 +65,7 @@ OUTPUT COMPONENTS:
   ASCII:   Printable character representation
 
 RELATED COMMANDS:
+  hex        Plain hex without offsets
   hexdump    Alternative hex viewer
   od         Octal dump
   file       Identify file type
@@ -72,6 +75,15 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
         let mut reverse = false;
         let mut cols = 16usize;
         let mut file: Option<&String> = None;
@@ -80,14 +92,12 @@ RELATED COMMANDS:
         while i < args.len() {
             match args[i].as_str() {
                 "-r" | "--reverse" => reverse = true,
-                "-c" | "--cols" => {
-                    if i + 1 < args.len() {
-                        cols = args[i + 1].parse().unwrap_or(16);
-                        i += 1;
-                    }
+                "-c" | "--cols" if i + 1 < args.len() => {
+                    cols = args[i + 1].parse().unwrap_or(16);
+                    i += 1;
                 }
                 "-h" | "--help" => {
-                    return Ok("Usage: xxd [OPTIONS] <file>\n\
+                    return Ok("Usage: xxd [OPTIONS] [FILE]\n\
                         Options:\n  \
                         -r         Reverse: convert hex dump to binary\n  \
                         -c <cols>  Number of columns (default 16)"
@@ -99,25 +109,27 @@ RELATED COMMANDS:
             i += 1;
         }
 
-        let file = file.ok_or_else(|| anyhow::anyhow!("xxd: missing file operand"))?;
-        let path = state.resolve_path(file);
+        let content = match (file, stdin) {
+            (Some(file), _) => {
+                let path = state.resolve_path(file);
+                fs::read(&path).map_err(|e| anyhow::anyhow!("xxd: {}: {}", file, e))?
+            }
+            (None, Some(input)) => input.as_bytes().to_vec(),
+            (None, None) => return Err(anyhow::anyhow!("xxd: missing file operand")),
+        };
 
         if reverse {
             // Reverse hex dump to binary
-            let content =
-                fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("xxd: {}: {}", file, e))?;
+            let content = String::from_utf8_lossy(&content);
 
             let mut bytes = Vec::new();
             for line in content.lines() {
                 // Skip the address and ASCII parts, just get hex
-                if let Some(hex_part) = line.split(':').nth(1) {
-                    let hex_str: String = hex_part
-                        .chars()
-                        .take_while(|c| {
-                            *c != ' ' || hex_part.chars().filter(|c| *c == ' ').count() < 2
-                        })
-                        .filter(|c| c.is_ascii_hexdigit())
-                        .collect();
+                if let Some((_, rest)) = line.split_once(':') {
+                    // Two spaces separate the hex bytes from the ASCII column
+                    let hex_part = rest.trim_start().split("  ").next().unwrap_or_default();
+                    let hex_str: String =
+                        hex_part.chars().filter(|c| c.is_ascii_hexdigit()).collect();
 
                     for chunk in hex_str.as_bytes().chunks(2) {
                         if chunk.len() == 2 {
@@ -134,8 +146,6 @@ RELATED COMMANDS:
             Ok(String::from_utf8_lossy(&bytes).to_string())
         } else {
             // Normal hex dump
-            let content = fs::read(&path).map_err(|e| anyhow::anyhow!("xxd: {}: {}", file, e))?;
-
             let mut output = Vec::new();
             let mut offset = 0;
 
@@ -163,4 +173,7 @@ RELATED COMMANDS:
             Ok(output.join("\n"))
         }
     }
+    fn supports_stdin(&self) -> bool {
+        true
+    }
 }
@@ -4,8 +4,10 @@
 
 pub mod ai;
 pub mod compress;
+pub mod context;
 pub mod files;
 pub mod fun;
+pub mod git;
 pub mod hash;
 pub mod nav;
 pub mod net;
@@ -13,6 +15,8 @@ pub mod registry;
 pub mod search;
 pub mod shell;
 pub mod system;
+#[cfg(test)]
+pub mod testing;
 pub mod text;
 pub mod tools;
 pub mod traits;
@@ -4,7 +4,7 @@ use anyhow::Result;
 use std::path::PathBuf;
 
 use crate::commands::traits::Command;
-use crate::terminal::state::TerminalState;
+use crate::terminal::state::{stack_index, TerminalState};
 
 /// Strip the Windows extended-length path prefix (\\?\) if present
 #[cfg(windows)]
@@ -34,16 +34,22 @@ impl Command for CdCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "cd [path]"
+        "cd [path | +n]"
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        let target = if args.is_empty() {
-            // No args = go to home directory
-            state.home().clone()
-        } else {
-            state.resolve_path(&args[0])
-        };
+        let target =
+            if args.is_empty() {
+                // No args = go to home directory
+                state.home().clone()
+            } else if let Some(n) = stack_index(&args[0]) {
+                // cd +n: entry n of the directory stack (see `dirs -v`)
+                state.dirs().get(n).cloned().ok_or_else(|| {
+                    anyhow::anyhow!("cd: +{}: directory stack index out of range", n)
+                })?
+            } else {
+                state.resolve_path(&args[0])
+            };
 
         // Canonicalize the path (resolve .., symlinks, etc.)
         let canonical = match target.canonicalize() {
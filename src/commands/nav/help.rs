@@ -45,6 +45,8 @@ impl Command for HelpCommand {
   ln        Create links                    stat      File information
   file      Determine file type             basename  Strip directory
   dirname   Get directory path              realpath  Get absolute path
+  open      Open with default app           start     Same as open
+  md        Render markdown                 less      Page through text (more)
 
   📝 TEXT PROCESSING
   ─────────────────────────────────────────────────────────────────
@@ -56,11 +58,13 @@ impl Command for HelpCommand {
   awk       Pattern processing              rev       Reverse lines
   nl        Number lines                    printf    Format output
   diff      Compare files                   tac       Reverse file
-  paste     Merge lines
+  paste     Merge lines                     str       Case/trim/pad/slug text
+  jq        Query & pretty-print JSON
 
   🔍 SEARCH
   ─────────────────────────────────────────────────────────────────
   grep      Search file contents            find      Find files
+  fd        Find files by name (fuzzy)
 
   💻 SYSTEM
   ─────────────────────────────────────────────────────────────────
@@ -70,17 +74,31 @@ impl Command for HelpCommand {
   free      Memory usage                    df        Disk space
   du        Directory size                  date      Show date/time
   cal       Show calendar                   id        User identity
-  neofetch  System info (fancy)
+  neofetch  System info (fancy)             layout    Arrange split panes
+  pane      Name the current pane           tips      Manage tips
+  history   List/search/delete history      fc        Edit & rerun a command
+  audit     Verify/export the audit log     timestamps Time blocks/lines
+  color     Preview/convert colors, palette buffer    Scrollback stats/trim
+  paths     Where config/data are stored    stats     Local usage dashboard
+  digest    Today's activity report card    zoom      Pane text size
+  keys      List/rebind shortcuts           workspace Save/open tabs & panes
+  session   List/save/restore sessions
 
   🌐 NETWORK
   ─────────────────────────────────────────────────────────────────
   curl      HTTP requests                   wget      Download files
   ping      Check connectivity              netstat   Network stats
+  scrape    Extract tables/links from HTML  lanscan   Find devices on the LAN
+  speedtest Measure internet speed          whois     Domain/IP registration
+  tlscert   Inspect TLS certificates        mock      Mock JSON API server
+  ssh       Remote shell in this pane
 
   🔐 HASH & ENCODING
   ─────────────────────────────────────────────────────────────────
   md5sum    MD5 hash                        sha256sum SHA256 hash
   base64    Base64 encode/decode            xxd       Hex dump
+  hex       Encode text as hex              unhex     Decode hex
+  urlencode Percent-encode for URLs         urldecode Decode %XX escapes
 
   📦 COMPRESSION
   ─────────────────────────────────────────────────────────────────
@@ -89,18 +107,32 @@ impl Command for HelpCommand {
 
   🔧 SHELL UTILITIES
   ─────────────────────────────────────────────────────────────────
-  alias     Create aliases                  env       Show variables
-  export    Set variables                   sleep     Pause execution
-  seq       Generate sequences              expr      Evaluate math
-  bc        Calculator                      tee       Split output
-  yes       Repeat output                   true      Return success
-  false     Return failure
+  alias     Create aliases (-s suffix, -g)  unalias   Remove aliases
+  env       Show variables                  export    Set variables
+  sleep     Pause execution                 seq       Generate sequences
+  expr      Evaluate math                   bc        Calculator
+  tee       Split output                    yes       Repeat output
+  true      Return success                  false     Return failure
+  source    Run a script (if/for/while)     uuid      Generate UUIDs (v4/v7)
+  random    Random ints/bytes/strings       lorem     Placeholder text
+  watch     Re-run a command in place       jobs      List background jobs
+  fg        Bring a job to the front        bg        Resume a job in background
+
+  🤖 AI
+  ─────────────────────────────────────────────────────────────────
+  # <q>     Ask the AI anything             ai        Manage providers
+  summarize Summarize piped output          ollama    Local models
 
   ⚡ GIT SHORTCUTS
   ─────────────────────────────────────────────────────────────────
-  gs → git status    gd → git diff      gl → git log
-  gp → git push      gpl → git pull     ga → git add
-  gc → git commit    gco → git checkout gb → git branch
+  gs → status        gd → colored diff  gl → graph log
+  gg → colored commit graph (also git graph; click a hash to copy it)
+  ga → stage files   gc → commit (editor without -m)
+  gp → push          gpl → pull (fast-forward)
+  gco → git checkout gb → git branch
+  gst → stash list/apply/pop  gtag → tags  gremote → remotes
+  gconflicts → list merge conflicts, resolve them in the editor
+  gpr → list/checkout/open PRs  gopen → open repo/file:line on the web
 
   🐍 PYTHON MODE
   ─────────────────────────────────────────────────────────────────
@@ -95,11 +95,9 @@ RELATED COMMANDS:
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-L" => {
-                    if i + 1 < args.len() {
-                        max_depth = args[i + 1].parse().ok();
-                        i += 1;
-                    }
+                "-L" if i + 1 < args.len() => {
+                    max_depth = args[i + 1].parse().ok();
+                    i += 1;
                 }
                 "-a" => show_hidden = true,
                 "-d" => dirs_only = true,
@@ -229,3 +227,21 @@ fn print_tree(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_tree_golden() {
+        let fixture = Fixture::new("tree")
+            .file("src/main.rs", "fn main() {}")
+            .file("src/lib/mod.rs", "")
+            .file("README.md", "# demo")
+            .dir("empty");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(&TreeCommand, &[], &mut state).unwrap();
+        assert_golden("tree", &fixture.scrub(&output));
+    }
+}
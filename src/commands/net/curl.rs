@@ -1,10 +1,38 @@
 //! curl command - HTTP requests
+//!
+//! A small HTTP client for API work: any method, headers, form, JSON, and
+//! multipart bodies, response headers, a timing breakdown, and a library
+//! of named requests saved under `[requests]` in config.toml.
 
-use anyhow::Result;
+use std::collections::VecDeque;
+use std::fs;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use eframe::egui::Color32;
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::{Method, StatusCode, Url};
 
 use crate::commands::traits::Command;
+use crate::config::settings::Config;
+use crate::config::theme::Theme;
+use crate::terminal::format::format_size;
 use crate::terminal::state::TerminalState;
 
+const RESET: &str = "\x1b[0m";
+
+/// Short flags without a value, which combine (`-sSL`)
+const SWITCHES: &str = "sSLfikIO";
+
+/// Short flags taking a value, which may be attached (`-XPOST`)
+const WITH_VALUE: &str = "XHdFoumA";
+
+/// Width of the longest bar in the timing breakdown
+const TIMING_BAR: usize = 30;
+
 pub struct CurlCommand;
 
 impl Command for CurlCommand {
@@ -17,7 +45,11 @@ impl Command for CurlCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "curl [-X method] [-H header] [-d data] <url>"
+        "curl [-X method] [-H header] [-d data | --json data | -F field] <url> | curl save|run <name>"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
     }
 
     fn extended_help(&self) -> String {
@@ -25,30 +57,64 @@ impl Command for CurlCommand {
 
 USAGE:
   curl [OPTIONS] <url>
+  curl save <name> [OPTIONS] <url>
+  curl run <name> [OPTIONS]
+  curl list
+  curl delete <name>
 
 OPTIONS:
-  -X, --request <method>   HTTP method (GET, POST, PUT, DELETE, etc.)
-  -H, --header <header>    Add header (format: "Key: Value")
-  -d, --data <data>        Send data in request body
-  -i, --include            Include response headers in output
-  -o, --output <file>      Write output to file
+  -X, --request <method>   HTTP method (GET, POST, PUT, PATCH, DELETE, ...)
+  -H, --header <header>    Add a header ("Name: value"), repeatable
+  -d, --data <data>        Send a form body (repeats are joined with &);
+                           @file reads a file, @- reads piped input
+      --data-raw <data>    Like -d, without the @file rule
+      --json <data>        Send JSON (checked first), with JSON
+                           Content-Type and Accept; also --data-json
+  -F, --form <name=value>  Multipart form field; name=@file uploads a
+                           file (;type=mime to set its type), name=<file
+                           sends a file's contents as the value
+  -u, --user <user:pass>   Basic authentication
+  -A, --user-agent <name>  User-Agent header
+  -o, --output <file>      Write the body to a file
+  -O, --remote-name        Write the body to a file named like the URL
+  -i, --include            Show the response status and headers
+  -I, --head               Send HEAD and show only the headers
+  -f, --fail               Fail on HTTP errors (status 400 and up)
+  -k, --insecure           Accept invalid TLS certificates
+  -m, --max-time <secs>    Give up after this many seconds
+      --timing             Show where the time went
+  -s, -S, -L               Accepted for compatibility (no progress meter
+                           is shown, and redirects are always followed)
 
 DESCRIPTION:
-  Transfer data from or to a server using HTTP/HTTPS.
-  Supports various HTTP methods and custom headers.
+  Sends one request and prints the response body. Without -X the
+  method is POST when there is a body, HEAD with -I, and GET
+  otherwise. URLs without a scheme get http://.
+
+  The timing breakdown shows the DNS lookup, the wait until the
+  response headers arrived (connecting, TLS, and the server's work),
+  and the download of the body.
+
+SAVED REQUESTS:
+  'curl save <name> ...' stores the options and URL under [requests]
+  in config.toml, and 'curl run <name>' sends it again. Options given
+  after the name are added, so headers can be appended and a second
+  URL replaces the saved one. Headers are stored as typed, tokens
+  included.
 
 EXAMPLES:
   curl https://api.github.com           Simple GET request
   curl -i https://httpbin.org/get       Include response headers
-  curl -X POST -d "name=test" URL       POST with form data
+  curl -X POST -d "name=test" URL       POST a form
+  curl --json '{"name":"test"}' URL     POST JSON
+  curl -X PUT --json @user.json URL     PUT JSON from a file
+  curl -F avatar=@me.png URL            Upload a file
   curl -H "Authorization: Bearer TOKEN" URL   With auth header
-  curl -X PUT -d '{"key":"value"}' URL  PUT with JSON data
   curl -o file.zip URL                  Download to file
-
-JSON APIs:
-  curl -H "Content-Type: application/json" \
-       -d '{"name":"test"}' \
-       https://api.example.com/users
+  curl --timing -I https://example.com  Where the time goes
+  curl -s URL | jq '.items[] | .name'   Query a JSON response
+  curl save me -H "Authorization: Bearer TOKEN" https://api.github.com/user
+  curl run me                           Send the saved request
 
 COMMON STATUS CODES:
   200  OK              201  Created
@@ -57,99 +123,797 @@ COMMON STATUS CODES:
   500  Server Error
 
 RELATED COMMANDS:
+  jq       Query JSON responses
   wget     Download files
+  mock     Serve mock API responses
   ping     Check host connectivity
 "#
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
-        let mut method = "GET";
-        let mut headers: Vec<(&str, &str)> = Vec::new();
-        let mut data: Option<&str> = None;
-        let mut url: Option<&str> = None;
-        let mut show_headers = false;
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
 
-        let mut iter = args.iter().peekable();
-        while let Some(arg) = iter.next() {
-            match arg.as_str() {
-                "-X" | "--request" => {
-                    if let Some(m) = iter.next() {
-                        method = m.as_str();
-                    }
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            Some("save") => {
+                let name = saved_name(args, "save <name> [options] <url>")?;
+                let request = Request::parse(&args[2..])?;
+                request.url()?;
+                let mut config = Config::load();
+                config.requests.insert(name.to_string(), args[2..].to_vec());
+                config.save()?;
+                Ok(format!(
+                    "Saved request '{}': {}\nSend it with: curl run {}",
+                    name,
+                    request.summary(),
+                    name
+                ))
+            }
+            Some("run") => {
+                let name = saved_name(args, "run <name> [options]")?;
+                let config = Config::load();
+                let saved = config.requests.get(name).ok_or_else(|| {
+                    anyhow!("curl: no saved request named '{}' (see: curl list)", name)
+                })?;
+                let combined: Vec<String> = saved.iter().chain(&args[2..]).cloned().collect();
+                perform(&Request::parse(&combined)?, stdin, state)
+            }
+            Some("list") if args.len() == 1 => Ok(list_saved(&Config::load())),
+            Some("delete") | Some("rm") => {
+                let name = saved_name(args, "delete <name>")?;
+                let mut config = Config::load();
+                if config.requests.remove(name).is_none() {
+                    return Err(anyhow!("curl: no saved request named '{}'", name));
                 }
-                "-H" | "--header" => {
-                    if let Some(h) = iter.next() {
-                        if let Some((key, value)) = h.split_once(':') {
-                            headers.push((key.trim(), value.trim()));
-                        }
+                config.save()?;
+                Ok(format!("Deleted request '{}'", name))
+            }
+            _ => perform(&Request::parse(args)?, stdin, state),
+        }
+    }
+}
+
+/// The name after `save`, `run`, or `delete`
+fn saved_name<'a>(args: &'a [String], usage: &str) -> Result<&'a str> {
+    args.get(1)
+        .map(|s| s.as_str())
+        .filter(|name| !name.starts_with('-'))
+        .ok_or_else(|| anyhow!("usage: curl {}", usage))
+}
+
+/// `curl list`: each saved request's method and URL
+fn list_saved(config: &Config) -> String {
+    if config.requests.is_empty() {
+        return "No saved requests. Save one with: curl save <name> [options] <url>".to_string();
+    }
+    let mut saved: Vec<_> = config.requests.iter().collect();
+    saved.sort_by(|a, b| a.0.cmp(b.0));
+    let width = saved.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    saved
+        .into_iter()
+        .map(|(name, args)| {
+            let summary = Request::parse(args)
+                .map(|request| request.summary())
+                .unwrap_or_else(|_| args.join(" "));
+            format!("  {:width$}  {}", name, summary, width = width)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A request as given on the command line
+#[derive(Debug, Default)]
+struct Request {
+    method: Option<String>,
+    url: Option<String>,
+    headers: Vec<(String, String)>,
+    /// `-d` values, and whether `@file` is read for each (not for `--data-raw`)
+    data: Vec<(String, bool)>,
+    /// `--json` value
+    json: Option<String>,
+    /// `-F` fields as given (`name=value`, `name=@file`, `name=<file`)
+    form: Vec<String>,
+    user: Option<String>,
+    output: Option<String>,
+    remote_name: bool,
+    include: bool,
+    head: bool,
+    fail: bool,
+    insecure: bool,
+    timing: bool,
+    timeout: Option<f64>,
+}
+
+impl Request {
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut request = Self::default();
+        let mut queue: VecDeque<String> = args.iter().cloned().collect();
+
+        while let Some(arg) = queue.pop_front() {
+            // Split `-sSL` and `-XPOST` into separate arguments
+            if let Some(rest) = arg
+                .strip_prefix('-')
+                .filter(|rest| rest.chars().count() > 1 && !rest.starts_with('-'))
+            {
+                let first = rest.chars().next().unwrap_or_default();
+                if rest.chars().all(|c| SWITCHES.contains(c)) {
+                    for flag in rest.chars().rev() {
+                        queue.push_front(format!("-{}", flag));
                     }
+                    continue;
                 }
-                "-d" | "--data" => {
-                    if let Some(d) = iter.next() {
-                        data = Some(d.as_str());
-                    }
+                if WITH_VALUE.contains(first) {
+                    queue.push_front(rest[first.len_utf8()..].to_string());
+                    queue.push_front(format!("-{}", first));
+                    continue;
                 }
-                "-i" | "--include" => {
-                    show_headers = true;
+            }
+
+            let mut value = || {
+                queue
+                    .pop_front()
+                    .ok_or_else(|| anyhow!("curl: option {} needs a value", arg))
+            };
+            match arg.as_str() {
+                "-X" | "--request" => request.method = Some(value()?.to_uppercase()),
+                "-H" | "--header" => {
+                    let header = value()?;
+                    let (name, value) = header.split_once(':').ok_or_else(|| {
+                        anyhow!("curl: header '{}' needs a colon (Name: value)", header)
+                    })?;
+                    request
+                        .headers
+                        .push((name.trim().to_string(), value.trim().to_string()));
+                }
+                "-d" | "--data" | "--data-ascii" => request.data.push((value()?, true)),
+                "--data-raw" => request.data.push((value()?, false)),
+                "--json" | "--data-json" => request.json = Some(value()?),
+                "-F" | "--form" => request.form.push(value()?),
+                "-u" | "--user" => request.user = Some(value()?),
+                "-A" | "--user-agent" => request.headers.push(("User-Agent".to_string(), value()?)),
+                "-o" | "--output" => request.output = Some(value()?),
+                "-O" | "--remote-name" => request.remote_name = true,
+                "-m" | "--max-time" => {
+                    let seconds = value()?;
+                    request.timeout = Some(
+                        seconds
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|s| *s > 0.0 && s.is_finite())
+                            .ok_or_else(|| anyhow!("curl: invalid --max-time '{}'", seconds))?,
+                    );
                 }
-                _ if !arg.starts_with('-') => {
-                    url = Some(arg.as_str());
+                "-i" | "--include" => request.include = true,
+                "-I" | "--head" => request.head = true,
+                "-f" | "--fail" => request.fail = true,
+                "-k" | "--insecure" => request.insecure = true,
+                "--timing" => request.timing = true,
+                "-s" | "--silent" | "-S" | "--show-error" | "-L" | "--location"
+                | "--compressed" => {}
+                _ if arg.starts_with('-') && arg.len() > 1 => {
+                    return Err(anyhow!("curl: unknown option '{}' (see: help curl)", arg));
                 }
-                _ => {}
+                _ => request.url = Some(arg.clone()),
             }
         }
 
-        let url = url.ok_or_else(|| anyhow::anyhow!("Usage: curl <url>"))?;
+        let bodies = [
+            !request.data.is_empty(),
+            request.json.is_some(),
+            !request.form.is_empty(),
+        ];
+        if bodies.iter().filter(|b| **b).count() > 1 {
+            return Err(anyhow!("curl: use only one of -d, --json, and -F"));
+        }
+        Ok(request)
+    }
 
-        // Build the request
-        let client = reqwest::blocking::Client::new();
+    fn has_body(&self) -> bool {
+        !self.data.is_empty() || self.json.is_some() || !self.form.is_empty()
+    }
 
-        let mut request = match method.to_uppercase().as_str() {
-            "GET" => client.get(url),
-            "POST" => client.post(url),
-            "PUT" => client.put(url),
-            "DELETE" => client.delete(url),
-            "PATCH" => client.patch(url),
-            "HEAD" => client.head(url),
-            _ => return Err(anyhow::anyhow!("Unsupported method: {}", method)),
+    /// The method to send: -X, or what the other options imply
+    fn method(&self) -> String {
+        match &self.method {
+            Some(method) => method.clone(),
+            None if self.head => "HEAD".to_string(),
+            None if self.has_body() => "POST".to_string(),
+            None => "GET".to_string(),
+        }
+    }
+
+    /// The URL, http:// when no scheme is given (like curl)
+    fn url(&self) -> Result<Url> {
+        let url = self
+            .url
+            .as_deref()
+            .ok_or_else(|| anyhow!("curl: no URL given (usage: curl [options] <url>)"))?;
+        let full = if url.contains("://") {
+            url.to_string()
+        } else {
+            format!("http://{}", url)
         };
+        Url::parse(&full).map_err(|e| anyhow!("curl: invalid URL '{}': {}", url, e))
+    }
 
-        // Add headers
-        for (key, value) in headers {
-            request = request.header(key, value);
+    /// `POST https://...`, for listings
+    fn summary(&self) -> String {
+        format!(
+            "{} {}",
+            self.method(),
+            self.url()
+                .map_or_else(|_| "(no URL)".to_string(), |url| url.to_string())
+        )
+    }
+
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    /// The body and its Content-Type, reading any files it names
+    fn body(
+        &self,
+        stdin: Option<&str>,
+        state: &TerminalState,
+    ) -> Result<Option<(Vec<u8>, String)>> {
+        if let Some(json) = &self.json {
+            let bytes = read_value(json, true, stdin, state)?;
+            serde_json::from_slice::<serde_json::Value>(&bytes)
+                .map_err(|e| anyhow!("curl: --json body is not valid JSON: {}", e))?;
+            return Ok(Some((bytes, "application/json".to_string())));
+        }
+        if !self.form.is_empty() {
+            return multipart(&self.form, state).map(Some);
+        }
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let mut parts = Vec::new();
+        for (value, from_file) in &self.data {
+            let mut bytes = read_value(value, *from_file, stdin, state)?;
+            // Like curl, -d @file drops line breaks
+            if *from_file && value.starts_with('@') {
+                bytes.retain(|b| *b != b'\n' && *b != b'\r');
+            }
+            parts.push(bytes);
         }
+        Ok(Some((
+            parts.join(&b'&'),
+            "application/x-www-form-urlencoded".to_string(),
+        )))
+    }
+}
+
+/// A body value: the text itself, or a file's bytes for `@file` (`@-` for
+/// piped input)
+fn read_value(
+    value: &str,
+    from_file: bool,
+    stdin: Option<&str>,
+    state: &TerminalState,
+) -> Result<Vec<u8>> {
+    match value.strip_prefix('@').filter(|_| from_file) {
+        Some("-") => stdin
+            .map(|input| input.as_bytes().to_vec())
+            .ok_or_else(|| anyhow!("curl: @- needs piped input")),
+        Some(file) => read_file(file, state),
+        None => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+fn read_file(file: &str, state: &TerminalState) -> Result<Vec<u8>> {
+    fs::read(state.resolve_path(file)).map_err(|e| anyhow!("curl: can't read {}: {}", file, e))
+}
+
+/// A multipart/form-data body from `-F` fields
+fn multipart(fields: &[String], state: &TerminalState) -> Result<(Vec<u8>, String)> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let boundary = format!("------------------------zaxiom{:x}", nanos);
+    let mut body = Vec::new();
 
-        // Add body
-        if let Some(body) = data {
-            request = request.body(body.to_string());
+    for field in fields {
+        let (name, value) = field
+            .split_once('=')
+            .ok_or_else(|| anyhow!("curl: form field '{}' needs name=value", field))?;
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        if let Some(spec) = value.strip_prefix('@') {
+            let (file, mime) = match spec.split_once(";type=") {
+                Some((file, mime)) => (file, mime),
+                None => (spec, mime_type(spec)),
+            };
+            let filename = Path::new(file)
+                .file_name()
+                .map_or_else(|| file.to_string(), |n| n.to_string_lossy().into_owned());
+            body.extend_from_slice(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                    name, filename, mime
+                )
+                .as_bytes(),
+            );
+            body.extend(read_file(file, state)?);
+        } else {
+            let contents = match value.strip_prefix('<') {
+                Some(file) => read_file(file, state)?,
+                None => value.as_bytes().to_vec(),
+            };
+            body.extend_from_slice(
+                format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name).as_bytes(),
+            );
+            body.extend(contents);
         }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    Ok((body, format!("multipart/form-data; boundary={}", boundary)))
+}
+
+/// Content-Type for an uploaded file, by extension
+fn mime_type(file: &str) -> &'static str {
+    let extension = Path::new(file)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" => "application/json",
+        "txt" | "md" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "js" => "text/javascript",
+        "css" => "text/css",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// What came back, and how long each part took
+struct Exchange {
+    status: StatusCode,
+    version: String,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    /// Resolving the host, when it was timed
+    dns: Option<Duration>,
+    /// Sending until the response headers arrived
+    wait: Duration,
+    /// Reading the body
+    download: Duration,
+    total: Duration,
+}
 
-        // Execute request
-        let response = request
-            .send()
-            .map_err(|e| anyhow::anyhow!("Request failed: {}", e))?;
+/// Send `request` and format the response
+fn perform(request: &Request, stdin: Option<&str>, state: &TerminalState) -> Result<String> {
+    let exchange = send(request, stdin, state)?;
+    if request.fail && exchange.status.as_u16() >= 400 {
+        return Err(anyhow!(
+            "curl: (22) The requested URL returned error: {}",
+            exchange.status.as_u16()
+        ));
+    }
 
-        let mut output = String::new();
+    let theme = (!state.output_piped).then(|| Theme::from_name(state.current_theme));
+    let mut sections = Vec::new();
+    if request.include || request.head {
+        sections.push(format_headers(&exchange, theme.as_ref()));
+    }
 
-        if show_headers {
-            output.push_str(&format!(
-                "HTTP/1.1 {} {}\n",
-                response.status().as_u16(),
-                response.status().as_str()
+    if !request.head {
+        let file = match (&request.output, request.remote_name) {
+            (Some(file), _) => Some(file.clone()),
+            (None, true) => Some(remote_name(&request.url()?)),
+            (None, false) => None,
+        };
+        if let Some(file) = file {
+            fs::write(state.resolve_path(&file), &exchange.body)
+                .map_err(|e| anyhow!("curl: can't write {}: {}", file, e))?;
+            sections.push(format!(
+                "Saved {} ({})",
+                file,
+                format_size(exchange.body.len() as u64)
             ));
-            for (key, value) in response.headers() {
-                output.push_str(&format!("{}: {}\n", key, value.to_str().unwrap_or("")));
+        } else {
+            match String::from_utf8(exchange.body.clone()) {
+                Ok(text) => sections.push(text.trim_end_matches('\n').to_string()),
+                Err(_) if !state.output_piped => {
+                    return Err(anyhow!(
+                        "curl: the response is binary ({}); save it with -o <file>",
+                        format_size(exchange.body.len() as u64)
+                    ))
+                }
+                Err(_) => sections.push(String::from_utf8_lossy(&exchange.body).into_owned()),
             }
-            output.push('\n');
         }
+    }
+
+    if request.timing {
+        sections.push(format_timing(&exchange, theme.as_ref()));
+    }
+    sections.retain(|section| !section.is_empty());
+    Ok(sections.join("\n\n"))
+}
+
+fn send(request: &Request, stdin: Option<&str>, state: &TerminalState) -> Result<Exchange> {
+    let url = request.url()?;
+    let body = request.body(stdin, state)?;
+    let started = Instant::now();
+
+    let mut builder = Client::builder().danger_accept_invalid_certs(request.insecure);
+    if let Some(seconds) = request.timeout {
+        builder = builder.timeout(Duration::from_secs_f64(seconds));
+    }
+
+    // Resolve the host here to time it, and hand the addresses to the client
+    let mut dns = None;
+    if request.timing {
+        if let Some(host) = url.host_str() {
+            let bare = host.trim_start_matches('[').trim_end_matches(']');
+            if bare.parse::<IpAddr>().is_err() {
+                let port = url.port_or_known_default().unwrap_or(80);
+                let lookup = Instant::now();
+                let addrs: Vec<SocketAddr> = (host, port)
+                    .to_socket_addrs()
+                    .map_err(|e| anyhow!("curl: could not resolve host {}: {}", host, e))?
+                    .collect();
+                dns = Some(lookup.elapsed());
+                builder = builder.resolve_to_addrs(host, &addrs);
+            }
+        }
+    }
+
+    let client = builder
+        .build()
+        .map_err(|e| anyhow!("curl: {:#}", anyhow::Error::from(e)))?;
+    let method = request.method();
+    let method = Method::from_bytes(method.as_bytes())
+        .map_err(|_| anyhow!("curl: invalid method '{}'", method))?;
+    let mut builder = client.request(method, url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if request.json.is_some() && !request.has_header("accept") {
+        builder = builder.header(ACCEPT, "application/json");
+    }
+    if let Some((bytes, content_type)) = body {
+        if !request.has_header("content-type") {
+            builder = builder.header(CONTENT_TYPE, content_type);
+        }
+        builder = builder.body(bytes);
+    }
+    if let Some(user) = &request.user {
+        builder = match user.split_once(':') {
+            Some((user, password)) => builder.basic_auth(user, Some(password)),
+            None => builder.basic_auth(user, None::<&str>),
+        };
+    }
+
+    let sent = Instant::now();
+    let response = builder
+        .send()
+        .map_err(|e| anyhow!("curl: {:#}", anyhow::Error::from(e)))?;
+    let wait = sent.elapsed();
+    let status = response.status();
+    let version = format!("{:?}", response.version());
+    let headers = response.headers().clone();
+
+    let reading = Instant::now();
+    let body = response
+        .bytes()
+        .map_err(|e| {
+            anyhow!(
+                "curl: failed to read the response: {:#}",
+                anyhow::Error::from(e)
+            )
+        })?
+        .to_vec();
+
+    Ok(Exchange {
+        status,
+        version,
+        headers,
+        body,
+        dns,
+        wait,
+        download: reading.elapsed(),
+        total: started.elapsed(),
+    })
+}
+
+/// A file name for `-O`: the last part of the URL's path
+fn remote_name(url: &Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("index.html")
+        .to_string()
+}
+
+/// The status line and headers, colored on screen
+fn format_headers(exchange: &Exchange, theme: Option<&Theme>) -> String {
+    let status = format!(
+        "{} {} {}",
+        exchange.version,
+        exchange.status.as_u16(),
+        exchange.status.canonical_reason().unwrap_or("")
+    );
+    let mut lines = vec![match theme {
+        Some(theme) => format!(
+            "\x1b[1m{}{}{}",
+            fg(status_color(exchange.status, theme)),
+            status.trim_end(),
+            RESET
+        ),
+        None => status.trim_end().to_string(),
+    }];
+    for (name, value) in &exchange.headers {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        lines.push(match theme {
+            Some(theme) => format!("{}{}{}: {}", fg(theme.info_color), name, RESET, value),
+            None => format!("{}: {}", name, value),
+        });
+    }
+    lines.join("\n")
+}
+
+/// Success, redirect, client error, and server error colors
+fn status_color(status: StatusCode, theme: &Theme) -> Color32 {
+    if status.is_success() {
+        theme.success_color
+    } else if status.is_redirection() {
+        theme.info_color
+    } else if status.is_client_error() {
+        theme.warning_color
+    } else {
+        theme.error_color
+    }
+}
+
+/// Each phase with a bar to scale, then the total and speed
+fn format_timing(exchange: &Exchange, theme: Option<&Theme>) -> String {
+    let total = exchange.total.as_secs_f64().max(f64::EPSILON);
+    let mut phases = Vec::new();
+    if let Some(dns) = exchange.dns {
+        phases.push(("DNS lookup", dns));
+    }
+    phases.push(("Connect, TLS, server", exchange.wait));
+    phases.push(("Download", exchange.download));
+
+    let mut lines = Vec::new();
+    for (label, duration) in phases {
+        let width = ((duration.as_secs_f64() / total) * TIMING_BAR as f64).round() as usize;
+        let bar = "█".repeat(width.clamp(1, TIMING_BAR));
+        let bar = match theme {
+            Some(theme) => format!("{}{}{}", fg(theme.accent), bar, RESET),
+            None => bar,
+        };
+        lines.push(format!("{:<20} {:>9}  {}", label, millis(duration), bar));
+    }
+    lines.push(format!("{:<20} {:>9}", "Total", millis(exchange.total)));
+
+    let size = exchange.body.len() as u64;
+    let speed = (size as f64 / exchange.download.as_secs_f64().max(0.001)) as u64;
+    lines.push(format!(
+        "{} {} · {} · {}/s",
+        exchange.status.as_u16(),
+        exchange.status.canonical_reason().unwrap_or(""),
+        format_size(size),
+        format_size(speed)
+    ));
+    lines.join("\n")
+}
+
+/// `12.3 ms` or `1.25 s`
+fn millis(duration: Duration) -> String {
+    let ms = duration.as_secs_f64() * 1000.0;
+    if ms >= 1000.0 {
+        format!("{:.2} s", ms / 1000.0)
+    } else {
+        format!("{:.1} ms", ms)
+    }
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, run_stdin, Fixture, MockTerminalState};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    /// Answer `count` requests by echoing each one back as the body
+    fn echo_server(count: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8_lossy(&body));
+
+                let status = if request.contains("/missing") {
+                    "404 Not Found"
+                } else {
+                    "200 OK"
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nX-Echo: yes\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    request.len(),
+                    request
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_curl_parse() {
+        let request =
+            Request::parse(&args(&["-sSL", "-XPATCH", "-Hx-a: 1", "example.com/api"])).unwrap();
+        assert_eq!(request.method(), "PATCH");
+        assert_eq!(request.headers, [("x-a".to_string(), "1".to_string())]);
+        assert_eq!(request.url().unwrap().as_str(), "http://example.com/api");
+
+        assert_eq!(
+            Request::parse(&args(&["-d", "a=1", "u"])).unwrap().method(),
+            "POST"
+        );
+        assert_eq!(
+            Request::parse(&args(&["-I", "u"])).unwrap().method(),
+            "HEAD"
+        );
+        assert_eq!(
+            Request::parse(&args(&["--json", "{}", "-o", "out.json", "u"]))
+                .unwrap()
+                .summary(),
+            "POST http://u/"
+        );
+
+        let fails = |list: &[&str]| Request::parse(&args(list)).unwrap_err().to_string();
+        assert!(fails(&["-H", "no colon", "u"]).contains("needs a colon"));
+        assert!(fails(&["--bogus", "u"]).contains("unknown option '--bogus'"));
+        assert!(fails(&["-d", "a", "-F", "b=c", "u"]).contains("only one of"));
+        assert!(fails(&["u", "-X"]).contains("-X needs a value"));
+        assert!(Request::parse(&[]).unwrap().url().is_err());
+        assert_eq!(
+            remote_name(&Url::parse("https://x.dev/files/app.zip?v=2").unwrap()),
+            "app.zip"
+        );
+    }
+
+    #[test]
+    fn test_curl_requests() {
+        let fixture = Fixture::new("curl")
+            .file("notes.txt", "hello\nworld\n")
+            .file("user.json", r#"{"name": "ada"}"#);
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        state.output_piped = true;
+        let url = echo_server(9);
+        let at = |path: &str| format!("{}{}", url, path);
+
+        let output = run(
+            &CurlCommand,
+            &["-H", "X-Token: abc", &at("/get")],
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.starts_with("GET /get HTTP/1.1"));
+        assert!(output.to_lowercase().contains("x-token: abc"));
+
+        let output = run(
+            &CurlCommand,
+            &["-d", "a=1", "-d", "@notes.txt", &at("/form")],
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.starts_with("POST /form"));
+        assert!(output.contains("application/x-www-form-urlencoded"));
+        assert!(output.ends_with("a=1&helloworld"));
+
+        let output = run(
+            &CurlCommand,
+            &["-X", "PUT", "--json", "@user.json", &at("/u")],
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.starts_with("PUT /u"));
+        assert!(output.contains("application/json"));
+        assert!(output.ends_with(r#"{"name": "ada"}"#));
+        assert!(
+            run(&CurlCommand, &["--json", "{oops", &at("/u")], &mut state)
+                .unwrap_err()
+                .to_string()
+                .contains("not valid JSON")
+        );
+
+        let output = run_stdin(
+            &CurlCommand,
+            &["-d", "@-", &at("/piped")],
+            "from a pipe",
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.ends_with("from a pipe"));
+
+        let output = run(
+            &CurlCommand,
+            &["-F", "title=notes", "-F", "file=@notes.txt", &at("/upload")],
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.contains("multipart/form-data; boundary="));
+        assert!(output.contains("Content-Disposition: form-data; name=\"title\"\r\n\r\nnotes\r\n"));
+        assert!(output.contains(
+            "name=\"file\"; filename=\"notes.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\nworld\n"
+        ));
 
-        let body = response
-            .text()
-            .map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))?;
+        let output = run(&CurlCommand, &["-i", &at("/head")], &mut state).unwrap();
+        assert!(output.starts_with("HTTP/1.1 200 OK\n"));
+        assert!(output.contains("x-echo: yes\n"));
+        assert!(output.contains("\n\nGET /head"));
 
-        output.push_str(&body);
+        let output = run(
+            &CurlCommand,
+            &["-o", "saved.txt", "--timing", &at("/file")],
+            &mut state,
+        )
+        .unwrap();
+        assert!(output.starts_with("Saved saved.txt ("));
+        assert!(output.contains("Connect, TLS, server"));
+        assert!(output.contains("\nTotal"));
+        assert!(fs::read_to_string(fixture.path().join("saved.txt"))
+            .unwrap()
+            .starts_with("GET /file"));
 
-        Ok(output)
+        let error = run(&CurlCommand, &["-f", &at("/missing")], &mut state).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "curl: (22) The requested URL returned error: 404"
+        );
     }
 }
@@ -22,6 +22,10 @@ impl Command for HostCommand {
         "host hostname"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("host: missing hostname"));
@@ -22,6 +22,10 @@ impl Command for IfconfigCommand {
         "ifconfig"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn execute(&self, _args: &[String], _state: &mut TerminalState) -> Result<String> {
         // On Windows, use ipconfig and format output to look more Unix-like
         let output = ProcessCommand::new("ipconfig")
@@ -0,0 +1,500 @@
+//! lanscan command - find live devices on the local network
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::process::{Command as ProcessCommand, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::Result;
+use sysinfo::Networks;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct LanscanCommand;
+
+/// Pings in flight at once
+const WORKERS: usize = 64;
+
+/// Largest subnet scanned; bigger networks scan the /22 around this machine
+const MIN_PREFIX: u8 = 22;
+
+/// Organizationally unique identifiers of common LAN devices
+const VENDORS: &[(&str, &str)] = &[
+    ("00:00:0C", "Cisco"),
+    ("00:03:93", "Apple"),
+    ("00:05:69", "VMware"),
+    ("00:09:5B", "Netgear"),
+    ("00:09:BF", "Nintendo"),
+    ("00:0C:29", "VMware"),
+    ("00:0E:58", "Sonos"),
+    ("00:11:32", "Synology"),
+    ("00:14:6C", "Netgear"),
+    ("00:15:5D", "Microsoft Hyper-V"),
+    ("00:15:6D", "Ubiquiti"),
+    ("00:1B:21", "Intel"),
+    ("00:1B:63", "Apple"),
+    ("00:27:22", "Ubiquiti"),
+    ("00:50:56", "VMware"),
+    ("02:42", "Docker"),
+    ("08:00:27", "VirtualBox"),
+    ("14:CC:20", "TP-Link"),
+    ("20:4E:7F", "Netgear"),
+    ("24:0A:C4", "Espressif"),
+    ("24:6F:28", "Espressif"),
+    ("24:A4:3C", "Ubiquiti"),
+    ("28:CD:C1", "Raspberry Pi"),
+    ("2C:CF:67", "Raspberry Pi"),
+    ("30:AE:A4", "Espressif"),
+    ("3C:5A:B4", "Google"),
+    ("44:65:0D", "Amazon"),
+    ("44:D9:E7", "Ubiquiti"),
+    ("50:C7:BF", "TP-Link"),
+    ("52:54:00", "QEMU/KVM"),
+    ("54:60:09", "Google"),
+    ("5C:AA:FD", "Sonos"),
+    ("74:C2:46", "Amazon"),
+    ("78:8A:20", "Ubiquiti"),
+    ("80:2A:A8", "Ubiquiti"),
+    ("84:F3:EB", "Espressif"),
+    ("94:9F:3E", "Sonos"),
+    ("98:B6:E9", "Nintendo"),
+    ("98:DA:C4", "TP-Link"),
+    ("A0:40:A0", "Netgear"),
+    ("A4:5E:60", "Apple"),
+    ("B8:27:EB", "Raspberry Pi"),
+    ("B8:E9:37", "Sonos"),
+    ("D8:3A:DD", "Raspberry Pi"),
+    ("DC:A6:32", "Raspberry Pi"),
+    ("E4:5F:01", "Raspberry Pi"),
+    ("EC:08:6B", "TP-Link"),
+    ("F0:18:98", "Apple"),
+    ("F0:9F:C2", "Ubiquiti"),
+    ("F0:D2:F1", "Amazon"),
+    ("F4:F2:6D", "TP-Link"),
+    ("F4:F5:D8", "Google"),
+];
+
+/// A device that answered
+struct Device {
+    ip: Ipv4Addr,
+    latency: Option<f64>,
+    hostname: Option<String>,
+    mac: Option<String>,
+}
+
+impl Command for LanscanCommand {
+    fn name(&self) -> &'static str {
+        "lanscan"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find live devices on the local network"
+    }
+
+    fn usage(&self) -> &'static str {
+        "lanscan [subnet/prefix] [-t timeout_ms] [-n]"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"lanscan - Find live devices on the local network
+
+USAGE:
+  lanscan [subnet/prefix] [OPTIONS]
+
+OPTIONS:
+  -t, --timeout <ms>  How long to wait for each reply (default: 500)
+  -n, --no-resolve    Skip hostname lookups
+
+DESCRIPTION:
+  Pings every address of the subnet in parallel and lists the
+  devices that answer, with their hostname (reverse DNS), MAC
+  address and vendor when the system knows them.
+
+  Without a subnet, scans the network of this machine's first
+  private IPv4 address. Networks larger than /22 are limited to
+  the /22 around this machine.
+
+  Devices that ignore ping (some phones and firewalls) won't
+  show up. MAC addresses come from the ARP table, so they're
+  only known for devices on the same network segment; "private"
+  marks a randomized MAC.
+
+EXAMPLES:
+  lanscan                     Scan the local network
+  lanscan 192.168.1.0/24      Scan a specific subnet
+  lanscan -t 200 -n           Faster: short timeout, no DNS
+
+RELATED COMMANDS:
+  ping       Ping one host
+  ifconfig   Show network interfaces
+  netstat    Network connections
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let mut timeout_ms = 500u64;
+        let mut resolve = true;
+        let mut subnet: Option<&String> = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-t" | "--timeout" => {
+                    timeout_ms = iter
+                        .next()
+                        .and_then(|t| t.parse().ok())
+                        .filter(|t| (1..=10_000).contains(t))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("lanscan: {} needs a time in ms (1-10000)", arg)
+                        })?;
+                }
+                "-n" | "--no-resolve" => resolve = false,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ if !arg.starts_with('-') => subnet = Some(arg),
+                _ => return Err(anyhow::anyhow!("lanscan: unknown option '{}'", arg)),
+            }
+        }
+
+        let (own_ip, base, prefix) = match subnet {
+            Some(spec) => {
+                let (ip, prefix) = parse_cidr(spec).ok_or_else(|| {
+                    anyhow::anyhow!("lanscan: expected a subnet like 192.168.1.0/24")
+                })?;
+                if prefix < MIN_PREFIX {
+                    return Err(anyhow::anyhow!(
+                        "lanscan: /{} is too large, use /{} or smaller",
+                        prefix,
+                        MIN_PREFIX
+                    ));
+                }
+                (None, ip, prefix)
+            }
+            None => {
+                let (ip, prefix) = local_network().ok_or_else(|| {
+                    anyhow::anyhow!("lanscan: no private IPv4 network found; pass a subnet")
+                })?;
+                (Some(ip), ip, prefix.max(MIN_PREFIX))
+            }
+        };
+
+        let targets = hosts(base, prefix);
+        let started = Instant::now();
+        let mut devices = ping_all(&targets, timeout_ms);
+
+        let arp = arp_table();
+        for device in &mut devices {
+            device.mac = arp.get(&device.ip).cloned();
+        }
+        if resolve {
+            resolve_all(&mut devices);
+        }
+        devices.sort_by_key(|device| device.ip);
+
+        let network = Ipv4Addr::from(u32::from(base) & mask(prefix));
+        let mut lines = vec![format!(
+            "{} device{} up on {}/{} ({} addresses scanned in {:.1}s)",
+            devices.len(),
+            if devices.len() == 1 { "" } else { "s" },
+            network,
+            prefix,
+            targets.len(),
+            started.elapsed().as_secs_f64()
+        )];
+        if devices.is_empty() {
+            return Ok(lines.remove(0));
+        }
+
+        lines.push(String::new());
+        lines.push(format!(
+            "{:<16} {:<28} {:<18} {:<18} {:>8}",
+            "IP", "HOSTNAME", "MAC", "VENDOR", "LATENCY"
+        ));
+        for device in &devices {
+            let mut hostname = device.hostname.clone().unwrap_or_else(|| "-".to_string());
+            if Some(device.ip) == own_ip {
+                hostname = format!("{} (this device)", hostname);
+            }
+            let vendor = device.mac.as_deref().and_then(vendor).unwrap_or("-");
+            let latency = device
+                .latency
+                .map(|ms| format!("{:.1} ms", ms))
+                .unwrap_or_else(|| "-".to_string());
+            lines.push(format!(
+                "{:<16} {:<28} {:<18} {:<18} {:>8}",
+                device.ip.to_string(),
+                hostname,
+                device.mac.as_deref().unwrap_or("-"),
+                vendor,
+                latency
+            ));
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// "192.168.1.0/24" → (192.168.1.0, 24); a bare address means /24
+fn parse_cidr(spec: &str) -> Option<(Ipv4Addr, u8)> {
+    let (ip, prefix) = match spec.split_once('/') {
+        Some((ip, prefix)) => (ip, prefix.parse().ok()?),
+        None => (spec, 24),
+    };
+    (prefix <= 32).then_some((ip.parse().ok()?, prefix))
+}
+
+fn mask(prefix: u8) -> u32 {
+    u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0)
+}
+
+/// Host addresses of the network containing `ip` (no network or broadcast address)
+fn hosts(ip: Ipv4Addr, prefix: u8) -> Vec<Ipv4Addr> {
+    let network = u32::from(ip) & mask(prefix);
+    let broadcast = network | !mask(prefix);
+    if prefix >= 31 {
+        return (network..=broadcast).map(Ipv4Addr::from).collect();
+    }
+    (network + 1..broadcast).map(Ipv4Addr::from).collect()
+}
+
+/// This machine's first private IPv4 address and its prefix
+fn local_network() -> Option<(Ipv4Addr, u8)> {
+    let networks = Networks::new_with_refreshed_list();
+    let mut candidates: Vec<(Ipv4Addr, u8)> = networks
+        .values()
+        .flat_map(|data| data.ip_networks())
+        .filter_map(|network| match network.addr {
+            std::net::IpAddr::V4(ip) if ip.is_private() => Some((ip, network.prefix)),
+            _ => None,
+        })
+        .collect();
+    // Docker and VM bridges usually live in 172.16/12; prefer home/office ranges
+    candidates.sort_by_key(|(ip, _)| ip.octets()[0] == 172);
+    candidates.into_iter().next()
+}
+
+/// Ping every target, `WORKERS` at a time, keeping the ones that answer
+fn ping_all(targets: &[Ipv4Addr], timeout_ms: u64) -> Vec<Device> {
+    let next = AtomicUsize::new(0);
+    let found = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..WORKERS.min(targets.len()) {
+            scope.spawn(|| {
+                while let Some(&ip) = targets.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if let Some(latency) = ping(ip, timeout_ms) {
+                        if let Ok(mut found) = found.lock() {
+                            found.push(Device {
+                                ip,
+                                latency,
+                                hostname: None,
+                                mac: None,
+                            });
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap_or_default()
+}
+
+/// One echo request; `Some(latency)` if the host answered
+fn ping(ip: Ipv4Addr, timeout_ms: u64) -> Option<Option<f64>> {
+    let mut cmd = ProcessCommand::new("ping");
+    #[cfg(windows)]
+    cmd.args(["-n", "1", "-w", &timeout_ms.to_string()]);
+    #[cfg(target_os = "macos")]
+    cmd.args(["-c", "1", "-W", &timeout_ms.to_string()]);
+    #[cfg(not(any(windows, target_os = "macos")))]
+    cmd.args(["-c", "1", "-W", &timeout_ms.div_ceil(1000).to_string()]);
+
+    let output = cmd
+        .arg(ip.to_string())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Windows ping exits 0 for "Destination host unreachable", so also
+    // require a reply line from the target itself
+    let replied = output.status.success()
+        && stdout
+            .lines()
+            .any(|line| line.contains(&ip.to_string()) && line.to_lowercase().contains("ttl"));
+    replied.then(|| parse_latency(&stdout))
+}
+
+/// Round-trip time from ping output ("time=1.23 ms", "time<1ms")
+fn parse_latency(output: &str) -> Option<f64> {
+    let rest = output.split("time").nth(1)?;
+    let rest = rest.trim_start_matches(['=', '<']);
+    let number: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse().ok()
+}
+
+/// IP → MAC from the system ARP cache
+fn arp_table() -> HashMap<Ipv4Addr, String> {
+    if let Ok(text) = std::fs::read_to_string("/proc/net/arp") {
+        return parse_arp(&text);
+    }
+    ProcessCommand::new("arp")
+        .arg("-a")
+        .stdin(Stdio::null())
+        .output()
+        .map(|output| parse_arp(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Pairs of IPv4 and MAC addresses from `arp -a` or /proc/net/arp
+fn parse_arp(text: &str) -> HashMap<Ipv4Addr, String> {
+    let mut table = HashMap::new();
+    for line in text.lines() {
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let ip = words.iter().find_map(|word| word.parse::<Ipv4Addr>().ok());
+        let mac = words.iter().find_map(|word| normalize_mac(word));
+        if let (Some(ip), Some(mac)) = (ip, mac) {
+            table.insert(ip, mac);
+        }
+    }
+    table
+}
+
+/// "0:1b:63:a-..." forms → "00:1B:63:0A:..."; `None` for non-MACs and
+/// incomplete or broadcast entries
+fn normalize_mac(word: &str) -> Option<String> {
+    let parts: Vec<&str> = word.split([':', '-']).collect();
+    if parts.len() != 6
+        || parts
+            .iter()
+            .any(|p| p.is_empty() || p.len() > 2 || !p.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+    let mac = parts
+        .iter()
+        .map(|p| format!("{:0>2}", p.to_uppercase()))
+        .collect::<Vec<_>>()
+        .join(":");
+    let unusable = mac == "00:00:00:00:00:00" || mac == "FF:FF:FF:FF:FF:FF";
+    (!unusable).then_some(mac)
+}
+
+/// Vendor from the MAC prefix; randomized (locally administered) MACs are "private"
+fn vendor(mac: &str) -> Option<&'static str> {
+    if let Some((_, name)) = VENDORS.iter().find(|(prefix, _)| mac.starts_with(prefix)) {
+        return Some(name);
+    }
+    let first = u8::from_str_radix(mac.get(..2)?, 16).ok()?;
+    (first & 0x02 != 0).then_some("private")
+}
+
+/// Reverse DNS for every device, in parallel
+fn resolve_all(devices: &mut [Device]) {
+    std::thread::scope(|scope| {
+        for chunk in devices.chunks_mut(devices.len().div_ceil(WORKERS).max(1)) {
+            scope.spawn(move || {
+                for device in chunk {
+                    device.hostname = reverse_lookup(device.ip);
+                }
+            });
+        }
+    });
+}
+
+fn reverse_lookup(ip: Ipv4Addr) -> Option<String> {
+    let output = ProcessCommand::new("nslookup")
+        .arg(ip.to_string())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_nslookup(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Hostname from `nslookup <ip>` ("... name = host." or "Name:    host")
+fn parse_nslookup(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let name = line
+            .split_once("name = ")
+            .map(|(_, name)| name)
+            .or_else(|| line.trim().strip_prefix("Name:"))?;
+        let name = name.trim().trim_end_matches('.');
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subnet_hosts() {
+        assert_eq!(
+            parse_cidr("192.168.1.7/24"),
+            Some((Ipv4Addr::new(192, 168, 1, 7), 24))
+        );
+        assert_eq!(parse_cidr("10.0.0.1").map(|(_, p)| p), Some(24));
+        assert_eq!(parse_cidr("10.0.0.1/33"), None);
+
+        let hosts = hosts(Ipv4Addr::new(192, 168, 1, 7), 24);
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts[0], Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(hosts[253], Ipv4Addr::new(192, 168, 1, 254));
+    }
+
+    #[test]
+    fn test_parse_tool_output() {
+        assert_eq!(
+            parse_latency("64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=1.23 ms"),
+            Some(1.23)
+        );
+        assert_eq!(
+            parse_latency("Reply from 10.0.0.1: bytes=32 time<1ms TTL=64"),
+            Some(1.0)
+        );
+
+        let arp = parse_arp(
+            "IP address       HW type     Flags       HW address            Mask     Device\n\
+             192.168.1.1      0x1         0x2         b8:27:eb:12:34:56     *        eth0\n\
+             router (192.168.1.2) at 0:1b:63:a:b:c on en0 ifscope [ethernet]\n  \
+             192.168.1.3           dc-a6-32-00-11-22     dynamic\n\
+             192.168.1.9      0x1         0x0         00:00:00:00:00:00     *        eth0",
+        );
+        assert_eq!(arp.len(), 3);
+        let mac = &arp[&Ipv4Addr::new(192, 168, 1, 2)];
+        assert_eq!(mac, "00:1B:63:0A:0B:0C");
+        assert_eq!(vendor(mac), Some("Apple"));
+        assert_eq!(
+            vendor(&arp[&Ipv4Addr::new(192, 168, 1, 3)]),
+            Some("Raspberry Pi")
+        );
+        assert_eq!(vendor("DA:00:00:00:00:01"), Some("private"));
+
+        assert_eq!(
+            parse_nslookup("1.1.168.192.in-addr.arpa\tname = router.lan.\n"),
+            Some("router.lan".to_string())
+        );
+        assert_eq!(
+            parse_nslookup(
+                "Server:  dns\nAddress:  10.0.0.1\n\nName:    nas.home\nAddress:  10.0.0.5"
+            ),
+            Some("nas.home".to_string())
+        );
+    }
+}
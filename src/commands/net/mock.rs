@@ -0,0 +1,640 @@
+//! mock command - serve canned JSON responses for frontend development
+//!
+//! The server runs on a background thread owned by the pane's state; each
+//! request is logged to a channel the app drains into the pane every frame.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct MockCommand;
+
+/// Largest request head (request line and headers) accepted
+const MAX_HEAD: usize = 64 * 1024;
+
+/// Longest delay a route can ask for
+const MAX_DELAY_MS: u64 = 60_000;
+
+impl Command for MockCommand {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn description(&self) -> &'static str {
+        "Serve mock JSON API responses"
+    }
+
+    fn usage(&self) -> &'static str {
+        "mock [--port N] [--delay ms] <routes.toml> | mock status | mock stop"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"mock - Serve mock JSON API responses
+
+USAGE:
+  mock [OPTIONS] <routes.toml>
+  mock status
+  mock stop
+
+OPTIONS:
+  -p, --port <N>      Port to listen on (default: 9000)
+  -d, --delay <ms>    Latency added to every route without its own
+
+DESCRIPTION:
+  Starts an HTTP server on 127.0.0.1 that answers the routes
+  declared in a TOML file, and logs every request to this pane.
+  The file is re-read when it changes, so edit responses without
+  restarting. One server runs per pane; 'mock stop' ends it.
+
+ROUTES FILE:
+  delay_ms = 100              # default latency (optional)
+  cors = true                 # allow browser calls (default)
+
+  [[route]]
+  method = "GET"              # optional: any method
+  path = "/api/users/:id"     # :name matches a segment, * the rest
+  status = 200                # default 200
+  delay_ms = 300              # latency for this route
+  json = { id = "{id}", name = "Ada" }
+
+  [[route]]
+  method = "POST"
+  path = "/api/login"
+  status = 401
+  body = "bad password"       # plain text instead of json
+  headers = { "X-Reason" = "demo" }
+
+  [[route]]
+  path = "/api/items"
+  file = "items.json"         # relative to the routes file
+
+  "{name}" in a response is replaced with the :name path segment.
+  The first matching route wins; unmatched requests get a 404.
+
+EXAMPLES:
+  mock routes.toml                    Serve on :9000
+  mock --port 3001 --delay 500 api.toml   Slow backend on :3001
+  mock status                         Show the routes being served
+  mock stop                           Stop the server
+
+RELATED COMMANDS:
+  curl       Send requests to the mock
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut port = 9000u16;
+        let mut delay: Option<u64> = None;
+        let mut file: Option<&String> = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-p" | "--port" => {
+                    port = iter
+                        .next()
+                        .and_then(|p| p.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("mock: {} needs a port number", arg))?;
+                }
+                "-d" | "--delay" => {
+                    delay = Some(
+                        iter.next()
+                            .and_then(|d| d.parse().ok())
+                            .filter(|d| *d <= MAX_DELAY_MS)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("mock: {} needs a time in ms (0-60000)", arg)
+                            })?,
+                    );
+                }
+                "-h" | "--help" => return Ok(self.extended_help()),
+                "stop" if file.is_none() => {
+                    return match state.mock_server.take() {
+                        Some(server) => Ok(format!("Stopped mock server on port {}", server.port)),
+                        None => Err(anyhow::anyhow!("mock: no server running in this pane")),
+                    };
+                }
+                "status" if file.is_none() => {
+                    return match &state.mock_server {
+                        Some(server) => {
+                            let mut routes = load_routes(&server.routes_path)?;
+                            if let Some(delay) = server.delay {
+                                routes.delay_ms = delay;
+                            }
+                            Ok(describe(server.port, &server.routes_path, &routes))
+                        }
+                        None => Ok("No mock server running in this pane".to_string()),
+                    };
+                }
+                _ if !arg.starts_with('-') => file = Some(arg),
+                _ => return Err(anyhow::anyhow!("mock: unknown option '{}'", arg)),
+            }
+        }
+
+        let file = file.ok_or_else(|| anyhow::anyhow!("Usage: {}", self.usage()))?;
+        if let Some(server) = &state.mock_server {
+            return Err(anyhow::anyhow!(
+                "mock: already serving on port {} (mock stop first)",
+                server.port
+            ));
+        }
+
+        let path = state.resolve_path(file);
+        let mut routes = load_routes(&path)?;
+        if let Some(delay) = delay {
+            routes.delay_ms = delay;
+        }
+        let server = MockServer::start(path.clone(), port, delay)?;
+        let summary = describe(server.port, &path, &routes);
+        state.mock_server = Some(server);
+
+        Ok(format!(
+            "{}\n\nRequests are logged below; 'mock stop' ends the server.",
+            summary
+        ))
+    }
+}
+
+/// A running mock server; dropping it stops the server
+pub struct MockServer {
+    pub port: u16,
+    routes_path: PathBuf,
+    delay: Option<u64>,
+    stop: Arc<AtomicBool>,
+    log: Receiver<String>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Bind 127.0.0.1:`port` (0 picks a free port) and start serving
+    pub fn start(routes_path: PathBuf, port: u16, delay: Option<u64>) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| anyhow::anyhow!("mock: can't listen on port {}: {}", port, e))?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, log) = channel();
+        let thread = {
+            let stop = stop.clone();
+            let path = routes_path.clone();
+            std::thread::spawn(move || serve(listener, path, delay, stop, tx))
+        };
+
+        Ok(Self {
+            port,
+            routes_path,
+            delay,
+            stop,
+            log,
+            thread: Some(thread),
+        })
+    }
+
+    /// Log lines for requests handled since the last call
+    pub fn drain_log(&self) -> Vec<String> {
+        self.log.try_iter().collect()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Routes {
+    #[serde(default)]
+    delay_ms: u64,
+    #[serde(default = "default_cors")]
+    cors: bool,
+    #[serde(default, rename = "route")]
+    routes: Vec<Route>,
+}
+
+fn default_cors() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct Route {
+    method: Option<String>,
+    path: String,
+    #[serde(default = "default_status")]
+    status: u16,
+    delay_ms: Option<u64>,
+    json: Option<toml::Value>,
+    body: Option<String>,
+    file: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+fn load_routes(path: &Path) -> Result<Routes> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("mock: can't read {}: {}", path.display(), e))?;
+    let routes: Routes =
+        toml::from_str(&text).map_err(|e| anyhow::anyhow!("mock: {}: {}", path.display(), e))?;
+    if routes.routes.is_empty() {
+        return Err(anyhow::anyhow!(
+            "mock: {} has no [[route]] entries",
+            path.display()
+        ));
+    }
+    Ok(routes)
+}
+
+fn describe(port: u16, path: &Path, routes: &Routes) -> String {
+    let mut lines = vec![format!(
+        "Mock server on http://127.0.0.1:{} ({})",
+        port,
+        path.display()
+    )];
+    for route in &routes.routes {
+        let delay = route.delay_ms.unwrap_or(routes.delay_ms);
+        lines.push(format!(
+            "  {:<7} {:<32} {}{}",
+            route.method.as_deref().unwrap_or("*").to_uppercase(),
+            route.path,
+            route.status,
+            if delay > 0 {
+                format!("  +{}ms", delay)
+            } else {
+                String::new()
+            }
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Accept loop; polls the stop flag between connections
+fn serve(
+    listener: TcpListener,
+    path: PathBuf,
+    delay: Option<u64>,
+    stop: Arc<AtomicBool>,
+    log: Sender<String>,
+) {
+    let mut routes: Option<Arc<Routes>> = None;
+    let mut loaded_at: Option<SystemTime> = None;
+
+    while !stop.load(Ordering::Relaxed) {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(50));
+                continue;
+            }
+            Err(_) => continue,
+        };
+
+        // Pick up edits to the routes file
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if routes.is_none() || modified != loaded_at {
+            match load_routes(&path) {
+                Ok(mut loaded) => {
+                    if routes.is_some() {
+                        let _ = log.send(format!("mock: reloaded {}", path.display()));
+                    }
+                    if let Some(delay) = delay {
+                        loaded.delay_ms = delay;
+                    }
+                    routes = Some(Arc::new(loaded));
+                }
+                Err(e) => {
+                    let _ = log.send(format!("\x1b[31m{}\x1b[0m", e));
+                }
+            }
+            loaded_at = modified;
+        }
+
+        if let Some(routes) = routes.clone() {
+            let log = log.clone();
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            std::thread::spawn(move || {
+                if let Some(line) = handle(stream, &routes, &dir) {
+                    let _ = log.send(line);
+                }
+            });
+        }
+    }
+}
+
+/// Answer one connection; returns its log line
+fn handle(stream: TcpStream, routes: &Routes, dir: &Path) -> Option<String> {
+    let started = Instant::now();
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).ok()?;
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_uppercase();
+    let target = parts.next()?.to_string();
+
+    let mut content_length = 0usize;
+    let mut head = request_line.len();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 || line.trim().is_empty() {
+            break;
+        }
+        head += line.len();
+        if head > MAX_HEAD {
+            return None;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    // Drain the body so the client sees a clean response
+    let _ = std::io::copy(
+        &mut reader.by_ref().take(content_length as u64),
+        &mut std::io::sink(),
+    );
+
+    let path = target.split('?').next().unwrap_or("/");
+    let response = respond(routes, &method, path, dir);
+    if response.delay_ms > 0 {
+        std::thread::sleep(Duration::from_millis(response.delay_ms.min(MAX_DELAY_MS)));
+    }
+
+    let mut stream = stream;
+    let _ = stream.write_all(&response.to_http(routes.cors));
+    let _ = stream.flush();
+
+    let color = match response.status {
+        200..=299 => "\x1b[32m",
+        300..=399 => "\x1b[36m",
+        400..=499 => "\x1b[33m",
+        _ => "\x1b[31m",
+    };
+    Some(format!(
+        "{} {:<7} {} → {}{}\x1b[0m ({} ms)",
+        chrono::Local::now().format("%H:%M:%S"),
+        method,
+        target,
+        color,
+        response.status,
+        started.elapsed().as_millis()
+    ))
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    delay_ms: u64,
+    content_type: &'static str,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Response {
+    fn to_http(&self, cors: bool) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        );
+        if cors {
+            head.push_str(
+                "Access-Control-Allow-Origin: *\r\n\
+                 Access-Control-Allow-Methods: GET, POST, PUT, PATCH, DELETE, OPTIONS\r\n\
+                 Access-Control-Allow-Headers: *\r\n",
+            );
+        }
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(self.body.as_bytes());
+        bytes
+    }
+}
+
+/// Build the response for a request from the first matching route
+fn respond(routes: &Routes, method: &str, path: &str, dir: &Path) -> Response {
+    let matched = routes.routes.iter().find_map(|route| {
+        let method_ok = route
+            .method
+            .as_ref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(method));
+        if !method_ok {
+            return None;
+        }
+        match_path(&route.path, path).map(|params| (route, params))
+    });
+
+    let Some((route, params)) = matched else {
+        if routes.cors && method == "OPTIONS" {
+            // CORS preflight for any path
+            return Response {
+                status: 204,
+                delay_ms: 0,
+                content_type: "text/plain",
+                headers: Vec::new(),
+                body: String::new(),
+            };
+        }
+        return Response {
+            status: 404,
+            delay_ms: routes.delay_ms,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: serde_json::json!({ "error": format!("no mock route for {} {}", method, path) })
+                .to_string(),
+        };
+    };
+
+    let (content_type, body) = if let Some(json) = &route.json {
+        (
+            "application/json",
+            serde_json::to_string_pretty(json).unwrap_or_default(),
+        )
+    } else if let Some(file) = &route.file {
+        let content_type = if file.ends_with(".json") {
+            "application/json"
+        } else {
+            "text/plain; charset=utf-8"
+        };
+        let body = std::fs::read_to_string(dir.join(file))
+            .unwrap_or_else(|e| format!("mock: can't read {}: {}", file, e));
+        (content_type, body)
+    } else {
+        (
+            "text/plain; charset=utf-8",
+            route.body.clone().unwrap_or_default(),
+        )
+    };
+
+    let body = params.iter().fold(body, |body, (name, value)| {
+        body.replace(&format!("{{{}}}", name), value)
+    });
+
+    Response {
+        status: route.status,
+        delay_ms: route.delay_ms.unwrap_or(routes.delay_ms),
+        content_type,
+        headers: route
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        body,
+    }
+}
+
+/// Match "/users/:id/*" style patterns; returns the named segments
+fn match_path(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let mut pattern_parts = pattern.split('/').filter(|p| !p.is_empty());
+    let mut path_parts = path.split('/').filter(|p| !p.is_empty());
+    let mut params = Vec::new();
+
+    loop {
+        match (pattern_parts.next(), path_parts.next()) {
+            (None, None) => return Some(params),
+            (Some("*"), _) => return Some(params),
+            (Some(expected), Some(actual)) => {
+                if let Some(name) = expected.strip_prefix(':') {
+                    params.push((name.to_string(), actual.to_string()));
+                } else if expected != actual {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTES: &str = r#"
+delay_ms = 5
+
+[[route]]
+method = "GET"
+path = "/api/users/:id"
+json = { id = "{id}", name = "Ada" }
+
+[[route]]
+method = "POST"
+path = "/api/login"
+status = 401
+delay_ms = 0
+body = "nope"
+headers = { "X-Reason" = "demo" }
+"#;
+
+    #[test]
+    fn test_routes_and_matching() {
+        assert_eq!(
+            match_path("/users/:id", "/users/7/"),
+            Some(vec![("id".to_string(), "7".to_string())])
+        );
+        assert_eq!(match_path("/static/*", "/static/a/b.css"), Some(vec![]));
+        assert_eq!(match_path("/users/:id", "/users"), None);
+
+        let routes: Routes = toml::from_str(ROUTES).unwrap();
+        let user = respond(&routes, "GET", "/api/users/42", Path::new("."));
+        assert_eq!(user.status, 200);
+        assert_eq!(user.delay_ms, 5);
+        assert!(user.body.contains("\"id\": \"42\""));
+
+        let login = respond(&routes, "POST", "/api/login", Path::new("."));
+        assert_eq!((login.status, login.delay_ms), (401, 0));
+        assert_eq!(login.headers, vec![("X-Reason".into(), "demo".into())]);
+
+        assert_eq!(
+            respond(&routes, "GET", "/api/login", Path::new(".")).status,
+            404
+        );
+        assert_eq!(
+            respond(&routes, "OPTIONS", "/x", Path::new(".")).status,
+            204
+        );
+    }
+
+    #[test]
+    fn test_server_answers_and_logs() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-mock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("routes.toml");
+        std::fs::write(&path, ROUTES).unwrap();
+
+        let server = MockServer::start(path, 0, Some(0)).unwrap();
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+        stream
+            .write_all(b"GET /api/users/1?x=y HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Access-Control-Allow-Origin: *"));
+        assert!(response.contains("\"name\": \"Ada\""));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut log = Vec::new();
+        while log.is_empty() && Instant::now() < deadline {
+            log = server.drain_log();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(log[0].contains("GET") && log[0].contains("/api/users/1?x=y"));
+
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,21 +1,53 @@
 //! Network commands
 //!
-//! curl, wget, ping, netstat, traceroute, nslookup, host, ifconfig
+//! curl, wget, scrape, ping, lanscan, speedtest, netstat, traceroute, nslookup, host, ifconfig,
+//! whois, tlscert, mock, ssh
 
 mod curl;
 mod host;
 mod ifconfig;
+mod lanscan;
+mod mock;
 mod netstat;
 mod nslookup;
 mod ping;
+mod scrape;
+mod speedtest;
+mod ssh;
+mod tlscert;
 mod traceroute;
 mod wget;
+mod whois;
 
 pub use curl::CurlCommand;
 pub use host::HostCommand;
 pub use ifconfig::IfconfigCommand;
+pub use lanscan::LanscanCommand;
+pub use mock::{MockCommand, MockServer};
 pub use netstat::NetstatCommand;
 pub use nslookup::NslookupCommand;
 pub use ping::PingCommand;
+pub use scrape::ScrapeCommand;
+pub use speedtest::SpeedtestCommand;
+pub use ssh::SshCommand;
+pub use tlscert::TlscertCommand;
 pub use traceroute::TracerouteCommand;
 pub use wget::WgetCommand;
+pub use whois::WhoisCommand;
+
+use chrono::{DateTime, Utc};
+
+/// "expires in N days" for a certificate or domain, yellow within a month
+/// and red once expired
+fn expiry_status(expires: DateTime<Utc>) -> String {
+    let now = Utc::now();
+    if expires < now {
+        return format!(
+            "\x1b[31mexpired {} days ago\x1b[0m",
+            (now - expires).num_days()
+        );
+    }
+    let days = (expires - now).num_days();
+    let color = if days <= 30 { "\x1b[33m" } else { "\x1b[32m" };
+    format!("{}expires in {} days\x1b[0m", color, days)
+}
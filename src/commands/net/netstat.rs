@@ -21,6 +21,10 @@ impl Command for NetstatCommand {
         "netstat [-a] [-n] [-o] [-p protocol]"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
         let mut show_all = false;
         let mut numeric = false;
@@ -33,11 +37,9 @@ impl Command for NetstatCommand {
                 "-a" | "--all" => show_all = true,
                 "-n" | "--numeric" => numeric = true,
                 "-o" | "--timers" => show_pid = true,
-                "-p" | "--protocol" => {
-                    if i + 1 < args.len() {
-                        protocol = Some(&args[i + 1]);
-                        i += 1;
-                    }
+                "-p" | "--protocol" if i + 1 < args.len() => {
+                    protocol = Some(&args[i + 1]);
+                    i += 1;
                 }
                 "-h" | "--help" => {
                     return Ok("Usage: netstat [OPTIONS]\n\
@@ -1,8 +1,10 @@
 //! ping command - send ICMP echo requests
 
 use anyhow::Result;
-use std::process::Command as ProcessCommand;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command as ProcessCommand, Stdio};
 
+use crate::commands::context::{CommandContext, Interrupted};
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -21,6 +23,10 @@ impl Command for PingCommand {
         "ping [-c count] [-t] <host>"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn extended_help(&self) -> String {
         r#"ping - Test network connectivity
 
@@ -62,7 +68,17 @@ RELATED COMMANDS:
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        _state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
         let mut count: Option<u32> = None;
         let mut continuous = false;
         let mut host: Option<&String> = None;
@@ -70,11 +86,9 @@ RELATED COMMANDS:
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-c" | "--count" => {
-                    if i + 1 < args.len() {
-                        count = args[i + 1].parse().ok();
-                        i += 1;
-                    }
+                "-c" | "--count" if i + 1 < args.len() => {
+                    count = args[i + 1].parse().ok();
+                    i += 1;
                 }
                 "-t" => continuous = true,
                 "-h" | "--help" => {
@@ -104,15 +118,39 @@ RELATED COMMANDS:
 
         cmd.arg(host);
 
-        let output = cmd.output().map_err(|e| anyhow::anyhow!("ping: {}", e))?;
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("ping: {}", e))?;
+
+        // One reply per line, shown as it arrives when streaming
+        let mut stdout = Vec::new();
+        let replies = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        for line in replies.lines() {
+            if ctx.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Interrupted.into());
+            }
+            let line = line.map_err(|e| anyhow::anyhow!("ping: {}", e))?;
+            if ctx.is_streaming() {
+                ctx.print(&line);
+            } else {
+                stdout.push(line);
+            }
+        }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        let status = child.wait().map_err(|e| anyhow::anyhow!("ping: {}", e))?;
 
-        if !output.status.success() && !stderr.is_empty() {
+        if !status.success() && !stderr.is_empty() {
             return Err(anyhow::anyhow!("ping: {}", stderr.trim()));
         }
 
-        Ok(stdout.to_string())
+        Ok(stdout.join("\n"))
     }
 }
@@ -0,0 +1,324 @@
+//! speedtest command - measure latency and download/upload throughput
+
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use reqwest::blocking::Client;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct SpeedtestCommand;
+
+/// Cloudflare's speed test endpoints (no API key, served from the nearest colo)
+const SERVER: &str = "https://speed.cloudflare.com";
+
+/// First transfer size; each next one is four times bigger
+const START_BYTES: usize = 1 << 20;
+
+/// Largest single transfer
+const MAX_BYTES: usize = 100 << 20;
+
+/// Latency samples
+const PINGS: usize = 10;
+
+/// Width of the throughput bars
+const BAR_WIDTH: usize = 30;
+
+/// One timed transfer
+struct Sample {
+    bytes: usize,
+    elapsed: Duration,
+}
+
+impl Sample {
+    fn mbps(&self) -> f64 {
+        mbps(self.bytes, self.elapsed)
+    }
+}
+
+impl Command for SpeedtestCommand {
+    fn name(&self) -> &'static str {
+        "speedtest"
+    }
+
+    fn description(&self) -> &'static str {
+        "Measure internet latency and speed"
+    }
+
+    fn usage(&self) -> &'static str {
+        "speedtest [--quick] [--no-download] [--no-upload]"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"speedtest - Measure internet latency and speed
+
+USAGE:
+  speedtest [OPTIONS]
+
+OPTIONS:
+  -q, --quick       Spend about 3s per direction instead of 8s
+  --no-download     Skip the download test
+  --no-upload       Skip the upload test
+
+DESCRIPTION:
+  Measures round-trip latency and jitter, then downloads and
+  uploads increasingly large payloads until a transfer takes
+  long enough to be reliable. Uses speed.cloudflare.com, which
+  answers from the nearest Cloudflare data center.
+
+  Each transfer is listed with its size and speed, followed
+  by a summary. Speeds are in megabits per second.
+
+  A full run moves up to a few hundred megabytes - mind
+  metered connections, or use --quick.
+
+EXAMPLES:
+  speedtest                  Full test
+  speedtest --quick          Shorter test, less data
+  speedtest --no-upload      Download and latency only
+
+RELATED COMMANDS:
+  ping       Latency to one host
+  lanscan    Devices on the local network
+  curl       HTTP requests
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let mut budget = Duration::from_secs(8);
+        let mut download = true;
+        let mut upload = true;
+
+        for arg in args {
+            match arg.as_str() {
+                "-q" | "--quick" => budget = Duration::from_secs(3),
+                "--no-download" => download = false,
+                "--no-upload" => upload = false,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ => return Err(anyhow::anyhow!("speedtest: unknown option '{}'", arg)),
+            }
+        }
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .user_agent(concat!("zaxiom/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        // Warm up the connection so latency doesn't include the TLS handshake
+        let response = client
+            .get(format!("{}/__down?bytes=0", SERVER))
+            .send()
+            .map_err(|e| anyhow::anyhow!("speedtest: can't reach {}: {}", SERVER, e))?;
+        let colo = response
+            .headers()
+            .get("cf-meta-colo")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let mut pings = Vec::with_capacity(PINGS);
+        for _ in 0..PINGS {
+            let started = Instant::now();
+            client
+                .get(format!("{}/__down?bytes=0", SERVER))
+                .send()
+                .and_then(|r| r.bytes())
+                .map_err(|e| anyhow::anyhow!("speedtest: {}", e))?;
+            pings.push(started.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        let downloads = if download {
+            ramp_up(budget, |bytes| download_once(&client, bytes))?
+        } else {
+            Vec::new()
+        };
+        let uploads = if upload {
+            ramp_up(budget, |bytes| upload_once(&client, bytes))?
+        } else {
+            Vec::new()
+        };
+
+        let down = downloads.last().map(Sample::mbps);
+        let up = uploads.last().map(Sample::mbps);
+        let scale = down.unwrap_or(0.0).max(up.unwrap_or(0.0));
+
+        let server = match colo {
+            Some(colo) => format!("speed.cloudflare.com ({})", colo),
+            None => "speed.cloudflare.com".to_string(),
+        };
+        let mut lines = vec![format!("Server    {}", server), String::new()];
+
+        for (label, samples) in [("Download", &downloads), ("Upload", &uploads)] {
+            for sample in samples {
+                lines.push(format!(
+                    "{:<9} {:>7}  {}  {:>8.1} Mbps  {:.2}s",
+                    label,
+                    format_size(sample.bytes),
+                    bar(sample.mbps(), scale),
+                    sample.mbps(),
+                    sample.elapsed.as_secs_f64()
+                ));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("─".repeat(48));
+        lines.push(format!(
+            "Latency   {:.1} ms  (jitter {:.1} ms)",
+            median(&pings),
+            jitter(&pings)
+        ));
+        if let Some(down) = down {
+            lines.push(format!("Download  {:.1} Mbps", down));
+        }
+        if let Some(up) = up {
+            lines.push(format!("Upload    {:.1} Mbps", up));
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Transfer bigger and bigger payloads until one takes a quarter of the
+/// budget, the budget runs out, or the size limit is reached
+fn ramp_up(
+    budget: Duration,
+    mut transfer: impl FnMut(usize) -> Result<Sample>,
+) -> Result<Vec<Sample>> {
+    let started = Instant::now();
+    let mut samples = Vec::new();
+    let mut bytes = START_BYTES;
+
+    loop {
+        let sample = transfer(bytes)?;
+        let long_enough = sample.elapsed >= budget / 4;
+        samples.push(sample);
+        if long_enough || started.elapsed() >= budget || bytes >= MAX_BYTES {
+            return Ok(samples);
+        }
+        bytes = (bytes * 4).min(MAX_BYTES);
+    }
+}
+
+fn download_once(client: &Client, bytes: usize) -> Result<Sample> {
+    let started = Instant::now();
+    let mut response = client
+        .get(format!("{}/__down?bytes={}", SERVER, bytes))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| anyhow::anyhow!("speedtest: download failed: {}", e))?;
+
+    let mut received = 0;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let n = response
+            .read(&mut buffer)
+            .map_err(|e| anyhow::anyhow!("speedtest: download failed: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        received += n;
+    }
+
+    Ok(Sample {
+        bytes: received,
+        elapsed: started.elapsed(),
+    })
+}
+
+fn upload_once(client: &Client, bytes: usize) -> Result<Sample> {
+    let body = vec![0u8; bytes];
+    let started = Instant::now();
+    client
+        .post(format!("{}/__up", SERVER))
+        .body(body)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| anyhow::anyhow!("speedtest: upload failed: {}", e))?;
+
+    Ok(Sample {
+        bytes,
+        elapsed: started.elapsed(),
+    })
+}
+
+fn mbps(bytes: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    bytes as f64 * 8.0 / seconds / 1_000_000.0
+}
+
+/// Bar filled in proportion to `value` out of `max`
+fn bar(value: f64, max: f64) -> String {
+    let filled = if max > 0.0 {
+        ((value / max) * BAR_WIDTH as f64).round() as usize
+    } else {
+        0
+    };
+    let filled = filled.min(BAR_WIDTH);
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{} MB", bytes >> 20)
+    } else {
+        format!("{} KB", bytes >> 10)
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+        n => sorted[n / 2],
+    }
+}
+
+/// Mean difference between consecutive samples
+fn jitter(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let total: f64 = values.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+    total / (values.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_math() {
+        assert_eq!(mbps(1_000_000, Duration::from_secs(1)), 8.0);
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
+        assert_eq!(jitter(&[10.0, 12.0, 11.0]), 1.5);
+        assert_eq!(bar(50.0, 100.0).chars().filter(|c| *c == '█').count(), 15);
+        assert_eq!(bar(1.0, 0.0).chars().count(), BAR_WIDTH);
+        assert_eq!(format_size(4 << 20), "4 MB");
+    }
+
+    #[test]
+    fn test_ramp_up_grows_until_slow() {
+        let mut sizes = Vec::new();
+        let samples = ramp_up(Duration::from_secs(8), |bytes| {
+            sizes.push(bytes);
+            Ok(Sample {
+                bytes,
+                // 8 MB/s, so the 16 MB transfer takes two seconds
+                elapsed: Duration::from_secs_f64(bytes as f64 / (8 << 20) as f64),
+            })
+        })
+        .unwrap();
+        assert_eq!(sizes, vec![1 << 20, 4 << 20, 16 << 20]);
+        assert_eq!(samples.len(), 3);
+    }
+}
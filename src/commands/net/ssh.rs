@@ -0,0 +1,52 @@
+//! ssh command - remote shells and commands
+//!
+//! On its own line, `ssh host` opens an interactive session in the pane's
+//! terminal grid (see `pty::ssh`); the executor starts that directly. This
+//! command handles the rest: `ssh host command` in pipelines and scripts,
+//! run without a terminal.
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::pty::ssh::{self, SshTarget};
+use crate::terminal::state::TerminalState;
+
+pub struct SshCommand;
+
+impl Command for SshCommand {
+    fn name(&self) -> &'static str {
+        "ssh"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open a remote shell over SSH"
+    }
+
+    fn usage(&self) -> &'static str {
+        "ssh [-p port] [-i key] [-l user] [user@]host [command...]\n\n\
+         On its own line, opens an interactive shell on the remote host in\n\
+         this pane; the tab is titled user@host until it exits. Logs in with\n\
+         the -i key, else ssh-agent and ~/.ssh/id_ed25519, id_ecdsa, id_rsa\n\
+         (asking for passphrases), else a password. New host keys are\n\
+         confirmed and saved to ~/.ssh/known_hosts; changed ones are refused.\n\n\
+         With a command, runs it remotely and prints its output. Piped or in\n\
+         a script, nothing can be asked, so the host must already be known\n\
+         and the login must need no password.\n\n\
+         Examples:\n  \
+         ssh pi@raspberrypi\n  \
+         ssh -p 2222 -i ~/.ssh/work deploy@web1\n  \
+         ssh web1 df -h | grep /var"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(format!("Usage: {}", self.usage()));
+        }
+        let target = SshTarget::parse(args, &ssh::default_user())?;
+        ssh::run_command(&target, state.home())
+    }
+}
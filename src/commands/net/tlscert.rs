@@ -0,0 +1,601 @@
+//! tlscert command - inspect a server's TLS certificate chain
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+use super::expiry_status;
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct TlscertCommand;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+impl Command for TlscertCommand {
+    fn name(&self) -> &'static str {
+        "tlscert"
+    }
+
+    fn description(&self) -> &'static str {
+        "Inspect a server's TLS certificates"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tlscert <host[:port]>"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"tlscert - Inspect a server's TLS certificates
+
+USAGE:
+  tlscert <host[:port]>
+
+DESCRIPTION:
+  Connects to the server (port 443 by default) and shows the
+  certificate chain it sends: subject, issuer, validity with
+  days remaining, subject alternative names, serial, signature
+  algorithm and SHA-256 fingerprint.
+
+  The chain is shown even when it's broken, and "Trusted" says
+  whether the system's trust store accepts it for that host
+  (and why not, if it doesn't).
+
+EXAMPLES:
+  tlscert example.com              Check a website
+  tlscert mail.example.com:993     An IMAPS server
+  tlscert https://example.com/x    URLs work too
+  tlscert 10.0.0.5:8443            An internal service
+
+RELATED COMMANDS:
+  whois      Domain registration and expiry
+  curl       HTTP requests
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let target = match args.first().map(String::as_str) {
+            None => return Err(anyhow::anyhow!("Usage: {}", self.usage())),
+            Some("-h" | "--help") => return Ok(self.extended_help()),
+            Some(target) => target,
+        };
+        let (host, port) = parse_target(target)
+            .ok_or_else(|| anyhow::anyhow!("tlscert: expected host[:port], got '{}'", target))?;
+
+        let handshake = fetch_chain(&host, port)?;
+        let trusted = match check_trust(&host, port) {
+            Ok(()) => "\x1b[32myes\x1b[0m (system trust store)".to_string(),
+            Err(e) => format!("\x1b[31mno\x1b[0m - {}", e),
+        };
+
+        let mut lines = vec![
+            format!("Host      {}:{}", host, port),
+            format!("Protocol  {}", handshake.protocol),
+            format!("Cipher    {}", handshake.cipher),
+            format!("Trusted   {}", trusted),
+        ];
+
+        for (i, der) in handshake.chain.iter().enumerate() {
+            lines.push(String::new());
+            let Some(cert) = Certificate::parse(der) else {
+                lines.push(format!(
+                    "[{}] (unreadable certificate, {} bytes)",
+                    i,
+                    der.len()
+                ));
+                continue;
+            };
+            let title = cert
+                .subject
+                .iter()
+                .find(|(key, _)| *key == "CN")
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| format_name(&cert.subject));
+            lines.push(format!("[{}] {}", i, title));
+            lines.push(format!("    Subject    {}", format_name(&cert.subject)));
+            lines.push(format!("    Issuer     {}", format_name(&cert.issuer)));
+            lines.push(format!(
+                "    Valid      {} → {}  ({})",
+                cert.not_before.format("%Y-%m-%d"),
+                cert.not_after.format("%Y-%m-%d"),
+                expiry_status(cert.not_after)
+            ));
+            if !cert.san.is_empty() {
+                lines.push(format!("    SAN        {}", cert.san.join(", ")));
+            }
+            lines.push(format!("    Serial     {}", colon_hex(&cert.serial)));
+            lines.push(format!("    Algorithm  {}", cert.signature_algorithm));
+            lines.push(format!(
+                "    SHA-256    {}",
+                colon_hex(&Sha256::digest(der))
+            ));
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// "example.com", "example.com:8443", "https://example.com/path", "[::1]:443"
+fn parse_target(target: &str) -> Option<(String, u16)> {
+    let rest = target.split_once("://").map_or(target, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+
+    if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, port) = bracketed.split_once(']')?;
+        let port = match port.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None => 443,
+        };
+        return Some((host.to_string(), port));
+    }
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (host, port.parse().ok()?),
+        _ => (authority, 443),
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+fn connect(host: &str, port: u16) -> Result<TcpStream> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| anyhow::anyhow!("tlscert: can't resolve {}: {}", host, e))?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("tlscert: can't resolve {}", host))?;
+    let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+        .map_err(|e| anyhow::anyhow!("tlscert: can't connect to {}:{}: {}", host, port, e))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    Ok(stream)
+}
+
+struct Handshake {
+    protocol: String,
+    cipher: String,
+    chain: Vec<CertificateDer<'static>>,
+}
+
+/// Handshake that accepts any certificate, so broken chains can be inspected
+fn fetch_chain(host: &str, port: u16) -> Result<Handshake> {
+    let provider = Arc::new(crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+        .with_no_client_auth();
+
+    let name = ServerName::try_from(host.to_string())
+        .map_err(|_| anyhow::anyhow!("tlscert: invalid host name '{}'", host))?;
+    let mut conn = ClientConnection::new(Arc::new(config), name)?;
+    let mut stream = connect(host, port)?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream)
+            .map_err(|e| anyhow::anyhow!("tlscert: TLS handshake failed: {}", e))?;
+    }
+
+    let chain = conn
+        .peer_certificates()
+        .map(|certs| certs.iter().map(|c| c.clone().into_owned()).collect())
+        .unwrap_or_default();
+    let protocol = conn
+        .protocol_version()
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .replace('_', ".")
+        .replace("TLSv1.", "TLS 1.");
+    let cipher = conn
+        .negotiated_cipher_suite()
+        .and_then(|suite| suite.suite().as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(Handshake {
+        protocol,
+        cipher,
+        chain,
+    })
+}
+
+/// Verify the chain and host name against the system trust store
+fn check_trust(host: &str, port: u16) -> Result<()> {
+    let connector = native_tls::TlsConnector::new()?;
+    let stream = connect(host, port)?;
+    connector
+        .connect(host, stream)
+        .map(|_| ())
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// The X.509 fields shown by tlscert
+struct Certificate {
+    serial: Vec<u8>,
+    signature_algorithm: &'static str,
+    issuer: Vec<(&'static str, String)>,
+    subject: Vec<(&'static str, String)>,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+    san: Vec<String>,
+}
+
+impl Certificate {
+    /// Parse the DER encoding (RFC 5280)
+    fn parse(der: &[u8]) -> Option<Self> {
+        let (certificate, _) = Der::read(der)?;
+        let mut fields = certificate.children();
+        let mut tbs = fields.next()?.children();
+
+        let mut item = tbs.next()?;
+        if item.tag == 0xa0 {
+            // [0] explicit version
+            item = tbs.next()?;
+        }
+        // A serial with its top bit set has a 0x00 in front to keep it positive
+        let serial = match item.value {
+            [0, rest @ ..] if !rest.is_empty() => rest.to_vec(),
+            value => value.to_vec(),
+        };
+        let signature_algorithm = algorithm_name(tbs.next()?.children().next()?.value);
+        let issuer = parse_name(tbs.next()?);
+        let mut validity = tbs.next()?.children();
+        let not_before = parse_time(validity.next()?)?;
+        let not_after = parse_time(validity.next()?)?;
+        let subject = parse_name(tbs.next()?);
+
+        let mut san = Vec::new();
+        for item in tbs {
+            // [3] explicit extensions
+            if item.tag != 0xa3 {
+                continue;
+            }
+            for extension in item.children().next()?.children() {
+                let mut parts = extension.children();
+                let oid = parts.next()?;
+                if oid.value != SUBJECT_ALT_NAME {
+                    continue;
+                }
+                let value = parts.find(|part| part.tag == 0x04)?;
+                san = parse_san(value.value)?;
+            }
+        }
+
+        Some(Self {
+            serial,
+            signature_algorithm,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            san,
+        })
+    }
+}
+
+/// 2.5.29.17
+const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+/// One DER element
+struct Der<'a> {
+    tag: u8,
+    value: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    /// Read the element at the start of `input`, returning it and the rest
+    fn read(input: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let (&tag, rest) = input.split_first()?;
+        let (&first, rest) = rest.split_first()?;
+        let (len, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return None;
+            }
+            let len = rest[..count]
+                .iter()
+                .fold(0usize, |len, &byte| (len << 8) | byte as usize);
+            (len, &rest[count..])
+        };
+        if rest.len() < len {
+            return None;
+        }
+        let (value, rest) = rest.split_at(len);
+        Some((Self { tag, value }, rest))
+    }
+
+    /// Elements inside a constructed element
+    fn children(&self) -> impl Iterator<Item = Der<'a>> {
+        let mut rest = self.value;
+        std::iter::from_fn(move || {
+            let (item, remaining) = Der::read(rest)?;
+            rest = remaining;
+            Some(item)
+        })
+    }
+}
+
+/// Distinguished name → [("CN", "example.com"), ("O", "Example Inc"), ...]
+fn parse_name(name: Der) -> Vec<(&'static str, String)> {
+    name.children()
+        .flat_map(|set| set.children().collect::<Vec<_>>())
+        .filter_map(|pair| {
+            let mut parts = pair.children();
+            let oid = parts.next()?;
+            let value = parts.next()?;
+            let key = match oid.value {
+                [0x55, 0x04, 0x03] => "CN",
+                [0x55, 0x04, 0x06] => "C",
+                [0x55, 0x04, 0x07] => "L",
+                [0x55, 0x04, 0x08] => "ST",
+                [0x55, 0x04, 0x0a] => "O",
+                [0x55, 0x04, 0x0b] => "OU",
+                _ => return None,
+            };
+            Some((key, String::from_utf8_lossy(value.value).into_owned()))
+        })
+        .collect()
+}
+
+fn format_name(name: &[(&str, String)]) -> String {
+    name.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// UTCTime (YYMMDDHHMMSSZ) or GeneralizedTime (YYYYMMDDHHMMSSZ)
+fn parse_time(time: Der) -> Option<DateTime<Utc>> {
+    let text = std::str::from_utf8(time.value).ok()?.trim_end_matches('Z');
+    let text = match time.tag {
+        0x17 => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            let century = if year >= 50 { "19" } else { "20" };
+            format!("{}{}", century, text)
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    let date = NaiveDate::parse_from_str(text.get(..8)?, "%Y%m%d").ok()?;
+    let time = NaiveTime::parse_from_str(text.get(8..14)?, "%H%M%S").ok()?;
+    Some(NaiveDateTime::new(date, time).and_utc())
+}
+
+/// DNS names and IP addresses from a SubjectAltName extension value
+fn parse_san(value: &[u8]) -> Option<Vec<String>> {
+    let (names, _) = Der::read(value)?;
+    Some(
+        names
+            .children()
+            .filter_map(|name| match (name.tag, name.value.len()) {
+                // [2] dNSName
+                (0x82, _) => Some(String::from_utf8_lossy(name.value).into_owned()),
+                // [7] iPAddress
+                (0x87, 4) => Some(
+                    std::net::Ipv4Addr::new(
+                        name.value[0],
+                        name.value[1],
+                        name.value[2],
+                        name.value[3],
+                    )
+                    .to_string(),
+                ),
+                (0x87, 16) => {
+                    let octets: [u8; 16] = name.value.try_into().ok()?;
+                    Some(std::net::Ipv6Addr::from(octets).to_string())
+                }
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+fn algorithm_name(oid: &[u8]) -> &'static str {
+    match oid {
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05] => "sha1WithRSAEncryption",
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b] => "sha256WithRSAEncryption",
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c] => "sha384WithRSAEncryption",
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d] => "sha512WithRSAEncryption",
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0a] => "rsassaPss",
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02] => "ecdsa-with-SHA256",
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03] => "ecdsa-with-SHA384",
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x04] => "ecdsa-with-SHA512",
+        [0x2b, 0x65, 0x70] => "Ed25519",
+        _ => "unknown",
+    }
+}
+
+fn colon_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            parse_target("example.com"),
+            Some(("example.com".into(), 443))
+        );
+        assert_eq!(
+            parse_target("https://example.com:8443/path"),
+            Some(("example.com".into(), 8443))
+        );
+        assert_eq!(parse_target("[::1]:993"), Some(("::1".into(), 993)));
+        assert_eq!(parse_target("host:notaport"), None);
+    }
+
+    #[test]
+    fn test_parse_der_fields() {
+        // SEQUENCE { SET { SEQUENCE { OID 2.5.4.3, UTF8String "a.io" } } }
+        let name = [
+            0x30, 0x0f, 0x31, 0x0d, 0x30, 0x0b, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x04, b'a',
+            b'.', b'i', b'o',
+        ];
+        let (der, rest) = Der::read(&name).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(parse_name(der), vec![("CN", "a.io".to_string())]);
+
+        let utc = b"\x17\x0d491231235959Z";
+        let (der, _) = Der::read(utc).unwrap();
+        assert_eq!(
+            parse_time(der).unwrap().to_rfc3339(),
+            "2049-12-31T23:59:59+00:00"
+        );
+
+        // SEQUENCE { [2] "a.io", [7] 10.0.0.1 }
+        let san = [
+            0x30, 0x0c, 0x82, 0x04, b'a', b'.', b'i', b'o', 0x87, 0x04, 10, 0, 0, 1,
+        ];
+        assert_eq!(
+            parse_san(&san),
+            Some(vec!["a.io".to_string(), "10.0.0.1".to_string()])
+        );
+
+        // Long-form length
+        let long = [0x04, 0x81, 0x02, 0xaa, 0xbb];
+        assert_eq!(Der::read(&long).unwrap().0.value, &[0xaa, 0xbb]);
+        assert!(Der::read(&[0x04, 0x05, 0x00]).is_none());
+    }
+
+    // Real certificates: two Let's Encrypt roots (RSA and ECDSA) and a leaf
+    // made with openssl that has DNS and IP SANs and expires after 2049, so
+    // its validity ends in a GeneralizedTime
+    const ISRG_ROOT_X1: &[u8] = include_bytes!("../../../tests/certs/isrg-root-x1.der");
+    const ISRG_ROOT_X2: &[u8] = include_bytes!("../../../tests/certs/isrg-root-x2.der");
+    const EXAMPLE_TEST: &[u8] = include_bytes!("../../../tests/certs/example-test.der");
+
+    #[test]
+    fn test_parse_real_certificates() {
+        let root = Certificate::parse(ISRG_ROOT_X1).unwrap();
+        assert_eq!(
+            format_name(&root.subject),
+            "C=US, O=Internet Security Research Group, CN=ISRG Root X1"
+        );
+        assert_eq!(root.issuer, root.subject);
+        assert_eq!(
+            colon_hex(&root.serial),
+            "82:10:CF:B0:D2:40:E3:59:44:63:E0:BB:63:82:8B:00"
+        );
+        assert_eq!(root.signature_algorithm, "sha256WithRSAEncryption");
+        assert_eq!(root.not_before.to_rfc3339(), "2015-06-04T11:04:38+00:00");
+        assert_eq!(root.not_after.to_rfc3339(), "2035-06-04T11:04:38+00:00");
+        assert!(root.san.is_empty());
+
+        let root = Certificate::parse(ISRG_ROOT_X2).unwrap();
+        assert_eq!(root.signature_algorithm, "ecdsa-with-SHA384");
+        assert_eq!(root.not_after.to_rfc3339(), "2040-09-17T16:00:00+00:00");
+
+        let leaf = Certificate::parse(EXAMPLE_TEST).unwrap();
+        assert_eq!(
+            format_name(&leaf.subject),
+            "C=US, O=Zaxiom Test, CN=example.test"
+        );
+        assert_eq!(leaf.serial, [0x12, 0x34]);
+        assert_eq!(leaf.signature_algorithm, "ecdsa-with-SHA256");
+        assert_eq!(leaf.not_before.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(leaf.not_after.to_rfc3339(), "2051-01-01T00:00:00+00:00");
+        assert_eq!(
+            leaf.san,
+            ["example.test", "*.example.test", "10.0.0.1", "::1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated_and_malformed_certificates() {
+        for der in [ISRG_ROOT_X1, ISRG_ROOT_X2, EXAMPLE_TEST] {
+            // Every truncation is rejected
+            for len in 0..der.len() {
+                assert!(Certificate::parse(&der[..len]).is_none(), "{} bytes", len);
+            }
+            // Corrupting any byte may change the fields but never panics
+            let mut bad = der.to_vec();
+            for i in 0..bad.len() {
+                for flip in [0x01, 0x80, 0xff] {
+                    bad[i] ^= flip;
+                    let _ = Certificate::parse(&bad);
+                    bad[i] ^= flip;
+                }
+            }
+        }
+
+        assert!(Certificate::parse(b"").is_none());
+        assert!(Certificate::parse(b"-----BEGIN CERTIFICATE-----").is_none());
+        // Indefinite length, and a length in more bytes than we accept
+        assert!(Certificate::parse(&[0x30, 0x80, 0x00, 0x00]).is_none());
+        assert!(Der::read(&[0x30, 0x85, 0, 0, 0, 0, 1, 0]).is_none());
+        // A certificate whose validity isn't a time
+        let mut bad_time = EXAMPLE_TEST.to_vec();
+        let at = bad_time
+            .windows(15)
+            .position(|w| w == b"\x17\x0d240101000000Z")
+            .unwrap();
+        bad_time[at] = 0x04;
+        assert!(Certificate::parse(&bad_time).is_none());
+    }
+}
@@ -21,6 +21,10 @@ impl Command for TracerouteCommand {
         "traceroute [-m maxhops] <host>"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
         let mut max_hops: Option<u32> = None;
         let mut host: Option<&String> = None;
@@ -28,11 +32,9 @@ impl Command for TracerouteCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-m" | "--max-hops" => {
-                    if i + 1 < args.len() {
-                        max_hops = args[i + 1].parse().ok();
-                        i += 1;
-                    }
+                "-m" | "--max-hops" if i + 1 < args.len() => {
+                    max_hops = args[i + 1].parse().ok();
+                    i += 1;
                 }
                 "-h" | "--help" => {
                     return Ok("Usage: traceroute [OPTIONS] <host>\n\
@@ -22,6 +22,10 @@ impl Command for WgetCommand {
         "wget [-O output] [-q] <url>"
     }
 
+    fn uses_network(&self) -> bool {
+        true
+    }
+
     fn extended_help(&self) -> String {
         r#"wget - Non-interactive network downloader
 
@@ -84,11 +88,9 @@ RELATED COMMANDS:
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-O" | "--output-document" => {
-                    if i + 1 < args.len() {
-                        output_file = Some(args[i + 1].clone());
-                        i += 1;
-                    }
+                "-O" | "--output-document" if i + 1 < args.len() => {
+                    output_file = Some(args[i + 1].clone());
+                    i += 1;
                 }
                 "-q" | "--quiet" => quiet = true,
                 "-h" | "--help" => {
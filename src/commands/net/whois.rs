@@ -0,0 +1,328 @@
+//! whois command - domain and IP registration lookup
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::expiry_status;
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct WhoisCommand;
+
+/// Starting point: IANA knows which server is authoritative for every TLD and IP block
+const IANA: &str = "whois.iana.org";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Summary labels and the record keys that feed them, across registry formats
+const FIELDS: &[(&str, &[&str])] = &[
+    ("Domain", &["domain name", "domain"]),
+    ("Registrar", &["registrar", "sponsoring registrar"]),
+    (
+        "Registrant",
+        &[
+            "registrant organization",
+            "registrant",
+            "org-name",
+            "organization",
+            "orgname",
+        ],
+    ),
+    ("Country", &["registrant country", "country"]),
+    ("Network", &["netrange", "inetnum", "inet6num", "cidr"]),
+    ("Name", &["netname"]),
+    (
+        "Created",
+        &["creation date", "created", "registered on", "regdate"],
+    ),
+    (
+        "Updated",
+        &[
+            "updated date",
+            "changed",
+            "last-modified",
+            "last updated",
+            "updated",
+        ],
+    ),
+    (
+        "Expires",
+        &[
+            "registry expiry date",
+            "registrar registration expiration date",
+            "expiry date",
+            "expires",
+            "expire",
+            "paid-till",
+        ],
+    ),
+    ("Status", &["domain status", "status"]),
+    ("Name servers", &["name server", "nserver", "nameservers"]),
+    ("DNSSEC", &["dnssec"]),
+];
+
+impl Command for WhoisCommand {
+    fn name(&self) -> &'static str {
+        "whois"
+    }
+
+    fn description(&self) -> &'static str {
+        "Look up domain or IP registration"
+    }
+
+    fn usage(&self) -> &'static str {
+        "whois [-r] [-s server] <domain|ip>"
+    }
+
+    fn uses_network(&self) -> bool {
+        true
+    }
+
+    fn extended_help(&self) -> String {
+        r#"whois - Look up domain or IP registration
+
+USAGE:
+  whois [OPTIONS] <domain|ip>
+
+OPTIONS:
+  -r, --raw             Print the full record instead of a summary
+  -s, --server <host>   Ask this whois server directly
+
+DESCRIPTION:
+  Asks whois.iana.org which registry is responsible, then asks
+  that registry (and the registrar it points to, for .com/.net
+  style registries). Prints registrar, registrant, dates, status
+  and name servers; the expiry date shows how many days are left.
+
+EXAMPLES:
+  whois example.com           Domain summary
+  whois -r example.org        Full registry record
+  whois 8.8.8.8               Who owns an IP address
+  whois -s whois.nic.io x.io  Ask a specific server
+
+RELATED COMMANDS:
+  tlscert    Inspect a site's TLS certificates
+  nslookup   DNS lookup
+  host       Resolve a hostname
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let mut raw = false;
+        let mut server: Option<String> = None;
+        let mut query: Option<&String> = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-r" | "--raw" => raw = true,
+                "-s" | "--server" => {
+                    server = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow::anyhow!("whois: {} needs a server", arg))?
+                            .clone(),
+                    );
+                }
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ if !arg.starts_with('-') => query = Some(arg),
+                _ => return Err(anyhow::anyhow!("whois: unknown option '{}'", arg)),
+            }
+        }
+        let query = query
+            .ok_or_else(|| anyhow::anyhow!("Usage: {}", self.usage()))?
+            .trim_end_matches('.')
+            .to_lowercase();
+
+        let mut source = match server {
+            Some(server) => server,
+            None => {
+                let iana = lookup(IANA, &query)?;
+                match field(&iana, "refer") {
+                    Some(refer) => refer,
+                    None => return Ok(finish(&iana, IANA, raw)),
+                }
+            }
+        };
+        let mut record = lookup(&source, &query)?;
+
+        // Thin registries (.com, .net) point at the registrar for the full record
+        if let Some(registrar) = field(&record, "registrar whois server") {
+            let registrar = registrar.trim_start_matches("whois://").to_string();
+            if !registrar.eq_ignore_ascii_case(&source) {
+                if let Ok(detail) = lookup(&registrar, &query) {
+                    if !summarize(&detail).is_empty() {
+                        record = detail;
+                        source = registrar;
+                    }
+                }
+            }
+        }
+
+        Ok(finish(&record, &source, raw))
+    }
+}
+
+fn finish(record: &str, source: &str, raw: bool) -> String {
+    let summary = summarize(record);
+    if raw || summary.is_empty() {
+        return format!("{}\n\nSource: {}", record.trim(), source);
+    }
+
+    let width = summary
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<String> = summary
+        .iter()
+        .map(|(label, value)| format!("{:<width$}  {}", label, value, width = width))
+        .collect();
+    lines.push(String::new());
+    lines.push(format!("Source: {} (whois -r for the full record)", source));
+    lines.join("\n")
+}
+
+/// One query over the whois protocol (RFC 3912)
+fn lookup(server: &str, query: &str) -> Result<String> {
+    let addr = (server, 43)
+        .to_socket_addrs()
+        .map_err(|e| anyhow::anyhow!("whois: can't resolve {}: {}", server, e))?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("whois: can't resolve {}", server))?;
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+        .map_err(|e| anyhow::anyhow!("whois: can't reach {}: {}", server, e))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.write_all(format!("{}\r\n", query).as_bytes())?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| anyhow::anyhow!("whois: {} didn't answer: {}", server, e))?;
+    Ok(String::from_utf8_lossy(&response).replace('\r', ""))
+}
+
+/// "key: value" pairs, skipping comments and notices
+fn pairs(record: &str) -> impl Iterator<Item = (String, &str)> {
+    record.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.starts_with(['%', '#', '>']) {
+            return None;
+        }
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        (!value.is_empty()).then(|| (key.trim().to_lowercase(), value))
+    })
+}
+
+/// First value of a key
+fn field(record: &str, key: &str) -> Option<String> {
+    pairs(record)
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Labelled values in `FIELDS` order; repeated keys (name servers,
+/// statuses) are joined, and the expiry gets a days-left note
+fn summarize(record: &str) -> Vec<(&'static str, String)> {
+    let pairs: Vec<(String, &str)> = pairs(record).collect();
+    let mut summary = Vec::new();
+
+    for (label, keys) in FIELDS {
+        let Some(key) = keys.iter().find(|key| pairs.iter().any(|(k, _)| k == *key)) else {
+            continue;
+        };
+        let mut values: Vec<String> = Vec::new();
+        for (_, value) in pairs.iter().filter(|(k, _)| k == key) {
+            // "clientTransferProhibited https://icann.org/epp#..." → first word
+            let value = match *label {
+                "Status" | "Name servers" => value.split_whitespace().next().unwrap_or(value),
+                _ => value,
+            };
+            let value = match *label {
+                "Name servers" => value.to_lowercase(),
+                _ => value.to_string(),
+            };
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+
+        let mut text = values.join(", ");
+        if *label == "Expires" {
+            if let Some(date) = values.first().and_then(|v| parse_date(v)) {
+                text = format!("{}  ({})", text, expiry_status(date));
+            }
+        }
+        summary.push((*label, text));
+    }
+
+    // A referral-only or "not found" answer has no registration data
+    if summary.iter().all(|(label, _)| *label == "Domain") {
+        summary.clear();
+    }
+    summary
+}
+
+/// Registry dates: "2025-08-13T04:00:00Z", "2025-08-13", "2025.08.13", "13-Aug-2025"
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    let day = value.split(['T', ' ']).next()?;
+    ["%Y-%m-%d", "%Y.%m.%d", "%d-%b-%Y", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(day, format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_record() {
+        let record = "\
+   Domain Name: EXAMPLE.COM
+   Registrar WHOIS Server: whois.example-registrar.com
+   Registrar: Example Registrar, Inc.
+   Creation Date: 1995-08-14T04:00:00Z
+   Registry Expiry Date: 2001-08-13T04:00:00Z
+   Domain Status: clientDeleteProhibited https://icann.org/epp#clientDeleteProhibited
+   Domain Status: clientTransferProhibited https://icann.org/epp#clientTransferProhibited
+   Name Server: A.IANA-SERVERS.NET
+   Name Server: B.IANA-SERVERS.NET
+>>> Last update of whois database: 2024-01-01T00:00:00Z <<<
+";
+        assert_eq!(
+            field(record, "registrar whois server").as_deref(),
+            Some("whois.example-registrar.com")
+        );
+
+        let summary = summarize(record);
+        let get = |label: &str| {
+            summary
+                .iter()
+                .find(|(l, _)| *l == label)
+                .map(|(_, v)| v.clone())
+        };
+        assert_eq!(get("Domain").as_deref(), Some("EXAMPLE.COM"));
+        assert_eq!(
+            get("Status").as_deref(),
+            Some("clientDeleteProhibited, clientTransferProhibited")
+        );
+        assert_eq!(
+            get("Name servers").as_deref(),
+            Some("a.iana-servers.net, b.iana-servers.net")
+        );
+        assert!(get("Expires").unwrap().contains("expired"));
+
+        assert!(summarize("No match for \"NOPE.COM\".\n").is_empty());
+        assert!(parse_date("13-Aug-2025").is_some());
+    }
+}
@@ -9,40 +9,51 @@ use std::sync::Arc;
 
 use anyhow::Result;
 
-use super::ai::{AiCommand, OllamaCommand};
+use super::ai::{AiCommand, OllamaCommand, SummarizeCommand};
 use super::compress::{GunzipCommand, GzipCommand, TarCommand, UnzipCommand, ZipCommand};
+use super::context::CommandContext;
 use super::files::{
     BasenameCommand, CatCommand, ChmodCommand, CpCommand, DirnameCommand, EditCommand, FileCommand,
-    LnCommand, MkdirCommand, MktempCommand, MvCommand, NanoCommand, ReadlinkCommand,
-    RealpathCommand, RmCommand, StatCommand, TouchCommand, ViCommand, VimCommand,
+    LessCommand, LnCommand, MdCommand, MkdirCommand, MktempCommand, MvCommand, NanoCommand,
+    OpenCommand, ReadlinkCommand, RealpathCommand, RmCommand, StatCommand, TouchCommand, ViCommand,
+    VimCommand,
 };
 use super::fun::{CoffeeCommand, CowsayCommand, FortuneCommand, MatrixCommand, PetCommand};
+use super::git::{
+    GaCommand, GcCommand, GconflictsCommand, GdCommand, GgCommand, GlCommand, GopenCommand,
+    GpCommand, GplCommand, GprCommand, GremoteCommand, GsCommand, GstCommand, GtagCommand,
+};
 use super::hash::{
-    Base64Command, Blake3sumCommand, Crc32Command, Md5sumCommand, Sha1sumCommand, Sha224sumCommand,
-    Sha256sumCommand, Sha384sumCommand, Sha512sumCommand, XxdCommand,
+    Base64Command, Blake3sumCommand, Crc32Command, HexCommand, Md5sumCommand, Sha1sumCommand,
+    Sha224sumCommand, Sha256sumCommand, Sha384sumCommand, Sha512sumCommand, UnhexCommand,
+    UrldecodeCommand, UrlencodeCommand, XxdCommand,
 };
 use super::nav::{CdCommand, ClearCommand, HelpCommand, LsCommand, PwdCommand, TreeCommand};
 use super::net::{
-    CurlCommand, HostCommand, IfconfigCommand, NetstatCommand, NslookupCommand, PingCommand,
-    TracerouteCommand, WgetCommand,
+    CurlCommand, HostCommand, IfconfigCommand, LanscanCommand, MockCommand, NetstatCommand,
+    NslookupCommand, PingCommand, ScrapeCommand, SpeedtestCommand, SshCommand, TlscertCommand,
+    TracerouteCommand, WgetCommand, WhoisCommand,
 };
-use super::search::{FindCommand, GrepCommand};
+use super::search::{FdCommand, FindCommand, GrepCommand};
 use super::shell::{
-    AliasCommand, BcCommand, CommandCommand, DirsCommand, EnvCommand, ExportCommand, ExprCommand,
-    FalseCommand, PopdCommand, PushdCommand, SeqCommand, SleepCommand, TeeCommand, TimeoutCommand,
-    TrueCommand, TypeCommand, WatchCommand, YesCommand,
+    AliasCommand, BcCommand, BgCommand, CommandCommand, DirsCommand, EnvCommand, ExportCommand,
+    ExprCommand, FalseCommand, FgCommand, JobsCommand, LoremCommand, PopdCommand, PushdCommand,
+    RandomCommand, SeqCommand, SleepCommand, SourceCommand, TeeCommand, TimeoutCommand,
+    TrueCommand, TypeCommand, UnaliasCommand, UuidCommand, WatchCommand, YesCommand,
 };
 use super::system::{
-    CalCommand, DateCommand, DfCommand, DuCommand, ExitCommand, FreeCommand, HistoryCommand,
-    HostnameCommand, IdCommand, KillCommand, LscpuCommand, ManCommand, NeofetchCommand,
-    PrintenvCommand, PsCommand, TestCommand, ThemeCommand, UnameCommand, UptimeCommand,
-    WhichCommand, WhoamiCommand,
+    AuditCommand, BufferCommand, CalCommand, ColorCommand, DateCommand, DfCommand, DigestCommand,
+    DuCommand, ExitCommand, FcCommand, FreeCommand, HistoryCommand, HostnameCommand, IdCommand,
+    KeysCommand, KillCommand, LayoutCommand, LscpuCommand, ManCommand, NeofetchCommand,
+    PaneCommand, PathsCommand, PrintenvCommand, PsCommand, SessionCommand, StatsCommand,
+    TestCommand, ThemeCommand, TimestampsCommand, TipsCommand, UnameCommand, UptimeCommand,
+    WhichCommand, WhoamiCommand, WorkspaceCommand, ZoomCommand,
 };
 use super::text::{
     AwkCommand, ColumnCommand, CommCommand, CutCommand, DiffCommand, EchoCommand, HeadCommand,
-    JoinCommand, NlCommand, PasteCommand, PrintfCommand, RevCommand, SedCommand, SortCommand,
-    SplitCommand, StringsCommand, TacCommand, TailCommand, TrCommand, UniqCommand, WcCommand,
-    XargsCommand,
+    JoinCommand, JqCommand, NlCommand, PasteCommand, PrintfCommand, RevCommand, SedCommand,
+    SortCommand, SplitCommand, StrCommand, StringsCommand, TacCommand, TailCommand, TrCommand,
+    UniqCommand, WcCommand, XargsCommand,
 };
 use super::tools::{
     AnsibleCommand,
@@ -146,7 +157,6 @@ use super::tools::{
     // Scala/Kotlin
     ScalaCommand,
     ScpCommand,
-    SshCommand,
     StackCommand,
     SublCommand,
     SwiftCommand,
@@ -180,6 +190,9 @@ impl CommandRegistry {
 
         // File commands
         commands.insert("cat", Arc::new(CatCommand));
+        commands.insert("less", Arc::new(LessCommand));
+        commands.insert("more", Arc::new(LessCommand)); // Alias
+        commands.insert("md", Arc::new(MdCommand));
         commands.insert("touch", Arc::new(TouchCommand));
         commands.insert("rm", Arc::new(RmCommand));
         commands.insert("mkdir", Arc::new(MkdirCommand));
@@ -206,13 +219,16 @@ impl CommandRegistry {
         commands.insert("tr", Arc::new(TrCommand));
         commands.insert("sed", Arc::new(SedCommand));
         commands.insert("awk", Arc::new(AwkCommand));
+        commands.insert("jq", Arc::new(JqCommand));
         commands.insert("rev", Arc::new(RevCommand));
+        commands.insert("str", Arc::new(StrCommand));
         commands.insert("nl", Arc::new(NlCommand));
         commands.insert("printf", Arc::new(PrintfCommand));
 
         // Search commands
         commands.insert("grep", Arc::new(GrepCommand));
         commands.insert("find", Arc::new(FindCommand));
+        commands.insert("fd", Arc::new(FdCommand));
 
         // System commands
         commands.insert("exit", Arc::new(ExitCommand));
@@ -229,13 +245,20 @@ impl CommandRegistry {
         commands.insert("free", Arc::new(FreeCommand));
         commands.insert("date", Arc::new(DateCommand));
         commands.insert("cal", Arc::new(CalCommand));
+        commands.insert("color", Arc::new(ColorCommand));
         commands.insert("id", Arc::new(IdCommand));
         commands.insert("neofetch", Arc::new(NeofetchCommand));
 
         // Network commands
         commands.insert("curl", Arc::new(CurlCommand));
         commands.insert("wget", Arc::new(WgetCommand));
+        commands.insert("scrape", Arc::new(ScrapeCommand));
         commands.insert("ping", Arc::new(PingCommand));
+        commands.insert("lanscan", Arc::new(LanscanCommand));
+        commands.insert("speedtest", Arc::new(SpeedtestCommand));
+        commands.insert("whois", Arc::new(WhoisCommand));
+        commands.insert("tlscert", Arc::new(TlscertCommand));
+        commands.insert("mock", Arc::new(MockCommand));
         commands.insert("netstat", Arc::new(NetstatCommand));
         commands.insert("traceroute", Arc::new(TracerouteCommand));
 
@@ -251,6 +274,10 @@ impl CommandRegistry {
         commands.insert("crc32", Arc::new(Crc32Command));
         commands.insert("base64", Arc::new(Base64Command));
         commands.insert("xxd", Arc::new(XxdCommand));
+        commands.insert("hex", Arc::new(HexCommand));
+        commands.insert("unhex", Arc::new(UnhexCommand));
+        commands.insert("urlencode", Arc::new(UrlencodeCommand));
+        commands.insert("urldecode", Arc::new(UrldecodeCommand));
 
         // Compression commands
         commands.insert("tar", Arc::new(TarCommand));
@@ -261,12 +288,16 @@ impl CommandRegistry {
 
         // Shell utilities
         commands.insert("alias", Arc::new(AliasCommand));
+        commands.insert("unalias", Arc::new(UnaliasCommand));
         commands.insert("env", Arc::new(EnvCommand));
         commands.insert("export", Arc::new(ExportCommand));
         commands.insert("sleep", Arc::new(SleepCommand));
         commands.insert("watch", Arc::new(WatchCommand));
         commands.insert("seq", Arc::new(SeqCommand));
         commands.insert("yes", Arc::new(YesCommand));
+        commands.insert("uuid", Arc::new(UuidCommand));
+        commands.insert("random", Arc::new(RandomCommand));
+        commands.insert("lorem", Arc::new(LoremCommand));
         commands.insert("true", Arc::new(TrueCommand));
         commands.insert("false", Arc::new(FalseCommand));
         commands.insert("expr", Arc::new(ExprCommand));
@@ -278,6 +309,11 @@ impl CommandRegistry {
         commands.insert("pushd", Arc::new(PushdCommand));
         commands.insert("popd", Arc::new(PopdCommand));
         commands.insert("dirs", Arc::new(DirsCommand));
+        commands.insert("jobs", Arc::new(JobsCommand));
+        commands.insert("fg", Arc::new(FgCommand));
+        commands.insert("bg", Arc::new(BgCommand));
+        commands.insert("source", Arc::new(SourceCommand));
+        commands.insert(".", Arc::new(SourceCommand)); // Alias for source
 
         // New file commands
         commands.insert("chmod", Arc::new(ChmodCommand));
@@ -296,10 +332,24 @@ impl CommandRegistry {
         commands.insert("printenv", Arc::new(PrintenvCommand));
         commands.insert("lscpu", Arc::new(LscpuCommand));
         commands.insert("history", Arc::new(HistoryCommand));
+        commands.insert("fc", Arc::new(FcCommand));
+        commands.insert("audit", Arc::new(AuditCommand));
+        commands.insert("timestamps", Arc::new(TimestampsCommand));
+        commands.insert("buffer", Arc::new(BufferCommand));
         commands.insert("test", Arc::new(TestCommand));
         commands.insert("[", Arc::new(TestCommand)); // Alias for test
         commands.insert("man", Arc::new(ManCommand));
         commands.insert("theme", Arc::new(ThemeCommand));
+        commands.insert("layout", Arc::new(LayoutCommand));
+        commands.insert("pane", Arc::new(PaneCommand));
+        commands.insert("zoom", Arc::new(ZoomCommand));
+        commands.insert("keys", Arc::new(KeysCommand));
+        commands.insert("workspace", Arc::new(WorkspaceCommand));
+        commands.insert("session", Arc::new(SessionCommand));
+        commands.insert("tips", Arc::new(TipsCommand));
+        commands.insert("paths", Arc::new(PathsCommand));
+        commands.insert("stats", Arc::new(StatsCommand));
+        commands.insert("digest", Arc::new(DigestCommand));
 
         // New network commands
         commands.insert("nslookup", Arc::new(NslookupCommand));
@@ -311,6 +361,8 @@ impl CommandRegistry {
         commands.insert("vim", Arc::new(VimCommand));
         commands.insert("vi", Arc::new(ViCommand));
         commands.insert("edit", Arc::new(EditCommand));
+        commands.insert("open", Arc::new(OpenCommand));
+        commands.insert("start", Arc::new(OpenCommand)); // Windows-style alias
 
         // Fun commands
         commands.insert("fortune", Arc::new(FortuneCommand));
@@ -322,6 +374,7 @@ impl CommandRegistry {
         // AI commands
         commands.insert("ai", Arc::new(AiCommand));
         commands.insert("ollama", Arc::new(OllamaCommand));
+        commands.insert("summarize", Arc::new(SummarizeCommand));
 
         // ============ External Dev Tools ============
 
@@ -369,6 +422,20 @@ impl CommandRegistry {
 
         // Version control
         commands.insert("git", Arc::new(GitCommand));
+        commands.insert("gs", Arc::new(GsCommand));
+        commands.insert("gd", Arc::new(GdCommand));
+        commands.insert("gl", Arc::new(GlCommand));
+        commands.insert("gg", Arc::new(GgCommand));
+        commands.insert("ga", Arc::new(GaCommand));
+        commands.insert("gc", Arc::new(GcCommand));
+        commands.insert("gp", Arc::new(GpCommand));
+        commands.insert("gpl", Arc::new(GplCommand));
+        commands.insert("gst", Arc::new(GstCommand));
+        commands.insert("gtag", Arc::new(GtagCommand));
+        commands.insert("gremote", Arc::new(GremoteCommand));
+        commands.insert("gconflicts", Arc::new(GconflictsCommand));
+        commands.insert("gpr", Arc::new(GprCommand));
+        commands.insert("gopen", Arc::new(GopenCommand));
 
         // Other languages
         commands.insert("ruby", Arc::new(RubyCommand));
@@ -495,6 +562,21 @@ impl CommandRegistry {
         }
     }
 
+    /// Execute a command that can be cancelled and report progress
+    pub fn execute_with_context(
+        &self,
+        name: &str,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        match self.commands.get(name) {
+            Some(cmd) => cmd.execute_with_context(args, stdin, state, ctx),
+            None => Err(anyhow::anyhow!("Command not found: {}", name)),
+        }
+    }
+
     /// Get a command
     pub fn get(&self, name: &str) -> Option<Arc<dyn Command>> {
         self.commands.get(name).cloned()
@@ -511,6 +593,18 @@ impl CommandRegistry {
         list
     }
 
+    /// Names of the commands that talk to the network
+    pub fn network_commands(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self
+            .commands
+            .iter()
+            .filter(|(_, cmd)| cmd.uses_network())
+            .map(|(name, _)| *name)
+            .collect();
+        names.sort();
+        names
+    }
+
     /// Get extended help for a command
     pub fn get_help(&self, name: &str) -> String {
         match self.commands.get(name) {
@@ -525,3 +619,49 @@ impl Default for CommandRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+
+    #[test]
+    fn test_every_command_is_documented() {
+        let registry = CommandRegistry::new();
+        for (name, description) in registry.list() {
+            let command = registry.get(name).unwrap();
+            // Aliases (quit, b3sum, vi, ...) share their command's struct
+            assert!(
+                registry.has_command(command.name()),
+                "{} reports unregistered name {}",
+                name,
+                command.name()
+            );
+            assert!(
+                !description.trim().is_empty(),
+                "{} has no description",
+                name
+            );
+            assert!(!command.usage().trim().is_empty(), "{} has no usage", name);
+            assert!(
+                command.extended_help().contains(command.name()),
+                "{} help doesn't mention it",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_by_name() {
+        let registry = CommandRegistry::new();
+        let mut state = MockTerminalState::new().build();
+        let args = vec!["hello".to_string(), "world".to_string()];
+        assert_eq!(
+            registry.execute("echo", &args, &mut state).unwrap(),
+            "hello world"
+        );
+        assert!(registry
+            .execute("no-such-command", &args, &mut state)
+            .is_err());
+    }
+}
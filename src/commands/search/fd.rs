@@ -0,0 +1,433 @@
+//! fd command - find files by name, the quick way
+//!
+//! A friendlier `find`: the pattern is a regex (fuzzy with `-z`, a glob
+//! with `-g`) matched against file names, ignored and hidden files are
+//! skipped, and the results are colored by kind. With `--pick` (or
+//! Ctrl+Shift+S afterwards) they open in the fuzzy finder.
+
+use anyhow::{anyhow, Result};
+use eframe::egui::Color32;
+use glob::{MatchOptions, Pattern};
+use ignore::DirEntry;
+use regex::RegexBuilder;
+
+use super::walk::{relative, Walk};
+use crate::commands::context::CommandContext;
+use crate::commands::traits::Command;
+use crate::config::theme::Theme;
+use crate::terminal::fuzzy::fuzzy_score;
+use crate::terminal::state::TerminalState;
+
+const RESET: &str = "\x1b[0m";
+
+pub struct FdCommand;
+
+impl Command for FdCommand {
+    fn name(&self) -> &'static str {
+        "fd"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find files by name (regex, fuzzy, or glob)"
+    }
+
+    fn usage(&self) -> &'static str {
+        "fd [-z|-g] [-t f|d|l|x] [-e ext] [-H] [-I] [--pick] [pattern] [path]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"fd - Find files by name
+
+USAGE:
+  fd [OPTIONS] [pattern] [path]
+
+OPTIONS:
+  -z, --fuzzy           Fuzzy pattern: its letters in order, best first
+  -g, --glob            Glob pattern (*.rs) matching the whole name
+  -i, --ignore-case     Ignore case
+  -s, --case-sensitive  Match case
+  -p, --full-path       Match the path below the search path, not the name
+  -t, --type <kind>     f file, d directory, l symlink, x executable
+  -e, --extension <ext> Only files with this extension (repeatable)
+  -d, --max-depth <n>   Descend at most n levels
+  -H, --hidden          Include hidden files and directories
+  -I, --no-ignore       Include what .gitignore lists
+  --color <when>        auto (default), always, or never
+  --pick                Open the results in the fuzzy finder
+
+DESCRIPTION:
+  Lists the files and directories under path (the current directory
+  by default) whose name matches pattern, walking the tree on every
+  core. With no pattern, lists everything. The pattern is a regular
+  expression found anywhere in the name, case-insensitive unless it
+  has an uppercase letter. Like git, fd skips what .gitignore lists
+  and hidden files.
+
+  Results are colored by kind in the theme's colors: directories,
+  executables, links, source, archives, and media. Paths are relative
+  to the current directory, so they work as arguments.
+
+  With --pick (or Ctrl+Shift+S afterwards), the results open in the
+  fuzzy finder: Enter inserts the path, Ctrl+Enter opens the file in
+  the editor, Shift+Enter cds to its directory.
+
+EXAMPLES:
+  fd                     Everything not ignored
+  fd test                Names containing "test"
+  fd '^main\.rs$'        Exactly main.rs
+  fd -e rs -e toml       Rust and TOML files
+  fd -z cmdrg            Fuzzy: src/commands/registry.rs
+  fd -g '*.md' docs      Markdown files under docs
+  fd -t d -d 2 src       Directories at most two levels into src
+  fd config --pick       Pick one and jump to it
+
+RELATED COMMANDS:
+  find     Search with tests on size, age, and type
+  grep     Search file contents
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let mut style = Style::Regex;
+        let mut case = None;
+        let mut full_path = false;
+        let mut kind = None;
+        let mut extensions = Vec::new();
+        let mut max_depth = None;
+        let mut walk = Walk::new();
+        let mut color = None;
+        let mut pick = false;
+        let mut positional = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| {
+                iter.next()
+                    .map(|s| s.as_str())
+                    .ok_or_else(|| anyhow!("fd: {} needs a value", name))
+            };
+            match arg.as_str() {
+                "-z" | "--fuzzy" => style = Style::Fuzzy,
+                "-g" | "--glob" => style = Style::Glob,
+                "-i" | "--ignore-case" => case = Some(false),
+                "-s" | "--case-sensitive" => case = Some(true),
+                "-p" | "--full-path" => full_path = true,
+                "-H" | "--hidden" => walk = walk.hidden(true),
+                "-I" | "--no-ignore" => walk = walk.respect_ignore(false),
+                "-t" | "--type" => match value(arg)? {
+                    kind_arg @ ("f" | "d" | "l" | "x") => kind = kind_arg.chars().next(),
+                    other => {
+                        return Err(anyhow!("fd: --type must be f, d, l, or x, not '{}'", other))
+                    }
+                },
+                "-e" | "--extension" => {
+                    extensions.push(value(arg)?.trim_start_matches('.').to_lowercase())
+                }
+                "-d" | "--max-depth" => {
+                    let depth = value(arg)?;
+                    max_depth =
+                        Some(depth.parse().map_err(|_| {
+                            anyhow!("fd: --max-depth needs a number, not '{}'", depth)
+                        })?);
+                }
+                "--color" | "--colour" => color = Some(value(arg)?),
+                "--pick" => pick = true,
+                _ if arg.starts_with("--color=") || arg.starts_with("--colour=") => {
+                    color = arg.split_once('=').map(|(_, when)| when);
+                }
+                _ if arg.starts_with('-') && arg.len() > 1 => {
+                    return Err(anyhow!("fd: unknown option '{}'", arg));
+                }
+                _ => positional.push(arg.as_str()),
+            }
+        }
+        if positional.len() > 2 {
+            return Err(anyhow!("fd: too many arguments (pattern and path)"));
+        }
+
+        let pattern = positional.first().copied().unwrap_or("");
+        // Smart case: exact only when the pattern has an uppercase letter
+        let case_sensitive = case.unwrap_or_else(|| pattern.chars().any(char::is_uppercase));
+        let matcher = Matcher::new(style, pattern, case_sensitive)?;
+
+        let colors = match color {
+            None | Some("auto") => !state.output_piped,
+            Some("always") => true,
+            Some("never") => false,
+            Some(other) => {
+                return Err(anyhow!(
+                    "fd: --color must be auto, always, or never, not '{}'",
+                    other
+                ))
+            }
+        }
+        .then(|| Theme::from_name(state.current_theme));
+
+        let base_path = match positional.get(1) {
+            Some(path) => state.resolve_path(path),
+            None => state.cwd().clone(),
+        };
+        if !base_path.is_dir() {
+            return Err(anyhow!("fd: no such directory: {}", base_path.display()));
+        }
+
+        let walk = walk.max_depth(max_depth);
+        let mut found = walk.run(&base_path, ctx, |entry| {
+            if entry.depth() == 0 || !kind.is_none_or(|kind| is_kind(entry, kind)) {
+                return None;
+            }
+            if !extensions.is_empty() {
+                let extension = entry.path().extension()?.to_string_lossy().to_lowercase();
+                if !extensions.contains(&extension) {
+                    return None;
+                }
+            }
+            let target = if full_path {
+                relative(entry.path(), &base_path)
+            } else {
+                entry.file_name().to_string_lossy().into_owned()
+            };
+            let score = matcher.score(&target)?;
+            Some((score, color_for(entry)))
+        })?;
+        if matcher.ranks() {
+            // Best matches first; ties stay in path order
+            found.sort_by_key(|(_, (score, _))| std::cmp::Reverse(*score));
+        }
+
+        let cwd = state.cwd().clone();
+        let paths: Vec<String> = found.iter().map(|(path, _)| relative(path, &cwd)).collect();
+        if pick {
+            state.requested_pick = Some(paths.join("\n"));
+        }
+        let lines: Vec<String> = match &colors {
+            Some(theme) => paths
+                .iter()
+                .zip(&found)
+                .map(|(path, (_, (_, color)))| paint(path, color(theme), theme))
+                .collect(),
+            None => paths,
+        };
+        Ok(lines.join("\n"))
+    }
+}
+
+/// How the pattern is read
+#[derive(Clone, Copy)]
+enum Style {
+    Regex,
+    Fuzzy,
+    Glob,
+}
+
+/// A compiled pattern
+enum Matcher {
+    Regex(regex::Regex),
+    /// Lowercased unless matching case
+    Fuzzy {
+        pattern: String,
+        case_sensitive: bool,
+    },
+    Glob {
+        pattern: Pattern,
+        case_sensitive: bool,
+    },
+}
+
+impl Matcher {
+    fn new(style: Style, pattern: &str, case_sensitive: bool) -> Result<Self> {
+        Ok(match style {
+            Style::Regex => Matcher::Regex(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|e| anyhow!("fd: invalid pattern: {}", e))?,
+            ),
+            Style::Fuzzy => Matcher::Fuzzy {
+                pattern: if case_sensitive {
+                    pattern.to_string()
+                } else {
+                    pattern.to_lowercase()
+                },
+                case_sensitive,
+            },
+            Style::Glob => Matcher::Glob {
+                pattern: Pattern::new(if pattern.is_empty() { "*" } else { pattern })
+                    .map_err(|e| anyhow!("fd: invalid glob: {}", e))?,
+                case_sensitive,
+            },
+        })
+    }
+
+    /// Whether results are ordered by score rather than path
+    fn ranks(&self) -> bool {
+        matches!(self, Matcher::Fuzzy { pattern, .. } if !pattern.is_empty())
+    }
+
+    /// How well `target` matches, if it does
+    fn score(&self, target: &str) -> Option<i32> {
+        match self {
+            Matcher::Regex(regex) => regex.is_match(target).then_some(0),
+            Matcher::Fuzzy {
+                pattern,
+                case_sensitive,
+            } => {
+                if pattern.is_empty() {
+                    return Some(0);
+                }
+                let score = if *case_sensitive {
+                    fuzzy_score(pattern, target).0
+                } else {
+                    fuzzy_score(pattern, &target.to_lowercase()).0
+                };
+                (score > 0).then_some(score)
+            }
+            Matcher::Glob {
+                pattern,
+                case_sensitive,
+            } => {
+                let options = MatchOptions {
+                    case_sensitive: *case_sensitive,
+                    ..MatchOptions::new()
+                };
+                pattern.matches_with(target, options).then_some(0)
+            }
+        }
+    }
+}
+
+/// Whether an entry is of `kind` (f, d, l, or x)
+fn is_kind(entry: &DirEntry, kind: char) -> bool {
+    let Some(file_type) = entry.file_type() else {
+        return false;
+    };
+    match kind {
+        'd' => file_type.is_dir(),
+        'l' => file_type.is_symlink(),
+        'x' => file_type.is_file() && is_executable(entry),
+        _ => file_type.is_file(),
+    }
+}
+
+/// Programs: by extension on Windows, by permission bits elsewhere
+fn is_executable(entry: &DirEntry) -> bool {
+    let by_extension = entry.path().extension().is_some_and(|ext| {
+        matches!(
+            ext.to_string_lossy().to_lowercase().as_str(),
+            "exe" | "cmd" | "bat" | "com" | "ps1" | "sh"
+        )
+    });
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        by_extension
+            || entry
+                .metadata()
+                .is_ok_and(|meta| meta.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    by_extension
+}
+
+/// Which theme color a result is shown in
+type ColorFor = fn(&Theme) -> Color32;
+
+fn color_for(entry: &DirEntry) -> ColorFor {
+    let file_type = entry.file_type();
+    if file_type.is_some_and(|t| t.is_dir()) {
+        return |theme| theme.folder_color;
+    }
+    if file_type.is_some_and(|t| t.is_symlink()) {
+        return |theme| theme.link_color;
+    }
+    if is_executable(entry) {
+        return |theme| theme.success_color;
+    }
+    let extension = entry
+        .path()
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "rs" | "py" | "js" | "ts" | "jsx" | "tsx" | "go" | "c" | "h" | "cpp" | "hpp" | "cs"
+        | "java" | "kt" | "rb" | "php" | "swift" | "lua" | "zig" => |theme| theme.command_color,
+        "toml" | "json" | "yaml" | "yml" | "ini" | "xml" | "lock" | "env" => {
+            |theme| theme.string_color
+        }
+        "md" | "txt" | "rst" | "pdf" | "html" | "css" => |theme| theme.info_color,
+        "zip" | "tar" | "gz" | "tgz" | "xz" | "bz2" | "7z" | "rar" => |theme| theme.warning_color,
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "ico" | "mp3" | "wav" | "flac"
+        | "mp4" | "mkv" | "mov" => |theme| theme.accent_secondary,
+        _ => |theme| theme.foreground,
+    }
+}
+
+/// A path with its directories dimmed and its name in `color`
+fn paint(path: &str, color: Color32, theme: &Theme) -> String {
+    let (parent, name) = match path.rfind('/') {
+        Some(slash) => path.split_at(slash + 1),
+        None => ("", path),
+    };
+    let parent = if parent.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}{}", fg(theme.comment_color), parent, RESET)
+    };
+    format!("{}{}{}{}", parent, fg(color), name, RESET)
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+    use crate::terminal::ansi::strip_ansi;
+
+    #[test]
+    fn test_fd() {
+        let fixture = Fixture::new("fd")
+            .file(".gitignore", "target/\n")
+            .file("Cargo.toml", "")
+            .file("src/main.rs", "")
+            .file("src/commands/registry.rs", "")
+            .file("target/debug/zaxiom.rs", "")
+            .file(".hidden/notes.md", "");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        state.output_piped = true;
+        let mut fd = |args: &[&str]| run(&FdCommand, args, &mut state).unwrap();
+
+        assert_eq!(
+            fd(&["-t", "f"]),
+            "Cargo.toml\nsrc/commands/registry.rs\nsrc/main.rs"
+        );
+        assert_eq!(fd(&["MAIN"]), "");
+        assert_eq!(fd(&["main|cargo"]), "Cargo.toml\nsrc/main.rs");
+        assert_eq!(
+            fd(&["-e", "RS", "-H", "-I"]),
+            "src/commands/registry.rs\nsrc/main.rs\ntarget/debug/zaxiom.rs"
+        );
+        assert_eq!(fd(&["-z", "rgs"]), "src/commands/registry.rs");
+        assert_eq!(fd(&["-g", "*.md", "-H"]), ".hidden/notes.md");
+        assert_eq!(fd(&["-t", "d", "-d", "1"]), "src");
+        assert_eq!(fd(&["-p", "^commands/", "src"]), "src/commands/registry.rs");
+
+        let output = fd(&["--color=always", "main"]);
+        assert!(output.contains("\x1b[38;2;"));
+        assert_eq!(strip_ansi(&output), "src/main.rs");
+    }
+}
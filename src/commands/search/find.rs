@@ -1,8 +1,22 @@
 //! find command - search for files
-
-use anyhow::Result;
-use walkdir::WalkDir;
-
+//!
+//! The tree is walked in parallel (see `walk`), and every entry is tested
+//! against an expression of `-name`, `-type`, `-size`, `-mtime` and the
+//! like, joined with `-a`, `-o`, `!`, and parentheses. Matches are printed,
+//! deleted (`-delete`), or handed to a command (`-exec`); the executor runs
+//! `-exec` itself, since the command can be a built-in or a program.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Result};
+use glob::{MatchOptions, Pattern};
+use ignore::DirEntry;
+
+use super::walk::{relative, Walk};
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -14,34 +28,67 @@ impl Command for FindCommand {
     }
 
     fn description(&self) -> &'static str {
-        "Search for files by name"
+        "Search for files by name, type, size, and age"
     }
 
     fn usage(&self) -> &'static str {
-        "find [path] -name <pattern>"
+        "find [path] [-maxdepth n] [expression] [-print|-delete|-exec cmd {} ;] [--pick]"
     }
 
     fn extended_help(&self) -> String {
         r#"find - Search for files and directories
 
 USAGE:
-  find [path] [OPTIONS]
+  find [path] [OPTIONS] [EXPRESSION] [ACTION]
 
 OPTIONS:
-  -name <pattern>    Search by filename pattern (supports wildcards)
-  -type f            Find only files
-  -type d            Find only directories
+  -maxdepth <n>      Descend at most n levels (0 is the path itself)
+  -mindepth <n>      Skip entries less than n levels deep
+  --pick             Open the results in the fuzzy finder
+
+TESTS:
+  -name <pattern>    File name matches (wildcards, case-sensitive)
+  -iname <pattern>   Same, ignoring case
+  -type f|d|l        Regular file, directory, or symbolic link
+  -size [+-]n[ckMG]  Size more (+), less (-), or exactly n units:
+                     c bytes, k KiB, M MiB, G GiB, none 512-byte blocks
+  -mtime [+-]n       Modified more (+), less (-), or exactly n days ago
+  -mmin [+-]n        Same in minutes
+  -empty             Empty file or directory
+
+OPERATORS:
+  expr expr          Both (same as expr -a expr)
+  expr -o expr       Either
+  ! expr, -not expr  Not
+  ( expr )           Grouping
+
+ACTIONS:
+  -print             Print the path (the default)
+  -delete            Delete matches (directories only when empty)
+  -exec cmd {} ;     Run cmd for each match, {} replaced by its path
+  -exec cmd {} +     Run cmd once with every match
+  Actions apply to every entry the tests match.
 
 DESCRIPTION:
-  Recursively search for files matching the given criteria.
-  Patterns support * and ? wildcards.
+  Recursively search for files matching the given expression, on
+  every core. Unlike grep, find shows hidden and ignored files.
+  The ; after -exec may be written \; as in other shells.
+
+  With --pick (or Ctrl+Shift+S after any find), the results open
+  in the fuzzy finder: Enter inserts the path, Ctrl+Enter opens
+  the file in the editor, Shift+Enter cds to its directory.
 
 EXAMPLES:
-  find . -name "*.rs"           Find all Rust files
-  find . -name "*.js" -type f   Find JS files only (not dirs)
-  find ~/projects -name "README*"   Find README files
-  find . -type d -name "src"    Find directories named "src"
-  find . -name "test*"          Find files starting with "test"
+  find . -name "*.rs"                Find all Rust files
+  find . -iname "readme*" -type f    README files, any case
+  find . -type d -name node_modules -maxdepth 2
+  find . -size +10M                  Files over 10 MiB
+  find . -mtime -1                   Changed in the last day
+  find . -name "*.log" -o -name "*.tmp"
+  find . ! -name "*.rs" -type f      Everything but Rust files
+  find . -name "*.tmp" -delete       Remove temp files
+  find . -name "*.rs" -exec wc -l {} \;
+  find . -name "*.toml" --pick       Pick one of the results
 
 PATTERNS:
   *        Match any characters
@@ -57,128 +104,571 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        let mut search_path = None;
-        let mut name_pattern = None;
-        let mut file_type = None; // "f" for file, "d" for directory
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let query = FindQuery::parse(args)?;
+        if query.exec.is_some() {
+            return Err(anyhow!("find: -exec runs from the command line"));
+        }
+        Ok(query.run(state, ctx)?.output.join("\n"))
+    }
+}
+
+/// A parsed `find` command line
+#[derive(Debug)]
+pub struct FindQuery {
+    path: Option<String>,
+    expr: Expr,
+    max_depth: Option<usize>,
+    min_depth: usize,
+    print: bool,
+    delete: bool,
+    exec: Option<Exec>,
+    pick: bool,
+}
+
+/// `-exec` arguments, `{}` standing for the path
+#[derive(Debug)]
+struct Exec {
+    args: Vec<String>,
+    /// Ended with `+`: one run with every path
+    batch: bool,
+}
 
-        let mut iter = args.iter().peekable();
+/// What a `find` run printed and matched
+pub struct Found {
+    pub output: Vec<String>,
+    pub paths: Vec<PathBuf>,
+}
+
+/// A test on one entry
+#[derive(Debug)]
+enum Expr {
+    True,
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Name {
+        pattern: Pattern,
+        ignore_case: bool,
+    },
+    Type(char),
+    /// Size in units of `unit` bytes, rounded up
+    Size {
+        unit: u64,
+        compare: Compare,
+    },
+    /// Time since modified in units of `unit` seconds, rounded down
+    Modified {
+        unit: u64,
+        compare: Compare,
+    },
+    Empty,
+}
+
+/// `+n`, `-n`, or `n`
+#[derive(Debug, Clone, Copy)]
+struct Compare {
+    ordering: Ordering,
+    value: u64,
+}
+
+impl FindQuery {
+    /// Whether a `find` command line has an `-exec` action
+    pub fn has_exec(args: &[String]) -> bool {
+        args.iter().any(|arg| arg == "-exec")
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut query = Self {
+            path: None,
+            expr: Expr::True,
+            max_depth: None,
+            min_depth: 0,
+            print: false,
+            delete: false,
+            exec: None,
+            pick: false,
+        };
+
+        // Options may come anywhere; what remains after the path is the expression
+        let mut tokens = Vec::new();
+        let mut iter = args.iter();
         while let Some(arg) = iter.next() {
             match arg.as_str() {
-                "-name" => {
-                    name_pattern = iter.next().map(|s| s.as_str());
-                }
-                "-type" => {
-                    file_type = iter.next().map(|s| s.as_str());
-                }
-                _ if !arg.starts_with('-') && search_path.is_none() => {
-                    search_path = Some(arg.as_str());
+                "-maxdepth" => query.max_depth = Some(number(iter.next(), arg)?),
+                "-mindepth" => query.min_depth = number(iter.next(), arg)?,
+                "--pick" => query.pick = true,
+                _ if tokens.is_empty()
+                    && query.path.is_none()
+                    && !arg.starts_with('-')
+                    && !matches!(arg.as_str(), "!" | "(" | ")") =>
+                {
+                    query.path = Some(arg.clone())
                 }
-                _ => {}
+                _ => tokens.push(arg.as_str()),
             }
         }
 
-        let base_path = match search_path {
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            query: &mut query,
+        };
+        let expr = if parser.tokens.is_empty() {
+            Expr::True
+        } else {
+            parser.or()?
+        };
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(anyhow!("find: unexpected '{}'", token));
+        }
+        query.expr = expr;
+        if !query.delete && query.exec.is_none() {
+            query.print = true;
+        }
+        Ok(query)
+    }
+
+    /// Walk the tree, printing (or streaming) and deleting matches as asked
+    pub fn run(&self, state: &mut TerminalState, ctx: &CommandContext) -> Result<Found> {
+        let base_path = match &self.path {
             Some(p) => state.resolve_path(p),
             None => state.cwd().clone(),
         };
-
         if !base_path.exists() {
-            return Err(anyhow::anyhow!(
-                "No such directory: {}",
-                base_path.display()
-            ));
+            return Err(anyhow!("No such directory: {}", base_path.display()));
         }
 
-        let mut results = Vec::new();
-
-        for entry in WalkDir::new(&base_path).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
+        // Paths are shown below the path as typed ("./src/main.rs")
+        let prefix = self
+            .path
+            .as_deref()
+            .unwrap_or(".")
+            .trim_end_matches(['/', '\\'])
+            .to_string();
+        let display = |path: &Path| match relative(path, &base_path) {
+            rel if rel.is_empty() => prefix.clone(),
+            rel => format!("{}/{}", prefix, rel),
+        };
 
-            // Check type filter
-            if let Some(t) = file_type {
-                let is_match = match t {
-                    "f" => path.is_file(),
-                    "d" => path.is_dir(),
-                    _ => true,
-                };
-                if !is_match {
-                    continue;
-                }
+        let now = SystemTime::now();
+        let stream = self.print && !self.delete && ctx.is_streaming();
+        let walk = Walk::new()
+            .hidden(true)
+            .respect_ignore(false)
+            .max_depth(self.max_depth);
+        let found = walk.run(&base_path, ctx, |entry| {
+            if entry.depth() < self.min_depth || !self.expr.eval(entry, now) {
+                return None;
             }
+            if stream {
+                ctx.print(&display(entry.path()));
+            }
+            Some(())
+        })?;
+        let paths: Vec<PathBuf> = found.into_iter().map(|(path, ())| path).collect();
 
-            // Check name pattern
-            if let Some(pattern) = name_pattern {
-                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                if !matches_glob(name, pattern) {
-                    continue;
+        let mut output = Vec::new();
+        if self.print && !stream {
+            output.extend(paths.iter().map(|path| display(path)));
+        }
+        if self.delete {
+            // Sorted paths put a directory before its contents
+            for path in paths.iter().rev() {
+                ctx.check()?;
+                let removed = if path.is_dir() {
+                    fs::remove_dir(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                if let Err(e) = removed {
+                    output.push(format!("find: cannot delete '{}': {}", display(path), e));
                 }
             }
+        }
+        if self.pick {
+            let picked: Vec<String> = paths
+                .iter()
+                .map(|path| relative(path, state.cwd()))
+                .collect();
+            state.requested_pick = Some(picked.join("\n"));
+        }
+        Ok(Found { output, paths })
+    }
 
-            // Format path with forward slashes
-            let display_path = path
-                .strip_prefix(&base_path)
-                .unwrap_or(path)
-                .display()
-                .to_string()
-                .replace('\\', "/");
-
-            if display_path.is_empty() {
-                results.push(".".to_string());
-            } else {
-                results.push(format!("./{}", display_path));
+    /// The commands `-exec` runs for `paths`, each as separate words with
+    /// `{}` replaced by a path (relative to `cwd` where it can be). They
+    /// run without a shell, so paths and arguments are never re-split.
+    pub fn exec_argvs(&self, paths: &[PathBuf], cwd: &Path) -> Vec<Vec<String>> {
+        let Some(exec) = &self.exec else {
+            return Vec::new();
+        };
+        let paths: Vec<String> = paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(cwd)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        if exec.batch {
+            if paths.is_empty() {
+                return Vec::new();
             }
+            // `{} +`: every path in place of the `{}` word
+            let argv = exec
+                .args
+                .iter()
+                .flat_map(|arg| {
+                    if arg == "{}" {
+                        paths.clone()
+                    } else {
+                        vec![arg.clone()]
+                    }
+                })
+                .collect();
+            vec![argv]
+        } else {
+            paths
+                .iter()
+                .map(|path| {
+                    exec.args
+                        .iter()
+                        .map(|arg| arg.replace("{}", path))
+                        .collect()
+                })
+                .collect()
         }
-
-        Ok(results.join("\n"))
     }
 }
 
-/// Simple glob matching (supports * and ?)
-fn matches_glob(name: &str, pattern: &str) -> bool {
-    let pattern = pattern.to_lowercase();
-    let name = name.to_lowercase();
-
-    let mut pattern_chars = pattern.chars().peekable();
-    let mut name_chars = name.chars().peekable();
-
-    while let Some(p) = pattern_chars.next() {
-        match p {
-            '*' => {
-                // * matches any sequence
-                if pattern_chars.peek().is_none() {
-                    // Trailing * matches everything
-                    return true;
-                }
+/// Recursive descent over the expression tokens: `or` of `and`s of
+/// (possibly negated) primaries
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+    query: &'a mut FindQuery,
+}
 
-                // Try matching rest of pattern at each position
-                let rest_pattern: String = pattern_chars.collect();
-                let mut remaining: String = name_chars.collect();
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
 
-                while !remaining.is_empty() {
-                    if matches_glob(&remaining, &rest_pattern) {
-                        return true;
-                    }
-                    remaining = remaining.chars().skip(1).collect();
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    /// The value after a test like `-name`
+    fn value(&mut self, test: &str) -> Result<&'a str> {
+        self.next()
+            .ok_or_else(|| anyhow!("find: {} needs a value", test))
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while matches!(self.peek(), Some("-o" | "-or")) {
+            self.next();
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        loop {
+            match self.peek() {
+                None | Some(")" | "-o" | "-or") => return Ok(expr),
+                Some("-a" | "-and") => {
+                    self.next();
                 }
+                _ => {}
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if matches!(self.peek(), Some("!" | "-not")) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
 
-                return matches_glob("", &rest_pattern);
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self
+            .next()
+            .ok_or_else(|| anyhow!("find: expression ends too early"))?;
+        Ok(match token {
+            "(" => {
+                let expr = self.or()?;
+                if self.next() != Some(")") {
+                    return Err(anyhow!("find: missing ')'"));
+                }
+                expr
             }
-            '?' => {
-                // ? matches any single character
-                if name_chars.next().is_none() {
-                    return false;
+            "-name" | "-iname" => {
+                let pattern = self.value(token)?;
+                Expr::Name {
+                    pattern: Pattern::new(pattern)
+                        .map_err(|e| anyhow!("find: bad pattern '{}': {}", pattern, e))?,
+                    ignore_case: token == "-iname",
                 }
             }
-            c => {
-                // Literal match
-                if name_chars.next() != Some(c) {
-                    return false;
+            "-type" => match self.value(token)? {
+                kind @ ("f" | "d" | "l") => Expr::Type(kind.chars().next().unwrap_or('f')),
+                other => return Err(anyhow!("find: -type must be f, d, or l, not '{}'", other)),
+            },
+            "-size" => {
+                let value = self.value(token)?;
+                let (digits, unit) = match value.char_indices().last() {
+                    Some((i, c)) if c.is_ascii_alphabetic() => (&value[..i], c),
+                    _ => (value, 'b'),
+                };
+                let unit = match unit {
+                    'c' => 1,
+                    'b' => 512,
+                    'k' => 1 << 10,
+                    'M' => 1 << 20,
+                    'G' => 1 << 30,
+                    _ => return Err(anyhow!("find: unknown -size unit in '{}'", value)),
+                };
+                Expr::Size {
+                    unit,
+                    compare: compare(digits, token)?,
+                }
+            }
+            "-mtime" | "-mmin" => Expr::Modified {
+                unit: if token == "-mtime" { 86_400 } else { 60 },
+                compare: compare(self.value(token)?, token)?,
+            },
+            "-empty" => Expr::Empty,
+            "-print" => {
+                self.query.print = true;
+                Expr::True
+            }
+            "-delete" => {
+                self.query.delete = true;
+                Expr::True
+            }
+            "-exec" => {
+                let mut args = Vec::new();
+                let batch = loop {
+                    match self.next() {
+                        Some(";" | "\\;") => break false,
+                        Some("+") if args.last() == Some(&"{}".to_string()) => break true,
+                        Some(arg) => args.push(arg.to_string()),
+                        None => return Err(anyhow!("find: -exec needs to end with ; or +")),
+                    }
+                };
+                if args.is_empty() {
+                    return Err(anyhow!("find: -exec needs a command"));
+                }
+                self.query.exec = Some(Exec { args, batch });
+                Expr::True
+            }
+            other => return Err(anyhow!("find: unknown test '{}'", other)),
+        })
+    }
+}
+
+impl Expr {
+    fn eval(&self, entry: &DirEntry, now: SystemTime) -> bool {
+        let file_type = entry.file_type();
+        match self {
+            Expr::True => true,
+            Expr::Not(expr) => !expr.eval(entry, now),
+            Expr::And(a, b) => a.eval(entry, now) && b.eval(entry, now),
+            Expr::Or(a, b) => a.eval(entry, now) || b.eval(entry, now),
+            Expr::Name {
+                pattern,
+                ignore_case,
+            } => {
+                let options = MatchOptions {
+                    case_sensitive: !ignore_case,
+                    ..MatchOptions::new()
+                };
+                pattern.matches_with(&entry.file_name().to_string_lossy(), options)
+            }
+            Expr::Type(kind) => file_type.is_some_and(|t| match kind {
+                'd' => t.is_dir(),
+                'l' => t.is_symlink(),
+                _ => t.is_file(),
+            }),
+            Expr::Size { unit, compare } => entry
+                .metadata()
+                .is_ok_and(|meta| compare.matches(meta.len().div_ceil(*unit))),
+            Expr::Modified { unit, compare } => entry
+                .metadata()
+                .ok()
+                .and_then(|meta| meta.modified().ok())
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| compare.matches(age.as_secs() / unit)),
+            Expr::Empty => {
+                if file_type.is_some_and(|t| t.is_dir()) {
+                    fs::read_dir(entry.path()).is_ok_and(|mut dir| dir.next().is_none())
+                } else {
+                    entry
+                        .metadata()
+                        .is_ok_and(|meta| meta.is_file() && meta.len() == 0)
                 }
             }
         }
     }
+}
 
-    // Pattern consumed, name should also be consumed
-    name_chars.next().is_none()
+impl Compare {
+    fn matches(self, value: u64) -> bool {
+        value.cmp(&self.value) == self.ordering
+    }
+}
+
+/// `+n` (more than), `-n` (less than), or `n` (exactly)
+fn compare(value: &str, test: &str) -> Result<Compare> {
+    let (ordering, digits) = match value.as_bytes().first() {
+        Some(b'+') => (Ordering::Greater, &value[1..]),
+        Some(b'-') => (Ordering::Less, &value[1..]),
+        _ => (Ordering::Equal, value),
+    };
+    let value = digits
+        .parse()
+        .map_err(|_| anyhow!("find: {} needs a number, not '{}'", test, value))?;
+    Ok(Compare { ordering, value })
+}
+
+/// The number after `-maxdepth` or `-mindepth`
+fn number(value: Option<&String>, option: &str) -> Result<usize> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| anyhow!("find: {} needs a number", option))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::context::{CancelToken, Interrupted};
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_find_stops_when_cancelled() {
+        let fixture = Fixture::new("find").file("a.rs", "").file("b.txt", "");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let args = vec!["-name".to_string(), "*.rs".to_string()];
+
+        let token = CancelToken::new();
+        let ctx = CommandContext::new(token.clone());
+        let output = FindCommand
+            .execute_with_context(&args, None, &mut state, &ctx)
+            .unwrap();
+        assert_eq!(output, "./a.rs");
+
+        token.cancel();
+        let err = FindCommand
+            .execute_with_context(&args, None, &mut state, &ctx)
+            .unwrap_err();
+        assert!(err.downcast_ref::<Interrupted>().is_some());
+    }
+
+    #[test]
+    fn test_find_expressions() {
+        let fixture = Fixture::new("find-expr")
+            .file("README.md", "read me")
+            .file("src/main.rs", "fn main() {}")
+            .file("src/lib.rs", "")
+            .file("logs/app.log", &"x".repeat(2000))
+            .file("logs/old.tmp", "")
+            .dir("empty");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let mut find = |args: &[&str]| run(&FindCommand, args, &mut state).unwrap();
+
+        assert_eq!(find(&["-name", "readme*"]), "");
+        assert_eq!(find(&["-iname", "readme*"]), "./README.md");
+        assert_eq!(
+            find(&["src", "-type", "f", "-name", "*.rs"]),
+            "src/lib.rs\nsrc/main.rs"
+        );
+        assert_eq!(
+            find(&["-maxdepth", "1", "-type", "d"]),
+            ".\n./empty\n./logs\n./src"
+        );
+        assert_eq!(
+            find(&["-type", "f", "-size", "+3", "-o", "-name", "*.md"]),
+            "./README.md\n./logs/app.log"
+        );
+        assert_eq!(
+            find(&["-size", "-2k", "-type", "f", "!", "-empty"]),
+            "./README.md\n./src/main.rs"
+        );
+        assert_eq!(
+            find(&["-type", "f", "(", "-name", "*.tmp", "-or", "-name", "*.log", ")"]),
+            "./logs/app.log\n./logs/old.tmp"
+        );
+        assert_eq!(find(&["-mtime", "-1", "-name", "lib.rs"]), "./src/lib.rs");
+        assert_eq!(find(&["-mtime", "+1", "-type", "f"]), "");
+        assert_eq!(find(&["-empty", "-type", "d"]), "./empty");
+
+        // Deleting children before their directory
+        assert_eq!(find(&["logs", "-delete"]), "");
+        assert!(!fixture.path().join("logs").exists());
+
+        for args in [
+            &["-type", "x"][..],
+            &["-size", "+1Q"],
+            &["(", "-empty"],
+            &["-frobnicate"],
+        ] {
+            assert!(run(&FindCommand, args, &mut state).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_exec_argvs() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        let paths = [PathBuf::from("/src/a b/x.rs"), PathBuf::from("/y.rs")];
+        let cwd = Path::new("/src");
+
+        let query = FindQuery::parse(&args(&[".", "-exec", "wc", "-l", "{}", "\\;"])).unwrap();
+        assert!(!query.print);
+        assert_eq!(
+            query.exec_argvs(&paths, cwd),
+            [
+                args(&["wc", "-l", "a b/x.rs"]),
+                args(&["wc", "-l", "/y.rs"])
+            ]
+        );
+        let query = FindQuery::parse(&args(&["-name", "*.rs", "-exec", "cat", "{}", "+"])).unwrap();
+        assert_eq!(
+            query.exec_argvs(&paths, cwd),
+            [args(&["cat", "a b/x.rs", "/y.rs"])]
+        );
+        // A quoted argument stays one word, and `{}` inside a word is replaced
+        let query = FindQuery::parse(&args(&[
+            "-exec",
+            "grep",
+            "-l",
+            "fn main",
+            "--label={}",
+            "{}",
+            ";",
+        ]))
+        .unwrap();
+        assert_eq!(
+            query.exec_argvs(&paths[1..], cwd),
+            [args(&["grep", "-l", "fn main", "--label=/y.rs", "/y.rs"])]
+        );
+        assert!(FindQuery::parse(&args(&["-exec", "cat", "{}"])).is_err());
+    }
 }
@@ -1,14 +1,31 @@
 //! grep command - search file contents
+//!
+//! Files named on the command line are searched as they are; directories
+//! (the current one when nothing is named) are walked in parallel, skipping
+//! ignored, hidden, and binary files like ripgrep does.
 
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use eframe::egui::Color32;
 use regex::Regex;
 
+use super::walk::{relative, Walk};
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
+use crate::config::theme::Theme;
+use crate::terminal::ansi::strip_ansi;
 use crate::terminal::state::TerminalState;
 
+/// Lines read between checks for cancellation
+const CHECK_EVERY_LINES: usize = 4096;
+
+/// Bytes looked at for a NUL when telling binary files from text
+const BINARY_PEEK: usize = 8192;
+
+const RESET: &str = "\x1b[0m";
+
 pub struct GrepCommand;
 
 impl Command for GrepCommand {
@@ -21,33 +38,55 @@ impl Command for GrepCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "grep [-i] [-n] [-v] <pattern> [file...]"
+        "grep [-i] [-n] [-v] [-C num] [--pick] <pattern> [file|dir...]"
     }
 
     fn extended_help(&self) -> String {
         r#"grep - Search for patterns in files
 
 USAGE:
-  grep [OPTIONS] <pattern> [file...]
+  grep [OPTIONS] <pattern> [file|dir...]
   command | grep <pattern>
 
 OPTIONS:
   -i, --ignore-case     Case-insensitive matching
   -n, --line-number     Show line numbers
   -v, --invert-match    Show lines that DON'T match
-  -in, -ni              Combine -i and -n
+  -C, --context <num>   Show num lines around each match
+  -A <num> / -B <num>   Show num lines after / before each match
+  -e <pattern>          Pattern (for one starting with -)
+  -r, -R                Accepted; directories are always searched
+  --hidden              Search hidden files and directories too
+  --no-ignore           Search files .gitignore lists too
+  --color <when>        auto (default), always, or never
+  --pick                Open the hits in the fuzzy finder
+  Short options combine: -in, -inC2
 
 DESCRIPTION:
   Search for PATTERN in each FILE or standard input.
   PATTERN is a regular expression.
 
+  Directories are searched recursively on every core, and with no
+  file or piped input the current directory is. Like git, this skips
+  what .gitignore and .ignore files list, hidden files, and binary
+  files (named binary files report whether they match). Matches are
+  highlighted in the theme's colors unless the output is piped.
+
+  With --pick (or Ctrl+Shift+S after any grep), the hits open in
+  the fuzzy finder: Enter inserts the path, Ctrl+Enter opens the
+  file in the editor at the matching line, Shift+Enter cds to its
+  directory. --pick always prints file names and line numbers.
+
 EXAMPLES:
   grep "error" log.txt           Find "error" in log.txt
   grep -i "error" log.txt        Case-insensitive search
   grep -n "TODO" *.rs            Show line numbers, search all .rs files
+  grep -n TODO src               Search every file under src
+  grep -C 2 "panic" src          Two lines of context around each hit
   grep -v "^#" config.txt        Show lines not starting with #
   cat file.txt | grep "pattern"  Search in piped input
   grep "func.*\(" *.js           Regex: find function definitions
+  grep --pick "TODO" *.rs        Pick a hit and jump to it
 
 REGEX PATTERNS:
   .        Any character
@@ -80,41 +119,102 @@ RELATED COMMANDS:
         stdin: Option<&str>,
         state: &mut TerminalState,
     ) -> Result<String> {
-        let mut case_insensitive = false;
-        let mut show_line_numbers = false;
-        let mut invert_match = false;
-        let mut pattern_str = None;
-        let mut files = Vec::new();
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
+    }
 
-        for arg in args {
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let mut case_insensitive = false;
+        let mut format = Format::default();
+        let mut context = None;
+        let mut walk = Walk::new();
+        let mut color = None;
+        let mut pick = false;
+        let mut pattern_flag = None;
+        let mut positional = Vec::new();
+
+        let mut iter = args.iter();
+        let mut options_done = false;
+        while let Some(arg) = iter.next() {
+            if options_done || !arg.starts_with('-') || arg == "-" {
+                positional.push(arg.as_str());
+                continue;
+            }
             match arg.as_str() {
-                "-i" | "--ignore-case" => case_insensitive = true,
-                "-n" | "--line-number" => show_line_numbers = true,
-                "-v" | "--invert-match" => invert_match = true,
+                "--" => options_done = true,
+                "--ignore-case" => case_insensitive = true,
+                "--line-number" => format.line_numbers = true,
+                "--invert-match" => format.invert = true,
+                "--context" => context = Some(count(iter.next().map(|s| s.as_str()), "--context")?),
+                "--hidden" => walk = walk.hidden(true),
+                "--no-ignore" => walk = walk.respect_ignore(false),
+                "--color" | "--colour" => color = iter.next().map(|s| s.as_str()),
+                "--pick" => pick = true,
                 "-h" | "--help" => {
-                    return Ok("Usage: grep [OPTIONS] <pattern> [file...]\n\
+                    return Ok("Usage: grep [OPTIONS] <pattern> [file|dir...]\n\
                         Options:\n  \
                         -i    Ignore case\n  \
                         -n    Show line numbers\n  \
-                        -v    Invert match (show non-matching lines)"
+                        -v    Invert match (show non-matching lines)\n  \
+                        -C N  Show N lines of context"
                         .to_string());
                 }
-                "-in" | "-ni" => {
-                    case_insensitive = true;
-                    show_line_numbers = true;
+                _ if arg.starts_with("--color=") || arg.starts_with("--colour=") => {
+                    color = arg.split_once('=').map(|(_, when)| when);
                 }
-                _ if !arg.starts_with('-') => {
-                    if pattern_str.is_none() {
-                        pattern_str = Some(arg.as_str());
-                    } else {
-                        files.push(arg.as_str());
+                _ if arg.starts_with("--") => {}
+                // Short options, which combine: -in, -C2, -inC 2
+                _ => {
+                    let flags = &arg[1..];
+                    for (i, flag) in flags.char_indices() {
+                        match flag {
+                            'i' => case_insensitive = true,
+                            'n' => format.line_numbers = true,
+                            'v' => format.invert = true,
+                            'r' | 'R' => {}
+                            'A' | 'B' | 'C' | 'e' => {
+                                let rest = &flags[i + 1..];
+                                let value = if rest.is_empty() {
+                                    iter.next().map(|s| s.as_str())
+                                } else {
+                                    Some(rest)
+                                };
+                                let name = format!("-{}", flag);
+                                match flag {
+                                    'A' => format.after = count(value, &name)?,
+                                    'B' => format.before = count(value, &name)?,
+                                    'C' => context = Some(count(value, &name)?),
+                                    _ => {
+                                        pattern_flag =
+                                            Some(value.ok_or_else(|| {
+                                                anyhow!("grep: -e needs a pattern")
+                                            })?)
+                                    }
+                                }
+                                break;
+                            }
+                            _ => {}
+                        }
                     }
                 }
-                _ => {}
             }
         }
+        if let Some(context) = context {
+            format.before = format.before.max(context);
+            format.after = format.after.max(context);
+        }
 
-        let pattern = pattern_str.ok_or_else(|| anyhow::anyhow!("grep: missing pattern"))?;
+        // The pattern is the first argument unless -e gave it
+        let (pattern, files) = match pattern_flag {
+            Some(pattern) => (pattern, positional),
+            None if positional.is_empty() => return Err(anyhow!("grep: missing pattern")),
+            None => (positional.remove(0), positional),
+        };
 
         // Build regex
         let regex_pattern = if case_insensitive {
@@ -123,82 +223,350 @@ RELATED COMMANDS:
             pattern.to_string()
         };
 
-        let regex = Regex::new(&regex_pattern)
-            .map_err(|e| anyhow::anyhow!("grep: invalid pattern: {}", e))?;
-
-        let mut output = Vec::new();
+        let regex =
+            Regex::new(&regex_pattern).map_err(|e| anyhow!("grep: invalid pattern: {}", e))?;
+
+        let colored = match color {
+            None | Some("auto") => !state.output_piped,
+            Some("always") => true,
+            Some("never") => false,
+            Some(other) => {
+                return Err(anyhow!(
+                    "grep: --color must be auto, always, or never, not '{}'",
+                    other
+                ))
+            }
+        };
+        if colored {
+            format.colors = Some(Colors::new(&Theme::from_name(state.current_theme)));
+        }
 
-        // If no files specified but we have stdin, use stdin
+        // Piped input is searched when no file or directory is named
         if files.is_empty() {
             if let Some(input) = stdin {
-                for (line_num, line) in input.lines().enumerate() {
-                    let matches = regex.is_match(line);
-                    if matches != invert_match {
-                        let mut result = String::new();
-                        if show_line_numbers {
-                            result.push_str(&format!("{}:", line_num + 1));
-                        }
-                        result.push_str(line);
-                        output.push(result);
-                    }
+                let output = search(input, "", &regex, &format, ctx)?.join("\n");
+                if pick {
+                    state.requested_pick = Some(strip_ansi(&output));
                 }
-                return Ok(output.join("\n"));
-            } else {
-                return Err(anyhow::anyhow!("grep: no input files"));
+                return Ok(output);
             }
         }
 
-        let show_filename = files.len() > 1;
+        // Picked hits need the file and line to act on
+        let walks = files.is_empty() || files.iter().any(|f| state.resolve_path(f).is_dir());
+        format.filenames = files.len() > 1 || walks || pick;
+        if pick {
+            format.line_numbers = true;
+        }
+
+        let mut output = Vec::new();
+        let mut add = |lines: Vec<String>| {
+            if lines.is_empty() {
+                return;
+            }
+            if format.has_context() && !output.is_empty() {
+                output.push("--".to_string());
+            }
+            output.extend(lines);
+        };
+
+        if files.is_empty() {
+            let cwd = state.cwd().clone();
+            search_tree(&cwd, "", walk, &regex, &format, ctx)?
+                .into_iter()
+                .for_each(&mut add);
+        }
 
         for file in files {
+            ctx.check()?;
             let path = state.resolve_path(file);
 
             if !path.exists() {
-                output.push(format!("grep: {}: No such file", file));
+                add(vec![format!("grep: {}: No such file", file)]);
                 continue;
             }
 
             if path.is_dir() {
-                output.push(format!("grep: {}: Is a directory", file));
+                let prefix = file.trim_end_matches(['/', '\\']);
+                search_tree(&path, prefix, walk, &regex, &format, ctx)?
+                    .into_iter()
+                    .for_each(&mut add);
                 continue;
             }
 
-            let file_handle = match fs::File::open(&path) {
-                Ok(f) => f,
-                Err(e) => {
-                    output.push(format!("grep: {}: {}", file, e));
-                    continue;
+            match search_file(&path, file, &regex, &format, ctx, true) {
+                Ok(lines) => add(lines),
+                Err(e) if e.downcast_ref::<std::io::Error>().is_some() => {
+                    add(vec![format!("grep: {}: {}", file, e)])
                 }
-            };
+                Err(e) => return Err(e),
+            }
+        }
 
-            let reader = BufReader::new(file_handle);
+        let output = output.join("\n");
+        if pick {
+            state.requested_pick = Some(strip_ansi(&output));
+        }
+        Ok(output)
+    }
 
-            for (line_num, line) in reader.lines().enumerate() {
-                if let Ok(line) = line {
-                    let matches = regex.is_match(&line);
-                    if matches != invert_match {
-                        let mut result = String::new();
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
 
-                        if show_filename {
-                            result.push_str(file);
-                            result.push(':');
-                        }
+/// How hits are printed
+#[derive(Default)]
+struct Format {
+    filenames: bool,
+    line_numbers: bool,
+    invert: bool,
+    /// Lines of context before and after each hit
+    before: usize,
+    after: usize,
+    colors: Option<Colors>,
+}
 
-                        if show_line_numbers {
-                            result.push_str(&format!("{}:", line_num + 1));
-                        }
+/// ANSI escapes for the parts of a hit, in the theme's colors
+struct Colors {
+    path: String,
+    line_number: String,
+    matched: String,
+}
 
-                        result.push_str(&line);
-                        output.push(result);
-                    }
+impl Colors {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            path: fg(theme.path_color),
+            line_number: fg(theme.number_color),
+            matched: format!("\x1b[1m{}", fg(theme.error_color)),
+        }
+    }
+}
+
+impl Format {
+    fn has_context(&self) -> bool {
+        self.before > 0 || self.after > 0
+    }
+
+    /// One printed line: `name:12:text` for a hit, `name-11-text` for context
+    fn line(&self, name: &str, number: usize, text: &str, regex: &Regex, hit: bool) -> String {
+        let separator = if hit { ':' } else { '-' };
+        let mut out = String::new();
+        let paint = |out: &mut String, color: Option<&String>, text: &str| match color {
+            Some(color) => out.push_str(&format!("{}{}{}", color, text, RESET)),
+            None => out.push_str(text),
+        };
+        if self.filenames {
+            paint(&mut out, self.colors.as_ref().map(|c| &c.path), name);
+            out.push(separator);
+        }
+        if self.line_numbers {
+            let number = number.to_string();
+            paint(
+                &mut out,
+                self.colors.as_ref().map(|c| &c.line_number),
+                &number,
+            );
+            out.push(separator);
+        }
+        match &self.colors {
+            Some(colors) if hit && !self.invert => {
+                let mut last = 0;
+                for found in regex.find_iter(text).filter(|m| !m.is_empty()) {
+                    out.push_str(&text[last..found.start()]);
+                    paint(&mut out, Some(&colors.matched), found.as_str());
+                    last = found.end();
                 }
+                out.push_str(&text[last..]);
+            }
+            _ => out.push_str(text),
+        }
+        out
+    }
+}
+
+/// Printed lines for `text`: hits, the context around them, and `--`
+/// between groups of lines that aren't next to each other
+fn search(
+    text: &str,
+    name: &str,
+    regex: &Regex,
+    format: &Format,
+    ctx: &CommandContext,
+) -> Result<Vec<String>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut output = Vec::new();
+    let mut last_printed: Option<usize> = None;
+    // Context lines after the last hit are printed up to here
+    let mut after_until = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if i % CHECK_EVERY_LINES == 0 {
+            ctx.check()?;
+        }
+        if regex.is_match(line) != format.invert {
+            let from = i
+                .saturating_sub(format.before)
+                .max(last_printed.map_or(0, |last| last + 1));
+            if format.has_context() && last_printed.is_some_and(|last| from > last + 1) {
+                output.push("--".to_string());
+            }
+            for (j, before) in lines.iter().enumerate().take(i).skip(from) {
+                output.push(format.line(name, j + 1, before, regex, false));
             }
+            output.push(format.line(name, i + 1, line, regex, true));
+            last_printed = Some(i);
+            after_until = i + 1 + format.after;
+        } else if i < after_until {
+            output.push(format.line(name, i + 1, line, regex, false));
+            last_printed = Some(i);
         }
+    }
+    Ok(output)
+}
 
-        Ok(output.join("\n"))
+/// Printed lines for one file. A binary file prints one line saying it
+/// matches when it was named on the command line (`explicit`), and
+/// nothing when it was found by walking a directory.
+fn search_file(
+    path: &Path,
+    name: &str,
+    regex: &Regex,
+    format: &Format,
+    ctx: &CommandContext,
+    explicit: bool,
+) -> Result<Vec<String>> {
+    let bytes = fs::read(path)?;
+    let text = String::from_utf8_lossy(&bytes);
+    if is_binary(&bytes) {
+        let matches = explicit
+            && text
+                .lines()
+                .any(|line| regex.is_match(line) != format.invert);
+        return Ok(if matches {
+            vec![format!("grep: {}: binary file matches", name)]
+        } else {
+            Vec::new()
+        });
     }
+    search(&text, name, regex, format, ctx)
+}
 
-    fn supports_stdin(&self) -> bool {
-        true
+/// Search every file under `root` in parallel, naming them by their path
+/// below it after `prefix`. Returns each file's printed lines, sorted by path.
+fn search_tree(
+    root: &Path,
+    prefix: &str,
+    walk: Walk,
+    regex: &Regex,
+    format: &Format,
+    ctx: &CommandContext,
+) -> Result<Vec<Vec<String>>> {
+    let found = walk.run(root, ctx, |entry| {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            return None;
+        }
+        let name = match relative(entry.path(), root) {
+            rel if prefix.is_empty() => rel,
+            rel => format!("{}/{}", prefix, rel),
+        };
+        let lines = search_file(entry.path(), &name, regex, format, ctx, false).ok()?;
+        (!lines.is_empty()).then_some(lines)
+    })?;
+    Ok(found.into_iter().map(|(_, lines)| lines).collect())
+}
+
+/// Whether a file looks binary (has a NUL near the start)
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_PEEK)].contains(&0)
+}
+
+/// The number after a context flag
+fn count(value: Option<&str>, flag: &str) -> Result<usize> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| anyhow!("grep: {} needs a number of lines", flag))
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, run_stdin, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_grep_golden() {
+        let fixture = Fixture::new("grep")
+            .file("todo.txt", "buy milk\nTODO: fix grep\n")
+            .file("done.txt", "todo list cleared\n")
+            .file("main.rs", "// TODO: tests\nfn main() {}\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(
+            &GrepCommand,
+            &["--color=never", "-i", "-n", "todo", "todo.txt", "done.txt"],
+            &mut state,
+        )
+        .unwrap();
+        assert_golden("grep_files", &fixture.scrub(&output));
+
+        let output = run_stdin(&GrepCommand, &["-v", "b"], "a\nb\nc", &mut state).unwrap();
+        assert_golden("grep_invert_stdin", &output);
+    }
+
+    #[test]
+    fn test_grep_tree() {
+        let fixture = Fixture::new("grep-tree")
+            .file(".gitignore", "target/\n")
+            .file(
+                "src/lib.rs",
+                "use std::fs;\n\nfn load() {\n    todo!()\n}\n\nfn save() {\n    todo!()\n}\n",
+            )
+            .file("src/main.rs", "fn main() {}\n")
+            .file("target/out.rs", "todo!()\n")
+            .file("logo.png", "\0\0todo!()");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        state.output_piped = true;
+
+        // The current directory, skipping ignored and binary files
+        assert_eq!(
+            run(&GrepCommand, &["-n", "todo"], &mut state).unwrap(),
+            "src/lib.rs:4:    todo!()\nsrc/lib.rs:8:    todo!()"
+        );
+        assert_eq!(
+            run(&GrepCommand, &["todo", "logo.png"], &mut state).unwrap(),
+            "grep: logo.png: binary file matches"
+        );
+        assert_eq!(
+            run(&GrepCommand, &["-nC1", "todo", "src"], &mut state).unwrap(),
+            "src/lib.rs-3-fn load() {\n\
+             src/lib.rs:4:    todo!()\n\
+             src/lib.rs-5-}\n\
+             --\n\
+             src/lib.rs-7-fn save() {\n\
+             src/lib.rs:8:    todo!()\n\
+             src/lib.rs-9-}"
+        );
+        state.set_cwd(fixture.path().join("src"));
+        assert_eq!(
+            run(
+                &GrepCommand,
+                &["-B", "1", "-e", "fn main", "main.rs"],
+                &mut state
+            )
+            .unwrap(),
+            "fn main() {}"
+        );
+        assert!(run(&GrepCommand, &["-C", "x", "todo"], &mut state).is_err());
+
+        // Matches in the theme's colors when the output is shown
+        state.output_piped = false;
+        let output = run(&GrepCommand, &["fs", "lib.rs"], &mut state).unwrap();
+        assert!(output.starts_with("use std::\x1b[1m\x1b[38;2;"));
+        assert_eq!(strip_ansi(&output), "use std::fs;");
     }
 }
@@ -1,9 +1,12 @@
 //! Search commands
 //!
-//! grep, find
+//! grep, find, fd, and the parallel directory walk they share
 
+mod fd;
 mod find;
 mod grep;
+mod walk;
 
-pub use find::FindCommand;
+pub use fd::FdCommand;
+pub use find::{FindCommand, FindQuery};
 pub use grep::GrepCommand;
@@ -0,0 +1,156 @@
+//! Parallel directory walking for the search commands
+//!
+//! Walks a tree on every core with the `ignore` crate, skipping what
+//! `.gitignore` and `.ignore` files list and hidden entries, the way git
+//! and ripgrep do, unless asked not to. Results are sorted by path so the
+//! output is the same from one run to the next.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use ignore::{DirEntry, WalkBuilder, WalkState};
+
+use crate::commands::context::CommandContext;
+
+/// Entries walked between progress reports
+const PROGRESS_EVERY: usize = 10_000;
+
+/// Which entries of a tree to visit
+#[derive(Debug, Clone, Copy)]
+pub struct Walk {
+    hidden: bool,
+    ignore: bool,
+    max_depth: Option<usize>,
+}
+
+impl Walk {
+    /// Skip hidden and ignored entries, at any depth
+    pub fn new() -> Self {
+        Self {
+            hidden: false,
+            ignore: true,
+            max_depth: None,
+        }
+    }
+
+    /// Visit hidden files and directories too
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Whether to skip what `.gitignore` and `.ignore` files list
+    pub fn respect_ignore(mut self, ignore: bool) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    /// Go no deeper than `depth` levels below the root (0 is the root alone)
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Call `visit` for every entry under `root`, the root included, on
+    /// worker threads, and keep what it returns. Stops early when `ctx` is
+    /// cancelled.
+    pub fn run<T: Send>(
+        &self,
+        root: &Path,
+        ctx: &CommandContext,
+        visit: impl Fn(&DirEntry) -> Option<T> + Sync,
+    ) -> Result<Vec<(PathBuf, T)>> {
+        let found = Mutex::new(Vec::new());
+        let seen = AtomicUsize::new(0);
+
+        WalkBuilder::new(root)
+            .hidden(!self.hidden)
+            .ignore(self.ignore)
+            .git_ignore(self.ignore)
+            .git_global(self.ignore)
+            .git_exclude(self.ignore)
+            .parents(self.ignore)
+            // .gitignore files count outside a repository too
+            .require_git(false)
+            .max_depth(self.max_depth)
+            .build_parallel()
+            .run(|| {
+                let (found, seen, visit) = (&found, &seen, &visit);
+                Box::new(move |entry| {
+                    if ctx.check().is_err() {
+                        return WalkState::Quit;
+                    }
+                    let count = seen.fetch_add(1, Ordering::Relaxed) + 1;
+                    if count % PROGRESS_EVERY == 0 {
+                        ctx.report(&format!("{} entries searched", count));
+                    }
+                    if let Ok(entry) = entry {
+                        if let Some(value) = visit(&entry) {
+                            let path = entry.into_path();
+                            found.lock().unwrap().push((path, value));
+                        }
+                    }
+                    WalkState::Continue
+                })
+            });
+
+        ctx.check()?;
+        let mut found = found.into_inner().unwrap();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(found)
+    }
+}
+
+impl Default for Walk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `path` relative to `root`, with forward slashes
+pub fn relative(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::Fixture;
+
+    #[test]
+    fn test_walk_skips_ignored_and_hidden() {
+        let fixture = Fixture::new("walk")
+            .file(".gitignore", "target/\n*.log\n")
+            .file("src/main.rs", "")
+            .file("target/debug/out", "")
+            .file("app.log", "")
+            .file(".env", "");
+        let ctx = CommandContext::default();
+        let files = |walk: Walk| -> Vec<String> {
+            walk.run(fixture.path(), &ctx, |entry| {
+                entry.file_type().is_some_and(|t| t.is_file()).then_some(())
+            })
+            .unwrap()
+            .into_iter()
+            .map(|(path, ())| relative(&path, fixture.path()))
+            .collect()
+        };
+
+        assert_eq!(files(Walk::new()), ["src/main.rs"]);
+        assert_eq!(
+            files(Walk::new().hidden(true)),
+            [".env", ".gitignore", "src/main.rs"]
+        );
+        assert_eq!(
+            files(Walk::new().respect_ignore(false)),
+            ["app.log", "src/main.rs", "target/debug/out"]
+        );
+        assert!(files(Walk::new().max_depth(Some(1))).is_empty());
+    }
+}
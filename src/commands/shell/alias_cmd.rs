@@ -1,8 +1,12 @@
 //! alias command - define or display aliases
 
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
 
 use crate::commands::traits::Command;
+use crate::config::aliases::{self, AliasConfig};
+use crate::config::settings::Config;
 use crate::terminal::state::TerminalState;
 
 pub struct AliasCommand;
@@ -17,7 +21,7 @@ impl Command for AliasCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "alias [name[=value] ...]"
+        "alias [-s|-g] [name[=value] ...] | alias --list [query]"
     }
 
     fn extended_help(&self) -> String {
@@ -25,8 +29,11 @@ impl Command for AliasCommand {
 
 USAGE:
   alias                    List all aliases
+  alias --list query       List aliases fuzzy-matching query (-l)
   alias name               Show specific alias
-  alias name='command'     Create new alias
+  alias name='command'     Create new alias (saved for next time)
+  alias -s ext=handler     Open *.ext files with handler (suffix alias)
+  alias -g name='text'     Expand name anywhere in a line (global alias)
 
 DESCRIPTION:
   Create shortcuts for frequently used commands.
@@ -44,6 +51,12 @@ CREATING ALIASES:
   alias name='cmd1 && cmd2'     Chained commands
   alias name='cmd -flags'       Command with flags
 
+ARGUMENTS:
+  $1..$9 and $@ take the words typed after the alias; any past the
+  highest $n still go at the end:
+  alias gco='git checkout $1 && git pull'    gco main
+  alias swap='mv $2 $1'                      swap new old
+
 POPULAR ALIASES:
   alias ll='ls -la'             Detailed listing
   alias la='ls -A'              Show hidden files
@@ -54,19 +67,32 @@ POPULAR ALIASES:
   alias gc='git commit -m'      Git commit
   alias cls='clear'             Clear screen
 
+SUFFIX ALIASES:
+  Type a file name as the command to open it by extension:
+  alias -s md=nano              notes.md  ->  nano notes.md
+  alias -s pdf=open             report.pdf  ->  open report.pdf
+  alias -s html='open --with firefox {}'   {} is replaced by the file
+  alias -s                      List suffix aliases
+
+GLOBAL ALIASES:
+  Expanded anywhere in the line, not just as the command:
+  alias -g G='| grep'           ls G toml  ->  ls | grep toml
+  alias -g L='| head -20'       cat log L  ->  cat log | head -20
+  alias -g                      List global aliases
+
 REMOVING ALIASES:
   unalias name                  Remove an alias
+  unalias -s ext / -g name      Remove a suffix / global alias
   unalias -a                    Remove all aliases
 
 TIPS:
   • Quote the value to preserve spaces
   • Use single quotes to prevent expansion
-  • Aliases are session-specific (lost on close)
-
-FOR PERMANENT ALIASES:
-  Add to your shell config file:
-  ~/.bashrc (Bash)
-  ~/.zshrc (Zsh)
+  • An alias may use other aliases; a cycle stops at the first repeat
+  • Aliases expand after |, &&, ||, and ; as well as at the start
+  • Aliases are saved in aliases.toml in the config directory (see
+    `paths`); suffix and global aliases under [aliases.suffix] /
+    [aliases.global] in config.toml
 
 RELATED COMMANDS:
   unalias   Remove aliases
@@ -77,25 +103,33 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            Some("-s") => return define_saved(AliasKind::Suffix, &args[1..], state),
+            Some("-g") => return define_saved(AliasKind::Global, &args[1..], state),
+            Some("-l" | "--list") => {
+                let aliases = all_aliases(state, &Config::load().aliases);
+                let matches = matching(&aliases, &args[1..].join(" "));
+                if matches.is_empty() {
+                    return Err(anyhow!("alias: no aliases match '{}'", args[1..].join(" ")));
+                }
+                return Ok(matches.join("\n"));
+            }
+            _ => {}
+        }
+
         if args.is_empty() {
             // List all aliases
-            let aliases = state.list_aliases();
-            if aliases.is_empty() {
-                return Ok(String::new());
-            }
-            let output: Vec<String> = aliases
-                .iter()
-                .map(|(name, value)| format!("alias {}='{}'", name, value))
-                .collect();
-            return Ok(output.join("\n"));
+            let aliases = all_aliases(state, &Config::load().aliases);
+            return Ok(matching(&aliases, "").join("\n"));
         }
 
-        for arg in args {
+        for arg in definitions(args) {
             if arg == "-h" || arg == "--help" {
-                return Ok("Usage: alias [name[=value] ...]\n\
+                return Ok("Usage: alias [-s|-g] [name[=value] ...]\n\
                     Without arguments, prints all defined aliases.\n\
                     With name=value, defines an alias.\n\
-                    With name only, prints that alias."
+                    With name only, prints that alias.\n\
+                    -s defines suffix aliases (ext=handler), -g global aliases."
                     .to_string());
             }
 
@@ -105,13 +139,19 @@ RELATED COMMANDS:
                 let value = &arg[eq_pos + 1..];
                 // Remove surrounding quotes if present
                 let value = value.trim_matches('\'').trim_matches('"');
+                if name.is_empty() {
+                    return Err(anyhow!("alias: usage: alias name=value"));
+                }
                 state.set_alias(name.to_string(), value.to_string());
+                save_alias(name, value, state)?;
             } else {
                 // Print specific alias
-                if let Some(value) = state.get_alias(arg) {
-                    return Ok(format!("alias {}='{}'", arg, value));
-                } else {
-                    return Err(anyhow::anyhow!("alias: {}: not found", arg));
+                let value = state
+                    .get_alias(&arg)
+                    .or_else(|| Config::load().aliases.get(&arg).map(str::to_string));
+                match value {
+                    Some(value) => return Ok(format!("alias {}='{}'", arg, value)),
+                    None => return Err(anyhow!("alias: {}: not found", arg)),
                 }
             }
         }
@@ -119,3 +159,216 @@ RELATED COMMANDS:
         Ok(String::new())
     }
 }
+
+/// Aliases kept in the config file
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum AliasKind {
+    Suffix,
+    Global,
+}
+
+impl AliasKind {
+    fn flag(self) -> &'static str {
+        match self {
+            AliasKind::Suffix => "-s",
+            AliasKind::Global => "-g",
+        }
+    }
+
+    /// The saved aliases of this kind
+    pub(super) fn map(self, config: &mut Config) -> &mut HashMap<String, String> {
+        match self {
+            AliasKind::Suffix => &mut config.aliases.suffix,
+            AliasKind::Global => &mut config.aliases.global,
+        }
+    }
+}
+
+/// `alias -s` / `alias -g`: list or define saved aliases
+fn define_saved(kind: AliasKind, args: &[String], state: &mut TerminalState) -> Result<String> {
+    let mut config = Config::load();
+    if args.is_empty() {
+        return Ok(list(kind, kind.map(&mut config)).join("\n"));
+    }
+
+    let mut output = Vec::new();
+    for arg in definitions(args) {
+        match arg.split_once('=') {
+            Some((name, value)) => {
+                let name = match kind {
+                    AliasKind::Suffix => name.trim_start_matches('.'),
+                    AliasKind::Global => name,
+                };
+                let value = value.trim_matches('\'').trim_matches('"');
+                if name.is_empty() || value.is_empty() {
+                    return Err(anyhow!(
+                        "alias {}: usage: alias {} name=value",
+                        kind.flag(),
+                        kind.flag()
+                    ));
+                }
+                kind.map(&mut config)
+                    .insert(name.to_string(), value.to_string());
+            }
+            None => match kind.map(&mut config).get(arg.trim_start_matches('.')) {
+                Some(value) => output.push(format!("alias {} {}='{}'", kind.flag(), arg, value)),
+                None => return Err(anyhow!("alias: {}: not found", arg)),
+            },
+        }
+    }
+
+    if output.is_empty() {
+        config.save()?;
+        state.requested_aliases = Some(config.aliases);
+    }
+    Ok(output.join("\n"))
+}
+
+/// Save an alias made with `alias name=value` in aliases.toml, and have
+/// every pane pick it up
+fn save_alias(name: &str, value: &str, state: &mut TerminalState) -> Result<()> {
+    let path = aliases::saved_path();
+    let mut saved = aliases::load_saved(&path);
+    if saved.get(name).is_some_and(|saved| saved == value) {
+        return Ok(());
+    }
+    saved.insert(name.to_string(), value.to_string());
+    aliases::save_saved(&path, &saved)?;
+    state.requested_aliases = Some(Config::load().aliases);
+    Ok(())
+}
+
+/// Every alias as (name, value, `alias` line): command aliases by name,
+/// this pane's winning over saved ones, then global and suffix aliases
+fn all_aliases(state: &TerminalState, config: &AliasConfig) -> Vec<(String, String, String)> {
+    let mut commands: HashMap<&str, &str> = config.list().collect();
+    for (name, value) in state.list_aliases() {
+        commands.insert(name, value);
+    }
+    let mut commands: Vec<_> = commands.into_iter().collect();
+    commands.sort();
+
+    let mut all: Vec<_> = commands
+        .into_iter()
+        .map(|(name, value)| {
+            let line = format!("alias {}='{}'", name, value);
+            (name.to_string(), value.to_string(), line)
+        })
+        .collect();
+    for kind in [AliasKind::Global, AliasKind::Suffix] {
+        let saved = match kind {
+            AliasKind::Suffix => &config.suffix,
+            AliasKind::Global => &config.global,
+        };
+        let mut saved: Vec<_> = saved.iter().collect();
+        saved.sort();
+        all.extend(saved.into_iter().map(|(name, value)| {
+            let line = format!("alias {} {}='{}'", kind.flag(), name, value);
+            (name.clone(), value.clone(), line)
+        }));
+    }
+    all
+}
+
+/// Lines of the aliases whose name or value fuzzy-matches `query`, best
+/// first: name prefix, then name, then value substring, then the query's
+/// letters in order (an empty query matches everything, in order)
+fn matching(aliases: &[(String, String, String)], query: &str) -> Vec<String> {
+    let query = query.trim().to_lowercase();
+    let in_order = |text: &str| {
+        let mut chars = query.chars().peekable();
+        for c in text.chars() {
+            if chars.peek() == Some(&c) {
+                chars.next();
+            }
+        }
+        chars.peek().is_none()
+    };
+    let score = |name: &str, value: &str| {
+        let (name, value) = (name.to_lowercase(), value.to_lowercase());
+        if name.starts_with(&query) {
+            Some(0)
+        } else if name.contains(&query) {
+            Some(1)
+        } else if value.contains(&query) {
+            Some(2)
+        } else if in_order(&name) || in_order(&value) {
+            Some(3)
+        } else {
+            None
+        }
+    };
+
+    let mut scored: Vec<_> = aliases
+        .iter()
+        .filter_map(|(name, value, line)| score(name, value).map(|s| (s, line.clone())))
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, line)| line).collect()
+}
+
+/// `alias -s`/`-g` lines for saved aliases, sorted by name
+fn list(kind: AliasKind, aliases: &HashMap<String, String>) -> Vec<String> {
+    let mut aliases: Vec<_> = aliases.iter().collect();
+    aliases.sort();
+    aliases
+        .into_iter()
+        .map(|(name, value)| format!("alias {} {}='{}'", kind.flag(), name, value))
+        .collect()
+}
+
+/// Rejoin `name=` and a quoted value the parser split into two arguments
+fn definitions(args: &[String]) -> Vec<String> {
+    let mut definitions = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match args.as_slice().first() {
+            Some(value) if arg.ends_with('=') => {
+                definitions.push(format!("{}{}", arg, value));
+                args.next();
+            }
+            _ => definitions.push(arg.clone()),
+        }
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+
+    #[test]
+    fn test_list_and_search() {
+        let state = MockTerminalState::new()
+            .alias("gs", "git status --short")
+            .build();
+        let mut config = AliasConfig::new();
+        config.add("gs".into(), "git status".into());
+        config.saved.insert("k".into(), "kubectl".into());
+        config.saved.insert("gl".into(), "git log --oneline".into());
+        config.global.insert("G".into(), "| grep".into());
+
+        let aliases = all_aliases(&state, &config);
+        assert_eq!(
+            matching(&aliases, ""),
+            [
+                "alias gl='git log --oneline'",
+                "alias gs='git status --short'",
+                "alias k='kubectl'",
+                "alias -g G='| grep'",
+            ]
+        );
+        assert_eq!(
+            matching(&aliases, "g"),
+            [
+                "alias gl='git log --oneline'",
+                "alias gs='git status --short'",
+                "alias -g G='| grep'",
+            ]
+        );
+        assert_eq!(matching(&aliases, "kbc"), ["alias k='kubectl'"]);
+        assert_eq!(matching(&aliases, "one"), ["alias gl='git log --oneline'"]);
+        assert!(matching(&aliases, "xyz").is_empty());
+    }
+}
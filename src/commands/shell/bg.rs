@@ -0,0 +1,35 @@
+//! bg command - resume a suspended job in the background
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct BgCommand;
+
+impl Command for BgCommand {
+    fn name(&self) -> &'static str {
+        "bg"
+    }
+
+    fn description(&self) -> &'static str {
+        "Resume a suspended job in the background"
+    }
+
+    fn usage(&self) -> &'static str {
+        "bg [%n]\n\n\
+         Lets a job suspended with Ctrl+Z (the current one by default) run\n\
+         on without holding up the pane. See `jobs` for job names."
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let jobs = state.jobs();
+        let id = jobs
+            .find(args.first().map(String::as_str))
+            .map_err(|e| anyhow!("bg: {}", e))?;
+        let command = jobs
+            .resume(id)
+            .ok_or_else(|| anyhow!("bg: %{}: no such job", id))?;
+        Ok(format!("[{}] {} &", id, command))
+    }
+}
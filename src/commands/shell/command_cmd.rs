@@ -20,7 +20,7 @@ impl Command for CommandCommand {
         "command [-v] [-V] command [args...]"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         let mut verbose = false;
         let mut very_verbose = false;
         let mut cmd_start = 0;
@@ -51,7 +51,7 @@ impl Command for CommandCommand {
 
         if verbose || very_verbose {
             // Just print where the command is
-            if let Some(path) = find_in_path(cmd_name) {
+            if let Some(path) = find_in_path(cmd_name, state) {
                 if very_verbose {
                     Ok(format!("{} is {}", cmd_name, path))
                 } else {
@@ -69,8 +69,8 @@ impl Command for CommandCommand {
     }
 }
 
-fn find_in_path(name: &str) -> Option<String> {
-    let path_var = std::env::var("PATH").ok()?;
+fn find_in_path(name: &str, state: &TerminalState) -> Option<String> {
+    let path_var = state.var("PATH")?;
     let extensions = ["", ".exe", ".cmd", ".bat", ".com"];
 
     for dir in path_var.split(';') {
@@ -2,9 +2,8 @@
 
 use anyhow::Result;
 
-use super::pushd::DIR_STACK;
 use crate::commands::traits::Command;
-use crate::terminal::state::TerminalState;
+use crate::terminal::state::{stack_index, TerminalState};
 
 pub struct DirsCommand;
 
@@ -18,45 +17,56 @@ impl Command for DirsCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "dirs [-c] [-l] [-v]"
+        "dirs [-c] [-l] [-p] [-v] [+n]"
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         let mut clear = false;
         let mut long_format = false;
         let mut vertical = false;
+        let mut numbered = false;
+        let mut entry = None;
 
         for arg in args {
             match arg.as_str() {
                 "-c" | "--clear" => clear = true,
                 "-l" | "--long" => long_format = true,
-                "-v" | "--vertical" => vertical = true,
+                "-v" | "--vertical" => {
+                    vertical = true;
+                    numbered = true;
+                }
                 "-p" => vertical = true,
-                _ => {}
+                _ => entry = entry.or_else(|| stack_index(arg)),
             }
         }
 
         if clear {
-            DIR_STACK.lock().unwrap().clear();
+            state.dir_stack.clear();
             return Ok(String::new());
         }
 
-        let stack = DIR_STACK.lock().unwrap();
         let home = state.home();
 
         // Current directory is always at "top" of displayed stack
-        let mut dirs: Vec<String> = vec![state.cwd().display().to_string()];
-        dirs.extend(
-            stack
-                .iter()
-                .rev()
-                .map(|p: &std::path::PathBuf| p.display().to_string()),
-        );
+        let mut dirs: Vec<(usize, String)> = state
+            .dirs()
+            .iter()
+            .map(|p| p.display().to_string())
+            .enumerate()
+            .collect();
+        if let Some(n) = entry {
+            if n >= dirs.len() {
+                return Err(anyhow::anyhow!(
+                    "dirs: +{}: directory stack index out of range",
+                    n
+                ));
+            }
+            dirs = vec![dirs.swap_remove(n)];
+        }
 
         // Format paths
         let formatted: Vec<String> = dirs
             .iter()
-            .enumerate()
             .map(|(i, d)| {
                 let display = if !long_format && d.starts_with(&home.display().to_string()) {
                     format!("~{}", &d[home.display().to_string().len()..])
@@ -65,8 +75,8 @@ impl Command for DirsCommand {
                 }
                 .replace('\\', "/");
 
-                if vertical {
-                    format!(" {} {}", i, display)
+                if numbered {
+                    format!("{:>2}  {}", i, display)
                 } else {
                     display
                 }
@@ -80,3 +90,32 @@ impl Command for DirsCommand {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_dirs_numbering() {
+        let fixture = Fixture::new("dirs").dir("a");
+        let root = fixture.path().to_path_buf();
+        let mut state = MockTerminalState::new().cwd(&root.join("a")).build();
+        state.dir_stack = vec![root.clone()];
+        let shown = |p: &std::path::Path| p.display().to_string().replace('\\', "/");
+
+        let output = run(&DirsCommand, &["-v", "-l"], &mut state).unwrap();
+        assert_eq!(
+            output,
+            format!(" 0  {}\n 1  {}", shown(&root.join("a")), shown(&root))
+        );
+        let output = run(&DirsCommand, &["-p", "-l"], &mut state).unwrap();
+        assert_eq!(
+            output,
+            format!("{}\n{}", shown(&root.join("a")), shown(&root))
+        );
+        let output = run(&DirsCommand, &["-l", "+1"], &mut state).unwrap();
+        assert_eq!(output, shown(&root));
+        assert!(run(&DirsCommand, &["+2"], &mut state).is_err());
+    }
+}
@@ -1,7 +1,6 @@
 //! env command - display environment variables
 
 use anyhow::Result;
-use std::env;
 
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
@@ -74,7 +73,7 @@ RELATED COMMANDS:
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         let mut null_sep = false;
         let mut name_filter: Option<&String> = None;
 
@@ -97,13 +96,12 @@ RELATED COMMANDS:
 
         if let Some(name) = name_filter {
             // Show specific variable
-            match env::var(name) {
-                Ok(val) => Ok(val),
-                Err(_) => Ok(String::new()),
-            }
+            Ok(state.var(name).unwrap_or_default())
         } else {
             // Show all variables
-            let vars: Vec<String> = env::vars()
+            let vars: Vec<String> = state
+                .vars()
+                .into_iter()
                 .map(|(key, val)| format!("{}={}", key, val))
                 .collect();
             Ok(vars.join(separator))
@@ -1,7 +1,6 @@
 //! export command - set environment variables
 
 use anyhow::Result;
-use std::env;
 
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
@@ -31,7 +30,7 @@ USAGE:
 
 DESCRIPTION:
   Set environment variables that will be available to
-  the current session and any child processes.
+  this pane and any programs started from it.
 
 EXAMPLES:
   export                          List all variables
@@ -64,8 +63,9 @@ APPENDING TO PATH:
   export PATH="/new/dir:$PATH"   Add to beginning
 
 SESSION SCOPE:
-  Variables set with export are temporary!
-  They disappear when you close the terminal.
+  Variables set with export are temporary and belong to
+  the pane they were set in - other panes don't see them.
+  They disappear when you close the pane.
 
 FOR PERMANENT VARIABLES:
   Add export commands to:
@@ -83,10 +83,12 @@ RELATED COMMANDS:
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
             // List all exported variables
-            let vars: Vec<String> = env::vars()
+            let vars: Vec<String> = state
+                .vars()
+                .into_iter()
                 .map(|(key, val)| format!("export {}=\"{}\"", key, val))
                 .collect();
             return Ok(vars.join("\n"));
@@ -107,7 +109,7 @@ RELATED COMMANDS:
                 let value = &arg[eq_pos + 1..];
                 // Remove surrounding quotes if present
                 let value = value.trim_matches('\'').trim_matches('"');
-                env::set_var(name, value);
+                state.set_var(name, value);
             }
             // If no =, we'd normally mark for export, but in this context
             // all env vars are already exported
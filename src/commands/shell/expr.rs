@@ -153,11 +153,9 @@ fn evaluate_expression(expr: &str) -> Result<String> {
             "!=" => return Ok(if left != right { "1" } else { "0" }.to_string()),
             "<" => return Ok(if left < right { "1" } else { "0" }.to_string()),
             ">" => return Ok(if left > right { "1" } else { "0" }.to_string()),
-            ":" => {
+            ":" if right == ".*" => {
                 // Match operation (simplified)
-                if right == ".*" {
-                    return Ok(left.len().to_string());
-                }
+                return Ok(left.len().to_string());
             }
             _ => {}
         }
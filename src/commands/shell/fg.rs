@@ -0,0 +1,35 @@
+//! fg command - bring a background job to the foreground
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct FgCommand;
+
+impl Command for FgCommand {
+    fn name(&self) -> &'static str {
+        "fg"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bring a job to the foreground"
+    }
+
+    fn usage(&self) -> &'static str {
+        "fg [%n]\n\n\
+         Resumes the job (the current one by default) and waits for it in\n\
+         this pane, showing what it printed meanwhile. Ctrl+C stops it,\n\
+         Ctrl+Z suspends it again. See `jobs` for job names."
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let id = state
+            .jobs()
+            .find(args.first().map(String::as_str))
+            .map_err(|e| anyhow!("fg: {}", e))?;
+        // The app takes the job out of the table and waits for it
+        state.requested_foreground = Some(id);
+        Ok(String::new())
+    }
+}
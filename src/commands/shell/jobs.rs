@@ -0,0 +1,49 @@
+//! jobs command - list the pane's background jobs
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::shell::job::JobStatus;
+use crate::terminal::state::TerminalState;
+
+pub struct JobsCommand;
+
+impl Command for JobsCommand {
+    fn name(&self) -> &'static str {
+        "jobs"
+    }
+
+    fn description(&self) -> &'static str {
+        "List background jobs"
+    }
+
+    fn usage(&self) -> &'static str {
+        "jobs\n\n\
+         Lists jobs started with `command &` or suspended with Ctrl+Z. The\n\
+         current job (+) is the one fg and bg pick without an argument.\n\n\
+         Job control:\n  \
+         command &     Run in the background\n  \
+         Ctrl+Z        Suspend the running command\n  \
+         fg [%n]       Bring a job back to the foreground\n  \
+         bg [%n]       Resume a suspended job in the background\n  \
+         kill %n       Stop a job\n\n\
+         Jobs are named %n (number), %+ or %% (current), %- (previous), or\n\
+         %text (command starts with text)."
+    }
+
+    fn execute(&self, _args: &[String], state: &mut TerminalState) -> Result<String> {
+        let lines: Vec<String> = state
+            .jobs()
+            .list()
+            .into_iter()
+            .map(|job| {
+                let status = match job.status {
+                    JobStatus::Running => "Running",
+                    JobStatus::Stopped => "Stopped",
+                };
+                format!("[{}]{}  {:<8}  {}", job.id, job.mark, status, job.command)
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,262 @@
+//! lorem command - placeholder text
+
+use anyhow::Result;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct LoremCommand;
+
+/// Most words, sentences or paragraphs generated in one go
+const MAX_COUNT: usize = 10_000;
+
+const OPENING: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+    "duis",
+    "aute",
+    "irure",
+    "in",
+    "reprehenderit",
+    "voluptate",
+    "velit",
+    "esse",
+    "cillum",
+    "fugiat",
+    "nulla",
+    "pariatur",
+    "excepteur",
+    "sint",
+    "occaecat",
+    "cupidatat",
+    "non",
+    "proident",
+    "sunt",
+    "culpa",
+    "qui",
+    "officia",
+    "deserunt",
+    "mollit",
+    "anim",
+    "id",
+    "est",
+    "laborum",
+    "integer",
+    "vitae",
+    "justo",
+    "eget",
+    "fermentum",
+    "iaculis",
+    "faucibus",
+    "ornare",
+    "suspendisse",
+    "potenti",
+    "nullam",
+    "porttitor",
+    "lacus",
+    "luctus",
+    "accumsan",
+    "tortor",
+    "posuere",
+];
+
+/// How much text to generate
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Unit {
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+impl Command for LoremCommand {
+    fn name(&self) -> &'static str {
+        "lorem"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generate lorem ipsum placeholder text"
+    }
+
+    fn usage(&self) -> &'static str {
+        "lorem [N][w|s|p]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"lorem - Generate lorem ipsum placeholder text
+
+USAGE:
+  lorem [N][w|s|p]
+
+DESCRIPTION:
+  Prints N words (w), sentences (s) or paragraphs (p) of
+  placeholder text, starting with the classic "Lorem ipsum
+  dolor sit amet". Without a unit, N counts paragraphs.
+
+EXAMPLES:
+  lorem                 One paragraph
+  lorem 3p              Three paragraphs
+  lorem 5s              Five sentences
+  lorem 12w             Twelve words
+  lorem 2 > filler.txt  Save two paragraphs
+
+RELATED COMMANDS:
+  random   Random numbers and strings
+  yes      Repeat a line
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let spec = match args.first().map(String::as_str) {
+            Some("-h" | "--help") => return Ok(self.extended_help()),
+            Some(spec) => spec,
+            None => "1p",
+        };
+        let (count, unit) = parse_spec(spec).ok_or_else(|| {
+            anyhow::anyhow!("lorem: expected a count like 3p, 5s or 12w, got '{}'", spec)
+        })?;
+        if count > MAX_COUNT {
+            return Err(anyhow::anyhow!("lorem: at most {} at a time", MAX_COUNT));
+        }
+
+        let mut rng = rand::rng();
+        let text = match unit {
+            Unit::Words => {
+                let mut words: Vec<String> = OPENING
+                    .to_lowercase()
+                    .replace(',', "")
+                    .split(' ')
+                    .map(str::to_string)
+                    .take(count)
+                    .collect();
+                while words.len() < count {
+                    words.push(WORDS.choose(&mut rng).unwrap_or(&"lorem").to_string());
+                }
+                capitalize(&words.join(" "))
+            }
+            Unit::Sentences => sentences(&mut rng, count, true).join(" "),
+            Unit::Paragraphs => (0..count)
+                .map(|i| {
+                    let n = rng.random_range(4..=7);
+                    sentences(&mut rng, n, i == 0).join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+
+        Ok(text)
+    }
+}
+
+/// "3p" → (3, Paragraphs); a bare number counts paragraphs
+fn parse_spec(spec: &str) -> Option<(usize, Unit)> {
+    let split = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    let (digits, unit) = spec.split_at(split);
+    let count = if digits.is_empty() {
+        1
+    } else {
+        digits.parse().ok()?
+    };
+    let unit = match unit {
+        "" | "p" | "para" | "paragraphs" => Unit::Paragraphs,
+        "s" | "sentences" => Unit::Sentences,
+        "w" | "words" => Unit::Words,
+        _ => return None,
+    };
+    Some((count, unit))
+}
+
+fn sentences(rng: &mut impl Rng, count: usize, opening: bool) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            if opening && i == 0 {
+                return format!("{}.", OPENING);
+            }
+            let len = rng.random_range(6..=14);
+            let comma = rng.random_bool(0.4).then(|| rng.random_range(3..len - 2));
+            let mut sentence = String::new();
+            for index in 0..len {
+                if index > 0 {
+                    sentence.push(' ');
+                }
+                sentence.push_str(WORDS.choose(rng).unwrap_or(&"lorem"));
+                if comma == Some(index) {
+                    sentence.push(',');
+                }
+            }
+            format!("{}.", capitalize(&sentence))
+        })
+        .collect()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lorem_counts() {
+        assert_eq!(parse_spec("3p"), Some((3, Unit::Paragraphs)));
+        assert_eq!(parse_spec("12w"), Some((12, Unit::Words)));
+        assert_eq!(parse_spec("2"), Some((2, Unit::Paragraphs)));
+        assert_eq!(parse_spec("3x"), None);
+
+        let mut state = TerminalState::new();
+        let words = LoremCommand
+            .execute(&["12w".to_string()], &mut state)
+            .unwrap();
+        assert_eq!(words.split(' ').count(), 12);
+        assert!(words.starts_with("Lorem ipsum dolor sit amet consectetur"));
+
+        let paragraphs = LoremCommand
+            .execute(&["3p".to_string()], &mut state)
+            .unwrap();
+        assert_eq!(paragraphs.split("\n\n").count(), 3);
+        assert!(paragraphs.starts_with(OPENING));
+    }
+}
@@ -1,42 +1,58 @@
 //! Shell utilities
 //!
-//! alias, env, export, sleep, watch, seq, yes, true, false, expr, bc, tee, timeout
-//! type, command, pushd, popd, dirs
+//! alias, unalias, env, export, sleep, watch, seq, yes, true, false, expr, bc, tee, timeout
+//! type, command, pushd, popd, dirs, source, uuid, random, lorem, jobs, fg, bg
 
 mod alias_cmd;
 mod bc;
+mod bg;
 mod command_cmd;
 mod dirs;
 mod env;
 mod export;
 mod expr;
 mod false_cmd;
+mod fg;
+mod jobs;
+mod lorem;
 mod popd;
 mod pushd;
+mod random;
 mod seq;
 mod sleep_cmd;
+mod source;
 mod tee;
 mod timeout;
 mod true_cmd;
 mod type_cmd;
+mod unalias;
+mod uuid;
 mod watch;
 mod yes;
 
 pub use alias_cmd::AliasCommand;
 pub use bc::BcCommand;
+pub use bg::BgCommand;
 pub use command_cmd::CommandCommand;
 pub use dirs::DirsCommand;
 pub use env::EnvCommand;
 pub use export::ExportCommand;
 pub use expr::ExprCommand;
 pub use false_cmd::FalseCommand;
+pub use fg::FgCommand;
+pub use jobs::JobsCommand;
+pub use lorem::LoremCommand;
 pub use popd::PopdCommand;
 pub use pushd::PushdCommand;
+pub use random::RandomCommand;
 pub use seq::SeqCommand;
 pub use sleep_cmd::SleepCommand;
+pub use source::SourceCommand;
 pub use tee::TeeCommand;
 pub use timeout::TimeoutCommand;
 pub use true_cmd::TrueCommand;
 pub use type_cmd::TypeCommand;
-pub use watch::WatchCommand;
+pub use unalias::UnaliasCommand;
+pub use uuid::UuidCommand;
+pub use watch::{WatchCommand, WatchOptions};
 pub use yes::YesCommand;
@@ -2,9 +2,9 @@
 
 use anyhow::Result;
 
-use super::pushd::DIR_STACK;
+use super::pushd::format_stack;
 use crate::commands::traits::Command;
-use crate::terminal::state::TerminalState;
+use crate::terminal::state::{stack_index, TerminalState};
 
 pub struct PopdCommand;
 
@@ -18,31 +18,32 @@ impl Command for PopdCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "popd"
+        "popd [+n]"
     }
 
-    fn execute(&self, _args: &[String], state: &mut TerminalState) -> Result<String> {
-        let mut stack = DIR_STACK.lock().unwrap();
-
-        if stack.is_empty() {
-            return Err(anyhow::anyhow!("popd: directory stack empty"));
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        // popd +n (n > 0) removes entry n and stays in the current directory
+        if let Some(n) = args.first().and_then(|arg| stack_index(arg)) {
+            if n > 0 {
+                let len = state.dir_stack.len();
+                if n > len {
+                    return Err(anyhow::anyhow!(
+                        "popd: +{}: directory stack index out of range",
+                        n
+                    ));
+                }
+                state.dir_stack.remove(len - n);
+                return Ok(format_stack(state));
+            }
         }
 
-        let target = stack.pop().unwrap();
-        drop(stack); // Release lock before set_cwd
+        let Some(target) = state.dir_stack.pop() else {
+            return Err(anyhow::anyhow!("popd: directory stack empty"));
+        };
 
-        state.set_cwd(target.clone());
+        state.set_cwd(target);
 
         // Show remaining directory stack
-        let stack = DIR_STACK.lock().unwrap();
-        let mut dirs: Vec<String> = vec![target.display().to_string()];
-        dirs.extend(
-            stack
-                .iter()
-                .rev()
-                .map(|p: &std::path::PathBuf| p.display().to_string()),
-        );
-
-        Ok(dirs.join(" "))
+        Ok(format_stack(state))
     }
 }
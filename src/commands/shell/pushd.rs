@@ -1,16 +1,9 @@
 //! pushd command - push directory onto stack
 
-use std::path::PathBuf;
-use std::sync::Mutex;
-
 use anyhow::Result;
-use once_cell::sync::Lazy;
 
 use crate::commands::traits::Command;
-use crate::terminal::state::TerminalState;
-
-// Global directory stack
-pub static DIR_STACK: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+use crate::terminal::state::{stack_index, TerminalState};
 
 pub struct PushdCommand;
 
@@ -24,20 +17,31 @@ impl Command for PushdCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "pushd [directory]"
+        "pushd [directory | +n]"
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         let current = state.cwd().clone();
 
-        let target = if args.is_empty() {
+        let target = if let Some(n) = args.first().and_then(|arg| stack_index(arg)) {
+            // Rotate the stack so entry n is on top
+            let mut dirs = state.dirs();
+            if n >= dirs.len() {
+                return Err(anyhow::anyhow!(
+                    "pushd: +{}: directory stack index out of range",
+                    n
+                ));
+            }
+            dirs.rotate_left(n);
+            let target = dirs.remove(0);
+            state.dir_stack = dirs.into_iter().rev().collect();
+            target
+        } else if args.is_empty() {
             // Swap top two directories
-            let mut stack = DIR_STACK.lock().unwrap();
-            if stack.is_empty() {
+            let Some(top) = state.dir_stack.pop() else {
                 return Err(anyhow::anyhow!("pushd: no other directory"));
-            }
-            let top = stack.pop().unwrap();
-            stack.push(current.clone());
+            };
+            state.dir_stack.push(current.clone());
             top
         } else {
             let target = state.resolve_path(&args[0]);
@@ -49,23 +53,59 @@ impl Command for PushdCommand {
             }
 
             // Push current directory onto stack
-            DIR_STACK.lock().unwrap().push(current.clone());
+            state.dir_stack.push(current.clone());
             target
         };
 
         // Change to new directory
-        state.set_cwd(target.clone());
+        state.set_cwd(target);
 
         // Show directory stack
-        let stack = DIR_STACK.lock().unwrap();
-        let mut dirs: Vec<String> = vec![target.display().to_string()];
-        dirs.extend(
-            stack
-                .iter()
-                .rev()
-                .map(|p: &PathBuf| p.display().to_string()),
+        Ok(format_stack(state))
+    }
+}
+
+/// The stack on one line, as pushd and popd print it
+pub fn format_stack(state: &TerminalState) -> String {
+    state
+        .dirs()
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::shell::PopdCommand;
+    use crate::commands::testing::{run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_stack_indices() {
+        let fixture = Fixture::new("pushd").dir("a").dir("b").dir("c");
+        let root = fixture.path().to_path_buf();
+        let mut state = MockTerminalState::new().cwd(&root).build();
+        run(&PushdCommand, &["a"], &mut state).unwrap();
+        assert_eq!(state.dirs(), [root.join("a"), root.clone()]);
+
+        state.set_cwd(root.join("c"));
+        state.dir_stack = vec![root.join("a"), root.join("b")];
+        // c b a -> a c b
+        run(&PushdCommand, &["+2"], &mut state).unwrap();
+        assert_eq!(
+            state.dirs(),
+            [root.join("a"), root.join("c"), root.join("b")]
         );
+        assert_eq!(state.cwd(), &root.join("a"));
+
+        // popd +n drops an entry and stays put
+        run(&PopdCommand, &["+1"], &mut state).unwrap();
+        assert_eq!(state.dirs(), [root.join("a"), root.join("b")]);
+        assert!(run(&PopdCommand, &["+2"], &mut state).is_err());
 
-        Ok(dirs.join(" "))
+        // popd +0 is popd
+        run(&PopdCommand, &["+0"], &mut state).unwrap();
+        assert_eq!(state.dirs(), [root.join("b")]);
     }
 }
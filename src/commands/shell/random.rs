@@ -0,0 +1,181 @@
+//! random command - random numbers, bytes and strings
+
+use anyhow::Result;
+use base64::{engine::general_purpose, Engine as _};
+use rand::distr::{Alphanumeric, SampleString};
+use rand::seq::IndexedRandom;
+use rand::{Rng, RngCore};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct RandomCommand;
+
+/// Largest count, byte length or string length accepted
+const MAX_SIZE: usize = 1 << 20;
+
+impl Command for RandomCommand {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn description(&self) -> &'static str {
+        "Random numbers, bytes, strings and picks"
+    }
+
+    fn usage(&self) -> &'static str {
+        "random [int <min> <max> [-n count] | bytes <n> [--hex|--base64] | string <n> | pick <items...>]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"random - Random numbers, bytes, strings and picks
+
+USAGE:
+  random int <min> <max> [-n count]
+  random float [-n count]
+  random bytes <n> [--hex|--base64]
+  random string <n>
+  random pick <items...>
+
+DESCRIPTION:
+  Uses the operating system's secure random source.
+  Without arguments, prints a number from 1 to 100.
+
+  int      Whole numbers between min and max (inclusive)
+  float    Numbers in [0, 1)
+  bytes    n random bytes as hex (default) or base64
+  string   n letters and digits - handy for passwords and tokens
+  pick     One of the given items (or piped lines)
+
+EXAMPLES:
+  random int 1 100          Roll a d100
+  random int 1 6 -n 3       Three dice
+  random bytes 32 --hex     A 256-bit key
+  random bytes 16 --base64  A base64 token
+  random string 20          A 20-character password
+  random pick red green     Choose one
+  ls | random pick          Pick a random file
+
+RELATED COMMANDS:
+  uuid     Generate UUIDs
+  lorem    Placeholder text
+  seq      Number sequences
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut count = 1usize;
+        let mut base64 = false;
+        let mut words: Vec<&str> = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-n" | "--count" => {
+                    count = iter
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("random: {} needs a number", arg))?;
+                }
+                "--hex" => base64 = false,
+                "--base64" => base64 = true,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ => words.push(arg),
+            }
+        }
+
+        if count > MAX_SIZE {
+            return Err(anyhow::anyhow!("random: count is limited to {}", MAX_SIZE));
+        }
+
+        let mut rng = rand::rng();
+        let kind = words.first().copied().unwrap_or("int");
+        let operands = words.get(1..).unwrap_or_default();
+
+        match kind {
+            "int" => {
+                let (min, max) = match operands {
+                    [] => (1, 100),
+                    [max] => (1, parse_int(max)?),
+                    [min, max, ..] => (parse_int(min)?, parse_int(max)?),
+                };
+                if min > max {
+                    return Err(anyhow::anyhow!("random: min {} is above max {}", min, max));
+                }
+                let numbers: Vec<String> = (0..count)
+                    .map(|_| rng.random_range(min..=max).to_string())
+                    .collect();
+                Ok(numbers.join("\n"))
+            }
+            "float" => {
+                let numbers: Vec<String> = (0..count)
+                    .map(|_| rng.random::<f64>().to_string())
+                    .collect();
+                Ok(numbers.join("\n"))
+            }
+            "bytes" => {
+                let mut bytes = vec![0u8; parse_size(operands.first(), 32)?];
+                rng.fill_bytes(&mut bytes);
+                if base64 {
+                    Ok(general_purpose::STANDARD.encode(&bytes))
+                } else {
+                    Ok(hex::encode(&bytes))
+                }
+            }
+            "string" => {
+                let len = parse_size(operands.first(), 16)?;
+                Ok(Alphanumeric.sample_string(&mut rng, len))
+            }
+            "pick" => {
+                let items: Vec<&str> = if operands.is_empty() {
+                    stdin
+                        .map(|input| input.lines().filter(|l| !l.trim().is_empty()).collect())
+                        .unwrap_or_default()
+                } else {
+                    operands.to_vec()
+                };
+                items
+                    .choose(&mut rng)
+                    .map(|item| item.to_string())
+                    .ok_or_else(|| anyhow::anyhow!("random: nothing to pick from"))
+            }
+            _ => Err(anyhow::anyhow!(
+                "random: unknown kind '{}'\nUsage: {}",
+                kind,
+                self.usage()
+            )),
+        }
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+fn parse_int(text: &str) -> Result<i64> {
+    text.parse()
+        .map_err(|_| anyhow::anyhow!("random: '{}' is not a whole number", text))
+}
+
+fn parse_size(text: Option<&&str>, default: usize) -> Result<usize> {
+    let size = match text {
+        Some(text) => text
+            .parse()
+            .map_err(|_| anyhow::anyhow!("random: '{}' is not a size", text))?,
+        None => default,
+    };
+    if size > MAX_SIZE {
+        return Err(anyhow::anyhow!("random: size is limited to {}", MAX_SIZE));
+    }
+    Ok(size)
+}
@@ -28,11 +28,9 @@ impl Command for SeqCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-s" | "--separator" => {
-                    if i + 1 < args.len() {
-                        separator = args[i + 1].clone();
-                        i += 1;
-                    }
+                "-s" | "--separator" if i + 1 < args.len() => {
+                    separator = args[i + 1].clone();
+                    i += 1;
                 }
                 "-w" | "--equal-width" => equal_width = true,
                 "-h" | "--help" => {
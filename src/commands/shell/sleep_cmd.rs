@@ -2,11 +2,15 @@
 
 use anyhow::Result;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
+/// How often a sleep checks whether it was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
 pub struct SleepCommand;
 
 impl Command for SleepCommand {
@@ -22,7 +26,17 @@ impl Command for SleepCommand {
         "sleep <seconds>"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        _state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("sleep: missing operand"));
         }
@@ -61,8 +75,12 @@ impl Command for SleepCommand {
             total_secs += num * suffix;
         }
 
-        let duration = Duration::from_secs_f64(total_secs);
-        thread::sleep(duration);
+        // Wake up now and then to see if we've been cancelled
+        let until = Instant::now() + Duration::from_secs_f64(total_secs);
+        while let Some(left) = until.checked_duration_since(Instant::now()) {
+            ctx.check()?;
+            thread::sleep(left.min(CANCEL_CHECK_INTERVAL));
+        }
 
         Ok(String::new())
     }
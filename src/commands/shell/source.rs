@@ -0,0 +1,48 @@
+//! source command - run a Zaxiom script
+//!
+//! Scripts run their commands through the shell executor, so the executor
+//! handles `source` itself (see `shell::script`); this entry provides the
+//! help text, routing, and completion.
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct SourceCommand;
+
+impl Command for SourceCommand {
+    fn name(&self) -> &'static str {
+        "source"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run a script of built-in commands"
+    }
+
+    fn usage(&self) -> &'static str {
+        "source [file] [args...]   (alias: . <file>)\n\n\
+         One command per line or separated by ';'. Lines starting with # are\n\
+         comments. $1..$9, $@, $# and $0 are the script's arguments.\n\n\
+         Control flow (conditions are commands, usually test or [ ]):\n  \
+         if [ -d src ]; then ...; elif test $1 = x; then ...; else ...; fi\n  \
+         for f in a b {1..3}; do echo $f; done\n  \
+         while [ -f lock ]; do sleep 1; done\n\n\
+         Functions take arguments as $1.. and stay defined in the pane:\n  \
+         greet() { echo \"hi $1\"; }\n\n\
+         All of these also work typed at the prompt; an unfinished if, for,\n\
+         while, or function asks for more lines.\n\n\
+         A failing command stops the script.\n\n\
+         The startup script runs in every new pane: put aliases, exports,\n\
+         theme, and other commands there ([startup] script in config.toml\n\
+         picks another file, enabled = false turns it off).\n\n\
+         Examples:\n  \
+         source setup.zx           - Run a script\n  \
+         source build.zx release   - Run it with $1 = release\n  \
+         source                    - Reload ~/.zaxiomrc after editing it"
+    }
+
+    fn execute(&self, _args: &[String], _state: &mut TerminalState) -> Result<String> {
+        Err(anyhow!("source: scripts run through the shell executor"))
+    }
+}
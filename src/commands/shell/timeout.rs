@@ -22,7 +22,7 @@ impl Command for TimeoutCommand {
         "timeout <duration> <command>"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.len() < 2 {
             return Err(anyhow::anyhow!("timeout: missing arguments"));
         }
@@ -62,6 +62,8 @@ impl Command for TimeoutCommand {
 
         let output = ProcessCommand::new("powershell")
             .args(["-NoProfile", "-Command", &ps_command])
+            .current_dir(state.cwd())
+            .envs(state.exported_vars())
             .output()
             .map_err(|e| anyhow::anyhow!("timeout: {}", e))?;
 
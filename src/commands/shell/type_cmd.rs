@@ -3,6 +3,7 @@
 use anyhow::Result;
 
 use crate::commands::traits::Command;
+use crate::config::settings::Config;
 use crate::terminal::state::TerminalState;
 
 pub struct TypeCommand;
@@ -158,8 +159,11 @@ impl Command for TypeCommand {
                 }
             }
 
-            // Check if alias
-            if let Some(alias_value) = state.get_alias(name) {
+            // Check if alias (this pane's, then saved ones)
+            let alias_value = state
+                .get_alias(name)
+                .or_else(|| Config::load().aliases.get(name).map(str::to_string));
+            if let Some(alias_value) = alias_value {
                 if type_only {
                     output.push("alias".to_string());
                 } else {
@@ -171,7 +175,7 @@ impl Command for TypeCommand {
             }
 
             // Check if external command
-            if let Some(path) = find_in_path(name) {
+            if let Some(path) = find_in_path(name, state) {
                 if type_only {
                     output.push("file".to_string());
                 } else {
@@ -189,8 +193,8 @@ impl Command for TypeCommand {
     }
 }
 
-fn find_in_path(name: &str) -> Option<String> {
-    let path_var = std::env::var("PATH").ok()?;
+fn find_in_path(name: &str, state: &TerminalState) -> Option<String> {
+    let path_var = state.var("PATH")?;
     let extensions = ["", ".exe", ".cmd", ".bat", ".com"];
 
     for dir in path_var.split(';') {
@@ -0,0 +1,121 @@
+//! unalias command - remove aliases
+
+use anyhow::{anyhow, Result};
+
+use super::alias_cmd::AliasKind;
+use crate::commands::traits::Command;
+use crate::config::aliases;
+use crate::config::settings::Config;
+use crate::terminal::state::TerminalState;
+
+pub struct UnaliasCommand;
+
+impl Command for UnaliasCommand {
+    fn name(&self) -> &'static str {
+        "unalias"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove aliases"
+    }
+
+    fn usage(&self) -> &'static str {
+        "unalias [-a] [-s|-g] name...\n\n\
+         Examples:\n  \
+         unalias ll        - Remove the ll alias (for good, if it was saved)\n  \
+         unalias -s md     - Stop opening *.md files by suffix\n  \
+         unalias -g G      - Remove the global alias G\n  \
+         unalias -a        - Remove all aliases made with alias"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let kind = match args.first().map(|s| s.as_str()) {
+            None => return Err(anyhow!("usage: unalias [-a] [-s|-g] name...")),
+            Some("-a") => {
+                let names: Vec<String> = state
+                    .list_aliases()
+                    .into_iter()
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                for name in names {
+                    state.remove_alias(&name);
+                }
+                let path = aliases::saved_path();
+                if !aliases::load_saved(&path).is_empty() {
+                    aliases::save_saved(&path, &Default::default())?;
+                    state.requested_aliases = Some(Config::load().aliases);
+                }
+                return Ok(String::new());
+            }
+            Some("-s") => AliasKind::Suffix,
+            Some("-g") => AliasKind::Global,
+            Some(_) => return remove_commands(args, state),
+        };
+
+        let mut config = Config::load();
+        for name in &args[1..] {
+            if kind
+                .map(&mut config)
+                .remove(name.trim_start_matches('.'))
+                .is_none()
+            {
+                return Err(anyhow!("unalias: {}: not found", name));
+            }
+        }
+        config.save()?;
+        state.requested_aliases = Some(config.aliases);
+        Ok(String::new())
+    }
+}
+
+/// Remove command aliases from this pane, aliases.toml, and config.toml,
+/// wherever each one is defined
+fn remove_commands(names: &[String], state: &mut TerminalState) -> Result<String> {
+    let path = aliases::saved_path();
+    let mut saved = aliases::load_saved(&path);
+    let mut config = Config::load();
+    let (mut saved_changed, mut config_changed) = (false, false);
+    for name in names {
+        let in_pane = state.remove_alias(name);
+        let in_saved = saved.remove(name).is_some();
+        let in_config = config.aliases.commands.remove(name).is_some();
+        if !(in_pane || in_saved || in_config) {
+            return Err(anyhow!("unalias: {}: not found", name));
+        }
+        saved_changed |= in_saved;
+        config_changed |= in_config;
+    }
+
+    if saved_changed {
+        aliases::save_saved(&path, &saved)?;
+    }
+    if config_changed {
+        config.save()?;
+    }
+    if saved_changed || config_changed {
+        state.requested_aliases = Some(Config::load().aliases);
+    }
+    Ok(String::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, MockTerminalState};
+
+    #[test]
+    fn test_unalias() {
+        let mut state = MockTerminalState::new()
+            .alias("ll", "ls -la")
+            .alias("gs", "git status")
+            .alias("k", "kubectl")
+            .build();
+        run(&UnaliasCommand, &["ll"], &mut state).unwrap();
+        assert_eq!(state.get_alias("ll"), None);
+        assert!(run(&UnaliasCommand, &["ll"], &mut state).is_err());
+
+        run(&UnaliasCommand, &["-a"], &mut state).unwrap();
+        assert!(state.list_aliases().is_empty());
+        assert!(run(&UnaliasCommand, &[], &mut state).is_err());
+    }
+}
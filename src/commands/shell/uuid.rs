@@ -0,0 +1,166 @@
+//! uuid command - generate UUIDs
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use rand::RngCore;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct UuidCommand;
+
+/// Most UUIDs generated in one go
+const MAX_COUNT: usize = 10_000;
+
+impl Command for UuidCommand {
+    fn name(&self) -> &'static str {
+        "uuid"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generate random (v4) or time-ordered (v7) UUIDs"
+    }
+
+    fn usage(&self) -> &'static str {
+        "uuid [v4|v7] [-n count] [-u]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"uuid - Generate UUIDs
+
+USAGE:
+  uuid [v4|v7] [OPTIONS]
+
+OPTIONS:
+  -n, --count <N>   Generate N UUIDs (default: 1)
+  -u, --upper       Uppercase hex digits
+
+VERSIONS:
+  v4   Random (default)
+  v7   Unix time in milliseconds, then random bits - sorts by
+       creation time, which makes a good database key
+
+EXAMPLES:
+  uuid                  One random UUID
+  uuid v7               A time-ordered UUID
+  uuid -n 5             Five UUIDs, one per line
+  uuid v7 -n 3 -u       Three uppercase v7 UUIDs
+
+RELATED COMMANDS:
+  random   Random numbers, bytes and strings
+  mktemp   Create a uniquely named temp file
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let mut version = 4;
+        let mut count = 1usize;
+        let mut upper = false;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "v4" | "-4" => version = 4,
+                "v7" | "-7" => version = 7,
+                "-n" | "--count" => {
+                    count = iter
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("uuid: {} needs a number", arg))?;
+                }
+                "-u" | "--upper" => upper = true,
+                "-h" | "--help" => return Ok(self.extended_help()),
+                _ => return Err(anyhow::anyhow!("uuid: unknown argument '{}'", arg)),
+            }
+        }
+
+        if count > MAX_COUNT {
+            return Err(anyhow::anyhow!("uuid: at most {} at a time", MAX_COUNT));
+        }
+
+        let mut uuids: Vec<[u8; 16]> = (0..count)
+            .map(|_| match version {
+                7 => uuid_v7(),
+                _ => uuid_v4(),
+            })
+            .collect();
+        if version == 7 {
+            // UUIDs from the same millisecond keep their creation order
+            uuids.sort();
+        }
+
+        let lines: Vec<String> = uuids
+            .iter()
+            .map(|bytes| {
+                let text = format_uuid(bytes);
+                if upper {
+                    text.to_uppercase()
+                } else {
+                    text
+                }
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+}
+
+fn uuid_v4() -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    set_version(&mut bytes, 4);
+    bytes
+}
+
+fn uuid_v7() -> [u8; 16] {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    set_version(&mut bytes, 7);
+    bytes
+}
+
+/// Version in the high nibble of byte 6, RFC 4122 variant in byte 8
+fn set_version(bytes: &mut [u8; 16], version: u8) {
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+}
+
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_layout() {
+        let v4 = format_uuid(&uuid_v4());
+        assert_eq!(v4.len(), 36);
+        assert_eq!(&v4[14..15], "4");
+        assert!(matches!(&v4[19..20], "8" | "9" | "a" | "b"));
+
+        let v7 = format_uuid(&uuid_v7());
+        assert_eq!(&v7[14..15], "7");
+        assert_eq!(
+            format_uuid(&[0x01; 16]),
+            "01010101-0101-0101-0101-010101010101"
+        );
+    }
+}
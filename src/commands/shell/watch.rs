@@ -1,8 +1,10 @@
 //! watch command - execute a program periodically
+//!
+//! The command being watched can be a built-in, a pipeline, or an external
+//! program, so the executor runs `watch` itself (see `Executor::run_watch`);
+//! this entry parses the options and provides the help text and routing.
 
-use anyhow::Result;
-use std::process::Command as ProcessCommand;
-use std::thread;
+use anyhow::{anyhow, Result};
 use std::time::Duration;
 
 use crate::commands::traits::Command;
@@ -10,99 +12,146 @@ use crate::terminal::state::TerminalState;
 
 pub struct WatchCommand;
 
-impl Command for WatchCommand {
-    fn name(&self) -> &'static str {
-        "watch"
-    }
-
-    fn description(&self) -> &'static str {
-        "Execute a program periodically, showing output"
-    }
-
-    fn usage(&self) -> &'static str {
-        "watch [-n seconds] <command>"
-    }
-
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
-        let mut interval: f64 = 2.0;
-        let mut iterations: Option<u32> = None;
-        let mut command_start = 0;
+/// Shortest interval accepted by `-n`
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What `watch` was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOptions {
+    /// Time between the end of one run and the start of the next
+    pub interval: Duration,
+    /// Stop after this many runs (forever, until Ctrl+C, when `None`)
+    pub count: Option<u32>,
+    /// Show the "Every 2s: ..." header line
+    pub title: bool,
+    /// The command line to re-run
+    pub command: String,
+}
 
-        let mut i = 0;
-        while i < args.len() {
-            match args[i].as_str() {
+impl WatchOptions {
+    /// Parse `watch` arguments: `[-n secs] [-c count] [-t] <command...>`
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut options = Self {
+            interval: Duration::from_secs(2),
+            count: None,
+            title: true,
+            command: String::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .ok_or_else(|| anyhow!("watch: option {} needs a value", arg))
+            };
+            match arg.as_str() {
                 "-n" | "--interval" => {
-                    if i + 1 < args.len() {
-                        interval = args[i + 1].parse().unwrap_or(2.0);
-                        i += 1;
-                        command_start = i + 1;
-                    }
+                    let value = value()?;
+                    let secs: f64 = value
+                        .parse()
+                        .ok()
+                        .filter(|secs: &f64| secs.is_finite() && *secs >= 0.0)
+                        .ok_or_else(|| anyhow!("watch: invalid interval '{}'", value))?;
+                    options.interval = Duration::from_secs_f64(secs).max(MIN_INTERVAL);
                 }
                 "-c" | "--count" => {
-                    if i + 1 < args.len() {
-                        iterations = args[i + 1].parse().ok();
-                        i += 1;
-                        command_start = i + 1;
-                    }
-                }
-                "-h" | "--help" => {
-                    return Ok("Usage: watch [OPTIONS] <command>\n\
-                        Options:\n  \
-                        -n <secs>    Seconds between updates (default: 2)\n  \
-                        -c <count>   Number of iterations (default: infinite)\n\n\
-                        Note: In this terminal, watch runs for limited iterations.\n\
-                        Use -c to specify how many times to run."
-                        .to_string());
+                    let value = value()?;
+                    options.count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or_else(|| anyhow!("watch: invalid count '{}'", value))?,
+                    );
                 }
-                _ if !args[i].starts_with('-') => {
-                    command_start = i;
-                    break;
+                "-t" | "--no-title" => options.title = false,
+                option if option.starts_with('-') => {
+                    return Err(anyhow!("watch: unknown option '{}'", option))
                 }
                 _ => {
-                    command_start = i + 1;
+                    let rest = std::iter::once(arg).chain(iter).cloned();
+                    options.command = rest.collect::<Vec<_>>().join(" ");
+                    break;
                 }
             }
-            i += 1;
         }
+        if options.command.is_empty() {
+            return Err(anyhow!("watch: no command specified"));
+        }
+        Ok(options)
+    }
 
-        if command_start >= args.len() {
-            return Err(anyhow::anyhow!("watch: no command specified"));
+    /// One screen: the header (unless `-t`), then the command's output
+    pub fn frame(&self, output: &str, time: &str) -> String {
+        if !self.title {
+            return output.to_string();
         }
+        format!(
+            "Every {}s: {}    {}\n\n{}",
+            self.interval.as_secs_f64(),
+            self.command,
+            time,
+            output
+        )
+    }
+}
 
-        let command_args = &args[command_start..];
-        let command_str = command_args.join(" ");
-
-        // For non-interactive use, default to 3 iterations if not specified
-        let max_iterations = iterations.unwrap_or(3);
-        let mut outputs = Vec::new();
-
-        for iter in 0..max_iterations {
-            // Run command via PowerShell
-            let output = ProcessCommand::new("powershell")
-                .args(["-NoProfile", "-Command", &command_str])
-                .output();
-
-            let result = match output {
-                Ok(out) => {
-                    let stdout = String::from_utf8_lossy(&out.stdout);
-                    let stderr = String::from_utf8_lossy(&out.stderr);
-                    if !stderr.is_empty() {
-                        format!("{}{}", stdout, stderr)
-                    } else {
-                        stdout.to_string()
-                    }
-                }
-                Err(e) => format!("Error: {}", e),
-            };
+impl Command for WatchCommand {
+    fn name(&self) -> &'static str {
+        "watch"
+    }
 
-            outputs.push(format!("--- Iteration {} ---\n{}", iter + 1, result.trim()));
+    fn description(&self) -> &'static str {
+        "Execute a program periodically, showing output"
+    }
 
-            // Sleep between iterations (but not after the last one)
-            if iter + 1 < max_iterations {
-                thread::sleep(Duration::from_secs_f64(interval));
-            }
-        }
+    fn usage(&self) -> &'static str {
+        "watch [-n seconds] [-c count] [-t] <command>\n\n\
+         Re-runs the command and redraws its output in place, until Ctrl+C\n\
+         (or -c runs). The command can be a built-in, a pipeline (quote it),\n\
+         or any program.\n\n\
+         Options:\n  \
+         -n <secs>    Seconds between runs (default: 2)\n  \
+         -c <count>   Stop after this many runs\n  \
+         -t           Hide the header line\n\n\
+         Examples:\n  \
+         watch git status -s\n  \
+         watch -n 1 \"ls -l build | wc -l\"\n  \
+         watch -c 5 -n 10 curl -s localhost:8080/health"
+    }
+
+    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+        WatchOptions::parse(args)?;
+        Err(anyhow!("watch: runs through the shell executor"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<WatchOptions> {
+        WatchOptions::parse(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
 
-        Ok(outputs.join("\n\n"))
+    #[test]
+    fn test_parse() {
+        let options = parse(&["-n", "0.5", "-c", "3", "ls", "-la", "src"]).unwrap();
+        assert_eq!(options.interval, Duration::from_millis(500));
+        assert_eq!(options.count, Some(3));
+        assert_eq!(options.command, "ls -la src");
+        assert_eq!(
+            options.frame("a\nb", "10:00:00"),
+            "Every 0.5s: ls -la src    10:00:00\n\na\nb"
+        );
+
+        let options = parse(&["-t", "-n", "0", "date"]).unwrap();
+        assert_eq!((options.interval, options.count), (MIN_INTERVAL, None));
+        assert_eq!(options.frame("now", "10:00:00"), "now");
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["-n", "2"]).is_err());
+        assert!(parse(&["-n", "soon", "date"]).is_err());
+        assert!(parse(&["-c", "0", "date"]).is_err());
+        assert!(parse(&["-x", "date"]).is_err());
     }
 }
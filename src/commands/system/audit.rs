@@ -0,0 +1,120 @@
+//! audit command - inspect, verify, and export the command audit log
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::settings::Config;
+use crate::terminal::audit::{self, AuditEntry};
+use crate::terminal::state::TerminalState;
+
+pub struct AuditCommand;
+
+impl Command for AuditCommand {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tamper-evident log of executed commands"
+    }
+
+    fn usage(&self) -> &'static str {
+        "audit [status|verify|on|off|export [file] [--csv|--json]]\n\n\
+         Examples:\n  \
+         audit                      - Show whether logging is on and the chain's head hash\n  \
+         audit on                   - Start recording every command\n  \
+         audit verify               - Check that no entry was edited, removed, or reordered\n  \
+         audit export log.csv       - Verify, then export as CSV (or .json)\n  \
+         audit export --json        - Print the verified log as JSON"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let config = Config::load();
+        let path = config.audit_path();
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("status") => {
+                let mut output = format!(
+                    "Audit log is {}\n  File: {}\n",
+                    if config.audit.enabled {
+                        "on"
+                    } else {
+                        "off (audit on to enable)"
+                    },
+                    path.display()
+                );
+                match audit::verify(&path) {
+                    Ok(entries) => match entries.last() {
+                        Some(last) => output.push_str(&format!(
+                            "  Entries: {} (chain intact)\n  Head hash: {}",
+                            entries.len(),
+                            last.hash
+                        )),
+                        None => output.push_str("  Entries: 0"),
+                    },
+                    Err(e) => output.push_str(&format!("  ⚠ Chain broken: {}", e)),
+                }
+                Ok(output)
+            }
+            Some("verify") => {
+                let entries = audit::verify(&path)?;
+                Ok(format!(
+                    "✓ {} entries verified - nothing edited, removed, or reordered",
+                    entries.len()
+                ))
+            }
+            Some(toggle @ ("on" | "off")) => {
+                let enabled = toggle == "on";
+                let mut config = config;
+                config.audit.enabled = enabled;
+                config.save()?;
+                state.requested_audit = Some(enabled);
+                Ok(if enabled {
+                    format!("Audit log on - recording to {}", path.display())
+                } else {
+                    "Audit log off.".to_string()
+                })
+            }
+            Some("export") => {
+                let entries = audit::verify(&path)
+                    .map_err(|e| anyhow!("refusing to export a broken log: {}", e))?;
+                export(&entries, &args[1..], state)
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: audit [status|verify|on|off|export]",
+                other
+            )),
+        }
+    }
+}
+
+/// Export to a file, or print when no file is given
+fn export(entries: &[AuditEntry], args: &[String], state: &TerminalState) -> Result<String> {
+    let file = args.iter().find(|a| !a.starts_with("--"));
+    let csv = if args.iter().any(|a| a == "--csv") {
+        true
+    } else if args.iter().any(|a| a == "--json") {
+        false
+    } else {
+        file.is_some_and(|f| f.to_lowercase().ends_with(".csv"))
+    };
+
+    let contents = if csv {
+        audit::to_csv(entries)
+    } else {
+        serde_json::to_string_pretty(entries)?
+    };
+
+    match file {
+        Some(file) => {
+            let target = state.resolve_path(file);
+            std::fs::write(&target, contents)?;
+            Ok(format!(
+                "Exported {} entries to {}",
+                entries.len(),
+                target.display()
+            ))
+        }
+        None => Ok(contents),
+    }
+}
@@ -0,0 +1,169 @@
+//! buffer command - scrollback size, trimming, and export
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::buffer::{BlockStats, WARN_PERCENT};
+use crate::terminal::format::format_size;
+use crate::terminal::state::TerminalState;
+
+/// Longest command shown in the per-block table
+const MAX_COMMAND_LEN: usize = 48;
+
+/// Fewest lines `buffer scrollback` keeps in memory
+const MIN_SCROLLBACK: usize = 100;
+
+pub struct BufferCommand;
+
+impl Command for BufferCommand {
+    fn name(&self) -> &'static str {
+        "buffer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show or trim this pane's scrollback"
+    }
+
+    fn usage(&self) -> &'static str {
+        "buffer [stats] | buffer trim <n> | buffer scrollback [n] | buffer export <file>\n\n\
+         Each pane keeps a fixed number of lines in memory. Older output\n\
+         moves to a file on disk (up to [terminal] scrollback_disk_mb) and\n\
+         can still be exported; with that set to 0 it is dropped instead,\n\
+         and the pane warns when it gets close.\n\n\
+         Examples:\n  \
+         buffer stats            - Lines, bytes, and per-block sizes\n  \
+         buffer trim 500         - Keep only the newest 500 lines in memory\n  \
+         buffer scrollback 50000 - Keep 50,000 lines in memory in every pane\n  \
+         buffer export out.txt   - Save the whole scrollback as plain text"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            None | Some("stats") => {
+                let stats = state
+                    .buffer_stats
+                    .take()
+                    .ok_or_else(|| anyhow!("buffer stats are only available in a pane"))?;
+
+                let percent = stats.lines * 100 / stats.max_lines.max(1);
+                let mut lines = vec![
+                    format!(
+                        "Lines   {} of {} ({}%{})",
+                        stats.lines,
+                        stats.max_lines,
+                        percent,
+                        if percent >= WARN_PERCENT && !stats.spilling {
+                            ", oldest output is being dropped soon"
+                        } else {
+                            ""
+                        }
+                    ),
+                    format!("Text    {}", format_size(stats.bytes as u64)),
+                    format!("Memory  ~{}", format_size(stats.memory as u64)),
+                ];
+                if stats.spilling {
+                    lines.push(format!(
+                        "On disk {} lines ({})",
+                        stats.spilled_lines,
+                        format_size(stats.spilled_bytes)
+                    ));
+                }
+                if stats.dropped_lines > 0 {
+                    lines.push(format!("Dropped {} lines", stats.dropped_lines));
+                }
+                lines.push(format!("Blocks  {}", stats.blocks.len()));
+
+                if stats.blocks.is_empty() && stats.loose.lines == 0 {
+                    return Ok(lines.join("\n"));
+                }
+                lines.push(String::new());
+                lines.push(format!(
+                    "{:>4}  {:>6}  {:>7}  {:>7}  COMMAND",
+                    "#", "LINES", "TEXT", "MEMORY"
+                ));
+                if stats.loose.lines > 0 {
+                    lines.push(block_row("-", &stats.loose, "(before the first command)"));
+                }
+                for (i, block) in stats.blocks.iter().enumerate() {
+                    lines.push(block_row(&(i + 1).to_string(), block, &block.command));
+                }
+                Ok(lines.join("\n"))
+            }
+            Some("trim") => {
+                let keep = args
+                    .get(1)
+                    .ok_or_else(|| anyhow!("usage: buffer trim <n>"))?
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("buffer trim needs a number of lines to keep"))?;
+                state.requested_buffer_trim = Some(keep);
+                Ok(format!(
+                    "Trimming the scrollback to the newest {} lines.",
+                    keep
+                ))
+            }
+            Some("scrollback") => {
+                let Some(lines) = args.get(1) else {
+                    let stats = state
+                        .buffer_stats
+                        .take()
+                        .ok_or_else(|| anyhow!("buffer scrollback is only available in a pane"))?;
+                    return Ok(format!(
+                        "Scrollback: {} lines in memory{}",
+                        stats.max_lines,
+                        if stats.spilling {
+                            ", older output on disk"
+                        } else {
+                            ""
+                        }
+                    ));
+                };
+                let lines = lines
+                    .replace(['_', ','], "")
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|lines| *lines >= MIN_SCROLLBACK)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "buffer scrollback needs a number of lines (at least {})",
+                            MIN_SCROLLBACK
+                        )
+                    })?;
+                state.requested_scrollback = Some(lines);
+                Ok(format!(
+                    "Keeping {} lines in memory in every pane (saved as [terminal] scrollback_lines).",
+                    lines
+                ))
+            }
+            Some("export") => {
+                let path = args
+                    .get(1)
+                    .ok_or_else(|| anyhow!("usage: buffer export <file>"))?;
+                let path = state.resolve_path(path);
+                let message = format!("Exporting the scrollback to {}", path.display());
+                state.requested_buffer_export = Some(path);
+                Ok(message)
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: buffer [stats|trim <n>|scrollback <n>|export <file>]",
+                other
+            )),
+        }
+    }
+}
+
+fn block_row(number: &str, block: &BlockStats, command: &str) -> String {
+    let command: String = if command.chars().count() > MAX_COMMAND_LEN {
+        let short: String = command.chars().take(MAX_COMMAND_LEN - 1).collect();
+        format!("{}…", short)
+    } else {
+        command.to_string()
+    };
+    format!(
+        "{:>4}  {:>6}  {:>7}  {:>7}  {}",
+        number,
+        block.lines,
+        format_size(block.bytes as u64),
+        format_size(block.memory as u64),
+        command
+    )
+}
@@ -0,0 +1,330 @@
+//! color command - preview and convert colors, list colors in output
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::theme::{parse_hex_color, Theme};
+use crate::terminal::ansi;
+use crate::terminal::state::TerminalState;
+
+pub struct ColorCommand;
+
+/// Most colors listed by `color palette`
+const PALETTE_LIMIT: usize = 32;
+
+type Rgb = (u8, u8, u8);
+
+impl Command for ColorCommand {
+    fn name(&self) -> &'static str {
+        "color"
+    }
+
+    fn description(&self) -> &'static str {
+        "Preview and convert colors"
+    }
+
+    fn usage(&self) -> &'static str {
+        "color <#hex|rgb(r,g,b)|hsl(h,s%,l%)|theme-color> | color palette"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"color - Preview and convert colors
+
+USAGE:
+  color <color>
+  color palette
+  command | color palette
+
+COLOR FORMATS:
+  #a6e3a1  a6e3a1  #abc       Hex
+  rgb(166, 227, 161)          RGB (also "166,227,161")
+  hsl(115, 54%, 76%)          HSL
+  accent  error  link ...     A color of the current theme
+
+DESCRIPTION:
+  Shows a swatch of the color, its hex/rgb/hsl forms, and the
+  closest color of the current theme.
+
+  'color palette' lists the distinct colors used in the previous
+  command's output (or piped output), most used first.
+
+EXAMPLES:
+  color #a6e3a1               Preview and convert
+  color "hsl(200, 80%, 60%)"  From HSL
+  color accent                Inspect a theme color
+  ls | color palette          Colors ls printed
+  color palette               Colors in the last output
+
+RELATED COMMANDS:
+  theme    Switch themes
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        let theme = Theme::from_name(state.current_theme);
+
+        match args.first().map(String::as_str) {
+            None => Err(anyhow!("Usage: {}", self.usage())),
+            Some("-h" | "--help") => Ok(self.extended_help()),
+            Some("palette") => {
+                let output = stdin
+                    .or(state.last_output.as_deref())
+                    .ok_or_else(|| anyhow!("color: no output to read colors from"))?;
+                palette(output, &theme)
+            }
+            Some(_) => {
+                let spec = args.join(" ");
+                let rgb = parse_color(&spec, &theme)
+                    .ok_or_else(|| anyhow!("color: can't read '{}' as a color", spec))?;
+                Ok(describe(rgb, &theme))
+            }
+        }
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+/// Swatch, conversions and nearest theme color
+fn describe(rgb: Rgb, theme: &Theme) -> String {
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let (name, nearest) = nearest_theme_color(rgb, theme);
+    let closeness = if nearest == rgb { "exact" } else { "closest" };
+    let details = [
+        hex(rgb),
+        format!("rgb({}, {}, {})", rgb.0, rgb.1, rgb.2),
+        format!("hsl({}, {}%, {}%)", h, s, l),
+        format!("theme: {} {} ({})", name, hex(nearest), closeness),
+    ];
+    details
+        .iter()
+        .map(|detail| format!("{}  {}", swatch(rgb, 8), detail))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Distinct colors in ANSI output, most used first
+fn palette(output: &str, theme: &Theme) -> Result<String> {
+    let mut counts: HashMap<Rgb, usize> = HashMap::new();
+    for line in output.lines() {
+        for segment in ansi::parse_ansi(line) {
+            let len = segment.text.chars().count();
+            for color in [segment.fg_color, segment.bg_color].into_iter().flatten() {
+                *counts.entry(color).or_default() += len;
+            }
+        }
+    }
+    if counts.is_empty() {
+        return Err(anyhow!("color: the output has no colors"));
+    }
+
+    let mut colors: Vec<(Rgb, usize)> = counts.into_iter().collect();
+    colors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let total = colors.len();
+    let mut lines: Vec<String> = colors
+        .iter()
+        .take(PALETTE_LIMIT)
+        .map(|(rgb, chars)| {
+            let (name, nearest) = nearest_theme_color(*rgb, theme);
+            let marker = if nearest == *rgb { "=" } else { "≈" };
+            format!(
+                "{}  {}  {:>6} chars  {} {}",
+                swatch(*rgb, 4),
+                hex(*rgb),
+                chars,
+                marker,
+                name
+            )
+        })
+        .collect();
+    if total > PALETTE_LIMIT {
+        lines.push(format!("… and {} more", total - PALETTE_LIMIT));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn swatch(rgb: Rgb, width: usize) -> String {
+    format!(
+        "\x1b[38;2;{};{};{}m{}\x1b[0m",
+        rgb.0,
+        rgb.1,
+        rgb.2,
+        "█".repeat(width)
+    )
+}
+
+fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2)
+}
+
+/// Parse hex, rgb(), hsl(), bare "r,g,b" or a theme color name
+fn parse_color(spec: &str, theme: &Theme) -> Option<Rgb> {
+    let spec = spec.trim().to_lowercase();
+
+    if let Some((_, color)) = theme
+        .named_colors()
+        .into_iter()
+        .find(|(name, _)| *name == spec || spec.strip_suffix("_color") == Some(name))
+    {
+        return Some((color.r(), color.g(), color.b()));
+    }
+
+    if let Some(inner) = function_args(&spec, "hsl") {
+        let [h, s, l] = numbers(inner)?;
+        if !(0.0..=100.0).contains(&s) || !(0.0..=100.0).contains(&l) {
+            return None;
+        }
+        return Some(hsl_to_rgb(h.rem_euclid(360.0), s / 100.0, l / 100.0));
+    }
+
+    if let Some(inner) =
+        function_args(&spec, "rgb").or_else(|| spec.contains(',').then_some(spec.as_str()))
+    {
+        let [r, g, b] = numbers(inner)?;
+        let channel = |v: f64| (0.0..=255.0).contains(&v).then_some(v.round() as u8);
+        return Some((channel(r)?, channel(g)?, channel(b)?));
+    }
+
+    let digits = spec.trim_start_matches('#');
+    let digits = match digits.len() {
+        // #abc → #aabbcc
+        3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        _ => digits.to_string(),
+    };
+    let color = parse_hex_color(&digits)?;
+    Some((color.r(), color.g(), color.b()))
+}
+
+/// "rgb(1, 2, 3)" → "1, 2, 3"
+fn function_args<'a>(spec: &'a str, name: &str) -> Option<&'a str> {
+    spec.strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn numbers(text: &str) -> Option<[f64; 3]> {
+    let values: Vec<f64> = text
+        .split([',', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.trim_end_matches('%').parse().ok())
+        .collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+/// Hue in degrees, saturation and lightness in percent
+fn rgb_to_hsl((r, g, b): Rgb) -> (u32, u32, u32) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+
+    let (h, s) = if d == 0.0 {
+        (0.0, 0.0)
+    } else {
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (h, s)
+    };
+
+    (
+        h.round() as u32 % 360,
+        (s * 100.0).round() as u32,
+        (l * 100.0).round() as u32,
+    )
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> Rgb {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Closest theme color by perceptually weighted RGB distance ("redmean")
+fn nearest_theme_color(rgb: Rgb, theme: &Theme) -> (&'static str, Rgb) {
+    let distance = |a: Rgb, b: Rgb| {
+        let mean_r = (a.0 as f64 + b.0 as f64) / 2.0;
+        let dr = a.0 as f64 - b.0 as f64;
+        let dg = a.1 as f64 - b.1 as f64;
+        let db = a.2 as f64 - b.2 as f64;
+        (2.0 + mean_r / 256.0) * dr * dr
+            + 4.0 * dg * dg
+            + (2.0 + (255.0 - mean_r) / 256.0) * db * db
+    };
+
+    theme
+        .named_colors()
+        .into_iter()
+        .map(|(name, color)| (name, (color.r(), color.g(), color.b())))
+        .min_by(|a, b| distance(rgb, a.1).total_cmp(&distance(rgb, b.1)))
+        .unwrap_or(("foreground", rgb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_convert() {
+        let theme = Theme::default();
+        let green = (0xa6, 0xe3, 0xa1);
+        assert_eq!(parse_color("#a6e3a1", &theme), Some(green));
+        assert_eq!(parse_color("A6E3A1", &theme), Some(green));
+        assert_eq!(parse_color("rgb(166, 227, 161)", &theme), Some(green));
+        assert_eq!(parse_color("166,227,161", &theme), Some(green));
+        assert_eq!(parse_color("#fff", &theme), Some((255, 255, 255)));
+        assert_eq!(parse_color("success", &theme), Some(green));
+        assert_eq!(parse_color("rgb(300, 0, 0)", &theme), None);
+
+        assert_eq!(rgb_to_hsl(green), (115, 54, 76));
+        assert_eq!(hsl_to_rgb(0.0, 1.0, 0.5), (255, 0, 0));
+        assert_eq!(
+            parse_color("hsl(240, 100%, 50%)", &theme),
+            Some((0, 0, 255))
+        );
+        assert_eq!(nearest_theme_color((0xa5, 0xe3, 0xa0), &theme).1, green);
+    }
+
+    #[test]
+    fn test_palette_counts_colors() {
+        let theme = Theme::default();
+        let output = "\x1b[31mred\x1b[0m plain \x1b[38;2;1;2;3mrg\x1b[0m\n\x1b[31mr\x1b[0m";
+        let listed = ansi::strip_ansi(&palette(output, &theme).unwrap());
+        let lines: Vec<&str> = listed.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("#cd3131") && lines[0].contains("4 chars"));
+        assert!(lines[1].contains("#010203"));
+        assert!(palette("plain", &theme).is_err());
+    }
+}
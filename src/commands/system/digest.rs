@@ -0,0 +1,47 @@
+//! digest command - report card of the day's activity
+
+use anyhow::{anyhow, Result};
+use chrono::{Days, Local};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct DigestCommand;
+
+impl Command for DigestCommand {
+    fn name(&self) -> &'static str {
+        "digest"
+    }
+
+    fn description(&self) -> &'static str {
+        "Report card of today's activity"
+    }
+
+    fn usage(&self) -> &'static str {
+        "digest [yesterday]\n\n\
+         Summarizes the commands run that day in any tab or window:\n\
+         how many ran and failed, directories visited, the longest command,\n\
+         git commits made, and # AI questions asked. Computed on this\n\
+         machine from your history; nothing is sent anywhere.\n\n\
+         Set [digest] toast = \"18:00\" in the config for an end-of-day toast.\n\n\
+         Examples:\n  \
+         digest            - Today's report card\n  \
+         digest yesterday  - Yesterday's"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let today = Local::now().date_naive();
+        let day = match args.first().map(|s| s.as_str()) {
+            None | Some("today") => today,
+            Some("yesterday") => today - Days::new(1),
+            Some(other) => {
+                return Err(anyhow!(
+                    "unknown day '{}'. Usage: digest [yesterday]",
+                    other
+                ))
+            }
+        };
+        state.requested_digest = Some(day);
+        Ok(String::new())
+    }
+}
@@ -3,6 +3,7 @@
 use anyhow::Result;
 use walkdir::WalkDir;
 
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -74,6 +75,16 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_context(args, None, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        _stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
         let mut human_readable = false;
         let mut summarize = false;
         let mut max_depth: Option<usize> = None;
@@ -84,11 +95,9 @@ RELATED COMMANDS:
             match args[i].as_str() {
                 "-h" | "--human-readable" => human_readable = true,
                 "-s" | "--summarize" => summarize = true,
-                "-d" | "--max-depth" => {
-                    if i + 1 < args.len() {
-                        max_depth = args[i + 1].parse().ok();
-                        i += 1;
-                    }
+                "-d" | "--max-depth" if i + 1 < args.len() => {
+                    max_depth = args[i + 1].parse().ok();
+                    i += 1;
                 }
                 "--help" => {
                     return Ok("Usage: du [OPTIONS] [FILE...]\n\
@@ -114,7 +123,7 @@ RELATED COMMANDS:
             let path = state.resolve_path(path_str);
 
             if summarize {
-                let size = calculate_dir_size(&path)?;
+                let size = calculate_dir_size(&path, ctx)?;
                 output.push(format!(
                     "{}\t{}",
                     format_size(size, human_readable),
@@ -128,7 +137,8 @@ RELATED COMMANDS:
                     .filter_map(|e| e.ok())
                 {
                     if entry.file_type().is_dir() {
-                        let size = calculate_dir_size(entry.path())?;
+                        ctx.report(&entry.path().display().to_string());
+                        let size = calculate_dir_size(entry.path(), ctx)?;
                         output.push(format!(
                             "{}\t{}",
                             format_size(size, human_readable),
@@ -143,7 +153,8 @@ RELATED COMMANDS:
     }
 }
 
-fn calculate_dir_size(path: &std::path::Path) -> Result<u64> {
+/// Total size of the files under `path` (stops early if `ctx` is cancelled)
+fn calculate_dir_size(path: &std::path::Path, ctx: &CommandContext) -> Result<u64> {
     let mut total = 0u64;
 
     if path.is_file() {
@@ -151,6 +162,7 @@ fn calculate_dir_size(path: &std::path::Path) -> Result<u64> {
     }
 
     for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        ctx.check()?;
         if entry.file_type().is_file() {
             total += entry.metadata().map(|m| m.len()).unwrap_or(0);
         }
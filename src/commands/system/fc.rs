@@ -0,0 +1,91 @@
+//! fc command - edit a previous command and run it again
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct FcCommand;
+
+impl Command for FcCommand {
+    fn name(&self) -> &'static str {
+        "fc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Edit a previous command and run it again"
+    }
+
+    fn usage(&self) -> &'static str {
+        "fc [n|-n|prefix]\n\n\
+         Opens the command in the editor; it runs when the editor closes.\n\
+         Each line of the saved file runs as a command - empty the file to cancel.\n\n\
+         Examples:\n  \
+         fc         - Edit the previous command\n  \
+         fc 42      - Edit history entry 42\n  \
+         fc -3      - Edit the third most recent command\n  \
+         fc cargo   - Edit the most recent command starting with 'cargo'"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        // The last entry is this fc invocation itself
+        let history = match state.history.split_last() {
+            Some((_, earlier)) => earlier,
+            None => &[],
+        };
+
+        let command = select_command(history, args.first().map(|s| s.as_str()))?;
+
+        let path = std::env::temp_dir().join(format!("zaxiom-fc-{}.sh", std::process::id()));
+        std::fs::write(&path, format!("{}\n", command))?;
+
+        // The app opens the editor and runs the file when it closes
+        Ok(format!("\x1b[FC]{}", path.display()))
+    }
+}
+
+/// Pick a command by number, offset from the end, or prefix
+fn select_command<'a>(history: &'a [String], selector: Option<&str>) -> Result<&'a str> {
+    let found = match selector {
+        None => history.last(),
+        Some(sel) => {
+            if let Some(offset) = sel.strip_prefix('-').and_then(|n| n.parse::<usize>().ok()) {
+                history
+                    .len()
+                    .checked_sub(offset)
+                    .and_then(|i| history.get(i))
+            } else if let Ok(n) = sel.parse::<usize>() {
+                n.checked_sub(1).and_then(|i| history.get(i))
+            } else {
+                history.iter().rev().find(|c| c.starts_with(sel))
+            }
+        }
+    };
+
+    found.map(|c| c.as_str()).ok_or_else(|| match selector {
+        Some(sel) => anyhow!("no history entry matches '{}'", sel),
+        None => anyhow!("history is empty"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_command() {
+        let history: Vec<String> = ["ls", "cargo build", "git status"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(select_command(&history, None).unwrap(), "git status");
+        assert_eq!(select_command(&history, Some("2")).unwrap(), "cargo build");
+        assert_eq!(select_command(&history, Some("-3")).unwrap(), "ls");
+        assert_eq!(
+            select_command(&history, Some("car")).unwrap(),
+            "cargo build"
+        );
+        assert!(select_command(&history, Some("4")).is_err());
+        assert!(select_command(&[], None).is_err());
+    }
+}
@@ -1,10 +1,34 @@
-//! history command - display command history
-//! Note: This is a placeholder - actual history is managed by the shell
+//! history command - list, search, and edit command history
+//!
+//! Numbers match `!n` expansion, so `history | grep cargo` followed by
+//! `!42` runs exactly the entry shown as 42. `history stats` charts how the
+//! history has been used.
 
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use egui::Color32;
 
 use crate::commands::traits::Command;
-use crate::terminal::state::TerminalState;
+use crate::config::theme::Theme;
+use crate::terminal::buffer::format_duration;
+use crate::terminal::digest::display_dir;
+use crate::terminal::smart_history::HistoryStats;
+use crate::terminal::state::{HistoryRequest, TerminalState};
+
+/// Cells in the longest bar of a chart
+const BAR_WIDTH: usize = 24;
+
+/// Width of the command and directory column
+const LABEL_WIDTH: usize = 24;
+
+/// Bar ends in eighths of a cell
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Hour-of-day levels, lowest first
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const RESET: &str = "\x1b[0m";
 
 pub struct HistoryCommand;
 
@@ -14,26 +38,345 @@ impl Command for HistoryCommand {
     }
 
     fn description(&self) -> &'static str {
-        "Display command history"
+        "List, search, or delete command history"
     }
 
     fn usage(&self) -> &'static str {
-        "history [-c] [n]"
+        "history [n] [-s text] [-d n|start-end] [-c] | history stats\n\n\
+         Examples:\n  \
+         history          - List all commands, numbered like !n\n  \
+         history 20       - List the last 20 commands\n  \
+         history stats    - Most used, most failing, and slowest commands,\n                     \
+         busiest directories and hours\n  \
+         history -s git   - Only commands containing 'git'\n  \
+         history -d 42    - Delete entry 42 (-d -1 deletes the last one)\n  \
+         history -d 10-15 - Delete entries 10 through 15\n  \
+         history -c       - Clear the whole history\n  \
+         fc 42            - Edit entry 42 and run it again"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
-        // Check for clear flag
-        for arg in args {
-            if arg == "-c" || arg == "--clear" {
-                return Ok("History cleared (note: actual implementation in shell)".to_string());
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        if args.first().is_some_and(|arg| arg == "stats") {
+            let stats = state
+                .history_stats
+                .take()
+                .ok_or_else(|| anyhow!("history stats are only available in a pane"))?;
+            let theme = Theme::from_name(state.current_theme);
+            return Ok(render_stats(&stats, &theme, state.home()));
+        }
+
+        let mut limit = None;
+        let mut search: Option<String> = None;
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-c" | "--clear" => {
+                    state.requested_history = Some(HistoryRequest::Clear);
+                    return Ok("History cleared.".to_string());
+                }
+                "-d" | "--delete" => {
+                    let spec = iter
+                        .next()
+                        .ok_or_else(|| anyhow!("-d needs an entry number or range"))?;
+                    let (start, end) = parse_range(spec, state.history.len())?;
+                    state.requested_history = Some(HistoryRequest::Delete { start, end });
+                    return Ok(if start == end {
+                        format!("Deleted history entry {}.", start)
+                    } else {
+                        format!("Deleted history entries {}-{}.", start, end)
+                    });
+                }
+                "-s" | "--search" => {
+                    let text = iter.next().ok_or_else(|| anyhow!("-s needs search text"))?;
+                    search = Some(text.to_lowercase());
+                }
+                other => match other.parse::<usize>() {
+                    Ok(n) => limit = Some(n),
+                    // Bare words filter too: `history cargo`
+                    Err(_) => search = Some(other.to_lowercase()),
+                },
             }
         }
 
-        // This is a placeholder - the actual history command would need
-        // access to the CommandHistory struct from the app
-        Ok("💡 Tip: Use ↑/↓ arrows to navigate command history\n\
-            📝 History is stored in memory during this session\n\
-            ⚡ history -c would clear history"
-            .to_string())
+        let numbered: Vec<(usize, &String)> = state
+            .history
+            .iter()
+            .enumerate()
+            .map(|(i, command)| (i + 1, command))
+            .filter(|(_, command)| {
+                search
+                    .as_ref()
+                    .is_none_or(|text| command.to_lowercase().contains(text))
+            })
+            .collect();
+
+        let skip = limit.map_or(0, |n| numbered.len().saturating_sub(n));
+        let width = state.history.len().to_string().len();
+
+        Ok(numbered[skip..]
+            .iter()
+            .map(|(n, command)| format!("  {:>width$}  {}", n, command, width = width))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+/// A bar `value / max` of the chart width, to an eighth of a cell (never
+/// empty, so every row shows)
+fn bar(value: f64, max: f64) -> String {
+    let eighths = if max > 0.0 {
+        (value / max * (BAR_WIDTH * 8) as f64).round() as usize
+    } else {
+        0
+    };
+    let eighths = eighths.clamp(1, BAR_WIDTH * 8);
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(EIGHTHS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/// `text` cut to `width` characters
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// The `history stats` charts
+fn render_stats(stats: &HistoryStats, theme: &Theme, home: &Path) -> String {
+    if stats.total_commands == 0 {
+        return "No history yet.".to_string();
+    }
+    let heading = |title: &str| format!("{}\x1b[1m{}{}", fg(theme.accent), title, RESET);
+    let row = |label: &str, value: f64, max: f64, color: Color32, note: String| {
+        format!(
+            "  {:<label$} {}{:<bar$}{} {}",
+            truncate(label, LABEL_WIDTH),
+            fg(color),
+            bar(value, max),
+            RESET,
+            note,
+            label = LABEL_WIDTH,
+            bar = BAR_WIDTH,
+        )
+    };
+
+    let finished = stats.successful + stats.failed;
+    let mut lines = vec![format!(
+        "{} commands, {} unique · {}{} failed{} ({}%)",
+        stats.total_commands,
+        stats.unique_commands,
+        fg(theme.error_color),
+        stats.failed,
+        RESET,
+        (stats.failed * 100).checked_div(finished).unwrap_or(0)
+    )];
+
+    lines.push(String::new());
+    lines.push(heading("Most used"));
+    let max = stats.most_used.first().map_or(0, |(_, runs)| *runs) as f64;
+    for (command, runs) in &stats.most_used {
+        lines.push(row(
+            command,
+            *runs as f64,
+            max,
+            theme.accent,
+            runs.to_string(),
+        ));
+    }
+
+    if !stats.most_failed.is_empty() {
+        lines.push(String::new());
+        lines.push(heading("Most failures"));
+        let max = stats.most_failed[0].1 as f64;
+        for (command, failed, runs) in &stats.most_failed {
+            let note = format!("{} of {} ({}%)", failed, runs, failed * 100 / runs);
+            lines.push(row(command, *failed as f64, max, theme.error_color, note));
+        }
+    }
+
+    if !stats.slowest.is_empty() {
+        lines.push(String::new());
+        lines.push(heading("Slowest on average"));
+        let max = stats.slowest[0].1.as_secs_f64();
+        for (command, average, runs) in &stats.slowest {
+            let note = format!(
+                "{} ({} run{})",
+                format_duration(*average),
+                runs,
+                if *runs == 1 { "" } else { "s" }
+            );
+            lines.push(row(
+                command,
+                average.as_secs_f64(),
+                max,
+                theme.warning_color,
+                note,
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(heading("Directories"));
+    let max = stats.directories.first().map_or(0, |(_, count)| *count) as f64;
+    for (dir, count) in &stats.directories {
+        let dir = display_dir(dir, home);
+        lines.push(row(
+            &dir,
+            *count as f64,
+            max,
+            theme.path_color,
+            count.to_string(),
+        ));
+    }
+
+    // One column pair per hour, midnight first
+    let max = stats.by_hour.iter().copied().max().unwrap_or(0);
+    let busiest = (0..24).max_by_key(|&hour| (stats.by_hour[hour], std::cmp::Reverse(hour)));
+    lines.push(String::new());
+    lines.push(heading(&format!(
+        "Hours (busiest {:02}:00)",
+        busiest.unwrap_or(0)
+    )));
+    let chart: String = stats
+        .by_hour
+        .iter()
+        .map(|&count| match count {
+            0 => ' ',
+            _ => LEVELS[count * (LEVELS.len() - 1) / max],
+        })
+        .flat_map(|level| [level, level])
+        .collect();
+    lines.push(format!("  {}{}{}", fg(theme.info_color), chart, RESET));
+    let axis: String = (0..24)
+        .step_by(6)
+        .map(|hour| format!("{:<12}", hour))
+        .collect();
+    lines.push(format!(
+        "  {}{}{}",
+        fg(theme.foreground_dim),
+        axis.trim_end(),
+        RESET
+    ));
+    lines.join("\n")
+}
+
+/// Parse `n`, `-n` (counted from the end), or `start-end` into a 1-based
+/// inclusive range within `len` entries
+pub fn parse_range(spec: &str, len: usize) -> Result<(usize, usize)> {
+    let invalid = || anyhow!("no history entry '{}' (history has {} entries)", spec, len);
+
+    let (start, end) = if let Some(offset) = spec.strip_prefix('-') {
+        let offset: usize = offset.parse().map_err(|_| invalid())?;
+        let n = (len + 1).checked_sub(offset).ok_or_else(invalid)?;
+        (n, n)
+    } else if let Some((start, end)) = spec.split_once('-') {
+        (
+            start.parse().map_err(|_| invalid())?,
+            end.parse().map_err(|_| invalid())?,
+        )
+    } else {
+        let n = spec.parse().map_err(|_| invalid())?;
+        (n, n)
+    };
+
+    if start == 0 || start > end || end > len {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+    use crate::terminal::ansi::strip_ansi_colors;
+    use std::time::Duration;
+
+    fn state_with(commands: &[&str]) -> TerminalState {
+        MockTerminalState::new().history(commands).build()
+    }
+
+    #[test]
+    fn test_numbering_and_search() {
+        let mut state = state_with(&["ls", "cargo build", "git status", "cargo test"]);
+        let output = HistoryCommand
+            .execute(&["-s".to_string(), "cargo".to_string()], &mut state)
+            .unwrap();
+        assert_eq!(output, "  2  cargo build\n  4  cargo test");
+
+        let output = HistoryCommand
+            .execute(&["1".to_string()], &mut state)
+            .unwrap();
+        assert_eq!(output, "  4  cargo test");
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut state = state_with(&[]);
+        assert!(HistoryCommand
+            .execute(&["stats".to_string()], &mut state)
+            .is_err());
+
+        let mut by_hour = [0; 24];
+        by_hour[9] = 2;
+        by_hour[14] = 8;
+        state.history_stats = Some(HistoryStats {
+            total_commands: 10,
+            unique_commands: 3,
+            successful: 7,
+            failed: 3,
+            most_used: vec![("cargo test".to_string(), 6), ("ls".to_string(), 3)],
+            most_failed: vec![("cargo test".to_string(), 3, 6)],
+            slowest: vec![("cargo test".to_string(), Duration::from_secs(90), 6)],
+            directories: vec![(state.home().join("src"), 10)],
+            by_hour,
+        });
+        let output = HistoryCommand
+            .execute(&["stats".to_string()], &mut state)
+            .unwrap();
+        let output = strip_ansi_colors(&output);
+        assert!(output.starts_with("10 commands, 3 unique · 3 failed (30%)"));
+        assert!(output.contains(&format!(
+            "  cargo test{}{} 6",
+            " ".repeat(15),
+            "█".repeat(24)
+        )));
+        assert!(output.contains(&format!("  ls{}{}", " ".repeat(23), "█".repeat(12))));
+        assert!(output.contains("3 of 6 (50%)"));
+        assert!(output.contains("1m 30s (6 runs)"));
+        assert!(output.contains("  ~/src "));
+        assert!(output.contains("Hours (busiest 14:00)"));
+        assert!(output.contains(&format!("{}▂▂{}██", " ".repeat(18), " ".repeat(8))));
+        assert!(state.history_stats.is_none());
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar(1.0, 1.0), "█".repeat(BAR_WIDTH));
+        assert_eq!(bar(1.0, 16.0), "█▌");
+        assert_eq!(bar(0.0, 100.0), "▏");
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("3", 5).unwrap(), (3, 3));
+        assert_eq!(parse_range("-1", 5).unwrap(), (5, 5));
+        assert_eq!(parse_range("2-4", 5).unwrap(), (2, 4));
+        assert!(parse_range("0", 5).is_err());
+        assert!(parse_range("4-2", 5).is_err());
+        assert!(parse_range("6", 5).is_err());
+        assert!(parse_range("-6", 5).is_err());
     }
 }
@@ -0,0 +1,146 @@
+//! keys command - list and rebind keyboard shortcuts
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::keymap::{self, Action, Chord, Keymap};
+use crate::terminal::state::TerminalState;
+
+pub struct KeysCommand;
+
+impl Command for KeysCommand {
+    fn name(&self) -> &'static str {
+        "keys"
+    }
+
+    fn description(&self) -> &'static str {
+        "List and rebind keyboard shortcuts"
+    }
+
+    fn usage(&self) -> &'static str {
+        "keys [<action> [<chord>...|none|reset]|reset]\n\n\
+         Changed shortcuts are saved to keybindings.toml next to config.toml\n\
+         and take effect right away. A chord taken from another action\n\
+         leaves that action without it.\n\n\
+         Examples:\n  \
+         keys                                - List actions and their keys\n  \
+         keys split_horizontal Ctrl+Shift+X  - Rebind an action\n  \
+         keys zoom_in Ctrl+= Ctrl+Plus       - Bind several chords\n  \
+         keys git_panel none                 - Unbind an action\n  \
+         keys git_panel reset                - Back to its default keys\n  \
+         keys reset                          - Every action back to its defaults"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut keymap = keymap::current();
+        let message = match args.first().map(|s| s.as_str()) {
+            None | Some("list") => return Ok(list(&keymap)),
+            Some("reset") if args.len() == 1 => {
+                keymap = Keymap::default();
+                "All shortcuts are back to their defaults".to_string()
+            }
+            Some(name) => {
+                let action = Action::find(name).ok_or_else(|| {
+                    anyhow!("unknown action '{}'. Run 'keys' to see them all.", name)
+                })?;
+                rebind(&mut keymap, action, &args[1..])?
+            }
+        };
+        keymap.save(&keymap::saved_path())?;
+        state.requested_keymap = Some(keymap);
+        Ok(message)
+    }
+}
+
+/// Apply `keys <action> ...` to `keymap`, returning what changed
+fn rebind(keymap: &mut Keymap, action: Action, args: &[String]) -> Result<String> {
+    let displaced = match args {
+        [] => {
+            return Err(anyhow!(
+                "usage: keys {} <chord>...|none|reset",
+                action.name()
+            ))
+        }
+        [arg] if arg == "reset" => keymap.reset(action),
+        [arg] if arg == "none" => {
+            keymap.bind(action, Vec::new());
+            return Ok(format!("{} is unbound", action.title()));
+        }
+        chords => {
+            let chords = chords
+                .iter()
+                .map(|chord| chord.parse())
+                .collect::<Result<Vec<Chord>>>()?;
+            keymap.bind(action, chords)
+        }
+    };
+    let mut message = format!("{} → {}", action.title(), keys_text(keymap, action));
+    if !displaced.is_empty() {
+        let names: Vec<&str> = displaced.iter().map(|a| a.title()).collect();
+        message.push_str(&format!(" (taken from {})", names.join(", ")));
+    }
+    Ok(message)
+}
+
+fn keys_text(keymap: &Keymap, action: Action) -> String {
+    let chords: Vec<String> = keymap.chords(action).iter().map(Chord::to_string).collect();
+    if chords.is_empty() {
+        "(none)".to_string()
+    } else {
+        chords.join(" / ")
+    }
+}
+
+fn list(keymap: &Keymap) -> String {
+    let mut output = String::from("Keyboard shortcuts (* changed):\n\n");
+    for action in Action::ALL {
+        let changed = if keymap.is_default(action) { ' ' } else { '*' };
+        output.push_str(&format!(
+            "{} {:20} {:22} {}\n",
+            changed,
+            action.name(),
+            keys_text(keymap, action),
+            action.title()
+        ));
+    }
+    output.push_str("\nUsage: keys <action> <chord>...  |  keys <action> reset");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+
+    #[test]
+    fn test_keys() {
+        let mut state = MockTerminalState::new().build();
+        let keys = |args: &[&str], state: &mut TerminalState| {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            KeysCommand.execute(&args, state)
+        };
+        let listing = keys(&[], &mut state).unwrap();
+        assert!(listing.contains("split_horizontal"));
+        assert!(listing.contains("Ctrl+Shift+D"));
+        assert!(keys(&["warp_drive", "Ctrl+W"], &mut state).is_err());
+        assert!(keys(&["new_tab", "Ctrl+Nope"], &mut state).is_err());
+        assert!(keys(&["new_tab"], &mut state).is_err());
+        assert!(state.requested_keymap.is_none());
+
+        let mut keymap = Keymap::default();
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(
+            rebind(&mut keymap, Action::NewTab, &args(&["Ctrl+Shift+E"])).unwrap(),
+            "New Tab → Ctrl+Shift+E (taken from Split Vertical)"
+        );
+        assert_eq!(
+            rebind(&mut keymap, Action::SplitVertical, &args(&["reset"])).unwrap(),
+            "Split Vertical → Ctrl+Shift+E (taken from New Tab)"
+        );
+        assert_eq!(
+            rebind(&mut keymap, Action::NewTab, &args(&["none"])).unwrap(),
+            "New Tab is unbound"
+        );
+        assert!(list(&keymap).contains("* new_tab"));
+    }
+}
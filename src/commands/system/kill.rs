@@ -17,7 +17,7 @@ impl Command for KillCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "kill [-9] <pid> [pid2...]"
+        "kill [-9] <pid|%job> [pid2...]"
     }
 
     fn extended_help(&self) -> String {
@@ -25,6 +25,7 @@ impl Command for KillCommand {
 
 USAGE:
   kill [OPTIONS] <pid> [pid2...]
+  kill %<job>
 
 OPTIONS:
   -9, -KILL, --force    Force kill (no graceful shutdown)
@@ -32,11 +33,13 @@ OPTIONS:
 DESCRIPTION:
   Terminate processes by their process ID (PID).
   Use 'ps' command to find process IDs.
+  %n stops background job n instead (see 'jobs').
 
 EXAMPLES:
   kill 1234             Gracefully terminate process
   kill -9 1234          Force kill process
   kill 1234 5678 9012   Kill multiple processes
+  kill %1               Stop background job 1
 
 SIGNALS (Linux-style, Windows compatible):
   (default)  Request graceful termination
@@ -63,14 +66,16 @@ NOTE:
 
 RELATED COMMANDS:
   ps       List processes
+  jobs     List background jobs
   taskkill Windows native kill command
 "#
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         let mut force = false;
         let mut pids: Vec<&String> = Vec::new();
+        let mut jobs: Vec<&String> = Vec::new();
 
         for arg in args {
             match arg.as_str() {
@@ -81,17 +86,31 @@ RELATED COMMANDS:
                         -9    Force kill (SIGKILL)"
                         .to_string());
                 }
+                _ if arg.starts_with('%') => jobs.push(arg),
                 _ if !arg.starts_with('-') => pids.push(arg),
                 _ => {}
             }
         }
 
-        if pids.is_empty() {
+        if pids.is_empty() && jobs.is_empty() {
             return Err(anyhow::anyhow!("kill: missing process ID"));
         }
 
         let mut results = Vec::new();
 
+        // Jobs are stopped through the pane's job table, not by PID
+        for spec in jobs {
+            let table = state.jobs();
+            let killed = table
+                .find(Some(spec))
+                .ok()
+                .and_then(|id| table.kill(id).map(|command| (id, command)));
+            match killed {
+                Some((id, command)) => results.push(format!("[{}]  Terminated  {}", id, command)),
+                None => results.push(format!("kill: {}: no such job", spec)),
+            }
+        }
+
         for pid in pids {
             let cmd = if force {
                 format!("Stop-Process -Id {} -Force -ErrorAction Stop", pid)
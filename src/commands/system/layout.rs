@@ -0,0 +1,103 @@
+//! layout command - arrange split panes with presets or saved layouts
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::settings::Config;
+use crate::terminal::layout::{Layout, PRESETS};
+use crate::terminal::state::{LayoutRequest, TerminalState};
+
+pub struct LayoutCommand;
+
+impl Command for LayoutCommand {
+    fn name(&self) -> &'static str {
+        "layout"
+    }
+
+    fn description(&self) -> &'static str {
+        "Arrange split panes with a preset or saved layout"
+    }
+
+    fn usage(&self) -> &'static str {
+        "layout [name|list|save <name>|delete <name>]\n\n\
+         Examples:\n  \
+         layout              - List available layouts\n  \
+         layout grid         - Arrange the current tab as a 2x2 grid\n  \
+         layout main-side    - Large main pane with a side pane\n  \
+         layout single       - Back to one pane\n  \
+         layout save dev     - Save the current arrangement as 'dev'\n  \
+         layout delete dev   - Remove a saved layout\n\n\
+         Saved layouts live under [layouts] in config.toml as a spec:\n  \
+         pane                - A single pane\n  \
+         v(0.65, pane, pane) - Side by side, first pane gets 65%\n  \
+         h(pane, pane)       - Stacked, split evenly"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let config = Config::load();
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list") => Ok(list_layouts(&config)),
+            Some("save") => {
+                let name = args
+                    .get(1)
+                    .ok_or_else(|| anyhow!("usage: layout save <name>"))?;
+                if Layout::preset(name).is_some() {
+                    return Err(anyhow!(
+                        "'{}' is a built-in layout, pick another name",
+                        name
+                    ));
+                }
+                state.requested_layout = Some(LayoutRequest::Save(name.clone()));
+                Ok(format!("Saved current layout as '{}'", name))
+            }
+            Some("delete") | Some("rm") => {
+                let name = args
+                    .get(1)
+                    .ok_or_else(|| anyhow!("usage: layout delete <name>"))?;
+                let mut config = config;
+                if config.layouts.remove(name).is_none() {
+                    return Err(anyhow!("no saved layout named '{}'", name));
+                }
+                config.save()?;
+                Ok(format!("Deleted layout '{}'", name))
+            }
+            Some(name) => {
+                let layout = match config.layouts.get(name) {
+                    Some(spec) => Layout::parse(spec)
+                        .map_err(|e| anyhow!("saved layout '{}' is invalid: {}", name, e))?,
+                    None => Layout::preset(name).ok_or_else(|| {
+                        anyhow!("unknown layout '{}'. Run 'layout' to see them all.", name)
+                    })?,
+                };
+                let panes = layout.pane_count();
+                state.requested_layout = Some(LayoutRequest::Apply(layout));
+                Ok(format!(
+                    "Layout '{}' ({} pane{})",
+                    name,
+                    panes,
+                    if panes == 1 { "" } else { "s" }
+                ))
+            }
+        }
+    }
+}
+
+fn list_layouts(config: &Config) -> String {
+    let mut output = String::from("Built-in layouts:\n\n");
+    for (name, description, _) in PRESETS {
+        output.push_str(&format!("  {:14} {}\n", name, description));
+    }
+
+    if !config.layouts.is_empty() {
+        output.push_str("\nSaved layouts:\n\n");
+        let mut saved: Vec<_> = config.layouts.iter().collect();
+        saved.sort();
+        for (name, spec) in saved {
+            output.push_str(&format!("  {:14} {}\n", name, spec));
+        }
+    }
+
+    output.push_str("\nUsage: layout <name>  |  layout save <name>");
+    output
+}
@@ -1,48 +1,80 @@
 //! System commands
 //!
 //! exit, which, du, df, ps, kill, whoami, hostname, uname, uptime, free, date, cal, id, neofetch
-//! printenv, lscpu, history, test, man, theme
+//! printenv, lscpu, history, fc, audit, test, man, theme, layout, pane, tips,
+//! timestamps, color, buffer, paths, stats, digest, zoom, keys,
+//! workspace, session
 
+mod audit;
+mod buffer;
 mod cal;
+mod color;
 mod date;
 mod df;
+mod digest;
 mod du;
 mod exit;
+mod fc;
 mod free;
 mod history_cmd;
 mod hostname;
 mod id;
+mod keys;
 mod kill;
+mod layout;
 mod lscpu;
 mod man;
 mod neofetch;
+mod pane;
+mod paths;
 mod printenv;
 mod ps;
+mod session;
+mod stats;
 mod test_cmd;
 mod theme;
+mod timestamps;
+mod tips;
 mod uname;
 mod uptime;
 mod which;
 mod whoami;
+mod workspace;
+mod zoom;
 
+pub use audit::AuditCommand;
+pub use buffer::BufferCommand;
 pub use cal::CalCommand;
+pub use color::ColorCommand;
 pub use date::DateCommand;
 pub use df::DfCommand;
+pub use digest::DigestCommand;
 pub use du::DuCommand;
 pub use exit::ExitCommand;
+pub use fc::FcCommand;
 pub use free::FreeCommand;
 pub use history_cmd::HistoryCommand;
 pub use hostname::HostnameCommand;
 pub use id::IdCommand;
+pub use keys::KeysCommand;
 pub use kill::KillCommand;
+pub use layout::LayoutCommand;
 pub use lscpu::LscpuCommand;
 pub use man::ManCommand;
 pub use neofetch::NeofetchCommand;
+pub use pane::PaneCommand;
+pub use paths::PathsCommand;
 pub use printenv::PrintenvCommand;
 pub use ps::PsCommand;
+pub use session::SessionCommand;
+pub use stats::StatsCommand;
 pub use test_cmd::TestCommand;
 pub use theme::ThemeCommand;
+pub use timestamps::TimestampsCommand;
+pub use tips::TipsCommand;
 pub use uname::UnameCommand;
 pub use uptime::UptimeCommand;
 pub use which::WhichCommand;
 pub use whoami::WhoamiCommand;
+pub use workspace::WorkspaceCommand;
+pub use zoom::ZoomCommand;
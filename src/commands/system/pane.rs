@@ -0,0 +1,57 @@
+//! pane command - name the current split pane
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+/// Longest name that still fits comfortably in a pane header
+const MAX_NAME_LEN: usize = 32;
+
+pub struct PaneCommand;
+
+impl Command for PaneCommand {
+    fn name(&self) -> &'static str {
+        "pane"
+    }
+
+    fn description(&self) -> &'static str {
+        "Name the current pane"
+    }
+
+    fn usage(&self) -> &'static str {
+        "pane [rename <name>|rename]\n\n\
+         Examples:\n  \
+         pane               - Show the current pane's name\n  \
+         pane rename build  - Show 'build' in this pane's header\n  \
+         pane rename        - Clear the name"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            None | Some("name") => Ok(match &state.pane_name {
+                Some(name) => format!("This pane is named '{}'", name),
+                None => "This pane has no name. Use: pane rename <name>".to_string(),
+            }),
+            Some("rename") => {
+                let name = args[1..].join(" ").trim().to_string();
+                if name.is_empty() {
+                    state.pane_name = None;
+                    return Ok("Pane name cleared".to_string());
+                }
+                if name.chars().count() > MAX_NAME_LEN {
+                    return Err(anyhow!(
+                        "pane name is too long (max {} characters)",
+                        MAX_NAME_LEN
+                    ));
+                }
+                state.pane_name = Some(name.clone());
+                Ok(format!("Pane renamed to '{}'", name))
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: pane rename <name>",
+                other
+            )),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! paths command - where config, sessions, and logs are stored
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::ai::cache::AiCache;
+use crate::commands::traits::Command;
+use crate::config::aliases;
+use crate::config::env::get_env_file_path;
+use crate::config::paths::{self, StorageMode, PORTABLE_DIR, PORTABLE_MARKER};
+use crate::config::settings::Config;
+use crate::config::theme::UserTheme;
+use crate::terminal::audit::AuditLog;
+use crate::terminal::buffer;
+use crate::terminal::session::SessionManager;
+use crate::terminal::smart_history;
+use crate::terminal::state::TerminalState;
+use crate::terminal::tips::TipsState;
+use crate::terminal::usage;
+
+pub struct PathsCommand;
+
+impl Command for PathsCommand {
+    fn name(&self) -> &'static str {
+        "paths"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show where config and data are stored"
+    }
+
+    fn usage(&self) -> &'static str {
+        "paths\n\n\
+         Start zaxiom with --portable (or put a file named 'portable' next to\n\
+         the executable) to keep everything in a zaxiom-data folder beside it,\n\
+         or with --config-dir <dir> to keep everything under <dir>."
+    }
+
+    fn execute(&self, _args: &[String], _state: &mut TerminalState) -> Result<String> {
+        let mode = match paths::get().mode {
+            StorageMode::Installed => {
+                "installed (platform config and data directories)".to_string()
+            }
+            StorageMode::Portable => format!("portable ({} next to the executable)", PORTABLE_DIR),
+            StorageMode::Custom => "--config-dir".to_string(),
+        };
+
+        let entries = [
+            ("Config", Config::config_path()),
+            ("Aliases", aliases::saved_path()),
+            ("Themes", UserTheme::dir()),
+            ("Env", get_env_file_path()),
+            ("Sessions", SessionManager::default_session_dir()),
+            ("Audit", AuditLog::default_path()),
+            ("AI cache", AiCache::cache_path()),
+            ("History", smart_history::default_path()),
+            ("Usage", usage::default_path()),
+            ("Tips", TipsState::path()),
+            ("Scrollback", buffer::spill_dir()),
+        ];
+
+        let mut lines = vec![format!("{:<10}{}", "Mode", mode)];
+        for (label, path) in &entries {
+            lines.push(format!("{:<10}{}{}", label, path.display(), missing(path)));
+        }
+        if paths::get().mode == StorageMode::Installed {
+            lines.push(String::new());
+            lines.push(format!(
+                "Run with --portable or add a '{}' file next to the executable for portable mode.",
+                PORTABLE_MARKER
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+fn missing(path: &Path) -> &'static str {
+    if path.exists() {
+        ""
+    } else {
+        "  (not created yet)"
+    }
+}
@@ -1,7 +1,5 @@
 //! printenv command - print environment variables
 
-use std::env;
-
 use anyhow::Result;
 
 use crate::commands::traits::Command;
@@ -22,13 +20,11 @@ impl Command for PrintenvCommand {
         "printenv [variable...]"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
             // Print all environment variables
-            let mut vars: Vec<_> = env::vars().collect();
-            vars.sort_by(|a, b| a.0.cmp(&b.0));
-
-            let output: Vec<String> = vars
+            let output: Vec<String> = state
+                .vars()
                 .into_iter()
                 .map(|(k, v)| format!("{}={}", k, v))
                 .collect();
@@ -39,7 +35,7 @@ impl Command for PrintenvCommand {
             let mut output = Vec::new();
 
             for var in args {
-                if let Ok(value) = env::var(var) {
+                if let Some(value) = state.var(var) {
                     output.push(value);
                 }
             }
@@ -0,0 +1,144 @@
+//! session command - list, save, and restore saved sessions
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::session::{saved_time, SavedSession, SessionManager, LAST_SESSION};
+use crate::terminal::state::{SessionRequest, TerminalState};
+
+pub struct SessionCommand;
+
+impl Command for SessionCommand {
+    fn name(&self) -> &'static str {
+        "session"
+    }
+
+    fn description(&self) -> &'static str {
+        "List, save, and restore sessions"
+    }
+
+    fn usage(&self) -> &'static str {
+        "session [list|restore [name]|save <name>|delete <name>]\n\n\
+         The window's tabs, splits, directories, and each pane's commands are\n\
+         saved as you go; the previous window's are kept as 'last'. Set\n\
+         restore = true under [session] in config.toml to be offered the\n\
+         saved sessions on every launch.\n\n\
+         Examples:\n  \
+         session                - List saved sessions\n  \
+         session restore        - Pick a session to restore\n  \
+         session restore last   - Reopen the previous window's tabs\n  \
+         session save release   - Save the current tabs as 'release'\n  \
+         session delete release - Remove a saved session"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let sessions = SessionManager::new();
+        let name = || {
+            args.get(1)
+                .map(|s| s.as_str())
+                .ok_or_else(|| anyhow!("usage: session {} <name>", args[0]))
+        };
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list") | Some("ls") => Ok(list(&sessions.restorable_sessions())),
+            Some("restore") => {
+                let Some(name) = args.get(1) else {
+                    if sessions.restorable_sessions().is_empty() {
+                        return Err(anyhow!("no saved sessions yet"));
+                    }
+                    state.requested_session = Some(SessionRequest::Pick);
+                    return Ok(String::new());
+                };
+                let session = sessions
+                    .load_session(name)
+                    .map_err(|_| anyhow!("no session named '{}'", name))?;
+                if session.tabs.is_empty() {
+                    return Err(anyhow!("session '{}' has no tabs", name));
+                }
+                let message = format!("Restoring session '{}' ({})", name, session.tab_titles());
+                state.requested_session = Some(SessionRequest::Restore(session));
+                Ok(message)
+            }
+            Some("save") => {
+                let name = name()?;
+                if name == "autosave" || name == LAST_SESSION {
+                    return Err(anyhow!(
+                        "'{}' is kept automatically, pick another name",
+                        name
+                    ));
+                }
+                state.requested_session = Some(SessionRequest::Save(name.to_string()));
+                Ok(format!("Saved session '{}'", name))
+            }
+            Some("delete") | Some("rm") => {
+                let name = name()?;
+                if !sessions.list_sessions()?.iter().any(|n| n == name) {
+                    return Err(anyhow!("no session named '{}'", name));
+                }
+                sessions.delete_session(name)?;
+                Ok(format!("Deleted session '{}'", name))
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: session [list|restore|save|delete] <name>",
+                other
+            )),
+        }
+    }
+}
+
+fn list(sessions: &[SavedSession]) -> String {
+    if sessions.is_empty() {
+        return "No saved sessions yet. The tabs of this window are kept as 'last' next time."
+            .to_string();
+    }
+    let mut output = String::from("Saved sessions (newest first):\n\n");
+    for session in sessions {
+        output.push_str(&format!(
+            "  {:16} {:16} {:8} {}\n",
+            session.name,
+            saved_time(session.saved_at),
+            format!(
+                "{} tab{}",
+                session.tabs.len(),
+                if session.tabs.len() == 1 { "" } else { "s" }
+            ),
+            session.tab_titles()
+        ));
+    }
+    output.push_str("\nUsage: session restore [name]  |  session save <name>");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+    use crate::terminal::session::SavedTab;
+
+    #[test]
+    fn test_session() {
+        let mut state = MockTerminalState::new().build();
+        let session = |args: &[&str], state: &mut TerminalState| {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            SessionCommand.execute(&args, state)
+        };
+        assert!(session(&["save"], &mut state).is_err());
+        assert!(session(&["save", "last"], &mut state).is_err());
+        assert!(session(&["frobnicate"], &mut state).is_err());
+        assert!(session(&["restore", "no-such-session-here"], &mut state).is_err());
+        assert!(state.requested_session.is_none());
+
+        session(&["save", "release"], &mut state).unwrap();
+        assert!(matches!(
+            state.requested_session,
+            Some(SessionRequest::Save(ref name)) if name == "release"
+        ));
+
+        let mut saved = SavedSession::new(LAST_SESSION);
+        saved.add_tab(SavedTab::new("src", "/src".into()));
+        saved.add_tab(SavedTab::new("logs", "/var/log".into()));
+        let listing = list(&[saved]);
+        assert!(listing.contains("2 tabs"));
+        assert!(listing.contains("src, logs"));
+    }
+}
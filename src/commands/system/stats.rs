@@ -0,0 +1,69 @@
+//! stats command - local usage dashboard
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::settings::Config;
+use crate::terminal::state::{StatsRequest, TerminalState};
+use crate::terminal::usage;
+
+pub struct StatsCommand;
+
+impl Command for StatsCommand {
+    fn name(&self) -> &'static str {
+        "stats"
+    }
+
+    fn description(&self) -> &'static str {
+        "Local usage dashboard (never leaves this machine)"
+    }
+
+    fn usage(&self) -> &'static str {
+        "stats [on|off|clear]\n\n\
+         Shows commands per day, busiest hours, top directories, average run\n\
+         time, and AI prompts. Only times, directories, and durations are\n\
+         recorded - never command text - and nothing is sent anywhere.\n\n\
+         Examples:\n  \
+         stats        - Open the dashboard\n  \
+         stats off    - Stop recording\n  \
+         stats clear  - Delete everything recorded so far"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            None | Some("show") => {
+                state.requested_stats = Some(StatsRequest::Show);
+                Ok(String::new())
+            }
+            Some(toggle @ ("on" | "off")) => {
+                let enabled = toggle == "on";
+                let mut config = Config::load();
+                config.stats.enabled = enabled;
+                config.save()?;
+                state.requested_stats = Some(StatsRequest::Record(enabled));
+                Ok(if enabled {
+                    format!(
+                        "Usage stats on - recording to {}",
+                        usage::default_path().display()
+                    )
+                } else {
+                    "Usage stats off.".to_string()
+                })
+            }
+            Some("clear") => {
+                let path = usage::default_path();
+                match std::fs::remove_file(&path) {
+                    Ok(()) => Ok("Usage stats cleared.".to_string()),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        Ok("No usage stats recorded yet.".to_string())
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: stats [on|off|clear]",
+                other
+            )),
+        }
+    }
+}
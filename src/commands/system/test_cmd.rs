@@ -24,6 +24,11 @@ impl Command for TestCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        // `[ expr ]` - drop the closing bracket
+        let args = match args.split_last() {
+            Some((last, rest)) if last == "]" => rest,
+            _ => args,
+        };
         if args.is_empty() {
             return Ok("false".to_string());
         }
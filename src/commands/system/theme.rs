@@ -1,9 +1,12 @@
 //! theme command - list and switch terminal themes
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use crate::commands::traits::Command;
-use crate::config::theme::ThemeName;
+use crate::config::settings::{Config, KawaiiLevel};
+use crate::config::theme::{
+    parse_hours, user_theme_template, ColorVision, ThemeConfig, ThemeName, UserTheme,
+};
 use crate::terminal::state::TerminalState;
 
 pub struct ThemeCommand;
@@ -18,14 +21,32 @@ impl Command for ThemeCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "theme [name] [--kawaii|--normal]\n\n\
+        "theme [name] [--kawaii [off|subtle|full]|--normal]\n\
+         theme edit <name>\n\
+         theme --vision [normal|deuteranopia|protanopia|tritanopia]\n\
+         theme auto [on|off|light <name>|dark <name>|hours <HH:MM-HH:MM|off>]\n\n\
+         Auto mode switches between a light and a dark theme to match the\n\
+         system setting, or by time of day when light hours are set.\n\
+         Picking a theme by name turns auto mode off.\n\n\
+         Your own themes are TOML files in the themes folder (see paths):\n\
+         a base theme plus the colors to change. theme edit switches to one\n\
+         and opens it in the editor, starting from a copy of the current\n\
+         theme if it's new; saved changes show up right away.\n\n\
          Examples:\n  \
-         theme           - List all available themes\n  \
-         theme dracula   - Switch to Dracula theme\n  \
-         theme nord      - Switch to Nord theme\n  \
-         theme list      - List all themes with descriptions\n  \
-         theme --kawaii  - Enable kawaii mode (cuter UI)\n  \
-         theme --normal  - Disable kawaii mode"
+         theme                  - List all available themes\n  \
+         theme dracula          - Switch to Dracula theme\n  \
+         theme nord             - Switch to Nord theme\n  \
+         theme list             - List all themes with descriptions\n  \
+         theme edit midnight    - Create or change your own theme\n  \
+         theme auto             - Follow the system light/dark setting\n  \
+         theme auto dark nord   - Use Nord when it's dark\n  \
+         theme auto hours 07:00-19:00 - Light theme during the day\n  \
+         theme --kawaii         - Full kawaii mode (cuter UI)\n  \
+         theme --kawaii subtle  - Keep greetings, tips, and confetti; plain errors\n  \
+         theme --kawaii off     - No kawaii extras at all\n  \
+         theme --normal         - Same as --kawaii subtle\n  \
+         theme --vision deuteranopia - Red-green safe success/warning/error colors\n  \
+         theme --vision normal  - Back to the theme's own status colors"
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
@@ -33,24 +54,84 @@ impl Command for ThemeCommand {
         if let Some(first_arg) = args.first() {
             match first_arg.as_str() {
                 "--kawaii" | "-k" => {
-                    state.set_kawaii_mode(true);
-                    return Ok("Kawaii mode enabled! ♡(◕‿◕)♡ Everything is cuter now~".to_string());
+                    let level = match args.get(1) {
+                        Some(name) => KawaiiLevel::parse(name).ok_or_else(|| {
+                            anyhow!("unknown kawaii level '{}' (use off, subtle, or full)", name)
+                        })?,
+                        None => KawaiiLevel::Full,
+                    };
+                    state.set_kawaii_level(level);
+                    return Ok(match level {
+                        KawaiiLevel::Full => {
+                            "Kawaii mode enabled! ♡(◕‿◕)♡ Everything is cuter now~".to_string()
+                        }
+                        KawaiiLevel::Subtle => {
+                            "Kawaii level set to subtle: greetings, tips, and confetti only."
+                                .to_string()
+                        }
+                        KawaiiLevel::Off => "Kawaii extras turned off.".to_string(),
+                    });
+                }
+                "--vision" | "--colorblind" => {
+                    let mut config = Config::load();
+                    let Some(name) = args.get(1) else {
+                        return Ok(format!(
+                            "Color vision: {}\nOptions: normal, deuteranopia, protanopia, tritanopia",
+                            config.theme.color_vision.name()
+                        ));
+                    };
+                    let vision = ColorVision::parse(name).ok_or_else(|| {
+                        anyhow!(
+                            "unknown color vision '{}' (use normal, deuteranopia, protanopia, or tritanopia)",
+                            name
+                        )
+                    })?;
+                    config.theme.color_vision = vision;
+                    config.save()?;
+                    state.requested_color_vision = Some(vision);
+                    return Ok(match vision {
+                        ColorVision::Normal => "Using the theme's own status colors.".to_string(),
+                        vision => format!(
+                            "Status colors adjusted for {} - applies to every theme.",
+                            vision.name()
+                        ),
+                    });
                 }
                 "--normal" | "-n" => {
-                    state.set_kawaii_mode(false);
+                    state.set_kawaii_level(KawaiiLevel::Subtle);
                     return Ok("Kawaii mode disabled. Back to normal mode.".to_string());
                 }
                 _ => {}
             }
         }
 
+        if args.first().map(|s| s.as_str()) == Some("auto") {
+            return self.auto(&args[1..], state);
+        }
+
+        if args.first().map(|s| s.as_str()) == Some("edit") {
+            return self.edit(&args[1..], state);
+        }
+
         if args.is_empty() || args.first().map(|s| s.as_str()) == Some("list") {
             // List all themes with current theme highlighted
-            return Ok(self.list_themes(state.current_theme, state.kawaii_mode));
+            return Ok(self.list_themes(
+                state.current_theme,
+                state.current_user_theme.as_deref(),
+                state.kawaii_level,
+            ));
         }
 
         let theme_name = args.join(" ").to_lowercase();
 
+        // Theme files come first, so one can stand in for a built-in
+        if let Some(user) = UserTheme::find(&theme_name) {
+            let user = user?;
+            let message = format!("Switched to {} theme", user.name);
+            state.requested_user_theme = Some(user);
+            return Ok(message);
+        }
+
         // Try to match theme name
         if let Some(theme) = self.parse_theme_name(&theme_name) {
             state.requested_theme = Some(theme);
@@ -75,44 +156,171 @@ impl Command for ThemeCommand {
 }
 
 impl ThemeCommand {
-    fn list_themes(&self, current: ThemeName, kawaii_mode: bool) -> String {
-        let kawaii_status = if kawaii_mode { "on ♡" } else { "off" };
+    /// `theme edit <name>` - open a theme file in the editor, creating it
+    /// from the current theme (or the built-in of that name) if needed, and
+    /// switch to it so saved changes show up as they're made
+    fn edit(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let [name] = args else {
+            return Err(anyhow!("usage: theme edit <name>"));
+        };
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(anyhow!("invalid theme name '{}'", name));
+        }
+        let existing = UserTheme::all()
+            .into_iter()
+            .find(|(stem, _)| stem.eq_ignore_ascii_case(name))
+            .map(|(_, path)| path);
+        let path = match existing {
+            Some(path) => path,
+            None => {
+                let path = UserTheme::path_for(name);
+                let base = self.parse_theme_name(name).unwrap_or(state.current_theme);
+                std::fs::create_dir_all(UserTheme::dir())?;
+                std::fs::write(&path, user_theme_template(name, base))?;
+                path
+            }
+        };
+        // A file that doesn't parse yet still opens, to be fixed
+        state.requested_user_theme = UserTheme::load(&path).ok();
+        Ok(format!("\x1b[EDIT]{}", path.display()))
+    }
+
+    /// `theme auto ...` - configure and toggle automatic light/dark switching
+    fn auto(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut config = Config::load();
+        let auto = &mut config.theme;
+
+        let message = match args.first().map(|s| s.as_str()) {
+            None | Some("on") => {
+                auto.auto = true;
+                "Auto theme on".to_string()
+            }
+            Some("off") => {
+                auto.auto = false;
+                config.save()?;
+                state.requested_auto_theme = Some(false);
+                return Ok("Auto theme off - keeping the current theme.".to_string());
+            }
+            Some("status") => return Ok(self.auto_status(auto)),
+            Some(which @ ("light" | "dark")) => {
+                let name = args[1..].join(" ");
+                let theme = self
+                    .parse_theme_name(&name)
+                    .ok_or_else(|| anyhow!("unknown theme '{}' (see theme list)", name))?;
+                let key = Some(theme.config_key().to_string());
+                if which == "light" {
+                    auto.light = key;
+                } else {
+                    auto.dark = key;
+                }
+                auto.auto = true;
+                let which = if which == "light" { "Light" } else { "Dark" };
+                format!("{} theme set to {}", which, theme.display_name())
+            }
+            Some("hours") => match args.get(1).map(|s| s.as_str()) {
+                None => return Err(anyhow!("usage: theme auto hours <HH:MM-HH:MM|off>")),
+                Some("off") => {
+                    auto.light_hours = None;
+                    auto.auto = true;
+                    "Following the system light/dark setting".to_string()
+                }
+                Some(range) => {
+                    parse_hours(range)
+                        .ok_or_else(|| anyhow!("invalid hours '{}' (e.g. 07:00-19:00)", range))?;
+                    auto.light_hours = Some(range.to_string());
+                    auto.auto = true;
+                    format!("Light theme from {}", range.replace('-', " to "))
+                }
+            },
+            Some(other) => {
+                return Err(anyhow!(
+                    "unknown option '{}'. Usage: theme auto [on|off|light|dark|hours]",
+                    other
+                ))
+            }
+        };
+
+        config.save()?;
+        state.requested_auto_theme = Some(true);
+        Ok(format!("{}\n{}", message, self.auto_status(&config.theme)))
+    }
+
+    fn auto_status(&self, config: &ThemeConfig) -> String {
+        let name = |key: &Option<String>, default: ThemeName| {
+            key.as_deref()
+                .and_then(ThemeName::from_string)
+                .unwrap_or(default)
+                .display_name()
+        };
+        format!(
+            "Auto theme: {}\n  Light: {}\n  Dark:  {}\n  Switch: {}",
+            if config.auto { "on" } else { "off" },
+            name(&config.light, ThemeName::CatppuccinLatte),
+            name(&config.dark, ThemeName::CatppuccinMocha),
+            match &config.light_hours {
+                Some(hours) => format!("light during {}", hours),
+                None => "follows the system setting".to_string(),
+            }
+        )
+    }
+
+    fn list_themes(
+        &self,
+        current: ThemeName,
+        current_user: Option<&str>,
+        kawaii_level: KawaiiLevel,
+    ) -> String {
+        let kawaii_status = match kawaii_level {
+            KawaiiLevel::Full => "full ♡",
+            level => level.name(),
+        };
         let mut output = format!(
-            "Current theme: {} ✨  (kawaii mode: {})\n\n",
-            current.display_name(),
+            "Current theme: {} ✨  (kawaii: {})\n\n",
+            current_user.unwrap_or(current.display_name()),
             kawaii_status
         );
         output.push_str("Available themes:\n\n");
 
-        // Dark themes
-        output.push_str("Dark themes:\n");
-        for theme in ThemeName::all() {
-            if !theme.is_light() {
-                let marker = if *theme == current { " ◀" } else { "" };
-                output.push_str(&format!(
-                    "  {:20} {}{}\n",
-                    self.theme_to_arg(*theme),
-                    theme.display_name(),
-                    marker
-                ));
+        // Dark themes, then light ones
+        for (heading, light) in [("Dark themes:\n", false), ("\nLight themes:\n", true)] {
+            output.push_str(heading);
+            for theme in ThemeName::all() {
+                if theme.is_light() == light {
+                    let marker = if *theme == current && current_user.is_none() {
+                        " ◀"
+                    } else {
+                        ""
+                    };
+                    output.push_str(&format!(
+                        "  {:20} {}{}\n",
+                        self.theme_to_arg(*theme),
+                        theme.display_name(),
+                        marker
+                    ));
+                }
             }
         }
 
-        // Light themes
-        output.push_str("\nLight themes:\n");
-        for theme in ThemeName::all() {
-            if theme.is_light() {
-                let marker = if *theme == current { " ◀" } else { "" };
-                output.push_str(&format!(
-                    "  {:20} {}{}\n",
-                    self.theme_to_arg(*theme),
-                    theme.display_name(),
-                    marker
-                ));
+        // Theme files
+        let user_themes = UserTheme::all();
+        if !user_themes.is_empty() {
+            output.push_str("\nYour themes:\n");
+            for (name, path) in user_themes {
+                let about = match UserTheme::load(&path) {
+                    Ok(user) => format!("based on {}", user.base.display_name()),
+                    Err(e) => format!("⚠ {:#}", e),
+                };
+                let marker = if current_user == Some(name.as_str()) {
+                    " ◀"
+                } else {
+                    ""
+                };
+                output.push_str(&format!("  {:20} {}{}\n", name, about, marker));
             }
         }
 
-        output.push_str("\nUsage: theme <name>");
+        output.push_str("\nUsage: theme <name>  (or theme auto to follow the system)");
+        output.push_str("\n       theme edit <name> to make your own");
         output
     }
 
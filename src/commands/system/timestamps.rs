@@ -0,0 +1,78 @@
+//! timestamps command - show wall-clock times beside the output
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use crate::commands::traits::Command;
+use crate::config::settings::{Config, TimestampMode};
+use crate::terminal::buffer::is_valid_time_format;
+use crate::terminal::state::TerminalState;
+
+pub struct TimestampsCommand;
+
+impl Command for TimestampsCommand {
+    fn name(&self) -> &'static str {
+        "timestamps"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show wall-clock times beside blocks or lines"
+    }
+
+    fn usage(&self) -> &'static str {
+        "timestamps [off|blocks|lines] | timestamps format <strftime>\n\n\
+         blocks stamps each command with the time it started; lines also\n\
+         stamps every output line with the time it was received.\n\n\
+         Examples:\n  \
+         timestamps                    - Show the current mode and format\n  \
+         timestamps blocks             - Stamp each command block\n  \
+         timestamps lines              - Stamp every line (verbose)\n  \
+         timestamps format %H:%M:%S%.3f - Include milliseconds\n  \
+         timestamps off                - Hide the gutter"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let mut config = Config::load();
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("status") => Ok(format!(
+                "Timestamps: {}\n  Format: {} (now: {})",
+                config.terminal.timestamps.name(),
+                config.terminal.timestamp_format,
+                Local::now().format(&config.terminal.timestamp_format)
+            )),
+            Some("format") => {
+                if args.len() < 2 {
+                    return Err(anyhow!("usage: timestamps format <strftime>"));
+                }
+                let format = args[1..].join(" ");
+                if !is_valid_time_format(&format) {
+                    return Err(anyhow!("invalid time format '{}'", format));
+                }
+                config.terminal.timestamp_format = format;
+                config.save()?;
+                state.requested_timestamps = Some(config.terminal.timestamps);
+                Ok(format!(
+                    "Timestamp format set - now: {}",
+                    Local::now().format(&config.terminal.timestamp_format)
+                ))
+            }
+            Some(name) => {
+                let mode = TimestampMode::parse(name).ok_or_else(|| {
+                    anyhow!(
+                        "unknown mode '{}'. Usage: timestamps [off|blocks|lines|format]",
+                        name
+                    )
+                })?;
+                config.terminal.timestamps = mode;
+                config.save()?;
+                state.requested_timestamps = Some(mode);
+                Ok(match mode {
+                    TimestampMode::Off => "Timestamps off.".to_string(),
+                    TimestampMode::Blocks => "Timestamps on for each command block.".to_string(),
+                    TimestampMode::Lines => "Timestamps on for every output line.".to_string(),
+                })
+            }
+        }
+    }
+}
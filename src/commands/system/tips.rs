@@ -0,0 +1,97 @@
+//! tips command - manage contextual tips
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::config::settings::Config;
+use crate::terminal::state::{TerminalState, TipsRequest};
+use crate::terminal::tips::{find_tip, TipsState, TIPS};
+
+pub struct TipsCommand;
+
+impl Command for TipsCommand {
+    fn name(&self) -> &'static str {
+        "tips"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show, dismiss, or turn off tips"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tips [list|dismiss|on|off|reset]\n\n\
+         Examples:\n  \
+         tips          - List tips and which ones are still pending\n  \
+         tips dismiss  - Never show the last tip again\n  \
+         tips off      - Turn tips off entirely\n  \
+         tips on       - Turn tips back on\n  \
+         tips reset    - Bring back dismissed tips"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list") => Ok(list_tips(state.kawaii.tips)),
+            Some("dismiss") => {
+                let saved = TipsState::load();
+                let tip = saved
+                    .last_tip
+                    .as_deref()
+                    .and_then(find_tip)
+                    .ok_or_else(|| anyhow!("no tip has been shown yet"))?;
+                state.requested_tips = Some(TipsRequest::Dismiss);
+                Ok(format!(
+                    "Got it - won't suggest this again:\n  {}",
+                    tip.text
+                ))
+            }
+            Some("reset") => {
+                state.requested_tips = Some(TipsRequest::Reset);
+                Ok("Dismissed tips will show up again.".to_string())
+            }
+            Some(toggle @ ("on" | "off")) => {
+                let enabled = toggle == "on";
+                let mut config = Config::load();
+                config.kawaii.tips = Some(enabled);
+                config.save()?;
+                state.kawaii = config.kawaii.features(state.kawaii_level);
+                Ok(if enabled {
+                    "Tips turned on~ (◕‿◕)".to_string()
+                } else {
+                    "Tips turned off.".to_string()
+                })
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: tips [list|dismiss|on|off|reset]",
+                other
+            )),
+        }
+    }
+}
+
+fn list_tips(enabled: bool) -> String {
+    let saved = TipsState::load();
+    let mut output = format!(
+        "Tips are {}\n\n",
+        if enabled {
+            "on"
+        } else {
+            "off (tips on to enable)"
+        }
+    );
+
+    for tip in TIPS {
+        let status = if saved.used.contains(tip.feature) {
+            "✓ used     "
+        } else if saved.dismissed.contains(tip.id) {
+            "✗ dismissed"
+        } else if saved.is_pending(tip) {
+            "· pending  "
+        } else {
+            "· seen     "
+        };
+        output.push_str(&format!("  {} {}\n", status, tip.text));
+    }
+
+    output.push_str("\nTips only suggest features you haven't used yet.");
+    output
+}
@@ -1,7 +1,5 @@
 //! which command - find command location
 
-use std::env;
-
 use anyhow::Result;
 
 use crate::commands::registry::CommandRegistry;
@@ -23,7 +21,7 @@ impl Command for WhichCommand {
         "which <command>"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: which <command>"));
         }
@@ -37,16 +35,13 @@ impl Command for WhichCommand {
         }
 
         // Check git shortcuts
-        let git_shortcuts = [
-            "gs", "gd", "gl", "gp", "gpl", "ga", "gc", "gco", "gb", "gba", "gcb", "grh", "gst",
-            "gstp",
-        ];
+        let git_shortcuts = ["gco", "gb"];
         if git_shortcuts.contains(&command.as_str()) {
             return Ok(format!("{}: zaxiom git shortcut", command));
         }
 
         // Search in PATH
-        if let Ok(path_var) = env::var("PATH") {
+        if let Some(path_var) = state.var("PATH") {
             let paths: Vec<&str> = path_var.split(';').collect();
 
             for path in paths {
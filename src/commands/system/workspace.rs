@@ -0,0 +1,158 @@
+//! workspace command - save and open named sets of tabs, panes, and directories
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::session::{saved_time, SessionManager, Workspace};
+use crate::terminal::state::{TerminalState, WorkspaceRequest};
+
+pub struct WorkspaceCommand;
+
+impl Command for WorkspaceCommand {
+    fn name(&self) -> &'static str {
+        "workspace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save and open workspaces of tabs, panes, and directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "workspace [list|save <name>|load <name>|delete <name>]\n\n\
+         A workspace remembers every tab of the window: its split layout and,\n\
+         for each pane, the directory, the name, and the command still\n\
+         running in it, which runs again when the workspace is loaded.\n\
+         Workspaces are JSON files in the data directory's workspaces folder;\n\
+         add more commands to a pane's \"commands\" list by hand.\n\n\
+         Examples:\n  \
+         workspace                 - List saved workspaces\n  \
+         workspace save rust-dev   - Save the current tabs as 'rust-dev'\n  \
+         workspace load rust-dev   - Open its tabs next to the current ones\n  \
+         workspace delete ops      - Remove a saved workspace"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let sessions = SessionManager::new();
+        let name = || {
+            args.get(1)
+                .map(|s| s.as_str())
+                .ok_or_else(|| anyhow!("usage: workspace {} <name>", args[0]))
+        };
+
+        match args.first().map(|s| s.as_str()) {
+            None | Some("list") | Some("ls") => Ok(list(&sessions.list_workspaces())),
+            Some("save") => {
+                let name = name()?;
+                state.requested_workspace = Some(WorkspaceRequest::Save(name.to_string()));
+                Ok(format!("Saved workspace '{}'", name))
+            }
+            Some("load") | Some("open") => {
+                let workspace = sessions.load_workspace(name()?)?;
+                if workspace.tabs.is_empty() {
+                    return Err(anyhow!("workspace '{}' has no tabs", workspace.name));
+                }
+                let message = format!(
+                    "Opening workspace '{}' ({})",
+                    workspace.name,
+                    summary(&workspace)
+                );
+                state.requested_workspace = Some(WorkspaceRequest::Open(workspace));
+                Ok(message)
+            }
+            Some("delete") | Some("rm") => {
+                let name = name()?;
+                if !sessions.delete_workspace(name)? {
+                    return Err(anyhow!("no workspace named '{}'", name));
+                }
+                Ok(format!("Deleted workspace '{}'", name))
+            }
+            Some(other) => Err(anyhow!(
+                "unknown subcommand '{}'. Usage: workspace [list|save|load|delete] <name>",
+                other
+            )),
+        }
+    }
+}
+
+/// "2 tabs, 3 panes"
+fn summary(workspace: &Workspace) -> String {
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    let tabs = workspace.tabs.len();
+    let panes = workspace.pane_count();
+    format!(
+        "{} tab{}, {} pane{}",
+        tabs,
+        plural(tabs),
+        panes,
+        plural(panes)
+    )
+}
+
+fn list(workspaces: &[Workspace]) -> String {
+    if workspaces.is_empty() {
+        return "No saved workspaces. Save the current tabs with: workspace save <name>"
+            .to_string();
+    }
+    let mut output = String::from("Saved workspaces:\n\n");
+    for workspace in workspaces {
+        let titles: Vec<&str> = workspace.tabs.iter().map(|t| t.title.as_str()).collect();
+        output.push_str(&format!(
+            "  {:16} {:18} {:16} {}\n",
+            workspace.name,
+            summary(workspace),
+            saved_time(workspace.saved_at),
+            titles.join(", ")
+        ));
+    }
+    output.push_str("\nUsage: workspace load <name>  |  workspace save <name>");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+    use crate::terminal::session::{WorkspacePane, WorkspaceTab};
+
+    #[test]
+    fn test_workspace() {
+        let mut state = MockTerminalState::new().build();
+        let workspace = |args: &[&str], state: &mut TerminalState| {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            WorkspaceCommand.execute(&args, state)
+        };
+        assert!(workspace(&["save"], &mut state).is_err());
+        assert!(workspace(&["frobnicate"], &mut state).is_err());
+        assert!(workspace(&["load", "no-such-workspace-here"], &mut state).is_err());
+        assert!(state.requested_workspace.is_none());
+
+        workspace(&["save", "rust-dev"], &mut state).unwrap();
+        assert!(matches!(
+            state.requested_workspace,
+            Some(WorkspaceRequest::Save(ref name)) if name == "rust-dev"
+        ));
+
+        let mut saved = Workspace::new("ops");
+        saved.tabs.push(WorkspaceTab {
+            title: "logs".to_string(),
+            layout: "h(pane, pane)".to_string(),
+            panes: vec![
+                WorkspacePane {
+                    cwd: "/var/log".into(),
+                    name: None,
+                    commands: vec!["tail -f syslog".to_string()],
+                },
+                WorkspacePane {
+                    cwd: "/etc".into(),
+                    name: None,
+                    commands: Vec::new(),
+                },
+            ],
+            focused: 0,
+        });
+        assert_eq!(summary(&saved), "1 tab, 2 panes");
+        let listing = list(&[saved]);
+        assert!(listing.contains("ops"));
+        assert!(listing.contains("logs"));
+    }
+}
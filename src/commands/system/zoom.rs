@@ -0,0 +1,87 @@
+//! zoom command - make the current pane's text larger or smaller
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::traits::Command;
+use crate::terminal::state::{TerminalState, ZoomRequest, MAX_FONT_SIZE, MIN_FONT_SIZE};
+
+pub struct ZoomCommand;
+
+impl Command for ZoomCommand {
+    fn name(&self) -> &'static str {
+        "zoom"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the pane's text size"
+    }
+
+    fn usage(&self) -> &'static str {
+        "zoom [in|out|reset|<size>]\n\n\
+         Changes the text size of the current pane only. The new size is\n\
+         saved as [font] size, so new panes and the next launch use it.\n\
+         Ctrl+= and Ctrl+- zoom too, and Ctrl+0 resets.\n\n\
+         Examples:\n  \
+         zoom        - Show the current size\n  \
+         zoom in     - One point larger\n  \
+         zoom out    - One point smaller\n  \
+         zoom 20     - 20 point text\n  \
+         zoom reset  - Back to the default size"
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let request = match args.first().map(|s| s.as_str()) {
+            None => return Ok(format!("Text size: {}pt", state.font_size)),
+            Some("in" | "+") => ZoomRequest::In,
+            Some("out" | "-") => ZoomRequest::Out,
+            Some("reset") => ZoomRequest::Reset,
+            Some(size) => match size.parse::<f32>() {
+                Ok(size) if (MIN_FONT_SIZE..=MAX_FONT_SIZE).contains(&size) => {
+                    ZoomRequest::Size(size)
+                }
+                Ok(_) => {
+                    return Err(anyhow!(
+                        "size must be between {} and {} points",
+                        MIN_FONT_SIZE,
+                        MAX_FONT_SIZE
+                    ))
+                }
+                Err(_) => {
+                    return Err(anyhow!(
+                        "unknown option '{}'. Usage: zoom [in|out|reset|<size>]",
+                        size
+                    ))
+                }
+            },
+        };
+        let size = request.apply(state.font_size);
+        state.requested_zoom = Some(request);
+        Ok(format!("Text size: {}pt", size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::MockTerminalState;
+
+    #[test]
+    fn test_zoom() {
+        let mut state = MockTerminalState::new().build();
+        state.font_size = 16.0;
+        let zoom = |args: &[&str], state: &mut TerminalState| {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            ZoomCommand.execute(&args, state)
+        };
+
+        assert_eq!(zoom(&[], &mut state).unwrap(), "Text size: 16pt");
+        assert_eq!(zoom(&["in"], &mut state).unwrap(), "Text size: 17pt");
+        assert_eq!(state.requested_zoom, Some(ZoomRequest::In));
+        assert_eq!(zoom(&["12.5"], &mut state).unwrap(), "Text size: 12.5pt");
+        assert!(zoom(&["200"], &mut state).is_err());
+        assert!(zoom(&["huge"], &mut state).is_err());
+
+        assert_eq!(ZoomRequest::Out.apply(MIN_FONT_SIZE), MIN_FONT_SIZE);
+        assert_eq!(ZoomRequest::Reset.apply(30.0), 16.0);
+    }
+}
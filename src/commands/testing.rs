@@ -0,0 +1,184 @@
+//! Test helpers for built-in commands
+//!
+//! - `Fixture`: a throwaway directory with files, removed when dropped
+//! - `MockTerminalState`: builds a `TerminalState` rooted in a fixture
+//! - `run` / `run_stdin`: call a command with `&str` arguments
+//! - `assert_golden`: compare output with `tests/golden/<name>.txt`
+//!
+//! Golden files are (re)written instead of compared when `UPDATE_GOLDEN=1`
+//! is set, so a deliberate output change is one `cargo test` away:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test commands::
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+
+use super::traits::Command;
+use crate::terminal::state::TerminalState;
+
+/// Placeholder for the fixture directory in golden output
+pub const FIXTURE_PLACEHOLDER: &str = "$FIXTURE";
+
+/// A temporary directory, unique per fixture, deleted on drop
+pub struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    /// Create an empty directory named after the test
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "zaxiom-test-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).expect("create fixture directory");
+        Self { root }
+    }
+
+    /// Write a file (parent directories are created)
+    pub fn file(self, path: &str, contents: &str) -> Self {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create fixture subdirectory");
+        }
+        std::fs::write(path, contents).expect("write fixture file");
+        self
+    }
+
+    /// Create an empty directory
+    pub fn dir(self, path: &str) -> Self {
+        std::fs::create_dir_all(self.root.join(path)).expect("create fixture subdirectory");
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Read a file back, e.g. after a command wrote it
+    pub fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.root.join(path)).unwrap_or_default()
+    }
+
+    /// Replace the fixture's location (or bare name) in command output with
+    /// `$FIXTURE`, and `\` with `/`, so golden files are the same on every machine
+    pub fn scrub(&self, output: &str) -> String {
+        let root = self.root.display().to_string();
+        let name = self
+            .root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        output
+            .replace(&root, FIXTURE_PLACEHOLDER)
+            .replace(&name, FIXTURE_PLACEHOLDER)
+            .replace('\\', "/")
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Builder for a `TerminalState` set up for a test
+#[derive(Default)]
+pub struct MockTerminalState {
+    cwd: Option<PathBuf>,
+    history: Vec<String>,
+    aliases: Vec<(String, String)>,
+}
+
+impl MockTerminalState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start in `dir` instead of the home directory
+    pub fn cwd(mut self, dir: &Path) -> Self {
+        self.cwd = Some(dir.to_path_buf());
+        self
+    }
+
+    /// Commands shared with `history` and `fc`, oldest first
+    pub fn history(mut self, commands: &[&str]) -> Self {
+        self.history = commands.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn alias(mut self, name: &str, value: &str) -> Self {
+        self.aliases.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn build(self) -> TerminalState {
+        let mut state = TerminalState::new();
+        if let Some(cwd) = self.cwd {
+            state.set_cwd(cwd);
+        }
+        state.history = self.history;
+        for (name, value) in self.aliases {
+            state.set_alias(name, value);
+        }
+        state
+    }
+}
+
+/// Run a command with string arguments
+pub fn run(command: &dyn Command, args: &[&str], state: &mut TerminalState) -> Result<String> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    command.execute(&args, state)
+}
+
+/// Run a command with string arguments and piped input
+pub fn run_stdin(
+    command: &dyn Command,
+    args: &[&str],
+    stdin: &str,
+    state: &mut TerminalState,
+) -> Result<String> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    command.execute_with_stdin(&args, Some(stdin), state)
+}
+
+/// Compare `actual` with the golden file `tests/golden/<name>.txt`, or write
+/// it when `UPDATE_GOLDEN` is set. Line endings are normalized.
+#[track_caller]
+pub fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.txt", name));
+    let actual = actual.replace("\r\n", "\n");
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).expect("create golden directory");
+        std::fs::write(&path, &actual).expect("write golden file");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| {
+            panic!(
+                "missing golden file {} (run with UPDATE_GOLDEN=1 to create it)",
+                path.display()
+            )
+        })
+        .replace("\r\n", "\n");
+    assert!(
+        expected == actual,
+        "output differs from {} (run with UPDATE_GOLDEN=1 to accept it)\n--- expected\n{}\n--- actual\n{}",
+        path.display(),
+        expected,
+        actual
+    );
+}
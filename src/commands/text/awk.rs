@@ -109,11 +109,9 @@ RELATED COMMANDS:
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-F" => {
-                    if i + 1 < args.len() {
-                        field_separator = args[i + 1].clone();
-                        i += 1;
-                    }
+                "-F" if i + 1 < args.len() => {
+                    field_separator = args[i + 1].clone();
+                    i += 1;
                 }
                 "-h" | "--help" => {
                     return Ok("Usage: awk [OPTIONS] 'program' [file]\n\
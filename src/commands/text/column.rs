@@ -42,11 +42,9 @@ impl Command for ColumnCommand {
         while i < args.len() {
             match args[i].as_str() {
                 "-t" => table_mode = true,
-                "-s" => {
-                    if i + 1 < args.len() {
-                        separator = &args[i + 1];
-                        i += 1;
-                    }
+                "-s" if i + 1 < args.len() => {
+                    separator = &args[i + 1];
+                    i += 1;
                 }
                 arg if !arg.starts_with('-') => files.push(arg),
                 _ => {}
@@ -18,7 +18,7 @@ impl Command for CutCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "cut -d <delim> -f <fields> <file>"
+        "cut -d <delim> -f <fields> [file]"
     }
 
     fn extended_help(&self) -> String {
@@ -90,6 +90,15 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
         let mut delimiter = '\t';
         let mut fields: Option<String> = None;
         let mut chars: Option<String> = None;
@@ -98,26 +107,20 @@ RELATED COMMANDS:
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-d" | "--delimiter" => {
-                    if i + 1 < args.len() {
-                        delimiter = args[i + 1].chars().next().unwrap_or('\t');
-                        i += 1;
-                    }
+                "-d" | "--delimiter" if i + 1 < args.len() => {
+                    delimiter = args[i + 1].chars().next().unwrap_or('\t');
+                    i += 1;
                 }
-                "-f" | "--fields" => {
-                    if i + 1 < args.len() {
-                        fields = Some(args[i + 1].clone());
-                        i += 1;
-                    }
+                "-f" | "--fields" if i + 1 < args.len() => {
+                    fields = Some(args[i + 1].clone());
+                    i += 1;
                 }
-                "-c" | "--characters" => {
-                    if i + 1 < args.len() {
-                        chars = Some(args[i + 1].clone());
-                        i += 1;
-                    }
+                "-c" | "--characters" if i + 1 < args.len() => {
+                    chars = Some(args[i + 1].clone());
+                    i += 1;
                 }
                 "-h" | "--help" => {
-                    return Ok("Usage: cut [OPTIONS] <file>\n\
+                    return Ok("Usage: cut [OPTIONS] [file]\n\
                         Options:\n  \
                         -d <char>    Use <char> as delimiter\n  \
                         -f <list>    Select only these fields\n  \
@@ -130,10 +133,14 @@ RELATED COMMANDS:
             i += 1;
         }
 
-        let file = file.ok_or_else(|| anyhow::anyhow!("cut: missing file operand"))?;
-        let path = state.resolve_path(file);
-        let content =
-            fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("cut: {}: {}", file, e))?;
+        let content = match (file, stdin) {
+            (Some(file), _) => {
+                let path = state.resolve_path(file);
+                fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("cut: {}: {}", file, e))?
+            }
+            (None, Some(input)) => input.to_string(),
+            (None, None) => return Err(anyhow::anyhow!("cut: missing file operand")),
+        };
 
         let mut result: Vec<String> = Vec::new();
 
@@ -162,6 +169,10 @@ RELATED COMMANDS:
 
         Ok(result.join("\n"))
     }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
 }
 
 fn parse_range(spec: &str) -> Result<Vec<usize>> {
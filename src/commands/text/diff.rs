@@ -183,3 +183,19 @@ RELATED COMMANDS:
         Ok(result.join("\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_diff_golden() {
+        let fixture = Fixture::new("diff")
+            .file("old.txt", "alpha\nbeta\ngamma\n")
+            .file("new.txt", "alpha\nbeta two\ngamma\ndelta\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(&DiffCommand, &["old.txt", "new.txt"], &mut state).unwrap();
+        assert_golden("diff", &fixture.scrub(&output));
+    }
+}
@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 
+use super::printf_cmd::{unescape, Escapes};
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
@@ -17,24 +18,31 @@ impl Command for EchoCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "echo [text...]"
+        "echo [-neE] [text...]"
     }
 
     fn extended_help(&self) -> String {
         r#"echo - Print text to output
 
 USAGE:
-  echo [text...]
+  echo [-neE] [text...]
 
 DESCRIPTION:
   Display a line of text. Simple but powerful when
   combined with pipes and redirections.
 
+OPTIONS:
+  -n    Don't end the output with a newline
+  -e    Interpret backslash escapes (\n \t \\ \xHH \0NNN,
+        \c to stop output)
+  -E    Print backslashes as written (default)
+
 EXAMPLES:
   echo Hello World            Print "Hello World"
   echo "Hello World"          Same, with quotes
   echo $HOME                  Print environment variable
-  echo "Line 1\nLine 2"       Print with newline
+  echo -e "Line 1\nLine 2"    Print with newline
+  echo -n "no newline" > f    Write without a final newline
   echo one two three          Print with spaces
 
 WITH REDIRECTION:
@@ -57,7 +65,56 @@ RELATED COMMANDS:
         .to_string()
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
-        Ok(args.join(" "))
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        // Leading words made only of n, e, and E are options, as in bash
+        let mut newline = true;
+        let mut escapes = false;
+        let mut words = args;
+        while let Some(flags) = words.first().and_then(|w| w.strip_prefix('-')) {
+            if flags.is_empty() || !flags.chars().all(|c| matches!(c, 'n' | 'e' | 'E')) {
+                break;
+            }
+            for flag in flags.chars() {
+                match flag {
+                    'n' => newline = false,
+                    'e' => escapes = true,
+                    _ => escapes = false,
+                }
+            }
+            words = &words[1..];
+        }
+
+        let text = words.join(" ");
+        let (text, stopped) = if escapes {
+            unescape(&text, Escapes::Echo)
+        } else {
+            (text, false)
+        };
+        if !newline || stopped {
+            state.omit_newline = true;
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, MockTerminalState};
+
+    #[test]
+    fn test_echo_options() {
+        let mut state = MockTerminalState::new().build();
+        let mut echo = |args: &[&str]| run(&EchoCommand, args, &mut state).unwrap();
+        assert_eq!(echo(&["a\\tb", "c"]), "a\\tb c");
+        assert_eq!(echo(&["-e", "a\\tb\\x41\\0101"]), "a\tbAA");
+        assert_eq!(echo(&["-eE", "a\\tb"]), "a\\tb");
+        assert_eq!(echo(&["-e", "one\\ctwo", "three"]), "one");
+        assert_eq!(echo(&["-x", "-n"]), "-x -n");
+        assert_eq!(echo(&["-"]), "-");
+
+        let mut state = MockTerminalState::new().build();
+        assert_eq!(run(&EchoCommand, &["-n", "hi"], &mut state).unwrap(), "hi");
+        assert!(state.omit_newline);
     }
 }
@@ -32,23 +32,17 @@ impl Command for JoinCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-1" => {
-                    if i + 1 < args.len() {
-                        field1 = args[i + 1].parse().unwrap_or(1);
-                        i += 1;
-                    }
+                "-1" if i + 1 < args.len() => {
+                    field1 = args[i + 1].parse().unwrap_or(1);
+                    i += 1;
                 }
-                "-2" => {
-                    if i + 1 < args.len() {
-                        field2 = args[i + 1].parse().unwrap_or(1);
-                        i += 1;
-                    }
+                "-2" if i + 1 < args.len() => {
+                    field2 = args[i + 1].parse().unwrap_or(1);
+                    i += 1;
                 }
-                "-t" => {
-                    if i + 1 < args.len() {
-                        separator = args[i + 1].chars().next().unwrap_or(' ');
-                        i += 1;
-                    }
+                "-t" if i + 1 < args.len() => {
+                    separator = args[i + 1].chars().next().unwrap_or(' ');
+                    i += 1;
                 }
                 arg if !arg.starts_with('-') => files.push(arg),
                 _ => {}
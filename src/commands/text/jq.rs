@@ -0,0 +1,1905 @@
+//! jq command - query and pretty-print JSON
+//!
+//! A subset of jq's filter language over serde_json values: paths
+//! (`.a.b`, `.[0]`, `.[2:4]`, `.[]`, `..`), pipes and commas, array and
+//! object construction, arithmetic, comparisons, `and`/`or`/`//`,
+//! `if`/`try`, the common builtins, and `@csv`/`@tsv` formats. Results
+//! print indented and in the theme's colors, like jq's.
+
+use std::cmp::Ordering;
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use eframe::egui::Color32;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::commands::traits::Command;
+use crate::config::theme::Theme;
+use crate::terminal::state::TerminalState;
+
+const RESET: &str = "\x1b[0m";
+
+pub struct JqCommand;
+
+impl Command for JqCommand {
+    fn name(&self) -> &'static str {
+        "jq"
+    }
+
+    fn description(&self) -> &'static str {
+        "Query and pretty-print JSON"
+    }
+
+    fn usage(&self) -> &'static str {
+        "jq [-r] [-c] [-s] [-n] [-S] [filter] [file...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"jq - Query and pretty-print JSON
+
+USAGE:
+  jq [OPTIONS] [filter] [file...]
+  command | jq [OPTIONS] [filter]
+
+OPTIONS:
+  -r, --raw-output      Print strings without quotes
+  -c, --compact-output  One line per result
+  -s, --slurp           Read all inputs into one array
+  -n, --null-input      Run the filter once on null, reading nothing
+  -S, --sort-keys       Sort object keys
+  --tab                 Indent with tabs
+  -C, --color-output    Colors even when piped
+  -M, --monochrome-output  No colors
+
+FILTERS:
+  .                     The input, pretty-printed
+  .name  ."a b"  .[k]   Field of an object (null when missing)
+  .[0]  .[-1]  .[2:4]   Array element or slice (strings slice too)
+  .[]                   Every element or value
+  ..                    The input and everything inside it
+  a | b                 Feed each result of a to b
+  a, b                  Results of a, then of b
+  [a]  {k: a, name}     Collect into an array / build an object
+  ?  try a              Drop errors
+  a // b                a's truthy results, or else b's
+  + - * / %             Arithmetic (+ also joins strings, arrays, objects)
+  == != < <= > >=       Comparisons; and, or, not
+  if c then a elif d then b else e end
+
+BUILTINS:
+  length keys values type empty not add first last reverse sort unique
+  min max flatten to_entries from_entries tostring tonumber tojson
+  fromjson ascii_downcase ascii_upcase floor ceil round any all
+  numbers strings booleans nulls arrays objects iterables scalars
+  map(f) select(f) has(k) sort_by(f) group_by(f) unique_by(f)
+  min_by(f) max_by(f) map_values(f) with_entries(f) join(s) split(s)
+  test(re) contains(x) startswith(s) endswith(s) ltrimstr(s)
+  rtrimstr(s) first(f) last(f) limit(n; f) range(n) range(a; b)
+  @csv @tsv @json @text
+
+EXAMPLES:
+  curl -s api.github.com/users/octocat | jq
+  jq .name package.json                  A field
+  jq '.items[] | .name' data.json        Every item's name
+  jq -r '.[] | select(.age > 30) | .name' people.json
+  jq '[.items[] | {id, title}]' data.json
+  jq 'map(.size) | add' files.json       Sum a field
+  jq -r '.[] | [.name, .email] | @csv' users.json
+
+RELATED COMMANDS:
+  curl     Fetch JSON from an API
+  grep     Search text
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
+        let mut raw = false;
+        let mut compact = false;
+        let mut slurp = false;
+        let mut null_input = false;
+        let mut sort_keys = false;
+        let mut tab = false;
+        let mut color = None;
+        let mut positional = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "--raw-output" => raw = true,
+                "--compact-output" => compact = true,
+                "--slurp" => slurp = true,
+                "--null-input" => null_input = true,
+                "--sort-keys" => sort_keys = true,
+                "--tab" => tab = true,
+                "--color-output" => color = Some(true),
+                "--monochrome-output" => color = Some(false),
+                _ if arg.starts_with("--") => {
+                    return Err(anyhow!("jq: unknown option '{}'", arg));
+                }
+                // Short flags combine: -rc
+                _ if arg.len() > 1
+                    && arg.starts_with('-')
+                    && arg[1..].chars().all(|c| "rcsnSCM".contains(c)) =>
+                {
+                    for flag in arg[1..].chars() {
+                        match flag {
+                            'r' => raw = true,
+                            'c' => compact = true,
+                            's' => slurp = true,
+                            'n' => null_input = true,
+                            'S' => sort_keys = true,
+                            'C' => color = Some(true),
+                            _ => color = Some(false),
+                        }
+                    }
+                }
+                _ => positional.push(arg.as_str()),
+            }
+        }
+
+        let (program, files) = match positional.split_first() {
+            Some((program, files)) => (*program, files),
+            None => (".", &[][..]),
+        };
+        let filter = parse(program)?;
+
+        let inputs = if null_input {
+            vec![Value::Null]
+        } else {
+            let text = if files.is_empty() {
+                stdin
+                    .ok_or_else(|| anyhow!("jq: no input (pipe JSON in or name a file)"))?
+                    .to_string()
+            } else {
+                let mut text = String::new();
+                for file in files {
+                    let path = state.resolve_path(file);
+                    let contents =
+                        fs::read_to_string(&path).map_err(|e| anyhow!("jq: {}: {}", file, e))?;
+                    text.push_str(&contents);
+                    text.push('\n');
+                }
+                text
+            };
+            let values = serde_json::Deserializer::from_str(&text)
+                .into_iter::<Value>()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("jq: invalid JSON input: {}", e))?;
+            if slurp {
+                vec![Value::Array(values)]
+            } else {
+                values
+            }
+        };
+
+        let colors = color
+            .unwrap_or(!state.output_piped)
+            .then(|| Colors::new(&Theme::from_name(state.current_theme)));
+        let printer = Printer {
+            indent: match (compact, tab) {
+                (true, _) => None,
+                (false, true) => Some("\t"),
+                (false, false) => Some("  "),
+            },
+            sort_keys,
+            colors,
+        };
+
+        let mut lines = Vec::new();
+        for input in &inputs {
+            for value in filter.eval(input)? {
+                match value {
+                    Value::String(text) if raw => lines.push(text),
+                    value => lines.push(printer.print(&value)),
+                }
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+// --- Filter language ------------------------------------------------------
+
+/// A parsed filter
+#[derive(Debug, Clone)]
+enum Filter {
+    Identity,
+    /// `..`
+    Recurse,
+    Literal(Value),
+    /// `.[key]` of each result of the target; the key is computed from the
+    /// same input as the target
+    Index(Box<Filter>, Box<Filter>),
+    /// `.[from:to]`
+    Slice(Box<Filter>, Option<Box<Filter>>, Option<Box<Filter>>),
+    /// `.[]`
+    Iterate(Box<Filter>),
+    /// `f?` or `try f`
+    Try(Box<Filter>),
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    Negate(Box<Filter>),
+    Binary(Op, Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    /// `a // b`
+    Alternative(Box<Filter>, Box<Filter>),
+    /// `[f]`
+    Array(Option<Box<Filter>>),
+    /// `{key: value, ...}`
+    Object(Vec<(Filter, Filter)>),
+    If(Box<Filter>, Box<Filter>, Option<Box<Filter>>),
+    Call(String, Vec<Filter>),
+    /// `@csv` and friends
+    Format(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    /// `.name` or `."name"`
+    Field(String),
+    Ident(String),
+    Format(String),
+    Number(f64),
+    Str(String),
+    Punct(&'static str),
+}
+
+const PUNCTUATION: &[&str] = &[
+    "==", "!=", "<=", ">=", "//", "|", ",", "(", ")", "[", "]", "{", "}", ":", ";", "?", "+", "-",
+    "*", "/", "%", "<", ">",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let ident = |i: &mut usize| {
+        let start = *i;
+        while *i < chars.len() && (chars[*i].is_alphanumeric() || chars[*i] == '_') {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>()
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '.' {
+            i += 1;
+            match chars.get(i) {
+                Some('.') => {
+                    i += 1;
+                    tokens.push(Token::DotDot);
+                }
+                Some('"') => tokens.push(Token::Field(string(&chars, &mut i)?)),
+                Some(&next) if next.is_alphabetic() || next == '_' => {
+                    tokens.push(Token::Field(ident(&mut i)))
+                }
+                _ => tokens.push(Token::Dot),
+            }
+        } else if c == '"' {
+            tokens.push(Token::Str(string(&chars, &mut i)?));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || matches!(chars[i], 'e' | 'E')
+                    || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| anyhow!("jq: error: invalid number {}", text))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '_' {
+            tokens.push(Token::Ident(ident(&mut i)));
+        } else if c == '@' {
+            i += 1;
+            tokens.push(Token::Format(ident(&mut i)));
+        } else if c == '$' {
+            return Err(anyhow!("jq: error: variables ($name) are not supported"));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let punct = PUNCTUATION
+                .iter()
+                .find(|p| rest.starts_with(**p))
+                .ok_or_else(|| anyhow!("jq: error: unexpected '{}'", c))?;
+            i += punct.len();
+            tokens.push(Token::Punct(punct));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A JSON-style string literal starting at `chars[*i]` (the opening quote)
+fn string(chars: &[char], i: &mut usize) -> Result<String> {
+    let mut text = String::new();
+    *i += 1;
+    loop {
+        let c = *chars
+            .get(*i)
+            .ok_or_else(|| anyhow!("jq: error: unterminated string"))?;
+        *i += 1;
+        match c {
+            '"' => return Ok(text),
+            '\\' => {
+                let escaped = *chars
+                    .get(*i)
+                    .ok_or_else(|| anyhow!("jq: error: unterminated string"))?;
+                *i += 1;
+                match escaped {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    'r' => text.push('\r'),
+                    'b' => text.push('\x08'),
+                    'f' => text.push('\x0c'),
+                    'u' => {
+                        let hex: String = chars.iter().skip(*i).take(4).collect();
+                        let code = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| anyhow!("jq: error: invalid escape \\u{}", hex))?;
+                        text.push(code);
+                        *i += 4;
+                    }
+                    '(' => {
+                        return Err(anyhow!(
+                            "jq: error: string interpolation \\(...) is not supported"
+                        ))
+                    }
+                    other => text.push(other),
+                }
+            }
+            other => text.push(other),
+        }
+    }
+}
+
+/// Parse a jq program
+fn parse(source: &str) -> Result<Filter> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Ok(Filter::Identity);
+    }
+    let filter = parser.pipe()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(token) => Err(anyhow!("jq: error: unexpected {}", describe_token(token))),
+    }
+}
+
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Dot => "'.'".to_string(),
+        Token::DotDot => "'..'".to_string(),
+        Token::Field(name) => format!("'.{}'", name),
+        Token::Ident(name) => format!("'{}'", name),
+        Token::Format(name) => format!("'@{}'", name),
+        Token::Number(n) => format!("'{}'", n),
+        Token::Str(s) => format!("\"{}\"", s),
+        Token::Punct(p) => format!("'{}'", p),
+    }
+}
+
+/// Recursive-descent parser, loosest binding first
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(name)) if name == keyword)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = self.at(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<()> {
+        if self.eat(punct) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => Err(anyhow!(
+                "jq: error: expected '{}' but found {}",
+                punct,
+                describe_token(token)
+            )),
+            None => Err(anyhow!("jq: error: expected '{}' at the end", punct)),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.at_keyword(keyword) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(anyhow!("jq: error: expected '{}'", keyword))
+        }
+    }
+
+    /// `a | b`
+    fn pipe(&mut self) -> Result<Filter> {
+        let left = self.comma()?;
+        if self.eat("|") {
+            Ok(Filter::Pipe(Box::new(left), Box::new(self.pipe()?)))
+        } else {
+            Ok(left)
+        }
+    }
+
+    /// `a, b`
+    fn comma(&mut self) -> Result<Filter> {
+        let mut left = self.alternative()?;
+        while self.eat(",") {
+            left = Filter::Comma(Box::new(left), Box::new(self.alternative()?));
+        }
+        Ok(left)
+    }
+
+    /// `a // b`
+    fn alternative(&mut self) -> Result<Filter> {
+        let left = self.or()?;
+        if self.eat("//") {
+            Ok(Filter::Alternative(
+                Box::new(left),
+                Box::new(self.alternative()?),
+            ))
+        } else {
+            Ok(left)
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter> {
+        let mut left = self.and()?;
+        while self.at_keyword("or") {
+            self.pos += 1;
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter> {
+        let mut left = self.comparison()?;
+        while self.at_keyword("and") {
+            self.pos += 1;
+            left = Filter::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Filter> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Punct("==")) => Op::Eq,
+            Some(Token::Punct("!=")) => Op::Ne,
+            Some(Token::Punct("<")) => Op::Lt,
+            Some(Token::Punct("<=")) => Op::Le,
+            Some(Token::Punct(">")) => Op::Gt,
+            Some(Token::Punct(">=")) => Op::Ge,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(Filter::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Filter> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("+")) => Op::Add,
+                Some(Token::Punct("-")) => Op::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Filter::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Filter> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("*")) => Op::Mul,
+                Some(Token::Punct("/")) => Op::Div,
+                Some(Token::Punct("%")) => Op::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Filter::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Filter> {
+        if self.eat("-") {
+            return Ok(Filter::Negate(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    /// A term followed by `.name`, `[...]`, and `?`
+    fn postfix(&mut self) -> Result<Filter> {
+        let mut term = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    let key = Filter::Literal(Value::String(name.clone()));
+                    self.pos += 1;
+                    term = Filter::Index(Box::new(term), Box::new(key));
+                }
+                Some(Token::Punct("[")) => {
+                    self.pos += 1;
+                    term = self.bracket(term)?;
+                }
+                // `.a.[0]`
+                Some(Token::Dot)
+                    if matches!(self.tokens.get(self.pos + 1), Some(Token::Punct("["))) =>
+                {
+                    self.pos += 2;
+                    term = self.bracket(term)?;
+                }
+                Some(Token::Punct("?")) => {
+                    self.pos += 1;
+                    term = Filter::Try(Box::new(term));
+                }
+                _ => return Ok(term),
+            }
+        }
+    }
+
+    /// What follows `[` after a term: `]`, `index]`, or `from:to]`
+    fn bracket(&mut self, target: Filter) -> Result<Filter> {
+        if self.eat("]") {
+            return Ok(Filter::Iterate(Box::new(target)));
+        }
+        let from = if self.at(":") {
+            None
+        } else {
+            Some(Box::new(self.pipe()?))
+        };
+        if self.eat(":") {
+            let to = if self.at("]") {
+                None
+            } else {
+                Some(Box::new(self.pipe()?))
+            };
+            self.expect("]")?;
+            return Ok(Filter::Slice(Box::new(target), from, to));
+        }
+        self.expect("]")?;
+        let key = from.ok_or_else(|| anyhow!("jq: error: empty index"))?;
+        Ok(Filter::Index(Box::new(target), key))
+    }
+
+    fn term(&mut self) -> Result<Filter> {
+        let token = self
+            .next()
+            .ok_or_else(|| anyhow!("jq: error: unexpected end of filter"))?;
+        match token {
+            Token::Dot => Ok(Filter::Identity),
+            Token::DotDot => Ok(Filter::Recurse),
+            Token::Field(name) => Ok(Filter::Index(
+                Box::new(Filter::Identity),
+                Box::new(Filter::Literal(Value::String(name))),
+            )),
+            Token::Number(n) => Ok(Filter::Literal(number(n))),
+            Token::Str(s) => Ok(Filter::Literal(Value::String(s))),
+            Token::Format(name) => Ok(Filter::Format(name)),
+            Token::Punct("(") => {
+                let inner = self.pipe()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Punct("[") => {
+                if self.eat("]") {
+                    return Ok(Filter::Array(None));
+                }
+                let inner = self.pipe()?;
+                self.expect("]")?;
+                Ok(Filter::Array(Some(Box::new(inner))))
+            }
+            Token::Punct("{") => self.object(),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Filter::Literal(Value::Bool(true))),
+                "false" => Ok(Filter::Literal(Value::Bool(false))),
+                "null" => Ok(Filter::Literal(Value::Null)),
+                "if" => self.if_then(),
+                "try" => Ok(Filter::Try(Box::new(self.postfix()?))),
+                "def" | "reduce" | "foreach" | "as" | "label" | "catch" | "import" | "include" => {
+                    Err(anyhow!("jq: error: '{}' is not supported", name))
+                }
+                _ => {
+                    let mut args = Vec::new();
+                    if self.eat("(") {
+                        loop {
+                            args.push(self.pipe()?);
+                            if !self.eat(";") {
+                                break;
+                            }
+                        }
+                        self.expect(")")?;
+                    }
+                    Ok(Filter::Call(name, args))
+                }
+            },
+            other => Err(anyhow!("jq: error: unexpected {}", describe_token(&other))),
+        }
+    }
+
+    /// After `if`: `c then a (elif c then b)* (else e)? end`
+    fn if_then(&mut self) -> Result<Filter> {
+        let condition = self.pipe()?;
+        self.expect_keyword("then")?;
+        let then = self.pipe()?;
+        let otherwise = if self.at_keyword("elif") {
+            self.pos += 1;
+            Some(Box::new(self.if_then()?))
+        } else {
+            if self.at_keyword("else") {
+                self.pos += 1;
+                let otherwise = self.pipe()?;
+                self.expect_keyword("end")?;
+                return Ok(Filter::If(
+                    Box::new(condition),
+                    Box::new(then),
+                    Some(Box::new(otherwise)),
+                ));
+            }
+            self.expect_keyword("end")?;
+            None
+        };
+        Ok(Filter::If(Box::new(condition), Box::new(then), otherwise))
+    }
+
+    /// After `{`: `key: value` and `name` shorthand entries
+    fn object(&mut self) -> Result<Filter> {
+        let mut entries = Vec::new();
+        if self.eat("}") {
+            return Ok(Filter::Object(entries));
+        }
+        loop {
+            let (key, shorthand) = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => {
+                    (Filter::Literal(Value::String(name.clone())), Some(name))
+                }
+                Some(Token::Punct("(")) => {
+                    let key = self.pipe()?;
+                    self.expect(")")?;
+                    (key, None)
+                }
+                Some(other) => {
+                    return Err(anyhow!(
+                        "jq: error: unexpected {} in object",
+                        describe_token(&other)
+                    ))
+                }
+                None => return Err(anyhow!("jq: error: unterminated object")),
+            };
+            let value = if self.eat(":") {
+                self.object_value()?
+            } else {
+                // `{name}` is `{name: .name}`
+                let name = shorthand
+                    .ok_or_else(|| anyhow!("jq: error: computed object keys need a value"))?;
+                Filter::Index(
+                    Box::new(Filter::Identity),
+                    Box::new(Filter::Literal(Value::String(name))),
+                )
+            };
+            entries.push((key, value));
+            if self.eat("}") {
+                return Ok(Filter::Object(entries));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// An object value: pipes, but no commas (they separate entries)
+    fn object_value(&mut self) -> Result<Filter> {
+        let left = self.alternative()?;
+        if self.eat("|") {
+            Ok(Filter::Pipe(Box::new(left), Box::new(self.object_value()?)))
+        } else {
+            Ok(left)
+        }
+    }
+}
+
+impl Filter {
+    /// Every result of running the filter on `input`
+    fn eval(&self, input: &Value) -> Result<Vec<Value>> {
+        match self {
+            Filter::Identity => Ok(vec![input.clone()]),
+            Filter::Recurse => {
+                let mut out = Vec::new();
+                recurse(input, &mut out);
+                Ok(out)
+            }
+            Filter::Literal(value) => Ok(vec![value.clone()]),
+            Filter::Index(target, key) => {
+                let mut out = Vec::new();
+                for value in target.eval(input)? {
+                    for key in key.eval(input)? {
+                        out.push(index(&value, &key)?);
+                    }
+                }
+                Ok(out)
+            }
+            Filter::Slice(target, from, to) => {
+                let bound = |filter: &Option<Box<Filter>>| -> Result<Vec<Value>> {
+                    match filter {
+                        Some(filter) => filter.eval(input),
+                        None => Ok(vec![Value::Null]),
+                    }
+                };
+                let mut out = Vec::new();
+                for value in target.eval(input)? {
+                    for to in bound(to)? {
+                        for from in bound(from)? {
+                            out.push(slice(&value, &from, &to)?);
+                        }
+                    }
+                }
+                Ok(out)
+            }
+            Filter::Iterate(target) => {
+                let mut out = Vec::new();
+                for value in target.eval(input)? {
+                    out.extend(iterate(&value)?);
+                }
+                Ok(out)
+            }
+            Filter::Try(inner) => Ok(inner.eval(input).unwrap_or_default()),
+            Filter::Pipe(left, right) => {
+                let mut out = Vec::new();
+                for value in left.eval(input)? {
+                    out.extend(right.eval(&value)?);
+                }
+                Ok(out)
+            }
+            Filter::Comma(left, right) => {
+                let mut out = left.eval(input)?;
+                out.extend(right.eval(input)?);
+                Ok(out)
+            }
+            Filter::Negate(inner) => inner
+                .eval(input)?
+                .into_iter()
+                .map(|value| match value.as_f64() {
+                    Some(n) => Ok(number(-n)),
+                    None => Err(anyhow!("jq: error: {} cannot be negated", describe(&value))),
+                })
+                .collect(),
+            Filter::Binary(op, left, right) => {
+                // Every pairing, the right side varying slowest (as jq does)
+                let mut out = Vec::new();
+                for b in right.eval(input)? {
+                    for a in left.eval(input)? {
+                        out.push(binary(*op, &a, &b)?);
+                    }
+                }
+                Ok(out)
+            }
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                let is_and = matches!(self, Filter::And(..));
+                let mut out = Vec::new();
+                for a in left.eval(input)? {
+                    if truthy(&a) != is_and {
+                        out.push(Value::Bool(!is_and));
+                        continue;
+                    }
+                    for b in right.eval(input)? {
+                        out.push(Value::Bool(truthy(&b)));
+                    }
+                }
+                Ok(out)
+            }
+            Filter::Alternative(left, right) => {
+                let found: Vec<Value> = left
+                    .eval(input)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(truthy)
+                    .collect();
+                if found.is_empty() {
+                    right.eval(input)
+                } else {
+                    Ok(found)
+                }
+            }
+            Filter::Array(inner) => Ok(vec![Value::Array(match inner {
+                Some(inner) => inner.eval(input)?,
+                None => Vec::new(),
+            })]),
+            Filter::Object(entries) => {
+                let mut objects = vec![Map::new()];
+                for (key, value) in entries {
+                    let keys = key.eval(input)?;
+                    let values = value.eval(input)?;
+                    let mut next = Vec::new();
+                    for object in &objects {
+                        for key in &keys {
+                            let Value::String(key) = key else {
+                                return Err(anyhow!(
+                                    "jq: error: object keys must be strings, not {}",
+                                    describe(key)
+                                ));
+                            };
+                            for value in &values {
+                                let mut object = object.clone();
+                                object.insert(key.clone(), value.clone());
+                                next.push(object);
+                            }
+                        }
+                    }
+                    objects = next;
+                }
+                Ok(objects.into_iter().map(Value::Object).collect())
+            }
+            Filter::If(condition, then, otherwise) => {
+                let mut out = Vec::new();
+                for value in condition.eval(input)? {
+                    if truthy(&value) {
+                        out.extend(then.eval(input)?);
+                    } else if let Some(otherwise) = otherwise {
+                        out.extend(otherwise.eval(input)?);
+                    } else {
+                        out.push(input.clone());
+                    }
+                }
+                Ok(out)
+            }
+            Filter::Call(name, args) => call(name, args, input),
+            Filter::Format(name) => Ok(vec![Value::String(format(name, input)?)]),
+        }
+    }
+}
+
+/// Run a builtin
+fn call(name: &str, args: &[Filter], input: &Value) -> Result<Vec<Value>> {
+    let one = |value: Value| Ok(vec![value]);
+    // Results of the first argument, each passed to `f`
+    let each_arg = |f: &dyn Fn(&Value) -> Result<Value>| -> Result<Vec<Value>> {
+        args[0].eval(input)?.iter().map(f).collect()
+    };
+    let array = || match input {
+        Value::Array(items) => Ok(items),
+        other => Err(anyhow!(
+            "jq: error: {} is not an array ({} needs one)",
+            describe(other),
+            name
+        )),
+    };
+    let text = || match input {
+        Value::String(text) => Ok(text.as_str()),
+        other => Err(anyhow!(
+            "jq: error: {} is not a string ({} needs one)",
+            describe(other),
+            name
+        )),
+    };
+    // Each element with the first result of `f` on it, in order of those keys
+    let keyed = || -> Result<Vec<(Value, Value)>> {
+        let mut keyed = array()?
+            .iter()
+            .map(|item| Ok((Value::Array(args[0].eval(item)?), item.clone())))
+            .collect::<Result<Vec<_>>>()?;
+        keyed.sort_by(|a, b| compare(&a.0, &b.0));
+        Ok(keyed)
+    };
+
+    match (name, args.len()) {
+        ("empty", 0) => Ok(Vec::new()),
+        ("not", 0) => one(Value::Bool(!truthy(input))),
+        ("length", 0) => one(match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err(anyhow!("jq: error: {} has no length", describe(input))),
+            Value::Number(n) => number(n.as_f64().unwrap_or(0.0).abs()),
+            Value::String(text) => Value::from(text.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }),
+        ("keys", 0) | ("keys_unsorted", 0) => one(match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                if name == "keys" {
+                    keys.sort();
+                }
+                keys.into_iter().map(|k| Value::String(k.clone())).collect()
+            }
+            Value::Array(items) => (0..items.len()).map(Value::from).collect(),
+            other => return Err(anyhow!("jq: error: {} has no keys", describe(other))),
+        }),
+        ("values", 0) => Ok(if input.is_null() {
+            Vec::new()
+        } else {
+            vec![input.clone()]
+        }),
+        ("type", 0) => one(Value::String(type_name(input).to_string())),
+        ("numbers" | "strings" | "booleans" | "nulls" | "arrays" | "objects", 0) => {
+            let wanted = match name {
+                "numbers" => "number",
+                "strings" => "string",
+                "booleans" => "boolean",
+                "nulls" => "null",
+                "arrays" => "array",
+                _ => "object",
+            };
+            Ok(if type_name(input) == wanted {
+                vec![input.clone()]
+            } else {
+                Vec::new()
+            })
+        }
+        ("iterables" | "scalars", 0) => {
+            let iterable = input.is_array() || input.is_object();
+            Ok(if iterable == (name == "iterables") {
+                vec![input.clone()]
+            } else {
+                Vec::new()
+            })
+        }
+        ("add", 0) => match input {
+            Value::Null => one(Value::Null),
+            _ => {
+                let mut sum = Value::Null;
+                for item in iterate(input)? {
+                    sum = binary(Op::Add, &sum, &item)?;
+                }
+                one(sum)
+            }
+        },
+        ("first", 0) => one(index(input, &Value::from(0))?),
+        ("last", 0) => one(index(input, &Value::from(-1))?),
+        ("reverse", 0) => one(match input {
+            Value::Null => Value::Array(Vec::new()),
+            Value::String(text) => Value::String(text.chars().rev().collect()),
+            _ => Value::Array(array()?.iter().rev().cloned().collect()),
+        }),
+        ("sort", 0) | ("unique", 0) => {
+            let mut items = array()?.clone();
+            items.sort_by(compare);
+            if name == "unique" {
+                items.dedup_by(|a, b| compare(a, b) == Ordering::Equal);
+            }
+            one(Value::Array(items))
+        }
+        ("min", 0) | ("max", 0) => {
+            let items = array()?.iter();
+            let found = if name == "min" {
+                items.min_by(|a, b| compare(a, b))
+            } else {
+                items.max_by(|a, b| compare(a, b))
+            };
+            one(found.cloned().unwrap_or(Value::Null))
+        }
+        ("flatten", 0) => {
+            fn flatten(items: &[Value], out: &mut Vec<Value>) {
+                for item in items {
+                    match item {
+                        Value::Array(inner) => flatten(inner, out),
+                        other => out.push(other.clone()),
+                    }
+                }
+            }
+            let mut out = Vec::new();
+            flatten(array()?, &mut out);
+            one(Value::Array(out))
+        }
+        ("any", 0) => one(Value::Bool(array()?.iter().any(truthy))),
+        ("all", 0) => one(Value::Bool(array()?.iter().all(truthy))),
+        ("to_entries", 0) => one(to_entries(input)?),
+        ("from_entries", 0) => one(from_entries(input)?),
+        ("tostring", 0) => one(Value::String(tostring(input))),
+        ("tojson", 0) => one(Value::String(input.to_string())),
+        ("tonumber", 0) => one(match input {
+            Value::Number(_) => input.clone(),
+            Value::String(text) => text
+                .trim()
+                .parse::<f64>()
+                .map(number)
+                .map_err(|_| anyhow!("jq: error: cannot parse '{}' as a number", text))?,
+            other => {
+                return Err(anyhow!(
+                    "jq: error: {} cannot be parsed as a number",
+                    describe(other)
+                ))
+            }
+        }),
+        ("fromjson", 0) => {
+            one(serde_json::from_str(text()?).map_err(|e| anyhow!("jq: error: fromjson: {}", e))?)
+        }
+        ("ascii_downcase", 0) => one(Value::String(text()?.to_ascii_lowercase())),
+        ("ascii_upcase", 0) => one(Value::String(text()?.to_ascii_uppercase())),
+        ("floor" | "ceil" | "round", 0) => {
+            let n = input
+                .as_f64()
+                .ok_or_else(|| anyhow!("jq: error: {} is not a number", describe(input)))?;
+            one(number(match name {
+                "floor" => n.floor(),
+                "ceil" => n.ceil(),
+                _ => n.round(),
+            }))
+        }
+        ("recurse", 0) => Filter::Recurse.eval(input),
+        ("map", 1) => {
+            let mut out = Vec::new();
+            for item in iterate(input)? {
+                out.extend(args[0].eval(&item)?);
+            }
+            one(Value::Array(out))
+        }
+        ("map_values", 1) => one(match input {
+            Value::Object(map) => {
+                let mut out = Map::new();
+                for (key, value) in map {
+                    if let Some(first) = args[0].eval(value)?.into_iter().next() {
+                        out.insert(key.clone(), first);
+                    }
+                }
+                Value::Object(out)
+            }
+            _ => {
+                let mut out = Vec::new();
+                for item in array()? {
+                    out.extend(args[0].eval(item)?.into_iter().next());
+                }
+                Value::Array(out)
+            }
+        }),
+        ("select", 1) => Ok(args[0]
+            .eval(input)?
+            .iter()
+            .filter(|value| truthy(value))
+            .map(|_| input.clone())
+            .collect()),
+        ("has", 1) => each_arg(&|key| {
+            Ok(Value::Bool(match (input, key) {
+                (Value::Object(map), Value::String(key)) => map.contains_key(key),
+                (Value::Array(items), Value::Number(n)) => n
+                    .as_f64()
+                    .is_some_and(|n| n >= 0.0 && (n as usize) < items.len()),
+                _ => {
+                    return Err(anyhow!(
+                        "jq: error: cannot check whether {} has {}",
+                        describe(input),
+                        describe(key)
+                    ))
+                }
+            }))
+        }),
+        ("sort_by", 1) => one(Value::Array(
+            keyed()?.into_iter().map(|(_, item)| item).collect(),
+        )),
+        ("group_by", 1) | ("unique_by", 1) => {
+            let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+            for (key, item) in keyed()? {
+                match groups.last_mut() {
+                    Some((last, group)) if compare(last, &key) == Ordering::Equal => {
+                        group.push(item)
+                    }
+                    _ => groups.push((key, vec![item])),
+                }
+            }
+            one(Value::Array(
+                groups
+                    .into_iter()
+                    .map(|(_, mut group)| {
+                        if name == "group_by" {
+                            Value::Array(group)
+                        } else {
+                            group.swap_remove(0)
+                        }
+                    })
+                    .collect(),
+            ))
+        }
+        ("min_by", 1) | ("max_by", 1) => {
+            let keyed = keyed()?;
+            let found = if name == "min_by" {
+                keyed.into_iter().next()
+            } else {
+                keyed.into_iter().last()
+            };
+            one(found.map_or(Value::Null, |(_, item)| item))
+        }
+        ("with_entries", 1) => {
+            let entries = to_entries(input)?;
+            let mapped = call("map", args, &entries)?;
+            mapped.iter().map(from_entries).collect()
+        }
+        ("join", 1) => each_arg(&|separator| {
+            let Value::String(separator) = separator else {
+                return Err(anyhow!("jq: error: join needs a string separator"));
+            };
+            let parts = array()?
+                .iter()
+                .map(|item| match item {
+                    Value::Null => Ok(String::new()),
+                    Value::String(text) => Ok(text.clone()),
+                    Value::Number(_) | Value::Bool(_) => Ok(item.to_string()),
+                    other => Err(anyhow!("jq: error: cannot join {}", describe(other))),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Value::String(parts.join(separator)))
+        }),
+        ("split", 1) => each_arg(&|separator| {
+            let Value::String(separator) = separator else {
+                return Err(anyhow!("jq: error: split needs a string separator"));
+            };
+            let text = text()?;
+            let parts: Vec<Value> = if text.is_empty() {
+                Vec::new()
+            } else if separator.is_empty() {
+                text.chars().map(|c| Value::String(c.to_string())).collect()
+            } else {
+                text.split(separator.as_str())
+                    .map(|part| Value::String(part.to_string()))
+                    .collect()
+            };
+            Ok(Value::Array(parts))
+        }),
+        ("test", 1) => each_arg(&|pattern| {
+            let Value::String(pattern) = pattern else {
+                return Err(anyhow!("jq: error: test needs a string pattern"));
+            };
+            let regex = Regex::new(pattern).map_err(|e| anyhow!("jq: error: test: {}", e))?;
+            Ok(Value::Bool(regex.is_match(text()?)))
+        }),
+        ("startswith" | "endswith" | "ltrimstr" | "rtrimstr", 1) => each_arg(&|affix| {
+            let (Value::String(affix), Value::String(text)) = (affix, input) else {
+                if name.ends_with("trimstr") {
+                    return Ok(input.clone());
+                }
+                return Err(anyhow!("jq: error: {} needs strings", name));
+            };
+            Ok(match name {
+                "startswith" => Value::Bool(text.starts_with(affix.as_str())),
+                "endswith" => Value::Bool(text.ends_with(affix.as_str())),
+                "ltrimstr" => Value::String(
+                    text.strip_prefix(affix.as_str())
+                        .unwrap_or(text)
+                        .to_string(),
+                ),
+                _ => Value::String(
+                    text.strip_suffix(affix.as_str())
+                        .unwrap_or(text)
+                        .to_string(),
+                ),
+            })
+        }),
+        ("contains", 1) => each_arg(&|wanted| Ok(Value::Bool(contains(input, wanted)?))),
+        ("first", 1) => Ok(args[0].eval(input)?.into_iter().take(1).collect()),
+        ("last", 1) => Ok(args[0]
+            .eval(input)?
+            .into_iter()
+            .last()
+            .into_iter()
+            .collect()),
+        ("limit", 2) => {
+            let mut out = Vec::new();
+            for limit in args[0].eval(input)? {
+                let limit = limit.as_f64().unwrap_or(0.0).max(0.0) as usize;
+                out.extend(args[1].eval(input)?.into_iter().take(limit));
+            }
+            Ok(out)
+        }
+        ("range", 1) | ("range", 2) => {
+            let bounds = |filter: &Filter| -> Result<Vec<f64>> {
+                filter
+                    .eval(input)?
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_f64()
+                            .ok_or_else(|| anyhow!("jq: error: range needs numbers"))
+                    })
+                    .collect()
+            };
+            let (starts, ends) = if args.len() == 1 {
+                (vec![0.0], bounds(&args[0])?)
+            } else {
+                (bounds(&args[0])?, bounds(&args[1])?)
+            };
+            let mut out = Vec::new();
+            for &start in &starts {
+                for &end in &ends {
+                    let mut n = start;
+                    while n < end {
+                        out.push(number(n));
+                        n += 1.0;
+                    }
+                }
+            }
+            Ok(out)
+        }
+        _ => Err(anyhow!("jq: error: {}/{} is not defined", name, args.len())),
+    }
+}
+
+/// `.[key]` of one value
+fn index(value: &Value, key: &Value) -> Result<Value> {
+    match (value, key) {
+        (Value::Object(map), Value::String(key)) => {
+            Ok(map.get(key).cloned().unwrap_or(Value::Null))
+        }
+        (Value::Array(items), Value::Number(n)) => {
+            let n = n.as_f64().unwrap_or(0.0).floor() as i64;
+            let index = if n < 0 { items.len() as i64 + n } else { n };
+            Ok(usize::try_from(index)
+                .ok()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        (Value::Null, Value::String(_) | Value::Number(_)) => Ok(Value::Null),
+        (_, Value::String(key)) => Err(anyhow!(
+            "jq: error: Cannot index {} with \"{}\"",
+            type_name(value),
+            key
+        )),
+        _ => Err(anyhow!(
+            "jq: error: Cannot index {} with {}",
+            type_name(value),
+            type_name(key)
+        )),
+    }
+}
+
+/// `.[from:to]` of an array or string, counting negative bounds from the end
+fn slice(value: &Value, from: &Value, to: &Value) -> Result<Value> {
+    let bounds = |len: usize| -> Result<(usize, usize)> {
+        let bound = |bound: &Value, default: usize| -> Result<usize> {
+            match bound {
+                Value::Null => Ok(default),
+                Value::Number(n) => {
+                    let n = n.as_f64().unwrap_or(0.0).floor() as i64;
+                    let n = if n < 0 { len as i64 + n } else { n };
+                    Ok(n.clamp(0, len as i64) as usize)
+                }
+                other => Err(anyhow!(
+                    "jq: error: slice bounds must be numbers, not {}",
+                    describe(other)
+                )),
+            }
+        };
+        let start = bound(from, 0)?;
+        Ok((start, bound(to, len)?.max(start)))
+    };
+    match value {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let (start, end) = bounds(items.len())?;
+            Ok(Value::Array(items[start..end].to_vec()))
+        }
+        Value::String(text) => {
+            let chars: Vec<char> = text.chars().collect();
+            let (start, end) = bounds(chars.len())?;
+            Ok(Value::String(chars[start..end].iter().collect()))
+        }
+        other => Err(anyhow!("jq: error: Cannot slice {}", describe(other))),
+    }
+}
+
+/// `.[]` of one value
+fn iterate(value: &Value) -> Result<Vec<Value>> {
+    match value {
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(map) => Ok(map.values().cloned().collect()),
+        other => Err(anyhow!(
+            "jq: error: Cannot iterate over {}",
+            describe(other)
+        )),
+    }
+}
+
+/// The value and everything inside it, parents first
+fn recurse(value: &Value, out: &mut Vec<Value>) {
+    out.push(value.clone());
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| recurse(item, out)),
+        Value::Object(map) => map.values().for_each(|item| recurse(item, out)),
+        _ => {}
+    }
+}
+
+fn binary(op: Op, a: &Value, b: &Value) -> Result<Value> {
+    let ordering = || compare(a, b);
+    let numbers = || a.as_f64().zip(b.as_f64());
+    let result = match op {
+        Op::Eq => Some(Value::Bool(ordering() == Ordering::Equal)),
+        Op::Ne => Some(Value::Bool(ordering() != Ordering::Equal)),
+        Op::Lt => Some(Value::Bool(ordering() == Ordering::Less)),
+        Op::Le => Some(Value::Bool(ordering() != Ordering::Greater)),
+        Op::Gt => Some(Value::Bool(ordering() == Ordering::Greater)),
+        Op::Ge => Some(Value::Bool(ordering() != Ordering::Less)),
+        Op::Add => match (a, b) {
+            (Value::Null, other) | (other, Value::Null) => Some(other.clone()),
+            (Value::String(x), Value::String(y)) => Some(Value::String(format!("{}{}", x, y))),
+            (Value::Array(x), Value::Array(y)) => {
+                Some(Value::Array(x.iter().chain(y).cloned().collect()))
+            }
+            (Value::Object(x), Value::Object(y)) => {
+                let mut merged = x.clone();
+                merged.extend(y.clone());
+                Some(Value::Object(merged))
+            }
+            _ => numbers().map(|(x, y)| number(x + y)),
+        },
+        Op::Sub => match (a, b) {
+            (Value::Array(x), Value::Array(y)) => Some(Value::Array(
+                x.iter()
+                    .filter(|item| {
+                        !y.iter()
+                            .any(|other| compare(item, other) == Ordering::Equal)
+                    })
+                    .cloned()
+                    .collect(),
+            )),
+            _ => numbers().map(|(x, y)| number(x - y)),
+        },
+        Op::Mul => match (a, b) {
+            (Value::Object(_), Value::Object(_)) => Some(deep_merge(a, b)),
+            _ => numbers().map(|(x, y)| number(x * y)),
+        },
+        Op::Div => match (a, b) {
+            (Value::String(x), Value::String(y)) => {
+                return call("split", &[Filter::Literal(Value::String(y.clone()))], a)
+                    .map(|mut parts| parts.pop().unwrap_or(Value::Null))
+                    .map_err(|_| anyhow!("jq: error: cannot split {}", x))
+            }
+            _ => match numbers() {
+                Some((_, 0.0)) => {
+                    return Err(anyhow!(
+                        "jq: error: {} cannot be divided because the divisor is zero",
+                        describe(a)
+                    ))
+                }
+                other => other.map(|(x, y)| number(x / y)),
+            },
+        },
+        Op::Mod => match numbers() {
+            Some((_, y)) if y as i64 == 0 => {
+                return Err(anyhow!(
+                    "jq: error: {} cannot be divided because the divisor is zero",
+                    describe(a)
+                ))
+            }
+            other => other.map(|(x, y)| Value::from((x as i64) % (y as i64).abs())),
+        },
+    };
+    let verb = match op {
+        Op::Add => "added",
+        Op::Sub => "subtracted",
+        Op::Mul => "multiplied",
+        _ => "divided",
+    };
+    result.ok_or_else(|| {
+        anyhow!(
+            "jq: error: {} and {} cannot be {}",
+            describe(a),
+            describe(b),
+            verb
+        )
+    })
+}
+
+/// Objects merged key by key, `b` winning
+fn deep_merge(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => {
+            let mut merged = x.clone();
+            for (key, value) in y {
+                let value = match merged.get(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        _ => b.clone(),
+    }
+}
+
+/// jq's `contains`: substrings, elements contained in some element, and
+/// objects whose fields contain the wanted fields
+fn contains(value: &Value, wanted: &Value) -> Result<bool> {
+    match (value, wanted) {
+        (Value::String(text), Value::String(part)) => Ok(text.contains(part.as_str())),
+        (Value::Array(items), Value::Array(wanted)) => {
+            for want in wanted {
+                let mut found = false;
+                for item in items {
+                    if contains(item, want).unwrap_or(false) {
+                        found = true;
+                        break;
+                    }
+                }
+                if !found {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (Value::Object(map), Value::Object(wanted)) => {
+            for (key, want) in wanted {
+                match map.get(key) {
+                    Some(item) if contains(item, want)? => {}
+                    _ => return Ok(false),
+                }
+            }
+            Ok(true)
+        }
+        _ if type_name(value) == type_name(wanted) => Ok(compare(value, wanted) == Ordering::Equal),
+        _ => Err(anyhow!(
+            "jq: error: {} and {} cannot have their containment checked",
+            describe(value),
+            describe(wanted)
+        )),
+    }
+}
+
+fn to_entries(value: &Value) -> Result<Value> {
+    match value {
+        Value::Object(map) => Ok(Value::Array(
+            map.iter()
+                .map(|(key, value)| {
+                    let mut entry = Map::new();
+                    entry.insert("key".to_string(), Value::String(key.clone()));
+                    entry.insert("value".to_string(), value.clone());
+                    Value::Object(entry)
+                })
+                .collect(),
+        )),
+        other => Err(anyhow!("jq: error: {} has no keys", describe(other))),
+    }
+}
+
+/// Objects from `{key, value}` entries (also `k`/`v` and `name`)
+fn from_entries(value: &Value) -> Result<Value> {
+    let Value::Array(entries) = value else {
+        return Err(anyhow!(
+            "jq: error: {} is not an array of entries",
+            describe(value)
+        ));
+    };
+    let mut map = Map::new();
+    for entry in entries {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| entry.get(name).filter(|v| !v.is_null()))
+                .cloned()
+        };
+        let key = match field(&["key", "k", "name", "Name", "Key", "K"]) {
+            Some(Value::String(key)) => key,
+            Some(key @ (Value::Number(_) | Value::Bool(_))) => key.to_string(),
+            _ => {
+                return Err(anyhow!(
+                    "jq: error: entry {} has no string key",
+                    describe(entry)
+                ))
+            }
+        };
+        map.insert(
+            key,
+            field(&["value", "v", "Value", "V"]).unwrap_or(Value::Null),
+        );
+    }
+    Ok(Value::Object(map))
+}
+
+/// `@csv`, `@tsv`, `@json`, and `@text`
+fn format(name: &str, value: &Value) -> Result<String> {
+    let row = |separator: &str, cell: &dyn Fn(&str) -> String| -> Result<String> {
+        let Value::Array(items) = value else {
+            return Err(anyhow!(
+                "jq: error: {} cannot be @{} formatted, only an array",
+                describe(value),
+                name
+            ));
+        };
+        let cells = items
+            .iter()
+            .map(|item| match item {
+                Value::Null => Ok(String::new()),
+                Value::String(text) => Ok(cell(text)),
+                Value::Number(_) | Value::Bool(_) => Ok(item.to_string()),
+                other => Err(anyhow!(
+                    "jq: error: {} is not valid in a {} row",
+                    type_name(other),
+                    name
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(cells.join(separator))
+    };
+    match name {
+        "csv" => row(",", &|text| format!("\"{}\"", text.replace('"', "\"\""))),
+        "tsv" => row("\t", &|text| {
+            text.replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        }),
+        "json" => Ok(value.to_string()),
+        "text" => Ok(tostring(value)),
+        other => Err(anyhow!("jq: error: @{} is not a valid format", other)),
+    }
+}
+
+/// Strings as they are, anything else as JSON
+fn tostring(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A number as JSON, whole ones without a fraction
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// jq's truthiness: everything but false and null
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `number (42)`, shortened like jq's error messages
+fn describe(value: &Value) -> String {
+    let json = value.to_string();
+    let short: String = json.chars().take(11).collect();
+    let ellipsis = if short.len() < json.len() { "..." } else { "" };
+    format!("{} ({}{})", type_name(value), short, ellipsis)
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects
+fn compare(a: &Value, b: &Value) -> Ordering {
+    let rank = |value: &Value| match value {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => {
+            for (x, y) in x.iter().zip(y) {
+                let ordering = compare(x, y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            x.len().cmp(&y.len())
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            // Key sets first, then values in key order
+            let mut x_keys: Vec<&String> = x.keys().collect();
+            let mut y_keys: Vec<&String> = y.keys().collect();
+            x_keys.sort();
+            y_keys.sort();
+            x_keys.cmp(&y_keys).then_with(|| {
+                x_keys
+                    .iter()
+                    .map(|key| compare(&x[*key], &y[*key]))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+// --- Output ---------------------------------------------------------------
+
+/// ANSI escapes for each kind of JSON token, in the theme's colors
+struct Colors {
+    key: String,
+    string: String,
+    number: String,
+    boolean: String,
+    null: String,
+}
+
+impl Colors {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            key: format!("\x1b[1m{}", fg(theme.info_color)),
+            string: fg(theme.string_color),
+            number: fg(theme.number_color),
+            boolean: fg(theme.flag_color),
+            null: fg(theme.comment_color),
+        }
+    }
+}
+
+/// ANSI foreground escape for a theme color
+fn fg(color: Color32) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r(), color.g(), color.b())
+}
+
+/// Writes values as JSON, indented unless compact
+struct Printer {
+    /// One level of indentation, or None for one line
+    indent: Option<&'static str>,
+    sort_keys: bool,
+    colors: Option<Colors>,
+}
+
+impl Printer {
+    fn print(&self, value: &Value) -> String {
+        let mut out = String::new();
+        self.write(&mut out, value, 0);
+        out
+    }
+
+    fn paint(&self, out: &mut String, color: impl Fn(&Colors) -> &str, text: &str) {
+        match &self.colors {
+            Some(colors) => {
+                out.push_str(color(colors));
+                out.push_str(text);
+                out.push_str(RESET);
+            }
+            None => out.push_str(text),
+        }
+    }
+
+    /// A newline and indentation for `depth`, when indenting
+    fn break_line(&self, out: &mut String, depth: usize) {
+        if let Some(indent) = self.indent {
+            out.push('\n');
+            out.push_str(&indent.repeat(depth));
+        }
+    }
+
+    fn write(&self, out: &mut String, value: &Value, depth: usize) {
+        match value {
+            Value::Null => self.paint(out, |c| &c.null, "null"),
+            Value::Bool(b) => self.paint(out, |c| &c.boolean, &b.to_string()),
+            Value::Number(n) => self.paint(out, |c| &c.number, &n.to_string()),
+            Value::String(_) => self.paint(out, |c| &c.string, &value.to_string()),
+            Value::Array(items) if items.is_empty() => out.push_str("[]"),
+            Value::Object(map) if map.is_empty() => out.push_str("{}"),
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.break_line(out, depth + 1);
+                    self.write(out, item, depth + 1);
+                }
+                self.break_line(out, depth);
+                out.push(']');
+            }
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                if self.sort_keys {
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                }
+                out.push('{');
+                for (i, (key, item)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.break_line(out, depth + 1);
+                    self.paint(out, |c| &c.key, &Value::String(key.clone()).to_string());
+                    out.push_str(if self.indent.is_some() { ": " } else { ":" });
+                    self.write(out, item, depth + 1);
+                }
+                self.break_line(out, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, run_stdin, Fixture, MockTerminalState};
+    use crate::terminal::ansi::strip_ansi;
+
+    const DATA: &str = r#"{
+        "name": "zaxiom",
+        "version": "0.3.2",
+        "items": [
+            {"id": 1, "name": "grep", "size": 12, "tags": ["search"]},
+            {"id": 2, "name": "find", "size": 30, "tags": ["search", "files"]},
+            {"id": 3, "name": "cat", "size": 8, "tags": []}
+        ]
+    }"#;
+
+    fn jq(args: &[&str]) -> String {
+        let mut state = MockTerminalState::new().build();
+        state.output_piped = true;
+        let mut args = args.to_vec();
+        args.insert(0, "-c");
+        run_stdin(&JqCommand, &args, DATA, &mut state).unwrap()
+    }
+
+    #[test]
+    fn test_jq_filters() {
+        assert_eq!(jq(&[".name"]), r#""zaxiom""#);
+        assert_eq!(jq(&["-r", ".items[] | .name"]), "grep\nfind\ncat");
+        assert_eq!(jq(&[".items[0].tags[0], .items[-1].id"]), "\"search\"\n3");
+        assert_eq!(jq(&[".items[1:].[0].name"]), r#""find""#);
+        assert_eq!(jq(&[".missing.deeper"]), "null");
+        assert_eq!(
+            jq(&["[.items[] | select(.size > 10) | {name, big: (.size >= 30)}]"]),
+            r#"[{"name":"grep","big":false},{"name":"find","big":true}]"#
+        );
+        assert_eq!(jq(&[".items | map(.size) | add"]), "50");
+        assert_eq!(jq(&[".items | map(.size) | max, min, length"]), "30\n8\n3");
+        assert_eq!(
+            jq(&[".items | sort_by(.size) | map(.name) | join(\",\")"]),
+            r#""cat,grep,find""#
+        );
+        assert_eq!(jq(&["[.items[].tags[]] | unique"]), r#"["files","search"]"#);
+        assert_eq!(jq(&[".version | split(\".\") | map(tonumber)"]), "[0,3,2]");
+        assert_eq!(jq(&["keys"]), r#"["items","name","version"]"#);
+        assert_eq!(jq(&["[.[] | type]"]), r#"["string","string","array"]"#);
+        assert_eq!(
+            jq(&[".items[] | if .size > 20 then \"big\" elif .size > 10 then \"mid\" else \"small\" end"]),
+            "\"mid\"\n\"big\"\n\"small\""
+        );
+        assert_eq!(jq(&[".nope // \"default\""]), r#""default""#);
+        assert_eq!(
+            jq(&[".items[0] | to_entries | map(.key)"]),
+            r#"["id","name","size","tags"]"#
+        );
+        assert_eq!(
+            jq(&["-r", ".items[] | [.id, .name] | @csv"]),
+            "1,\"grep\"\n2,\"find\"\n3,\"cat\""
+        );
+        assert_eq!(
+            jq(&["[.items[] | .name | test(\"^.i\")]"]),
+            "[false,true,false]"
+        );
+        assert_eq!(
+            jq(&["[limit(2; .items[])] | length, ([range(3)] | add)"]),
+            "2\n3"
+        );
+        assert_eq!(jq(&["[.. | numbers] | add"]), "56");
+        assert_eq!(
+            jq(&["{a: 1} * {a: {b: 2}} | .a.b, (1, 2) + 10"]),
+            "2\n11\n12"
+        );
+
+        // Errors, and ? to drop them
+        let mut state = MockTerminalState::new().build();
+        let fails = |args: &[&str], state: &mut TerminalState| {
+            run_stdin(&JqCommand, args, DATA, state)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            fails(&[".items.name"], &mut state),
+            "jq: error: Cannot index array with \"name\""
+        );
+        assert!(fails(&[".name | .[]"], &mut state).contains("Cannot iterate over string"));
+        assert!(fails(&[".items["], &mut state).contains("jq: error"));
+        assert!(fails(&["frobnicate"], &mut state).contains("frobnicate/0 is not defined"));
+        assert_eq!(jq(&["[.items.name?, .name[]?]"]), "[]");
+        assert!(run_stdin(&JqCommand, &["."], "{oops", &mut state)
+            .unwrap_err()
+            .to_string()
+            .starts_with("jq: invalid JSON input"));
+    }
+
+    #[test]
+    fn test_jq_output() {
+        let fixture =
+            Fixture::new("jq").file("data.json", r#"{"b": [1, {"c": null}], "a": true} 7"#);
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+
+        // Pretty-printed in input key order, in colors on screen
+        let output = run(&JqCommand, &[".", "data.json"], &mut state).unwrap();
+        assert!(output.contains("\x1b[38;2;"));
+        assert_eq!(
+            strip_ansi(&output),
+            "{\n  \"b\": [\n    1,\n    {\n      \"c\": null\n    }\n  ],\n  \"a\": true\n}\n7"
+        );
+
+        state.output_piped = true;
+        assert_eq!(
+            run(&JqCommand, &["-cS", ".", "data.json"], &mut state).unwrap(),
+            "{\"a\":true,\"b\":[1,{\"c\":null}]}\n7"
+        );
+        assert_eq!(
+            run(&JqCommand, &["-s", "length", "data.json"], &mut state).unwrap(),
+            "2"
+        );
+        assert_eq!(
+            run(&JqCommand, &["-n", "[1, 2] | add"], &mut state).unwrap(),
+            "3"
+        );
+        assert_eq!(
+            run_stdin(&JqCommand, &[], "[\"x\", {}, []]", &mut state).unwrap(),
+            "[\n  \"x\",\n  {},\n  []\n]"
+        );
+    }
+}
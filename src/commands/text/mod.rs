@@ -1,7 +1,7 @@
 //! Text utility commands
 //!
 //! echo, head, tail, wc, sort, uniq, tac, cut, paste, diff, tr, sed, awk, rev, nl, printf
-//! xargs, column, strings, split, join, comm
+//! xargs, column, strings, split, join, comm, str, jq
 
 mod awk;
 mod column;
@@ -11,6 +11,7 @@ mod diff;
 mod echo;
 mod head;
 mod join;
+mod jq;
 mod nl;
 mod paste;
 mod printf_cmd;
@@ -18,6 +19,7 @@ mod rev;
 mod sed;
 mod sort;
 mod split;
+mod str_cmd;
 mod strings;
 mod tac;
 mod tail;
@@ -34,6 +36,7 @@ pub use diff::DiffCommand;
 pub use echo::EchoCommand;
 pub use head::HeadCommand;
 pub use join::JoinCommand;
+pub use jq::JqCommand;
 pub use nl::NlCommand;
 pub use paste::PasteCommand;
 pub use printf_cmd::PrintfCommand;
@@ -41,6 +44,7 @@ pub use rev::RevCommand;
 pub use sed::SedCommand;
 pub use sort::SortCommand;
 pub use split::SplitCommand;
+pub use str_cmd::StrCommand;
 pub use strings::StringsCommand;
 pub use tac::TacCommand;
 pub use tail::TailCommand;
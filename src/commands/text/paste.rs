@@ -28,11 +28,9 @@ impl Command for PasteCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-d" | "--delimiters" => {
-                    if i + 1 < args.len() {
-                        delimiter = args[i + 1].clone();
-                        i += 1;
-                    }
+                "-d" | "--delimiters" if i + 1 < args.len() => {
+                    delimiter = args[i + 1].clone();
+                    i += 1;
                 }
                 "-h" | "--help" => {
                     return Ok("Usage: paste [OPTIONS] <file1> <file2> ...\n\
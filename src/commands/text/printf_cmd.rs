@@ -1,12 +1,425 @@
 //! printf command - format and print data
+//!
+//! Follows bash's printf: `%[flags][width][.precision]conversion` with the
+//! d i u o x X f F e E g G c s b conversions, `*` for a width or precision
+//! taken from the arguments, and backslash escapes in the format. The format
+//! is reused until every argument has been consumed.
 
-use anyhow::Result;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use anyhow::{anyhow, Result};
 
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
 pub struct PrintfCommand;
 
+/// Which escape dialect a string is written in
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Escapes {
+    /// printf formats: octal is `\NNN`
+    Format,
+    /// `echo -e` and `%b` arguments: octal is `\0NNN`
+    Echo,
+}
+
+/// One backslash escape
+enum Escape {
+    Char(char),
+    /// `\c`: produce no further output
+    Stop,
+    /// Not an escape; kept as written
+    Literal(String),
+}
+
+/// Read the escape after a backslash
+fn read_escape(chars: &mut Peekable<Chars>, style: Escapes) -> Escape {
+    let Some(c) = chars.next() else {
+        return Escape::Literal("\\".to_string());
+    };
+    let ch = match c {
+        'a' => '\x07',
+        'b' => '\x08',
+        'e' | 'E' => '\x1b',
+        'f' => '\x0c',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\x0b',
+        '\\' => '\\',
+        'c' => return Escape::Stop,
+        'x' => match read_digits(chars, 16, 2) {
+            Some(n) => char::from(n as u8),
+            None => return Escape::Literal("\\x".to_string()),
+        },
+        'u' | 'U' => {
+            let max = if c == 'u' { 4 } else { 8 };
+            match read_digits(chars, 16, max).and_then(char::from_u32) {
+                Some(ch) => ch,
+                None => return Escape::Literal(format!("\\{}", c)),
+            }
+        }
+        '0' if style == Escapes::Echo => char::from(read_digits(chars, 8, 3).unwrap_or(0) as u8),
+        '0'..='7' if style == Escapes::Format => {
+            let first = c.to_digit(8).unwrap_or(0);
+            let rest = read_digits_with(chars, 8, 2, first);
+            char::from(rest as u8)
+        }
+        other => return Escape::Literal(format!("\\{}", other)),
+    };
+    Escape::Char(ch)
+}
+
+/// Read up to `max` digits in `radix`, or None if there are none
+fn read_digits(chars: &mut Peekable<Chars>, radix: u32, max: usize) -> Option<u32> {
+    chars.peek()?.to_digit(radix)?;
+    Some(read_digits_with(chars, radix, max, 0))
+}
+
+/// Read up to `max` more digits onto `value`
+fn read_digits_with(chars: &mut Peekable<Chars>, radix: u32, max: usize, mut value: u32) -> u32 {
+    for _ in 0..max {
+        match chars.peek().and_then(|c| c.to_digit(radix)) {
+            Some(digit) => {
+                value = value * radix + digit;
+                chars.next();
+            }
+            None => break,
+        }
+    }
+    value
+}
+
+/// Expand backslash escapes. Also returns whether `\c` cut the text short.
+pub(super) fn unescape(text: &str, style: Escapes) -> (String, bool) {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match read_escape(&mut chars, style) {
+            Escape::Char(ch) => out.push(ch),
+            Escape::Literal(text) => out.push_str(&text),
+            Escape::Stop => return (out, true),
+        }
+    }
+    (out, false)
+}
+
+/// The arguments a format consumes, left to right
+struct Arguments<'a> {
+    items: &'a [String],
+    next: usize,
+}
+
+impl<'a> Arguments<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let item = self.items.get(self.next)?;
+        self.next += 1;
+        Some(item)
+    }
+
+    fn exhausted(&self) -> bool {
+        self.next >= self.items.len()
+    }
+
+    /// Next argument as an integer (0 when missing)
+    fn int(&mut self) -> Result<i64> {
+        match self.next() {
+            Some(arg) => parse_int(arg).ok_or_else(|| anyhow!("printf: {}: invalid number", arg)),
+            None => Ok(0),
+        }
+    }
+
+    /// Next argument as a float (0 when missing)
+    fn float(&mut self) -> Result<f64> {
+        match self.next() {
+            Some(arg) => arg
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .or_else(|| parse_int(arg).map(|n| n as f64))
+                .ok_or_else(|| anyhow!("printf: {}: invalid number", arg)),
+            None => Ok(0.0),
+        }
+    }
+}
+
+/// Parse a printf integer: decimal, `0x` hex, leading-zero octal, or `'c`
+/// for the character's code
+fn parse_int(arg: &str) -> Option<i64> {
+    let arg = arg.trim();
+    if let Some(quoted) = arg.strip_prefix('\'').or_else(|| arg.strip_prefix('"')) {
+        return Some(quoted.chars().next().map_or(0, |c| c as i64));
+    }
+    let (negative, digits) = match arg.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, arg.strip_prefix('+').unwrap_or(arg)),
+    };
+    let magnitude = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i64>().ok()?
+    };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+/// A parsed `%...` conversion
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    zero: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    /// Parse flags, width, and precision up to the conversion character
+    fn parse(chars: &mut Peekable<Chars>, args: &mut Arguments) -> Result<Self> {
+        let mut spec = Spec::default();
+        while let Some(&c) = chars.peek() {
+            match c {
+                '-' => spec.left = true,
+                '0' => spec.zero = true,
+                '+' => spec.plus = true,
+                ' ' => spec.space = true,
+                '#' => spec.alt = true,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        if chars.peek() == Some(&'*') {
+            chars.next();
+            let width = args.int()?;
+            spec.left |= width < 0;
+            spec.width = width.unsigned_abs() as usize;
+        } else {
+            spec.width = read_digits(chars, 10, usize::MAX).unwrap_or(0) as usize;
+        }
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            spec.precision = if chars.peek() == Some(&'*') {
+                chars.next();
+                // A negative precision counts as none
+                usize::try_from(args.int()?).ok()
+            } else {
+                Some(read_digits(chars, 10, usize::MAX).unwrap_or(0) as usize)
+            };
+        }
+        Ok(spec)
+    }
+
+    /// Pad text to the field width with spaces
+    fn pad(&self, text: &str) -> String {
+        let len = text.chars().count();
+        if len >= self.width {
+            text.to_string()
+        } else if self.left {
+            format!("{}{}", text, " ".repeat(self.width - len))
+        } else {
+            format!("{}{}", " ".repeat(self.width - len), text)
+        }
+    }
+
+    /// Pad a number, putting zeros (`%05d`) between its sign or prefix and digits
+    fn pad_number(&self, prefix: &str, digits: &str, zero_pad: bool) -> String {
+        let len = prefix.len() + digits.len();
+        if zero_pad && self.zero && !self.left && len < self.width {
+            format!("{}{}{}", prefix, "0".repeat(self.width - len), digits)
+        } else {
+            self.pad(&format!("{}{}", prefix, digits))
+        }
+    }
+
+    fn sign(&self, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if self.plus {
+            "+"
+        } else if self.space {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    /// Apply an integer precision (minimum number of digits)
+    fn min_digits(&self, digits: String) -> String {
+        match self.precision {
+            Some(0) if digits == "0" => String::new(),
+            Some(p) if digits.len() < p => format!("{}{}", "0".repeat(p - digits.len()), digits),
+            _ => digits,
+        }
+    }
+
+    fn signed(&self, value: i64) -> String {
+        let digits = self.min_digits(value.unsigned_abs().to_string());
+        self.pad_number(self.sign(value < 0), &digits, self.precision.is_none())
+    }
+
+    fn unsigned(&self, value: i64, conversion: char) -> String {
+        // Negative values wrap around, as in C
+        let value = value as u64;
+        let digits = match conversion {
+            'o' => format!("{:o}", value),
+            'x' => format!("{:x}", value),
+            'X' => format!("{:X}", value),
+            _ => value.to_string(),
+        };
+        let mut digits = self.min_digits(digits);
+        let prefix = match conversion {
+            'o' if self.alt && !digits.starts_with('0') => {
+                digits.insert(0, '0');
+                ""
+            }
+            'x' if self.alt && value != 0 => "0x",
+            'X' if self.alt && value != 0 => "0X",
+            _ => "",
+        };
+        self.pad_number(prefix, &digits, self.precision.is_none())
+    }
+
+    fn float(&self, value: f64, conversion: char) -> String {
+        let upper = conversion.is_ascii_uppercase();
+        let sign = self.sign(value < 0.0);
+        if !value.is_finite() {
+            let text = if value.is_nan() { "nan" } else { "inf" };
+            let text = if upper {
+                text.to_uppercase()
+            } else {
+                text.to_string()
+            };
+            return self.pad(&format!("{}{}", sign, text));
+        }
+
+        let value = value.abs();
+        let precision = self.precision.unwrap_or(6);
+        let digits = match conversion.to_ascii_lowercase() {
+            'e' => exponential(value, precision, upper),
+            'g' => {
+                let precision = precision.max(1);
+                let exponent = exponent_of(value, precision - 1);
+                let mut text = if exponent < -4 || exponent >= precision as i32 {
+                    exponential(value, precision - 1, upper)
+                } else {
+                    format!("{:.*}", (precision as i32 - 1 - exponent) as usize, value)
+                };
+                if !self.alt {
+                    text = strip_fraction_zeros(&text);
+                }
+                text
+            }
+            _ if self.alt && precision == 0 => format!("{:.0}.", value),
+            _ => format!("{:.*}", precision, value),
+        };
+        self.pad_number(sign, &digits, true)
+    }
+}
+
+/// Decimal exponent of `value` once rounded to `precision` fraction digits
+fn exponent_of(value: f64, precision: usize) -> i32 {
+    let text = format!("{:.*e}", precision, value);
+    text.rsplit('e')
+        .next()
+        .and_then(|e| e.parse().ok())
+        .unwrap_or(0)
+}
+
+/// `1.500000e+02`: Rust's `{:e}` with C's signed, two-digit exponent
+fn exponential(value: f64, precision: usize, upper: bool) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    format!(
+        "{}{}{}{:02}",
+        mantissa,
+        if upper { 'E' } else { 'e' },
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+/// `%g` drops trailing zeros in the fraction (and a bare `.`)
+fn strip_fraction_zeros(text: &str) -> String {
+    let (number, exponent) = match text.find(['e', 'E']) {
+        Some(i) => text.split_at(i),
+        None => (text, ""),
+    };
+    let number = if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    };
+    format!("{}{}", number, exponent)
+}
+
+/// Apply the format once, appending to `out`. Returns true if `\c` ended output.
+fn format_once(format: &str, args: &mut Arguments, out: &mut String) -> Result<bool> {
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match read_escape(&mut chars, Escapes::Format) {
+                Escape::Char(ch) => out.push(ch),
+                Escape::Literal(text) => out.push_str(&text),
+                Escape::Stop => return Ok(true),
+            },
+            '%' if chars.peek() == Some(&'%') => {
+                chars.next();
+                out.push('%');
+            }
+            '%' => {
+                let spec = Spec::parse(&mut chars, args)?;
+                let conversion = chars
+                    .next()
+                    .ok_or_else(|| anyhow!("printf: %: missing format character"))?;
+                match conversion {
+                    'd' | 'i' => out.push_str(&spec.signed(args.int()?)),
+                    'u' | 'o' | 'x' | 'X' => out.push_str(&spec.unsigned(args.int()?, conversion)),
+                    'f' | 'F' | 'e' | 'E' | 'g' | 'G' => {
+                        out.push_str(&spec.float(args.float()?, conversion))
+                    }
+                    'c' => {
+                        let ch = args.next().and_then(|a| a.chars().next());
+                        out.push_str(&spec.pad(&ch.map(String::from).unwrap_or_default()));
+                    }
+                    's' | 'b' => {
+                        let arg = args.next().unwrap_or_default();
+                        let (text, stop) = if conversion == 'b' {
+                            unescape(arg, Escapes::Echo)
+                        } else {
+                            (arg.to_string(), false)
+                        };
+                        let text: String = match spec.precision {
+                            Some(p) => text.chars().take(p).collect(),
+                            None => text,
+                        };
+                        out.push_str(&spec.pad(&text));
+                        if stop {
+                            return Ok(true);
+                        }
+                    }
+                    other => return Err(anyhow!("printf: %{}: invalid format character", other)),
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    Ok(false)
+}
+
 impl Command for PrintfCommand {
     fn name(&self) -> &'static str {
         "printf"
@@ -20,126 +433,159 @@ impl Command for PrintfCommand {
         "printf <format> [arguments...]"
     }
 
-    fn execute(&self, args: &[String], _state: &mut TerminalState) -> Result<String> {
-        if args.is_empty() {
-            return Ok(String::new());
-        }
-
-        if args[0] == "-h" || args[0] == "--help" {
-            return Ok("Usage: printf FORMAT [ARGUMENT...]\n\
-                Format specifiers:\n  \
-                %s    String\n  \
-                %d    Integer\n  \
-                %f    Float\n  \
-                %x    Hex\n  \
-                %o    Octal\n  \
-                %%    Literal %\n\n\
-                Escape sequences:\n  \
-                \\n   Newline\n  \
-                \\t   Tab\n  \
-                \\\\   Backslash"
-                .to_string());
-        }
-
-        let format = &args[0];
-        let arguments: Vec<&str> = args[1..].iter().map(|s| s.as_str()).collect();
-        let mut arg_index = 0;
-
-        let mut result = String::new();
-        let mut chars = format.chars().peekable();
-
-        while let Some(c) = chars.next() {
-            if c == '\\' {
-                // Escape sequence
-                match chars.next() {
-                    Some('n') => result.push('\n'),
-                    Some('t') => result.push('\t'),
-                    Some('r') => result.push('\r'),
-                    Some('\\') => result.push('\\'),
-                    Some('0') => result.push('\0'),
-                    Some(other) => {
-                        result.push('\\');
-                        result.push(other);
-                    }
-                    None => result.push('\\'),
-                }
-            } else if c == '%' {
-                // Format specifier
-                match chars.next() {
-                    Some('%') => result.push('%'),
-                    Some('s') => {
-                        if arg_index < arguments.len() {
-                            result.push_str(arguments[arg_index]);
-                            arg_index += 1;
-                        }
-                    }
-                    Some('d') | Some('i') => {
-                        if arg_index < arguments.len() {
-                            if let Ok(n) = arguments[arg_index].parse::<i64>() {
-                                result.push_str(&n.to_string());
-                            } else {
-                                result.push_str(arguments[arg_index]);
-                            }
-                            arg_index += 1;
-                        }
-                    }
-                    Some('f') => {
-                        if arg_index < arguments.len() {
-                            if let Ok(n) = arguments[arg_index].parse::<f64>() {
-                                result.push_str(&format!("{:.6}", n));
-                            } else {
-                                result.push_str(arguments[arg_index]);
-                            }
-                            arg_index += 1;
-                        }
-                    }
-                    Some('x') => {
-                        if arg_index < arguments.len() {
-                            if let Ok(n) = arguments[arg_index].parse::<i64>() {
-                                result.push_str(&format!("{:x}", n));
-                            } else {
-                                result.push_str(arguments[arg_index]);
-                            }
-                            arg_index += 1;
-                        }
-                    }
-                    Some('X') => {
-                        if arg_index < arguments.len() {
-                            if let Ok(n) = arguments[arg_index].parse::<i64>() {
-                                result.push_str(&format!("{:X}", n));
-                            } else {
-                                result.push_str(arguments[arg_index]);
-                            }
-                            arg_index += 1;
-                        }
-                    }
-                    Some('o') => {
-                        if arg_index < arguments.len() {
-                            if let Ok(n) = arguments[arg_index].parse::<i64>() {
-                                result.push_str(&format!("{:o}", n));
-                            } else {
-                                result.push_str(arguments[arg_index]);
-                            }
-                            arg_index += 1;
-                        }
-                    }
-                    Some('c') => {
-                        if arg_index < arguments.len() && !arguments[arg_index].is_empty() {
-                            result.push(arguments[arg_index].chars().next().unwrap());
-                            arg_index += 1;
-                        }
-                    }
-                    Some(other) => {
-                        result.push('%');
-                        result.push(other);
-                    }
-                    None => result.push('%'),
-                }
-            } else {
-                result.push(c);
+    fn extended_help(&self) -> String {
+        r#"printf - Format and print data
+
+USAGE:
+  printf <format> [arguments...]
+
+DESCRIPTION:
+  Print the arguments as the format says. Unlike echo, no newline is
+  added - end the format with \n. If there are more arguments than
+  conversions, the format is used again until all are printed.
+
+CONVERSIONS:
+  %d %i       Signed integer
+  %u          Unsigned integer
+  %o %x %X    Octal, hex, upper-case hex
+  %f %e %g    Fixed, exponent, or shortest float (%F %E %G upper-case)
+  %s          String
+  %b          String with echo -e escapes
+  %c          First character
+  %%          Literal %
+
+  Between % and the conversion: flags (- left-align, 0 zero-pad,
+  + sign, space, # alternate form), a width, and .precision.
+  Use * to take the width or precision from the arguments.
+
+ESCAPES:
+  \n \t \r \\   Newline, tab, carriage return, backslash
+  \a \b \f \v   Bell, backspace, form feed, vertical tab
+  \e            Escape (for ANSI colors)
+  \xHH \NNN     Byte in hex or octal
+  \uHHHH        Unicode character
+  \c            Stop output
+
+  Integer arguments may be hex (0x1F), octal (017), or 'c for the
+  character code of c.
+
+EXAMPLES:
+  printf "%s is %d\n" age 42        age is 42
+  printf "%-10s|%5.2f\n" total 3.14159
+  printf "%05d\n" 42                00042
+  printf "%x %o %#x\n" 255 8 255    ff 10 0xff
+  printf "%s\n" a b c               One per line
+  printf "%*d\n" 6 42               Width from argument
+  printf "\e[32mgreen\e[0m\n"
+
+RELATED COMMANDS:
+  echo     Print text
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        let args = match args.first() {
+            Some(first) if first == "--" => &args[1..],
+            _ => args,
+        };
+        let Some(format) = args.first() else {
+            return Err(anyhow!("usage: {}", self.usage()));
+        };
+
+        let mut arguments = Arguments {
+            items: &args[1..],
+            next: 0,
+        };
+        let mut out = String::new();
+        loop {
+            let start = arguments.next;
+            let stop = format_once(format, &mut arguments, &mut out)?;
+            if stop || arguments.exhausted() || arguments.next == start {
+                break;
+            }
+        }
+
+        // The output's own final newline stands in for the one the terminal adds
+        match out.strip_suffix('\n') {
+            Some(line) => Ok(line.to_string()),
+            None => {
+                state.omit_newline = true;
+                Ok(out)
             }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{run, MockTerminalState};
+
+    fn printf(args: &[&str]) -> String {
+        let mut state = MockTerminalState::new().build();
+        run(&PrintfCommand, args, &mut state).unwrap()
+    }
+
+    #[test]
+    fn test_integer_and_string_specs() {
+        assert_eq!(
+            printf(&["%d|%5d|%-5d|%05d\\n", "42", "42", "42", "-42"]),
+            "42|   42|42   |-0042"
+        );
+        assert_eq!(printf(&["%+d % d %.3d", "7", "7", "7"]), "+7  7 007");
+        assert_eq!(
+            printf(&["%x %X %o %#x %#o", "255", "255", "8", "255", "8"]),
+            "ff FF 10 0xff 010"
+        );
+        assert_eq!(printf(&["%d %d %d", "0x10", "010", "'A"]), "16 8 65");
+        assert_eq!(
+            printf(&["[%8s][%-4s][%.2s]", "right", "l", "truncate"]),
+            "[   right][l   ][tr]"
+        );
+        assert_eq!(printf(&["%*d|%-*s|", "4", "7", "3", "x"]), "   7|x  |");
+        assert_eq!(printf(&["%c%c", "hello", "world"]), "hw");
+        assert!(run(
+            &PrintfCommand,
+            &["%d", "abc"],
+            &mut MockTerminalState::new().build()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_float_specs() {
+        assert_eq!(printf(&["%f", "3.14159"]), "3.141590");
+        assert_eq!(
+            printf(&["%.2f|%8.3f|%-8.1f|%08.2f", "2.5", "3.14159", "1", "-1.5"]),
+            "2.50|   3.142|1.0     |-0001.50"
+        );
+        assert_eq!(
+            printf(&["%e %.2E", "1234.5", "0.000123"]),
+            "1.234500e+03 1.23E-04"
+        );
+        assert_eq!(
+            printf(&["%g %g %g %g", "100000", "1000000", "0.0001", "2.50"]),
+            "100000 1e+06 0.0001 2.5"
+        );
+    }
+
+    #[test]
+    fn test_escapes_and_format_reuse() {
+        assert_eq!(printf(&["a\\tb\\x41\\101\\u00e9\\\\"]), "a\tbAAé\\");
+        assert_eq!(printf(&["%s\\n", "a", "b", "c"]), "a\nb\nc");
+        assert_eq!(printf(&["%s=%s;", "k1", "v1", "k2"]), "k1=v1;k2=;");
+        assert_eq!(printf(&["%b|%s", "x\\ty", "x\\ty"]), "x\ty|x\\ty");
+        assert_eq!(printf(&["one\\ctwo"]), "one");
+        assert_eq!(printf(&["100%%"]), "100%");
+    }
 
-        Ok(result)
+    #[test]
+    fn test_final_newline() {
+        let mut state = MockTerminalState::new().build();
+        assert_eq!(run(&PrintfCommand, &["hi\\n"], &mut state).unwrap(), "hi");
+        assert!(!state.omit_newline);
+        assert_eq!(run(&PrintfCommand, &["hi"], &mut state).unwrap(), "hi");
+        assert!(state.omit_newline);
     }
 }
@@ -151,3 +151,19 @@ RELATED COMMANDS:
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run_stdin, MockTerminalState};
+
+    #[test]
+    fn test_sort_golden() {
+        let mut state = MockTerminalState::new().build();
+        let input = "10 pears\n9 apples\n10 pears\n2 figs\n";
+        let output = run_stdin(&SortCommand, &["-n"], input, &mut state).unwrap();
+        assert_golden("sort_numeric", &output);
+        let output = run_stdin(&SortCommand, &["-r", "-u"], input, &mut state).unwrap();
+        assert_golden("sort_reverse_unique", &output);
+    }
+}
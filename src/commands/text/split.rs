@@ -32,17 +32,13 @@ impl Command for SplitCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-l" => {
-                    if i + 1 < args.len() {
-                        lines_per_file = args[i + 1].parse().ok();
-                        i += 1;
-                    }
+                "-l" if i + 1 < args.len() => {
+                    lines_per_file = args[i + 1].parse().ok();
+                    i += 1;
                 }
-                "-b" => {
-                    if i + 1 < args.len() {
-                        _bytes_per_file = parse_size(&args[i + 1]);
-                        i += 1;
-                    }
+                "-b" if i + 1 < args.len() => {
+                    _bytes_per_file = parse_size(&args[i + 1]);
+                    i += 1;
                 }
                 arg if !arg.starts_with('-') => {
                     if input_file.is_none() {
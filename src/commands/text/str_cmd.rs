@@ -0,0 +1,345 @@
+//! str command - case conversion, trimming, padding and slugs
+
+use anyhow::Result;
+
+use crate::commands::traits::Command;
+use crate::terminal::state::TerminalState;
+
+pub struct StrCommand;
+
+/// Widest padding accepted by `str pad`
+const MAX_WIDTH: usize = 10_000;
+
+impl Command for StrCommand {
+    fn name(&self) -> &'static str {
+        "str"
+    }
+
+    fn description(&self) -> &'static str {
+        "Convert case, trim, pad or slugify text"
+    }
+
+    fn usage(&self) -> &'static str {
+        "str <upper|lower|title|camel|pascal|snake|kebab|slug|trim|pad|len> [text...]"
+    }
+
+    fn extended_help(&self) -> String {
+        r#"str - Quick string transformations
+
+USAGE:
+  str <operation> [OPTIONS] [text...]
+  command | str <operation> [OPTIONS]
+
+OPERATIONS:
+  upper      HELLO WORLD
+  lower      hello world
+  title      Hello World
+  camel      helloWorld
+  pascal     HelloWorld
+  snake      hello_world
+  kebab      hello-world
+  slug       hello-world (URL-safe, accents removed)
+  trim       Strip surrounding whitespace (--left / --right for one side)
+  pad <n>    Pad to n characters (--left to right-align, --center,
+             --char <c> to pad with something other than spaces)
+  len        Length in characters
+
+DESCRIPTION:
+  Works on the arguments, or on each piped line. Word boundaries
+  for camel/snake/kebab come from spaces, punctuation and case
+  changes, so "parseHTTPResponse" becomes parse_http_response.
+
+EXAMPLES:
+  str snake "Hello World"             hello_world
+  str camel user-profile-id           userProfileId
+  str slug "Café Menu: 2024!"         cafe-menu-2024
+  str pad 8 --left 42                 "      42"
+  str pad 20 --center --char = TITLE  =======TITLE========
+  ls | str upper                      Shout the file names
+  cat names.txt | str trim            Clean up each line
+
+RELATED COMMANDS:
+  tr       Translate characters
+  sed      Stream editor
+"#
+        .to_string()
+    }
+
+    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        _state: &mut TerminalState,
+    ) -> Result<String> {
+        let Some(operation) = args.first().map(String::as_str) else {
+            return Err(anyhow::anyhow!("Usage: {}", self.usage()));
+        };
+        if operation == "-h" || operation == "--help" {
+            return Ok(self.extended_help());
+        }
+
+        let mut side = Side::Both;
+        let mut align = Align::Left;
+        let mut fill = ' ';
+        let mut width: Option<usize> = None;
+        let mut words: Vec<&str> = Vec::new();
+
+        let mut iter = args[1..].iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--left" | "-l" => {
+                    side = Side::Left;
+                    align = Align::Right;
+                }
+                "--right" | "-r" => {
+                    side = Side::Right;
+                    align = Align::Left;
+                }
+                "--center" | "-c" => align = Align::Center,
+                "--char" => {
+                    fill = iter
+                        .next()
+                        .and_then(|c| c.chars().next())
+                        .ok_or_else(|| anyhow::anyhow!("str: --char needs a character"))?;
+                }
+                _ if operation == "pad" && width.is_none() => {
+                    let n = arg
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("str: pad needs a width, got '{}'", arg))?;
+                    if n > MAX_WIDTH {
+                        return Err(anyhow::anyhow!("str: width is limited to {}", MAX_WIDTH));
+                    }
+                    width = Some(n);
+                }
+                _ => words.push(arg),
+            }
+        }
+
+        let input = match (words.is_empty(), stdin) {
+            (false, _) => words.join(" "),
+            (true, Some(input)) => input.to_string(),
+            (true, None) => return Err(anyhow::anyhow!("str: no input")),
+        };
+
+        let transform: Box<dyn Fn(&str) -> String> = match operation {
+            "upper" => Box::new(|s| s.to_uppercase()),
+            "lower" => Box::new(|s| s.to_lowercase()),
+            "title" => Box::new(title_case),
+            "camel" => Box::new(|s| camel_case(s, false)),
+            "pascal" => Box::new(|s| camel_case(s, true)),
+            "snake" => Box::new(|s| join_words(s, "_")),
+            "kebab" => Box::new(|s| join_words(s, "-")),
+            "slug" => Box::new(slugify),
+            "trim" => Box::new(move |s| match side {
+                Side::Both => s.trim().to_string(),
+                Side::Left => s.trim_start().to_string(),
+                Side::Right => s.trim_end().to_string(),
+            }),
+            "pad" => {
+                let width = width.ok_or_else(|| anyhow::anyhow!("str: pad needs a width"))?;
+                Box::new(move |s| pad(s, width, align, fill))
+            }
+            "len" => Box::new(|s| s.chars().count().to_string()),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "str: unknown operation '{}'\nUsage: {}",
+                    operation,
+                    self.usage()
+                ))
+            }
+        };
+
+        Ok(input.lines().map(transform).collect::<Vec<_>>().join("\n"))
+    }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Both,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// Split into words at punctuation, spaces and case changes
+/// ("parseHTTPResponse" → parse, HTTP, Response)
+fn split_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(prev) = current.chars().last() {
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let boundary = (prev.is_lowercase() && c.is_uppercase())
+                || (prev.is_uppercase() && c.is_uppercase() && next_lower);
+            if boundary {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Capitalize each word, keeping the original spacing
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            result.push_str(&capitalize(&word));
+            word.clear();
+            result.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    result.push_str(&capitalize(&word));
+    result
+}
+
+fn camel_case(text: &str, pascal: bool) -> String {
+    split_words(text)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            if i == 0 && !pascal {
+                word.to_lowercase()
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect()
+}
+
+fn join_words(text: &str, separator: &str) -> String {
+    split_words(text)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Lowercase ASCII words joined by '-', with common accents folded
+fn slugify(text: &str) -> String {
+    let folded: String = text.chars().map(fold_accent).collect();
+    folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' => 'A',
+        'ç' | 'ć' | 'č' => 'c',
+        'Ç' | 'Ć' | 'Č' => 'C',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ę' | 'Ě' => 'E',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' => 'I',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'Ñ' | 'Ń' | 'Ň' => 'N',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' | 'Ÿ' => 'Y',
+        'š' | 'ś' => 's',
+        'Š' | 'Ś' => 'S',
+        'ž' | 'ź' | 'ż' => 'z',
+        'Ž' | 'Ź' | 'Ż' => 'Z',
+        'ł' => 'l',
+        'Ł' => 'L',
+        _ => c,
+    }
+}
+
+fn pad(text: &str, width: usize, align: Align, fill: char) -> String {
+    let missing = width.saturating_sub(text.chars().count());
+    let (left, right) = match align {
+        Align::Left => (0, missing),
+        Align::Right => (missing, 0),
+        Align::Center => (missing / 2, missing - missing / 2),
+    };
+    let fill = fill.to_string();
+    format!("{}{}{}", fill.repeat(left), text, fill.repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str], stdin: Option<&str>) -> String {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut state = TerminalState::new();
+        StrCommand
+            .execute_with_stdin(&args, stdin, &mut state)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_case_conversions() {
+        assert_eq!(
+            run(&["snake", "parseHTTPResponse"], None),
+            "parse_http_response"
+        );
+        assert_eq!(run(&["camel", "user-profile-id"], None), "userProfileId");
+        assert_eq!(run(&["pascal", "user_profile id"], None), "UserProfileId");
+        assert_eq!(run(&["kebab", "Hello World"], None), "hello-world");
+        assert_eq!(run(&["title", "hello  wORLD"], None), "Hello  World");
+        assert_eq!(run(&["slug", "Café Menu: 2024!"], None), "cafe-menu-2024");
+        assert_eq!(run(&["upper"], Some("a\nb")), "A\nB");
+    }
+
+    #[test]
+    fn test_trim_and_pad() {
+        assert_eq!(run(&["trim"], Some("  a  \n b")), "a\nb");
+        assert_eq!(run(&["trim", "--left"], Some("  a  ")), "a  ");
+        assert_eq!(run(&["pad", "5", "--left", "42"], None), "   42");
+        assert_eq!(
+            run(&["pad", "9", "--center", "--char", "=", "TITLE"], None),
+            "==TITLE=="
+        );
+        assert_eq!(run(&["len", "héllo"], None), "5");
+    }
+}
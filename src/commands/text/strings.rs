@@ -34,11 +34,9 @@ impl Command for StringsCommand {
         let mut i = 0;
         while i < args.len() {
             match args[i].as_str() {
-                "-n" => {
-                    if i + 1 < args.len() {
-                        min_length = args[i + 1].parse().unwrap_or(4);
-                        i += 1;
-                    }
+                "-n" if i + 1 < args.len() => {
+                    min_length = args[i + 1].parse().unwrap_or(4);
+                    i += 1;
                 }
                 arg if !arg.starts_with('-') => files.push(arg),
                 _ => {}
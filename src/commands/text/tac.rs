@@ -18,19 +18,31 @@ impl Command for TacCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "tac <file> [file2...]"
+        "tac [file...]"
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
         if args.is_empty() {
-            return Err(anyhow::anyhow!("tac: missing file operand"));
+            let input = stdin.ok_or_else(|| anyhow::anyhow!("tac: missing file operand"))?;
+            let mut lines: Vec<&str> = input.lines().collect();
+            lines.reverse();
+            return Ok(lines.join("\n"));
         }
 
         let mut all_lines: Vec<String> = Vec::new();
 
         for arg in args {
             if arg == "-h" || arg == "--help" {
-                return Ok("Usage: tac <file> [file2...]\n\
+                return Ok("Usage: tac [file...]\n\
                     Print files in reverse order, line by line."
                     .to_string());
             }
@@ -49,4 +61,8 @@ impl Command for TacCommand {
         all_lines.reverse();
         Ok(all_lines.join("\n"))
     }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
 }
@@ -1,16 +1,26 @@
-//! tail command - print last N lines
+//! tail command - print last N lines, and follow a file as it grows
 
 use std::collections::VecDeque;
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
 
 use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
 
+use crate::commands::context::CommandContext;
 use crate::commands::traits::Command;
 use crate::terminal::state::TerminalState;
 
 pub struct TailCommand;
 
+/// How often a followed file is checked when no change was reported (some
+/// filesystems, like network shares, send no events)
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 impl Command for TailCommand {
     fn name(&self) -> &'static str {
         "tail"
@@ -21,7 +31,7 @@ impl Command for TailCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "tail [-n lines] [file]"
+        "tail [-n lines] [-f] [file]"
     }
 
     fn extended_help(&self) -> String {
@@ -34,7 +44,7 @@ USAGE:
 OPTIONS:
   -n <lines>    Number of lines to show (default: 10)
   -<number>     Shorthand for -n (e.g., tail -5 file)
-  -f            Follow file (coming soon)
+  -f, --follow  Keep printing lines as they are appended, until Ctrl+C
 
 DESCRIPTION:
   Output the last part of files. By default, prints
@@ -45,6 +55,7 @@ EXAMPLES:
   tail -n 5 file.txt      Last 5 lines
   tail -20 file.txt       Last 20 lines (shorthand)
   tail -n 1 file.txt      Last line only
+  tail -f app.log         Watch a log as it is written
   cat log.txt | tail      Last 10 lines of piped input
 
 COMMON USE CASES:
@@ -70,9 +81,20 @@ RELATED COMMANDS:
         args: &[String],
         stdin: Option<&str>,
         state: &mut TerminalState,
+    ) -> Result<String> {
+        self.execute_with_context(args, stdin, state, &CommandContext::default())
+    }
+
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
     ) -> Result<String> {
         let mut lines = 10usize;
         let mut file_path = None;
+        let mut follow = false;
 
         let mut iter = args.iter().peekable();
         while let Some(arg) = iter.next() {
@@ -84,9 +106,10 @@ RELATED COMMANDS:
                             .map_err(|_| anyhow::anyhow!("tail: invalid line count"))?;
                     }
                 }
+                "-f" | "--follow" => follow = true,
                 "-h" | "--help" => {
-                    return Ok("Usage: tail [-n lines] [file]\n\
-                        Print last N lines (default: 10)"
+                    return Ok("Usage: tail [-n lines] [-f] [file]\n\
+                        Print last N lines (default: 10); -f keeps printing new ones"
                         .to_string());
                 }
                 _ if arg.starts_with("-n") => {
@@ -110,8 +133,17 @@ RELATED COMMANDS:
             }
         }
 
+        if follow && !ctx.is_streaming() {
+            return Err(anyhow::anyhow!(
+                "tail: -f only works on its own line (its output can't be piped)"
+            ));
+        }
+
         // Use stdin if no file specified
         if file_path.is_none() {
+            if follow {
+                return Err(anyhow::anyhow!("tail: -f needs a file"));
+            }
             if let Some(input) = stdin {
                 let all_lines: Vec<&str> = input.lines().collect();
                 let start = all_lines.len().saturating_sub(lines);
@@ -128,13 +160,12 @@ RELATED COMMANDS:
             return Err(anyhow::anyhow!("tail: {}: No such file", file));
         }
 
-        let file = fs::File::open(&path)?;
-        let reader = BufReader::new(file);
+        let mut reader = BufReader::new(fs::File::open(&path)?);
 
         // Keep last N lines in a ring buffer
         let mut ring: VecDeque<String> = VecDeque::with_capacity(lines);
 
-        for l in reader.lines().map_while(Result::ok) {
+        for l in reader.by_ref().lines().map_while(Result::ok) {
             if ring.len() >= lines {
                 ring.pop_front();
             }
@@ -142,10 +173,133 @@ RELATED COMMANDS:
         }
 
         let output: Vec<String> = ring.into_iter().collect();
-        Ok(output.join("\n"))
+        if !follow {
+            return Ok(output.join("\n"));
+        }
+
+        for line in &output {
+            ctx.print(line);
+        }
+        let offset = reader.into_inner().stream_position()?;
+        follow_file(&path, file, offset, ctx)
     }
 
     fn supports_stdin(&self) -> bool {
         true
     }
 }
+
+/// Print lines appended to `path` after `offset` as they arrive, until the
+/// job is cancelled. A file that shrinks was truncated (or rotated) and is
+/// read again from the start; one that disappears is waited for.
+fn follow_file(path: &Path, name: &str, mut offset: u64, ctx: &CommandContext) -> Result<String> {
+    // Watch the directory rather than the file, so a log that is rotated or
+    // recreated is still seen
+    let (tx, rx) = mpsc::channel();
+    let watcher = notify::recommended_watcher(tx)
+        .ok()
+        .and_then(|mut watcher| {
+            let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty())?;
+            watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+            Some(watcher)
+        });
+    // Bytes after the last newline, printed once the line is finished
+    let mut partial = Vec::new();
+
+    loop {
+        ctx.check()?;
+        if watcher.is_none() {
+            thread::sleep(POLL_INTERVAL);
+        } else if let Err(RecvTimeoutError::Disconnected) = rx.recv_timeout(POLL_INTERVAL) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        // One read covers a burst of events
+        while rx.try_recv().is_ok() {}
+
+        let Ok(len) = fs::metadata(path).map(|meta| meta.len()) else {
+            continue;
+        };
+        if len < offset {
+            ctx.print(&format!("tail: {}: file truncated", name));
+            offset = 0;
+            partial.clear();
+        }
+        if len == offset {
+            continue;
+        }
+
+        let mut file = fs::File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        offset += file.read_to_end(&mut partial)? as u64;
+        while let Some(end) = partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = partial.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            ctx.print(line.trim_end_matches(['\n', '\r']));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::context::{CancelToken, Interrupted};
+    use crate::commands::testing::{Fixture, MockTerminalState};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    #[test]
+    fn test_follow() {
+        let fixture = Fixture::new("tail-follow").file("app.log", "one\ntwo\nthree\n");
+        let log = fixture.path().join("app.log");
+        let printed = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&printed);
+        let cancel = CancelToken::new();
+        let ctx = CommandContext::new(cancel.clone())
+            .with_output(move |line| sink.lock().unwrap().push(line.to_string()));
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let follower = thread::spawn(move || {
+            let args = ["-f", "-n", "2", "app.log"].map(String::from);
+            TailCommand.execute_with_context(&args, None, &mut state, &ctx)
+        });
+
+        let wait_for = |expected: &[&str]| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while *printed.lock().unwrap() != expected && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(20));
+            }
+            assert_eq!(*printed.lock().unwrap(), expected);
+        };
+        wait_for(&["two", "three"]);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+        write!(file, "four\nfi").unwrap();
+        wait_for(&["two", "three", "four"]);
+        write!(file, "ve\r\n").unwrap();
+        wait_for(&["two", "three", "four", "five"]);
+
+        fs::write(&log, "new\n").unwrap();
+        wait_for(&[
+            "two",
+            "three",
+            "four",
+            "five",
+            "tail: app.log: file truncated",
+            "new",
+        ]);
+
+        cancel.cancel();
+        let err = follower.join().unwrap().unwrap_err();
+        assert_eq!(err.downcast_ref::<Interrupted>(), Some(&Interrupted));
+    }
+
+    #[test]
+    fn test_follow_needs_the_pane() {
+        let fixture = Fixture::new("tail-follow-piped").file("app.log", "one\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let args = ["-f", "app.log"].map(String::from);
+        assert!(TailCommand.execute(&args, &mut state).is_err());
+        let args = ["-n", "1", "app.log"].map(String::from);
+        assert_eq!(TailCommand.execute(&args, &mut state).unwrap(), "one");
+    }
+}
@@ -18,7 +18,7 @@ impl Command for TrCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "tr [-d] <set1> [set2] <file>"
+        "tr [-d] <set1> [set2] [file]"
     }
 
     fn extended_help(&self) -> String {
@@ -89,6 +89,15 @@ RELATED COMMANDS:
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
+        self.execute_with_stdin(args, None, state)
+    }
+
+    fn execute_with_stdin(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+    ) -> Result<String> {
         let mut delete = false;
         let mut squeeze = false;
         let mut positional: Vec<&String> = Vec::new();
@@ -98,7 +107,7 @@ RELATED COMMANDS:
                 "-d" | "--delete" => delete = true,
                 "-s" | "--squeeze-repeats" => squeeze = true,
                 "-h" | "--help" => {
-                    return Ok("Usage: tr [OPTIONS] <set1> [set2] <file>\n\
+                    return Ok("Usage: tr [OPTIONS] <set1> [set2] [file]\n\
                         Options:\n  \
                         -d    Delete characters in set1\n  \
                         -s    Squeeze repeated characters"
@@ -109,31 +118,35 @@ RELATED COMMANDS:
             }
         }
 
-        if delete {
-            // tr -d <set> <file>
-            if positional.len() < 2 {
-                return Err(anyhow::anyhow!("tr: missing operand"));
+        // Read the file after the sets, or piped input
+        let read_input = |file: Option<&&String>| match file {
+            Some(file) => {
+                let path = state.resolve_path(file);
+                fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("tr: {}: {}", file, e))
             }
-            let set1 = positional[0];
-            let file = positional[1];
-            let path = state.resolve_path(file);
-            let content =
-                fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("tr: {}: {}", file, e))?;
+            None => stdin
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("tr: missing operand")),
+        };
+
+        if delete {
+            // tr -d <set> [file]
+            let set1 = positional
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("tr: missing operand"))?;
+            let content = read_input(positional.get(1))?;
 
             let result: String = content.chars().filter(|c| !set1.contains(*c)).collect();
 
             Ok(result)
         } else {
-            // tr <set1> <set2> <file>
-            if positional.len() < 3 {
+            // tr <set1> <set2> [file]
+            if positional.len() < 2 {
                 return Err(anyhow::anyhow!("tr: missing operand"));
             }
             let set1 = positional[0];
             let set2 = positional[1];
-            let file = positional[2];
-            let path = state.resolve_path(file);
-            let content =
-                fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("tr: {}: {}", file, e))?;
+            let content = read_input(positional.get(2))?;
 
             let chars1: Vec<char> = expand_set(set1);
             let chars2: Vec<char> = expand_set(set2);
@@ -171,6 +184,10 @@ RELATED COMMANDS:
             Ok(result)
         }
     }
+
+    fn supports_stdin(&self) -> bool {
+        true
+    }
 }
 
 fn expand_set(set: &str) -> Vec<char> {
@@ -200,3 +200,22 @@ RELATED COMMANDS:
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{assert_golden, run, run_stdin, Fixture, MockTerminalState};
+
+    #[test]
+    fn test_wc_golden() {
+        let fixture = Fixture::new("wc")
+            .file("a.txt", "one two\nthree\n")
+            .file("b.txt", "four\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let output = run(&WcCommand, &["a.txt", "b.txt"], &mut state).unwrap();
+        assert_golden("wc_files", &fixture.scrub(&output));
+
+        let output = run_stdin(&WcCommand, &["-l"], "x\ny\nz\n", &mut state).unwrap();
+        assert_eq!(output.trim(), "3");
+    }
+}
@@ -8,11 +8,12 @@ use anyhow::{anyhow, Result};
 use std::os::windows::process::CommandExt;
 use std::process::Command;
 
+use super::git::GgCommand;
 use super::traits::Command as CommandTrait;
 use crate::terminal::state::TerminalState;
 
 /// Generic tool executor - runs a command with args
-fn run_tool(program: &str, args: &[String], cwd: &std::path::Path) -> Result<String> {
+fn run_tool(program: &str, args: &[String], state: &TerminalState) -> Result<String> {
     // On Windows, use cmd /C to handle both .exe and .cmd/.bat files
     // CREATE_NO_WINDOW (0x08000000) prevents console window flash and speeds up execution
     #[cfg(windows)]
@@ -22,7 +23,8 @@ fn run_tool(program: &str, args: &[String], cwd: &std::path::Path) -> Result<Str
     let output = Command::new("cmd")
         .args(["/C", program])
         .args(args)
-        .current_dir(cwd)
+        .current_dir(state.cwd())
+        .envs(state.exported_vars())
         .creation_flags(CREATE_NO_WINDOW)
         .output()
         .map_err(|e| {
@@ -36,7 +38,8 @@ fn run_tool(program: &str, args: &[String], cwd: &std::path::Path) -> Result<Str
     #[cfg(not(windows))]
     let output = Command::new(program)
         .args(args)
-        .current_dir(cwd)
+        .current_dir(state.cwd())
+        .envs(state.exported_vars())
         .output()
         .map_err(|e| {
             if e.kind() == std::io::ErrorKind::NotFound {
@@ -81,7 +84,7 @@ impl CommandTrait for NpmCommand {
         if args.is_empty() {
             return Ok("Usage: npm <command>\n\nCommon commands:\n  npm install    Install dependencies\n  npm run        Run scripts\n  npm start      Start the app\n  npm test       Run tests\n  npm init       Create package.json".to_string());
         }
-        run_tool("npm", args, state.cwd())
+        run_tool("npm", args, state)
     }
 }
 
@@ -103,7 +106,7 @@ impl CommandTrait for NpxCommand {
         if args.is_empty() {
             return Err(anyhow!("Usage: npx <package> [args...]"));
         }
-        run_tool("npx", args, state.cwd())
+        run_tool("npx", args, state)
     }
 }
 
@@ -122,7 +125,7 @@ impl CommandTrait for YarnCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("yarn", args, state.cwd())
+        run_tool("yarn", args, state)
     }
 }
 
@@ -141,7 +144,7 @@ impl CommandTrait for PnpmCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("pnpm", args, state.cwd())
+        run_tool("pnpm", args, state)
     }
 }
 
@@ -160,7 +163,7 @@ impl CommandTrait for BunCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("bun", args, state.cwd())
+        run_tool("bun", args, state)
     }
 }
 
@@ -181,9 +184,9 @@ impl CommandTrait for NodeCommand {
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
             // Show version
-            run_tool("node", &["--version".to_string()], state.cwd())
+            run_tool("node", &["--version".to_string()], state)
         } else {
-            run_tool("node", args, state.cwd())
+            run_tool("node", args, state)
         }
     }
 }
@@ -203,7 +206,7 @@ impl CommandTrait for DenoCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("deno", args, state.cwd())
+        run_tool("deno", args, state)
     }
 }
 
@@ -225,9 +228,9 @@ impl CommandTrait for PythonCommand {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("python", &["--version".to_string()], state.cwd())
+            run_tool("python", &["--version".to_string()], state)
         } else {
-            run_tool("python", args, state.cwd())
+            run_tool("python", args, state)
         }
     }
 }
@@ -248,9 +251,9 @@ impl CommandTrait for Python3Command {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("python3", &["--version".to_string()], state.cwd())
+            run_tool("python3", &["--version".to_string()], state)
         } else {
-            run_tool("python3", args, state.cwd())
+            run_tool("python3", args, state)
         }
     }
 }
@@ -273,7 +276,7 @@ impl CommandTrait for PipCommand {
         if args.is_empty() {
             return Ok("Usage: pip <command>\n\nCommon commands:\n  pip install <pkg>    Install package\n  pip uninstall <pkg>  Remove package\n  pip list             List installed packages\n  pip freeze           Output requirements format\n  pip search <query>   Search packages".to_string());
         }
-        run_tool("pip", args, state.cwd())
+        run_tool("pip", args, state)
     }
 }
 
@@ -292,7 +295,7 @@ impl CommandTrait for Pip3Command {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("pip3", args, state.cwd())
+        run_tool("pip3", args, state)
     }
 }
 
@@ -311,7 +314,7 @@ impl CommandTrait for UvCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("uv", args, state.cwd())
+        run_tool("uv", args, state)
     }
 }
 
@@ -330,7 +333,7 @@ impl CommandTrait for PoetryCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("poetry", args, state.cwd())
+        run_tool("poetry", args, state)
     }
 }
 
@@ -354,7 +357,7 @@ impl CommandTrait for CargoCommand {
         if args.is_empty() {
             return Ok("Usage: cargo <command>\n\nCommon commands:\n  cargo build      Compile the project\n  cargo run        Build and run\n  cargo test       Run tests\n  cargo check      Check for errors\n  cargo clippy     Run linter\n  cargo fmt        Format code\n  cargo add <pkg>  Add dependency".to_string());
         }
-        run_tool("cargo", args, state.cwd())
+        run_tool("cargo", args, state)
     }
 }
 
@@ -374,9 +377,9 @@ impl CommandTrait for RustcCommand {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("rustc", &["--version".to_string()], state.cwd())
+            run_tool("rustc", &["--version".to_string()], state)
         } else {
-            run_tool("rustc", args, state.cwd())
+            run_tool("rustc", args, state)
         }
     }
 }
@@ -396,7 +399,7 @@ impl CommandTrait for RustupCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("rustup", args, state.cwd())
+        run_tool("rustup", args, state)
     }
 }
 
@@ -420,7 +423,7 @@ impl CommandTrait for GoCommand {
         if args.is_empty() {
             return Ok("Usage: go <command>\n\nCommon commands:\n  go build       Compile packages\n  go run         Compile and run\n  go test        Run tests\n  go get         Download packages\n  go mod init    Initialize module\n  go fmt         Format code".to_string());
         }
-        run_tool("go", args, state.cwd())
+        run_tool("go", args, state)
     }
 }
 
@@ -442,9 +445,9 @@ impl CommandTrait for JavaCommand {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("java", &["--version".to_string()], state.cwd())
+            run_tool("java", &["--version".to_string()], state)
         } else {
-            run_tool("java", args, state.cwd())
+            run_tool("java", args, state)
         }
     }
 }
@@ -464,7 +467,7 @@ impl CommandTrait for JavacCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("javac", args, state.cwd())
+        run_tool("javac", args, state)
     }
 }
 
@@ -483,7 +486,7 @@ impl CommandTrait for MvnCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("mvn", args, state.cwd())
+        run_tool("mvn", args, state)
     }
 }
 
@@ -502,7 +505,7 @@ impl CommandTrait for GradleCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gradle", args, state.cwd())
+        run_tool("gradle", args, state)
     }
 }
 
@@ -526,7 +529,7 @@ impl CommandTrait for DotnetCommand {
         if args.is_empty() {
             return Ok("Usage: dotnet <command>\n\nCommon commands:\n  dotnet new        Create new project\n  dotnet build      Build project\n  dotnet run        Run project\n  dotnet test       Run tests\n  dotnet add        Add package/reference".to_string());
         }
-        run_tool("dotnet", args, state.cwd())
+        run_tool("dotnet", args, state)
     }
 }
 
@@ -550,7 +553,7 @@ impl CommandTrait for DockerCommand {
         if args.is_empty() {
             return Ok("Usage: docker <command>\n\nCommon commands:\n  docker ps          List containers\n  docker images      List images\n  docker run         Run container\n  docker build       Build image\n  docker compose     Docker Compose".to_string());
         }
-        run_tool("docker", args, state.cwd())
+        run_tool("docker", args, state)
     }
 }
 
@@ -569,7 +572,7 @@ impl CommandTrait for KubectlCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("kubectl", args, state.cwd())
+        run_tool("kubectl", args, state)
     }
 }
 
@@ -590,7 +593,7 @@ impl CommandTrait for MakeCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("make", args, state.cwd())
+        run_tool("make", args, state)
     }
 }
 
@@ -609,7 +612,7 @@ impl CommandTrait for CmakeCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("cmake", args, state.cwd())
+        run_tool("cmake", args, state)
     }
 }
 
@@ -807,7 +810,11 @@ RELATED COMMANDS:
         if args.is_empty() {
             return Ok("Usage: git <command>\n\nCommon commands:\n  git status       Show status\n  git add          Stage changes\n  git commit       Commit changes\n  git push         Push to remote\n  git pull         Pull from remote\n  git log          Show history\n  git diff         Show changes\n  git branch       List branches\n  git checkout     Switch branches\n\nRun 'git --help' for complete beginner guide!".to_string());
         }
-        run_tool("git", args, state.cwd())
+        // `git graph` is the built-in commit graph
+        if args[0] == "graph" {
+            return GgCommand.execute(&args[1..], state);
+        }
+        run_tool("git", args, state)
     }
 }
 
@@ -829,9 +836,9 @@ impl CommandTrait for RubyCommand {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("ruby", &["--version".to_string()], state.cwd())
+            run_tool("ruby", &["--version".to_string()], state)
         } else {
-            run_tool("ruby", args, state.cwd())
+            run_tool("ruby", args, state)
         }
     }
 }
@@ -851,7 +858,7 @@ impl CommandTrait for GemCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gem", args, state.cwd())
+        run_tool("gem", args, state)
     }
 }
 
@@ -870,7 +877,7 @@ impl CommandTrait for BundleCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("bundle", args, state.cwd())
+        run_tool("bundle", args, state)
     }
 }
 
@@ -890,9 +897,9 @@ impl CommandTrait for PhpCommand {
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
         if args.is_empty() {
-            run_tool("php", &["--version".to_string()], state.cwd())
+            run_tool("php", &["--version".to_string()], state)
         } else {
-            run_tool("php", args, state.cwd())
+            run_tool("php", args, state)
         }
     }
 }
@@ -912,7 +919,7 @@ impl CommandTrait for ComposerCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("composer", args, state.cwd())
+        run_tool("composer", args, state)
     }
 }
 
@@ -931,7 +938,7 @@ impl CommandTrait for SwiftCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("swift", args, state.cwd())
+        run_tool("swift", args, state)
     }
 }
 
@@ -950,7 +957,7 @@ impl CommandTrait for ZigCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("zig", args, state.cwd())
+        run_tool("zig", args, state)
     }
 }
 
@@ -969,7 +976,7 @@ impl CommandTrait for GccCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gcc", args, state.cwd())
+        run_tool("gcc", args, state)
     }
 }
 
@@ -988,7 +995,7 @@ impl CommandTrait for GppCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("g++", args, state.cwd())
+        run_tool("g++", args, state)
     }
 }
 
@@ -1007,7 +1014,7 @@ impl CommandTrait for ClangCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("clang", args, state.cwd())
+        run_tool("clang", args, state)
     }
 }
 
@@ -1033,7 +1040,7 @@ impl CommandTrait for CodeCommand {
         } else {
             args.to_vec()
         };
-        run_tool("code", &args, state.cwd())?;
+        run_tool("code", &args, state)?;
         Ok("Opening in VS Code...".to_string())
     }
 }
@@ -1058,7 +1065,7 @@ impl CommandTrait for CursorCommand {
         } else {
             args.to_vec()
         };
-        run_tool("cursor", &args, state.cwd())?;
+        run_tool("cursor", &args, state)?;
         Ok("Opening in Cursor...".to_string())
     }
 }
@@ -1078,30 +1085,11 @@ impl CommandTrait for SublCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("subl", args, state.cwd())?;
+        run_tool("subl", args, state)?;
         Ok("Opening in Sublime Text...".to_string())
     }
 }
 
-/// ssh - SSH client
-pub struct SshCommand;
-
-impl CommandTrait for SshCommand {
-    fn name(&self) -> &'static str {
-        "ssh"
-    }
-    fn description(&self) -> &'static str {
-        "SSH client"
-    }
-    fn usage(&self) -> &'static str {
-        "ssh [user@]host"
-    }
-
-    fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ssh", args, state.cwd())
-    }
-}
-
 /// scp - Secure copy
 pub struct ScpCommand;
 
@@ -1115,9 +1103,12 @@ impl CommandTrait for ScpCommand {
     fn usage(&self) -> &'static str {
         "scp <source> <dest>"
     }
+    fn uses_network(&self) -> bool {
+        true
+    }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("scp", args, state.cwd())
+        run_tool("scp", args, state)
     }
 }
 
@@ -1134,9 +1125,12 @@ impl CommandTrait for RsyncCommand {
     fn usage(&self) -> &'static str {
         "rsync [options] <source> <dest>"
     }
+    fn uses_network(&self) -> bool {
+        true
+    }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("rsync", args, state.cwd())
+        run_tool("rsync", args, state)
     }
 }
 
@@ -1155,7 +1149,7 @@ impl CommandTrait for GhCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gh", args, state.cwd())
+        run_tool("gh", args, state)
     }
 }
 
@@ -1174,7 +1168,7 @@ impl CommandTrait for AwsCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("aws", args, state.cwd())
+        run_tool("aws", args, state)
     }
 }
 
@@ -1193,7 +1187,7 @@ impl CommandTrait for AzCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("az", args, state.cwd())
+        run_tool("az", args, state)
     }
 }
 
@@ -1212,7 +1206,7 @@ impl CommandTrait for GcloudCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gcloud", args, state.cwd())
+        run_tool("gcloud", args, state)
     }
 }
 
@@ -1231,7 +1225,7 @@ impl CommandTrait for TerraformCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("terraform", args, state.cwd())
+        run_tool("terraform", args, state)
     }
 }
 
@@ -1250,7 +1244,7 @@ impl CommandTrait for AnsibleCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ansible", args, state.cwd())
+        run_tool("ansible", args, state)
     }
 }
 
@@ -1269,7 +1263,7 @@ impl CommandTrait for FfmpegCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ffmpeg", args, state.cwd())
+        run_tool("ffmpeg", args, state)
     }
 }
 
@@ -1288,7 +1282,7 @@ impl CommandTrait for ConvertCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("convert", args, state.cwd())
+        run_tool("convert", args, state)
     }
 }
 
@@ -1309,7 +1303,7 @@ impl CommandTrait for ClangppCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("clang++", args, state.cwd())
+        run_tool("clang++", args, state)
     }
 }
 
@@ -1328,7 +1322,7 @@ impl CommandTrait for LdCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ld", args, state.cwd())
+        run_tool("ld", args, state)
     }
 }
 
@@ -1347,7 +1341,7 @@ impl CommandTrait for ArCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ar", args, state.cwd())
+        run_tool("ar", args, state)
     }
 }
 
@@ -1366,7 +1360,7 @@ impl CommandTrait for NmCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("nm", args, state.cwd())
+        run_tool("nm", args, state)
     }
 }
 
@@ -1385,7 +1379,7 @@ impl CommandTrait for ObjdumpCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("objdump", args, state.cwd())
+        run_tool("objdump", args, state)
     }
 }
 
@@ -1404,7 +1398,7 @@ impl CommandTrait for GdbCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gdb", args, state.cwd())
+        run_tool("gdb", args, state)
     }
 }
 
@@ -1423,7 +1417,7 @@ impl CommandTrait for LldbCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("lldb", args, state.cwd())
+        run_tool("lldb", args, state)
     }
 }
 
@@ -1442,7 +1436,7 @@ impl CommandTrait for ValgrindCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("valgrind", args, state.cwd())
+        run_tool("valgrind", args, state)
     }
 }
 
@@ -1463,7 +1457,7 @@ impl CommandTrait for NasmCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("nasm", args, state.cwd())
+        run_tool("nasm", args, state)
     }
 }
 
@@ -1482,7 +1476,7 @@ impl CommandTrait for AsCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("as", args, state.cwd())
+        run_tool("as", args, state)
     }
 }
 
@@ -1503,7 +1497,7 @@ impl CommandTrait for GhcCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ghc", args, state.cwd())
+        run_tool("ghc", args, state)
     }
 }
 
@@ -1522,7 +1516,7 @@ impl CommandTrait for CabalCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("cabal", args, state.cwd())
+        run_tool("cabal", args, state)
     }
 }
 
@@ -1541,7 +1535,7 @@ impl CommandTrait for StackCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("stack", args, state.cwd())
+        run_tool("stack", args, state)
     }
 }
 
@@ -1562,7 +1556,7 @@ impl CommandTrait for ElixirCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("elixir", args, state.cwd())
+        run_tool("elixir", args, state)
     }
 }
 
@@ -1581,7 +1575,7 @@ impl CommandTrait for MixCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("mix", args, state.cwd())
+        run_tool("mix", args, state)
     }
 }
 
@@ -1600,7 +1594,7 @@ impl CommandTrait for IexCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("iex", args, state.cwd())
+        run_tool("iex", args, state)
     }
 }
 
@@ -1619,7 +1613,7 @@ impl CommandTrait for ErlCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("erl", args, state.cwd())
+        run_tool("erl", args, state)
     }
 }
 
@@ -1640,7 +1634,7 @@ impl CommandTrait for ScalaCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("scala", args, state.cwd())
+        run_tool("scala", args, state)
     }
 }
 
@@ -1659,7 +1653,7 @@ impl CommandTrait for SbtCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("sbt", args, state.cwd())
+        run_tool("sbt", args, state)
     }
 }
 
@@ -1678,7 +1672,7 @@ impl CommandTrait for KotlinCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("kotlin", args, state.cwd())
+        run_tool("kotlin", args, state)
     }
 }
 
@@ -1697,7 +1691,7 @@ impl CommandTrait for KotlincCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("kotlinc", args, state.cwd())
+        run_tool("kotlinc", args, state)
     }
 }
 
@@ -1718,7 +1712,7 @@ impl CommandTrait for LuaCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("lua", args, state.cwd())
+        run_tool("lua", args, state)
     }
 }
 
@@ -1737,7 +1731,7 @@ impl CommandTrait for LuarocksCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("luarocks", args, state.cwd())
+        run_tool("luarocks", args, state)
     }
 }
 
@@ -1756,7 +1750,7 @@ impl CommandTrait for PerlCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("perl", args, state.cwd())
+        run_tool("perl", args, state)
     }
 }
 
@@ -1775,7 +1769,7 @@ impl CommandTrait for RCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("R", args, state.cwd())
+        run_tool("R", args, state)
     }
 }
 
@@ -1794,7 +1788,7 @@ impl CommandTrait for RscriptCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("Rscript", args, state.cwd())
+        run_tool("Rscript", args, state)
     }
 }
 
@@ -1813,7 +1807,7 @@ impl CommandTrait for JuliaCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("julia", args, state.cwd())
+        run_tool("julia", args, state)
     }
 }
 
@@ -1832,7 +1826,7 @@ impl CommandTrait for OcamlCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ocaml", args, state.cwd())
+        run_tool("ocaml", args, state)
     }
 }
 
@@ -1851,7 +1845,7 @@ impl CommandTrait for OpamCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("opam", args, state.cwd())
+        run_tool("opam", args, state)
     }
 }
 
@@ -1870,7 +1864,7 @@ impl CommandTrait for DuneCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("dune", args, state.cwd())
+        run_tool("dune", args, state)
     }
 }
 
@@ -1889,7 +1883,7 @@ impl CommandTrait for RacketCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("racket", args, state.cwd())
+        run_tool("racket", args, state)
     }
 }
 
@@ -1908,7 +1902,7 @@ impl CommandTrait for SbclCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("sbcl", args, state.cwd())
+        run_tool("sbcl", args, state)
     }
 }
 
@@ -1927,7 +1921,7 @@ impl CommandTrait for GfortranCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("gfortran", args, state.cwd())
+        run_tool("gfortran", args, state)
     }
 }
 
@@ -1946,7 +1940,7 @@ impl CommandTrait for CobolCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("cobc", args, state.cwd())
+        run_tool("cobc", args, state)
     }
 }
 
@@ -1967,7 +1961,7 @@ impl CommandTrait for PrettierCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("prettier", args, state.cwd())
+        run_tool("prettier", args, state)
     }
 }
 
@@ -1986,7 +1980,7 @@ impl CommandTrait for EslintCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("eslint", args, state.cwd())
+        run_tool("eslint", args, state)
     }
 }
 
@@ -2005,7 +1999,7 @@ impl CommandTrait for BlackCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("black", args, state.cwd())
+        run_tool("black", args, state)
     }
 }
 
@@ -2024,7 +2018,7 @@ impl CommandTrait for RuffCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("ruff", args, state.cwd())
+        run_tool("ruff", args, state)
     }
 }
 
@@ -2043,7 +2037,7 @@ impl CommandTrait for MypyCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("mypy", args, state.cwd())
+        run_tool("mypy", args, state)
     }
 }
 
@@ -2062,7 +2056,7 @@ impl CommandTrait for PytestCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("pytest", args, state.cwd())
+        run_tool("pytest", args, state)
     }
 }
 
@@ -2081,7 +2075,7 @@ impl CommandTrait for JestCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("jest", args, state.cwd())
+        run_tool("jest", args, state)
     }
 }
 
@@ -2100,6 +2094,6 @@ impl CommandTrait for VitestCommand {
     }
 
     fn execute(&self, args: &[String], state: &mut TerminalState) -> Result<String> {
-        run_tool("vitest", args, state.cwd())
+        run_tool("vitest", args, state)
     }
 }
@@ -6,6 +6,7 @@
 
 use anyhow::Result;
 
+use super::context::CommandContext;
 use crate::terminal::state::TerminalState;
 
 /// Trait for built-in commands
@@ -45,10 +46,31 @@ pub trait Command: Send + Sync {
         self.execute(args, state)
     }
 
+    /// Execute the command with a context for cancellation and progress.
+    /// Long-running commands override this, call `ctx.check()` between
+    /// units of work, and have `execute` pass `CommandContext::default()`.
+    /// Default implementation ignores the context and calls execute_with_stdin
+    fn execute_with_context(
+        &self,
+        args: &[String],
+        stdin: Option<&str>,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let _ = ctx;
+        self.execute_with_stdin(args, stdin, state)
+    }
+
     /// Whether this command supports reading from stdin
     fn supports_stdin(&self) -> bool {
         false
     }
+
+    /// Whether this command talks to the network (always disabled in
+    /// restricted mode)
+    fn uses_network(&self) -> bool {
+        false
+    }
 }
 
 /// Result of command execution
@@ -1,26 +1,39 @@
 //! User-defined aliases
 //!
-//! Custom command shortcuts.
+//! Custom command shortcuts, global aliases expanded anywhere in a command
+//! line, and suffix aliases that open files by extension. Aliases made with
+//! `alias name=value` are kept in aliases.toml next to config.toml.
 
 #![allow(dead_code)]
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 /// Alias configuration
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct AliasConfig {
     /// User-defined aliases (alias -> expansion)
     #[serde(default)]
     pub commands: HashMap<String, String>,
+    /// Suffix aliases (extension -> handler): typing `notes.md` runs the
+    /// handler with the file, or fills `{}` in a command template
+    #[serde(default)]
+    pub suffix: HashMap<String, String>,
+    /// Global aliases (word -> expansion), expanded anywhere in the line
+    #[serde(default)]
+    pub global: HashMap<String, String>,
+    /// Aliases saved by `alias name=value` (aliases.toml), which win over
+    /// `commands`
+    #[serde(skip)]
+    pub saved: HashMap<String, String>,
 }
 
 impl AliasConfig {
     /// Create new empty alias config
     pub fn new() -> Self {
-        Self {
-            commands: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Add a default set of useful aliases
@@ -36,12 +49,18 @@ impl AliasConfig {
         // Git shortcuts are handled separately in executor
         // but can also be defined here for customization
 
-        Self { commands: aliases }
+        Self {
+            commands: aliases,
+            ..Self::default()
+        }
     }
 
     /// Look up an alias
     pub fn get(&self, alias: &str) -> Option<&str> {
-        self.commands.get(alias).map(|s| s.as_str())
+        self.saved
+            .get(alias)
+            .or_else(|| self.commands.get(alias))
+            .map(|s| s.as_str())
     }
 
     /// Add an alias
@@ -54,8 +73,76 @@ impl AliasConfig {
         self.commands.remove(alias).is_some()
     }
 
+    /// Handler for a file name's extension (case-insensitive, with or
+    /// without the leading dot in the config)
+    pub fn suffix_handler(&self, file: &str) -> Option<&str> {
+        let extension = std::path::Path::new(file).extension()?.to_str()?;
+        self.suffix
+            .iter()
+            .find(|(ext, _)| ext.trim_start_matches('.').eq_ignore_ascii_case(extension))
+            .map(|(_, handler)| handler.as_str())
+    }
+
     /// List all aliases
     pub fn list(&self) -> impl Iterator<Item = (&str, &str)> {
-        self.commands.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+        self.commands
+            .iter()
+            .filter(|(k, _)| !self.saved.contains_key(*k))
+            .chain(&self.saved)
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Where `alias name=value` saves aliases
+pub fn saved_path() -> PathBuf {
+    super::paths::config_dir().join("aliases.toml")
+}
+
+/// Aliases saved in `path` (none if it's missing or unreadable)
+pub fn load_saved(path: &Path) -> HashMap<String, String> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    toml::from_str(&contents).unwrap_or_else(|e| {
+        eprintln!("Failed to parse {}: {}", path.display(), e);
+        HashMap::new()
+    })
+}
+
+/// Write `aliases` to `path`, sorted by name
+pub fn save_saved(path: &Path, aliases: &HashMap<String, String>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let sorted: BTreeMap<_, _> = aliases.iter().collect();
+    let contents = format!(
+        "# Aliases made with `alias name=value` (removed with `unalias name`)\n{}",
+        toml::to_string(&sorted)?
+    );
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_aliases() {
+        let path = std::env::temp_dir().join(format!("zaxiom-aliases-{}.toml", std::process::id()));
+        let mut saved = HashMap::new();
+        saved.insert("gs".to_string(), "git status".to_string());
+        saved.insert("..".to_string(), "cd ..".to_string());
+        save_saved(&path, &saved).unwrap();
+        assert_eq!(load_saved(&path), saved);
+        let _ = std::fs::remove_file(&path);
+        assert!(load_saved(&path).is_empty());
+
+        // Saved aliases win over config.toml's
+        let mut config = AliasConfig::with_defaults();
+        config.saved.insert("ll".to_string(), "ls -l".to_string());
+        assert_eq!(config.get("ll"), Some("ls -l"));
+        assert_eq!(config.get("la"), Some("ls -a"));
+        assert_eq!(config.list().filter(|(name, _)| *name == "ll").count(), 1);
     }
 }
@@ -8,6 +8,11 @@ use std::path::PathBuf;
 
 /// Get the path to the .env file in the project/user directory
 pub fn get_env_file_path() -> PathBuf {
+    // Portable mode and --config-dir keep it alongside the config
+    if super::paths::get().mode != super::paths::StorageMode::Installed {
+        return super::paths::config_dir().join(".env");
+    }
+
     // Try current directory first, then fall back to home directory
     let current_dir = std::env::current_dir().unwrap_or_default();
     let env_path = current_dir.join(".env");
@@ -0,0 +1,569 @@
+//! Rebindable keyboard shortcuts
+//!
+//! App-level shortcuts are looked up through a `Keymap` of actions to key
+//! chords rather than matched on fixed keys. Bindings changed with `keys`
+//! (or by hand) are kept in keybindings.toml next to config.toml, which only
+//! lists the actions that differ from the defaults:
+//!
+//! ```toml
+//! split_horizontal = "Ctrl+Shift+X"
+//! zoom_in = ["Ctrl+=", "Ctrl+Plus"]
+//! git_panel = []
+//! ```
+//!
+//! Line editing keys, Ctrl+W, Ctrl+1-9, and the keys of modal overlays
+//! (palette, finders, vi and hints modes) stay fixed.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{LazyLock, RwLock};
+
+use anyhow::{anyhow, Result};
+use eframe::egui::{InputState, Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Something a shortcut can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    NewTab,
+    NextTab,
+    PrevTab,
+    SplitHorizontal,
+    SplitVertical,
+    NextPane,
+    PrevPane,
+    ResizeLeft,
+    ResizeRight,
+    ResizeUp,
+    ResizeDown,
+    Search,
+    FilterOutput,
+    HistorySearch,
+    FindFile,
+    RecentFiles,
+    JumpToDirectory,
+    PickSearchResult,
+    SwitchBranch,
+    CommandPalette,
+    ViMode,
+    HintsMode,
+    GitPanel,
+    Shortcuts,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    CollapseBlocks,
+    CopyInput,
+    Paste,
+    PasteRaw,
+    Interrupt,
+    Suspend,
+    Clear,
+    InsertLastArg,
+}
+
+impl Action {
+    /// Every action, in the order `keys` lists them
+    pub const ALL: [Action; 35] = [
+        Action::NewTab,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::SplitHorizontal,
+        Action::SplitVertical,
+        Action::NextPane,
+        Action::PrevPane,
+        Action::ResizeLeft,
+        Action::ResizeRight,
+        Action::ResizeUp,
+        Action::ResizeDown,
+        Action::Search,
+        Action::FilterOutput,
+        Action::HistorySearch,
+        Action::FindFile,
+        Action::RecentFiles,
+        Action::JumpToDirectory,
+        Action::PickSearchResult,
+        Action::SwitchBranch,
+        Action::CommandPalette,
+        Action::ViMode,
+        Action::HintsMode,
+        Action::GitPanel,
+        Action::Shortcuts,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetZoom,
+        Action::CollapseBlocks,
+        Action::CopyInput,
+        Action::Paste,
+        Action::PasteRaw,
+        Action::Interrupt,
+        Action::Suspend,
+        Action::Clear,
+        Action::InsertLastArg,
+    ];
+
+    /// Name in keybindings.toml and for `keys`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::NewTab => "new_tab",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::SplitHorizontal => "split_horizontal",
+            Action::SplitVertical => "split_vertical",
+            Action::NextPane => "next_pane",
+            Action::PrevPane => "prev_pane",
+            Action::ResizeLeft => "resize_left",
+            Action::ResizeRight => "resize_right",
+            Action::ResizeUp => "resize_up",
+            Action::ResizeDown => "resize_down",
+            Action::Search => "search",
+            Action::FilterOutput => "filter_output",
+            Action::HistorySearch => "history_search",
+            Action::FindFile => "find_file",
+            Action::RecentFiles => "recent_files",
+            Action::JumpToDirectory => "jump_to_directory",
+            Action::PickSearchResult => "pick_search_result",
+            Action::SwitchBranch => "switch_branch",
+            Action::CommandPalette => "command_palette",
+            Action::ViMode => "vi_mode",
+            Action::HintsMode => "hints_mode",
+            Action::GitPanel => "git_panel",
+            Action::Shortcuts => "shortcuts",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ResetZoom => "reset_zoom",
+            Action::CollapseBlocks => "collapse_blocks",
+            Action::CopyInput => "copy_input",
+            Action::Paste => "paste",
+            Action::PasteRaw => "paste_raw",
+            Action::Interrupt => "interrupt",
+            Action::Suspend => "suspend",
+            Action::Clear => "clear",
+            Action::InsertLastArg => "insert_last_arg",
+        }
+    }
+
+    /// What it is called in the shortcut overlay and the palette
+    pub fn title(self) -> &'static str {
+        match self {
+            Action::NewTab => "New Tab",
+            Action::NextTab => "Next Tab",
+            Action::PrevTab => "Previous Tab",
+            Action::SplitHorizontal => "Split Horizontal",
+            Action::SplitVertical => "Split Vertical",
+            Action::NextPane => "Next Pane",
+            Action::PrevPane => "Previous Pane",
+            Action::ResizeLeft => "Resize Pane Left",
+            Action::ResizeRight => "Resize Pane Right",
+            Action::ResizeUp => "Resize Pane Up",
+            Action::ResizeDown => "Resize Pane Down",
+            Action::Search => "Search",
+            Action::FilterOutput => "Filter Output",
+            Action::HistorySearch => "History Search",
+            Action::FindFile => "Find File",
+            Action::RecentFiles => "Recent Files",
+            Action::JumpToDirectory => "Jump to Directory",
+            Action::PickSearchResult => "Pick Search Result",
+            Action::SwitchBranch => "Switch Git Branch",
+            Action::CommandPalette => "Command Palette",
+            Action::ViMode => "Vi Mode",
+            Action::HintsMode => "Hints Mode",
+            Action::GitPanel => "Git Panel",
+            Action::Shortcuts => "Keyboard Shortcuts",
+            Action::ZoomIn => "Zoom In",
+            Action::ZoomOut => "Zoom Out",
+            Action::ResetZoom => "Reset Zoom",
+            Action::CollapseBlocks => "Collapse Previous Blocks",
+            Action::CopyInput => "Copy Input",
+            Action::Paste => "Paste",
+            Action::PasteRaw => "Paste with Newlines",
+            Action::Interrupt => "Interrupt / Clear Line",
+            Action::Suspend => "Suspend Running Command",
+            Action::Clear => "Clear",
+            Action::InsertLastArg => "Insert Last Argument",
+        }
+    }
+
+    /// Built-in chords
+    fn defaults(self) -> &'static [&'static str] {
+        match self {
+            Action::NewTab => &["Ctrl+T"],
+            Action::NextTab => &["Ctrl+Tab"],
+            Action::PrevTab => &["Ctrl+Shift+Tab"],
+            Action::SplitHorizontal => &["Ctrl+Shift+D"],
+            Action::SplitVertical => &["Ctrl+Shift+E"],
+            Action::NextPane => &["Alt+→"],
+            Action::PrevPane => &["Alt+←"],
+            Action::ResizeLeft => &["Ctrl+Shift+←"],
+            Action::ResizeRight => &["Ctrl+Shift+→"],
+            Action::ResizeUp => &["Ctrl+Shift+↑"],
+            Action::ResizeDown => &["Ctrl+Shift+↓"],
+            Action::Search => &["Ctrl+F"],
+            Action::FilterOutput => &["Ctrl+Shift+L"],
+            Action::HistorySearch => &["Ctrl+R"],
+            Action::FindFile => &["Ctrl+Shift+F"],
+            Action::RecentFiles => &["Ctrl+Shift+O"],
+            Action::JumpToDirectory => &["Ctrl+Shift+J"],
+            Action::PickSearchResult => &["Ctrl+Shift+S"],
+            Action::SwitchBranch => &["Ctrl+G"],
+            Action::CommandPalette => &["Ctrl+P"],
+            Action::ViMode => &["Ctrl+Shift+M"],
+            Action::HintsMode => &["Ctrl+Shift+H"],
+            Action::GitPanel => &["Ctrl+Shift+G"],
+            Action::Shortcuts => &["F1"],
+            Action::ZoomIn => &["Ctrl+=", "Ctrl+Plus"],
+            Action::ZoomOut => &["Ctrl+-"],
+            Action::ResetZoom => &["Ctrl+0"],
+            Action::CollapseBlocks => &["Ctrl+Shift+K"],
+            Action::CopyInput => &["Ctrl+Shift+C"],
+            Action::Paste => &["Ctrl+V"],
+            Action::PasteRaw => &["Ctrl+Shift+V"],
+            Action::Interrupt => &["Ctrl+C"],
+            Action::Suspend => &["Ctrl+Z"],
+            Action::Clear => &["Ctrl+L"],
+            Action::InsertLastArg => &["Alt+."],
+        }
+    }
+
+    /// Look an action up by its name (dashes work too) or title
+    pub fn find(name: &str) -> Option<Action> {
+        let name = name.trim().replace('-', "_");
+        Action::ALL
+            .into_iter()
+            .find(|a| a.name().eq_ignore_ascii_case(&name) || a.title().eq_ignore_ascii_case(&name))
+    }
+}
+
+/// A key with the modifiers held for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: Key,
+}
+
+impl Chord {
+    /// Whether the chord was pressed this frame. Modifiers must match
+    /// exactly, except that Shift is ignored for `+`, which needs it on
+    /// most layouts.
+    pub fn pressed(&self, input: &InputState) -> bool {
+        let Modifiers {
+            ctrl, shift, alt, ..
+        } = input.modifiers;
+        ctrl == self.ctrl
+            && alt == self.alt
+            && (shift == self.shift || (self.key == Key::Plus && !self.shift))
+            && input.key_pressed(self.key)
+    }
+}
+
+impl FromStr for Chord {
+    type Err = anyhow::Error;
+
+    /// `Ctrl+Shift+D`, `alt+.`, `F1`, `Ctrl++`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let mut chord = Chord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: parse_key(key).ok_or_else(|| anyhow!("unknown key '{}' in '{}'", key, s))?,
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" | "option" => chord.alt = true,
+                other => return Err(anyhow!("unknown modifier '{}' in '{}'", other, s)),
+            }
+        }
+        Ok(chord)
+    }
+}
+
+fn parse_key(key: &str) -> Option<Key> {
+    let key = key.trim();
+    match key {
+        "←" => Some(Key::ArrowLeft),
+        "→" => Some(Key::ArrowRight),
+        "↑" => Some(Key::ArrowUp),
+        "↓" => Some(Key::ArrowDown),
+        _ => Key::from_name(key).or_else(|| {
+            // Letters and names in any case (`ctrl+d`, `pageup`)
+            let mut chars = key.chars();
+            let capitalized: String = chars
+                .next()?
+                .to_uppercase()
+                .chain(chars.as_str().to_lowercase().chars())
+                .collect();
+            Key::from_name(&capitalized).or_else(|| {
+                Key::ALL
+                    .iter()
+                    .copied()
+                    .find(|k| k.name().eq_ignore_ascii_case(key))
+            })
+        }),
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        let key = match self.key {
+            Key::ArrowLeft => "←",
+            Key::ArrowRight => "→",
+            Key::ArrowUp => "↑",
+            Key::ArrowDown => "↓",
+            Key::Minus => "-",
+            key => key.symbol_or_name(),
+        };
+        f.write_str(key)
+    }
+}
+
+/// One chord or a list of them, as written in keybindings.toml
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum Chords {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Chords for every action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<Chord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let chords = action
+                    .defaults()
+                    .iter()
+                    .filter_map(|chord| chord.parse().ok())
+                    .collect();
+                (action, chords)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The defaults with the changes saved in `path` (an unreadable file or
+    /// bad entry is reported and skipped)
+    pub fn load(path: &Path) -> Self {
+        let mut keymap = Self::default();
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return keymap;
+        };
+        let saved: BTreeMap<String, Chords> = match toml::from_str(&contents) {
+            Ok(saved) => saved,
+            Err(e) => {
+                eprintln!("Failed to parse {}: {}", path.display(), e);
+                return keymap;
+            }
+        };
+        for (name, chords) in saved {
+            let Some(action) = Action::find(&name) else {
+                eprintln!("{}: unknown action '{}'", path.display(), name);
+                continue;
+            };
+            let chords = match chords {
+                Chords::One(chord) => vec![chord],
+                Chords::Many(chords) => chords,
+            };
+            match chords
+                .iter()
+                .map(|c| c.parse())
+                .collect::<Result<Vec<Chord>>>()
+            {
+                Ok(chords) => {
+                    keymap.bindings.insert(action, chords);
+                }
+                Err(e) => eprintln!("{}: {}", path.display(), e),
+            }
+        }
+        keymap
+    }
+
+    /// Write the bindings that differ from the defaults to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let defaults = Self::default();
+        let changed: BTreeMap<&str, Chords> = Action::ALL
+            .into_iter()
+            .filter(|action| self.chords(*action) != defaults.chords(*action))
+            .map(|action| {
+                let mut chords: Vec<String> =
+                    self.chords(action).iter().map(Chord::to_string).collect();
+                let chords = if chords.len() == 1 {
+                    Chords::One(chords.remove(0))
+                } else {
+                    Chords::Many(chords)
+                };
+                (action.name(), chords)
+            })
+            .collect();
+        let contents = format!(
+            "# Shortcuts changed with `keys` (run `keys` for the action names)\n{}",
+            toml::to_string(&changed)?
+        );
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Chords bound to `action` (none if it was unbound)
+    pub fn chords(&self, action: Action) -> &[Chord] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether any of `action`'s chords was pressed this frame
+    pub fn pressed(&self, input: &InputState, action: Action) -> bool {
+        self.chords(action).iter().any(|chord| chord.pressed(input))
+    }
+
+    /// First chord of `action`, as shown in menus and the overlay
+    pub fn label(&self, action: Action) -> Option<String> {
+        self.chords(action).first().map(Chord::to_string)
+    }
+
+    /// Bind `action` to `chords` alone, taking them from any action that
+    /// had them; returns the actions that lost a chord
+    pub fn bind(&mut self, action: Action, chords: Vec<Chord>) -> Vec<Action> {
+        let mut displaced = Vec::new();
+        for (other, bound) in self.bindings.iter_mut() {
+            if *other != action && bound.iter().any(|c| chords.contains(c)) {
+                bound.retain(|c| !chords.contains(c));
+                displaced.push(*other);
+            }
+        }
+        self.bindings.insert(action, chords);
+        displaced
+    }
+
+    /// Put `action` back on its default chords, like `bind`
+    pub fn reset(&mut self, action: Action) -> Vec<Action> {
+        let chords = Self::default().chords(action).to_vec();
+        self.bind(action, chords)
+    }
+
+    /// Whether `action` is on its default chords
+    pub fn is_default(&self, action: Action) -> bool {
+        self.chords(action) == Self::default().chords(action)
+    }
+}
+
+/// Where `keys` saves changed bindings
+pub fn saved_path() -> PathBuf {
+    super::paths::config_dir().join("keybindings.toml")
+}
+
+/// The keymap in use, shared with the overlay, menus, and palette
+static CURRENT: LazyLock<RwLock<Keymap>> = LazyLock::new(|| RwLock::new(Keymap::default()));
+
+/// A copy of the keymap in use
+pub fn current() -> Keymap {
+    CURRENT.read().map(|k| k.clone()).unwrap_or_default()
+}
+
+/// Make `keymap` the one in use
+pub fn set_current(keymap: Keymap) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = keymap;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chord_round_trip() {
+        for text in [
+            "Ctrl+Shift+D",
+            "Alt+.",
+            "F1",
+            "Ctrl+=",
+            "Ctrl+-",
+            "Alt+→",
+            "Ctrl+Tab",
+        ] {
+            let chord: Chord = text.parse().unwrap();
+            assert_eq!(chord.to_string(), text);
+        }
+        let chord: Chord = "ctrl+shift+d".parse().unwrap();
+        assert_eq!(chord.to_string(), "Ctrl+Shift+D");
+        assert_eq!("Ctrl++".parse::<Chord>().unwrap().key, Key::Plus);
+        assert_eq!("alt+left".parse::<Chord>().unwrap().to_string(), "Alt+←");
+        assert!("Hyper+D".parse::<Chord>().is_err());
+        assert!("Ctrl+Nope".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_defaults_parse() {
+        let keymap = Keymap::default();
+        for action in Action::ALL {
+            assert_eq!(
+                keymap.chords(action).len(),
+                action.defaults().len(),
+                "{}",
+                action.name()
+            );
+            assert_eq!(Action::find(action.name()), Some(action));
+        }
+        assert_eq!(
+            Action::find("Split-Horizontal"),
+            Some(Action::SplitHorizontal)
+        );
+        assert_eq!(Action::find("Vi Mode"), Some(Action::ViMode));
+    }
+
+    #[test]
+    fn test_bind_and_save() {
+        let mut keymap = Keymap::default();
+        let chord: Chord = "Ctrl+Shift+E".parse().unwrap();
+        assert_eq!(keymap.chords(Action::SplitVertical), [chord]);
+        let displaced = keymap.bind(Action::SplitHorizontal, vec![chord]);
+        assert_eq!(displaced, [Action::SplitVertical]);
+        assert!(keymap.chords(Action::SplitVertical).is_empty());
+        assert_eq!(
+            keymap.label(Action::SplitHorizontal).as_deref(),
+            Some("Ctrl+Shift+E")
+        );
+
+        let path = std::env::temp_dir().join(format!("zaxiom-keys-{}.toml", std::process::id()));
+        keymap.save(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("split_horizontal = \"Ctrl+Shift+E\""));
+        assert!(saved.contains("split_vertical = []"));
+        assert!(!saved.contains("new_tab"));
+        assert_eq!(Keymap::load(&path), keymap);
+
+        keymap.reset(Action::SplitHorizontal);
+        keymap.reset(Action::SplitVertical);
+        assert_eq!(keymap, Keymap::default());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Keymap::load(&path), Keymap::default());
+    }
+}
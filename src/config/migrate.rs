@@ -0,0 +1,183 @@
+//! Config schema versions
+//!
+//! config.toml records the schema version it was written with. When a
+//! release changes the schema (renamed keys, new theme names), older files
+//! are upgraded step by step on load, after copying the original to
+//! `config.toml.v<old>.bak`, so no setting is silently dropped.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use toml::{Table, Value};
+
+use super::theme::ThemeName;
+
+/// Schema version written by this release
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Schema changes in order: entry `n` upgrades version `n` to `n + 1`
+const MIGRATIONS: [fn(&mut Table); CONFIG_VERSION as usize] = [v0_to_v1, v1_to_v2];
+
+/// An upgrade that was applied to the config file
+#[derive(Debug)]
+pub struct Migration {
+    pub from: u32,
+    pub to: u32,
+    /// Copy of the file before migrating
+    pub backup: PathBuf,
+}
+
+/// Version a parsed config was written with (files from before versioning are 0)
+pub fn version_of(table: &Table) -> u32 {
+    table
+        .get("version")
+        .and_then(Value::as_integer)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Upgrade a parsed config to the current schema in place, returning the
+/// version it started at (newer versions are left alone)
+pub fn migrate(table: &mut Table) -> u32 {
+    let from = version_of(table);
+    if from >= CONFIG_VERSION {
+        return from;
+    }
+    for step in &MIGRATIONS[from as usize..] {
+        step(table);
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION.into()));
+    from
+}
+
+/// Migrate the config file at `path` if it is out of date: back it up, then
+/// rewrite it (keys zaxiom doesn't know about are kept)
+pub fn migrate_file(path: &Path, table: &mut Table) -> Result<Option<Migration>> {
+    let from = migrate(table);
+    if from >= CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", from));
+    let backup = PathBuf::from(backup);
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("could not back up config to {}", backup.display()))?;
+
+    let contents = toml::to_string_pretty(table)?;
+    std::fs::write(path, contents)
+        .with_context(|| format!("could not write {}", path.display()))?;
+
+    Ok(Some(Migration {
+        from,
+        to: CONFIG_VERSION,
+        backup,
+    }))
+}
+
+/// `kawaii_mode = true` became `[kawaii] level = "full"`, and theme aliases
+/// (`mocha`, `Tokyo Night`) are stored under their canonical names
+fn v0_to_v1(table: &mut Table) {
+    if let Some(Value::Boolean(true)) = table.remove("kawaii_mode") {
+        let kawaii = table
+            .entry("kawaii")
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Some(kawaii) = kawaii.as_table_mut() {
+            kawaii
+                .entry("level")
+                .or_insert_with(|| Value::String("full".to_string()));
+        }
+    }
+
+    if let Some(theme) = table.get_mut("theme").and_then(Value::as_table_mut) {
+        for key in ["name", "light", "dark"] {
+            if let Some(Value::String(name)) = theme.get_mut(key) {
+                if let Some(canonical) = ThemeName::from_string(name) {
+                    *name = canonical.config_key().to_string();
+                }
+            }
+        }
+    }
+}
+
+/// `[font] size` used to be written but never read (text was always 16pt);
+/// now that it sizes pane text, its old default of 14 becomes 16 so text
+/// doesn't shrink
+fn v1_to_v2(table: &mut Table) {
+    if let Some(font) = table.get_mut("font").and_then(Value::as_table_mut) {
+        if font.get("size").and_then(Value::as_float) == Some(14.0) {
+            font.insert("size".to_string(), Value::Float(16.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_v0() {
+        let mut table: Table = toml::from_str(
+            r#"
+            kawaii_mode = true
+
+            [theme]
+            name = "Tokyo Night"
+            dark = "mocha"
+            light = "my-custom"
+
+            [kawaii]
+            confetti = false
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut table), 0);
+        assert_eq!(version_of(&table), CONFIG_VERSION);
+        assert!(!table.contains_key("kawaii_mode"));
+        assert_eq!(table["kawaii"]["level"].as_str(), Some("full"));
+        assert_eq!(table["kawaii"]["confetti"].as_bool(), Some(false));
+        assert_eq!(table["theme"]["name"].as_str(), Some("tokyo-night"));
+        assert_eq!(table["theme"]["dark"].as_str(), Some("catppuccin-mocha"));
+        assert_eq!(table["theme"]["light"].as_str(), Some("my-custom"));
+
+        // Already current: nothing to do
+        assert_eq!(migrate(&mut table), CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_v1_font_size() {
+        let mut table: Table = toml::from_str("version = 1\n[font]\nsize = 14.0\n").unwrap();
+        assert_eq!(migrate(&mut table), 1);
+        assert_eq!(table["font"]["size"].as_float(), Some(16.0));
+
+        let mut table: Table = toml::from_str("version = 1\n[font]\nsize = 12.5\n").unwrap();
+        migrate(&mut table);
+        assert_eq!(table["font"]["size"].as_float(), Some(12.5));
+    }
+
+    #[test]
+    fn test_migrate_file_keeps_backup() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-migrate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let original = "kawaii_mode = false\nunknown_key = 3\n";
+        std::fs::write(&path, original).unwrap();
+
+        let mut table: Table = toml::from_str(original).unwrap();
+        let migration = migrate_file(&path, &mut table).unwrap().unwrap();
+        assert_eq!((migration.from, migration.to), (0, CONFIG_VERSION));
+        assert_eq!(migration.backup, dir.join("config.toml.v0.bak"));
+        assert_eq!(
+            std::fs::read_to_string(&migration.backup).unwrap(),
+            original
+        );
+
+        let migrated: Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(version_of(&migrated), CONFIG_VERSION);
+        assert_eq!(migrated["unknown_key"].as_integer(), Some(3));
+        assert!(migrate_file(&path, &mut table.clone()).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
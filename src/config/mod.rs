@@ -1,8 +1,12 @@
 //! Configuration management
 //!
-//! Handles loading/saving settings, themes, and user aliases.
+//! Handles loading/saving settings, themes, user aliases, keybindings, and
+//! where they are stored.
 
 pub mod aliases;
 pub mod env;
+pub mod keymap;
+pub mod migrate;
+pub mod paths;
 pub mod settings;
 pub mod theme;
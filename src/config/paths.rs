@@ -0,0 +1,160 @@
+//! Storage locations
+//!
+//! By default config lives in the platform config directory (`XDG_CONFIG_HOME`
+//! on Linux) and sessions, history, and logs in the local data directory
+//! (`XDG_DATA_HOME`). Portable mode keeps everything in a `zaxiom-data` folder
+//! next to the executable - turned on with `--portable` or by placing a file
+//! named `portable` beside the executable, e.g. on a USB stick.
+//! `--config-dir <dir>` keeps everything under `<dir>` instead.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Folder next to the executable that holds everything in portable mode
+pub const PORTABLE_DIR: &str = "zaxiom-data";
+
+/// File next to the executable that turns portable mode on
+pub const PORTABLE_MARKER: &str = "portable";
+
+/// How the storage locations were chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Platform config and data directories
+    Installed,
+    /// Next to the executable
+    Portable,
+    /// Given with `--config-dir`
+    Custom,
+}
+
+/// Where config and data are stored for this run
+#[derive(Debug, Clone)]
+pub struct Paths {
+    pub mode: StorageMode,
+    /// config.toml and .env (portable/custom only)
+    pub config_dir: PathBuf,
+    /// Sessions, audit log, AI cache, tips
+    pub data_dir: PathBuf,
+}
+
+static PATHS: OnceLock<Paths> = OnceLock::new();
+
+impl Paths {
+    /// Platform directories, each with a `zaxiom` subfolder
+    pub fn installed() -> Self {
+        Self {
+            mode: StorageMode::Installed,
+            config_dir: dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("zaxiom"),
+            data_dir: dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("zaxiom"),
+        }
+    }
+
+    /// Everything under one directory
+    fn rooted(mode: StorageMode, root: PathBuf) -> Self {
+        Self {
+            mode,
+            config_dir: root.clone(),
+            data_dir: root,
+        }
+    }
+
+    /// Pick the locations from the command-line arguments (program name
+    /// excluded): `--config-dir` wins over portable mode
+    pub fn from_args(args: &[String]) -> Self {
+        let mut config_dir = None;
+        let mut portable = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--config-dir" {
+                config_dir = iter.next().cloned();
+            } else if let Some(dir) = arg.strip_prefix("--config-dir=") {
+                config_dir = Some(dir.to_string());
+            } else if arg == "--portable" {
+                portable = true;
+            }
+        }
+
+        if let Some(dir) = config_dir.filter(|d| !d.is_empty()) {
+            let dir = PathBuf::from(dir);
+            let dir = if dir.is_absolute() {
+                dir
+            } else {
+                std::env::current_dir().unwrap_or_default().join(dir)
+            };
+            return Self::rooted(StorageMode::Custom, dir);
+        }
+
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+        match exe_dir {
+            Some(dir) if portable || dir.join(PORTABLE_MARKER).exists() => {
+                Self::rooted(StorageMode::Portable, dir.join(PORTABLE_DIR))
+            }
+            _ => Self::installed(),
+        }
+    }
+}
+
+/// Fix the locations for this run; call once at startup before anything is loaded
+pub fn init(paths: Paths) {
+    let _ = PATHS.set(paths);
+}
+
+/// Locations in use (the installed defaults if `init` was never called;
+/// unit tests get a scratch directory so they never touch real files)
+pub fn get() -> &'static Paths {
+    PATHS.get_or_init(|| {
+        if cfg!(test) {
+            let root = std::env::temp_dir().join(format!("zaxiom-test-{}", std::process::id()));
+            Paths::rooted(StorageMode::Custom, root)
+        } else {
+            Paths::installed()
+        }
+    })
+}
+
+/// Directory holding config.toml
+pub fn config_dir() -> &'static Path {
+    &get().config_dir
+}
+
+/// Directory holding sessions, logs, and caches
+pub fn data_dir() -> &'static Path {
+    &get().data_dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let paths = Paths::from_args(&args(&["--config-dir", "/tmp/zx"]));
+        assert_eq!(paths.mode, StorageMode::Custom);
+        assert_eq!(paths.config_dir, PathBuf::from("/tmp/zx"));
+        assert_eq!(paths.data_dir, PathBuf::from("/tmp/zx"));
+
+        // Relative directories are resolved now, not whenever they're used
+        let paths = Paths::from_args(&args(&["--portable", "--config-dir=zx"]));
+        assert_eq!(paths.mode, StorageMode::Custom);
+        assert!(paths.config_dir.is_absolute());
+        assert!(paths.config_dir.ends_with("zx"));
+
+        let paths = Paths::from_args(&args(&["--portable"]));
+        assert_eq!(paths.mode, StorageMode::Portable);
+        assert!(paths.data_dir.ends_with(PORTABLE_DIR));
+
+        let paths = Paths::from_args(&args(&["--restricted"]));
+        assert_eq!(paths.mode, StorageMode::Installed);
+        assert!(paths.config_dir.ends_with("zaxiom"));
+    }
+}
@@ -5,14 +5,23 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use super::aliases::AliasConfig;
+use super::aliases::{self, AliasConfig};
+use super::migrate::{self, version_of as table_version, CONFIG_VERSION};
 use super::theme::ThemeConfig;
 
+/// Startup script in the home directory, run in every new pane
+pub const STARTUP_SCRIPT: &str = ".zaxiomrc";
+
 /// Main configuration
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Config {
+    /// Schema version the file was written with (see `config::migrate`)
+    #[serde(default)]
+    pub version: u32,
+
     #[serde(default)]
     pub theme: ThemeConfig,
 
@@ -31,9 +40,235 @@ pub struct Config {
     #[serde(default)]
     pub ai: AiConfig,
 
-    /// Kawaii mode - cuter UI elements when enabled
+    /// Saved split-pane layouts (name -> layout spec)
+    #[serde(default)]
+    pub layouts: HashMap<String, String>,
+
+    /// Saved curl requests (name -> curl arguments)
     #[serde(default)]
+    pub requests: HashMap<String, Vec<String>>,
+
+    /// Kawaii mode - cuter UI elements when enabled (read from older
+    /// configs, migrated to `[kawaii] level` and no longer written)
+    #[serde(default, skip_serializing)]
     pub kawaii_mode: bool,
+
+    #[serde(default)]
+    pub kawaii: KawaiiConfig,
+
+    #[serde(default)]
+    pub restricted: RestrictedConfig,
+
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    #[serde(default)]
+    pub stats: StatsConfig,
+
+    #[serde(default)]
+    pub digest: DigestConfig,
+
+    #[serde(default)]
+    pub startup: StartupConfig,
+
+    #[serde(default)]
+    pub session: SessionConfig,
+
+    /// User-defined command palette entries (`[[palette]]` tables)
+    #[serde(default)]
+    pub palette: Vec<PaletteCommand>,
+}
+
+/// A command palette entry defined in config.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PaletteCommand {
+    /// Name shown (and searched) in the palette
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Command line to run; `{input}` is replaced with the typed input and
+    /// `{cwd}` with the current directory
+    pub command: String,
+
+    /// Shortcut that runs the entry without opening the palette
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<String>,
+}
+
+/// Script run in every new pane: aliases, exports, theme, and any commands
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Run the script when a pane opens
+    pub enabled: bool,
+
+    /// Script file (defaults to ~/.zaxiomrc)
+    pub script: Option<String>,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            script: None,
+        }
+    }
+}
+
+/// Restoring the tabs of an earlier session (off: every launch starts fresh)
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Offer the saved sessions when Zaxiom starts
+    pub restore: bool,
+}
+
+/// Local usage statistics for the `stats` dashboard (never sent anywhere)
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StatsConfig {
+    /// Record when, where, and how long each command ran
+    pub enabled: bool,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Daily activity digest (`digest` command)
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Time of day (`"18:00"`) to show a toast with the day's digest; unset
+    /// means no toast
+    pub toast: Option<String>,
+}
+
+/// Tamper-evident log of executed commands (off unless enabled)
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Record every executed command
+    pub enabled: bool,
+
+    /// Log file (defaults to audit.jsonl in the local data directory)
+    pub path: Option<String>,
+}
+
+/// Restricted mode for shared machines, demos, and kiosks
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RestrictedConfig {
+    /// Only allow allowlisted builtins (also enabled with `--restricted`)
+    pub enabled: bool,
+
+    /// Allowed builtins (empty = the built-in safe set); programs outside
+    /// the command registry never run in restricted mode
+    pub allow: Vec<String>,
+
+    /// Only restrict on these host names (empty = every host)
+    pub hosts: Vec<String>,
+
+    /// Extra builtins allowed inside a directory (path -> commands)
+    pub directories: HashMap<String, Vec<String>>,
+}
+
+/// How much kawaii flair to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KawaiiLevel {
+    /// Plain terminal: no greetings, kaomoji, tips, or confetti
+    Off,
+    /// Greetings, tips, and confetti, but plain errors and the regular theme
+    Subtle,
+    /// Everything: kawaii palette, heart prompt, kaomoji errors, rounded corners
+    Full,
+}
+
+impl KawaiiLevel {
+    /// Parse a level name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "off" | "none" | "0" => Some(Self::Off),
+            "subtle" | "some" | "1" => Some(Self::Subtle),
+            "full" | "on" | "max" | "2" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    /// Config / display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Subtle => "subtle",
+            Self::Full => "full",
+        }
+    }
+}
+
+/// Kawaii level plus per-feature overrides
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct KawaiiConfig {
+    /// off, subtle, or full (defaults to full if `kawaii_mode = true`, else subtle)
+    pub level: Option<KawaiiLevel>,
+    /// Override: kawaii colors and heart prompt
+    pub palette: Option<bool>,
+    /// Override: greeting messages in new panes
+    pub greetings: Option<bool>,
+    /// Override: kaomoji in error messages
+    pub error_faces: Option<bool>,
+    /// Override: tips after commands
+    pub tips: Option<bool>,
+    /// Override: mascot confetti
+    pub confetti: Option<bool>,
+    /// Override: rounded window and widget corners
+    pub rounded_corners: Option<bool>,
+}
+
+/// Resolved on/off state of each kawaii feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KawaiiFeatures {
+    pub palette: bool,
+    pub greetings: bool,
+    pub error_faces: bool,
+    pub tips: bool,
+    pub confetti: bool,
+    pub rounded_corners: bool,
+}
+
+impl KawaiiFeatures {
+    /// Defaults for a level, before overrides
+    pub fn for_level(level: KawaiiLevel) -> Self {
+        let full = level == KawaiiLevel::Full;
+        let any = level != KawaiiLevel::Off;
+        Self {
+            palette: full,
+            greetings: any,
+            error_faces: full,
+            tips: any,
+            confetti: any,
+            rounded_corners: full,
+        }
+    }
+}
+
+impl KawaiiConfig {
+    /// Features for a level with this config's overrides applied
+    pub fn features(&self, level: KawaiiLevel) -> KawaiiFeatures {
+        let defaults = KawaiiFeatures::for_level(level);
+        KawaiiFeatures {
+            palette: self.palette.unwrap_or(defaults.palette),
+            greetings: self.greetings.unwrap_or(defaults.greetings),
+            error_faces: self.error_faces.unwrap_or(defaults.error_faces),
+            tips: self.tips.unwrap_or(defaults.tips),
+            confetti: self.confetti.unwrap_or(defaults.confetti),
+            rounded_corners: self.rounded_corners.unwrap_or(defaults.rounded_corners),
+        }
+    }
 }
 
 /// AI provider configuration
@@ -44,11 +279,89 @@ pub struct AiConfig {
 
     /// Default model override (uses provider's default if not set)
     pub default_model: Option<String>,
+
+    /// Default persona: "assistant", "sysadmin", "eli5", "reviewer", or a custom one
+    pub default_persona: Option<String>,
+
+    /// User-defined personas (name -> template)
+    #[serde(default)]
+    pub personas: HashMap<String, PersonaConfig>,
+
+    /// What terminal context is shared with AI providers
+    #[serde(default)]
+    pub privacy: AiPrivacyConfig,
+
+    /// Response cache for repeated questions and offline use
+    #[serde(default)]
+    pub cache: AiCacheConfig,
+}
+
+/// AI response cache settings
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AiCacheConfig {
+    /// Cache answers and serve repeats from disk
+    pub enabled: bool,
+
+    /// How long a cached answer counts as fresh (expired answers are still used offline)
+    pub ttl_minutes: u64,
+}
+
+impl Default for AiCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_minutes: 24 * 60,
+        }
+    }
+}
+
+/// Controls what context leaves the machine with `#` prompts
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AiPrivacyConfig {
+    /// Include the current working directory
+    pub include_cwd: bool,
+
+    /// Number of recent commands to include (0 = none)
+    pub history_commands: usize,
+
+    /// Include the output of the last command
+    pub include_output: bool,
+
+    /// Maximum number of output lines to include
+    pub max_output_lines: usize,
+
+    /// Scrub tokens, keys, passwords, and .env values before sending
+    pub redact_secrets: bool,
+}
+
+impl Default for AiPrivacyConfig {
+    fn default() -> Self {
+        Self {
+            include_cwd: true,
+            history_commands: 5,
+            include_output: false,
+            max_output_lines: 50,
+            redact_secrets: true,
+        }
+    }
+}
+
+/// A user-defined AI persona
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PersonaConfig {
+    /// Short description shown in `ai personas`
+    pub description: Option<String>,
+
+    /// Prompt template; supports {context} and {question} placeholders
+    pub template: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FontConfig {
     pub family: String,
+    /// Text size of new panes in points (the last Ctrl+= / Ctrl+- / `zoom`)
     pub size: f32,
 }
 
@@ -56,7 +369,7 @@ impl Default for FontConfig {
     fn default() -> Self {
         Self {
             family: "JetBrainsMono Nerd Font".to_string(),
-            size: 14.0,
+            size: 16.0,
         }
     }
 }
@@ -75,29 +388,122 @@ impl Default for PromptConfig {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TerminalConfig {
+    /// Output lines each pane keeps in memory (`buffer scrollback <n>`)
     pub scrollback_lines: usize,
+    /// Older output kept on disk once it leaves memory, in megabytes (0
+    /// drops it instead)
+    pub scrollback_disk_mb: u64,
     pub history_size: usize,
+    /// Show the scrollback minimap (block, error, and match markers)
+    pub minimap: bool,
+    /// Show built-in output taller than the pane in the pager
+    pub pager: bool,
+    /// Wall-clock timestamp gutter beside the output
+    pub timestamps: TimestampMode,
+    /// strftime format for the gutter (e.g. `%H:%M:%S%.3f`)
+    pub timestamp_format: String,
+    /// Mouse menu: a menu bar, a ☰ button in the tab bar, or none
+    pub menu: MenuStyle,
+}
+
+/// How the File/Edit/View/AI/Help menus are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenuStyle {
+    /// A slim menu bar above the tabs
+    Bar,
+    /// One ☰ button at the start of the tab bar
+    #[default]
+    Hamburger,
+    /// No menu (shortcuts and the palette only)
+    Off,
+}
+
+/// Which output lines get a timestamp in the gutter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampMode {
+    /// No gutter
+    #[default]
+    Off,
+    /// When each command started
+    Blocks,
+    /// When each line was received (verbose)
+    Lines,
+}
+
+impl TimestampMode {
+    /// Parse a mode name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "blocks" | "block" | "on" => Some(Self::Blocks),
+            "lines" | "line" | "verbose" => Some(Self::Lines),
+            _ => None,
+        }
+    }
+
+    /// Config / display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Blocks => "blocks",
+            Self::Lines => "lines",
+        }
+    }
 }
 
 impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
             scrollback_lines: 10_000,
+            scrollback_disk_mb: 100,
             history_size: 1_000,
+            minimap: true,
+            pager: true,
+            timestamps: TimestampMode::Off,
+            timestamp_format: "%H:%M:%S".to_string(),
+            menu: MenuStyle::Hamburger,
         }
     }
 }
 
 impl Config {
-    /// Load config from file
+    /// Load config from file, with the aliases saved by `alias`
     pub fn load() -> Self {
+        let mut config = Self::load_file();
+        config.aliases.saved = aliases::load_saved(&aliases::saved_path());
+        config
+    }
+
+    /// Load config.toml alone
+    fn load_file() -> Self {
         let config_path = Self::config_path();
 
         if config_path.exists() {
             match std::fs::read_to_string(&config_path) {
-                Ok(contents) => match toml::from_str(&contents) {
-                    Ok(config) => return config,
+                Ok(contents) => match toml::from_str::<toml::Table>(&contents) {
+                    Ok(mut table) => {
+                        match migrate::migrate_file(&config_path, &mut table) {
+                            Ok(Some(migration)) => eprintln!(
+                                "Migrated config from version {} to {} (backup: {})",
+                                migration.from,
+                                migration.to,
+                                migration.backup.display()
+                            ),
+                            Ok(None) => {}
+                            Err(e) => eprintln!("Failed to migrate config: {:#}", e),
+                        }
+                        if table_version(&table) > CONFIG_VERSION {
+                            eprintln!("Config was written by a newer Zaxiom; unknown settings are ignored");
+                        }
+                        match toml::Value::Table(table).try_into() {
+                            Ok(config) => return config,
+                            Err(e) => eprintln!("Failed to parse config: {}", e),
+                        }
+                    }
                     Err(e) => {
                         eprintln!("Failed to parse config: {}", e);
                     }
@@ -115,25 +521,29 @@ impl Config {
     pub fn save(&self) -> std::io::Result<()> {
         Self::ensure_config_dir()?;
         let config_path = Self::config_path();
-        let contents = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let contents = self.to_toml().map_err(std::io::Error::other)?;
         std::fs::write(config_path, contents)
     }
 
+    /// Serialize for config.toml, stamped with the schema version (never
+    /// lowering the version of a file written by a newer release)
+    fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        let mut value = toml::Value::try_from(self)?;
+        if let Some(table) = value.as_table_mut() {
+            let version = self.version.max(CONFIG_VERSION);
+            table.insert("version".to_string(), toml::Value::Integer(version.into()));
+        }
+        toml::to_string_pretty(&value)
+    }
+
     /// Get config file path
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zaxiom");
-
-        config_dir.join("config.toml")
+        super::paths::config_dir().join("config.toml")
     }
 
     /// Ensure config directory exists
     pub fn ensure_config_dir() -> std::io::Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zaxiom");
-
+        let config_dir = super::paths::config_dir().to_path_buf();
         std::fs::create_dir_all(&config_dir)?;
         Ok(config_dir)
     }
@@ -144,9 +554,88 @@ impl Config {
         self.save()
     }
 
-    /// Set kawaii mode and save
-    pub fn set_kawaii_mode(&mut self, enabled: bool) -> std::io::Result<()> {
-        self.kawaii_mode = enabled;
+    /// Audit log file, from `[audit] path` or the default location
+    pub fn audit_path(&self) -> PathBuf {
+        self.audit
+            .path
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(crate::terminal::audit::AuditLog::default_path)
+    }
+
+    /// Startup script, from `[startup] script` or ~/.zaxiomrc (`~/` is the
+    /// home directory); `None` when startup scripts are turned off
+    pub fn startup_script(&self) -> Option<PathBuf> {
+        if !self.startup.enabled {
+            return None;
+        }
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Some(match self.startup.script.as_deref() {
+            Some(path) => match path.strip_prefix("~/") {
+                Some(rest) => home.join(rest),
+                None => PathBuf::from(path),
+            },
+            None => home.join(STARTUP_SCRIPT),
+        })
+    }
+
+    /// Effective kawaii level (older configs only have `kawaii_mode`)
+    pub fn kawaii_level(&self) -> KawaiiLevel {
+        self.kawaii.level.unwrap_or(if self.kawaii_mode {
+            KawaiiLevel::Full
+        } else {
+            KawaiiLevel::Subtle
+        })
+    }
+
+    /// Effective kawaii features for the configured level
+    pub fn kawaii_features(&self) -> KawaiiFeatures {
+        self.kawaii.features(self.kawaii_level())
+    }
+
+    /// Set kawaii level and save
+    pub fn set_kawaii_level(&mut self, level: KawaiiLevel) -> std::io::Result<()> {
+        self.kawaii.level = Some(level);
+        self.kawaii_mode = level == KawaiiLevel::Full;
         self.save()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kawaii_level_defaults_and_overrides() {
+        let mut config = Config::default();
+        assert_eq!(config.kawaii_level(), KawaiiLevel::Subtle);
+        config.kawaii_mode = true;
+        assert_eq!(config.kawaii_level(), KawaiiLevel::Full);
+
+        config.kawaii.level = Some(KawaiiLevel::Full);
+        config.kawaii.error_faces = Some(false);
+        let features = config.kawaii_features();
+        assert!(features.palette && features.confetti);
+        assert!(!features.error_faces);
+
+        let off = KawaiiFeatures::for_level(KawaiiLevel::Off);
+        assert!(!off.greetings && !off.tips && !off.confetti);
+    }
+
+    #[test]
+    fn test_saved_config_is_versioned() {
+        let mut config = Config {
+            kawaii_mode: true,
+            ..Default::default()
+        };
+        config.kawaii.level = Some(KawaiiLevel::Full);
+
+        let saved: toml::Table = toml::from_str(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(table_version(&saved), CONFIG_VERSION);
+        assert!(!saved.contains_key("kawaii_mode"));
+
+        config.version = CONFIG_VERSION + 1;
+        let saved: toml::Table = toml::from_str(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(table_version(&saved), CONFIG_VERSION + 1);
+    }
+}
@@ -5,9 +5,16 @@
 
 #![allow(dead_code)]
 
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveTime;
 use eframe::egui::Color32;
 use serde::{Deserialize, Serialize};
 
+use super::paths;
+
 /// Nerd Font icons for terminal indicators
 pub mod icons {
     // Folder icons
@@ -180,12 +187,59 @@ pub struct ThemeConfig {
     pub selection: Option<String>,
     pub accent: Option<String>,
     pub accent_secondary: Option<String>,
+    /// Follow the OS light/dark preference (`theme auto`)
+    #[serde(default)]
+    pub auto: bool,
+    /// Theme used in auto mode when it's light (default Catppuccin Latte)
+    pub light: Option<String>,
+    /// Theme used in auto mode when it's dark (default Catppuccin Mocha)
+    pub dark: Option<String>,
+    /// Use the light theme between these times instead of following the
+    /// OS, e.g. `"07:00-19:00"`
+    pub light_hours: Option<String>,
+    /// Status colors safe for a color vision deficiency
+    #[serde(default)]
+    pub color_vision: ColorVision,
     #[serde(default)]
     pub syntax: SyntaxColors,
     #[serde(default)]
     pub status: StatusColors,
 }
 
+impl ThemeConfig {
+    /// Theme auto mode wants right now, or `None` when auto mode is off.
+    /// `system_dark` is the OS preference, if it is known.
+    pub fn auto_theme(&self, system_dark: Option<bool>, now: NaiveTime) -> Option<ThemeName> {
+        if !self.auto {
+            return None;
+        }
+        let light = match self.light_hours.as_deref().and_then(parse_hours) {
+            Some((start, end)) if start <= end => start <= now && now < end,
+            // Wraps past midnight (e.g. a night shift's "22:00-06:00")
+            Some((start, end)) => now >= start || now < end,
+            None => system_dark == Some(false),
+        };
+        Some(if light {
+            self.light
+                .as_deref()
+                .and_then(ThemeName::from_string)
+                .unwrap_or(ThemeName::CatppuccinLatte)
+        } else {
+            self.dark
+                .as_deref()
+                .and_then(ThemeName::from_string)
+                .unwrap_or(ThemeName::CatppuccinMocha)
+        })
+    }
+}
+
+/// Parse a `HH:MM-HH:MM` range
+pub fn parse_hours(range: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = range.split_once('-')?;
+    let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+    Some((time(start)?, time(end)?))
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct SyntaxColors {
     pub command: Option<String>,
@@ -205,6 +259,153 @@ pub struct StatusColors {
     pub info: Option<String>,
 }
 
+/// A theme saved as `<name>.toml` in the themes folder: a built-in `base`
+/// theme with any of the `[theme]` colors from config.toml laid over it
+#[derive(Clone, Debug)]
+pub struct UserTheme {
+    /// File name without `.toml`, which `theme <name>` picks it by
+    pub name: String,
+    pub path: PathBuf,
+    /// Built-in theme it starts from (and takes light/dark from)
+    pub base: ThemeName,
+    pub theme: Theme,
+    /// When the file was last changed, to notice edits
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct UserThemeFile {
+    base: Option<String>,
+    #[serde(flatten)]
+    colors: ThemeConfig,
+}
+
+impl UserTheme {
+    /// Folder holding user theme files
+    pub fn dir() -> PathBuf {
+        paths::config_dir().join("themes")
+    }
+
+    /// File a theme called `name` lives in (whether or not it exists)
+    pub fn path_for(name: &str) -> PathBuf {
+        Self::dir().join(format!("{}.toml", name))
+    }
+
+    /// Read a theme file
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow!("bad theme file name: {}", path.display()))?
+            .to_string();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let file: UserThemeFile = toml::from_str(&content)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let base = match file.base.as_deref() {
+            Some(base) => ThemeName::from_string(base)
+                .ok_or_else(|| anyhow!("{}: unknown base theme '{}'", path.display(), base))?,
+            None => ThemeName::default(),
+        };
+        Ok(Self {
+            name,
+            path: path.to_path_buf(),
+            base,
+            theme: Theme::from_name(base).with_config(&file.colors),
+            modified: modified(path),
+        })
+    }
+
+    /// The theme called `name` (ignoring case), or `None` if there's no file
+    pub fn find(name: &str) -> Option<Result<Self>> {
+        Self::all()
+            .into_iter()
+            .find(|(stem, _)| stem.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, path)| Self::load(&path))
+    }
+
+    /// Every theme file by name, sorted
+    pub fn all() -> Vec<(String, PathBuf)> {
+        let Ok(entries) = std::fs::read_dir(Self::dir()) else {
+            return Vec::new();
+        };
+        let mut themes: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| Some((path.file_stem()?.to_str()?.to_string(), path)))
+            .collect();
+        themes.sort();
+        themes
+    }
+
+    /// Read the file again if it changed since it was last looked at;
+    /// `None` when it hasn't
+    pub fn reload_if_changed(&mut self) -> Option<Result<Self>> {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Self::load(&self.path))
+    }
+
+    pub fn is_light(&self) -> bool {
+        self.base.is_light()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// A new theme file for `name` spelling out `base`'s colors, ready to edit
+pub fn user_theme_template(name: &str, base: ThemeName) -> String {
+    let theme = Theme::from_name(base);
+    let hex = |color: Color32| format!("\"#{:02x}{:02x}{:02x}\"", color.r(), color.g(), color.b());
+    let mut out = format!(
+        "# Zaxiom theme \"{name}\" - switch to it with `theme {name}`.\n\
+         # Changes show up as soon as the file is saved. Colors left out come\n\
+         # from the base theme (any name from `theme list`).\n\n\
+         base = \"{}\"\n\n",
+        base.config_key()
+    );
+    for (key, color) in [
+        ("background", theme.background),
+        ("background_secondary", theme.background_secondary),
+        ("background_tertiary", theme.background_tertiary),
+        ("foreground", theme.foreground),
+        ("foreground_dim", theme.foreground_dim),
+        ("cursor", theme.cursor),
+        ("accent", theme.accent),
+        ("accent_secondary", theme.accent_secondary),
+    ] {
+        out.push_str(&format!("{} = {}\n", key, hex(color)));
+    }
+    out.push_str("# selection = \"#585b70\"\n\n[syntax]\n");
+    for (key, color) in [
+        ("command", theme.command_color),
+        ("path", theme.path_color),
+        ("string", theme.string_color),
+        ("number", theme.number_color),
+        ("flag", theme.flag_color),
+        ("comment", theme.comment_color),
+        ("link", theme.link_color),
+    ] {
+        out.push_str(&format!("{} = {}\n", key, hex(color)));
+    }
+    out.push_str("\n[status]\n");
+    for (key, color) in [
+        ("error", theme.error_color),
+        ("warning", theme.warning_color),
+        ("success", theme.success_color),
+        ("info", theme.info_color),
+    ] {
+        out.push_str(&format!("{} = {}\n", key, hex(color)));
+    }
+    out
+}
+
 /// Parse a hex color string like "#ff0000"
 pub fn parse_hex_color(hex: &str) -> Option<Color32> {
     let hex = hex.trim_start_matches('#');
@@ -1080,7 +1281,12 @@ impl Theme {
 
     /// Load theme from config
     pub fn from_config(config: &ThemeConfig) -> Self {
-        let mut theme = Self::default();
+        Self::default().with_config(config)
+    }
+
+    /// This theme with the colors `config` sets laid over it
+    pub fn with_config(&self, config: &ThemeConfig) -> Self {
+        let mut theme = self.clone();
 
         // Helper macro to reduce boilerplate
         macro_rules! apply_color {
@@ -1163,6 +1369,120 @@ impl Theme {
 
         theme
     }
+
+    /// Remap success/warning/error (and git clean/dirty) so they stay
+    /// distinguishable with a color vision deficiency. Colors come from the
+    /// Okabe-Ito palette, darkened on light backgrounds for contrast.
+    pub fn apply_color_vision(&self, vision: ColorVision) -> Self {
+        let mut theme = self.clone();
+        let light = {
+            let bg = theme.background;
+            (bg.r() as u32 + bg.g() as u32 + bg.b() as u32) > 3 * 0x80
+        };
+        let rgb = |dark: (u8, u8, u8), on_light: (u8, u8, u8)| {
+            let (r, g, b) = if light { on_light } else { dark };
+            Color32::from_rgb(r, g, b)
+        };
+
+        let (success, warning, error) = match vision {
+            ColorVision::Normal => return theme,
+            // Red-green: blue for success, yellow and orange for trouble
+            ColorVision::Deuteranopia => (
+                rgb((0x56, 0xb4, 0xe9), (0x00, 0x72, 0xb2)), // sky blue / blue
+                rgb((0xf0, 0xe4, 0x42), (0x8a, 0x7a, 0x00)), // yellow
+                rgb((0xe6, 0x7e, 0x22), (0xd5, 0x5e, 0x00)), // vermillion
+            ),
+            // Reds look dark, so errors use a brighter orange
+            ColorVision::Protanopia => (
+                rgb((0x56, 0xb4, 0xe9), (0x00, 0x72, 0xb2)), // sky blue / blue
+                rgb((0xf0, 0xe4, 0x42), (0x8a, 0x7a, 0x00)), // yellow
+                rgb((0xf5, 0x9e, 0x2a), (0xb3, 0x5a, 0x00)), // orange
+            ),
+            // Blue-yellow: bluish green vs. red, warnings told apart by lightness
+            ColorVision::Tritanopia => (
+                rgb((0x2e, 0xc4, 0xb6), (0x00, 0x80, 0x75)), // bluish green
+                rgb((0xf7, 0xb8, 0xc8), (0xa8, 0x4a, 0x6a)), // pale rose
+                rgb((0xf2, 0x4c, 0x4c), (0xc0, 0x1c, 0x1c)), // red
+            ),
+        };
+        theme.success_color = success;
+        theme.git_clean_color = success;
+        theme.warning_color = warning;
+        theme.git_dirty_color = warning;
+        theme.error_color = error;
+        theme
+    }
+
+    /// Colors by their config name (without the `_color` suffix)
+    pub fn named_colors(&self) -> Vec<(&'static str, Color32)> {
+        vec![
+            ("background", self.background),
+            ("background_secondary", self.background_secondary),
+            ("background_tertiary", self.background_tertiary),
+            ("foreground", self.foreground),
+            ("foreground_dim", self.foreground_dim),
+            ("cursor", self.cursor),
+            ("selection", self.selection),
+            ("path", self.path_color),
+            ("branch", self.branch_color),
+            ("command", self.command_color),
+            ("string", self.string_color),
+            ("number", self.number_color),
+            ("flag", self.flag_color),
+            ("error", self.error_color),
+            ("warning", self.warning_color),
+            ("success", self.success_color),
+            ("info", self.info_color),
+            ("accent", self.accent),
+            ("accent_secondary", self.accent_secondary),
+            ("link", self.link_color),
+            ("comment", self.comment_color),
+            ("folder", self.folder_color),
+            ("git_icon", self.git_icon_color),
+            ("git_clean", self.git_clean_color),
+            ("git_dirty", self.git_dirty_color),
+            ("prompt", self.prompt_color),
+            ("root", self.root_color),
+        ]
+    }
+}
+
+/// Color vision deficiency the status colors are adjusted for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorVision {
+    /// Theme colors as designed
+    #[default]
+    Normal,
+    /// Red-green (green-weak), the most common
+    Deuteranopia,
+    /// Red-green (red-weak)
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+impl ColorVision {
+    /// Parse a mode name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "normal" | "off" | "none" => Some(Self::Normal),
+            "deuteranopia" | "deutan" | "deuteranomaly" => Some(Self::Deuteranopia),
+            "protanopia" | "protan" | "protanomaly" => Some(Self::Protanopia),
+            "tritanopia" | "tritan" | "tritanomaly" => Some(Self::Tritanopia),
+            _ => None,
+        }
+    }
+
+    /// Config / display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Deuteranopia => "deuteranopia",
+            Self::Protanopia => "protanopia",
+            Self::Tritanopia => "tritanopia",
+        }
+    }
 }
 
 /// Kawaii mode icons - cuter alternatives
@@ -1178,3 +1498,119 @@ pub mod kawaii_icons {
     pub const STAR: &str = "⭐"; // Star
     pub const HEART: &str = "💕"; // Hearts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_auto_theme() {
+        let mut config = ThemeConfig::default();
+        assert_eq!(config.auto_theme(Some(false), at("12:00")), None);
+
+        config.auto = true;
+        config.dark = Some("dracula".to_string());
+        assert_eq!(
+            config.auto_theme(Some(false), at("12:00")),
+            Some(ThemeName::CatppuccinLatte)
+        );
+        assert_eq!(
+            config.auto_theme(Some(true), at("12:00")),
+            Some(ThemeName::Dracula)
+        );
+        assert_eq!(
+            config.auto_theme(None, at("12:00")),
+            Some(ThemeName::Dracula)
+        );
+
+        config.light_hours = Some("07:00-19:00".to_string());
+        config.light = Some("gruvbox-light".to_string());
+        assert_eq!(
+            config.auto_theme(Some(true), at("08:30")),
+            Some(ThemeName::GruvboxLight)
+        );
+        assert_eq!(
+            config.auto_theme(Some(false), at("19:00")),
+            Some(ThemeName::Dracula)
+        );
+
+        config.light_hours = Some("22:00-06:00".to_string());
+        assert_eq!(
+            config.auto_theme(None, at("23:15")),
+            Some(ThemeName::GruvboxLight)
+        );
+        assert!(parse_hours("7am-7pm").is_none());
+    }
+
+    #[test]
+    fn test_user_theme() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-themes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // The template reproduces its base theme
+        let path = dir.join("Mine.toml");
+        std::fs::write(&path, user_theme_template("Mine", ThemeName::Nord)).unwrap();
+        let mut user = UserTheme::load(&path).unwrap();
+        let nord = Theme::from_name(ThemeName::Nord);
+        assert_eq!(user.name, "Mine");
+        assert_eq!(user.base, ThemeName::Nord);
+        assert_eq!(user.theme.background, nord.background);
+        assert_eq!(user.theme.comment_color, nord.comment_color);
+        assert_eq!(user.theme.error_color, nord.error_color);
+        assert!(user.reload_if_changed().is_none());
+
+        // Only the colors given change
+        std::fs::write(
+            &path,
+            "base = \"gruvbox-light\"\nforeground = \"#102030\"\n[status]\nerror = \"#ff0000\"\n",
+        )
+        .unwrap();
+        let user = UserTheme::load(&path).unwrap();
+        let gruvbox = Theme::from_name(ThemeName::GruvboxLight);
+        assert!(user.is_light());
+        assert_eq!(user.theme.foreground, Color32::from_rgb(0x10, 0x20, 0x30));
+        assert_eq!(user.theme.error_color, Color32::from_rgb(0xff, 0, 0));
+        assert_eq!(user.theme.background, gruvbox.background);
+
+        std::fs::write(&path, "base = \"nope\"\n").unwrap();
+        assert!(UserTheme::load(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_color_vision() {
+        let theme = Theme::from_name(ThemeName::CatppuccinMocha);
+        let normal = theme.apply_color_vision(ColorVision::Normal);
+        assert_eq!(normal.success_color, theme.success_color);
+
+        for vision in [
+            ColorVision::Deuteranopia,
+            ColorVision::Protanopia,
+            ColorVision::Tritanopia,
+        ] {
+            let remapped = theme.apply_color_vision(vision);
+            assert_ne!(remapped.success_color, remapped.error_color);
+            assert_ne!(remapped.warning_color, remapped.error_color);
+            assert_eq!(remapped.git_dirty_color, remapped.warning_color);
+        }
+
+        // Darker variants on light themes
+        let latte = Theme::from_name(ThemeName::CatppuccinLatte);
+        assert_ne!(
+            latte
+                .apply_color_vision(ColorVision::Deuteranopia)
+                .success_color,
+            theme
+                .apply_color_vision(ColorVision::Deuteranopia)
+                .success_color
+        );
+        assert_eq!(
+            ColorVision::parse("Deutan"),
+            Some(ColorVision::Deuteranopia)
+        );
+    }
+}
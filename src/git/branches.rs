@@ -0,0 +1,221 @@
+//! Git branches
+//!
+//! Branch listing and switching for the branch finder (Ctrl+G): local and
+//! remote branches with how far each local one is ahead of or behind its
+//! upstream, checking one out (a remote branch gets a local tracking branch
+//! the first time), and creating a new branch at HEAD.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use git2::build::CheckoutBuilder;
+use git2::{Branch, BranchType, Repository};
+
+/// A branch as shown in the finder
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchInfo {
+    /// Short name (`main`, `origin/main`)
+    pub name: String,
+    /// A remote-tracking branch
+    pub remote: bool,
+    /// The branch HEAD is on
+    pub current: bool,
+    /// Upstream of a local branch (`origin/main`)
+    pub upstream: Option<String>,
+    /// Commits not on the upstream
+    pub ahead: usize,
+    /// Upstream commits not on the branch
+    pub behind: usize,
+}
+
+impl BranchInfo {
+    /// Preview column text: `↑2 ↓1 origin/main`, `local`, or `remote`
+    pub fn preview(&self) -> String {
+        let Some(upstream) = &self.upstream else {
+            return if self.remote { "remote" } else { "local" }.to_string();
+        };
+        let mut parts = Vec::new();
+        if self.ahead > 0 {
+            parts.push(format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            parts.push(format!("↓{}", self.behind));
+        }
+        if parts.is_empty() {
+            parts.push("✓".to_string());
+        }
+        parts.push(upstream.clone());
+        parts.join(" ")
+    }
+}
+
+/// Local branches (the current one first) then remote ones
+pub fn list(cwd: &Path) -> Result<Vec<BranchInfo>> {
+    let repo = Repository::discover(cwd)?;
+    let mut branches = Vec::new();
+    for branch in repo.branches(None)? {
+        let (branch, kind) = branch?;
+        let Some(name) = branch.name()?.map(str::to_string) else {
+            continue;
+        };
+        // origin/HEAD only points at another remote branch
+        if kind == BranchType::Remote && name.ends_with("/HEAD") {
+            continue;
+        }
+        let mut info = BranchInfo {
+            name,
+            remote: kind == BranchType::Remote,
+            current: branch.is_head(),
+            upstream: None,
+            ahead: 0,
+            behind: 0,
+        };
+        if let Ok(upstream) = branch.upstream() {
+            info.upstream = upstream.name()?.map(str::to_string);
+            if let (Some(local), Some(remote)) = (branch.get().target(), upstream.get().target()) {
+                let (ahead, behind) = repo.graph_ahead_behind(local, remote)?;
+                info.ahead = ahead;
+                info.behind = behind;
+            }
+        }
+        branches.push(info);
+    }
+    branches.sort_by(|a, b| (!a.current, a.remote, &a.name).cmp(&(!b.current, b.remote, &b.name)));
+    Ok(branches)
+}
+
+/// Switch to a branch. A remote branch (`origin/feature`) checks out the
+/// local branch of the same name, creating it to track the remote one when
+/// there is none yet. Local edits are kept unless the switch would
+/// overwrite them.
+pub fn checkout(cwd: &Path, name: &str) -> Result<String> {
+    let repo = Repository::discover(cwd)?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        switch(&repo, name)?;
+        return Ok(format!("Switched to branch '{}'", name));
+    }
+
+    let remote = repo
+        .find_branch(name, BranchType::Remote)
+        .map_err(|_| anyhow!("no branch named '{}'", name))?;
+    let (_, local_name) = name
+        .split_once('/')
+        .ok_or_else(|| anyhow!("'{}' isn't a remote branch", name))?;
+    if repo.find_branch(local_name, BranchType::Local).is_ok() {
+        switch(&repo, local_name)?;
+        return Ok(format!("Switched to branch '{}'", local_name));
+    }
+
+    let commit = remote.get().peel_to_commit()?;
+    let mut local = repo.branch(local_name, &commit, false)?;
+    if let Err(e) = local
+        .set_upstream(Some(name))
+        .map_err(Into::into)
+        .and_then(|_| switch(&repo, local_name))
+    {
+        local.delete()?;
+        return Err(e);
+    }
+    Ok(format!(
+        "Switched to a new branch '{}' tracking '{}'",
+        local_name, name
+    ))
+}
+
+/// Create a branch at HEAD and switch to it
+pub fn create(cwd: &Path, name: &str) -> Result<String> {
+    let name = name.trim();
+    if !Branch::name_is_valid(name)? {
+        return Err(anyhow!("'{}' isn't a valid branch name", name));
+    }
+    let repo = Repository::discover(cwd)?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(anyhow!("a branch named '{}' already exists", name));
+    }
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| anyhow!("no commits yet to branch from"))?;
+    repo.branch(name, &head, false)?;
+    // Same commit, so only HEAD moves; the working tree is left alone
+    repo.set_head(&format!("refs/heads/{}", name))?;
+    Ok(format!("Switched to a new branch '{}'", name))
+}
+
+/// Check out a local branch's tree, then point HEAD at it
+fn switch(repo: &Repository, name: &str) -> Result<()> {
+    let refname = format!("refs/heads/{}", name);
+    let tree = repo.find_reference(&refname)?.peel_to_tree()?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(tree.as_object(), Some(&mut checkout))
+        .map_err(|e| anyhow!("{} (commit or stash your changes)", e.message()))?;
+    repo.set_head(&refname)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkout_and_create() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-branches-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let first = repo
+            .commit(Some("HEAD"), &signature, &signature, "first", &tree, &[])
+            .unwrap();
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        // A remote branch one commit ahead of the local one
+        let first = repo.find_commit(first).unwrap();
+        std::fs::write(dir.join("a.txt"), "b\n").unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let second = repo
+            .commit(None, &signature, &signature, "second", &tree, &[&first])
+            .unwrap();
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/feature", second, false, "test")
+            .unwrap();
+        let mut builder = CheckoutBuilder::new();
+        repo.checkout_head(Some(builder.force())).unwrap();
+
+        assert_eq!(
+            checkout(&dir, "origin/feature").unwrap(),
+            "Switched to a new branch 'feature' tracking 'origin/feature'"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "b\n");
+        assert_eq!(
+            checkout(&dir, &main).unwrap(),
+            format!("Switched to branch '{}'", main)
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "a\n");
+
+        assert_eq!(
+            create(&dir, "topic").unwrap(),
+            "Switched to a new branch 'topic'"
+        );
+        assert!(create(&dir, "topic").is_err());
+        assert!(create(&dir, "bad name").is_err());
+
+        let branches = list(&dir).unwrap();
+        let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names[0], "topic");
+        assert_eq!(names.last(), Some(&"origin/feature"));
+        let feature = branches.iter().find(|b| b.name == "feature").unwrap();
+        assert_eq!(feature.preview(), "✓ origin/feature");
+
+        // Local edits that a switch would overwrite block it
+        std::fs::write(dir.join("a.txt"), "edited\n").unwrap();
+        assert!(checkout(&dir, "feature").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Merge conflict regions
+//!
+//! Finds `<<<<<<<` / `=======` / `>>>>>>>` regions in a file (including
+//! diff3-style `|||||||` base sections) and resolves them one at a time.
+
+/// Which side of a conflict to keep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    /// Our version (between `<<<<<<<` and `=======`)
+    Ours,
+    /// Their version (between `=======` and `>>>>>>>`)
+    Theirs,
+    /// Ours followed by theirs
+    Both,
+}
+
+/// A conflict region, as 0-based line numbers of its markers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conflict {
+    /// `<<<<<<<` line
+    pub start: usize,
+    /// `|||||||` line, for diff3-style conflicts
+    pub base: Option<usize>,
+    /// `=======` line
+    pub separator: usize,
+    /// `>>>>>>>` line
+    pub end: usize,
+}
+
+/// Part of a conflict a line belongs to, for highlighting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region {
+    Marker,
+    Ours,
+    Base,
+    Theirs,
+}
+
+impl Conflict {
+    /// Whether a line is inside this conflict (markers included)
+    pub fn contains(&self, line: usize) -> bool {
+        (self.start..=self.end).contains(&line)
+    }
+
+    /// Which part of the conflict a line is in
+    pub fn region(&self, line: usize) -> Option<Region> {
+        if !self.contains(line) {
+            return None;
+        }
+        let is_marker =
+            [self.start, self.separator, self.end].contains(&line) || self.base == Some(line);
+        let region = if is_marker {
+            Region::Marker
+        } else if line > self.separator {
+            Region::Theirs
+        } else if self.base.is_some_and(|base| line > base) {
+            Region::Base
+        } else {
+            Region::Ours
+        };
+        Some(region)
+    }
+}
+
+/// Every complete conflict region in the text
+pub fn find_conflicts(content: &str) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut start = None;
+    let mut base = None;
+    let mut separator = None;
+
+    for (i, line) in content.lines().enumerate() {
+        if line.starts_with("<<<<<<<") {
+            start = Some(i);
+            base = None;
+            separator = None;
+        } else if line.starts_with("|||||||") && start.is_some() && separator.is_none() {
+            base = Some(i);
+        } else if line.starts_with("=======") && start.is_some() && separator.is_none() {
+            separator = Some(i);
+        } else if line.starts_with(">>>>>>>") {
+            if let (Some(start), Some(separator)) = (start, separator) {
+                conflicts.push(Conflict {
+                    start,
+                    base,
+                    separator,
+                    end: i,
+                });
+            }
+            start = None;
+            base = None;
+            separator = None;
+        }
+    }
+    conflicts
+}
+
+/// Replace a conflict region with the chosen side
+pub fn resolve(content: &str, conflict: &Conflict, side: Side) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let ours_end = conflict.base.unwrap_or(conflict.separator);
+    let ours = &lines[conflict.start + 1..ours_end];
+    let theirs = &lines[conflict.separator + 1..conflict.end];
+
+    let mut kept: Vec<&str> = lines[..conflict.start].to_vec();
+    match side {
+        Side::Ours => kept.extend_from_slice(ours),
+        Side::Theirs => kept.extend_from_slice(theirs),
+        Side::Both => {
+            kept.extend_from_slice(ours);
+            kept.extend_from_slice(theirs);
+        }
+    }
+    kept.extend_from_slice(&lines[conflict.end + 1..]);
+
+    let mut resolved = kept.join("\n");
+    if content.ends_with('\n') && !resolved.is_empty() {
+        resolved.push('\n');
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERGED: &str = "fn main() {\n\
+        <<<<<<< HEAD\n    println!(\"ours\");\n\
+        =======\n    println!(\"theirs\");\n\
+        >>>>>>> feature\n}\n\
+        <<<<<<< HEAD\na\n||||||| base\nb\n=======\nc\n>>>>>>> feature\n";
+
+    #[test]
+    fn test_find_conflicts() {
+        let conflicts = find_conflicts(MERGED);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            conflicts[0],
+            Conflict {
+                start: 1,
+                base: None,
+                separator: 3,
+                end: 5
+            }
+        );
+        assert_eq!(conflicts[1].base, Some(9));
+        assert_eq!(conflicts[1].region(8), Some(Region::Ours));
+        assert_eq!(conflicts[1].region(10), Some(Region::Base));
+        assert_eq!(conflicts[1].region(12), Some(Region::Theirs));
+        assert_eq!(conflicts[1].region(11), Some(Region::Marker));
+        assert!(find_conflicts("<<<<<<< HEAD\nunfinished\n").is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let conflicts = find_conflicts(MERGED);
+        let ours = resolve(MERGED, &conflicts[0], Side::Ours);
+        assert!(ours.starts_with("fn main() {\n    println!(\"ours\");\n}\n<<<<<<<"));
+        assert_eq!(find_conflicts(&ours).len(), 1);
+
+        let theirs = resolve(MERGED, &conflicts[1], Side::Theirs);
+        assert!(theirs.ends_with("}\nc\n"));
+        let both = resolve(MERGED, &conflicts[1], Side::Both);
+        assert!(both.ends_with("}\na\nc\n"));
+    }
+}
@@ -0,0 +1,284 @@
+//! GitHub and GitLab integration
+//!
+//! Works out the forge and project from a remote URL, builds web links to
+//! files and lines, and lists open pull/merge requests through the REST
+//! APIs. Tokens come from `GITHUB_TOKEN`/`GH_TOKEN` and `GITLAB_TOKEN`
+//! (saved to the .env file by `gpr login`); public repos work without one.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+/// Which forge hosts a repository
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+}
+
+impl ForgeKind {
+    /// Environment variables a token is read from, preferred first
+    pub fn token_envs(self) -> &'static [&'static str] {
+        match self {
+            ForgeKind::GitHub => &["GITHUB_TOKEN", "GH_TOKEN"],
+            ForgeKind::GitLab => &["GITLAB_TOKEN"],
+        }
+    }
+
+    /// What the forge calls a pull request
+    pub fn request_name(self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "pull request",
+            ForgeKind::GitLab => "merge request",
+        }
+    }
+}
+
+/// A repository on a forge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forge {
+    pub kind: ForgeKind,
+    /// e.g. `github.com` or `gitlab.example.com`
+    pub host: String,
+    /// `owner/repo`, or `group/subgroup/repo` on GitLab
+    pub project: String,
+}
+
+/// An open pull/merge request
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    /// Source branch
+    pub branch: String,
+    pub url: String,
+    pub draft: bool,
+}
+
+impl Forge {
+    /// Parse an https, ssh, or scp-style (`git@host:owner/repo.git`) remote URL
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+            // https://host/path, ssh://git@host:22/path
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            (host.split(':').next()?, path)
+        } else {
+            // git@host:path
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        };
+
+        let project = path.trim_matches('/').trim_end_matches(".git");
+        if host.is_empty() || !project.contains('/') {
+            return None;
+        }
+
+        let host_lower = host.to_lowercase();
+        let kind = if host_lower.contains("github") {
+            ForgeKind::GitHub
+        } else if host_lower.contains("gitlab") {
+            ForgeKind::GitLab
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            kind,
+            host: host_lower,
+            project: project.to_string(),
+        })
+    }
+
+    /// Home page of the project
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}", self.host, self.project)
+    }
+
+    /// Link to a file (and optionally a line or line range) at a ref
+    pub fn file_url(
+        &self,
+        git_ref: &str,
+        path: &str,
+        lines: Option<(usize, Option<usize>)>,
+    ) -> String {
+        let blob = match self.kind {
+            ForgeKind::GitHub => "blob",
+            ForgeKind::GitLab => "-/blob",
+        };
+        let mut url = format!("{}/{}/{}/{}", self.web_url(), blob, git_ref, path);
+        match (self.kind, lines) {
+            (_, Some((start, None))) => url.push_str(&format!("#L{}", start)),
+            (ForgeKind::GitHub, Some((start, Some(end)))) => {
+                url.push_str(&format!("#L{}-L{}", start, end))
+            }
+            (ForgeKind::GitLab, Some((start, Some(end)))) => {
+                url.push_str(&format!("#L{}-{}", start, end))
+            }
+            (_, None) => {}
+        }
+        url
+    }
+
+    /// Web page of a pull/merge request, or the list of them
+    pub fn request_url(&self, number: Option<u64>) -> String {
+        let base = match self.kind {
+            ForgeKind::GitHub => format!("{}/pull", self.web_url()),
+            ForgeKind::GitLab => format!("{}/-/merge_requests", self.web_url()),
+        };
+        match number {
+            Some(number) => format!("{}/{}", base, number),
+            None if self.kind == ForgeKind::GitHub => format!("{}s", base),
+            None => base,
+        }
+    }
+
+    /// Remote ref holding a request's head commit
+    pub fn head_ref(&self, number: u64) -> String {
+        match self.kind {
+            ForgeKind::GitHub => format!("pull/{}/head", number),
+            ForgeKind::GitLab => format!("merge-requests/{}/head", number),
+        }
+    }
+
+    /// Token from the environment, if one is set
+    pub fn token(&self) -> Option<String> {
+        self.kind
+            .token_envs()
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()))
+    }
+
+    fn api_base(&self) -> String {
+        match self.kind {
+            ForgeKind::GitHub if self.host == "github.com" => "https://api.github.com".to_string(),
+            // GitHub Enterprise
+            ForgeKind::GitHub => format!("https://{}/api/v3", self.host),
+            ForgeKind::GitLab => format!("https://{}/api/v4", self.host),
+        }
+    }
+
+    /// Open pull/merge requests, newest first
+    pub fn list_pull_requests(&self) -> Result<Vec<PullRequest>> {
+        let url = match self.kind {
+            ForgeKind::GitHub => format!(
+                "{}/repos/{}/pulls?state=open&per_page=50",
+                self.api_base(),
+                self.project
+            ),
+            ForgeKind::GitLab => format!(
+                "{}/projects/{}/merge_requests?state=opened&per_page=50",
+                self.api_base(),
+                self.project.replace('/', "%2F")
+            ),
+        };
+
+        let client = reqwest::blocking::Client::new();
+        let mut request = client
+            .get(&url)
+            .header("User-Agent", "zaxiom")
+            .timeout(Duration::from_secs(20));
+        if let Some(token) = self.token() {
+            request = match self.kind {
+                ForgeKind::GitHub => request
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Accept", "application/vnd.github+json"),
+                ForgeKind::GitLab => request.header("PRIVATE-TOKEN", token),
+            };
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| anyhow!("Failed to connect to {}: {}", self.host, e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let hint = if matches!(status.as_u16(), 401 | 403 | 404) && self.token().is_none() {
+                " (private repo? save a token with gpr login <token>)"
+            } else {
+                ""
+            };
+            return Err(anyhow!("{} API error ({}){}", self.host, status, hint));
+        }
+
+        let json: serde_json::Value = response
+            .json()
+            .map_err(|e| anyhow!("Failed to parse {} response: {}", self.host, e))?;
+        let items = json
+            .as_array()
+            .ok_or_else(|| anyhow!("Unexpected {} response", self.host))?;
+        Ok(items
+            .iter()
+            .filter_map(|item| self.parse_request(item))
+            .collect())
+    }
+
+    fn parse_request(&self, item: &serde_json::Value) -> Option<PullRequest> {
+        let text = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+        let request = match self.kind {
+            ForgeKind::GitHub => PullRequest {
+                number: item["number"].as_u64()?,
+                title: text(&item["title"]),
+                author: text(&item["user"]["login"]),
+                branch: text(&item["head"]["ref"]),
+                url: text(&item["html_url"]),
+                draft: item["draft"].as_bool().unwrap_or(false),
+            },
+            ForgeKind::GitLab => PullRequest {
+                number: item["iid"].as_u64()?,
+                title: text(&item["title"]),
+                author: text(&item["author"]["username"]),
+                branch: text(&item["source_branch"]),
+                url: text(&item["web_url"]),
+                draft: item["draft"].as_bool().unwrap_or(false),
+            },
+        };
+        Some(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_remote_url() {
+        let https =
+            Forge::from_remote_url("https://github.com/aayushadhikari7/zaxiom.git").unwrap();
+        assert_eq!(https.kind, ForgeKind::GitHub);
+        assert_eq!(https.project, "aayushadhikari7/zaxiom");
+
+        let scp = Forge::from_remote_url("git@gitlab.com:group/sub/app.git").unwrap();
+        assert_eq!(scp.kind, ForgeKind::GitLab);
+        assert_eq!(scp.host, "gitlab.com");
+        assert_eq!(scp.project, "group/sub/app");
+
+        let ssh = Forge::from_remote_url("ssh://git@gitlab.example.com:2222/team/app").unwrap();
+        assert_eq!(ssh.host, "gitlab.example.com");
+        assert_eq!(ssh.project, "team/app");
+
+        assert!(Forge::from_remote_url("https://example.com/a/b.git").is_none());
+        assert!(Forge::from_remote_url("/srv/git/app.git").is_none());
+    }
+
+    #[test]
+    fn test_file_url() {
+        let github = Forge::from_remote_url("git@github.com:o/r.git").unwrap();
+        assert_eq!(
+            github.file_url("main", "src/app.rs", Some((10, Some(20)))),
+            "https://github.com/o/r/blob/main/src/app.rs#L10-L20"
+        );
+        let gitlab = Forge::from_remote_url("https://gitlab.com/g/r").unwrap();
+        assert_eq!(
+            gitlab.file_url("main", "README.md", Some((3, Some(5)))),
+            "https://gitlab.com/g/r/-/blob/main/README.md#L3-5"
+        );
+        assert_eq!(gitlab.head_ref(7), "merge-requests/7/head");
+        assert_eq!(
+            gitlab.request_url(Some(7)),
+            "https://gitlab.com/g/r/-/merge_requests/7"
+        );
+        assert_eq!(github.request_url(None), "https://github.com/o/r/pulls");
+    }
+}
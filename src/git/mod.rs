@@ -1,6 +1,12 @@
 //! Git integration
 //!
-//! Git shortcuts and prompt integration.
+//! Git shortcuts, prompt integration, the status bar watcher, the git
+//! panel, branch switching, and GitHub/GitLab integration.
 
+pub mod branches;
+pub mod conflicts;
+pub mod forge;
+pub mod panel;
 pub mod prompt;
 pub mod shortcuts;
+pub mod watcher;
@@ -0,0 +1,620 @@
+//! Git panel
+//!
+//! The model behind the git sidebar (Ctrl+Shift+G): the working tree's
+//! changes split into staged, unstaged, and untracked files, the selected
+//! file's diff as hunks, and staging, unstaging, or discarding a whole file
+//! or a single hunk. Everything goes through libgit2, so it works without
+//! git on PATH; one hunk is applied with `Repository::apply` and a hunk
+//! filter.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository, Status, StatusOptions,
+};
+
+use crate::terminal::syntax::DiffHighlighter;
+
+/// How often an open panel re-reads the status
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Which list a changed file is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    /// Changes in the index, not yet committed
+    Staged,
+    /// Changes to tracked files, not yet staged
+    Unstaged,
+    /// New files git doesn't track yet
+    Untracked,
+}
+
+impl Section {
+    pub const ALL: [Section; 3] = [Section::Staged, Section::Unstaged, Section::Untracked];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Staged => "Staged",
+            Section::Unstaged => "Changes",
+            Section::Untracked => "Untracked",
+        }
+    }
+}
+
+/// One changed file in one section (a file staged and then edited again
+/// shows up in both Staged and Changes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// Path relative to the repository root
+    pub path: String,
+    pub section: Section,
+    /// `M`odified, `A`dded, `D`eleted, `R`enamed, `T`ype changed,
+    /// `U`nmerged, or `?` for untracked
+    pub status: char,
+}
+
+impl FileChange {
+    /// The git command that shows the same diff
+    pub fn diff_command(&self) -> String {
+        match self.section {
+            Section::Staged => format!("git diff --cached -- {}", self.path),
+            _ => format!("git diff -- {}", self.path),
+        }
+    }
+}
+
+/// One hunk of a file's diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// `@@ -1,4 +1,5 @@ fn main() {`
+    pub header: String,
+    /// Lines with their `+`, `-`, or ` ` prefix
+    pub lines: Vec<String>,
+}
+
+/// What to do with a file or hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Stage,
+    Unstage,
+    Discard,
+}
+
+/// State of the git sidebar
+#[derive(Default)]
+pub struct GitPanel {
+    pub open: bool,
+    /// Root of the repository being shown
+    root: Option<PathBuf>,
+    pub changes: Vec<FileChange>,
+    /// Index into `changes` of the file whose diff is shown
+    pub selected: Option<usize>,
+    /// Hunks of the selected file
+    pub hunks: Vec<Hunk>,
+    /// Hunk the keys act on
+    pub hunk: usize,
+    /// Last error (or "not a git repository")
+    pub error: Option<String>,
+    /// Discard waiting for a second press: (file, hunk or whole file)
+    armed: Option<(usize, Option<usize>)>,
+    refreshed: Option<Instant>,
+}
+
+impl GitPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show or hide the panel, reading the repository containing `cwd`
+    pub fn toggle(&mut self, cwd: &Path) {
+        self.open = !self.open;
+        if self.open {
+            self.refresh(cwd);
+        }
+    }
+
+    /// Re-read the status every `REFRESH_INTERVAL` while open
+    pub fn refresh_if_stale(&mut self, cwd: &Path) {
+        if self.open
+            && self
+                .refreshed
+                .is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL)
+        {
+            self.refresh(cwd);
+        }
+    }
+
+    /// Re-read the status, keeping the selected file when it is still there
+    pub fn refresh(&mut self, cwd: &Path) {
+        self.refreshed = Some(Instant::now());
+        let repo = match Repository::discover(cwd) {
+            Ok(repo) => repo,
+            Err(_) => {
+                self.root = None;
+                self.changes.clear();
+                self.select_none();
+                self.error = Some("Not a git repository".to_string());
+                return;
+            }
+        };
+        let root = repo.workdir().map(Path::to_path_buf);
+        if root != self.root {
+            self.root = root;
+            self.select_none();
+        }
+        let previous = self.selected_change().cloned();
+        match changes(&repo) {
+            Ok(changes) => {
+                self.changes = changes;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+
+        let Some(previous) = previous else { return };
+        match self.changes.iter().position(|c| *c == previous) {
+            Some(index) => {
+                self.selected = Some(index);
+                let hunk = self.hunk;
+                self.load_hunks(&repo);
+                self.hunk = hunk.min(self.hunks.len().saturating_sub(1));
+            }
+            None => self.select_none(),
+        }
+    }
+
+    pub fn selected_change(&self) -> Option<&FileChange> {
+        self.changes.get(self.selected?)
+    }
+
+    /// Select a file and return its colored diff, for the output buffer
+    pub fn select(&mut self, index: usize) -> Option<Vec<String>> {
+        let repo = self.repo()?;
+        self.changes.get(index)?;
+        self.selected = Some(index);
+        self.hunk = 0;
+        self.armed = None;
+        self.load_hunks(&repo);
+        let change = self.selected_change()?;
+        Some(render_diff(change, &self.hunks))
+    }
+
+    /// Make hunk `index` of the selected file the one the keys act on
+    pub fn select_hunk(&mut self, index: usize) {
+        if index < self.hunks.len() && index != self.hunk {
+            self.hunk = index;
+            self.armed = None;
+        }
+    }
+
+    pub fn next_hunk(&mut self) {
+        if self.hunk + 1 < self.hunks.len() {
+            self.hunk += 1;
+            self.armed = None;
+        }
+    }
+
+    pub fn prev_hunk(&mut self) {
+        if self.hunk > 0 {
+            self.hunk -= 1;
+            self.armed = None;
+        }
+    }
+
+    /// Whether the next `run` of `Discard` on this target goes ahead
+    pub fn is_armed(&self, whole_file: bool) -> bool {
+        self.armed.is_some() && self.armed == self.target(whole_file)
+    }
+
+    /// Apply `action` to the selected file (or its current hunk) and
+    /// re-read the status. Discarding throws work away, so the first
+    /// request only arms it and returns `Ok(false)`; asking again for the
+    /// same target goes ahead.
+    pub fn run(&mut self, action: Action, whole_file: bool) -> Result<bool> {
+        let target = self.target(whole_file);
+        let (index, hunk) = target.ok_or_else(|| anyhow!("Select a file first"))?;
+        if action == Action::Discard && self.armed != target {
+            self.armed = target;
+            return Ok(false);
+        }
+        self.armed = None;
+
+        let repo = self.repo().ok_or_else(|| anyhow!("Not a git repository"))?;
+        let result = apply(&repo, &self.changes[index], action, hunk);
+        let root = self.root.clone().unwrap_or_default();
+        self.refresh(&root);
+        result.map(|_| true)
+    }
+
+    /// The selected file and, unless acting on the whole file, its hunk
+    fn target(&self, whole_file: bool) -> Option<(usize, Option<usize>)> {
+        let index = self.selected?;
+        if whole_file || self.changes[index].section == Section::Untracked {
+            Some((index, None))
+        } else if self.hunk < self.hunks.len() {
+            Some((index, Some(self.hunk)))
+        } else {
+            None
+        }
+    }
+
+    fn repo(&self) -> Option<Repository> {
+        Repository::open(self.root.as_ref()?).ok()
+    }
+
+    fn select_none(&mut self) {
+        self.selected = None;
+        self.hunks.clear();
+        self.hunk = 0;
+        self.armed = None;
+    }
+
+    fn load_hunks(&mut self, repo: &Repository) {
+        let Some(change) = self.selected_change() else {
+            return;
+        };
+        match file_diff(repo, change, false).and_then(|diff| hunks(&diff)) {
+            Ok(hunks) => self.hunks = hunks,
+            Err(e) => {
+                self.hunks.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+/// Every changed file, grouped by section and sorted by path
+pub fn changes(repo: &Repository) -> Result<Vec<FileChange>> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+    let mut changes = Vec::new();
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let Some(path) = entry.path() else { continue };
+        let status = entry.status();
+        let mut push = |section, letter| {
+            changes.push(FileChange {
+                path: path.to_string(),
+                section,
+                status: letter,
+            })
+        };
+        if status.is_conflicted() {
+            push(Section::Unstaged, 'U');
+            continue;
+        }
+        let staged = [
+            (Status::INDEX_NEW, 'A'),
+            (Status::INDEX_MODIFIED, 'M'),
+            (Status::INDEX_DELETED, 'D'),
+            (Status::INDEX_RENAMED, 'R'),
+            (Status::INDEX_TYPECHANGE, 'T'),
+        ];
+        if let Some((_, letter)) = staged.iter().find(|(flag, _)| status.contains(*flag)) {
+            push(Section::Staged, *letter);
+        }
+        let unstaged = [
+            (Status::WT_MODIFIED, 'M'),
+            (Status::WT_DELETED, 'D'),
+            (Status::WT_RENAMED, 'R'),
+            (Status::WT_TYPECHANGE, 'T'),
+        ];
+        if status.contains(Status::WT_NEW) {
+            push(Section::Untracked, '?');
+        } else if let Some((_, letter)) = unstaged.iter().find(|(flag, _)| status.contains(*flag)) {
+            push(Section::Unstaged, *letter);
+        }
+    }
+    changes.sort_by(|a, b| (a.section, &a.path).cmp(&(b.section, &b.path)));
+    Ok(changes)
+}
+
+/// Diff of one file: HEAD to index when staged, index to working tree
+/// otherwise (`reverse` swaps the sides, for undoing a hunk)
+fn file_diff<'r>(repo: &'r Repository, change: &FileChange, reverse: bool) -> Result<Diff<'r>> {
+    let mut options = DiffOptions::new();
+    options
+        .pathspec(&change.path)
+        .disable_pathspec_match(true)
+        .reverse(reverse);
+    let diff = match change.section {
+        Section::Staged => {
+            let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+            repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?
+        }
+        Section::Unstaged => repo.diff_index_to_workdir(None, Some(&mut options))?,
+        Section::Untracked => {
+            options
+                .include_untracked(true)
+                .show_untracked_content(true)
+                .recurse_untracked_dirs(true);
+            repo.diff_index_to_workdir(None, Some(&mut options))?
+        }
+    };
+    Ok(diff)
+}
+
+/// The hunks of a single-file diff (none for a binary file)
+fn hunks(diff: &Diff) -> Result<Vec<Hunk>> {
+    let mut hunks = Vec::new();
+    for delta in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, delta)? else {
+            continue;
+        };
+        for index in 0..patch.num_hunks() {
+            let (hunk, count) = patch.hunk(index)?;
+            let header = String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string();
+            let mut lines = Vec::with_capacity(count);
+            for line in 0..count {
+                let line = patch.line_in_hunk(index, line)?;
+                let content = String::from_utf8_lossy(line.content());
+                let origin = match line.origin() {
+                    '+' | '-' | ' ' => line.origin(),
+                    // "\ No newline at end of file" markers
+                    _ => continue,
+                };
+                lines.push(format!(
+                    "{}{}",
+                    origin,
+                    content.trim_end_matches(['\n', '\r'])
+                ));
+            }
+            hunks.push(Hunk { header, lines });
+        }
+    }
+    Ok(hunks)
+}
+
+/// A file's diff with ANSI colors, one entry per buffer line
+pub fn render_diff(change: &FileChange, hunks: &[Hunk]) -> Vec<String> {
+    if hunks.is_empty() {
+        return vec![format!("{} (no textual changes)", change.path)];
+    }
+    let mut lines = vec![format!(
+        "{}{} {}{}",
+        BOLD, change.status, change.path, RESET
+    )];
+    let mut code = DiffHighlighter::for_path(Path::new(&change.path));
+    for hunk in hunks {
+        lines.push(format!("{}{}{}", CYAN, hunk.header, RESET));
+        for line in &hunk.lines {
+            let mut chars = line.chars();
+            let origin = chars.next().unwrap_or(' ');
+            let color = match origin {
+                '+' => GREEN,
+                '-' => RED,
+                _ => "",
+            };
+            lines.push(match (&mut code, origin) {
+                // The marker keeps the diff color; the code after it gets
+                // the file's language colors
+                (Some(code), '+' | '-' | ' ') => format!(
+                    "{}{}{}{}",
+                    color,
+                    origin,
+                    RESET,
+                    code.line(origin, chars.as_str())
+                ),
+                _ if color.is_empty() => line.clone(),
+                _ => format!("{}{}{}", color, line, RESET),
+            });
+        }
+    }
+    lines
+}
+
+/// Apply `action` to a file, or to one hunk of it
+fn apply(
+    repo: &Repository,
+    change: &FileChange,
+    action: Action,
+    hunk: Option<usize>,
+) -> Result<()> {
+    match (action, change.section) {
+        (Action::Stage, Section::Staged) => Err(anyhow!("{} is already staged", change.path)),
+        (Action::Unstage, Section::Unstaged | Section::Untracked) => {
+            Err(anyhow!("{} isn't staged", change.path))
+        }
+        (Action::Discard, Section::Staged) => Err(anyhow!(
+            "Unstage {} before discarding its changes",
+            change.path
+        )),
+        (Action::Stage, _) => match hunk {
+            Some(hunk) => apply_hunk(repo, change, false, ApplyLocation::Index, hunk),
+            None => {
+                let mut index = repo.index()?;
+                let workdir = repo
+                    .workdir()
+                    .ok_or_else(|| anyhow!("Bare repositories have no working tree"))?;
+                if workdir.join(&change.path).exists() {
+                    index.add_path(Path::new(&change.path))?;
+                } else {
+                    index.remove_path(Path::new(&change.path))?;
+                }
+                Ok(index.write()?)
+            }
+        },
+        (Action::Unstage, Section::Staged) => match hunk {
+            Some(hunk) => apply_hunk(repo, change, true, ApplyLocation::Index, hunk),
+            None => match repo.head().ok().and_then(|h| h.peel_to_commit().ok()) {
+                Some(head) => Ok(repo.reset_default(Some(head.as_object()), [&change.path])?),
+                // Nothing committed yet: unstaging drops the file from the index
+                None => {
+                    let mut index = repo.index()?;
+                    index.remove_path(Path::new(&change.path))?;
+                    Ok(index.write()?)
+                }
+            },
+        },
+        (Action::Discard, Section::Untracked) => {
+            let workdir = repo
+                .workdir()
+                .ok_or_else(|| anyhow!("Bare repositories have no working tree"))?;
+            Ok(std::fs::remove_file(workdir.join(&change.path))?)
+        }
+        (Action::Discard, Section::Unstaged) => match hunk {
+            Some(hunk) => apply_hunk(repo, change, true, ApplyLocation::WorkDir, hunk),
+            None => {
+                let mut checkout = CheckoutBuilder::new();
+                checkout.force().path(&change.path);
+                Ok(repo.checkout_index(None, Some(&mut checkout))?)
+            }
+        },
+    }
+}
+
+/// Apply only hunk `wanted` of the file's diff (reversed to undo it)
+fn apply_hunk(
+    repo: &Repository,
+    change: &FileChange,
+    reverse: bool,
+    location: ApplyLocation,
+    wanted: usize,
+) -> Result<()> {
+    let diff = file_diff(repo, change, reverse)?;
+    let mut seen = 0;
+    let mut options = ApplyOptions::new();
+    options.hunk_callback(|_| {
+        seen += 1;
+        seen - 1 == wanted
+    });
+    repo.apply(&diff, location, Some(&mut options))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A repo with `notes.txt` committed as ten numbered lines
+    fn repo(name: &str) -> (PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!("zaxiom-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let lines: Vec<String> = (1..=10).map(|n| format!("line {}", n)).collect();
+        fs::write(dir.join("notes.txt"), lines.join("\n") + "\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        {
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+                .unwrap();
+        }
+        (dir, repo)
+    }
+
+    /// Change the first and last lines: two hunks
+    fn edit(dir: &Path) {
+        let text = fs::read_to_string(dir.join("notes.txt")).unwrap();
+        let text = text
+            .replace("line 1\n", "first\n")
+            .replace("line 10\n", "last\n");
+        fs::write(dir.join("notes.txt"), text).unwrap();
+    }
+
+    fn sections(panel: &GitPanel) -> Vec<(Section, &str, char)> {
+        panel
+            .changes
+            .iter()
+            .map(|c| (c.section, c.path.as_str(), c.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_changes_and_diff() {
+        let (dir, _repo) = repo("gitpanel-diff");
+        edit(&dir);
+        fs::write(dir.join("new.txt"), "hello\n").unwrap();
+
+        let mut panel = GitPanel::new();
+        panel.toggle(&dir);
+        assert_eq!(
+            sections(&panel),
+            [
+                (Section::Unstaged, "notes.txt", 'M'),
+                (Section::Untracked, "new.txt", '?')
+            ]
+        );
+
+        let diff = panel.select(0).unwrap();
+        assert_eq!(panel.hunks.len(), 2);
+        assert_eq!(diff[0], "\x1b[1mM notes.txt\x1b[0m");
+        assert!(diff.contains(&"\x1b[31m-line 1\x1b[0m".to_string()));
+        assert!(diff.contains(&"\x1b[32m+last\x1b[0m".to_string()));
+        let untracked = panel.select(1).unwrap();
+        assert!(untracked.contains(&"\x1b[32m+hello\x1b[0m".to_string()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_stage_and_unstage_a_hunk() {
+        let (dir, repo) = repo("gitpanel-stage");
+        edit(&dir);
+        let mut panel = GitPanel::new();
+        panel.toggle(&dir);
+        panel.select(0);
+        panel.next_hunk();
+        assert!(panel.run(Action::Stage, false).unwrap());
+
+        // The last line is staged, the first is still only in the working tree
+        assert_eq!(
+            sections(&panel),
+            [
+                (Section::Staged, "notes.txt", 'M'),
+                (Section::Unstaged, "notes.txt", 'M')
+            ]
+        );
+        let staged = file_diff(&repo, &panel.changes[0], false).unwrap();
+        assert_eq!(hunks(&staged).unwrap()[0].lines.last().unwrap(), "+last");
+
+        panel.select(0);
+        assert!(panel.run(Action::Unstage, false).unwrap());
+        assert_eq!(sections(&panel), [(Section::Unstaged, "notes.txt", 'M')]);
+        assert!(panel.run(Action::Unstage, true).is_err());
+
+        panel.select(0);
+        assert!(panel.run(Action::Stage, true).unwrap());
+        assert_eq!(sections(&panel), [(Section::Staged, "notes.txt", 'M')]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_discard_asks_twice() {
+        let (dir, _repo) = repo("gitpanel-discard");
+        edit(&dir);
+        fs::write(dir.join("scratch.txt"), "tmp\n").unwrap();
+        let mut panel = GitPanel::new();
+        panel.toggle(&dir);
+
+        panel.select(0);
+        assert!(!panel.run(Action::Discard, false).unwrap());
+        assert!(panel.is_armed(false) && !panel.is_armed(true));
+        assert!(panel.run(Action::Discard, false).unwrap());
+        let text = fs::read_to_string(dir.join("notes.txt")).unwrap();
+        assert!(text.starts_with("line 1\n") && text.ends_with("last\n"));
+
+        // Untracked files go as a whole
+        panel.select(1);
+        assert!(!panel.run(Action::Discard, false).unwrap());
+        assert!(panel.run(Action::Discard, true).unwrap());
+        assert!(!dir.join("scratch.txt").exists());
+        assert_eq!(sections(&panel), [(Section::Unstaged, "notes.txt", 'M')]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
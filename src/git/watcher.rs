@@ -0,0 +1,301 @@
+//! Git status watcher
+//!
+//! Keeps the status bar's branch, ahead/behind counts, and dirty state
+//! current without polling. A background thread listens for filesystem
+//! events in the repository, waits for them to settle, then re-reads the
+//! status with libgit2. Changes show up within a second, and a burst of
+//! events (a checkout, a build) costs a single refresh.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use git2::{Repository, StatusOptions};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet time after the last event before refreshing
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Longest a refresh waits during a steady stream of events
+const MAX_DELAY: Duration = Duration::from_millis(800);
+
+/// Branch and sync state of a repository
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitSnapshot {
+    /// Branch name, or `:abc1234` for a detached HEAD
+    pub branch: String,
+    /// Commits not yet pushed to the upstream
+    pub ahead: usize,
+    /// Commits not yet pulled from the upstream
+    pub behind: usize,
+    /// Uncommitted or untracked changes
+    pub dirty: bool,
+}
+
+/// Watches the repository containing the focused pane's directory
+#[derive(Default)]
+pub struct GitStatusWatcher {
+    /// Directory the current watch was set up for
+    cwd: Option<PathBuf>,
+    watch: Option<RepoWatch>,
+}
+
+/// A live watch on one repository
+struct RepoWatch {
+    root: PathBuf,
+    snapshot: Arc<Mutex<Option<GitSnapshot>>>,
+    /// Dropping the watcher ends the background thread
+    _watcher: RecommendedWatcher,
+}
+
+impl GitStatusWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status of the repository containing `cwd` (None outside a repo
+    /// or before the first read finishes)
+    pub fn status(&mut self, ctx: &egui::Context, cwd: &Path) -> Option<GitSnapshot> {
+        if self.cwd.as_deref() != Some(cwd) {
+            self.cwd = Some(cwd.to_path_buf());
+            match Repository::discover(cwd) {
+                Ok(repo) => {
+                    let root = repo_root(&repo);
+                    // Moving around inside the same repo keeps the watch
+                    if self.watch.as_ref().map(|w| &w.root) != Some(&root) {
+                        self.watch = RepoWatch::start(ctx, repo, root);
+                    }
+                }
+                Err(_) => self.watch = None,
+            }
+        }
+
+        let watch = self.watch.as_ref()?;
+        let snapshot = watch.snapshot.lock().ok()?;
+        snapshot.clone()
+    }
+}
+
+impl RepoWatch {
+    fn start(ctx: &egui::Context, repo: Repository, root: PathBuf) -> Option<Self> {
+        let (tx, rx) = channel::<notify::Result<Event>>();
+        let mut watcher = notify::recommended_watcher(tx).ok()?;
+        watcher.watch(&root, RecursiveMode::Recursive).ok()?;
+
+        let snapshot = Arc::new(Mutex::new(None));
+        let shared = Arc::clone(&snapshot);
+        let repaint = ctx.clone();
+        std::thread::spawn(move || {
+            let publish = |repo: &Repository| {
+                if let Ok(mut snapshot) = shared.lock() {
+                    *snapshot = read_status(repo);
+                }
+                repaint.request_repaint();
+            };
+            publish(&repo);
+
+            let git_dir = repo.path().to_path_buf();
+            while let Ok(event) = rx.recv() {
+                let Ok(event) = event else { continue };
+                if !is_relevant(&event, &git_dir, |path| is_ignored(&repo, path)) {
+                    continue;
+                }
+
+                // Let the burst settle before reading the status
+                let first = Instant::now();
+                while let Some(remaining) = MAX_DELAY.checked_sub(first.elapsed()) {
+                    match rx.recv_timeout(DEBOUNCE.min(remaining)) {
+                        Ok(_) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                publish(&repo);
+            }
+        });
+
+        Some(Self {
+            root,
+            snapshot,
+            _watcher: watcher,
+        })
+    }
+}
+
+/// Working tree root (or the git dir of a bare repo)
+fn repo_root(repo: &Repository) -> PathBuf {
+    repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf()
+}
+
+/// Read branch, ahead/behind, and dirty state
+pub fn read_status(repo: &Repository) -> Option<GitSnapshot> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        // Fresh repo with no commits yet: HEAD points at an unborn branch
+        Err(_) => {
+            let branch = repo
+                .find_reference("HEAD")
+                .ok()?
+                .symbolic_target()?
+                .trim_start_matches("refs/heads/")
+                .to_string();
+            return Some(GitSnapshot {
+                branch,
+                dirty: is_dirty(repo),
+                ..Default::default()
+            });
+        }
+    };
+
+    let branch = if head.is_branch() {
+        head.shorthand().unwrap_or("HEAD").to_string()
+    } else {
+        let id = head.target()?.to_string();
+        format!(":{}", &id[..7])
+    };
+
+    let (ahead, behind) = head
+        .is_branch()
+        .then(|| {
+            let local = git2::Branch::wrap(head);
+            let upstream = local.upstream().ok()?;
+            repo.graph_ahead_behind(local.get().target()?, upstream.get().target()?)
+                .ok()
+        })
+        .flatten()
+        .unwrap_or((0, 0));
+
+    Some(GitSnapshot {
+        branch,
+        ahead,
+        behind,
+        dirty: is_dirty(repo),
+    })
+}
+
+fn is_dirty(repo: &Repository) -> bool {
+    if repo.is_bare() {
+        return false;
+    }
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    repo.statuses(Some(&mut options))
+        .is_ok_and(|statuses| !statuses.is_empty())
+}
+
+fn is_ignored(repo: &Repository, path: &Path) -> bool {
+    let relative = repo
+        .workdir()
+        .and_then(|workdir| path.strip_prefix(workdir).ok());
+    match relative {
+        Some(relative) => repo.status_should_ignore(relative).unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Whether an event can change the branch, sync, or dirty state.
+/// Reads, object/reflog writes, lock files, and ignored paths (build
+/// output) don't.
+fn is_relevant(event: &Event, git_dir: &Path, ignored: impl Fn(&Path) -> bool) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    if event.paths.is_empty() {
+        return true;
+    }
+
+    event
+        .paths
+        .iter()
+        .any(|path| match path.strip_prefix(git_dir) {
+            Ok(inside) => {
+                let top = inside.components().next();
+                let skipped = top.is_some_and(|c| {
+                    let name = c.as_os_str();
+                    name == "objects" || name == "logs"
+                });
+                !skipped && path.extension().is_none_or(|ext| ext != "lock")
+            }
+            Err(_) => !ignored(path),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let git_dir = Path::new("/repo/.git");
+        let modify = EventKind::Modify(notify::event::ModifyKind::Any);
+        let ignored = |path: &Path| path.starts_with("/repo/target");
+
+        assert!(is_relevant(
+            &event(modify, "/repo/.git/HEAD"),
+            git_dir,
+            ignored
+        ));
+        assert!(is_relevant(
+            &event(modify, "/repo/.git/refs/heads/main"),
+            git_dir,
+            ignored
+        ));
+        assert!(is_relevant(
+            &event(modify, "/repo/src/main.rs"),
+            git_dir,
+            ignored
+        ));
+        assert!(!is_relevant(
+            &event(modify, "/repo/.git/objects/ab/cdef"),
+            git_dir,
+            ignored
+        ));
+        assert!(!is_relevant(
+            &event(modify, "/repo/.git/index.lock"),
+            git_dir,
+            ignored
+        ));
+        assert!(!is_relevant(
+            &event(modify, "/repo/target/debug/app"),
+            git_dir,
+            ignored
+        ));
+        assert!(!is_relevant(
+            &event(
+                EventKind::Access(notify::event::AccessKind::Any),
+                "/repo/.git/HEAD"
+            ),
+            git_dir,
+            ignored
+        ));
+    }
+
+    #[test]
+    fn test_read_status() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-gitwatch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let branch = read_status(&repo).unwrap().branch;
+        assert!(!read_status(&repo).unwrap().dirty);
+
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        std::fs::write(dir.join("notes.txt"), "hi").unwrap();
+
+        let status = read_status(&repo).unwrap();
+        assert_eq!(status.branch, branch);
+        assert!(status.dirty);
+        assert_eq!((status.ahead, status.behind), (0, 0));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
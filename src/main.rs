@@ -49,9 +49,23 @@ fn load_icon() -> Arc<egui::IconData> {
 }
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // --portable / --config-dir: where config, sessions, and logs are kept
+    config::paths::init(config::paths::Paths::from_args(&args));
+
     // Initialize environment (load .env file, create if needed)
     config::env::init_env();
 
+    // --self-test (hidden): run the builtin smoke suite and exit, no window
+    if args.iter().any(|arg| arg == "--self-test") {
+        let passed = shell::self_test::run();
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // --restricted: allowlisted builtins only (demos, kiosks, shared machines)
+    let restricted = args.iter().any(|arg| arg == "--restricted");
+
     let icon = load_icon();
 
     let viewport = egui::ViewportBuilder::default()
@@ -69,6 +83,6 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Zaxiom",
         options,
-        Box::new(|cc| Ok(Box::new(ZaxiomApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(ZaxiomApp::new(cc, restricted)))),
     )
 }
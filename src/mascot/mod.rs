@@ -62,6 +62,11 @@ pub struct Mascot {
     is_blinking: bool,
     blink_until: Instant,
     colors: MascotColors,
+    /// Whether celebrations draw confetti
+    pub confetti: bool,
+    /// Something is still being worked on (an AI answer streaming in); the
+    /// mascot keeps thinking until this is cleared
+    pub waiting: bool,
 }
 
 impl Default for Mascot {
@@ -80,6 +85,8 @@ impl Mascot {
             is_blinking: false,
             blink_until: Instant::now(),
             colors: MascotColors::default(),
+            confetti: true,
+            waiting: false,
         }
     }
 
@@ -87,6 +94,14 @@ impl Mascot {
     pub fn update(&mut self) {
         self.frame = self.frame.wrapping_add(1);
 
+        if self.waiting {
+            self.last_activity = Instant::now();
+            if self.mood != MascotMood::Thinking {
+                self.set_mood(MascotMood::Thinking);
+            }
+            self.mood_started = Instant::now();
+        }
+
         // Check for sleepy mode
         if self.last_activity.elapsed() > Duration::from_secs(30) && self.mood != MascotMood::Sleepy
         {
@@ -253,7 +268,7 @@ impl Mascot {
         let head_center = Pos2::new(center.x + sway, rect.top() + 28.0 + bounce);
 
         // Draw special effects first (behind mascot)
-        if self.mood == MascotMood::Celebrating {
+        if self.mood == MascotMood::Celebrating && self.confetti {
             self.draw_confetti(&painter, center);
         }
         if self.mood == MascotMood::Confused {
@@ -1,8 +1,12 @@
 //! Terminal grid for proper VT100/ANSI emulation
 //!
 //! Implements a 2D character grid with cursor positioning and ANSI escape sequence handling.
+//! Rows remember whether they were soft-wrapped, so the main screen and its
+//! scrollback reflow when the pane is resized; full-screen programs on the
+//! alternate screen redraw themselves instead.
 
 use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
 
 /// A single cell in the terminal grid
 #[derive(Debug, Clone)]
@@ -21,6 +25,10 @@ pub struct Cell {
     pub italic: bool,
     /// Underline
     pub underline: bool,
+    /// Swap foreground and background
+    pub inverse: bool,
+    /// Strikethrough
+    pub strikethrough: bool,
 }
 
 impl Default for Cell {
@@ -33,11 +41,22 @@ impl Default for Cell {
             dim: false,
             italic: false,
             underline: false,
+            inverse: false,
+            strikethrough: false,
         }
     }
 }
 
 impl Cell {
+    /// A space with no colors or attributes (what erasing leaves behind)
+    pub fn is_blank(&self) -> bool {
+        self.ch == ' '
+            && self.bg.is_none()
+            && !self.inverse
+            && !self.underline
+            && !self.strikethrough
+    }
+
     #[allow(dead_code)]
     pub fn with_char(ch: char) -> Self {
         Self {
@@ -47,6 +66,37 @@ impl Cell {
     }
 }
 
+/// One row of cells
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub cells: Vec<Cell>,
+    /// The text continues on the next row (wrapped at the edge, not ended by a newline)
+    pub wrapped: bool,
+}
+
+impl Row {
+    fn blank(cols: usize) -> Self {
+        Self {
+            cells: vec![Cell::default(); cols],
+            wrapped: false,
+        }
+    }
+}
+
+impl Deref for Row {
+    type Target = Vec<Cell>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl DerefMut for Row {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cells
+    }
+}
+
 /// Parser state for ANSI escape sequences
 #[derive(Debug, Clone, PartialEq)]
 enum ParserState {
@@ -61,7 +111,7 @@ enum ParserState {
 /// Terminal grid with VT100/ANSI emulation
 pub struct TerminalGrid {
     /// The grid of cells (rows x cols)
-    cells: Vec<Vec<Cell>>,
+    cells: Vec<Row>,
     /// Number of rows
     rows: usize,
     /// Number of columns
@@ -71,7 +121,7 @@ pub struct TerminalGrid {
     /// Cursor column (0-indexed)
     cursor_col: usize,
     /// Scrollback buffer (lines that scrolled off the top)
-    scrollback: VecDeque<Vec<Cell>>,
+    scrollback: VecDeque<Row>,
     /// Maximum scrollback lines
     max_scrollback: usize,
     /// Parser state
@@ -87,17 +137,27 @@ pub struct TerminalGrid {
     current_dim: bool,
     current_italic: bool,
     current_underline: bool,
+    current_inverse: bool,
+    current_strikethrough: bool,
+    /// Scrolling region (DECSTBM), first and last row inclusive
+    scroll_top: usize,
+    scroll_bottom: usize,
+    /// Cursor shown (DECTCEM)
+    cursor_visible: bool,
     /// Alternate screen mode
     alternate_screen: bool,
     /// Saved main screen (when in alternate mode)
-    saved_cells: Option<Vec<Vec<Cell>>>,
-    saved_scrollback: Option<VecDeque<Vec<Cell>>>,
+    saved_cells: Option<Vec<Row>>,
+    saved_scrollback: Option<VecDeque<Row>>,
     saved_cursor: Option<(usize, usize)>,
+    /// Main screen cursor while the alternate screen is shown (kept apart
+    /// from `saved_cursor`, which programs also use with ESC 7 / CSI s)
+    main_cursor: Option<(usize, usize)>,
 }
 
 impl TerminalGrid {
     pub fn new(rows: usize, cols: usize) -> Self {
-        let cells = vec![vec![Cell::default(); cols]; rows];
+        let cells = vec![Row::blank(cols); rows];
         Self {
             cells,
             rows,
@@ -115,10 +175,16 @@ impl TerminalGrid {
             current_dim: false,
             current_italic: false,
             current_underline: false,
+            current_inverse: false,
+            current_strikethrough: false,
+            scroll_top: 0,
+            scroll_bottom: rows.saturating_sub(1),
+            cursor_visible: true,
             alternate_screen: false,
             saved_cells: None,
             saved_scrollback: None,
             saved_cursor: None,
+            main_cursor: None,
         }
     }
 
@@ -187,11 +253,9 @@ impl TerminalGrid {
                     0x07 => {
                         // Bell - ignore
                     }
-                    0x08 => {
+                    0x08 if self.cursor_col > 0 => {
                         // Backspace
-                        if self.cursor_col > 0 {
-                            self.cursor_col -= 1;
-                        }
+                        self.cursor_col -= 1;
                     }
                     0x09 => {
                         // Tab
@@ -245,10 +309,10 @@ impl TerminalGrid {
                         self.parser_state = ParserState::Normal;
                     }
                     b'M' => {
-                        // Reverse index (scroll down)
-                        if self.cursor_row == 0 {
+                        // Reverse index (scroll down at the top of the scroll region)
+                        if self.cursor_row == self.scroll_top {
                             self.scroll_down();
-                        } else {
+                        } else if self.cursor_row > 0 {
                             self.cursor_row -= 1;
                         }
                         self.parser_state = ParserState::Normal;
@@ -400,7 +464,7 @@ impl TerminalGrid {
                 for i in 0..n {
                     let col = self.cursor_col + i;
                     if col < self.cols {
-                        self.cells[self.cursor_row][col] = Cell::default();
+                        self.cells[self.cursor_row][col] = self.blank_cell();
                     }
                 }
             }
@@ -413,28 +477,46 @@ impl TerminalGrid {
                 // SGR (Select Graphic Rendition)
                 self.handle_sgr(&params);
             }
-            b'h' => {
+            b'h' if params_str.starts_with('?') => {
                 // Set Mode
-                if params_str.starts_with('?') {
-                    // DEC Private Mode Set
-                    if params.contains(&1049) || params.contains(&47) {
-                        // Alternate screen buffer
-                        self.enter_alternate_screen();
-                    }
+                // DEC Private Mode Set
+                if params.contains(&25) {
+                    self.cursor_visible = true;
+                }
+                if params.iter().any(|p| matches!(p, 47 | 1047 | 1049)) {
+                    // Alternate screen buffer
+                    self.enter_alternate_screen();
                 }
             }
-            b'l' => {
+            b'l' if params_str.starts_with('?') => {
                 // Reset Mode
-                if params_str.starts_with('?') {
-                    // DEC Private Mode Reset
-                    if params.contains(&1049) || params.contains(&47) {
-                        // Exit alternate screen
-                        self.exit_alternate_screen();
-                    }
+                // DEC Private Mode Reset
+                if params.contains(&25) {
+                    self.cursor_visible = false;
+                }
+                if params.iter().any(|p| matches!(p, 47 | 1047 | 1049)) {
+                    // Exit alternate screen
+                    self.exit_alternate_screen();
                 }
             }
             b'r' => {
-                // Set Scrolling Region - ignore for now
+                // Set Scrolling Region (whole screen without parameters)
+                let top = param(0, 1) - 1;
+                let bottom = params
+                    .get(1)
+                    .copied()
+                    .filter(|&b| b > 0)
+                    .unwrap_or(self.rows)
+                    .min(self.rows)
+                    - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                } else {
+                    self.reset_scroll_region();
+                }
+                self.cursor_row = 0;
+                self.cursor_col = 0;
             }
             b's' => {
                 // Save Cursor Position
@@ -469,12 +551,16 @@ impl TerminalGrid {
                 2 => self.current_dim = true,
                 3 => self.current_italic = true,
                 4 => self.current_underline = true,
+                7 => self.current_inverse = true,
+                9 => self.current_strikethrough = true,
                 22 => {
                     self.current_bold = false;
                     self.current_dim = false;
                 }
                 23 => self.current_italic = false,
                 24 => self.current_underline = false,
+                27 => self.current_inverse = false,
+                29 => self.current_strikethrough = false,
                 30..=37 => self.current_fg = Some((params[i] - 30) as u32),
                 38 => {
                     // Extended foreground color
@@ -523,12 +609,37 @@ impl TerminalGrid {
         self.current_dim = false;
         self.current_italic = false;
         self.current_underline = false;
+        self.current_inverse = false;
+        self.current_strikethrough = false;
+    }
+
+    /// What erased cells become: blank, keeping the current background
+    fn blank_cell(&self) -> Cell {
+        Cell {
+            bg: self.current_bg,
+            ..Default::default()
+        }
+    }
+
+    /// A row of erased cells
+    fn blank_row(&self) -> Row {
+        Row {
+            cells: vec![self.blank_cell(); self.cols],
+            wrapped: false,
+        }
+    }
+
+    /// Scroll the whole screen again
+    fn reset_scroll_region(&mut self) {
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows.saturating_sub(1);
     }
 
     /// Put a character at current cursor position
     fn put_char(&mut self, ch: char) {
         if self.cursor_col >= self.cols {
             // Wrap to next line
+            self.cells[self.cursor_row].wrapped = true;
             self.cursor_col = 0;
             self.line_feed();
         }
@@ -541,38 +652,41 @@ impl TerminalGrid {
             dim: self.current_dim,
             italic: self.current_italic,
             underline: self.current_underline,
+            inverse: self.current_inverse,
+            strikethrough: self.current_strikethrough,
         };
         self.cursor_col += 1;
     }
 
-    /// Line feed (move cursor down, scroll if needed)
+    /// Line feed (move cursor down, scroll the region at its bottom)
     fn line_feed(&mut self) {
-        if self.cursor_row < self.rows - 1 {
-            self.cursor_row += 1;
-        } else {
+        if self.cursor_row == self.scroll_bottom {
             self.scroll_up();
+        } else if self.cursor_row < self.rows - 1 {
+            self.cursor_row += 1;
         }
     }
 
-    /// Scroll the screen up (move content up, new line at bottom)
+    /// Scroll the region up (move content up, new line at its bottom).
+    /// Only rows leaving the top of the whole main screen go to scrollback.
     fn scroll_up(&mut self) {
-        if !self.cells.is_empty() {
-            let top_row = self.cells.remove(0);
-            if !self.alternate_screen {
+        if self.scroll_bottom < self.cells.len() {
+            let top_row = self.cells.remove(self.scroll_top);
+            if self.scroll_top == 0 && !self.alternate_screen {
                 self.scrollback.push_back(top_row);
                 while self.scrollback.len() > self.max_scrollback {
                     self.scrollback.pop_front();
                 }
             }
-            self.cells.push(vec![Cell::default(); self.cols]);
+            self.cells.insert(self.scroll_bottom, self.blank_row());
         }
     }
 
-    /// Scroll the screen down (move content down, new line at top)
+    /// Scroll the region down (move content down, new line at its top)
     fn scroll_down(&mut self) {
-        if !self.cells.is_empty() {
-            self.cells.pop();
-            self.cells.insert(0, vec![Cell::default(); self.cols]);
+        if self.scroll_bottom < self.cells.len() {
+            self.cells.remove(self.scroll_bottom);
+            self.cells.insert(self.scroll_top, self.blank_row());
         }
     }
 
@@ -583,20 +697,20 @@ impl TerminalGrid {
                 // Erase from cursor to end of screen
                 self.erase_in_line(0);
                 for row in (self.cursor_row + 1)..self.rows {
-                    self.cells[row] = vec![Cell::default(); self.cols];
+                    self.cells[row] = self.blank_row();
                 }
             }
             1 => {
                 // Erase from start to cursor
                 for row in 0..self.cursor_row {
-                    self.cells[row] = vec![Cell::default(); self.cols];
+                    self.cells[row] = self.blank_row();
                 }
                 self.erase_in_line(1);
             }
             2 | 3 => {
                 // Erase entire screen (3 also clears scrollback)
                 for row in 0..self.rows {
-                    self.cells[row] = vec![Cell::default(); self.cols];
+                    self.cells[row] = self.blank_row();
                 }
                 if mode == 3 {
                     self.scrollback.clear();
@@ -610,53 +724,60 @@ impl TerminalGrid {
     fn erase_in_line(&mut self, mode: usize) {
         match mode {
             0 => {
-                // Erase from cursor to end of line
+                // Erase from cursor to end of line (the line now ends here)
+                let blank = self.blank_cell();
+                let row = &mut self.cells[self.cursor_row];
                 for col in self.cursor_col..self.cols {
-                    self.cells[self.cursor_row][col] = Cell::default();
+                    row[col] = blank.clone();
                 }
+                row.wrapped = false;
             }
             1 => {
                 // Erase from start to cursor
                 for col in 0..=self.cursor_col.min(self.cols - 1) {
-                    self.cells[self.cursor_row][col] = Cell::default();
+                    self.cells[self.cursor_row][col] = self.blank_cell();
                 }
             }
             2 => {
                 // Erase entire line
-                self.cells[self.cursor_row] = vec![Cell::default(); self.cols];
+                self.cells[self.cursor_row] = self.blank_row();
             }
             _ => {}
         }
     }
 
-    /// Insert blank lines at cursor
+    /// Insert blank lines at cursor, pushing lines off the bottom of the scroll region
     fn insert_lines(&mut self, n: usize) {
-        for _ in 0..n {
-            if self.cursor_row < self.rows {
-                self.cells.pop();
-                self.cells
-                    .insert(self.cursor_row, vec![Cell::default(); self.cols]);
-            }
+        if self.cursor_row < self.scroll_top || self.cursor_row > self.scroll_bottom {
+            return;
+        }
+        for _ in 0..n.min(self.scroll_bottom - self.cursor_row + 1) {
+            self.cells.remove(self.scroll_bottom);
+            self.cells.insert(self.cursor_row, self.blank_row());
         }
+        self.cursor_col = 0;
     }
 
-    /// Delete lines at cursor
+    /// Delete lines at cursor, pulling up lines from the bottom of the scroll region
     fn delete_lines(&mut self, n: usize) {
-        for _ in 0..n {
-            if self.cursor_row < self.rows {
-                self.cells.remove(self.cursor_row);
-                self.cells.push(vec![Cell::default(); self.cols]);
-            }
+        if self.cursor_row < self.scroll_top || self.cursor_row > self.scroll_bottom {
+            return;
         }
+        for _ in 0..n.min(self.scroll_bottom - self.cursor_row + 1) {
+            self.cells.remove(self.cursor_row);
+            self.cells.insert(self.scroll_bottom, self.blank_row());
+        }
+        self.cursor_col = 0;
     }
 
     /// Delete characters at cursor
     fn delete_chars(&mut self, n: usize) {
+        let blank = self.blank_cell();
         let row = &mut self.cells[self.cursor_row];
         for _ in 0..n {
             if self.cursor_col < row.len() {
                 row.remove(self.cursor_col);
-                row.push(Cell::default());
+                row.push(blank.clone());
             }
         }
     }
@@ -667,12 +788,13 @@ impl TerminalGrid {
             self.alternate_screen = true;
             self.saved_cells = Some(std::mem::replace(
                 &mut self.cells,
-                vec![vec![Cell::default(); self.cols]; self.rows],
+                vec![Row::blank(self.cols); self.rows],
             ));
             self.saved_scrollback = Some(std::mem::take(&mut self.scrollback));
-            self.saved_cursor = Some((self.cursor_row, self.cursor_col));
+            self.main_cursor = Some((self.cursor_row, self.cursor_col));
             self.cursor_row = 0;
             self.cursor_col = 0;
+            self.reset_scroll_region();
         }
     }
 
@@ -686,46 +808,125 @@ impl TerminalGrid {
             if let Some(scrollback) = self.saved_scrollback.take() {
                 self.scrollback = scrollback;
             }
-            if let Some((row, col)) = self.saved_cursor.take() {
+            if let Some((row, col)) = self.main_cursor.take() {
                 self.cursor_row = row;
                 self.cursor_col = col;
             }
+            self.reset_scroll_region();
+
+            // The pane was resized while the program ran
+            let width = self.cells.first().map_or(0, |row| row.len());
+            if self.cells.len() != self.rows || width != self.cols {
+                self.reflow(self.rows, self.cols);
+            }
         }
     }
 
-    /// Resize the grid
+    /// Resize the grid. The main screen and scrollback are rewrapped to the
+    /// new width; the alternate screen is cut or padded, since its program
+    /// redraws on resize.
     pub fn resize(&mut self, new_rows: usize, new_cols: usize) {
         // Ensure minimum dimensions to avoid empty grid panics
         let new_rows = new_rows.max(1);
         let new_cols = new_cols.max(1);
 
-        // Resize each existing row
-        for row in &mut self.cells {
-            row.resize(new_cols, Cell::default());
+        if self.alternate_screen {
+            for row in &mut self.cells {
+                row.resize(new_cols, Cell::default());
+            }
+            self.cells.resize(new_rows, Row::blank(new_cols));
+            self.cursor_row = self.cursor_row.min(new_rows - 1);
+            self.cursor_col = self.cursor_col.min(new_cols - 1);
+        } else {
+            self.reflow(new_rows, new_cols);
         }
 
-        // Add or remove rows
-        while self.cells.len() < new_rows {
-            self.cells.push(vec![Cell::default(); new_cols]);
+        self.rows = new_rows;
+        self.cols = new_cols;
+        self.reset_scroll_region();
+    }
+
+    /// Rewrap scrollback and screen into `new_rows` x `new_cols`, keeping the
+    /// cursor on the same character and blank rows below it dropped
+    fn reflow(&mut self, new_rows: usize, new_cols: usize) {
+        let cursor_index = self.scrollback.len() + self.cursor_row;
+        let old_rows: Vec<Row> = self
+            .scrollback
+            .drain(..)
+            .chain(self.cells.drain(..))
+            .collect();
+
+        // Join wrapped rows back into logical lines
+        let mut lines: Vec<Vec<Cell>> = Vec::new();
+        let mut current: Vec<Cell> = Vec::new();
+        let mut cursor = (0, 0);
+        for (index, row) in old_rows.into_iter().enumerate() {
+            if index == cursor_index {
+                cursor = (lines.len(), current.len() + self.cursor_col);
+            }
+            let wrapped = row.wrapped;
+            current.extend(row.cells);
+            if !wrapped {
+                while current.last().is_some_and(Cell::is_blank) {
+                    current.pop();
+                }
+                lines.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        while lines.len() > cursor.0 + 1 && lines.last().is_some_and(Vec::is_empty) {
+            lines.pop();
         }
-        while self.cells.len() > new_rows {
-            let removed = self.cells.remove(0);
-            if !self.alternate_screen {
-                self.scrollback.push_back(removed);
+
+        // Split them again at the new width
+        let mut rows: Vec<Row> = Vec::new();
+        let (mut cursor_row, mut cursor_col) = (0, 0);
+        for (index, mut line) in lines.into_iter().enumerate() {
+            let first = rows.len();
+            let mut needed = line.len().div_ceil(new_cols).max(1);
+            if index == cursor.0 {
+                needed = needed.max(cursor.1 / new_cols + 1);
+                cursor_row = first + cursor.1 / new_cols;
+                cursor_col = cursor.1 % new_cols;
+            }
+            line.resize(needed * new_cols, Cell::default());
+            for (chunk, cells) in line.chunks(new_cols).enumerate() {
+                rows.push(Row {
+                    cells: cells.to_vec(),
+                    wrapped: chunk + 1 < needed,
+                });
             }
         }
+        while rows.len() < new_rows {
+            rows.push(Row::blank(new_cols));
+        }
 
-        // Resize scrollback rows
-        for row in &mut self.scrollback {
-            row.resize(new_cols, Cell::default());
+        // The last screenful is the screen, everything above is scrollback
+        let split = rows.len() - new_rows;
+        self.cells = rows.split_off(split);
+        self.scrollback = rows.into();
+        while self.scrollback.len() > self.max_scrollback {
+            self.scrollback.pop_front();
         }
+        self.cursor_row = cursor_row.saturating_sub(split).min(new_rows - 1);
+        self.cursor_col = cursor_col.min(new_cols - 1);
+    }
 
-        self.rows = new_rows;
-        self.cols = new_cols;
+    /// Every row, scrollback first
+    pub fn all_rows(&self) -> impl Iterator<Item = &Row> {
+        self.scrollback.iter().chain(self.cells.iter())
+    }
+
+    /// Number of scrollback rows above the screen
+    pub fn scrollback_len(&self) -> usize {
+        self.scrollback.len()
+    }
 
-        // Clamp cursor
-        self.cursor_row = self.cursor_row.min(new_rows.saturating_sub(1));
-        self.cursor_col = self.cursor_col.min(new_cols.saturating_sub(1));
+    /// Whether the program wants the cursor shown
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
     }
 
     /// Get the visible grid as lines of text with ANSI colors preserved
@@ -745,6 +946,18 @@ impl TerminalGrid {
         result
     }
 
+    /// The program exited: restore the main screen if it was left on the
+    /// alternate one, and return the scrollback and main screen as lines
+    /// (trailing blank rows dropped) - what is kept in the pane's history
+    pub fn finish(&mut self) -> Vec<String> {
+        self.exit_alternate_screen();
+        let mut lines = self.get_lines();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+
     /// Get only the visible screen (no scrollback)
     #[allow(dead_code)]
     pub fn get_visible_lines(&self) -> Vec<String> {
@@ -763,6 +976,8 @@ impl TerminalGrid {
         let mut last_dim = false;
         let mut last_italic = false;
         let mut last_underline = false;
+        let mut last_inverse = false;
+        let mut last_strikethrough = false;
 
         for cell in row {
             // Check if we need to change attributes
@@ -771,7 +986,9 @@ impl TerminalGrid {
                 || (cell.bold != last_bold)
                 || (cell.dim != last_dim)
                 || (cell.italic != last_italic)
-                || (cell.underline != last_underline);
+                || (cell.underline != last_underline)
+                || (cell.inverse != last_inverse)
+                || (cell.strikethrough != last_strikethrough);
 
             if need_reset {
                 // Build SGR sequence
@@ -789,6 +1006,12 @@ impl TerminalGrid {
                 if cell.underline {
                     sgr_parts.push("4".to_string());
                 }
+                if cell.inverse {
+                    sgr_parts.push("7".to_string());
+                }
+                if cell.strikethrough {
+                    sgr_parts.push("9".to_string());
+                }
 
                 if let Some(fg) = cell.fg {
                     if fg >= 0x1000000 {
@@ -829,6 +1052,8 @@ impl TerminalGrid {
                 last_dim = cell.dim;
                 last_italic = cell.italic;
                 last_underline = cell.underline;
+                last_inverse = cell.inverse;
+                last_strikethrough = cell.strikethrough;
             }
 
             result.push(cell.ch);
@@ -841,6 +1066,8 @@ impl TerminalGrid {
             || last_dim
             || last_italic
             || last_underline
+            || last_inverse
+            || last_strikethrough
         {
             result.push_str("\x1b[0m");
         }
@@ -855,23 +1082,23 @@ impl TerminalGrid {
     }
 
     /// Get dimensions (rows, cols)
-    #[allow(dead_code)]
     pub fn dimensions(&self) -> (usize, usize) {
         (self.rows, self.cols)
     }
 
     /// Check if in alternate screen mode
-    #[allow(dead_code)]
     pub fn is_alternate_screen(&self) -> bool {
         self.alternate_screen
     }
 
     /// Clear the grid and scrollback
     pub fn clear(&mut self) {
-        self.cells = vec![vec![Cell::default(); self.cols]; self.rows];
+        self.cells = vec![Row::blank(self.cols); self.rows];
         self.scrollback.clear();
         self.cursor_row = 0;
         self.cursor_col = 0;
+        self.cursor_visible = true;
+        self.reset_scroll_region();
         self.utf8_buffer.clear();
         self.parser_state = ParserState::Normal;
         self.escape_buffer.clear();
@@ -928,4 +1155,92 @@ mod tests {
         let lines = grid.get_visible_lines();
         assert_eq!(lines[0].trim(), "Hello");
     }
+
+    #[test]
+    fn test_scroll_region() {
+        let mut grid = TerminalGrid::new(5, 10);
+        grid.process(b"head\n1\n2\n3\nfoot");
+        // Scroll rows 2-4 only, like a pager keeping a header and status line
+        grid.process(b"\x1b[2;4r\x1b[4;1H\nnew");
+        let lines = grid.get_visible_lines();
+        assert_eq!(lines, vec!["head", "2", "3", "new", "foot"]);
+        assert_eq!(grid.scrollback_len(), 0);
+
+        // Reverse index at the top of the region scrolls it down
+        grid.process(b"\x1b[2;1H\x1bM");
+        assert_eq!(grid.get_visible_lines(), vec!["head", "", "2", "3", "foot"]);
+    }
+
+    #[test]
+    fn test_attributes_and_cursor_visibility() {
+        let mut grid = TerminalGrid::new(2, 10);
+        grid.process(b"\x1b[7;9mA\x1b[27mB\x1b[0mC\x1b[?25l");
+        let row = grid.all_rows().next().unwrap();
+        assert!(row[0].inverse && row[0].strikethrough);
+        assert!(!row[1].inverse && row[1].strikethrough);
+        assert!(!row[2].strikethrough);
+        assert!(!grid.cursor_visible());
+        grid.process(b"\x1b[?25h");
+        assert!(grid.cursor_visible());
+    }
+
+    #[test]
+    fn test_reflow_on_resize() {
+        let mut grid = TerminalGrid::new(4, 10);
+        grid.process(b"abcdefghijklmn\nxy");
+        assert_eq!(grid.get_visible_lines()[..3], ["abcdefghij", "klmn", "xy"]);
+
+        // Wider: the wrapped line joins back up, the cursor stays after "xy"
+        grid.resize(4, 20);
+        assert_eq!(grid.get_visible_lines()[..2], ["abcdefghijklmn", "xy"]);
+        assert_eq!(grid.cursor_position(), (1, 2));
+
+        // Narrower: it wraps again and pushes the top into scrollback
+        grid.resize(2, 5);
+        assert_eq!(grid.get_visible_lines(), vec!["klmn", "xy"]);
+        assert_eq!(grid.get_lines()[..2], ["abcde", "fghij"]);
+        assert_eq!(grid.cursor_position(), (1, 2));
+    }
+
+    #[test]
+    fn test_alternate_screen_keeps_main_screen() {
+        let mut grid = TerminalGrid::new(3, 10);
+        grid.process(b"$ htop");
+        grid.process(b"\x1b[?1049h\x1b[2;1HCPU");
+        assert!(grid.is_alternate_screen());
+        assert_eq!(grid.get_lines(), vec!["", "CPU", ""]);
+
+        // Resized while htop runs: the main screen is rewrapped on the way out
+        grid.resize(3, 4);
+        grid.process(b"\x1b[?1049l");
+        assert!(!grid.is_alternate_screen());
+        assert_eq!(grid.get_lines(), vec!["$ ht", "op", ""]);
+    }
+
+    #[test]
+    fn test_finish_restores_main_screen() {
+        let mut grid = TerminalGrid::new(3, 10);
+        grid.process(
+            b"$ vim
+",
+        );
+        grid.process(
+            b"[?1049h[H~
+~7[3;1H-- INSERT --",
+        );
+
+        // ESC 7 in the program doesn't move where the main cursor comes back to
+        grid.process(b"[?1049l");
+        assert_eq!(grid.cursor_position(), (1, 0));
+        assert_eq!(grid.get_lines(), vec!["$ vim", "", ""]);
+
+        // A program that dies on the alternate screen leaves none of it behind
+        grid.process(
+            b"[?1049h[2J~
+~
+~",
+        );
+        assert_eq!(grid.finish(), vec!["$ vim"]);
+        assert!(!grid.is_alternate_screen());
+    }
 }
@@ -7,6 +7,7 @@ pub mod buffer;
 pub mod grid;
 pub mod input;
 pub mod session;
+pub mod ssh;
 
 pub use buffer::PtyBuffer;
 pub use grid::TerminalGrid;
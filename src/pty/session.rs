@@ -1,14 +1,18 @@
 //! PTY session management
 //!
-//! Handles spawning and communicating with a PTY process (PowerShell on Windows).
+//! Handles spawning and communicating with a PTY process (PowerShell on Windows),
+//! or a remote shell over SSH shown the same way.
 
 use anyhow::{Context, Result};
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread::{self, JoinHandle};
 
+use super::ssh::{self, InputWriter, Message, SshTarget};
+
 /// Output from the PTY process
 #[derive(Debug)]
 pub enum PtyOutput {
@@ -20,12 +24,28 @@ pub enum PtyOutput {
     Error(String),
 }
 
+/// What a session is connected to
+enum Process {
+    /// A program on this machine
+    Local {
+        /// The master PTY handle for resizing
+        master: Box<dyn MasterPty + Send>,
+        /// Child process handle
+        child: Box<dyn Child + Send + Sync>,
+    },
+    /// A shell on another machine, run by an SSH worker thread
+    Remote {
+        /// Resizes and hang-ups for the worker
+        messages: Sender<Message>,
+        /// `user@host`
+        label: String,
+    },
+}
+
 /// A PTY session connected to a shell process
 pub struct PtySession {
-    /// The master PTY handle for resizing
-    master: Box<dyn MasterPty + Send>,
-    /// Child process handle
-    child: Box<dyn Child + Send + Sync>,
+    /// The process behind the terminal
+    process: Process,
     /// Writer to send input to PTY
     writer: Box<dyn Write + Send>,
     /// Channel receiver for output from reader thread
@@ -42,16 +62,18 @@ impl PtySession {
     /// Create a new PTY session with PowerShell
     #[allow(dead_code)]
     pub fn new(rows: u16, cols: u16, cwd: &Path) -> Result<Self> {
-        Self::new_with_command("powershell.exe", &[], rows, cols, cwd)
+        Self::new_with_command("powershell.exe", &[], rows, cols, cwd, &HashMap::new())
     }
 
-    /// Create a new PTY session with a specific command and arguments
+    /// Create a new PTY session with a specific command and arguments,
+    /// started in `cwd` with `env` added to the inherited environment
     pub fn new_with_command(
         program: &str,
         args: &[String],
         rows: u16,
         cols: u16,
         cwd: &Path,
+        env: &HashMap<String, String>,
     ) -> Result<Self> {
         let size = PtySize {
             rows,
@@ -68,7 +90,7 @@ impl PtySession {
 
         // Build the command to spawn
         // Run commands directly via cmd.exe /c for proper PATH resolution and TTY handling
-        let cmd = {
+        let mut cmd = {
             #[cfg(windows)]
             {
                 // Use cmd.exe /c to run the command - this:
@@ -111,6 +133,9 @@ impl PtySession {
                 cmd
             }
         };
+        for (name, value) in env {
+            cmd.env(name, value);
+        }
 
         // Spawn the process directly attached to PTY
         let child = pair
@@ -138,8 +163,10 @@ impl PtySession {
         });
 
         Ok(Self {
-            master: pair.master,
-            child,
+            process: Process::Local {
+                master: pair.master,
+                child,
+            },
             writer,
             output_rx,
             _shutdown_tx: shutdown_tx,
@@ -148,6 +175,46 @@ impl PtySession {
         })
     }
 
+    /// Open an SSH session to `target` in a grid of `rows` x `cols`. It
+    /// connects in the background: login questions and errors show up as
+    /// output, and a failed login ends with `Exited(Some(255))`.
+    pub fn ssh(target: SshTarget, rows: u16, cols: u16, home: PathBuf) -> Self {
+        let (output_tx, output_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = mpsc::channel();
+        let (messages, messages_rx) = mpsc::channel();
+        let label = target.label();
+        let reader_handle = ssh::spawn(
+            target,
+            (rows, cols),
+            home,
+            output_tx,
+            messages_rx,
+            shutdown_rx,
+        );
+
+        Self {
+            writer: Box::new(InputWriter(messages.clone())),
+            process: Process::Remote { messages, label },
+            output_rx,
+            _shutdown_tx: shutdown_tx,
+            _reader_handle: reader_handle,
+            size: PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            },
+        }
+    }
+
+    /// `user@host` when this is an SSH session
+    pub fn remote(&self) -> Option<&str> {
+        match &self.process {
+            Process::Remote { label, .. } => Some(label),
+            Process::Local { .. } => None,
+        }
+    }
+
     /// Reader loop that runs in a separate thread
     fn reader_loop(
         mut reader: Box<dyn Read + Send>,
@@ -217,36 +284,51 @@ impl PtySession {
         self.output_rx.try_recv().ok()
     }
 
-    /// Resize the PTY
-    pub fn resize(&self, rows: u16, cols: u16) -> Result<()> {
+    /// Resize the PTY (the child is sent SIGWINCH, or ConPTY's equivalent)
+    pub fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
         let new_size = PtySize {
             rows,
             cols,
             pixel_width: 0,
             pixel_height: 0,
         };
-        self.master
-            .resize(new_size)
-            .context("Failed to resize PTY")?;
+        match &self.process {
+            Process::Local { master, .. } => {
+                master.resize(new_size).context("Failed to resize PTY")?
+            }
+            Process::Remote { messages, .. } => {
+                let _ = messages.send(Message::Resize { rows, cols });
+            }
+        }
+        self.size = new_size;
         Ok(())
     }
 
     /// Get the current size
+    #[allow(dead_code)]
     pub fn size(&self) -> (u16, u16) {
         (self.size.rows, self.size.cols)
     }
 
-    /// Check if the child process is still running
+    /// Check if the child process (or SSH connection) is still running
     #[allow(dead_code)]
     pub fn is_running(&mut self) -> bool {
-        self.child.try_wait().ok().flatten().is_none()
+        match &mut self.process {
+            Process::Local { child, .. } => child.try_wait().ok().flatten().is_none(),
+            Process::Remote { .. } => !self._reader_handle.is_finished(),
+        }
     }
 
-    /// Kill the child process
+    /// Kill the child process, or hang up the SSH connection
     pub fn kill(&mut self) -> Result<()> {
-        self.child.kill().context("Failed to kill child process")
+        match &mut self.process {
+            Process::Local { child, .. } => child.kill().context("Failed to kill child process"),
+            Process::Remote { messages, .. } => {
+                let _ = messages.send(Message::Close);
+                Ok(())
+            }
+        }
     }
-
 }
 
 impl Drop for PtySession {
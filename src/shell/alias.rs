@@ -0,0 +1,292 @@
+//! Alias expansion
+//!
+//! Expands aliases before a command line is routed and run:
+//!
+//! - command aliases in command position (`ll` -> `ls -la`), following an
+//!   alias of an alias but never the same one twice, so `a -> b -> a`
+//!   cycles stop instead of looping. Command position is the start of the
+//!   line and anything after `|`, `&&`, `||`, or `;`
+//! - alias arguments: an alias using `$1`..`$9` or `$@` takes the words
+//!   typed after it there instead of at the end (`alias gco='git checkout
+//!   $1 && git pull'`)
+//! - global aliases anywhere outside quotes (`ls G toml` -> `ls | grep toml`)
+//! - suffix aliases: a file name typed as the command opens with the handler
+//!   for its extension (`notes.md` -> `nano notes.md`)
+
+use std::collections::HashSet;
+
+use crate::config::aliases::AliasConfig;
+
+/// A word or the whitespace and pipes between words
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Word(String),
+    Separator(String),
+}
+
+/// Expand the aliases in a command line. `session` looks up aliases made
+/// with `alias name=value`, which win over the configured ones.
+pub fn expand_aliases(
+    input: &str,
+    config: &AliasConfig,
+    session: impl Fn(&str) -> Option<String>,
+) -> String {
+    let input = input.trim();
+    // AI prompts and Python code are not command lines
+    if input.starts_with('#') || (input.starts_with('!') && input.ends_with('!')) {
+        return input.to_string();
+    }
+
+    // Global aliases first: they may add pipes, and so new commands
+    let line: String = split(input)
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Word(word) => config.global.get(&word).cloned().unwrap_or(word),
+            Piece::Separator(separator) => separator,
+        })
+        .collect();
+
+    let pieces = split(&line);
+    let mut result = String::new();
+    let mut command_position = true;
+    let mut i = 0;
+    while i < pieces.len() {
+        match &pieces[i] {
+            Piece::Separator(separator) => {
+                command_position |= separator.contains('|');
+                result.push_str(separator);
+            }
+            Piece::Word(word) if command_position => {
+                // `ll;` is a command with no arguments
+                if let Some(name) = word.strip_suffix(';').filter(|name| !name.is_empty()) {
+                    result.push_str(&expand_command(name, &[], config, &session).0);
+                    result.push(';');
+                    i += 1;
+                    continue;
+                }
+                // Its arguments run up to the next pipe, `&&`, `||`, or `;`
+                let end = pieces[i + 1..]
+                    .iter()
+                    .position(ends_command)
+                    .map_or(pieces.len(), |n| i + 1 + n);
+                let (command, used) = expand_command(word, &pieces[i + 1..end], config, &session);
+                result.push_str(&command);
+                command_position = false;
+                i += 1 + used;
+                continue;
+            }
+            Piece::Word(word) => {
+                command_position = ends_command(&pieces[i]) || word.ends_with(';');
+                result.push_str(word);
+            }
+        }
+        i += 1;
+    }
+    result
+}
+
+/// A pipe or a word joining commands
+fn ends_command(piece: &Piece) -> bool {
+    match piece {
+        Piece::Separator(separator) => separator.contains('|'),
+        Piece::Word(word) => matches!(word.as_str(), "&&" | "||" | ";"),
+    }
+}
+
+/// Expand a word in command position through command and suffix aliases.
+/// Returns the expansion and how many of the `args` pieces it took.
+fn expand_command(
+    word: &str,
+    args: &[Piece],
+    config: &AliasConfig,
+    session: &impl Fn(&str) -> Option<String>,
+) -> (String, usize) {
+    let mut seen = HashSet::new();
+    let mut command = word.to_string();
+    loop {
+        let (first, rest) = first_word(&command);
+        if !seen.insert(first.to_string()) {
+            break;
+        }
+        let Some(value) = session(first).or_else(|| config.get(first).map(str::to_string)) else {
+            break;
+        };
+        command = format!("{}{}", value, rest);
+    }
+    if command != word {
+        if let Some(expanded) = substitute_args(&command, args) {
+            return expanded;
+        }
+    }
+
+    let (first, rest) = first_word(&command);
+    let file = first.trim_matches(|c| c == '"' || c == '\'');
+    let command = match config.suffix_handler(file) {
+        Some(handler) if handler.contains("{}") => {
+            format!("{}{}", handler.replace("{}", first), rest)
+        }
+        Some(handler) => format!("{} {}{}", handler, first, rest),
+        None => command,
+    };
+    (command, 0)
+}
+
+/// Fill `$1`..`$9` and `$@` (or `$*`) in an alias's expansion from the
+/// words after it. Returns the result and how many `args` pieces were
+/// used (up to the highest `$n`, or all for `$@`), or `None` if the
+/// expansion takes no arguments.
+fn substitute_args(expansion: &str, args: &[Piece]) -> Option<(String, usize)> {
+    let words: Vec<&str> = args
+        .iter()
+        .filter_map(|piece| match piece {
+            Piece::Word(word) => Some(word.as_str()),
+            Piece::Separator(_) => None,
+        })
+        .collect();
+
+    let mut result = String::new();
+    let mut taken = None;
+    let mut chars = expansion.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = chars.peek().copied();
+        match (c, next) {
+            ('$', Some(n @ '1'..='9')) => {
+                chars.next();
+                let n = n as usize - '0' as usize;
+                result.push_str(words.get(n - 1).copied().unwrap_or(""));
+                taken = taken.max(Some(n));
+            }
+            ('$', Some('@' | '*')) => {
+                chars.next();
+                result.push_str(&words.join(" "));
+                taken = Some(usize::MAX);
+            }
+            _ => result.push(c),
+        }
+    }
+
+    // Pieces up to and including the last word taken
+    let taken = taken?.min(words.len());
+    let used = args
+        .iter()
+        .enumerate()
+        .filter(|(_, piece)| matches!(piece, Piece::Word(_)))
+        .nth(taken.wrapping_sub(1))
+        .map_or(0, |(i, _)| i + 1);
+    Some((result, used))
+}
+
+/// The first word of a command and the rest (with its leading space)
+fn first_word(command: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (i, c) in command.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (_, Some(q)) if c == q => quote = None,
+            (_, None) if c.is_whitespace() => return command.split_at(i),
+            _ => {}
+        }
+    }
+    (command, "")
+}
+
+/// Split a line into words and separators, keeping quoted text in its word
+fn split(input: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut word = String::new();
+    let mut separator = String::new();
+    let mut quote = None;
+
+    for c in input.chars() {
+        let separates = quote.is_none() && (c.is_whitespace() || c == '|');
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (_, Some(q)) if c == q => quote = None,
+            _ => {}
+        }
+        if separates {
+            if !word.is_empty() {
+                pieces.push(Piece::Word(std::mem::take(&mut word)));
+            }
+            separator.push(c);
+        } else {
+            if !separator.is_empty() {
+                pieces.push(Piece::Separator(std::mem::take(&mut separator)));
+            }
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        pieces.push(Piece::Word(word));
+    }
+    if !separator.is_empty() {
+        pieces.push(Piece::Separator(separator));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_aliases() {
+        let mut config = AliasConfig::new();
+        config.add("ll".into(), "ls -la".into());
+        config.add("a".into(), "b --from-a".into());
+        config.add("b".into(), "a --from-b".into());
+        config.suffix.insert("md".into(), "nano".into());
+        config
+            .suffix
+            .insert(".html".into(), "open --with firefox {}".into());
+        config.global.insert("G".into(), "| grep".into());
+        let session = |name: &str| (name == "ll").then(|| "ls -l".to_string());
+        let expand = |input: &str| expand_aliases(input, &config, session);
+
+        // Session aliases win; global aliases add a pipe; quotes are left alone
+        assert_eq!(expand("ll src G toml"), "ls -l src | grep toml");
+        assert_eq!(expand("echo 'G' ll"), "echo 'G' ll");
+        assert_eq!(expand("cat x|ll"), "cat x|ls -l");
+        // Cycles stop after each alias has been used once
+        assert_eq!(expand("a x"), "a --from-b --from-a x");
+        // Suffix aliases, by handler or template, case-insensitive
+        assert_eq!(expand("notes.MD -n"), "nano notes.MD -n");
+        assert_eq!(
+            expand("\"my page.html\""),
+            "open --with firefox \"my page.html\""
+        );
+        assert_eq!(expand("cat notes.md"), "cat notes.md");
+        assert_eq!(expand("# what is notes.md"), "# what is notes.md");
+        // After `&&`, `||`, and `;` too
+        assert_eq!(
+            expand("cd src && ll; ll x || ll"),
+            "cd src && ls -l; ls -l x || ls -l"
+        );
+    }
+
+    #[test]
+    fn test_alias_arguments() {
+        let mut config = AliasConfig::new();
+        config.add("gco".into(), "git checkout $1 && git pull".into());
+        config.add("swap".into(), "mv $2 $1".into());
+        config.add("each".into(), "for f in $@; do echo $f; done".into());
+        config.add("co".into(), "gco".into());
+        let expand = |input: &str| expand_aliases(input, &config, |_| None);
+
+        assert_eq!(expand("gco main"), "git checkout main && git pull");
+        // Words past the highest `$n` still go at the end
+        assert_eq!(expand("swap a b -v"), "mv b a -v");
+        assert_eq!(expand("swap a"), "mv  a");
+        assert_eq!(
+            expand("each 'x y' z | wc -l"),
+            "for f in 'x y' z; do echo $f; done | wc -l"
+        );
+        // Through an alias of an alias, stopping at the end of the command
+        assert_eq!(
+            expand("co dev && co main"),
+            "git checkout dev && git pull && git checkout main && git pull"
+        );
+        // `$` in what's typed is left for the shell
+        assert_eq!(expand("echo $1"), "echo $1");
+    }
+}
@@ -1,16 +1,42 @@
 //! Command execution
 //!
 //! Routes commands to appropriate handlers and executes them.
+//!
+//! One executor serves every pane and holds nothing a command can change:
+//! the registry is shared behind an `Arc` and the restricted-mode policy is
+//! fixed at startup. Everything that varies per pane - working directory,
+//! exported variables, directory stack, script nesting - is in the
+//! `TerminalState` passed to each call, so commands running in two panes at
+//! once only ever touch their own. External programs are started in the
+//! pane's directory with its variables rather than the process's.
 
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
-use std::process::Command;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
+use chrono::Local;
 
-use super::parser::{parse_command_line, ParsedCommand, RedirectType};
+use super::job::{Job, JobStatus};
+use super::parser::{
+    parse_command_line, parse_command_line_with, ParsedCommand, Pipeline, RedirectType,
+};
+use super::pipeline::run_pipeline;
+use super::restricted::RestrictedMode;
+use super::script::{is_compound, ScriptRunner};
+use super::wildcard;
 use crate::ai::handle_ai_chat_with_context;
+use crate::commands::context::{CommandContext, Interrupted};
 use crate::commands::registry::CommandRegistry;
+use crate::commands::search::FindQuery;
+use crate::commands::shell::WatchOptions;
+use crate::pty::ssh::{self, SshTarget};
+use crate::pty::PtySession;
 use crate::terminal::state::TerminalState;
 
 /// Indicates how a command should be executed
@@ -22,22 +48,107 @@ pub enum ExecutionTarget {
     PtyRaw,
     /// Special handling (python mode, AI chat, easter eggs)
     Special,
+    /// Run a slow built-in on a worker thread (see `Executor::spawn_job`)
+    Background,
 }
 
-/// Command executor
+/// Built-ins that can take a while (network round trips, walking, hashing,
+/// or packing large trees). A command line using any of them runs as a
+/// background job so the window stays responsive.
+const BACKGROUND_COMMANDS: &[&str] = &[
+    "ping",
+    "traceroute",
+    "curl",
+    "wget",
+    "scrape",
+    "speedtest",
+    "lanscan",
+    "whois",
+    "nslookup",
+    "host",
+    "tlscert",
+    "ssh",
+    "watch",
+    "find",
+    "fd",
+    "grep",
+    "du",
+    "md5sum",
+    "sha1sum",
+    "sha224sum",
+    "sha256sum",
+    "sha384sum",
+    "sha512sum",
+    "blake3sum",
+    "crc32",
+    "tar",
+    "zip",
+    "unzip",
+    "gzip",
+    "gunzip",
+    "sleep",
+    "gp",
+    "gpl",
+];
+
+/// Built-ins whose arguments are patterns or arithmetic of their own
+/// (`find -name *.rs`, `expr 6 * 7`), left unexpanded
+const LITERAL_PATTERN_COMMANDS: &[&str] = &["find", "expr", "bc", "alias", "watch"];
+
+/// How often `watch` and captured programs check for Ctrl+C
+const TICK: Duration = Duration::from_millis(50);
+
+/// How deeply scripts may `source` other scripts
+const MAX_SCRIPT_DEPTH: usize = 16;
+
+/// Command executor (cheap to clone; clones share the registry)
+#[derive(Clone)]
 pub struct Executor {
     /// Registry of built-in commands
-    registry: CommandRegistry,
+    registry: Arc<CommandRegistry>,
+    /// Restricted-mode policy, if active
+    restricted: Option<Arc<RestrictedMode>>,
+    /// Script run when a pane opens, and by `source` without a file
+    startup_script: Option<Arc<PathBuf>>,
 }
 
 impl Executor {
     /// Create a new executor
     pub fn new() -> Self {
         Self {
-            registry: CommandRegistry::new(),
+            registry: Arc::new(CommandRegistry::new()),
+            restricted: None,
+            startup_script: None,
         }
     }
 
+    /// Set the startup script (`None` turns startup scripts off)
+    pub fn set_startup_script(&mut self, path: Option<PathBuf>) {
+        self.startup_script = path.map(Arc::new);
+    }
+
+    /// Run the startup script in a new pane's state. `None` when there is no
+    /// script to run, otherwise what it printed or where it failed.
+    pub fn run_startup_script(&self, state: &mut TerminalState) -> Option<Result<String>> {
+        let path = self
+            .startup_script
+            .as_deref()
+            .filter(|path| path.is_file())?;
+        let name = display_path(path, state);
+        let result = self.source_file(path, &name, &[], state, &CommandContext::default());
+        Some(result.map_err(|e| e.context(format!("{} failed", name))))
+    }
+
+    /// Turn restricted mode on (with a policy) or off
+    pub fn set_restricted(&mut self, restricted: Option<RestrictedMode>) {
+        self.restricted = restricted.map(Arc::new);
+    }
+
+    /// Whether restricted mode is active
+    pub fn is_restricted(&self) -> bool {
+        self.restricted.is_some()
+    }
+
     /// Execute a command line (simplified API without history context)
     /// Kept for API compatibility - use execute_with_history for full functionality
     #[allow(dead_code)]
@@ -51,19 +162,41 @@ impl Executor {
         input: &str,
         state: &mut TerminalState,
         history: Option<&[String]>,
+    ) -> Result<String> {
+        self.execute_with_context(input, state, history, &CommandContext::default())
+    }
+
+    /// Execute a command line that can be cancelled through `ctx`; builtins
+    /// that support it stop early, and pipelines and scripts stop between
+    /// commands
+    pub fn execute_with_context(
+        &self,
+        input: &str,
+        state: &mut TerminalState,
+        history: Option<&[String]>,
+        ctx: &CommandContext,
     ) -> Result<String> {
         let input = input.trim();
         if input.is_empty() {
             return Ok(String::new());
         }
 
+        // Restricted mode: only allowlisted builtins (easter eggs are harmless)
+        if let Some(restricted) = &self.restricted {
+            if self.check_easter_eggs(input).is_none() {
+                restricted.check(input, state.cwd())?;
+            }
+        }
+
         // Check for Python mode: ! code !
         if input.starts_with('!') && input.ends_with('!') && input.len() > 2 {
             let python_code = &input[1..input.len() - 1].trim();
-            return self.execute_python(python_code);
+            return self.execute_python(python_code, state);
         }
 
-        // Check for AI chat mode: # prompt
+        // AI chat mode: # prompt. This waits for the whole answer, so it is
+        // only for jobs and scripts; typed questions stream in through
+        // `prepare_ai_chat` instead
         if input.starts_with('#') {
             return Ok(handle_ai_chat_with_context(input, state, history));
         }
@@ -73,64 +206,117 @@ impl Executor {
             return Ok(easter_egg);
         }
 
+        // `cmd &` runs as a numbered job without holding up the pane
+        if let Some(command) = detached(input) {
+            return self.start_background(command, state);
+        }
+
+        // if/for/while and function definitions run as a one-off script
+        if is_compound(input) {
+            return self.run_source(input, "zaxiom", &[], state, ctx);
+        }
+
         // Parse the command line
-        let pipeline = parse_command_line(input).map_err(|e| anyhow!("{}", e))?;
+        let pipeline = parse_command_line_with(input, state.exported_vars())?;
 
         // Handle pipelines
         if !pipeline.is_single() {
-            return self.execute_native_pipeline(&pipeline, input, state);
+            return run_pipeline(self, &pipeline, state, ctx);
         }
 
         let cmd = pipeline.first().ok_or_else(|| anyhow!("Empty command"))?;
 
         // Execute single command with redirections
-        self.execute_single_command(cmd, None, state)
+        self.execute_single_command(cmd, None, false, state, ctx)
     }
 
-    /// Execute a single command with redirections
-    fn execute_single_command(
+    /// Execute a single command with redirections. `piped` says its output
+    /// goes to the next command in a pipeline rather than the screen.
+    pub(super) fn execute_single_command(
         &self,
         cmd: &ParsedCommand,
         stdin_input: Option<&str>,
+        piped: bool,
         state: &mut TerminalState,
+        ctx: &CommandContext,
     ) -> Result<String> {
-        // Handle input redirection
-        let stdin = if let Some(input_redir) = cmd
+        // Unquoted wildcards match files in the pane's directory
+        let expanded;
+        let cmd = if cmd.patterns.is_empty()
+            || LITERAL_PATTERN_COMMANDS.contains(&cmd.command.as_str())
+        {
+            cmd
+        } else {
+            expanded = wildcard::expand_command(cmd, state.cwd(), state.home());
+            &expanded
+        };
+
+        // Handle input redirection (the last of <, << and <<< wins)
+        let stdin = match cmd.redirections.iter().rev().find(|r| {
+            matches!(
+                r.redirect_type,
+                RedirectType::Input | RedirectType::HereDoc | RedirectType::HereString
+            )
+        }) {
+            Some(redir) if redir.redirect_type == RedirectType::Input => {
+                let path = state.resolve_path(&redir.target);
+                let mut file = File::open(&path).map_err(|e| anyhow!("{}: {}", redir.target, e))?;
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|e| anyhow!("{}: {}", redir.target, e))?;
+                Some(content)
+            }
+            Some(redir) if redir.redirect_type == RedirectType::HereString => {
+                Some(format!("{}\n", redir.target))
+            }
+            Some(redir) => Some(redir.target.clone()),
+            None => stdin_input.map(|s| s.to_string()),
+        };
+
+        state.omit_newline = false;
+
+        // Output redirected to a file isn't streamed to the screen
+        let redirected = cmd
             .redirections
             .iter()
-            .find(|r| r.redirect_type == RedirectType::Input)
-        {
-            let path = state.resolve_path(&input_redir.target);
-            let mut file =
-                File::open(&path).map_err(|e| anyhow!("{}: {}", input_redir.target, e))?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)
-                .map_err(|e| anyhow!("{}: {}", input_redir.target, e))?;
-            Some(content)
+            .any(|r| matches!(r.redirect_type, RedirectType::Output | RedirectType::Append));
+        let ctx = &if redirected {
+            ctx.without_output()
         } else {
-            stdin_input.map(|s| s.to_string())
+            ctx.clone()
         };
+        state.output_piped = piped || redirected;
 
         // Check for --help or -h flag
         let wants_help = cmd.args.iter().any(|a| a == "--help" || a == "-h");
 
         // Execute the command - native only, no fallbacks
-        let output = if self.registry.has_command(&cmd.command) {
+        let output = if let Some(body) = state.get_function(&cmd.command) {
+            // Shell functions come before built-ins of the same name
+            self.run_source(&body, &cmd.command, &cmd.args, state, ctx)?
+        } else if self.registry.has_command(&cmd.command) {
             if wants_help {
                 // Return extended help instead of executing
                 self.registry.get_help(&cmd.command)
+            } else if cmd.command == "source" || cmd.command == "." {
+                self.run_script(cmd, state, ctx)?
+            } else if cmd.command == "watch" {
+                self.run_watch(cmd, state, ctx)?
+            } else if cmd.command == "find" && FindQuery::has_exec(&cmd.args) {
+                self.run_find_exec(cmd, state, ctx)?
             } else {
                 // Built-in command - execute directly (instant!)
-                self.registry.execute_with_stdin(
+                self.registry.execute_with_context(
                     &cmd.command,
                     &cmd.args,
                     stdin.as_deref(),
                     state,
+                    ctx,
                 )?
             }
         } else if let Some(expanded) = self.expand_git_shortcut(&cmd.command, &cmd.args) {
             // Git shortcut - run git directly
-            self.execute_git(&expanded)?
+            self.execute_git(&expanded, state)?
         } else {
             // Unknown command - fail instantly with style
             return Err(anyhow!("🤷 '{}' — never heard of it lol", cmd.command));
@@ -145,7 +331,8 @@ impl Executor {
             let path = state.resolve_path(&output_redir.target);
             let mut file =
                 File::create(&path).map_err(|e| anyhow!("{}: {}", output_redir.target, e))?;
-            writeln!(file, "{}", output).map_err(|e| anyhow!("{}: {}", output_redir.target, e))?;
+            write_output(&mut file, &output, state.omit_newline)
+                .map_err(|e| anyhow!("{}: {}", output_redir.target, e))?;
             return Ok(String::new()); // No output to terminal when redirecting
         }
 
@@ -161,74 +348,72 @@ impl Executor {
                 .append(true)
                 .open(&path)
                 .map_err(|e| anyhow!("{}: {}", append_redir.target, e))?;
-            writeln!(file, "{}", output).map_err(|e| anyhow!("{}: {}", append_redir.target, e))?;
+            write_output(&mut file, &output, state.omit_newline)
+                .map_err(|e| anyhow!("{}: {}", append_redir.target, e))?;
             return Ok(String::new()); // No output to terminal when redirecting
         }
 
         Ok(output)
     }
 
-    /// Execute a pipeline of native commands
-    fn execute_native_pipeline(
+    /// Run a script file: `source <file> [args...]`, or `source` alone to
+    /// re-run the startup script
+    fn run_script(
         &self,
-        pipeline: &super::parser::Pipeline,
-        _original_input: &str,
+        cmd: &ParsedCommand,
         state: &mut TerminalState,
+        ctx: &CommandContext,
     ) -> Result<String> {
-        // Check if all commands in the pipeline are built-in
-        let all_builtin = pipeline
-            .commands
-            .iter()
-            .all(|cmd| self.registry.has_command(&cmd.command));
-
-        if !all_builtin {
-            // Find the first non-builtin command
-            let unknown = pipeline
-                .commands
-                .iter()
-                .find(|cmd| !self.registry.has_command(&cmd.command))
-                .map(|cmd| cmd.command.as_str())
-                .unwrap_or("unknown");
-            return Err(anyhow!("🤷 '{}' — never heard of it lol", unknown));
-        }
-
-        // Execute native pipeline
-        let mut output: Option<String> = None;
+        let Some(file) = cmd.args.first() else {
+            let path = self
+                .startup_script
+                .as_deref()
+                .ok_or_else(|| anyhow!("usage: source <file> [args...]"))?;
+            return self.source_file(path, &display_path(path, state), &[], state, ctx);
+        };
+        let path = state.resolve_path(file);
+        self.source_file(&path, file, &cmd.args[1..], state, ctx)
+    }
 
-        for (i, cmd) in pipeline.commands.iter().enumerate() {
-            let result = self.execute_single_command(cmd, output.as_deref(), state)?;
+    /// Run the script at `path`, called `name` in errors and `$0`
+    fn source_file(
+        &self,
+        path: &Path,
+        name: &str,
+        args: &[String],
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let source = fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", name, e))?;
+        self.run_source(&source, name, args, state, ctx)
+    }
 
-            if i < pipeline.commands.len() - 1 {
-                output = Some(result);
-            } else {
-                return Ok(result);
-            }
+    /// Run script `source` (a file's contents, a function body, or a
+    /// compound command line) called `name`, with positional `args`
+    fn run_source(
+        &self,
+        source: &str,
+        name: &str,
+        args: &[String],
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if state.script_depth >= MAX_SCRIPT_DEPTH {
+            return Err(anyhow!("{}: scripts nested too deeply", name));
         }
 
-        Ok(output.unwrap_or_default())
+        state.script_depth += 1;
+        let result = ScriptRunner::new(self, name, args)
+            .with_context(ctx.clone())
+            .run(source, state);
+        state.script_depth -= 1;
+        result
     }
 
-    /// Expand git shortcuts to full git commands
+    /// Expand git shortcuts to full git commands (gs, gd, gl, ga, gc, gp,
+    /// and gpl are native built-ins)
     fn expand_git_shortcut(&self, command: &str, args: &[String]) -> Option<String> {
         match command {
-            "gs" => Some("status".to_string()),
-            "gd" => Some("diff".to_string()),
-            "gl" => Some("log --oneline -20".to_string()),
-            "gp" => Some("push".to_string()),
-            "gpl" => Some("pull".to_string()),
-            "ga" => {
-                if args.is_empty() {
-                    Some("add .".to_string())
-                } else {
-                    Some(format!("add {}", args.join(" ")))
-                }
-            }
-            "gc" => {
-                if args.is_empty() {
-                    return None; // Need a message
-                }
-                Some(format!("commit -m \"{}\"", args.join(" ")))
-            }
             "gco" => {
                 if args.is_empty() {
                     return None;
@@ -241,11 +426,13 @@ impl Executor {
     }
 
     /// Execute git command directly (fast!)
-    fn execute_git(&self, git_args: &str) -> Result<String> {
+    fn execute_git(&self, git_args: &str, state: &TerminalState) -> Result<String> {
         let args: Vec<&str> = git_args.split_whitespace().collect();
 
         let output = Command::new("git")
             .args(&args)
+            .current_dir(state.cwd())
+            .envs(state.exported_vars())
             .output()
             .map_err(|e| anyhow!("git: {}", e))?;
 
@@ -260,9 +447,11 @@ impl Executor {
     }
 
     /// Execute Python code directly: ! print("hello") !
-    fn execute_python(&self, code: &str) -> Result<String> {
+    fn execute_python(&self, code: &str, state: &TerminalState) -> Result<String> {
         let output = Command::new("python")
             .args(["-c", code])
+            .current_dir(state.cwd())
+            .envs(state.exported_vars())
             .output()
             .map_err(|e| anyhow!("🐍 Python error: {}", e))?;
 
@@ -276,6 +465,153 @@ impl Executor {
         Ok(format!("🐍 {}", stdout.trim()))
     }
 
+    /// Re-run a command line on an interval: `watch [options] <command>`.
+    /// In the pane each run redraws the last one until Ctrl+C; piped or in
+    /// a script, the runs (one unless `-c`) are printed one after another.
+    fn run_watch(
+        &self,
+        cmd: &ParsedCommand,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let options = WatchOptions::parse(&cmd.args)?;
+        let live = ctx.can_redraw();
+        let count = options.count.or((!live).then_some(1));
+        // The watched command's own output is shown through the frames
+        let inner = ctx.without_output();
+
+        let mut frames = Vec::new();
+        let mut runs = 0;
+        loop {
+            let output = match self.run_captured(&options.command, state, &inner) {
+                Ok(output) => output,
+                Err(e) if e.downcast_ref::<Interrupted>().is_some() => return Err(e),
+                Err(e) => format!("{:#}", e),
+            };
+            let time = Local::now().format("%H:%M:%S").to_string();
+            let frame = options.frame(output.trim_end_matches('\n'), &time);
+            if live {
+                ctx.redraw(&frame);
+            } else {
+                frames.push(frame);
+            }
+            runs += 1;
+            if count.is_some_and(|count| runs >= count) {
+                return Ok(frames.join("\n\n"));
+            }
+
+            // Sleep in short steps so Ctrl+C stops it promptly
+            let next = Instant::now() + options.interval;
+            while let Some(left) = next.checked_duration_since(Instant::now()) {
+                ctx.check()?;
+                thread::sleep(left.min(TICK));
+            }
+        }
+    }
+
+    /// `find ... -exec`: run the command for the matches, built-ins and
+    /// programs alike, showing what each run printed
+    fn run_find_exec(
+        &self,
+        cmd: &ParsedCommand,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let query = FindQuery::parse(&cmd.args)?;
+        let found = query.run(state, ctx)?;
+        let inner = ctx.without_output();
+
+        let mut output = found.output;
+        for argv in query.exec_argvs(&found.paths, &state.cwd().clone()) {
+            ctx.check()?;
+            let result = match self.run_argv(&argv, state, &inner) {
+                Ok(result) => result,
+                Err(e) if e.downcast_ref::<Interrupted>().is_some() => return Err(e),
+                Err(e) => format!("{:#}", e),
+            };
+            let result = result.trim_end_matches('\n');
+            if result.is_empty() {
+                continue;
+            }
+            if ctx.is_streaming() {
+                ctx.print(result);
+            } else {
+                output.push(result.to_string());
+            }
+        }
+        Ok(output.join("\n"))
+    }
+
+    /// Run a command given as separate words, with no shell in between
+    /// (`find -exec`): built-ins and shell functions here, other programs
+    /// directly with their output captured like `run_captured`
+    fn run_argv(
+        &self,
+        argv: &[String],
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        let (program, args) = argv.split_first().ok_or_else(|| anyhow!("Empty command"))?;
+        if let Some(restricted) = &self.restricted {
+            if !restricted.allows(program, state.cwd()) {
+                return Err(anyhow!("🔒 '{}' isn't allowed in restricted mode", program));
+            }
+        }
+        let native = self.restricted.is_some()
+            || state.get_function(program).is_some()
+            || self.registry.has_command(program)
+            || self.expand_git_shortcut(program, args).is_some();
+        if native {
+            let cmd = ParsedCommand {
+                command: program.clone(),
+                args: args.to_vec(),
+                redirections: Vec::new(),
+                span: 0..0,
+                patterns: Vec::new(),
+            };
+            return self.execute_single_command(&cmd, None, false, state, ctx);
+        }
+        let mut command = Command::new(program);
+        command.args(args);
+        capture_process(command, program, state, ctx)
+    }
+
+    /// Run a command line without the pane's terminal: built-ins here,
+    /// other programs through the system shell with their output captured
+    /// (see `capture_process`)
+    fn run_captured(
+        &self,
+        input: &str,
+        state: &mut TerminalState,
+        ctx: &CommandContext,
+    ) -> Result<String> {
+        if self.route_command_in(input, state) != ExecutionTarget::PtyRaw {
+            return self.execute_with_context(input, state, None, ctx);
+        }
+        let command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.args(["/C", input]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", input]);
+            command
+        };
+        capture_process(command, input, state, ctx)
+    }
+
+    /// Start a command line as a numbered job in the pane's job table
+    /// (`cmd &`): `[1] cmd`
+    fn start_background(&self, input: &str, state: &TerminalState) -> Result<String> {
+        let executor = self.clone();
+        let line = input.to_string();
+        let job = Job::start(state.snapshot(), move |state, ctx| {
+            executor.run_captured(&line, state, ctx)
+        });
+        let id = state.jobs().add(input, job, JobStatus::Running);
+        Ok(format!("[{}] {}", id, input))
+    }
+
     /// Check for easter eggs 🥚
     fn check_easter_eggs(&self, input: &str) -> Option<String> {
         let input_lower = input.to_lowercase();
@@ -358,6 +694,11 @@ impl Executor {
     pub fn route_command(&self, input: &str) -> ExecutionTarget {
         let input = input.trim();
 
+        // Restricted mode never spawns external programs directly
+        if self.restricted.is_some() {
+            return ExecutionTarget::Native;
+        }
+
         // Special modes first
         if input.starts_with('!') && input.ends_with('!') && input.len() > 2 {
             return ExecutionTarget::Special; // Python mode
@@ -369,10 +710,25 @@ impl Executor {
             return ExecutionTarget::Special; // Easter egg
         }
 
+        // if/for/while and function definitions run in the pane's state, and
+        // `cmd &` only starts a job
+        if is_compound(input) || detached(input).is_some() {
+            return ExecutionTarget::Native;
+        }
+
         // Parse to get the command
         let pipeline = match parse_command_line(input) {
             Ok(p) => p,
-            Err(_) => return ExecutionTarget::PtyRaw, // Let PTY handle malformed input
+            // Builtins report the syntax error; other programs get the line
+            // as typed (their shell may understand it, e.g. `&&` in cmd.exe)
+            Err(_) => {
+                let first_word = input.split_whitespace().next().unwrap_or_default();
+                return if self.registry.has_command(first_word) {
+                    ExecutionTarget::Native
+                } else {
+                    ExecutionTarget::PtyRaw
+                };
+            }
         };
 
         // For pipelines, check if all commands are built-in
@@ -382,7 +738,7 @@ impl Executor {
                 .iter()
                 .all(|cmd| self.registry.has_command(&cmd.command));
             if all_builtin {
-                return ExecutionTarget::Native;
+                return builtin_target(&pipeline);
             } else {
                 // External pipelines need PTY for proper TTY handling
                 return ExecutionTarget::PtyRaw;
@@ -393,9 +749,14 @@ impl Executor {
         if let Some(cmd) = pipeline.first() {
             let cmd_name = cmd.command.as_str();
 
+            // `ssh host` on its own line opens a session in the grid
+            if ssh_session(input).is_some() {
+                return ExecutionTarget::PtyRaw;
+            }
+
             // Check if it's a built-in command - these are fast native implementations
             if self.registry.has_command(cmd_name) {
-                return ExecutionTarget::Native;
+                return builtin_target(&pipeline);
             }
 
             // Check for git shortcuts - these are also fast
@@ -411,11 +772,238 @@ impl Executor {
         ExecutionTarget::PtyRaw
     }
 
+    /// Like `route_command`, for a line entered in the pane with `state`:
+    /// calls of the pane's shell functions run natively too
+    pub fn route_command_in(&self, input: &str, state: &TerminalState) -> ExecutionTarget {
+        let target = self.route_command(input);
+        let calls_function = || {
+            parse_command_line(input).is_ok_and(|pipeline| {
+                pipeline
+                    .commands
+                    .iter()
+                    .any(|cmd| state.get_function(&cmd.command).is_some())
+            })
+        };
+        if target == ExecutionTarget::PtyRaw && calls_function() {
+            ExecutionTarget::Native
+        } else {
+            target
+        }
+    }
+
+    /// Program and arguments that run an external command line in a PTY. A
+    /// plain command runs directly, with quotes removed; pipes, redirections,
+    /// `&&`, wildcards, and anything our parser rejects go to the system shell
+    /// as typed.
+    pub fn pty_command(&self, input: &str) -> (String, Vec<String>) {
+        let input = input.trim();
+        let is_plain = |cmd: &ParsedCommand| {
+            cmd.redirections.is_empty()
+                && cmd.patterns.is_empty()
+                && !cmd.args.iter().any(|arg| arg == "&")
+        };
+        match parse_command_line(input) {
+            Ok(pipeline) if pipeline.is_single() => match pipeline.first() {
+                Some(cmd) if is_plain(cmd) => (cmd.command.clone(), cmd.args.clone()),
+                _ => shell_command(input),
+            },
+            _ => shell_command(input),
+        }
+    }
+
+    /// Start an external command line in a PTY of `rows` x `cols`, in the
+    /// pane's directory with its exported variables (or an SSH session)
+    pub fn spawn_pty(
+        &self,
+        input: &str,
+        state: &TerminalState,
+        rows: u16,
+        cols: u16,
+    ) -> Result<PtySession> {
+        if let Some(target) = ssh_session(input) {
+            return Ok(PtySession::ssh(target, rows, cols, state.home().clone()));
+        }
+        let (program, args) = self.pty_command(input);
+        PtySession::new_with_command(
+            &program,
+            &args,
+            rows,
+            cols,
+            state.cwd(),
+            state.exported_vars(),
+        )
+    }
+
+    /// Start a command line on a worker thread. It runs against `state`
+    /// (a snapshot of the pane's), with `history` as AI context.
+    pub fn spawn_job(&self, input: &str, state: TerminalState, history: Vec<String>) -> Job {
+        Job::spawn(self.clone(), input, state, history)
+    }
+
     /// Check if the command registry has a specific command
-    #[allow(dead_code)]
     pub fn has_command(&self, cmd: &str) -> bool {
         self.registry.has_command(cmd)
     }
+
+    /// Usage line of a built-in command (for signature help)
+    pub fn usage(&self, cmd: &str) -> Option<&'static str> {
+        self.registry.get(cmd).map(|command| command.usage())
+    }
+}
+
+/// Run `command` in the pane's directory with its exported variables and
+/// capture what it prints (`label` names it in errors). Its lines are
+/// printed as they come while `ctx` streams; it is stopped while the job is
+/// paused (on Unix) and killed on cancel.
+fn capture_process(
+    mut command: Command,
+    label: &str,
+    state: &TerminalState,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let mut child = command
+        .current_dir(state.cwd())
+        .envs(state.exported_vars())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("{}: {}", label, e))?;
+
+    let (sender, lines) = mpsc::channel();
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>);
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>);
+    for pipe in [stdout, stderr].into_iter().flatten() {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    let mut output = Vec::new();
+    let mut stopped = false;
+    loop {
+        if ctx.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Interrupted.into());
+        }
+        if ctx.is_paused() != stopped {
+            stopped = !stopped;
+            signal_stop(&child, stopped);
+        }
+        match lines.recv_timeout(TICK) {
+            Ok(line) if ctx.is_streaming() => ctx.print(&line),
+            Ok(line) => output.push(line),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let status = child.wait().map_err(|e| anyhow!("{}: {}", label, e))?;
+    let output = output.join("\n");
+    match status.code() {
+        _ if status.success() => Ok(output),
+        code => {
+            let code = code.map_or("a signal".to_string(), |code| format!("code {}", code));
+            if output.is_empty() {
+                Err(anyhow!("exited with {}", code))
+            } else {
+                Err(anyhow!("{}\nexited with {}", output, code))
+            }
+        }
+    }
+}
+
+/// Where a line of built-ins runs: slow ones, and `tail -f`, in the
+/// background
+fn builtin_target(pipeline: &Pipeline) -> ExecutionTarget {
+    let follows = |cmd: &ParsedCommand| {
+        cmd.command == "tail" && cmd.args.iter().any(|arg| arg == "-f" || arg == "--follow")
+    };
+    if pipeline
+        .commands
+        .iter()
+        .any(|cmd| BACKGROUND_COMMANDS.contains(&cmd.command.as_str()) || follows(cmd))
+    {
+        ExecutionTarget::Background
+    } else {
+        ExecutionTarget::Native
+    }
+}
+
+/// Where to connect when `input` is an interactive `ssh` line: a lone,
+/// unredirected `ssh` with valid arguments (anything else runs the built-in,
+/// which prints help, errors, or a remote command's output)
+fn ssh_session(input: &str) -> Option<SshTarget> {
+    let pipeline = parse_command_line(input.trim()).ok()?;
+    let cmd = pipeline.first().filter(|_| pipeline.is_single())?;
+    let interactive = cmd.command == "ssh"
+        && cmd.redirections.is_empty()
+        && !cmd.args.iter().any(|arg| arg == "-h" || arg == "--help");
+    interactive
+        .then(|| SshTarget::parse(&cmd.args, &ssh::default_user()).ok())
+        .flatten()
+}
+
+/// The command of a line ending in a lone `&` (`sleep 10 &`)
+fn detached(input: &str) -> Option<&str> {
+    let command = input.trim().strip_suffix('&')?;
+    if command.ends_with('&') || command.ends_with('\\') {
+        return None;
+    }
+    Some(command.trim_end()).filter(|command| !command.is_empty())
+}
+
+/// Stop or continue a captured program whose job was paused or resumed
+/// (Unix only; elsewhere it keeps running)
+fn signal_stop(child: &Child, stop: bool) {
+    if cfg!(unix) {
+        let signal = if stop { "-STOP" } else { "-CONT" };
+        let _ = Command::new("kill")
+            .args([signal, &child.id().to_string()])
+            .status();
+    }
+}
+
+/// Run a command line through the system shell (the PTY session already
+/// wraps everything in `cmd.exe /c` on Windows)
+fn shell_command(input: &str) -> (String, Vec<String>) {
+    if cfg!(windows) {
+        (input.to_string(), Vec::new())
+    } else {
+        ("sh".to_string(), vec!["-c".to_string(), input.to_string()])
+    }
+}
+
+/// A script's path as shown in errors and `$0`: `~/.zaxiomrc` for a file in
+/// the home directory
+fn display_path(path: &Path, state: &TerminalState) -> String {
+    match path.strip_prefix(state.home()) {
+        Ok(rest) => format!("~/{}", rest.display()).replace('\\', "/"),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+/// Write a command's output to a redirection target, ending it with a
+/// newline unless the command asked for its output to be left unterminated
+fn write_output(file: &mut File, output: &str, omit_newline: bool) -> std::io::Result<()> {
+    if omit_newline {
+        file.write_all(output.as_bytes())
+    } else {
+        writeln!(file, "{}", output)
+    }
 }
 
 impl Default for Executor {
@@ -423,3 +1011,346 @@ impl Default for Executor {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::commands::testing::{Fixture, MockTerminalState};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn test_executor_is_shareable() {
+        assert_send_sync::<Executor>();
+        assert_send::<TerminalState>();
+    }
+
+    #[test]
+    fn test_panes_keep_their_own_state() {
+        let executor = Arc::new(Executor::new());
+        let panes: Vec<_> = (0..2)
+            .map(|pane| {
+                let executor = Arc::clone(&executor);
+                thread::spawn(move || {
+                    let fixture = Fixture::new("executor-pane").dir("inner");
+                    let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+                    let mut run = |line: &str| {
+                        executor
+                            .execute_with_history(line, &mut state, None)
+                            .unwrap_or_else(|e| panic!("pane {}: {}: {}", pane, line, e))
+                    };
+                    for _ in 0..50 {
+                        run(&format!("export ZAXIOM_PANE_TEST={}", pane));
+                        run("pushd inner");
+                        assert_eq!(
+                            run("echo $((ZAXIOM_PANE_TEST * 10))"),
+                            (pane * 10).to_string()
+                        );
+                        run("popd");
+                    }
+                    run("cd inner");
+                    (fixture.path().join("inner"), state)
+                })
+            })
+            .collect();
+
+        for (pane, handle) in panes.into_iter().enumerate() {
+            let (inner, state) = handle.join().unwrap();
+            assert_eq!(state.cwd(), &inner);
+            assert_eq!(state.var("ZAXIOM_PANE_TEST"), Some(pane.to_string()));
+            assert!(state.dir_stack.is_empty());
+        }
+        // Nothing leaked into the process
+        assert!(std::env::var("ZAXIOM_PANE_TEST").is_err());
+    }
+
+    #[test]
+    fn test_redirect_keeps_exact_printf_and_echo_output() {
+        let fixture = Fixture::new("executor-redirect");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        for line in [
+            "printf 'a\\n' > out.txt",
+            "echo -n b >> out.txt",
+            "printf '%s' c >> out.txt",
+            "echo d >> out.txt",
+        ] {
+            executor
+                .execute_with_history(line, &mut state, None)
+                .unwrap();
+        }
+        assert_eq!(fixture.read("out.txt"), "a\nbcd\n");
+    }
+
+    #[test]
+    fn test_startup_script() {
+        let fixture = Fixture::new("executor-startup").file(
+            ".zaxiomrc",
+            "# set up the pane\nalias ll='ls -la'\nexport EDITOR=nano\ntheme dracula\necho ready\n",
+        );
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let mut executor = Executor::new();
+        assert!(executor.run_startup_script(&mut state).is_none());
+
+        executor.set_startup_script(Some(fixture.path().join(".zaxiomrc")));
+        assert_eq!(
+            executor.run_startup_script(&mut state).unwrap().unwrap(),
+            "Switched to Dracula theme\nready"
+        );
+        assert_eq!(state.get_alias("ll").as_deref(), Some("ls -la"));
+        assert_eq!(
+            state.exported_vars().get("EDITOR").map(String::as_str),
+            Some("nano")
+        );
+        assert!(state.requested_theme.is_some());
+
+        // `source` alone runs it again; a failing line is reported
+        std::fs::write(fixture.path().join(".zaxiomrc"), "echo again\nfrobnicate\n").unwrap();
+        let err = executor
+            .execute_with_history("source", &mut state, None)
+            .unwrap_err();
+        assert!(err.to_string().starts_with("again\n"), "{}", err);
+        let err = executor
+            .run_startup_script(&mut state)
+            .unwrap()
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("failed: again"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_watch_redraws_each_run() {
+        let fixture = Fixture::new("executor-watch").file("count.txt", "1");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        let screens = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&screens);
+        let ctx = CommandContext::default()
+            .with_redraw(move |screen| sink.lock().unwrap().push(screen.to_string()));
+
+        let output = executor
+            .execute_with_context(
+                "watch -t -c 2 -n 0.1 \"cat count.txt | head -n 1\"",
+                &mut state,
+                None,
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(output, "");
+        assert_eq!(*screens.lock().unwrap(), ["1", "1"]);
+
+        // Errors are shown in the frame; the watch goes on
+        let output = executor
+            .execute_with_context("watch -c 1 cat missing.txt", &mut state, None, &ctx)
+            .unwrap();
+        assert_eq!(output, "");
+        let screens = screens.lock().unwrap();
+        assert!(screens[2].starts_with("Every 2s: cat missing.txt"));
+        assert!(screens[2].contains("missing.txt"));
+
+        // Without a pane to redraw in, it runs once and prints the frame
+        let output = executor
+            .execute("watch -t head -n 1 count.txt | tr 1 2", &mut state)
+            .unwrap();
+        assert_eq!(output, "2");
+    }
+
+    #[test]
+    fn test_find_exec() {
+        let fixture = Fixture::new("executor-find-exec")
+            .file("a.txt", "alpha")
+            .file("b.txt", "beta")
+            .file("c.md", "");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        let mut run = |line: &str| executor.execute_with_history(line, &mut state, None);
+
+        assert_eq!(
+            run("find . -name '*.txt' -exec cat {} \\;").unwrap(),
+            "alpha\nbeta"
+        );
+        assert_eq!(
+            run("find -name '*.txt' -print -exec echo {} +").unwrap(),
+            "./a.txt\n./b.txt\na.txt b.txt"
+        );
+        assert!(run("find . -exec cat {}").is_err());
+
+        // Quoted -exec arguments stay one word
+        assert_eq!(
+            run(r#"find . -name 'a*' -exec printf "[%s]" "one word" {} \;"#).unwrap(),
+            "[one word][a.txt]"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_exec_file_names_are_not_commands() {
+        let name = "q\"$(touch pwned) `touch pwned2` b.txt";
+        let fixture = Fixture::new("executor-find-exec-names").file(name, "kept");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        let mut run = |line: &str| executor.execute_with_history(line, &mut state, None);
+
+        // A built-in and a program get the name as one argument
+        assert_eq!(run("find . -name 'q*' -exec cat {} \\;").unwrap(), "kept");
+        assert_eq!(
+            run("find . -name 'q*' -exec /bin/cat {} +").unwrap(),
+            "kept"
+        );
+        assert!(!fixture.path().join("pwned").exists());
+        assert!(!fixture.path().join("pwned2").exists());
+    }
+
+    #[test]
+    fn test_wildcards_expand_unless_quoted() {
+        let fixture = Fixture::new("executor-wildcards")
+            .file("a.tmp", "")
+            .file("b.tmp", "")
+            .file("keep.txt", "kept")
+            .file("src/lib.rs", "")
+            .file("src/shell/mod.rs", "");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        let mut run = |line: &str| executor.execute_with_history(line, &mut state, None);
+
+        assert_eq!(
+            run("echo src/**/*.rs").unwrap(),
+            "src/lib.rs src/shell/mod.rs"
+        );
+        assert_eq!(
+            run("echo '*.tmp' \"*.tmp\" *.zip").unwrap(),
+            "*.tmp *.tmp *.zip"
+        );
+        assert_eq!(run("expr 6 * 7").unwrap(), "42");
+        run("rm *.tmp").unwrap();
+        assert!(!fixture.path().join("a.tmp").exists());
+        assert!(!fixture.path().join("b.tmp").exists());
+        assert_eq!(run("head -n 1 ?eep.txt").unwrap(), "kept");
+    }
+
+    #[test]
+    fn test_slow_builtins_run_in_background() {
+        let executor = Executor::new();
+        for line in [
+            "ping example.com",
+            "find . -name '*.rs'",
+            "cat log.txt | grep error",
+            "tail -f app.log",
+        ] {
+            assert_eq!(
+                executor.route_command(line),
+                ExecutionTarget::Background,
+                "{}",
+                line
+            );
+        }
+        assert_eq!(
+            executor.route_command("echo hi | sort"),
+            ExecutionTarget::Native
+        );
+        assert_eq!(executor.route_command("cd src"), ExecutionTarget::Native);
+        assert_eq!(
+            executor.route_command("tail -n 5 app.log"),
+            ExecutionTarget::Native
+        );
+    }
+
+    #[test]
+    fn test_compound_commands_and_functions_run_natively() {
+        let executor = Executor::new();
+        let mut state = MockTerminalState::new().build();
+        assert_eq!(
+            executor.route_command("while true; do ls; done"),
+            ExecutionTarget::Native
+        );
+        assert_eq!(
+            executor.route_command_in("deploy prod", &state),
+            ExecutionTarget::PtyRaw
+        );
+        state.set_function("deploy".into(), "echo deploying $1".into());
+        assert_eq!(
+            executor.route_command_in("deploy prod", &state),
+            ExecutionTarget::Native
+        );
+    }
+
+    #[test]
+    fn test_restricted_mode_only_runs_builtins() {
+        let mut executor = Executor::new();
+        let config = crate::config::settings::RestrictedConfig {
+            allow: vec!["echo".to_string(), "htop".to_string()],
+            ..Default::default()
+        };
+        executor.set_restricted(RestrictedMode::from_config(&config, true, "kiosk"));
+        let mut state = MockTerminalState::new().build();
+
+        // An allowlisted program that isn't a builtin still goes through the
+        // native path, so it never starts
+        assert_eq!(
+            executor.route_command("htop --version"),
+            ExecutionTarget::Native
+        );
+        assert!(executor
+            .execute_with_history("htop --version", &mut state, None)
+            .is_err());
+        assert!(executor
+            .execute_with_history("echo hi", &mut state, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_ssh_sessions_open_in_the_grid() {
+        let executor = Executor::new();
+        for line in ["ssh pi@raspberrypi", "ssh -p 2222 web1 htop"] {
+            assert_eq!(
+                executor.route_command(line),
+                ExecutionTarget::PtyRaw,
+                "{}",
+                line
+            );
+        }
+        // Help, bad arguments, pipes, and redirections run the built-in
+        for line in [
+            "ssh --help",
+            "ssh",
+            "ssh -p nope web1",
+            "ssh web1 df -h | grep var",
+            "ssh web1 uptime > up.txt",
+        ] {
+            assert_eq!(
+                executor.route_command(line),
+                ExecutionTarget::Background,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_pty_command() {
+        let executor = Executor::new();
+        assert_eq!(
+            executor.pty_command("python -c \"print('hi there')\""),
+            (
+                "python".to_string(),
+                vec!["-c".to_string(), "print('hi there')".to_string()]
+            )
+        );
+
+        let line = "node build.js | tee log.txt";
+        let (program, args) = executor.pty_command(line);
+        if cfg!(windows) {
+            assert_eq!((program.as_str(), args.len()), (line, 0));
+        } else {
+            assert_eq!(
+                (program.as_str(), args.as_slice()),
+                ("sh", ["-c", line].map(String::from).as_slice())
+            );
+        }
+        assert_ne!(executor.pty_command("make && make install").0, "make");
+        assert_ne!(executor.pty_command("cargo build > out.txt").0, "cargo");
+        assert_ne!(executor.pty_command("wc -l *.rs").0, "wc");
+    }
+}
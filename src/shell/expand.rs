@@ -0,0 +1,475 @@
+//! Word expansion
+//!
+//! Bash-style brace expansion (`{a,b,c}`, `{1..10}`, `{a..e..2}`) and
+//! arithmetic expansion (`$((2+3*4))`), applied by the parser to each word
+//! before a command runs.
+
+use std::collections::HashMap;
+
+/// Largest number of words a single `{x..y}` sequence may produce
+const MAX_SEQUENCE: usize = 65_536;
+
+/// Largest number of words one word may expand to (`{1..1000}{1..1000}`)
+const MAX_WORDS: usize = 65_536;
+
+/// Expand the braces in an unquoted word into one or more words.
+/// Braces without a comma or a valid `..` sequence (`{}`, `{x}`, `${VAR}`)
+/// are kept as they are, and so is a word that would expand to more than
+/// `MAX_WORDS` words. `\{`, `\}` and `\,` are literal characters.
+pub fn expand_braces(word: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    if !expand_into(word, &mut words) {
+        return vec![unescape_braces(word)];
+    }
+    words.iter().map(|word| unescape_braces(word)).collect()
+}
+
+/// Push the expansions of `word` onto `words`, escapes still in them.
+/// False once there would be more than `MAX_WORDS`.
+fn expand_into(word: &str, words: &mut Vec<String>) -> bool {
+    let bytes = word.as_bytes();
+    let mut open = 0;
+    while open < bytes.len() {
+        if bytes[open] == b'\\' {
+            open += 2;
+            continue;
+        }
+        if bytes[open] != b'{' || (open > 0 && bytes[open - 1] == b'$') {
+            open += 1;
+            continue;
+        }
+
+        // Find the matching brace and the commas at this level
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        let mut close = None;
+        let mut i = open;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(i);
+                        break;
+                    }
+                }
+                b',' if depth == 1 => commas.push(i),
+                _ => {}
+            }
+            i += 1;
+        }
+        let Some(close) = close else {
+            open += 1;
+            continue;
+        };
+
+        let alternatives = if commas.is_empty() {
+            match sequence(&word[open + 1..close]) {
+                Some(items) => items,
+                None => {
+                    open += 1;
+                    continue;
+                }
+            }
+        } else {
+            let mut start = open + 1;
+            let mut items = Vec::new();
+            for &comma in commas.iter().chain(std::iter::once(&close)) {
+                items.push(word[start..comma].to_string());
+                start = comma + 1;
+            }
+            items
+        };
+
+        let (prefix, suffix) = (&word[..open], &word[close + 1..]);
+        return alternatives
+            .iter()
+            .all(|item| expand_into(&format!("{}{}{}", prefix, item, suffix), words));
+    }
+    if words.len() >= MAX_WORDS {
+        return false;
+    }
+    words.push(word.to_string());
+    true
+}
+
+/// Drop the backslash from `\{`, `\}` and `\,` (other backslashes, as in
+/// `C:\Users`, stay)
+fn unescape_braces(word: &str) -> String {
+    let mut result = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(next @ ('{' | '}' | ',')) => result.push(next),
+            Some(next) => {
+                result.push(c);
+                result.push(next);
+            }
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// Items of a `start..end[..step]` sequence of integers or letters
+fn sequence(inner: &str) -> Option<Vec<String>> {
+    let parts: Vec<&str> = inner.split("..").collect();
+    if parts.len() != 2 && parts.len() != 3 {
+        return None;
+    }
+    let step = match parts.get(2) {
+        Some(step) => step.parse::<i64>().ok()?.unsigned_abs().max(1),
+        None => 1,
+    };
+
+    if let (Ok(start), Ok(end)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
+        // {01..10} pads every number to the same width
+        let padded = |s: &str| {
+            s.trim_start_matches('-').len() > 1 && s.trim_start_matches('-').starts_with('0')
+        };
+        let width = if padded(parts[0]) || padded(parts[1]) {
+            parts[0].len().max(parts[1].len())
+        } else {
+            0
+        };
+        let count = start.abs_diff(end) / step + 1;
+        if count > MAX_SEQUENCE as u64 {
+            return None;
+        }
+        let items = (0..count as i64)
+            .map(|i| {
+                let n = if start <= end {
+                    start + i * step as i64
+                } else {
+                    start - i * step as i64
+                };
+                if n < 0 {
+                    format!("-{:0>width$}", -n, width = width.saturating_sub(1))
+                } else {
+                    format!("{:0>width$}", n, width = width)
+                }
+            })
+            .collect();
+        return Some(items);
+    }
+
+    let letter = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Some(c as u8),
+            _ => None,
+        }
+    };
+    let (start, end) = (letter(parts[0])?, letter(parts[1])?);
+    let items = if start <= end {
+        (start..=end).step_by(step as usize).collect::<Vec<_>>()
+    } else {
+        (end..=start).rev().step_by(step as usize).collect()
+    };
+    Some(items.into_iter().map(|c| (c as char).to_string()).collect())
+}
+
+/// Length of the `$((...))` at the start of `text`, if it is closed
+pub fn arithmetic_len(text: &str) -> Option<usize> {
+    if !text.starts_with("$((") {
+        return None;
+    }
+    let mut depth = 2;
+    for (i, c) in text.char_indices().skip(3) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Replace every `$((expression))` in `text` with its value. `vars` are the
+/// pane's exported variables, looked up before the process environment.
+pub fn expand_arithmetic(text: &str, vars: &HashMap<String, String>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("$((") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(len) = arithmetic_len(rest) else {
+            break;
+        };
+        // Expand nested $((...)) first
+        let inner = expand_arithmetic(&rest[3..len - 2], vars)?;
+        result.push_str(&evaluate(&inner, vars)?.to_string());
+        rest = &rest[len..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Evaluate an integer arithmetic expression the way bash's `$((...))` does.
+/// Names are read from `vars`, then the environment (unset or non-numeric is 0).
+pub fn evaluate(expression: &str, vars: &HashMap<String, String>) -> Result<i64, String> {
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Ok(0);
+    }
+    let mut parser = ExprParser {
+        tokens,
+        pos: 0,
+        vars,
+    };
+    let value = parser.binary(0)?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(value),
+        Some(token) => Err(format!(
+            "{}: syntax error in expression (error token is \"{}\")",
+            expression.trim(),
+            token.text()
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ExprToken {
+    Number(i64),
+    Name(String),
+    Op(&'static str),
+}
+
+impl ExprToken {
+    fn text(&self) -> String {
+        match self {
+            ExprToken::Number(n) => n.to_string(),
+            ExprToken::Name(name) => name.clone(),
+            ExprToken::Op(op) => op.to_string(),
+        }
+    }
+}
+
+/// Operators, longest first so `**` wins over `*`
+const OPERATORS: &[&str] = &[
+    "**", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "<", ">", "&",
+    "|", "^", "!", "~", "(", ")",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<ExprToken>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            let literal = &rest[..len];
+            let value = match literal.strip_prefix("0x").or(literal.strip_prefix("0X")) {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None if literal.len() > 1 && literal.starts_with('0') => {
+                    i64::from_str_radix(&literal[1..], 8)
+                }
+                None => literal.parse(),
+            }
+            .map_err(|_| format!("{}: value too great for base", literal))?;
+            tokens.push(ExprToken::Number(value));
+            rest = &rest[len..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(ExprToken::Name(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            tokens.push(ExprToken::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            return Err(format!(
+                "{}: syntax error: invalid arithmetic operator (error token is \"{}\")",
+                expression.trim(),
+                rest
+            ));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Binding strength of a binary operator (higher binds tighter)
+fn precedence(op: &str) -> Option<u8> {
+    Some(match op {
+        "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" => 6,
+        "<" | "<=" | ">" | ">=" => 7,
+        "<<" | ">>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        "**" => 11,
+        _ => return None,
+    })
+}
+
+struct ExprParser<'a> {
+    tokens: Vec<ExprToken>,
+    pos: usize,
+    vars: &'a HashMap<String, String>,
+}
+
+impl ExprParser<'_> {
+    fn next(&mut self) -> Option<ExprToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Binary operators binding at least as tightly as `min`
+    fn binary(&mut self, min: u8) -> Result<i64, String> {
+        let mut lhs = self.unary()?;
+        while let Some(ExprToken::Op(op)) = self.tokens.get(self.pos).cloned() {
+            let Some(prec) = precedence(op).filter(|&prec| prec >= min) else {
+                break;
+            };
+            self.pos += 1;
+            // ** is right-associative
+            let rhs = self.binary(if op == "**" { prec } else { prec + 1 })?;
+            lhs = apply(op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        match self.next() {
+            Some(ExprToken::Number(n)) => Ok(n),
+            Some(ExprToken::Name(name)) => Ok(self
+                .vars
+                .get(&name)
+                .cloned()
+                .or_else(|| std::env::var(&name).ok())
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(0)),
+            Some(ExprToken::Op("-")) => Ok(self.unary()?.wrapping_neg()),
+            Some(ExprToken::Op("+")) => self.unary(),
+            Some(ExprToken::Op("!")) => Ok((self.unary()? == 0) as i64),
+            Some(ExprToken::Op("~")) => Ok(!self.unary()?),
+            Some(ExprToken::Op("(")) => {
+                let value = self.binary(0)?;
+                match self.next() {
+                    Some(ExprToken::Op(")")) => Ok(value),
+                    _ => Err("missing `)'".to_string()),
+                }
+            }
+            Some(token) => Err(format!(
+                "syntax error: operand expected (error token is \"{}\")",
+                token.text()
+            )),
+            None => Err("syntax error: operand expected".to_string()),
+        }
+    }
+}
+
+fn apply(op: &str, lhs: i64, rhs: i64) -> Result<i64, String> {
+    Ok(match op {
+        "||" => (lhs != 0 || rhs != 0) as i64,
+        "&&" => (lhs != 0 && rhs != 0) as i64,
+        "|" => lhs | rhs,
+        "^" => lhs ^ rhs,
+        "&" => lhs & rhs,
+        "==" => (lhs == rhs) as i64,
+        "!=" => (lhs != rhs) as i64,
+        "<" => (lhs < rhs) as i64,
+        "<=" => (lhs <= rhs) as i64,
+        ">" => (lhs > rhs) as i64,
+        ">=" => (lhs >= rhs) as i64,
+        "<<" => lhs.wrapping_shl(rhs as u32),
+        ">>" => lhs.wrapping_shr(rhs as u32),
+        "+" => lhs.wrapping_add(rhs),
+        "-" => lhs.wrapping_sub(rhs),
+        "*" => lhs.wrapping_mul(rhs),
+        "/" | "%" if rhs == 0 => return Err("division by 0".to_string()),
+        "/" => lhs.wrapping_div(rhs),
+        "%" => lhs.wrapping_rem(rhs),
+        "**" if rhs < 0 => return Err("exponent less than 0".to_string()),
+        "**" => lhs.wrapping_pow(rhs.min(u32::MAX as i64) as u32),
+        _ => unreachable!("not a binary operator: {}", op),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("src/{models,views,controllers}"),
+            ["src/models", "src/views", "src/controllers"]
+        );
+        assert_eq!(expand_braces("{1..4}"), ["1", "2", "3", "4"]);
+        assert_eq!(expand_braces("{3..1}"), ["3", "2", "1"]);
+        assert_eq!(expand_braces("{01..10..3}"), ["01", "04", "07", "10"]);
+        assert_eq!(expand_braces("{a..e..2}"), ["a", "c", "e"]);
+        assert_eq!(expand_braces("x{a,b{1,2}}y"), ["xay", "xb1y", "xb2y"]);
+        assert_eq!(expand_braces("{a,b}{1,2}"), ["a1", "a2", "b1", "b2"]);
+        // Not expandable: kept as is
+        for word in ["{}", "{x}", "${HOME}", "{a..}", "{1..2", "file.{txt}"] {
+            assert_eq!(expand_braces(word), [word]);
+        }
+    }
+
+    #[test]
+    fn test_expand_braces_escapes() {
+        assert_eq!(expand_braces(r"\{a,b\}"), ["{a,b}"]);
+        assert_eq!(expand_braces(r"{a\,b,c}"), ["a,b", "c"]);
+        assert_eq!(expand_braces(r"x{a,\}}"), ["xa", "x}"]);
+        assert_eq!(expand_braces(r"C:\Users\{a,b}"), [r"C:\Users{a,b}"]);
+        assert_eq!(expand_braces(r"C:\\{a,b}"), [r"C:\\a", r"C:\\b"]);
+    }
+
+    #[test]
+    fn test_expand_braces_caps_total_words() {
+        assert_eq!(expand_braces("{1..256}{1..256}").len(), MAX_WORDS);
+        let word = "{1..1000}{1..1000}";
+        assert_eq!(expand_braces(word), [word]);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let no_vars = HashMap::new();
+        assert_eq!(evaluate("2+3*4", &no_vars), Ok(14));
+        assert_eq!(evaluate(" (2 + 3) * 4 ", &no_vars), Ok(20));
+        assert_eq!(evaluate("2**3**2", &no_vars), Ok(512));
+        assert_eq!(evaluate("-7 / 2", &no_vars), Ok(-3));
+        assert_eq!(evaluate("7 % 3 == 1 && 0x10 > 010", &no_vars), Ok(1));
+        assert_eq!(evaluate("!0 + ~0 + (1 << 4)", &no_vars), Ok(16));
+        assert_eq!(evaluate("ZAXIOM_UNSET_ARITH_VAR + 1", &no_vars), Ok(1));
+        assert!(evaluate("1 / 0", &no_vars).is_err());
+        assert!(evaluate("2 +", &no_vars).is_err());
+        assert!(evaluate("(1", &no_vars).is_err());
+        assert!(evaluate("1 2", &no_vars).is_err());
+
+        assert_eq!(
+            expand_arithmetic("n$((1+1))-$(( 2 * 3 ))", &no_vars).unwrap(),
+            "n2-6"
+        );
+        assert_eq!(
+            expand_arithmetic("$(( $((2+1)) * 2 ))", &no_vars).unwrap(),
+            "6"
+        );
+        assert_eq!(expand_arithmetic("$((1", &no_vars).unwrap(), "$((1");
+        assert_eq!(arithmetic_len("$(( (1) ))x"), Some(10));
+
+        let vars = HashMap::from([("ZAXIOM_ARITH_N".to_string(), "4".to_string())]);
+        assert_eq!(evaluate("ZAXIOM_ARITH_N * 2", &vars), Ok(8));
+    }
+}
@@ -0,0 +1,480 @@
+//! Background jobs
+//!
+//! Slow built-ins (`ping`, `curl`, `find` over a large tree) run on a worker
+//! thread instead of inside the frame that entered them. The job works on a
+//! snapshot of the pane's state and sends what it prints, its progress, and
+//! finally its result back over a channel that the app polls every frame.
+//!
+//! Dropping a `Job` cancels it; commands that check their `CommandContext`
+//! stop soon after, and anything still sent afterwards is discarded.
+//!
+//! A job the pane isn't waiting for - started with `cmd &`, or suspended
+//! with Ctrl+Z - lives in the pane's `JobTable` under a number, where
+//! `jobs`, `fg`, `bg`, and `kill %n` find it.
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+
+use super::executor::Executor;
+use crate::commands::context::{CancelToken, CommandContext};
+use crate::terminal::state::TerminalState;
+
+/// Something a running job has to say
+pub enum JobEvent {
+    /// A line of output, printed while the command runs
+    Line(String),
+    /// A progress message ("1200 entries searched")
+    Progress(String),
+    /// A screen replacing the previous one (`watch`)
+    Redraw(String),
+    /// The command finished: the rest of its output (or its error), and the
+    /// state it ran against
+    Done(Result<String>, Box<TerminalState>),
+}
+
+/// A command line running on a worker thread
+pub struct Job {
+    /// Events in the order they happened, ending with `Done`
+    pub events: Receiver<JobEvent>,
+    cancel: CancelToken,
+}
+
+impl Job {
+    /// Run `input` with `executor` on a new thread
+    pub fn spawn(
+        executor: Executor,
+        input: &str,
+        state: TerminalState,
+        history: Vec<String>,
+    ) -> Self {
+        let input = input.to_string();
+        Self::start(state, move |state, ctx| {
+            executor.execute_with_context(&input, state, Some(&history), ctx)
+        })
+    }
+
+    /// Run `task` against `state` on a new thread
+    pub fn start(
+        state: TerminalState,
+        task: impl FnOnce(&mut TerminalState, &CommandContext) -> Result<String> + Send + 'static,
+    ) -> Self {
+        Self::start_with(CancelToken::new(), state, task)
+    }
+
+    /// Like `start`, but paused before the task gets to run
+    #[cfg(test)]
+    pub fn start_paused(
+        state: TerminalState,
+        task: impl FnOnce(&mut TerminalState, &CommandContext) -> Result<String> + Send + 'static,
+    ) -> Self {
+        let cancel = CancelToken::new();
+        cancel.pause();
+        Self::start_with(cancel, state, task)
+    }
+
+    fn start_with(
+        cancel: CancelToken,
+        mut state: TerminalState,
+        task: impl FnOnce(&mut TerminalState, &CommandContext) -> Result<String> + Send + 'static,
+    ) -> Self {
+        let (sender, events) = mpsc::channel();
+
+        let lines = sender.clone();
+        let progress = sender.clone();
+        let screens = sender.clone();
+        let ctx = CommandContext::new(cancel.clone())
+            .with_output(move |line| {
+                let _ = lines.send(JobEvent::Line(line.to_string()));
+            })
+            .with_progress(move |message| {
+                let _ = progress.send(JobEvent::Progress(message.to_string()));
+            })
+            .with_redraw(move |screen| {
+                let _ = screens.send(JobEvent::Redraw(screen.to_string()));
+            });
+
+        thread::spawn(move || {
+            let result = task(&mut state, &ctx);
+            let _ = sender.send(JobEvent::Done(result, Box::new(state)));
+        });
+
+        Self { events, cancel }
+    }
+
+    /// Ask the command to stop
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Ask the command to wait until `resume` (Ctrl+Z)
+    pub fn pause(&self) {
+        self.cancel.pause();
+    }
+
+    pub fn resume(&self) {
+        self.cancel.resume();
+    }
+}
+
+/// What a numbered job is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    /// Paused with Ctrl+Z until `fg` or `bg`
+    Stopped,
+}
+
+/// A numbered job as listed by `jobs`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobInfo {
+    pub id: usize,
+    pub command: String,
+    pub status: JobStatus,
+    /// `+` for the current job (what `fg` picks), `-` for the previous one
+    pub mark: char,
+}
+
+/// A numbered job that has finished, reported once
+pub struct FinishedJob {
+    pub id: usize,
+    pub command: String,
+    /// What it printed while nobody was watching
+    pub output: Vec<String>,
+    /// The rest of its output, or its error
+    pub result: Result<String>,
+}
+
+/// A job brought back to the foreground by `fg`
+pub struct ForegroundJob {
+    pub command: String,
+    pub job: Job,
+    /// What it printed while in the background
+    pub output: Vec<String>,
+}
+
+/// The pane's numbered jobs (cheap to clone; clones share the table)
+#[derive(Clone, Default)]
+pub struct JobTable(Arc<Mutex<Vec<Entry>>>);
+
+struct Entry {
+    id: usize,
+    command: String,
+    job: Job,
+    status: JobStatus,
+    output: Vec<String>,
+    /// Set once the job's `Done` has been received
+    done: Option<(Result<String>, Box<TerminalState>)>,
+}
+
+impl Entry {
+    /// Collect what the job sent since last time
+    fn drain(&mut self) {
+        while self.done.is_none() {
+            match self.job.events.try_recv() {
+                Ok(JobEvent::Line(line)) => self.output.push(line),
+                Ok(JobEvent::Progress(_)) => {}
+                Ok(JobEvent::Redraw(screen)) => {
+                    self.output = screen.lines().map(str::to_string).collect()
+                }
+                Ok(JobEvent::Done(result, state)) => self.done = Some((result, state)),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    let error = anyhow!("the command stopped unexpectedly");
+                    self.done = Some((Err(error), Box::new(TerminalState::new())));
+                }
+            }
+        }
+    }
+}
+
+impl JobTable {
+    fn entries(&self) -> MutexGuard<'_, Vec<Entry>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Number `job` (one more than the highest number in use) and keep it
+    pub fn add(&self, command: &str, job: Job, status: JobStatus) -> usize {
+        let mut entries = self.entries();
+        let id = entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+        entries.push(Entry {
+            id,
+            command: command.to_string(),
+            job,
+            status,
+            output: Vec::new(),
+            done: None,
+        });
+        id
+    }
+
+    /// Jobs that haven't been reported as finished, by number
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut entries = self.entries();
+        entries.iter_mut().for_each(Entry::drain);
+        let last = entries.len();
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| JobInfo {
+                id: entry.id,
+                command: entry.command.clone(),
+                status: entry.status,
+                mark: match last - i {
+                    1 => '+',
+                    2 => '-',
+                    _ => ' ',
+                },
+            })
+            .collect()
+    }
+
+    /// The job number `spec` names: `%n`, `%+`/`%%` (current, also when
+    /// `spec` is `None`), `%-` (previous), or `%text` (command starts with)
+    pub fn find(&self, spec: Option<&str>) -> Result<usize> {
+        let entries = self.entries();
+        let spec = spec.unwrap_or("%+");
+        let name = spec.strip_prefix('%').unwrap_or(spec);
+        let found = match name {
+            "" | "+" | "%" => entries.last(),
+            "-" => entries.iter().rev().nth(1),
+            _ => match name.parse::<usize>() {
+                Ok(id) => entries.iter().find(|entry| entry.id == id),
+                Err(_) => entries
+                    .iter()
+                    .rev()
+                    .find(|entry| entry.command.starts_with(name)),
+            },
+        };
+        found
+            .map(|entry| entry.id)
+            .ok_or_else(|| anyhow!("{}: no such job", spec))
+    }
+
+    /// Let a stopped job run on in the background (`bg`); its command
+    pub fn resume(&self, id: usize) -> Option<String> {
+        let mut entries = self.entries();
+        let entry = entries.iter_mut().find(|entry| entry.id == id)?;
+        entry.job.resume();
+        entry.status = JobStatus::Running;
+        Some(entry.command.clone())
+    }
+
+    /// Take a job out of the table to run in the foreground (`fg`),
+    /// resumed if it was stopped
+    pub fn take(&self, id: usize) -> Option<ForegroundJob> {
+        let mut entries = self.entries();
+        let index = entries.iter().position(|entry| entry.id == id)?;
+        let mut entry = entries.remove(index);
+        entry.drain();
+        entry.job.resume();
+        let job = match entry.done.take() {
+            // Finished already: hand over a job that is done
+            Some(done) => {
+                let (sender, events) = mpsc::channel();
+                let _ = sender.send(JobEvent::Done(done.0, done.1));
+                Job {
+                    events,
+                    cancel: entry.job.cancel.clone(),
+                }
+            }
+            None => entry.job,
+        };
+        Some(ForegroundJob {
+            command: entry.command,
+            job,
+            output: entry.output,
+        })
+    }
+
+    /// Stop a job and forget it (`kill %n`); its command
+    pub fn kill(&self, id: usize) -> Option<String> {
+        let mut entries = self.entries();
+        let index = entries.iter().position(|entry| entry.id == id)?;
+        // Dropping the job cancels it
+        Some(entries.remove(index).command)
+    }
+
+    /// Remove and return the jobs that have finished
+    pub fn reap(&self) -> Vec<FinishedJob> {
+        let mut entries = self.entries();
+        entries.iter_mut().for_each(Entry::drain);
+        let (finished, running): (Vec<Entry>, Vec<Entry>) =
+            entries.drain(..).partition(|entry| entry.done.is_some());
+        *entries = running;
+        finished
+            .into_iter()
+            .map(|mut entry| FinishedJob {
+                id: entry.id,
+                command: entry.command,
+                output: std::mem::take(&mut entry.output),
+                result: entry
+                    .done
+                    .take()
+                    .map(|(result, _)| result)
+                    .unwrap_or(Ok(String::new())),
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::context::Interrupted;
+    use crate::commands::testing::{Fixture, MockTerminalState};
+    use std::time::Duration;
+
+    /// Wait for the job to finish, collecting the lines it printed
+    fn finish(job: &Job) -> (Vec<String>, Result<String>, Box<TerminalState>) {
+        let mut lines = Vec::new();
+        loop {
+            match job.events.recv_timeout(Duration::from_secs(10)).unwrap() {
+                JobEvent::Line(line) => lines.push(line),
+                JobEvent::Progress(_) | JobEvent::Redraw(_) => {}
+                JobEvent::Done(result, state) => return (lines, result, state),
+            }
+        }
+    }
+
+    #[test]
+    fn test_job_streams_output_and_returns_state() {
+        let fixture = Fixture::new("job-find")
+            .file("a.txt", "")
+            .file("b.txt", "")
+            .file("c.rs", "");
+        let state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+
+        let job = executor.spawn_job("find . -name *.txt", state, Vec::new());
+        let (mut lines, result, state) = finish(&job);
+        lines.sort();
+        assert_eq!(lines, ["./a.txt", "./b.txt"]);
+        assert_eq!(result.unwrap(), "");
+        assert_eq!(state.cwd(), fixture.path());
+
+        // Piped output isn't streamed; the pipeline's result holds it all
+        let job = executor.spawn_job("find . -name *.txt | sort", *state, Vec::new());
+        let (lines, result, _) = finish(&job);
+        assert!(lines.is_empty());
+        assert_eq!(result.unwrap(), "./a.txt\n./b.txt");
+    }
+
+    #[test]
+    fn test_cancelled_job_stops() {
+        let fixture = Fixture::new("job-cancel");
+        let state = MockTerminalState::new().cwd(fixture.path()).build();
+        let job = Executor::new().spawn_job("sleep 60", state, Vec::new());
+        job.cancel();
+        let (_, result, _) = finish(&job);
+        let err = result.unwrap_err();
+        assert_eq!(err.downcast_ref::<Interrupted>(), Some(&Interrupted));
+    }
+
+    /// Wait until the table reports finished jobs
+    fn reap(table: &JobTable) -> Vec<FinishedJob> {
+        for _ in 0..200 {
+            let finished = table.reap();
+            if !finished.is_empty() {
+                return finished;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("no job finished");
+    }
+
+    #[test]
+    fn test_background_jobs() {
+        let fixture = Fixture::new("job-table").file("notes.txt", "hi");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+
+        assert_eq!(
+            executor.execute("sleep 60 &", &mut state).unwrap(),
+            "[1] sleep 60"
+        );
+        assert_eq!(
+            executor.execute("sleep 30 &", &mut state).unwrap(),
+            "[2] sleep 30"
+        );
+        assert_eq!(
+            executor.execute("jobs", &mut state).unwrap(),
+            "[1]-  Running   sleep 60\n[2]+  Running   sleep 30"
+        );
+        let jobs = state.jobs().clone();
+        assert_eq!(jobs.find(None).unwrap(), 2);
+        assert_eq!(jobs.find(Some("%-")).unwrap(), 1);
+        assert_eq!(jobs.find(Some("%sleep 6")).unwrap(), 1);
+        assert!(jobs.find(Some("%9")).is_err());
+
+        assert_eq!(
+            executor.execute("kill %1", &mut state).unwrap(),
+            "[1]  Terminated  sleep 60"
+        );
+        assert!(executor.execute("fg %1", &mut state).is_err());
+        executor.execute("fg", &mut state).unwrap();
+        assert_eq!(state.requested_foreground, Some(2));
+        let foreground = jobs.take(2).unwrap();
+        assert_eq!(foreground.command, "sleep 30");
+        foreground.job.cancel();
+        let (_, result, _) = finish(&foreground.job);
+        assert!(result.is_err());
+        assert!(jobs.is_empty());
+
+        // Finished jobs are reported once, with what they printed
+        executor
+            .execute("head -n 1 notes.txt &", &mut state)
+            .unwrap();
+        let finished = reap(&jobs);
+        assert_eq!(finished[0].id, 1);
+        assert_eq!(finished[0].result.as_ref().unwrap(), "hi");
+        assert!(jobs.is_empty());
+    }
+
+    #[test]
+    fn test_suspended_job_waits_for_bg() {
+        let fixture = Fixture::new("job-suspend");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let job = Job::start_paused(state.snapshot(), |_, ctx| {
+            ctx.check()?;
+            Ok("ran".to_string())
+        });
+        let jobs = state.jobs().clone();
+        jobs.add("task", job, JobStatus::Stopped);
+
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(jobs.reap().is_empty());
+        assert_eq!(jobs.list()[0].status, JobStatus::Stopped);
+
+        let executor = Executor::new();
+        assert_eq!(executor.execute("bg", &mut state).unwrap(), "[1] task &");
+        assert_eq!(reap(&jobs)[0].result.as_ref().unwrap(), "ran");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_background_job() {
+        let fixture = Fixture::new("job-external");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        executor
+            .execute("sh -c 'echo out; exit 3' &", &mut state)
+            .unwrap();
+        let finished = reap(state.jobs());
+        assert_eq!(finished[0].output, ["out"]);
+        let err = finished[0].result.as_ref().unwrap_err();
+        assert_eq!(err.to_string(), "exited with code 3");
+    }
+}
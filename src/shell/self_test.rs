@@ -0,0 +1,134 @@
+//! `--self-test`: smoke suite of builtins run through the real executor
+//!
+//! Each case is a command line as a user would type it - redirections,
+//! pipes, and `cd` included - run in a scratch directory that is removed
+//! afterwards. Meant for CI and for checking a fresh install; the exit
+//! code is 0 only if every case passes.
+
+use std::path::PathBuf;
+
+use super::executor::Executor;
+use crate::terminal::ansi::strip_ansi;
+use crate::terminal::state::TerminalState;
+
+/// What a case's output must look like
+enum Expect {
+    /// Exactly this output
+    Equals(&'static str),
+    /// Output containing this text
+    Contains(&'static str),
+    /// The command fails
+    Fails,
+}
+
+/// Command lines in order (later cases see earlier cases' files)
+const CASES: &[(&str, Expect)] = &[
+    ("echo hello world", Expect::Equals("hello world")),
+    ("mkdir sub", Expect::Equals("")),
+    ("cd sub", Expect::Equals("")),
+    ("pwd", Expect::Contains("sub")),
+    ("echo alpha > notes.txt", Expect::Equals("")),
+    ("echo beta >> notes.txt", Expect::Equals("")),
+    ("wc -l notes.txt", Expect::Contains("2 notes.txt")),
+    ("grep beta notes.txt", Expect::Equals("beta")),
+    ("sort -r < notes.txt", Expect::Equals("beta\nalpha")),
+    (
+        "sha256sum notes.txt",
+        Expect::Contains("e49c81e2d2f84e259d40e2fb8192f3bcd198b355184845d76d8f58807d0d78ee"),
+    ),
+    ("echo zaxiom | base64", Expect::Equals("emF4aW9t")),
+    (
+        r#"echo '{"a": [1, 2]}' | jq -c .a"#,
+        Expect::Equals("[1,2]"),
+    ),
+    ("expr 2 + 3", Expect::Equals("5")),
+    ("basename /a/b/c.txt", Expect::Equals("c.txt")),
+    ("true", Expect::Equals("")),
+    ("false", Expect::Fails),
+    ("cd ..", Expect::Equals("")),
+    ("ls", Expect::Contains("sub")),
+    ("rm -r sub", Expect::Equals("")),
+    ("ls", Expect::Equals("")),
+];
+
+/// Outcome of one case
+pub struct CaseResult {
+    pub command: &'static str,
+    /// Why it failed
+    pub failure: Option<String>,
+}
+
+/// Run every case in a fresh scratch directory
+pub fn run_suite() -> Vec<CaseResult> {
+    let dir = scratch_dir();
+    let _ = std::fs::remove_dir_all(&dir);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        return vec![CaseResult {
+            command: "(setup)",
+            failure: Some(format!("cannot create {}: {}", dir.display(), e)),
+        }];
+    }
+
+    let executor = Executor::new();
+    let mut state = TerminalState::new();
+    state.set_cwd(dir.clone());
+
+    let results = CASES
+        .iter()
+        .map(|(command, expect)| {
+            // Compared as shown, without colors
+            let outcome = executor
+                .execute_with_history(command, &mut state, None)
+                .map(|got| strip_ansi(&got));
+            let failure = match (expect, outcome) {
+                (Expect::Equals(want), Ok(got)) if got == *want => None,
+                (Expect::Contains(want), Ok(got)) if got.contains(want) => None,
+                (Expect::Fails, Err(_)) => None,
+                (Expect::Fails, Ok(got)) => Some(format!("expected an error, got {:?}", got)),
+                (Expect::Equals(want) | Expect::Contains(want), Ok(got)) => {
+                    Some(format!("expected {:?}, got {:?}", want, got))
+                }
+                (_, Err(e)) => Some(format!("error: {}", e)),
+            };
+            CaseResult { command, failure }
+        })
+        .collect();
+
+    let _ = std::fs::remove_dir_all(&dir);
+    results
+}
+
+/// Run the suite and print a report; returns whether every case passed
+pub fn run() -> bool {
+    let results = run_suite();
+    let failed = results.iter().filter(|r| r.failure.is_some()).count();
+    for result in &results {
+        match &result.failure {
+            None => println!("ok    {}", result.command),
+            Some(why) => println!("FAIL  {}\n      {}", result.command, why),
+        }
+    }
+    println!("\n{} passed, {} failed", results.len() - failed, failed);
+    failed == 0
+}
+
+fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("zaxiom-self-test-{}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test_passes() {
+        for result in run_suite() {
+            assert!(
+                result.failure.is_none(),
+                "{}: {}",
+                result.command,
+                result.failure.unwrap()
+            );
+        }
+    }
+}
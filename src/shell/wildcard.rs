@@ -0,0 +1,132 @@
+//! Wildcard expansion
+//!
+//! Unquoted arguments with `*`, `?`, `**`, or `[...]` classes are replaced by
+//! the paths they match before a built-in runs (`rm *.tmp`,
+//! `cat src/**/*.rs`). Patterns are relative to the pane's directory; `~/`
+//! and absolute patterns work too. As in bash, a pattern that matches
+//! nothing is passed on as written, and hidden files only match a pattern
+//! that spells out the leading dot.
+
+use std::path::{Path, PathBuf};
+
+use glob::{glob_with, MatchOptions, Pattern};
+
+use super::parser::ParsedCommand;
+
+/// Whether an unquoted word is a pattern
+pub fn is_pattern(word: &str) -> bool {
+    word.contains(['*', '?', '['])
+}
+
+/// The paths `pattern` matches, sorted and spelled the way the pattern was
+/// (relative, `~/...`, or absolute); empty when nothing matches
+pub fn expand(pattern: &str, cwd: &Path, home: &Path) -> Vec<String> {
+    let options = MatchOptions {
+        case_sensitive: !cfg!(windows),
+        require_literal_separator: true,
+        // Checked below: the glob crate skips every hidden entry when set
+        require_literal_leading_dot: false,
+    };
+    let (base, prefix, rest): (Option<&Path>, &str, &str) =
+        if let Some(rest) = pattern.strip_prefix("~/") {
+            (Some(home), "~/", rest)
+        } else if Path::new(pattern).is_absolute() {
+            (None, "", pattern)
+        } else {
+            (Some(cwd), "", pattern)
+        };
+    let full = match base {
+        Some(base) => format!("{}/{}", Pattern::escape(&base.to_string_lossy()), rest),
+        None => rest.to_string(),
+    };
+
+    let Ok(paths) = glob_with(&full, options) else {
+        // Not a valid pattern (`[` with no `]`): taken literally
+        return Vec::new();
+    };
+    let is_hidden = |name: &str| name.starts_with('.') && name != "." && name != "..";
+    let spells_dot = rest.split('/').any(is_hidden);
+    let shown = |path: PathBuf| {
+        let path = match base {
+            Some(base) => path
+                .strip_prefix(base)
+                .map(Path::to_path_buf)
+                .unwrap_or(path),
+            None => path,
+        };
+        let hidden = path
+            .components()
+            .any(|part| is_hidden(&part.as_os_str().to_string_lossy()));
+        (!hidden || spells_dot).then(|| format!("{}{}", prefix, path.display()).replace('\\', "/"))
+    };
+    let mut matches: Vec<String> = paths.filter_map(Result::ok).filter_map(shown).collect();
+    matches.sort();
+    matches
+}
+
+/// `cmd` with its pattern arguments replaced by the paths they match
+pub fn expand_command(cmd: &ParsedCommand, cwd: &Path, home: &Path) -> ParsedCommand {
+    let mut args = Vec::with_capacity(cmd.args.len());
+    for (i, arg) in cmd.args.iter().enumerate() {
+        let matches = if cmd.patterns.contains(&i) {
+            expand(arg, cwd, home)
+        } else {
+            Vec::new()
+        };
+        if matches.is_empty() {
+            args.push(arg.clone());
+        } else {
+            args.extend(matches);
+        }
+    }
+    ParsedCommand {
+        args,
+        patterns: Vec::new(),
+        ..cmd.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::Fixture;
+
+    #[test]
+    fn test_expand() {
+        let fixture = Fixture::new("wildcard-expand")
+            .file("a.txt", "")
+            .file("b.txt", "")
+            .file("c.md", "")
+            .file(".hidden.txt", "")
+            .file(".git/config.txt", "")
+            .file("src/main.rs", "")
+            .file("src/shell/parser.rs", "")
+            .file("src/shell/notes.md", "");
+        let cwd = fixture.path();
+        let expand = |pattern: &str| expand(pattern, cwd, Path::new("/nonexistent"));
+
+        assert_eq!(expand("*.txt"), ["a.txt", "b.txt"]);
+        assert_eq!(expand("?.md"), ["c.md"]);
+        assert_eq!(expand("[ab].txt"), ["a.txt", "b.txt"]);
+        assert_eq!(expand("[!a].txt"), ["b.txt"]);
+        assert_eq!(expand(".*.txt"), [".hidden.txt"]);
+        assert_eq!(expand("**/*.txt"), ["a.txt", "b.txt"]);
+        assert_eq!(expand("src/*.rs"), ["src/main.rs"]);
+        assert_eq!(
+            expand("src/**/*.rs"),
+            ["src/main.rs", "src/shell/parser.rs"]
+        );
+        assert!(expand("*.zip").is_empty());
+        assert!(expand("[oops").is_empty());
+
+        let absolute = format!("{}/*.md", cwd.display());
+        assert_eq!(
+            expand(&absolute),
+            [format!("{}/c.md", cwd.display()).replace('\\', "/")]
+        );
+        assert_eq!(
+            super::expand("~/*.md", Path::new("/nonexistent"), cwd),
+            ["~/c.md"]
+        );
+    }
+}
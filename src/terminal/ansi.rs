@@ -157,21 +157,19 @@ fn parse_codes(codes: &str, state: &mut StyleState) {
             97 => state.fg_color = Some((255, 255, 255)), // Bright White
 
             // 256-color and 24-bit color (38;2;r;g;b or 38;5;n)
-            38 => {
-                if i + 1 < parts.len() {
-                    match parts[i + 1] {
-                        2 if i + 4 < parts.len() => {
-                            // 24-bit color
-                            state.fg_color = Some((parts[i + 2], parts[i + 3], parts[i + 4]));
-                            i += 4;
-                        }
-                        5 if i + 2 < parts.len() => {
-                            // 256-color palette
-                            state.fg_color = Some(color_256_to_rgb(parts[i + 2]));
-                            i += 2;
-                        }
-                        _ => {}
+            38 if i + 1 < parts.len() => {
+                match parts[i + 1] {
+                    2 if i + 4 < parts.len() => {
+                        // 24-bit color
+                        state.fg_color = Some((parts[i + 2], parts[i + 3], parts[i + 4]));
+                        i += 4;
                     }
+                    5 if i + 2 < parts.len() => {
+                        // 256-color palette
+                        state.fg_color = Some(color_256_to_rgb(parts[i + 2]));
+                        i += 2;
+                    }
+                    _ => {}
                 }
             }
 
@@ -187,21 +185,17 @@ fn parse_codes(codes: &str, state: &mut StyleState) {
             49 => state.bg_color = None,
 
             // 256-color and 24-bit background (48;2;r;g;b or 48;5;n)
-            48 => {
-                if i + 1 < parts.len() {
-                    match parts[i + 1] {
-                        2 if i + 4 < parts.len() => {
-                            state.bg_color = Some((parts[i + 2], parts[i + 3], parts[i + 4]));
-                            i += 4;
-                        }
-                        5 if i + 2 < parts.len() => {
-                            state.bg_color = Some(color_256_to_rgb(parts[i + 2]));
-                            i += 2;
-                        }
-                        _ => {}
-                    }
+            48 if i + 1 < parts.len() => match parts[i + 1] {
+                2 if i + 4 < parts.len() => {
+                    state.bg_color = Some((parts[i + 2], parts[i + 3], parts[i + 4]));
+                    i += 4;
                 }
-            }
+                5 if i + 2 < parts.len() => {
+                    state.bg_color = Some(color_256_to_rgb(parts[i + 2]));
+                    i += 2;
+                }
+                _ => {}
+            },
 
             _ => {}
         }
@@ -209,6 +203,34 @@ fn parse_codes(codes: &str, state: &mut StyleState) {
     }
 }
 
+/// RGB for a terminal grid color: 0-15 use the same 16 colors as SGR
+/// 30-37/90-97 above, 16-255 the 256-color palette, and `0x1rrggbb` is direct RGB
+pub fn grid_color_to_rgb(color: u32) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 49, 49),
+        (13, 188, 121),
+        (229, 229, 16),
+        (36, 114, 200),
+        (188, 63, 188),
+        (17, 168, 205),
+        (229, 229, 229),
+        (102, 102, 102),
+        (241, 76, 76),
+        (35, 209, 139),
+        (245, 245, 67),
+        (59, 142, 234),
+        (214, 112, 214),
+        (41, 184, 219),
+        (255, 255, 255),
+    ];
+    match color {
+        0..=15 => BASIC[color as usize],
+        16..=255 => color_256_to_rgb(color as u8),
+        _ => ((color >> 16) as u8, (color >> 8) as u8, color as u8),
+    }
+}
+
 /// Convert 256-color palette index to RGB
 fn color_256_to_rgb(n: u8) -> (u8, u8, u8) {
     match n {
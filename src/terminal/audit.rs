@@ -0,0 +1,299 @@
+//! Command audit log
+//!
+//! An opt-in, append-only JSONL file with one entry per executed command.
+//! Each entry stores the SHA-256 of the previous one, so editing, removing,
+//! or reordering past entries breaks the chain and `audit verify` reports
+//! where. Truncating the tail can't be detected from the file alone - note
+//! the head hash from `audit` somewhere else if that matters.
+
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// `prev_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One executed command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log, starting at 1
+    pub seq: u64,
+    /// When the command started (RFC 3339)
+    pub started: String,
+    /// When the command finished (RFC 3339)
+    pub finished: String,
+    /// User who ran it
+    pub user: String,
+    /// Directory it ran in
+    pub cwd: String,
+    /// The command line
+    pub command: String,
+    /// Exit code (None for programs still running in the PTY)
+    pub exit_code: Option<i32>,
+    /// Hash of the previous entry
+    pub prev_hash: String,
+    /// Hash of this entry (covers every field above)
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Hash of every field except `hash` itself
+    fn digest(&self) -> String {
+        let fields = (
+            self.seq,
+            &self.started,
+            &self.finished,
+            &self.user,
+            &self.cwd,
+            &self.command,
+            self.exit_code,
+            &self.prev_hash,
+        );
+        let json = serde_json::to_string(&fields).unwrap_or_default();
+        hex::encode(Sha256::digest(json.as_bytes()))
+    }
+}
+
+/// A command to record
+pub struct AuditRecord<'a> {
+    pub command: &'a str,
+    pub cwd: &'a Path,
+    pub started: DateTime<Local>,
+    pub exit_code: Option<i32>,
+}
+
+/// Appends chained entries to the audit file. Other windows may append
+/// to the same file, so each append locks it and continues from whatever
+/// entry is last at that moment.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Open (or start) the log at `path`, checking its last entry is readable
+    pub fn open(path: PathBuf) -> Result<Self> {
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                last_entry(&contents)?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(Self { path })
+    }
+
+    /// Append an entry for a finished command
+    pub fn record(&mut self, record: AuditRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        // Held until `file` is dropped, so no other process appends between
+        // reading the last entry and writing the next
+        file.lock()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let last = last_entry(&contents)?;
+
+        let mut entry = AuditEntry {
+            seq: last.as_ref().map_or(0, |entry| entry.seq) + 1,
+            started: record.started.to_rfc3339(),
+            finished: Local::now().to_rfc3339(),
+            user: current_user(),
+            cwd: record.cwd.display().to_string(),
+            command: record.command.to_string(),
+            exit_code: record.exit_code,
+            prev_hash: last.map_or_else(|| GENESIS_HASH.to_string(), |entry| entry.hash),
+            hash: String::new(),
+        };
+        entry.hash = entry.digest();
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Default log location
+    pub fn default_path() -> PathBuf {
+        crate::config::paths::data_dir().join("audit.jsonl")
+    }
+}
+
+/// The last entry in the log's contents, if any
+fn last_entry(contents: &str) -> Result<Option<AuditEntry>> {
+    match contents.lines().rfind(|l| !l.trim().is_empty()) {
+        Some(line) => Ok(Some(serde_json::from_str(line)?)),
+        None => Ok(None),
+    }
+}
+
+/// Read and verify every entry, failing at the first broken link
+pub fn verify(path: &Path) -> Result<Vec<AuditEntry>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries: Vec<AuditEntry> = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_no = i + 1;
+        let entry: AuditEntry = serde_json::from_str(line)
+            .map_err(|e| anyhow!("line {}: not a valid entry ({})", line_no, e))?;
+
+        let (expected_seq, expected_prev) = match entries.last() {
+            Some(prev) => (prev.seq + 1, prev.hash.as_str()),
+            None => (1, GENESIS_HASH),
+        };
+        if entry.seq != expected_seq {
+            return Err(anyhow!(
+                "line {}: expected entry {}, found {} (entries missing or reordered)",
+                line_no,
+                expected_seq,
+                entry.seq
+            ));
+        }
+        if entry.prev_hash != expected_prev {
+            return Err(anyhow!(
+                "line {}: chain broken - entry {} doesn't follow the one before it",
+                line_no,
+                entry.seq
+            ));
+        }
+        if entry.hash != entry.digest() {
+            return Err(anyhow!(
+                "line {}: entry {} was modified after it was written",
+                line_no,
+                entry.seq
+            ));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Render entries as CSV (header + one row per entry)
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut csv = String::from("seq,started,finished,user,cwd,command,exit_code,prev_hash,hash\n");
+    for entry in entries {
+        let exit_code = entry.exit_code.map(|c| c.to_string()).unwrap_or_default();
+        let row = [
+            entry.seq.to_string(),
+            entry.started.clone(),
+            entry.finished.clone(),
+            entry.user.clone(),
+            entry.cwd.clone(),
+            entry.command.clone(),
+            exit_code,
+            entry.prev_hash.clone(),
+            entry.hash.clone(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "zaxiom-audit-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn record(log: &mut AuditLog, command: &str) {
+        log.record(AuditRecord {
+            command,
+            cwd: Path::new("/tmp"),
+            started: Local::now(),
+            exit_code: Some(0),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_chain_survives_reopen() {
+        let path = temp_log("reopen");
+        record(&mut AuditLog::open(path.clone()).unwrap(), "ls");
+        record(&mut AuditLog::open(path.clone()).unwrap(), "cd src");
+
+        let entries = verify(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_two_handles_share_one_chain() {
+        // Two windows with the log open at once
+        let path = temp_log("handles");
+        let mut first = AuditLog::open(path.clone()).unwrap();
+        let mut second = AuditLog::open(path.clone()).unwrap();
+        record(&mut first, "ls");
+        record(&mut second, "pwd");
+        record(&mut first, "cd src");
+
+        let entries = verify(&path).unwrap();
+        let seqs: Vec<u64> = entries.iter().map(|entry| entry.seq).collect();
+        assert_eq!(seqs, [1, 2, 3]);
+        assert_eq!(entries[2].command, "cd src");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let path = temp_log("tamper");
+        let mut log = AuditLog::open(path.clone()).unwrap();
+        record(&mut log, "ls");
+        record(&mut log, "rm secrets.txt");
+        record(&mut log, "pwd");
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replace("rm secrets.txt", "ls -la")).unwrap();
+        assert!(verify(&path).unwrap_err().to_string().contains("modified"));
+
+        let lines: Vec<&str> = contents.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(verify(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        assert_eq!(csv_field("echo a,b"), "\"echo a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("ls"), "ls");
+    }
+}
@@ -6,7 +6,8 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 /// Autocomplete suggestion with metadata
 #[derive(Clone, Debug)]
@@ -36,6 +37,8 @@ pub enum SuggestionKind {
     Command,
     /// Environment variable
     EnvVar,
+    /// Directory stack entry (`+n`)
+    StackEntry,
 }
 
 /// Autocomplete engine
@@ -411,13 +414,15 @@ impl Autocomplete {
         );
     }
 
-    /// Get suggestions for the current input
+    /// Get suggestions for the current input. `dirs` is the directory stack
+    /// as `dirs` lists it (working directory first), for `cd +n` and `popd +n`.
     pub fn suggest(
         &self,
         input: &str,
         cursor_pos: usize,
         cwd: &Path,
         history: &[String],
+        dirs: &[PathBuf],
     ) -> Vec<Suggestion> {
         let input = &input[..cursor_pos.min(input.len())];
         let parts: Vec<&str> = input.split_whitespace().collect();
@@ -435,6 +440,15 @@ impl Autocomplete {
             parts.last().copied().unwrap_or("")
         };
 
+        // Directory stack indices: `cd +`, `pushd +`, and anything after popd/dirs
+        let takes_index = matches!(cmd, "cd" | "pushd" | "popd" | "dirs");
+        if takes_index
+            && (current_word.starts_with('+')
+                || (current_word.is_empty() && matches!(cmd, "popd" | "dirs")))
+        {
+            return self.suggest_stack_entries(current_word, dirs);
+        }
+
         // Check if we're completing a flag
         if current_word.starts_with('-') {
             return self.suggest_flags(cmd, current_word);
@@ -538,6 +552,21 @@ impl Autocomplete {
         suggestions
     }
 
+    /// Suggest `+n` indices into the directory stack, with the directory each
+    /// one names
+    fn suggest_stack_entries(&self, prefix: &str, dirs: &[PathBuf]) -> Vec<Suggestion> {
+        dirs.iter()
+            .enumerate()
+            .map(|(n, dir)| (format!("+{}", n), dir))
+            .filter(|(index, _)| index.starts_with(prefix))
+            .map(|(index, dir)| Suggestion {
+                text: index,
+                kind: SuggestionKind::StackEntry,
+                description: Some(dir.display().to_string().replace('\\', "/")),
+            })
+            .collect()
+    }
+
     /// Suggest git branches
     fn suggest_git_branches(&self, prefix: &str, cwd: &Path) -> Vec<Suggestion> {
         let mut suggestions = Vec::new();
@@ -683,3 +712,162 @@ impl Default for Autocomplete {
         Self::new()
     }
 }
+
+/// Signature help: the usage line of the command being typed, with the
+/// parameter the cursor is on marked
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageHint {
+    pub usage: &'static str,
+    /// Byte range in `usage` of the current parameter, if one matches
+    pub active: Option<Range<usize>>,
+}
+
+/// Usage hint for the input, once a command name and a space are typed.
+/// `usage_of` gives a registered command's usage string (None for anything
+/// else). Only the last command of a pipeline or list is considered.
+pub fn usage_hint(
+    input: &str,
+    usage_of: impl Fn(&str) -> Option<&'static str>,
+) -> Option<UsageHint> {
+    let segment = current_segment(input).trim_start();
+    let (name, rest) = segment.split_once(char::is_whitespace)?;
+    let usage = usage_of(name)?.lines().next()?.trim_end();
+    let params: Vec<Range<usize>> = split_groups(usage, true)
+        .into_iter()
+        .skip_while(|r| &usage[r.clone()] == name)
+        .collect();
+
+    let is_flag = |r: &&Range<usize>| {
+        let text = &usage[(*r).clone()];
+        text.starts_with('-') || text.starts_with("[-") || text == "[options]"
+    };
+    let words: Vec<&str> = split_groups(rest, false)
+        .into_iter()
+        .map(|r| &rest[r])
+        .collect();
+    let (done, current) = if rest.is_empty() || rest.ends_with(char::is_whitespace) {
+        (&words[..], None)
+    } else {
+        (&words[..words.len() - 1], words.last().copied())
+    };
+
+    let active = match current {
+        Some(flag) if flag.starts_with('-') => {
+            let flags: Vec<&Range<usize>> = params.iter().filter(is_flag).collect();
+            flags
+                .iter()
+                .find(|r| flag.len() > 1 && usage[(**r).clone()].contains(flag))
+                .or(flags.first())
+                .copied()
+        }
+        _ => {
+            let index = done.iter().filter(|w| !w.starts_with('-')).count();
+            let positional: Vec<&Range<usize>> = params.iter().filter(|r| !is_flag(r)).collect();
+            positional.get(index).copied().or_else(|| {
+                // A repeated parameter (`[file...]`) takes every later argument
+                positional
+                    .last()
+                    .copied()
+                    .filter(|r| usage[(*r).clone()].contains("..."))
+            })
+        }
+    };
+    Some(UsageHint {
+        usage,
+        active: active.cloned(),
+    })
+}
+
+/// The text after the last `|`, `;`, or `&` outside quotes
+fn current_segment(input: &str) -> &str {
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '|' | ';' | '&') => start = i + 1,
+            _ => {}
+        }
+    }
+    &input[start..]
+}
+
+/// Split text into whitespace-separated words, keeping quoted groups whole
+/// (and `[...]`, `<...>`, `{...}` groups too when `brackets` is set, for
+/// usage lines)
+fn split_groups(text: &str, brackets: bool) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '<' | '{') if brackets => depth += 1,
+            (None, ']' | '>' | '}') if brackets => depth = depth.saturating_sub(1),
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    words.push(s..i);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        words.push(s..text.len());
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_index_completion() {
+        let autocomplete = Autocomplete::new();
+        let cwd = PathBuf::from("/work");
+        let dirs = vec![cwd.clone(), PathBuf::from("/a"), PathBuf::from("/b")];
+
+        let all = autocomplete.suggest("popd ", 5, &cwd, &[], &dirs);
+        let indices: Vec<&str> = all.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(indices, ["+0", "+1", "+2"]);
+        assert_eq!(all[1].description.as_deref(), Some("/a"));
+
+        let cd = autocomplete.suggest("cd +2", 5, &cwd, &[], &dirs);
+        assert_eq!(cd.len(), 1);
+        assert_eq!(cd[0].kind, SuggestionKind::StackEntry);
+    }
+
+    #[test]
+    fn test_usage_hint() {
+        let usage_of = |name: &str| match name {
+            "cp" => Some("cp [-r] <source> <dest>"),
+            "cat" => Some("cat [-n] [-s|--syntax] [file...]"),
+            "awk" => Some("awk [-F sep] 'pattern {action}' [file]"),
+            _ => None,
+        };
+        let active = |input: &str| {
+            let hint = usage_hint(input, usage_of)?;
+            hint.active.map(|range| &hint.usage[range])
+        };
+
+        assert_eq!(usage_hint("cp", usage_of), None);
+        assert_eq!(usage_hint("nope ", usage_of), None);
+        assert_eq!(active("cp "), Some("<source>"));
+        assert_eq!(active("cp -r a"), Some("<source>"));
+        assert_eq!(active("cp a "), Some("<dest>"));
+        assert_eq!(active("cp a b "), None);
+        assert_eq!(active("cp -"), Some("[-r]"));
+        assert_eq!(active("cat a b c"), Some("[file...]"));
+        assert_eq!(active("cat --syn"), Some("[-s|--syntax]"));
+        assert_eq!(active("awk '{print $1}' "), Some("[file]"));
+        assert_eq!(active("ls | cat "), Some("[file...]"));
+    }
+}
@@ -1,17 +1,34 @@
 //! Output buffer with scrollback
 //!
 //! Stores terminal output lines with a configurable scrollback limit.
-//! Supports block-based output grouping and URL detection.
+//! Supports block-based output grouping and URL detection, and a live
+//! region at the end that a running command (`watch`) redraws in place.
+//!
+//! Only the newest lines stay in memory (and get laid out on screen). Older
+//! ones can spill to a ring of files on disk instead of being dropped, so
+//! `buffer export` still has the whole session.
 
 #![allow(dead_code)]
 
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::ansi;
+use crate::config::settings::TimestampMode;
+
+/// Percentage of the scrollback cap at which the pane warns that old output will be dropped
+pub const WARN_PERCENT: usize = 90;
 
 /// Regex for detecting URLs in output
 static URL_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"https?://[^\s<>"{}|\\^\[\]`]+"#).unwrap());
+    Lazy::new(|| Regex::new(r#"https?://[^\s<>"{}|\\^\[\]`\x1b]+"#).unwrap());
 
 /// Output buffer for terminal display
 pub struct OutputBuffer {
@@ -25,6 +42,176 @@ pub struct OutputBuffer {
     selected_block: Option<usize>,
     /// Search query for filtering
     search_query: Option<String>,
+    /// Whether the near-cap warning was shown since the buffer last had room
+    cap_warned: bool,
+    /// First line of the live region, which runs to the end of the buffer
+    live_start: Option<usize>,
+    /// Where lines go when they leave memory (dropped when `None`)
+    spill: Option<Spill>,
+    /// Lines dropped without being spilled
+    dropped: usize,
+    /// `OutputLine::id` for the next new or changed line
+    next_id: u64,
+}
+
+/// Spill files made by this process so far, for unique names
+static NEXT_SPILL: AtomicUsize = AtomicUsize::new(0);
+
+/// Lines that left memory, kept in a ring of two segment files: once the
+/// one being written is full, the older one is emptied and written next, so
+/// the disk copy stays under its cap and loses its oldest half at a time
+struct Spill {
+    paths: [PathBuf; 2],
+    /// Segment being written
+    current: usize,
+    writer: Option<BufWriter<File>>,
+    /// Lines and bytes in each segment
+    lines: [usize; 2],
+    bytes: [u64; 2],
+    /// Size a segment may reach before the ring moves on
+    segment_bytes: u64,
+    /// Lines lost off the end of the ring
+    dropped: usize,
+}
+
+impl Spill {
+    fn new(max_bytes: u64) -> Self {
+        let id = format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_SPILL.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = |segment: usize| spill_dir().join(format!("{}-{}.txt", id, segment));
+        Self {
+            paths: [path(0), path(1)],
+            current: 0,
+            writer: None,
+            lines: [0; 2],
+            bytes: [0; 2],
+            segment_bytes: (max_bytes / 2).max(1),
+            dropped: 0,
+        }
+    }
+
+    fn write<'a>(&mut self, lines: impl Iterator<Item = &'a OutputLine>) -> io::Result<()> {
+        for line in lines {
+            if self.bytes[self.current] >= self.segment_bytes {
+                self.flush()?;
+                self.writer = None;
+                self.current ^= 1;
+                self.dropped += self.lines[self.current];
+                self.lines[self.current] = 0;
+                self.bytes[self.current] = 0;
+            }
+            let writer = match &mut self.writer {
+                Some(writer) => writer,
+                None => {
+                    std::fs::create_dir_all(spill_dir())?;
+                    let file = if self.lines[self.current] == 0 {
+                        File::create(&self.paths[self.current])?
+                    } else {
+                        File::options()
+                            .append(true)
+                            .open(&self.paths[self.current])?
+                    };
+                    self.writer.insert(BufWriter::new(file))
+                }
+            };
+            writer.write_all(line.text.as_bytes())?;
+            writer.write_all(b"\n")?;
+            self.lines[self.current] += 1;
+            self.bytes[self.current] += line.text.len() as u64 + 1;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Readers for both segments, oldest first
+    fn readers(&mut self) -> io::Result<Vec<BufReader<File>>> {
+        self.flush()?;
+        let mut readers = Vec::new();
+        for segment in [self.current ^ 1, self.current] {
+            if self.lines[segment] == 0 {
+                continue;
+            }
+            readers.push(BufReader::new(File::open(&self.paths[segment])?));
+        }
+        Ok(readers)
+    }
+
+    fn clear(&mut self) {
+        self.writer = None;
+        for path in &self.paths {
+            let _ = std::fs::remove_file(path);
+        }
+        self.current = 0;
+        self.lines = [0; 2];
+        self.bytes = [0; 2];
+        self.dropped = 0;
+    }
+
+    fn lines(&self) -> usize {
+        self.lines[0] + self.lines[1]
+    }
+
+    fn bytes(&self) -> u64 {
+        self.bytes[0] + self.bytes[1]
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Folder holding the spilled scrollback of running panes
+pub fn spill_dir() -> PathBuf {
+    std::env::temp_dir().join("zaxiom-scrollback")
+}
+
+/// Size of the scrollback, for `buffer stats`
+#[derive(Clone, Debug, Default)]
+pub struct BufferStats {
+    /// Lines currently kept
+    pub lines: usize,
+    /// Scrollback cap in lines
+    pub max_lines: usize,
+    /// Bytes of text
+    pub bytes: usize,
+    /// Estimated memory, including per-line bookkeeping
+    pub memory: usize,
+    /// Older lines spilled to disk
+    pub spilled_lines: usize,
+    /// Bytes of spilled text
+    pub spilled_bytes: u64,
+    /// Lines gone for good (off the end of the disk ring, or never spilled)
+    pub dropped_lines: usize,
+    /// Whether old lines spill to disk rather than being dropped
+    pub spilling: bool,
+    /// Lines before the first command block (banner, greetings)
+    pub loose: BlockStats,
+    /// Per-block sizes, oldest first
+    pub blocks: Vec<BlockStats>,
+}
+
+/// Size of one command block
+#[derive(Clone, Debug, Default)]
+pub struct BlockStats {
+    /// The command that produced the block
+    pub command: String,
+    /// Lines still in the scrollback
+    pub lines: usize,
+    /// Bytes of text
+    pub bytes: usize,
+    /// Estimated memory, including per-line bookkeeping
+    pub memory: usize,
 }
 
 /// A command block groups a command with its output
@@ -42,8 +229,19 @@ pub struct CommandBlock {
     pub success: bool,
     /// Timestamp when the command was executed
     pub timestamp: std::time::Instant,
+    /// Wall-clock time the command was executed (for the timestamp gutter)
+    pub started_at: DateTime<Local>,
     /// Duration of command execution (set when block ends)
     pub duration: Option<std::time::Duration>,
+    /// Shown as a one-line summary instead of its lines
+    pub collapsed: bool,
+}
+
+impl CommandBlock {
+    /// Lines of output, not counting the echoed command
+    pub fn output_len(&self) -> usize {
+        (self.end_line - self.start_line).saturating_sub(self.command.split('\n').count())
+    }
 }
 
 /// A single line of output
@@ -57,6 +255,11 @@ pub struct OutputLine {
     pub block_id: Option<usize>,
     /// Detected URLs in this line
     pub urls: Vec<UrlSpan>,
+    /// Wall-clock time the line was received
+    pub time: DateTime<Local>,
+    /// Differs from every other line's, and changes when the text does, so
+    /// a renderer can keep the line's layout for as long as the id is the same
+    pub id: u64,
 }
 
 /// A URL span in a line
@@ -87,14 +290,26 @@ impl OutputBuffer {
     /// Create a new output buffer with specified max lines
     pub fn new(max_lines: usize) -> Self {
         Self {
-            lines: VecDeque::with_capacity(max_lines),
-            max_lines,
+            lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
             blocks: Vec::new(),
             selected_block: None,
             search_query: None,
+            cap_warned: false,
+            live_start: None,
+            spill: None,
+            dropped: 0,
+            next_id: 0,
         }
     }
 
+    /// Move lines that no longer fit in memory to disk, keeping up to
+    /// `max_bytes` of them, instead of dropping them (0 keeps dropping)
+    pub fn with_spill(mut self, max_bytes: u64) -> Self {
+        self.spill = (max_bytes > 0).then(|| Spill::new(max_bytes));
+        self
+    }
+
     /// Detect URLs in a text string
     fn detect_urls(text: &str) -> Vec<UrlSpan> {
         URL_REGEX
@@ -109,43 +324,74 @@ impl OutputBuffer {
 
     /// Push a normal line
     pub fn push_line(&mut self, text: &str) {
-        let urls = Self::detect_urls(text);
-        let current_block_id = self.blocks.last().map(|b| b.id);
-        self.push(OutputLine {
-            text: text.to_string(),
-            line_type: LineType::Normal,
-            block_id: current_block_id,
-            urls,
-        });
+        self.push(text, LineType::Normal);
     }
 
     /// Push an error line
     pub fn push_error(&mut self, text: &str) {
-        let urls = Self::detect_urls(text);
-        let current_block_id = self.blocks.last().map(|b| b.id);
-        self.push(OutputLine {
-            text: text.to_string(),
-            line_type: LineType::Error,
-            block_id: current_block_id,
-            urls,
-        });
+        self.push(text, LineType::Error);
     }
 
     /// Push a success line
     pub fn push_success(&mut self, text: &str) {
-        let urls = Self::detect_urls(text);
-        let current_block_id = self.blocks.last().map(|b| b.id);
-        self.push(OutputLine {
-            text: text.to_string(),
-            line_type: LineType::Success,
-            block_id: current_block_id,
-            urls,
-        });
+        self.push(text, LineType::Success);
+    }
+
+    fn take_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    /// Append streamed text: it continues the last line, and each newline in
+    /// it starts a new one
+    pub fn append_text(&mut self, text: &str) {
+        let mut pieces = text.split('\n');
+        if let Some(first) = pieces.next().filter(|_| !self.lines.is_empty()) {
+            let id = self.take_id();
+            if let Some(line) = self.lines.back_mut() {
+                line.text.push_str(first);
+                line.urls = Self::detect_urls(&line.text);
+                line.id = id;
+            }
+        }
+        for piece in pieces {
+            self.push_line(piece);
+        }
+    }
+
+    /// Remove the newest `count` lines, e.g. to replace streamed text with
+    /// its final rendering
+    pub fn pop_lines(&mut self, count: usize) {
+        let keep = self.lines.len().saturating_sub(count);
+        self.lines.truncate(keep);
+        if let Some(start) = &mut self.live_start {
+            *start = (*start).min(keep);
+        }
+        for block in &mut self.blocks {
+            block.start_line = block.start_line.min(keep);
+            block.end_line = block.end_line.min(keep);
+        }
+    }
+
+    /// Show `text` in the live region in place of what it showed before,
+    /// opening the region at the end of the buffer if there is none
+    pub fn set_live(&mut self, text: &str) {
+        let start = *self.live_start.get_or_insert(self.lines.len());
+        self.pop_lines(self.lines.len().saturating_sub(start));
+        for line in text.split('\n') {
+            self.push_line(line);
+        }
+    }
+
+    /// Close the live region; its last contents stay as ordinary output
+    pub fn end_live(&mut self) {
+        self.live_start = None;
     }
 
     /// Start a new command block
     pub fn start_block(&mut self, command: &str) {
-        let block_id = self.blocks.len();
+        // Ids keep counting after old blocks leave the scrollback
+        let block_id = self.blocks.last().map_or(0, |b| b.id + 1);
         let start_line = self.lines.len();
         self.blocks.push(CommandBlock {
             id: block_id,
@@ -154,7 +400,9 @@ impl OutputBuffer {
             end_line: start_line,
             success: true,
             timestamp: std::time::Instant::now(),
+            started_at: Local::now(),
             duration: None,
+            collapsed: false,
         });
     }
 
@@ -235,22 +483,19 @@ impl OutputBuffer {
             .collect()
     }
 
-    /// Push a line
-    fn push(&mut self, line: OutputLine) {
+    /// Push a line of the given type
+    fn push(&mut self, text: &str, line_type: LineType) {
         if self.lines.len() >= self.max_lines {
-            self.lines.pop_front();
-            // Adjust block start/end indices
-            for block in &mut self.blocks {
-                if block.start_line > 0 {
-                    block.start_line -= 1;
-                }
-                if block.end_line > 0 {
-                    block.end_line -= 1;
-                }
-            }
-            // Remove blocks that are now entirely out of bounds
-            self.blocks.retain(|b| b.end_line > 0);
+            self.drop_front(1);
         }
+        let line = OutputLine {
+            text: text.to_string(),
+            line_type,
+            block_id: self.blocks.last().map(|b| b.id),
+            urls: Self::detect_urls(text),
+            time: Local::now(),
+            id: self.take_id(),
+        };
         self.lines.push_back(line);
         // Update current block's end line
         if let Some(block) = self.blocks.last_mut() {
@@ -258,12 +503,163 @@ impl OutputBuffer {
         }
     }
 
-    /// Clear all lines
+    /// Drop the `count` oldest lines (spilling them if enabled), shifting
+    /// block indices to match
+    fn drop_front(&mut self, count: usize) {
+        let count = count.min(self.lines.len());
+        if let Some(spill) = &mut self.spill {
+            if let Err(e) = spill.write(self.lines.range(..count)) {
+                eprintln!(
+                    "Scrollback spill failed, dropping old output instead: {}",
+                    e
+                );
+                self.spill = None;
+            }
+        }
+        if self.spill.is_none() {
+            self.dropped += count;
+        }
+        self.lines.drain(..count);
+        if let Some(start) = &mut self.live_start {
+            *start = start.saturating_sub(count);
+        }
+        for block in &mut self.blocks {
+            block.start_line = block.start_line.saturating_sub(count);
+            block.end_line = block.end_line.saturating_sub(count);
+        }
+        // Remove blocks that are now entirely out of bounds
+        self.blocks.retain(|b| b.end_line > 0);
+        if self.selected_block.is_some_and(|i| i >= self.blocks.len()) {
+            self.selected_block = None;
+        }
+    }
+
+    /// Keep only the newest `keep` lines in memory; returns how many left
+    pub fn trim(&mut self, keep: usize) -> usize {
+        let dropped = self.lines.len().saturating_sub(keep);
+        self.drop_front(dropped);
+        dropped
+    }
+
+    /// Scrollback cap in lines
+    pub fn max_lines(&self) -> usize {
+        self.max_lines
+    }
+
+    /// Change how many lines stay in memory, moving any beyond the new cap
+    /// out right away
+    pub fn set_max_lines(&mut self, max_lines: usize) {
+        self.max_lines = max_lines.max(1);
+        self.trim(self.max_lines);
+        self.cap_warned = false;
+    }
+
+    /// Whether old lines spill to disk rather than being dropped
+    pub fn is_spilling(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Whether the buffer just reached `WARN_PERCENT` of its cap. True once,
+    /// then again only after the buffer has had room (cleared or trimmed).
+    /// Never when old lines spill to disk, since nothing is lost.
+    pub fn cap_warning_due(&mut self) -> bool {
+        if self.spill.is_some() {
+            return false;
+        }
+        let full = self.lines.len() * 100 >= self.max_lines * WARN_PERCENT;
+        let due = full && !self.cap_warned;
+        self.cap_warned = full;
+        due
+    }
+
+    /// Lines, bytes, and estimated memory of the scrollback and each block
+    pub fn stats(&self) -> BufferStats {
+        let mut blocks: Vec<BlockStats> = self
+            .blocks
+            .iter()
+            .map(|block| BlockStats {
+                command: block.command.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut stats = BufferStats {
+            lines: self.lines.len(),
+            max_lines: self.max_lines,
+            spilled_lines: self.spill.as_ref().map_or(0, Spill::lines),
+            spilled_bytes: self.spill.as_ref().map_or(0, Spill::bytes),
+            dropped_lines: self.dropped + self.spill.as_ref().map_or(0, |spill| spill.dropped),
+            spilling: self.spill.is_some(),
+            ..Default::default()
+        };
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            let bytes = line.text.len();
+            let memory = std::mem::size_of::<OutputLine>()
+                + line.text.capacity()
+                + line
+                    .urls
+                    .iter()
+                    .map(|url| std::mem::size_of::<UrlSpan>() + url.url.capacity())
+                    .sum::<usize>();
+            stats.bytes += bytes;
+            stats.memory += memory;
+
+            let entry = match self
+                .blocks
+                .iter()
+                .position(|b| b.start_line <= idx && idx < b.end_line)
+            {
+                Some(i) => &mut blocks[i],
+                None => &mut stats.loose,
+            };
+            entry.lines += 1;
+            entry.bytes += bytes;
+            entry.memory += memory;
+        }
+
+        stats.blocks = blocks;
+        stats
+    }
+
+    /// Clear all lines, including any spilled to disk
     pub fn clear(&mut self) {
         self.lines.clear();
         self.blocks.clear();
         self.selected_block = None;
         self.search_query = None;
+        self.cap_warned = false;
+        self.live_start = None;
+        if let Some(spill) = &mut self.spill {
+            spill.clear();
+        }
+        self.dropped = 0;
+    }
+
+    /// Write the whole scrollback as plain text, spilled lines first;
+    /// returns how many lines were written
+    pub fn export(&mut self, out: &mut impl Write) -> io::Result<usize> {
+        let mut count = 0;
+        if let Some(spill) = &mut self.spill {
+            let mut line = Vec::new();
+            for mut reader in spill.readers()? {
+                while reader.read_until(b'\n', &mut line)? > 0 {
+                    let text = String::from_utf8_lossy(&line);
+                    writeln!(
+                        out,
+                        "{}",
+                        ansi::strip_ansi(text.trim_end_matches(['\r', '\n']))
+                    )?;
+                    count += 1;
+                    line.clear();
+                }
+            }
+        }
+        for line in &self.lines {
+            writeln!(out, "{}", ansi::strip_ansi(&line.text))?;
+            count += 1;
+        }
+        out.flush()?;
+        Ok(count)
     }
 
     /// Get all lines as strings (for simple rendering)
@@ -293,12 +689,67 @@ impl OutputBuffer {
 
     /// Get block by ID
     pub fn get_block(&self, block_id: usize) -> Option<&CommandBlock> {
-        self.blocks.get(block_id)
+        self.blocks
+            .binary_search_by_key(&block_id, |b| b.id)
+            .ok()
+            .map(|index| &self.blocks[index])
+    }
+
+    /// Collapse block `block_id` to its summary, or expand it again
+    pub fn toggle_collapsed(&mut self, block_id: usize) {
+        if let Ok(index) = self.blocks.binary_search_by_key(&block_id, |b| b.id) {
+            self.blocks[index].collapsed = !self.blocks[index].collapsed;
+        }
+    }
+
+    /// Collapse every block but the newest, returning how many were open
+    pub fn collapse_previous(&mut self) -> usize {
+        let Some((_, previous)) = self.blocks.split_last_mut() else {
+            return 0;
+        };
+        previous
+            .iter_mut()
+            .map(|block| !std::mem::replace(&mut block.collapsed, true))
+            .filter(|was_open| *was_open)
+            .count()
+    }
+
+    /// Expand every collapsed block
+    pub fn expand_all(&mut self) {
+        for block in &mut self.blocks {
+            block.collapsed = false;
+        }
+    }
+
+    /// Remove a finished block and its lines from the scrollback, returning
+    /// whether it was there
+    pub fn remove_block(&mut self, block_id: usize) -> bool {
+        let Ok(index) = self.blocks.binary_search_by_key(&block_id, |b| b.id) else {
+            return false;
+        };
+        let block = self.blocks.remove(index);
+        let end = block.end_line.min(self.lines.len());
+        let start = block.start_line.min(end);
+        let count = end - start;
+        self.lines.drain(start..end);
+        if let Some(live) = &mut self.live_start {
+            if *live >= end {
+                *live -= count;
+            } else {
+                *live = (*live).min(start);
+            }
+        }
+        for block in &mut self.blocks[index..] {
+            block.start_line = block.start_line.saturating_sub(count);
+            block.end_line = block.end_line.saturating_sub(count);
+        }
+        self.selected_block = None;
+        true
     }
 
     /// Get all text content of a command block (for copying)
     pub fn get_block_content(&self, block_id: usize) -> Option<String> {
-        let block = self.blocks.get(block_id)?;
+        let block = self.get_block(block_id)?;
         let mut content = String::new();
 
         // Get lines that belong to this block (skip the command line itself)
@@ -319,10 +770,30 @@ impl OutputBuffer {
 
     /// Get command from a block
     pub fn get_block_command(&self, block_id: usize) -> Option<&str> {
-        self.blocks.get(block_id).map(|b| b.command.as_str())
+        self.get_block(block_id).map(|b| b.command.as_str())
+    }
+
+    /// Time to show in the timestamp gutter beside line `idx`: the block's
+    /// start on its first line, or (verbose) when each line arrived
+    pub fn gutter_time(&self, idx: usize, mode: TimestampMode) -> Option<DateTime<Local>> {
+        let line = self.lines.get(idx)?;
+        match mode {
+            TimestampMode::Off => None,
+            TimestampMode::Lines => Some(line.time),
+            TimestampMode::Blocks => line
+                .block_id
+                .and_then(|id| self.get_block(id))
+                .filter(|block| block.start_line == idx)
+                .map(|block| block.started_at),
+        }
     }
 }
 
+/// Whether `format` is a strftime format chrono can render
+pub fn is_valid_time_format(format: &str) -> bool {
+    StrftimeItems::new(format).all(|item| !matches!(item, Item::Error))
+}
+
 /// Format a duration for display
 pub fn format_duration(d: std::time::Duration) -> String {
     let secs = d.as_secs();
@@ -340,3 +811,221 @@ pub fn format_duration(d: std::time::Duration) -> String {
         format!("{}µs", d.as_micros())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gutter_time() {
+        let mut buffer = OutputBuffer::new(100);
+        buffer.push_line("banner");
+        buffer.start_block("ls");
+        buffer.push_line("$ ls");
+        buffer.push_line("Cargo.toml");
+        buffer.end_block(true);
+
+        let started = buffer.blocks()[0].started_at;
+        assert_eq!(buffer.gutter_time(1, TimestampMode::Blocks), Some(started));
+        assert_eq!(buffer.gutter_time(0, TimestampMode::Blocks), None);
+        assert_eq!(buffer.gutter_time(2, TimestampMode::Blocks), None);
+        assert!(buffer.gutter_time(2, TimestampMode::Lines).is_some());
+        assert_eq!(buffer.gutter_time(1, TimestampMode::Off), None);
+
+        assert!(is_valid_time_format("%H:%M:%S%.3f"));
+        assert!(!is_valid_time_format("%H:%Q"));
+    }
+
+    #[test]
+    fn test_stats_and_trim() {
+        let mut buffer = OutputBuffer::new(10);
+        buffer.push_line("banner");
+        buffer.start_block("ls");
+        buffer.push_line("$ ls");
+        buffer.push_line("Cargo.toml");
+        buffer.end_block(true);
+        buffer.start_block("pwd");
+        buffer.push_line("$ pwd");
+        buffer.push_line("/home");
+        buffer.end_block(true);
+
+        let stats = buffer.stats();
+        assert_eq!(stats.lines, 5);
+        assert_eq!(stats.bytes, 6 + 4 + 10 + 5 + 5);
+        assert_eq!(stats.loose.lines, 1);
+        assert_eq!(stats.blocks.len(), 2);
+        assert_eq!(stats.blocks[0].command, "ls");
+        assert_eq!(stats.blocks[0].bytes, 14);
+        assert!(stats.memory > stats.bytes);
+
+        assert_eq!(buffer.trim(3), 2);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.blocks().len(), 2);
+        assert_eq!(buffer.stats().blocks[0].lines, 1);
+        assert_eq!(buffer.trim(10), 0);
+
+        // Warns once when reaching 90% of the cap, again after trimming
+        for i in 0..5 {
+            buffer.push_line(&i.to_string());
+        }
+        assert!(!buffer.cap_warning_due());
+        buffer.push_line("9");
+        assert!(buffer.cap_warning_due());
+        buffer.push_line("10");
+        assert!(!buffer.cap_warning_due());
+        buffer.trim(2);
+        assert!(!buffer.cap_warning_due());
+        for i in 0..8 {
+            buffer.push_line(&i.to_string());
+        }
+        assert!(buffer.cap_warning_due());
+    }
+
+    #[test]
+    fn test_spill_and_export() {
+        let mut buffer = OutputBuffer::new(3).with_spill(80);
+        for i in 0..10 {
+            buffer.push_line(&format!("\x1b[32mline {}\x1b[0m", i));
+        }
+        assert_eq!(buffer.len(), 3);
+        assert!(buffer.is_spilling());
+        assert!(!buffer.cap_warning_due());
+
+        // Two 40 byte segments of three 16 byte lines: line 6 overwrote 0-2
+        let stats = buffer.stats();
+        assert_eq!(stats.spilled_lines, 4);
+        assert_eq!(stats.dropped_lines, 3);
+
+        let mut out = Vec::new();
+        assert_eq!(buffer.export(&mut out).unwrap(), 7);
+        let expected: Vec<String> = (3..10).map(|i| format!("line {}", i)).collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected.join("\n") + "\n");
+
+        buffer.set_max_lines(2);
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.stats().max_lines, 2);
+
+        buffer.clear();
+        let mut out = Vec::new();
+        assert_eq!(buffer.export(&mut out).unwrap(), 0);
+        assert_eq!(buffer.stats().spilled_lines, 0);
+    }
+
+    #[test]
+    fn test_collapse_blocks() {
+        let mut buffer = OutputBuffer::new(4);
+        for command in ["ls", "pwd", "date"] {
+            buffer.start_block(command);
+            buffer.push_line(&format!("$ {}", command));
+            buffer.push_line("out");
+            buffer.end_block(true);
+        }
+        // "ls" left the scrollback; ids still find the right blocks
+        assert_eq!(buffer.blocks().len(), 2);
+        assert_eq!(buffer.get_block_command(2), Some("date"));
+        assert_eq!(buffer.get_block(2).unwrap().output_len(), 1);
+
+        assert_eq!(buffer.collapse_previous(), 1);
+        assert_eq!(buffer.collapse_previous(), 0);
+        assert!(buffer.get_block(1).unwrap().collapsed);
+        assert!(!buffer.get_block(2).unwrap().collapsed);
+        buffer.toggle_collapsed(1);
+        assert!(!buffer.get_block(1).unwrap().collapsed);
+        buffer.toggle_collapsed(2);
+        buffer.expand_all();
+        assert!(buffer.blocks().iter().all(|block| !block.collapsed));
+
+        buffer.start_block("whoami");
+        assert_eq!(buffer.blocks().last().unwrap().id, 3);
+    }
+
+    #[test]
+    fn test_remove_block() {
+        let mut buffer = OutputBuffer::new(100);
+        for command in ["ls", "pwd", "date"] {
+            buffer.start_block(command);
+            buffer.push_line(&format!("$ {}", command));
+            buffer.push_line(command);
+            buffer.end_block(true);
+        }
+        assert!(buffer.remove_block(1));
+        assert!(!buffer.remove_block(1));
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            ["$ ls", "ls", "$ date", "date"]
+        );
+        assert_eq!(buffer.get_block_content(2).as_deref(), Some("date"));
+        assert_eq!(buffer.get_block(2).unwrap().start_line, 2);
+    }
+
+    #[test]
+    fn test_detect_urls_stops_at_escape() {
+        let urls = OutputBuffer::detect_urls("see \x1b[4;94mhttps://example.com/a\x1b[0m)");
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].url, "https://example.com/a");
+    }
+
+    #[test]
+    fn test_append_and_pop_streamed_text() {
+        let mut buffer = OutputBuffer::new(100);
+        buffer.start_block("# hi");
+        buffer.push_line("🧠 Ollama:");
+        buffer.push_line("");
+        buffer.end_block(true);
+        for chunk in ["Hel", "lo,\nsee http", "s://example.com", "\n"] {
+            buffer.append_text(chunk);
+        }
+
+        let lines: Vec<&str> = buffer.lines().collect();
+        assert_eq!(
+            lines,
+            ["🧠 Ollama:", "Hello,", "see https://example.com", ""]
+        );
+        assert_eq!(
+            buffer.get_line(2).unwrap().urls[0].url,
+            "https://example.com"
+        );
+        assert_eq!(buffer.blocks()[0].end_line, 4);
+        // Appending gives a line a new id, and ids keep increasing
+        let id = buffer.get_line(3).unwrap().id;
+        buffer.append_text("more");
+        assert!(buffer.get_line(3).unwrap().id > id);
+        let ids: Vec<u64> = buffer.output_lines().map(|line| line.id).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        buffer.pop_lines(3);
+        buffer.push_line("Hello, rendered");
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            ["🧠 Ollama:", "Hello, rendered"]
+        );
+        assert_eq!(buffer.blocks()[0].end_line, 2);
+    }
+
+    #[test]
+    fn test_live_region_is_redrawn_in_place() {
+        let mut buffer = OutputBuffer::new(5);
+        buffer.start_block("watch date");
+        buffer.push_line("before");
+        buffer.set_live("Every 2s\n\n10:00");
+        buffer.set_live("Every 2s\n\n10:02");
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            ["before", "Every 2s", "", "10:02"]
+        );
+
+        // A frame taller than the scrollback keeps its newest lines
+        buffer.set_live("a\nb\nc\nd\ne\nf");
+        buffer.set_live("short");
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["short"]);
+
+        buffer.end_live();
+        buffer.end_block(true);
+        buffer.push_line("after");
+        buffer.set_live("next");
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            ["short", "after", "next"]
+        );
+    }
+}
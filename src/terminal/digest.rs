@@ -0,0 +1,274 @@
+//! Daily digest
+//!
+//! A report card of one day's activity, built from the open panes' command
+//! history: commands run and failed, directories visited, the longest
+//! command, git commits, and `#` AI questions. Computed locally when the
+//! `digest` command asks for it, or for the optional end-of-day toast
+//! (`[digest] toast = "18:00"`); nothing is stored or sent anywhere.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::shell::parser::{parse_command_line, split_list};
+use crate::terminal::buffer::format_duration;
+use crate::terminal::smart_history::HistoryEntry;
+
+/// Directories listed on the card
+const DIRECTORIES_SHOWN: usize = 3;
+
+/// Commit messages listed on the card
+const COMMITS_SHOWN: usize = 3;
+
+/// One day's activity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Digest {
+    /// Commands run (AI questions not included)
+    pub commands: usize,
+    pub failed: usize,
+    /// Directories commands ran in, most used first
+    pub directories: Vec<(PathBuf, usize)>,
+    /// The command that took longest
+    pub longest: Option<(String, Duration)>,
+    /// Messages (or command lines) of the `git commit`s that succeeded
+    pub commits: Vec<String>,
+    pub ai_questions: usize,
+}
+
+impl Digest {
+    /// Summarize the entries that started on `day`
+    pub fn from_entries<'a>(
+        entries: impl IntoIterator<Item = &'a HistoryEntry>,
+        day: NaiveDate,
+    ) -> Self {
+        let mut digest = Self::default();
+        let mut directories: HashMap<&Path, usize> = HashMap::new();
+
+        for entry in entries {
+            if DateTime::<Local>::from(entry.timestamp).date_naive() != day {
+                continue;
+            }
+            let command = entry.command.trim();
+            if command.starts_with('#') {
+                digest.ai_questions += 1;
+                continue;
+            }
+
+            digest.commands += 1;
+            digest.failed += usize::from(entry.exit_code.is_some_and(|code| code != 0));
+            *directories.entry(entry.cwd.as_path()).or_insert(0) += 1;
+            if let Some(duration) = entry.duration {
+                if digest.longest.as_ref().is_none_or(|(_, d)| duration > *d) {
+                    digest.longest = Some((command.to_string(), duration));
+                }
+            }
+            if entry.is_success() {
+                digest.commits.extend(git_commit_message(command));
+            }
+        }
+
+        let mut directories: Vec<_> = directories
+            .into_iter()
+            .map(|(dir, count)| (dir.to_path_buf(), count))
+            .collect();
+        directories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        digest.directories = directories;
+        digest
+    }
+
+    /// Nothing happened that day
+    pub fn is_empty(&self) -> bool {
+        self.commands == 0 && self.ai_questions == 0
+    }
+
+    /// Letter grade from the share of commands that succeeded
+    pub fn grade(&self) -> &'static str {
+        if self.commands == 0 {
+            return "-";
+        }
+        let passed = (self.commands - self.failed) as f64 / self.commands as f64;
+        match passed {
+            p if p >= 0.98 => "A+",
+            p if p >= 0.9 => "A",
+            p if p >= 0.75 => "B",
+            p if p >= 0.5 => "C",
+            _ => "D",
+        }
+    }
+
+    /// The mascot's face and verdict for the grade
+    fn mascot(&self) -> (&'static str, &'static str) {
+        match self.grade() {
+            "A+" => ("(★▽★)", "Flawless! Take the rest of the day off~"),
+            "A" => ("(◕‿◕)♡", "Great work today!"),
+            "B" => ("(^_^)", "Solid day - a few bumps, nothing we couldn't fix."),
+            "C" => ("(・_・;)", "Rough patches, but you kept going!"),
+            "D" => ("(╥﹏╥)", "Tomorrow will be kinder. Hugs!"),
+            _ => ("(-_-) zzz", "A quiet day. Rest is productive too."),
+        }
+    }
+
+    /// One-line summary for the end-of-day toast
+    pub fn summary(&self) -> String {
+        format!(
+            "{} commands, {} failed, {} commits, {} AI questions",
+            self.commands,
+            self.failed,
+            self.commits.len(),
+            self.ai_questions
+        )
+    }
+
+    /// The report card for `day`. Paths under `home` are shown with `~`;
+    /// `kawaii` adds the mascot's face and verdict.
+    pub fn render(&self, day: NaiveDate, home: &Path, kawaii: bool) -> String {
+        let mut lines = vec![format!("Daily digest - {}", day.format("%A, %B %-d"))];
+        if kawaii {
+            let (face, verdict) = self.mascot();
+            lines.push(format!("  {}  Grade {}  {}", face, self.grade(), verdict));
+        } else {
+            lines.push(format!("  Grade {}", self.grade()));
+        }
+        lines.push(String::new());
+
+        let row = |label: &str, value: String| format!("  {:<14}{}", label, value);
+        lines.push(row("Commands", self.commands.to_string()));
+        lines.push(row("Failures", self.failed.to_string()));
+        lines.push(row("AI questions", self.ai_questions.to_string()));
+        if let Some((command, duration)) = &self.longest {
+            lines.push(row(
+                "Longest",
+                format!("{} ({})", command, format_duration(*duration)),
+            ));
+        }
+
+        lines.push(row("Directories", self.directories.len().to_string()));
+        for (dir, count) in self.directories.iter().take(DIRECTORIES_SHOWN) {
+            lines.push(format!("    {} ({})", display_dir(dir, home), count));
+        }
+
+        lines.push(row("Git commits", self.commits.len().to_string()));
+        for message in self.commits.iter().take(COMMITS_SHOWN) {
+            lines.push(format!("    · {}", message));
+        }
+        if self.commits.len() > COMMITS_SHOWN {
+            lines.push(format!(
+                "    … and {} more",
+                self.commits.len() - COMMITS_SHOWN
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// The message of a `git commit` in a command line (the command line itself
+/// when there's no `-m`), or `None` if it doesn't commit
+fn git_commit_message(command: &str) -> Option<String> {
+    split_list(command).into_iter().find_map(|part| {
+        let pipeline = parse_command_line(part).ok()?;
+        let words: Vec<&str> = pipeline
+            .commands
+            .iter()
+            .flat_map(|parsed| std::iter::once(&parsed.command).chain(&parsed.args))
+            .map(|word| word.as_str())
+            .collect();
+        let (&program, rest) = words.split_first()?;
+        let mut args = rest.iter();
+        if program != "git" {
+            return None;
+        }
+        // Skip global options (`-C dir`, `-c key=value` take a value)
+        loop {
+            match *args.next()? {
+                "-C" | "-c" => {
+                    args.next();
+                }
+                option if option.starts_with('-') => {}
+                "commit" => break,
+                _ => return None,
+            }
+        }
+        let message = args
+            .skip_while(|arg| !matches!(**arg, "-m" | "--message"))
+            .nth(1);
+        Some(message.map_or_else(|| command.to_string(), |m| m.to_string()))
+    })
+}
+
+/// `dir` with the home directory shown as `~`
+pub fn display_dir(dir: &Path, home: &Path) -> String {
+    match dir.strip_prefix(home) {
+        Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Ok(rest) => format!("~/{}", rest.display()).replace('\\', "/"),
+        Err(_) => dir.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::SystemTime;
+
+    fn entry(command: &str, cwd: &str, exit_code: i32, secs: u64, day: u32) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(command.to_string(), PathBuf::from(cwd), 1);
+        entry.timestamp = SystemTime::from(Local.with_ymd_and_hms(2026, 3, day, 14, 0, 0).unwrap());
+        entry.complete(exit_code, Duration::from_secs(secs));
+        entry
+    }
+
+    #[test]
+    fn test_digest() {
+        let entries = [
+            entry("cargo build", "/home/me/app", 0, 40, 10),
+            entry("cargo test", "/home/me/app", 1, 90, 10),
+            entry(
+                "git add . && git commit -m 'fix parser'",
+                "/home/me/app",
+                0,
+                1,
+                10,
+            ),
+            entry("git -C lib commit", "/home/me/lib", 0, 2, 10),
+            entry("git commit -m 'nope'", "/home/me/app", 1, 1, 10),
+            entry("# why did the test fail?", "/home/me/app", 0, 5, 10),
+            entry("ls", "/tmp", 0, 0, 9),
+        ];
+        let day = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let digest = Digest::from_entries(&entries, day);
+
+        assert_eq!(
+            (digest.commands, digest.failed, digest.ai_questions),
+            (5, 2, 1)
+        );
+        assert_eq!(
+            digest.directories,
+            vec![
+                (PathBuf::from("/home/me/app"), 4),
+                (PathBuf::from("/home/me/lib"), 1)
+            ]
+        );
+        assert_eq!(
+            digest.longest,
+            Some(("cargo test".to_string(), Duration::from_secs(90)))
+        );
+        assert_eq!(digest.commits, ["fix parser", "git -C lib commit"]);
+        assert_eq!(digest.grade(), "C");
+
+        let card = digest.render(day, Path::new("/home/me"), true);
+        assert!(card.starts_with("Daily digest - Tuesday, March 10"));
+        assert!(card.contains("(・_・;)"));
+        assert!(card.contains("    ~/app (4)"));
+        assert!(card.contains("    · fix parser"));
+        assert!(!digest
+            .render(day, Path::new("/home/me"), false)
+            .contains("(・_・;)"));
+
+        let quiet = Digest::from_entries(&entries, day.succ_opt().unwrap());
+        assert!(quiet.is_empty());
+        assert_eq!(quiet.grade(), "-");
+        assert_eq!(git_commit_message("git status"), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Styled output export
+//!
+//! Renders output lines as HTML (for docs, chat apps, and anything else
+//! that accepts rich text) or as text with ANSI escape codes (for other
+//! terminals), keeping the colors they have on screen. Lines without
+//! their own ANSI colors get the theme's color for their line type.
+
+use eframe::egui::Color32;
+
+use super::ansi::{self, StyledSegment};
+use super::buffer::{LineType, OutputLine};
+use crate::config::theme::Theme;
+
+/// How copied output keeps its colors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// A `<pre>` block with inline styles
+    Html,
+    /// Text with 24-bit ANSI color codes
+    Ansi,
+}
+
+impl ExportFormat {
+    /// Short name for feedback messages
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "HTML",
+            ExportFormat::Ansi => "ANSI",
+        }
+    }
+}
+
+/// Render lines in the given format
+pub fn export<'a>(
+    lines: impl IntoIterator<Item = &'a OutputLine>,
+    theme: &Theme,
+    format: ExportFormat,
+) -> String {
+    let lines = lines.into_iter().map(|line| segments(line, theme));
+    match format {
+        ExportFormat::Html => to_html(lines, theme),
+        ExportFormat::Ansi => to_ansi(lines),
+    }
+}
+
+/// Plain text of the lines (the fallback for targets without rich text)
+pub fn plain_text<'a>(lines: impl IntoIterator<Item = &'a OutputLine>) -> String {
+    lines
+        .into_iter()
+        .map(|line| ansi::strip_ansi(&line.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Styled segments of a line, with the line type's color filled in
+fn segments(line: &OutputLine, theme: &Theme) -> Vec<StyledSegment> {
+    let base = match line.line_type {
+        LineType::Normal => theme.foreground,
+        LineType::Error => theme.error_color,
+        LineType::Command => theme.command_color,
+        LineType::Success => theme.success_color,
+    };
+    let mut segments = if ansi::has_ansi(&line.text) {
+        ansi::parse_ansi(&line.text)
+    } else {
+        vec![StyledSegment {
+            text: line.text.clone(),
+            ..Default::default()
+        }]
+    };
+    for segment in &mut segments {
+        segment
+            .fg_color
+            .get_or_insert((base.r(), base.g(), base.b()));
+    }
+    segments
+}
+
+fn to_html(lines: impl Iterator<Item = Vec<StyledSegment>>, theme: &Theme) -> String {
+    let mut html = format!(
+        "<pre style=\"background:{};color:{};padding:8px;font-family:monospace\">",
+        hex(theme.background),
+        hex(theme.foreground)
+    );
+    for (index, segments) in lines.enumerate() {
+        if index > 0 {
+            html.push('\n');
+        }
+        for segment in segments {
+            let mut style = String::new();
+            if let Some((r, g, b)) = segment.fg_color {
+                style.push_str(&format!("color:{};", hex(Color32::from_rgb(r, g, b))));
+            }
+            if let Some((r, g, b)) = segment.bg_color {
+                style.push_str(&format!("background:{};", hex(Color32::from_rgb(r, g, b))));
+            }
+            if segment.bold {
+                style.push_str("font-weight:bold;");
+            }
+            if segment.italic {
+                style.push_str("font-style:italic;");
+            }
+            if segment.underline {
+                style.push_str("text-decoration:underline;");
+            }
+            html.push_str(&format!(
+                "<span style=\"{}\">{}</span>",
+                style,
+                escape_html(&segment.text)
+            ));
+        }
+    }
+    html.push_str("</pre>");
+    html
+}
+
+fn to_ansi(lines: impl Iterator<Item = Vec<StyledSegment>>) -> String {
+    let mut out = String::new();
+    for (index, segments) in lines.enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        for segment in segments {
+            let mut codes = Vec::new();
+            if segment.bold {
+                codes.push("1".to_string());
+            }
+            if segment.italic {
+                codes.push("3".to_string());
+            }
+            if segment.underline {
+                codes.push("4".to_string());
+            }
+            if let Some((r, g, b)) = segment.fg_color {
+                codes.push(format!("38;2;{};{};{}", r, g, b));
+            }
+            if let Some((r, g, b)) = segment.bg_color {
+                codes.push(format!("48;2;{};{};{}", r, g, b));
+            }
+            if codes.is_empty() {
+                out.push_str(&segment.text);
+            } else {
+                out.push_str(&format!("\x1b[{}m{}\x1b[0m", codes.join(";"), segment.text));
+            }
+        }
+    }
+    out
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::buffer::OutputBuffer;
+
+    #[test]
+    fn test_export() {
+        let mut buffer = OutputBuffer::new(10);
+        buffer.push_line("\x1b[1;32mok\x1b[0m <done>");
+        buffer.push_error("failed");
+        let theme = Theme::default();
+        let lines: Vec<_> = buffer.output_lines().collect();
+
+        let html = export(lines.iter().copied(), &theme, ExportFormat::Html);
+        assert!(html.starts_with("<pre style=\"background:#1e1e2e;"));
+        assert!(html.contains("font-weight:bold;\">ok</span>"));
+        assert!(html.contains("&lt;done&gt;"));
+        let error = theme.error_color;
+        assert!(html.contains(&format!("color:{};\">failed", hex(error))));
+
+        let ansi_text = export(lines.iter().copied(), &theme, ExportFormat::Ansi);
+        assert!(ansi_text.contains(&format!(
+            "\x1b[38;2;{};{};{}mfailed\x1b[0m",
+            error.r(),
+            error.g(),
+            error.b()
+        )));
+        assert_eq!(ansi::strip_ansi(&ansi_text), "ok <done>\nfailed");
+        assert_eq!(plain_text(lines.iter().copied()), "ok <done>\nfailed");
+    }
+}
@@ -0,0 +1,222 @@
+//! Output filter
+//!
+//! A non-destructive view over the scrollback (Ctrl+Shift+L): show only
+//! errors, only one command block, or only lines matching a pattern. The
+//! buffer itself is never changed - closing the filter bar shows
+//! everything again.
+
+use regex::Regex;
+
+use super::ansi;
+use super::buffer::{LineType, OutputBuffer, OutputLine};
+
+/// Which lines of a pane's output are visible
+#[derive(Default)]
+pub struct OutputFilter {
+    /// Whether the filter bar is open (the filter only applies while it is)
+    pub active: bool,
+    /// Show only error lines (and the commands that failed)
+    pub errors_only: bool,
+    /// Show only one command block
+    pub block: Option<usize>,
+    /// Case-insensitive text, or a regex between slashes (`/warn|error/`)
+    pub pattern: String,
+    /// Compiled pattern and the text it was compiled from
+    compiled: Option<(String, Matcher)>,
+}
+
+enum Matcher {
+    Text(String),
+    Regex(Regex),
+    /// The regex doesn't compile yet (still being typed) - hide nothing
+    Invalid,
+}
+
+impl OutputFilter {
+    /// Open or close the filter bar
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+    }
+
+    /// Whether any lines can be hidden right now
+    pub fn is_filtering(&self) -> bool {
+        self.active && (self.errors_only || self.block.is_some() || !self.pattern.is_empty())
+    }
+
+    /// Whether the pattern is a regex that doesn't compile
+    pub fn pattern_error(&mut self) -> bool {
+        matches!(self.matcher(), Some(Matcher::Invalid))
+    }
+
+    /// Reset every criterion (the bar stays open)
+    pub fn clear(&mut self) {
+        self.errors_only = false;
+        self.block = None;
+        self.pattern.clear();
+    }
+
+    /// Whether the line at `index` passes the filter
+    pub fn matches(&mut self, index: usize, line: &OutputLine, buffer: &OutputBuffer) -> bool {
+        if !self.is_filtering() {
+            return true;
+        }
+        let block = line.block_id.and_then(|id| buffer.get_block(id));
+        // The prompt + command line that opens a block
+        let is_command = line.line_type == LineType::Command
+            || block.is_some_and(|block| block.start_line == index);
+
+        if let Some(block) = self.block {
+            if line.block_id != Some(block) {
+                return false;
+            }
+        }
+
+        if self.errors_only {
+            let failed_command = is_command && block.is_some_and(|block| !block.success);
+            if line.line_type != LineType::Error && !failed_command {
+                return false;
+            }
+        }
+
+        // Keep the command so matches stay attached to their block
+        if is_command && (self.block.is_some() || self.errors_only) {
+            return true;
+        }
+
+        match self.matcher() {
+            None | Some(Matcher::Invalid) => true,
+            Some(Matcher::Text(text)) => line_text(line).to_lowercase().contains(text.as_str()),
+            Some(Matcher::Regex(regex)) => regex.is_match(&line_text(line)),
+        }
+    }
+
+    /// Number of lines passing the filter
+    pub fn count(&mut self, buffer: &OutputBuffer) -> usize {
+        buffer
+            .output_lines()
+            .enumerate()
+            .filter(|(index, line)| self.matches(*index, line, buffer))
+            .count()
+    }
+
+    /// Which lines pass the filter, by index (`None` when nothing is hidden)
+    pub fn visible(&mut self, buffer: &OutputBuffer) -> Option<Vec<bool>> {
+        if !self.is_filtering() {
+            return None;
+        }
+        Some(
+            buffer
+                .output_lines()
+                .enumerate()
+                .map(|(index, line)| self.matches(index, line, buffer))
+                .collect(),
+        )
+    }
+
+    fn matcher(&mut self) -> Option<&Matcher> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        let stale = self
+            .compiled
+            .as_ref()
+            .is_none_or(|(source, _)| *source != self.pattern);
+        if stale {
+            let matcher = match self
+                .pattern
+                .strip_prefix('/')
+                .and_then(|p| p.strip_suffix('/'))
+            {
+                Some(regex) => Regex::new(&format!("(?i){}", regex))
+                    .map(Matcher::Regex)
+                    .unwrap_or(Matcher::Invalid),
+                None => Matcher::Text(self.pattern.to_lowercase()),
+            };
+            self.compiled = Some((self.pattern.clone(), matcher));
+        }
+        self.compiled.as_ref().map(|(_, matcher)| matcher)
+    }
+}
+
+fn line_text(line: &OutputLine) -> std::borrow::Cow<'_, str> {
+    if ansi::has_ansi(&line.text) {
+        ansi::strip_ansi(&line.text).into()
+    } else {
+        line.text.as_str().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> OutputBuffer {
+        let mut buffer = OutputBuffer::new(100);
+        buffer.start_block("cargo build");
+        buffer.push_line("$ cargo build");
+        buffer.push_line("   Compiling zaxiom");
+        buffer.push_error("error[E0308]: mismatched types");
+        buffer.end_block(false);
+        buffer.start_block("ls");
+        buffer.push_line("$ ls");
+        buffer.push_line("Cargo.toml  src");
+        buffer.end_block(true);
+        buffer
+    }
+
+    fn visible(filter: &mut OutputFilter, buffer: &OutputBuffer) -> Vec<String> {
+        buffer
+            .output_lines()
+            .enumerate()
+            .filter(|(index, line)| filter.matches(*index, line, buffer))
+            .map(|(_, line)| line.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_pattern_and_block() {
+        let buffer = buffer();
+        let mut filter = OutputFilter {
+            active: true,
+            pattern: "CARGO".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            visible(&mut filter, &buffer),
+            ["$ cargo build", "Cargo.toml  src"]
+        );
+
+        filter.pattern = "/^cargo\\.\\w+/".to_string();
+        assert_eq!(visible(&mut filter, &buffer), ["Cargo.toml  src"]);
+
+        filter.pattern = "/unclosed(/".to_string();
+        assert!(filter.pattern_error());
+        assert_eq!(filter.count(&buffer), buffer.len());
+
+        filter.pattern = "src".to_string();
+        filter.block = Some(0);
+        assert_eq!(visible(&mut filter, &buffer), ["$ cargo build"]);
+
+        filter.active = false;
+        assert_eq!(filter.count(&buffer), buffer.len());
+    }
+
+    #[test]
+    fn test_errors_only() {
+        let buffer = buffer();
+        let mut filter = OutputFilter {
+            active: true,
+            errors_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            visible(&mut filter, &buffer),
+            ["$ cargo build", "error[E0308]: mismatched types"]
+        );
+
+        filter.pattern = "E0308".to_string();
+        assert_eq!(filter.count(&buffer), 2);
+        filter.pattern = "warning".to_string();
+        assert_eq!(visible(&mut filter, &buffer), ["$ cargo build"]);
+    }
+}
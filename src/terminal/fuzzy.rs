@@ -1,13 +1,19 @@
 //! Fuzzy Finder
 //!
-//! fzf-like fuzzy search for history, files, and git branches.
-//! Ctrl+R for history, Ctrl+Shift+F for files, Ctrl+G for git branches.
+//! fzf-like fuzzy search for history, files, recent files, and git branches.
+//! Ctrl+R for history, Ctrl+Shift+F for files, Ctrl+Shift+O for files used
+//! by recent commands, Ctrl+G to switch git branches (Ctrl+N there creates
+//! one named by the query), Ctrl+Shift+S (or `--pick` on grep/find/fd) for the
+//! hits in the last command's output, Ctrl+Shift+J to jump to a directory on
+//! the pushd stack or one recently worked in.
 
 #![allow(dead_code)]
 
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::git::branches;
+
 /// Fuzzy finder mode
 #[derive(Clone, Debug, PartialEq)]
 pub enum FuzzyMode {
@@ -17,6 +23,12 @@ pub enum FuzzyMode {
     Files,
     /// Search git branches (Ctrl+G)
     GitBranches,
+    /// Search files used by recent commands (Ctrl+Shift+O)
+    RecentFiles,
+    /// Search grep/find hits from command output (Ctrl+Shift+S, `--pick`)
+    Results,
+    /// Jump to a stacked or recently used directory (Ctrl+Shift+J)
+    Directories,
 }
 
 /// A fuzzy finder result item
@@ -34,6 +46,8 @@ pub struct FuzzyItem {
     pub match_positions: Vec<usize>,
     /// Icon for the item type
     pub icon: &'static str,
+    /// Line number of a grep hit (results mode)
+    pub line: Option<usize>,
 }
 
 /// Fuzzy finder result action
@@ -45,6 +59,12 @@ pub enum FuzzyAction {
     Insert(String),
     /// Execute result immediately
     Execute(String),
+    /// Open a file in the editor, at a line if known
+    Edit(PathBuf, Option<usize>),
+    /// Check out a branch (branches mode)
+    Checkout(String),
+    /// Create a branch named by the query and switch to it (Ctrl+N)
+    CreateBranch(String),
     /// Cancelled by user
     Cancelled,
 }
@@ -104,7 +124,11 @@ impl FuzzyFinder {
 
         // Load items based on mode
         self.all_items = match &mode {
-            FuzzyMode::History => Vec::new(), // Will be populated externally
+            // Populated externally
+            FuzzyMode::History
+            | FuzzyMode::RecentFiles
+            | FuzzyMode::Results
+            | FuzzyMode::Directories => Vec::new(),
             FuzzyMode::Files => self.load_files(),
             FuzzyMode::GitBranches => self.load_git_branches(),
         };
@@ -123,11 +147,110 @@ impl FuzzyFinder {
                 score: 0,
                 match_positions: Vec::new(),
                 icon: "📜",
+                line: None,
+            })
+            .collect();
+        self.items = self.all_items.clone();
+    }
+
+    /// Set recent files (path, "when · command" preview), most recent first.
+    /// Paths inside the working directory are shown relative to it.
+    pub fn set_recent_files(&mut self, files: Vec<(PathBuf, String)>) {
+        self.all_items = files
+            .into_iter()
+            .map(|(path, preview)| {
+                let display = path
+                    .strip_prefix(&self.cwd)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let value = if display.contains(char::is_whitespace) {
+                    format!("\"{}\"", display)
+                } else {
+                    display.clone()
+                };
+                FuzzyItem {
+                    display,
+                    value,
+                    preview: Some(preview),
+                    score: 0,
+                    match_positions: Vec::new(),
+                    icon: "🕘",
+                    line: None,
+                }
             })
             .collect();
         self.items = self.all_items.clone();
     }
 
+    /// Set directories to jump to (path, "+n · stack" or similar preview).
+    /// Selecting one runs `cd` on it.
+    pub fn set_directories(&mut self, dirs: Vec<(PathBuf, String)>) {
+        self.all_items = dirs
+            .into_iter()
+            .map(|(path, preview)| {
+                let path = path.to_string_lossy().replace('\\', "/");
+                let value = if path.contains(char::is_whitespace) {
+                    format!("cd \"{}\"", path)
+                } else {
+                    format!("cd {}", path)
+                };
+                FuzzyItem {
+                    display: path,
+                    value,
+                    preview: Some(preview),
+                    score: 0,
+                    match_positions: Vec::new(),
+                    icon: "📁",
+                    line: None,
+                }
+            })
+            .collect();
+        self.items = self.all_items.clone();
+    }
+
+    /// Set hits from grep (`path:line:text`) or find (one path per line)
+    /// output. Lines that don't name an existing file are skipped.
+    pub fn set_results(&mut self, output: &str) {
+        self.all_items = output
+            .lines()
+            .filter_map(|line| parse_result(line, &self.cwd))
+            .map(|(path, line, text)| {
+                let value = if path.contains(char::is_whitespace) {
+                    format!("\"{}\"", path)
+                } else {
+                    path.clone()
+                };
+                let display = match (line, text) {
+                    (Some(n), Some(text)) => format!("{}:{}: {}", path, n, text.trim()),
+                    (None, Some(text)) => format!("{}: {}", path, text.trim()),
+                    _ => path.clone(),
+                };
+                let is_dir = self.cwd.join(&path).is_dir();
+                FuzzyItem {
+                    display,
+                    value,
+                    preview: None,
+                    score: 0,
+                    match_positions: Vec::new(),
+                    icon: if is_dir { "📁" } else { "📄" },
+                    line,
+                }
+            })
+            .collect();
+        self.items = self.all_items.clone();
+    }
+
+    /// Path of the selected file (recent files and results modes), for previews
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let item = self.get_selected()?;
+        match self.mode {
+            FuzzyMode::RecentFiles => Some(self.cwd.join(&item.display)),
+            FuzzyMode::Results => Some(self.cwd.join(item.value.trim_matches('"'))),
+            _ => None,
+        }
+    }
+
     /// Deactivate fuzzy finder
     pub fn deactivate(&mut self) {
         self.active = false;
@@ -205,8 +328,7 @@ impl FuzzyFinder {
             .all_items
             .iter()
             .filter_map(|item| {
-                let (score, positions) =
-                    self.fuzzy_score(&query_lower, &item.display.to_lowercase());
+                let (score, positions) = fuzzy_score(&query_lower, &item.display.to_lowercase());
                 if score > 0 {
                     let mut new_item = item.clone();
                     new_item.score = score;
@@ -219,72 +341,7 @@ impl FuzzyFinder {
             .collect();
 
         // Sort by score (highest first)
-        self.items.sort_by(|a, b| b.score.cmp(&a.score));
-    }
-
-    /// Calculate fuzzy match score and positions
-    fn fuzzy_score(&self, query: &str, target: &str) -> (i32, Vec<usize>) {
-        // Exact match
-        if target == query {
-            return (1000, (0..query.len()).collect());
-        }
-
-        // Starts with query
-        if target.starts_with(query) {
-            return (500, (0..query.len()).collect());
-        }
-
-        // Contains query as substring
-        if let Some(pos) = target.find(query) {
-            return (200, (pos..pos + query.len()).collect());
-        }
-
-        // Fuzzy match (all chars in order)
-        let mut score = 0;
-        let mut positions = Vec::new();
-        let mut query_chars = query.chars().peekable();
-        let target_chars: Vec<char> = target.chars().collect();
-
-        let mut target_idx = 0;
-        while let Some(&query_char) = query_chars.peek() {
-            if target_idx >= target_chars.len() {
-                break;
-            }
-
-            if query_char == target_chars[target_idx] {
-                positions.push(target_idx);
-                query_chars.next();
-                score += 10;
-
-                // Bonus for consecutive matches
-                if positions.len() > 1 {
-                    let prev = positions[positions.len() - 2];
-                    if target_idx == prev + 1 {
-                        score += 5;
-                    }
-                }
-
-                // Bonus for match at word boundary
-                if target_idx == 0 {
-                    score += 10;
-                } else {
-                    let prev_char = target_chars[target_idx - 1];
-                    if prev_char == '/' || prev_char == ' ' || prev_char == '_' || prev_char == '-'
-                    {
-                        score += 10;
-                    }
-                }
-            }
-
-            target_idx += 1;
-        }
-
-        // All query chars must be matched
-        if query_chars.peek().is_some() {
-            return (0, Vec::new());
-        }
-
-        (score, positions)
+        self.items.sort_by_key(|item| std::cmp::Reverse(item.score));
     }
 
     /// Load files from current directory (recursive, limited depth)
@@ -329,82 +386,54 @@ impl FuzzyFinder {
                 score: 0,
                 match_positions: Vec::new(),
                 icon,
+                line: None,
             });
         }
 
         items
     }
 
-    /// Load git branches from repository
+    /// Load git branches: the current one first, then local, then remote,
+    /// with ahead/behind counts against the upstream as the preview
     fn load_git_branches(&self) -> Vec<FuzzyItem> {
-        let mut items = Vec::new();
-
-        // Find .git directory
-        let mut current = self.cwd.clone();
-        loop {
-            let git_dir = current.join(".git");
-            if git_dir.is_dir() {
-                // Read local branches
-                let heads_dir = git_dir.join("refs").join("heads");
-                if let Ok(entries) = std::fs::read_dir(&heads_dir) {
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        items.push(FuzzyItem {
-                            display: name.clone(),
-                            value: name,
-                            preview: Some("local".to_string()),
-                            score: 0,
-                            match_positions: Vec::new(),
-                            icon: "🌿",
-                        });
-                    }
-                }
-
-                // Read remote branches
-                let remotes_dir = git_dir.join("refs").join("remotes");
-                if let Ok(remotes) = std::fs::read_dir(&remotes_dir) {
-                    for remote in remotes.filter_map(|e| e.ok()) {
-                        if let Ok(branches) = std::fs::read_dir(remote.path()) {
-                            let remote_name = remote.file_name().to_string_lossy().to_string();
-                            for branch in branches.filter_map(|e| e.ok()) {
-                                let branch_name = branch.file_name().to_string_lossy().to_string();
-                                if branch_name == "HEAD" {
-                                    continue;
-                                }
-                                let full_name = format!("{}/{}", remote_name, branch_name);
-                                items.push(FuzzyItem {
-                                    display: full_name.clone(),
-                                    value: full_name,
-                                    preview: Some("remote".to_string()),
-                                    score: 0,
-                                    match_positions: Vec::new(),
-                                    icon: "🌐",
-                                });
-                            }
-                        }
-                    }
-                }
-                break;
-            }
-
-            if !current.pop() {
-                break;
+        let branches = match branches::list(&self.cwd) {
+            Ok(branches) if !branches.is_empty() => branches,
+            result => {
+                let message = if result.is_ok() {
+                    "(no branches yet)"
+                } else {
+                    "(not a git repository)"
+                };
+                return vec![FuzzyItem {
+                    display: message.to_string(),
+                    value: String::new(),
+                    preview: None,
+                    score: 0,
+                    match_positions: Vec::new(),
+                    icon: "⚠️",
+                    line: None,
+                }];
             }
-        }
+        };
 
-        // If no branches found, show message
-        if items.is_empty() {
-            items.push(FuzzyItem {
-                display: "(not a git repository)".to_string(),
-                value: String::new(),
-                preview: None,
+        branches
+            .into_iter()
+            .map(|branch| FuzzyItem {
+                display: branch.name.clone(),
+                preview: Some(branch.preview()),
+                value: branch.name,
                 score: 0,
                 match_positions: Vec::new(),
-                icon: "⚠️",
-            });
-        }
-
-        items
+                icon: if branch.current {
+                    "📍"
+                } else if branch.remote {
+                    "🌐"
+                } else {
+                    "🌿"
+                },
+                line: None,
+            })
+            .collect()
     }
 
     /// Get mode display name
@@ -413,6 +442,9 @@ impl FuzzyFinder {
             FuzzyMode::History => "History",
             FuzzyMode::Files => "Files",
             FuzzyMode::GitBranches => "Branches",
+            FuzzyMode::RecentFiles => "Recent Files",
+            FuzzyMode::Results => "Results",
+            FuzzyMode::Directories => "Directories",
         }
     }
 
@@ -422,6 +454,9 @@ impl FuzzyFinder {
             FuzzyMode::History => "📜",
             FuzzyMode::Files => "📂",
             FuzzyMode::GitBranches => "🌿",
+            FuzzyMode::RecentFiles => "🕘",
+            FuzzyMode::Results => "🔎",
+            FuzzyMode::Directories => "📁",
         }
     }
 
@@ -430,3 +465,140 @@ impl FuzzyFinder {
         format!("{}/{}", self.items.len(), self.all_items.len())
     }
 }
+
+/// Fuzzy match score of `query` in `target` (0 when some character of
+/// the query is missing) and the positions it matched
+pub fn fuzzy_score(query: &str, target: &str) -> (i32, Vec<usize>) {
+    // Exact match
+    if target == query {
+        return (1000, (0..query.len()).collect());
+    }
+
+    // Starts with query
+    if target.starts_with(query) {
+        return (500, (0..query.len()).collect());
+    }
+
+    // Contains query as substring
+    if let Some(pos) = target.find(query) {
+        return (200, (pos..pos + query.len()).collect());
+    }
+
+    // Fuzzy match (all chars in order)
+    let mut score = 0;
+    let mut positions = Vec::new();
+    let mut query_chars = query.chars().peekable();
+    let target_chars: Vec<char> = target.chars().collect();
+
+    let mut target_idx = 0;
+    while let Some(&query_char) = query_chars.peek() {
+        if target_idx >= target_chars.len() {
+            break;
+        }
+
+        if query_char == target_chars[target_idx] {
+            positions.push(target_idx);
+            query_chars.next();
+            score += 10;
+
+            // Bonus for consecutive matches
+            if positions.len() > 1 {
+                let prev = positions[positions.len() - 2];
+                if target_idx == prev + 1 {
+                    score += 5;
+                }
+            }
+
+            // Bonus for match at word boundary
+            if target_idx == 0 {
+                score += 10;
+            } else {
+                let prev_char = target_chars[target_idx - 1];
+                if prev_char == '/' || prev_char == ' ' || prev_char == '_' || prev_char == '-' {
+                    score += 10;
+                }
+            }
+        }
+
+        target_idx += 1;
+    }
+
+    // All query chars must be matched
+    if query_chars.peek().is_some() {
+        return (0, Vec::new());
+    }
+
+    (score, positions)
+}
+
+/// Split one line of grep or find output into (path, line number, text).
+/// The path must exist relative to `cwd` (or be absolute).
+fn parse_result(line: &str, cwd: &Path) -> Option<(String, Option<usize>, Option<String>)> {
+    let line = crate::terminal::ansi::strip_ansi(line);
+    let line = line.trim_end();
+    if line.trim().is_empty() {
+        return None;
+    }
+    let exists = |path: &str| !path.is_empty() && cwd.join(path).exists();
+
+    // Don't split a Windows drive letter ("C:\src\main.rs:12:...")
+    let bytes = line.as_bytes();
+    let skip = if bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/')
+    {
+        2
+    } else {
+        0
+    };
+
+    if let Some(colon) = line[skip..].find(':').map(|i| i + skip) {
+        let (path, rest) = (&line[..colon], &line[colon + 1..]);
+        if exists(path) {
+            return Some(match rest.split_once(':') {
+                Some((n, text)) if n.parse::<usize>().is_ok() => {
+                    (path.to_string(), n.parse().ok(), Some(text.to_string()))
+                }
+                _ => (path.to_string(), None, Some(rest.to_string())),
+            });
+        }
+    }
+    exists(line).then(|| (line.to_string(), None, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_results_parses_grep_and_find_output() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-fuzzy-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("my notes.txt"), "todo\n").unwrap();
+
+        let mut finder = FuzzyFinder::new();
+        finder.activate(FuzzyMode::Results, &dir);
+        finder.set_results(
+            "src/main.rs:1:fn main() {}\n\
+             grep: missing.rs: No such file\n\
+             ./src\n\
+             my notes.txt:todo\n\
+             \x1b[35m./src/main.rs\x1b[0m\n",
+        );
+
+        let items = &finder.items;
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].value, "src/main.rs");
+        assert_eq!(items[0].line, Some(1));
+        assert_eq!(items[0].display, "src/main.rs:1: fn main() {}");
+        assert_eq!(items[1].icon, "📁");
+        assert_eq!(items[2].value, "\"my notes.txt\"");
+        assert_eq!(items[2].line, None);
+        assert_eq!(items[3].value, "./src/main.rs");
+        assert_eq!(finder.selected_path(), Some(dir.join("src/main.rs")));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -8,6 +8,8 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
+use crate::terminal::ansi::strip_ansi;
+
 /// Types of hints that can be extracted
 #[derive(Clone, Debug, PartialEq)]
 pub enum HintType {
@@ -128,6 +130,11 @@ static DOCKER_ID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\b[0-9a-f]{12}\b|\b[0-9a-f]{64}\b").unwrap()
 });
 
+/// Whether `text` is exactly a git hash (as gl and gg print them)
+pub fn is_git_hash(text: &str) -> bool {
+    (7..=40).contains(&text.len()) && text.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
 /// Hints extractor
 pub struct HintsExtractor {
     /// Which hint types to extract
@@ -157,8 +164,10 @@ impl HintsExtractor {
         Self::default()
     }
 
-    /// Extract all hints from text
+    /// Extract all hints from text; colors are stripped first, so positions
+    /// are in the plain text
     pub fn extract(&self, text: &str, line_number: usize) -> Vec<Hint> {
+        let text = &strip_ansi(text);
         let mut hints = Vec::new();
         let mut label_counter = 0;
 
@@ -432,6 +441,18 @@ mod tests {
         assert_eq!(refs[0], ("src/main.rs".to_string(), 42, Some(10)));
     }
 
+    #[test]
+    fn test_git_hash_in_colored_line() {
+        let line = "\x1b[33m● \x1b[0m\x1b[38;2;249;226;175m1a2b3c4\x1b[0m fix the build";
+        let hints = HintsExtractor::new().extract(line, 0);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].text, "1a2b3c4");
+        assert_eq!(hints[0].hint_type, HintType::GitHash);
+        assert!(is_git_hash("1a2b3c4"));
+        assert!(!is_git_hash("1a2b3c"));
+        assert!(!is_git_hash("1A2B3C4"));
+    }
+
     #[test]
     fn test_label_generation() {
         assert_eq!(HintsExtractor::generate_label(0), "a");
@@ -1,115 +1,349 @@
 //! Input handling
 //!
-//! Handles keyboard input and line editing.
-
-/// Input handler for terminal
-#[allow(dead_code)]
-pub struct InputHandler {
-    /// Current input buffer
-    buffer: String,
-    /// Cursor position within buffer
-    cursor: usize,
-}
+//! Readline-style editing of the input line: moving by line and word, and
+//! killing (cutting) text into a kill ring that Ctrl+Y yanks back, so the
+//! prompt behaves like bash or zsh. The line itself lives in the pane's
+//! `input` string and egui's text field; these functions work on a byte
+//! cursor into it.
 
-#[allow(dead_code)]
-impl InputHandler {
-    /// Create a new input handler
-    pub fn new() -> Self {
-        Self {
-            buffer: String::new(),
-            cursor: 0,
-        }
-    }
+use std::collections::VecDeque;
 
-    /// Get current input
-    pub fn text(&self) -> &str {
-        &self.buffer
-    }
+/// Kills kept for Alt+Y to cycle through
+const KILL_RING_SIZE: usize = 16;
 
-    /// Set input text
-    pub fn set_text(&mut self, text: &str) {
-        self.buffer = text.to_string();
-        self.cursor = self.buffer.len();
-    }
+/// A line-editing command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditAction {
+    /// Ctrl+A
+    LineStart,
+    /// Ctrl+E
+    LineEnd,
+    /// Alt+B
+    WordBack,
+    /// Alt+F
+    WordForward,
+    /// Ctrl+W: back to the previous space
+    KillWhitespaceWord,
+    /// Alt+Backspace: back to the start of the word
+    KillWordBack,
+    /// Alt+D
+    KillWordForward,
+    /// Ctrl+K
+    KillToEnd,
+    /// Ctrl+U
+    KillToStart,
+    /// Ctrl+Y
+    Yank,
+    /// Alt+Y, right after a yank: swap it for the kill before
+    YankPop,
+}
 
-    /// Clear input
-    pub fn clear(&mut self) {
-        self.buffer.clear();
-        self.cursor = 0;
+impl EditAction {
+    /// The action bound to a key press, if any. `line_empty` leaves Ctrl+W
+    /// to close the tab or pane when there is nothing to delete.
+    pub fn from_key(key: egui::Key, modifiers: egui::Modifiers, line_empty: bool) -> Option<Self> {
+        use egui::Key;
+        if modifiers.shift {
+            return None;
+        }
+        match (modifiers.ctrl, modifiers.alt, key) {
+            (true, false, Key::A) => Some(Self::LineStart),
+            (true, false, Key::E) => Some(Self::LineEnd),
+            (true, false, Key::W) if !line_empty => Some(Self::KillWhitespaceWord),
+            (true, false, Key::K) => Some(Self::KillToEnd),
+            (true, false, Key::U) => Some(Self::KillToStart),
+            (true, false, Key::Y) => Some(Self::Yank),
+            (false, true, Key::B) => Some(Self::WordBack),
+            (false, true, Key::F) => Some(Self::WordForward),
+            (false, true, Key::D) => Some(Self::KillWordForward),
+            (false, true, Key::Backspace) => Some(Self::KillWordBack),
+            (false, true, Key::Y) => Some(Self::YankPop),
+            _ => None,
+        }
     }
+}
 
-    /// Insert character at cursor
-    pub fn insert(&mut self, c: char) {
-        self.buffer.insert(self.cursor, c);
-        self.cursor += c.len_utf8();
-    }
+/// Killed text, newest first
+#[derive(Debug, Default)]
+pub struct KillRing {
+    kills: VecDeque<String>,
+    /// The line and cursor right after the last kill; a kill starting
+    /// from exactly there adds to that kill rather than making a new one
+    after_kill: Option<(String, usize)>,
+    /// The line, cursor, yanked range, and ring position after a yank,
+    /// for Alt+Y
+    after_yank: Option<(String, usize, std::ops::Range<usize>, usize)>,
+}
 
-    /// Delete character before cursor (backspace)
-    pub fn backspace(&mut self) {
-        if self.cursor > 0 {
-            let prev_char_boundary = self.buffer[..self.cursor]
-                .char_indices()
-                .last()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-            self.buffer.remove(prev_char_boundary);
-            self.cursor = prev_char_boundary;
+impl KillRing {
+    fn kill(&mut self, line: &mut String, range: std::ops::Range<usize>, backward: bool) -> usize {
+        if range.is_empty() {
+            return range.start;
+        }
+        let continues = self.after_kill.as_ref().is_some_and(|(text, cursor)| {
+            text == line && (*cursor == range.start || *cursor == range.end)
+        });
+        let text: String = line.drain(range.clone()).collect();
+        match self.kills.front_mut() {
+            Some(last) if continues => {
+                if backward {
+                    last.insert_str(0, &text);
+                } else {
+                    last.push_str(&text);
+                }
+            }
+            _ => {
+                self.kills.push_front(text);
+                self.kills.truncate(KILL_RING_SIZE);
+            }
         }
+        self.after_kill = Some((line.clone(), range.start));
+        range.start
     }
+}
 
-    /// Delete character at cursor (delete)
-    pub fn delete(&mut self) {
-        if self.cursor < self.buffer.len() {
-            self.buffer.remove(self.cursor);
+/// Apply `action` to `line` with the cursor at byte `cursor`, returning the
+/// new cursor position
+pub fn apply(action: EditAction, line: &mut String, cursor: usize, ring: &mut KillRing) -> usize {
+    let cursor = cursor.min(line.len());
+    let after_yank = ring.after_yank.take();
+    let cursor = match action {
+        EditAction::LineStart => 0,
+        EditAction::LineEnd => line.len(),
+        EditAction::WordBack => word_start(line, cursor),
+        EditAction::WordForward => word_end(line, cursor),
+        EditAction::KillWhitespaceWord => {
+            let start = line[..cursor].trim_end().rfind(' ').map_or(0, |i| i + 1);
+            ring.kill(line, start..cursor, true)
+        }
+        EditAction::KillWordBack => {
+            let start = word_start(line, cursor);
+            ring.kill(line, start..cursor, true)
+        }
+        EditAction::KillWordForward => {
+            let end = word_end(line, cursor);
+            ring.kill(line, cursor..end, false)
         }
+        EditAction::KillToEnd => ring.kill(line, cursor..line.len(), false),
+        EditAction::KillToStart => ring.kill(line, 0..cursor, true),
+        EditAction::Yank => yank(line, cursor, ring, 0),
+        EditAction::YankPop => match after_yank {
+            Some((text, at, range, index)) if text == *line && at == cursor => {
+                line.replace_range(range.clone(), "");
+                yank(
+                    line,
+                    range.start,
+                    ring,
+                    (index + 1) % ring.kills.len().max(1),
+                )
+            }
+            // Alt+Y only means something straight after a yank
+            _ => cursor,
+        },
+    };
+    if !matches!(
+        action,
+        EditAction::KillWhitespaceWord
+            | EditAction::KillWordBack
+            | EditAction::KillWordForward
+            | EditAction::KillToEnd
+            | EditAction::KillToStart
+    ) {
+        ring.after_kill = None;
     }
+    cursor
+}
 
-    /// Move cursor left
-    pub fn move_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor = self.buffer[..self.cursor]
-                .char_indices()
-                .last()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-        }
+fn yank(line: &mut String, cursor: usize, ring: &mut KillRing, index: usize) -> usize {
+    let Some(text) = ring.kills.get(index) else {
+        return cursor;
+    };
+    line.insert_str(cursor, text);
+    let end = cursor + text.len();
+    ring.after_yank = Some((line.clone(), end, cursor..end, index));
+    end
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Start of the word before `cursor` (skipping anything between)
+fn word_start(line: &str, cursor: usize) -> usize {
+    let before = &line[..cursor];
+    let word_end = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| is_word_char(*c))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    before[..word_end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_word_char(*c))
+        .map_or(0, |(i, c)| i + c.len_utf8())
+}
+
+/// End of the word at or after `cursor`
+fn word_end(line: &str, cursor: usize) -> usize {
+    let after = &line[cursor..];
+    let word_start = after
+        .char_indices()
+        .find(|(_, c)| is_word_char(*c))
+        .map_or(after.len(), |(i, _)| i);
+    cursor
+        + after[word_start..]
+            .char_indices()
+            .find(|(_, c)| !is_word_char(*c))
+            .map_or(after.len(), |(i, _)| word_start + i)
+}
+
+/// A path as one double-quoted word, with forward slashes like the rest
+/// of the shell's paths
+pub fn quote_path(path: &std::path::Path) -> String {
+    format!("\"{}\"", path.to_string_lossy().replace('\\', "/"))
+}
+
+/// Insert `word` at byte `cursor`, adding spaces so it stays a word of its
+/// own; returns the cursor after it
+pub fn insert_word(line: &mut String, cursor: usize, word: &str) -> usize {
+    let cursor = cursor.min(line.len());
+    let mut text = word.to_string();
+    if !line[..cursor].is_empty() && !line[..cursor].ends_with(' ') {
+        text.insert(0, ' ');
+    }
+    if !line[cursor..].starts_with(' ') {
+        text.push(' ');
     }
+    line.insert_str(cursor, &text);
+    cursor + text.len()
+}
+
+/// Byte offset of character `index` (egui cursors count characters)
+pub fn char_to_byte(line: &str, index: usize) -> usize {
+    line.char_indices()
+        .nth(index)
+        .map_or(line.len(), |(i, _)| i)
+}
+
+/// Character index of byte offset `byte`
+pub fn byte_to_char(line: &str, byte: usize) -> usize {
+    line[..byte.min(line.len())].chars().count()
+}
 
-    /// Move cursor right
-    pub fn move_right(&mut self) {
-        if self.cursor < self.buffer.len() {
-            self.cursor = self.buffer[self.cursor..]
-                .char_indices()
-                .nth(1)
-                .map(|(i, _)| self.cursor + i)
-                .unwrap_or(self.buffer.len());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        line: &str,
+        cursor: usize,
+        actions: &[EditAction],
+        ring: &mut KillRing,
+    ) -> (String, usize) {
+        let mut line = line.to_string();
+        let mut cursor = cursor;
+        for action in actions {
+            cursor = apply(*action, &mut line, cursor, ring);
         }
+        (line, cursor)
     }
 
-    /// Move cursor to start
-    pub fn home(&mut self) {
-        self.cursor = 0;
+    #[test]
+    fn test_motion() {
+        let mut ring = KillRing::default();
+        let line = "git commit -m 'fix bug'";
+        assert_eq!(run(line, 10, &[EditAction::LineStart], &mut ring).1, 0);
+        assert_eq!(
+            run(line, 0, &[EditAction::LineEnd], &mut ring).1,
+            line.len()
+        );
+        assert_eq!(run(line, 10, &[EditAction::WordBack], &mut ring).1, 4);
+        assert_eq!(run(line, 4, &[EditAction::WordBack], &mut ring).1, 0);
+        assert_eq!(run(line, 4, &[EditAction::WordForward], &mut ring).1, 10);
+        assert_eq!(run(line, 10, &[EditAction::WordForward], &mut ring).1, 13);
+        assert_eq!(
+            run("héllo wörld", 0, &[EditAction::WordForward], &mut ring).1,
+            6
+        );
     }
 
-    /// Move cursor to end
-    pub fn end(&mut self) {
-        self.cursor = self.buffer.len();
-    }
+    #[test]
+    fn test_kill_and_yank() {
+        let mut ring = KillRing::default();
+        // Ctrl+W stops at spaces, Alt+Backspace at punctuation
+        let (line, cursor) = run(
+            "cd ~/src/app",
+            12,
+            &[EditAction::KillWhitespaceWord],
+            &mut ring,
+        );
+        assert_eq!((line.as_str(), cursor), ("cd ", 3));
+        assert_eq!(ring.kills.front().map(String::as_str), Some("~/src/app"));
+        let (line, _) = run("cd ~/src/app", 12, &[EditAction::KillWordBack], &mut ring);
+        assert_eq!(line, "cd ~/src/");
 
-    /// Get cursor position
-    pub fn cursor(&self) -> usize {
-        self.cursor
+        // Consecutive kills collect into one entry
+        let mut ring = KillRing::default();
+        let (line, cursor) = run(
+            "echo one two three",
+            18,
+            &[
+                EditAction::KillWhitespaceWord,
+                EditAction::KillWhitespaceWord,
+            ],
+            &mut ring,
+        );
+        assert_eq!((line.as_str(), cursor), ("echo one ", 9));
+        assert_eq!(ring.kills.front().map(String::as_str), Some("two three"));
+        let (line, cursor) = run(&line, 0, &[EditAction::Yank], &mut ring);
+        assert_eq!((line.as_str(), cursor), ("two threeecho one ", 9));
+
+        let (line, cursor) = run("ls -la /tmp", 3, &[EditAction::KillToEnd], &mut ring);
+        assert_eq!((line.as_str(), cursor), ("ls ", 3));
+        let (line, _) = run("ls -la /tmp", 3, &[EditAction::KillToStart], &mut ring);
+        assert_eq!(line, "-la /tmp");
+
+        // Alt+Y swaps the yank for older kills
+        let (line, _) = run("", 0, &[EditAction::Yank], &mut ring);
+        assert_eq!(line, "ls ");
+        let (line, _) = run(
+            "",
+            0,
+            &[EditAction::Yank, EditAction::YankPop, EditAction::YankPop],
+            &mut ring,
+        );
+        assert_eq!(line, "two three");
+        // ...but not once something else happened
+        let (line, _) = run("x", 1, &[EditAction::YankPop], &mut ring);
+        assert_eq!(line, "x");
     }
 
-    /// Take the current input and clear
-    pub fn take(&mut self) -> String {
-        self.cursor = 0;
-        std::mem::take(&mut self.buffer)
+    #[test]
+    fn test_insert_dropped_path() {
+        let path = quote_path(std::path::Path::new("C:\\My Files\\a.txt"));
+        assert_eq!(path, "\"C:/My Files/a.txt\"");
+
+        let mut line = "cat".to_string();
+        let cursor = insert_word(&mut line, 3, &path);
+        assert_eq!(line, "cat \"C:/My Files/a.txt\" ");
+        assert_eq!(cursor, line.len());
+
+        let mut line = "cp  /tmp".to_string();
+        assert_eq!(insert_word(&mut line, 3, "\"b\""), 6);
+        assert_eq!(line, "cp \"b\" /tmp");
     }
-}
 
-impl Default for InputHandler {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_ctrl_w_on_empty_line() {
+        let ctrl = egui::Modifiers::CTRL;
+        assert_eq!(EditAction::from_key(egui::Key::W, ctrl, true), None);
+        assert_eq!(
+            EditAction::from_key(egui::Key::W, ctrl, false),
+            Some(EditAction::KillWhitespaceWord)
+        );
+        assert_eq!(
+            EditAction::from_key(egui::Key::A, egui::Modifiers::ALT, false),
+            None
+        );
     }
 }
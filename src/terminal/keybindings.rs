@@ -0,0 +1,335 @@
+//! Keyboard shortcut registry
+//!
+//! One table of the app's shortcuts, grouped by category. The which-key
+//! overlay (hold Ctrl, or press F1) and the shortcut column of the command
+//! palette are generated from it, so a binding only has to be described
+//! once. The keys listed here are the defaults: shortcuts that can be
+//! rebound (`config::keymap`) are shown with their current keys, and the
+//! rest are matched in app.rs; keep the two in step when adding a shortcut.
+
+use std::time::{Duration, Instant};
+
+use crate::config::keymap::{self, Action, Keymap};
+
+/// Heading a shortcut is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Tabs,
+    Panes,
+    Search,
+    Modes,
+    View,
+    Clipboard,
+    Editing,
+}
+
+impl Category {
+    /// Every category, in overlay order
+    pub const ALL: [Category; 7] = [
+        Category::Tabs,
+        Category::Panes,
+        Category::Search,
+        Category::Modes,
+        Category::View,
+        Category::Clipboard,
+        Category::Editing,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::Tabs => "Tabs",
+            Category::Panes => "Panes",
+            Category::Search => "Search & Jump",
+            Category::Modes => "Modes",
+            Category::View => "View",
+            Category::Clipboard => "Clipboard",
+            Category::Editing => "Editing",
+        }
+    }
+}
+
+/// When a shortcut does something
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Always,
+    /// The tab has a single pane
+    SinglePane,
+    /// The tab is split
+    SplitPanes,
+    /// More than one tab is open
+    SeveralTabs,
+}
+
+/// One keyboard shortcut
+#[derive(Debug)]
+pub struct KeyBinding {
+    /// Keys as shown to the user (`Ctrl+Shift+D`)
+    pub keys: &'static str,
+    /// What it does; matches the palette entry's name where there is one
+    pub action: &'static str,
+    pub category: Category,
+    pub when: When,
+}
+
+const fn bind(keys: &'static str, action: &'static str, category: Category) -> KeyBinding {
+    KeyBinding {
+        keys,
+        action,
+        category,
+        when: When::Always,
+    }
+}
+
+const fn bind_when(
+    keys: &'static str,
+    action: &'static str,
+    category: Category,
+    when: When,
+) -> KeyBinding {
+    KeyBinding {
+        keys,
+        action,
+        category,
+        when,
+    }
+}
+
+/// Every app-level shortcut
+pub const BINDINGS: &[KeyBinding] = &[
+    bind("Ctrl+T", "New Tab", Category::Tabs),
+    bind_when("Ctrl+W", "Close Tab", Category::Tabs, When::SinglePane),
+    bind_when("Ctrl+Tab", "Next Tab", Category::Tabs, When::SeveralTabs),
+    bind_when(
+        "Ctrl+Shift+Tab",
+        "Previous Tab",
+        Category::Tabs,
+        When::SeveralTabs,
+    ),
+    bind_when("Ctrl+1-9", "Go to Tab", Category::Tabs, When::SeveralTabs),
+    bind("Ctrl+Shift+D", "Split Horizontal", Category::Panes),
+    bind("Ctrl+Shift+E", "Split Vertical", Category::Panes),
+    bind_when("Ctrl+W", "Close Pane", Category::Panes, When::SplitPanes),
+    bind_when("Alt+→", "Next Pane", Category::Panes, When::SplitPanes),
+    bind_when("Alt+←", "Previous Pane", Category::Panes, When::SplitPanes),
+    bind_when(
+        "Ctrl+Shift+←",
+        "Resize Pane Left",
+        Category::Panes,
+        When::SplitPanes,
+    ),
+    bind_when(
+        "Ctrl+Shift+→",
+        "Resize Pane Right",
+        Category::Panes,
+        When::SplitPanes,
+    ),
+    bind_when(
+        "Ctrl+Shift+↑",
+        "Resize Pane Up",
+        Category::Panes,
+        When::SplitPanes,
+    ),
+    bind_when(
+        "Ctrl+Shift+↓",
+        "Resize Pane Down",
+        Category::Panes,
+        When::SplitPanes,
+    ),
+    bind("Ctrl+F", "Search", Category::Search),
+    bind("Ctrl+Shift+L", "Filter Output", Category::Search),
+    bind("Ctrl+R", "History Search", Category::Search),
+    bind("Ctrl+Shift+F", "Find File", Category::Search),
+    bind("Ctrl+Shift+O", "Recent Files", Category::Search),
+    bind("Ctrl+Shift+J", "Jump to Directory", Category::Search),
+    bind("Ctrl+Shift+S", "Pick Search Result", Category::Search),
+    bind("Ctrl+G", "Switch Git Branch", Category::Search),
+    bind("Ctrl+P", "Command Palette", Category::Modes),
+    bind("Ctrl+Shift+M", "Vi Mode", Category::Modes),
+    bind("Ctrl+Shift+H", "Hints Mode", Category::Modes),
+    bind("Ctrl+Shift+G", "Git Panel", Category::Modes),
+    bind("F1", "Keyboard Shortcuts", Category::Modes),
+    bind("Ctrl+=", "Zoom In", Category::View),
+    bind("Ctrl+-", "Zoom Out", Category::View),
+    bind("Ctrl+0", "Reset Zoom", Category::View),
+    bind("Ctrl+Shift+K", "Collapse Previous Blocks", Category::View),
+    bind("Ctrl+Shift+C", "Copy Input", Category::Clipboard),
+    bind("Ctrl+V", "Paste", Category::Clipboard),
+    bind("Ctrl+Shift+V", "Paste with Newlines", Category::Clipboard),
+    bind("Ctrl+C", "Interrupt / Clear Line", Category::Editing),
+    bind("Ctrl+Z", "Suspend Running Command", Category::Editing),
+    bind("Ctrl+A / Ctrl+E", "Line Start / End", Category::Editing),
+    bind("Alt+B / Alt+F", "Word Back / Forward", Category::Editing),
+    bind("Ctrl+W / Alt+⌫", "Delete Word Back", Category::Editing),
+    bind("Alt+D", "Delete Word Forward", Category::Editing),
+    bind("Ctrl+U", "Delete to Line Start", Category::Editing),
+    bind("Ctrl+K", "Delete to Line End", Category::Editing),
+    bind("Ctrl+Y / Alt+Y", "Paste Deleted Text / Cycle", Category::Editing),
+    bind("Ctrl+L", "Clear", Category::Editing),
+    bind("Alt+.", "Insert Last Argument", Category::Editing),
+    bind("Alt+1-3", "Take Suggestion", Category::Editing),
+];
+
+impl KeyBinding {
+    /// Keys as currently bound (`None` once a rebindable action is unbound)
+    pub fn current_keys(&self, keymap: &Keymap) -> Option<String> {
+        match Action::find(self.action) {
+            Some(action) => keymap.label(action),
+            None => Some(self.keys.to_string()),
+        }
+    }
+}
+
+/// Shortcut for an action (palette entry name), if it has one
+pub fn shortcut_for(action: &str) -> Option<String> {
+    BINDINGS
+        .iter()
+        .find(|binding| binding.action == action)
+        .and_then(|binding| binding.current_keys(&keymap::current()))
+}
+
+/// What decides which shortcuts apply right now
+#[derive(Debug, Clone, Copy)]
+pub struct Context {
+    pub tabs: usize,
+    pub panes: usize,
+}
+
+impl When {
+    fn applies(self, context: Context) -> bool {
+        match self {
+            When::Always => true,
+            When::SinglePane => context.panes <= 1,
+            When::SplitPanes => context.panes > 1,
+            When::SeveralTabs => context.tabs > 1,
+        }
+    }
+}
+
+/// Shortcuts that apply in `context` with their current keys, by category,
+/// leaving out empty categories. `ctrl_only` keeps just the Ctrl shortcuts.
+pub fn available(
+    context: Context,
+    ctrl_only: bool,
+) -> Vec<(Category, Vec<(String, &'static KeyBinding)>)> {
+    let keymap = keymap::current();
+    Category::ALL
+        .into_iter()
+        .map(|category| {
+            let bindings: Vec<_> = BINDINGS
+                .iter()
+                .filter(|b| b.category == category && b.when.applies(context))
+                .filter_map(|b| Some((b.current_keys(&keymap)?, b)))
+                .filter(|(keys, _)| !ctrl_only || keys.starts_with("Ctrl+"))
+                .collect();
+            (category, bindings)
+        })
+        .filter(|(_, bindings)| !bindings.is_empty())
+        .collect()
+}
+
+/// How long Ctrl has to be held on its own before the overlay appears
+pub const HOLD_DELAY: Duration = Duration::from_millis(600);
+
+/// Decides when holding Ctrl brings up the overlay: after Ctrl has been down
+/// on its own for `HOLD_DELAY`, and not until it is released again once
+/// another key (or a click) has been used with it
+#[derive(Debug, Default)]
+pub struct CtrlHold {
+    since: Option<Instant>,
+    used: bool,
+}
+
+impl CtrlHold {
+    /// Feed this frame's state; returns whether the overlay should show
+    pub fn update(&mut self, ctrl: bool, other_input: bool, now: Instant) -> bool {
+        if !ctrl {
+            *self = Self::default();
+            return false;
+        }
+        self.used |= other_input;
+        let since = *self.since.get_or_insert(now);
+        !self.used && now.duration_since(since) >= HOLD_DELAY
+    }
+
+    /// Time left until the overlay would show, for scheduling a repaint
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let since = self.since.filter(|_| !self.used)?;
+        Some(HOLD_DELAY.saturating_sub(now.duration_since(since)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_conflicting_bindings() {
+        let contexts = [1, 2].map(|tabs| [1, 2].map(|panes| Context { tabs, panes }));
+        for context in contexts.into_iter().flatten() {
+            let live: Vec<_> = BINDINGS
+                .iter()
+                .filter(|b| b.when.applies(context))
+                .collect();
+            for (i, a) in live.iter().enumerate() {
+                assert!(
+                    live[i + 1..].iter().all(|b| b.keys != a.keys),
+                    "{} is bound twice",
+                    a.keys
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_available_follows_context() {
+        let single = Context { tabs: 1, panes: 1 };
+        let split = Context { tabs: 2, panes: 2 };
+        let actions = |context, ctrl_only| -> Vec<&str> {
+            available(context, ctrl_only)
+                .into_iter()
+                .flat_map(|(_, bindings)| bindings.into_iter().map(|(_, b)| b.action))
+                .collect()
+        };
+
+        assert!(actions(single, false).contains(&"Close Tab"));
+        assert!(!actions(single, false).contains(&"Next Tab"));
+        assert!(actions(split, false).contains(&"Close Pane"));
+        assert!(!actions(split, false).contains(&"Close Tab"));
+        assert!(!actions(split, true).contains(&"Next Pane"));
+        assert_eq!(
+            shortcut_for("Jump to Directory").as_deref(),
+            Some("Ctrl+Shift+J")
+        );
+    }
+
+    #[test]
+    fn test_defaults_match_keymap() {
+        let keymap = Keymap::default();
+        for action in Action::ALL {
+            let binding = BINDINGS.iter().find(|b| b.action == action.title());
+            assert_eq!(
+                binding.map(|b| b.keys),
+                keymap.label(action).as_deref(),
+                "{}",
+                action.title()
+            );
+        }
+    }
+
+    #[test]
+    fn test_ctrl_hold() {
+        let start = Instant::now();
+        let later = start + HOLD_DELAY;
+        let mut hold = CtrlHold::default();
+        assert!(!hold.update(true, false, start));
+        assert!(hold.update(true, false, later));
+
+        // Ctrl+T: using Ctrl for a shortcut never shows the overlay
+        let mut hold = CtrlHold::default();
+        hold.update(true, true, start);
+        assert!(!hold.update(true, false, later));
+        assert_eq!(hold.remaining(later), None);
+        assert!(!hold.update(false, false, later));
+        assert!(!hold.update(true, false, later));
+    }
+}
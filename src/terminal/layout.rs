@@ -0,0 +1,235 @@
+//! Split pane layouts
+//!
+//! Predefined and user-saved pane arrangements. Layouts are written as a
+//! compact spec so custom ones fit on one line in config.toml:
+//!
+//! ```text
+//! pane                      a single pane
+//! v(0.65, pane, pane)       side by side, first pane gets 65% of the width
+//! h(pane, pane)             stacked, split evenly
+//! h(v(pane, pane), v(pane, pane))   2x2 grid
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+
+use super::split::SplitDirection;
+
+/// Built-in layouts: (name, description, spec)
+pub const PRESETS: &[(&str, &str, &str)] = &[
+    ("single", "One pane", "pane"),
+    (
+        "main-side",
+        "Large main pane with a side pane",
+        "v(0.65, pane, pane)",
+    ),
+    (
+        "main-stack",
+        "Large main pane with two stacked side panes",
+        "v(0.6, pane, h(pane, pane))",
+    ),
+    ("grid", "2x2 grid", "h(v(pane, pane), v(pane, pane))"),
+    (
+        "columns",
+        "Three equal columns",
+        "v(0.333, pane, v(pane, pane))",
+    ),
+    ("rows", "Two stacked rows", "h(pane, pane)"),
+];
+
+/// A pane arrangement
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout {
+    /// A single pane
+    Pane,
+    /// Two child layouts sharing the space
+    Split {
+        direction: SplitDirection,
+        ratio: f32,
+        first: Box<Layout>,
+        second: Box<Layout>,
+    },
+}
+
+impl Layout {
+    /// Look up a built-in layout by name
+    pub fn preset(name: &str) -> Option<Layout> {
+        let name = match name {
+            "main" | "side" => "main-side",
+            "2x2" | "quad" => "grid",
+            "cols" | "three" => "columns",
+            other => other,
+        };
+        PRESETS
+            .iter()
+            .find(|(preset, _, _)| *preset == name)
+            .and_then(|(_, _, spec)| Layout::parse(spec).ok())
+    }
+
+    /// Parse a layout spec like `v(0.6, pane, h(pane, pane))`
+    pub fn parse(spec: &str) -> Result<Layout> {
+        let mut parser = Parser {
+            input: spec.as_bytes(),
+            pos: 0,
+        };
+        let layout = parser.node()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.input.len() {
+            bail!("unexpected text at position {} in layout spec", parser.pos);
+        }
+        Ok(layout)
+    }
+
+    /// Render back to spec form (inverse of `parse`)
+    pub fn to_spec(&self) -> String {
+        match self {
+            Layout::Pane => "pane".to_string(),
+            Layout::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let dir = match direction {
+                    SplitDirection::Horizontal => "h",
+                    SplitDirection::Vertical => "v",
+                };
+                let ratio = if (ratio - 0.5).abs() < 0.001 {
+                    String::new()
+                } else {
+                    format!("{:.2}, ", ratio)
+                };
+                format!(
+                    "{}({}{}, {})",
+                    dir,
+                    ratio,
+                    first.to_spec(),
+                    second.to_spec()
+                )
+            }
+        }
+    }
+
+    /// Number of panes this layout creates
+    pub fn pane_count(&self) -> usize {
+        match self {
+            Layout::Pane => 1,
+            Layout::Split { first, second, .. } => first.pane_count() + second.pane_count(),
+        }
+    }
+}
+
+/// Recursive-descent parser for layout specs
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "expected '{}' at position {} in layout spec",
+                byte as char,
+                self.pos
+            ))
+        }
+    }
+
+    fn word(&mut self) -> &str {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.input.len()
+            && (self.input[self.pos].is_ascii_alphanumeric() || self.input[self.pos] == b'.')
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos]).unwrap_or("")
+    }
+
+    fn node(&mut self) -> Result<Layout> {
+        let start = self.pos;
+        let direction = match self.word() {
+            "pane" => return Ok(Layout::Pane),
+            "v" => SplitDirection::Vertical,
+            "h" => SplitDirection::Horizontal,
+            "" => bail!("expected a layout at position {}", start),
+            other => bail!("unknown layout element '{}' (use pane, v or h)", other),
+        };
+
+        self.expect(b'(')?;
+
+        // Optional leading ratio
+        let checkpoint = self.pos;
+        let ratio = match self.word().parse::<f32>() {
+            Ok(ratio) => {
+                if !(0.1..=0.9).contains(&ratio) {
+                    bail!("split ratio {} must be between 0.1 and 0.9", ratio);
+                }
+                self.expect(b',')?;
+                ratio
+            }
+            Err(_) => {
+                self.pos = checkpoint;
+                0.5
+            }
+        };
+
+        let first = self.node()?;
+        self.expect(b',')?;
+        let second = self.node()?;
+        self.expect(b')')?;
+
+        Ok(Layout::Split {
+            direction,
+            ratio,
+            first: Box::new(first),
+            second: Box::new(second),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_parse() {
+        for (name, _, _) in PRESETS {
+            assert!(Layout::preset(name).is_some(), "preset {} failed", name);
+        }
+        assert_eq!(Layout::preset("grid").unwrap().pane_count(), 4);
+        assert_eq!(Layout::preset("columns").unwrap().pane_count(), 3);
+    }
+
+    #[test]
+    fn test_spec_roundtrip() {
+        let spec = "v(0.65, pane, h(pane, pane))";
+        assert_eq!(Layout::parse(spec).unwrap().to_spec(), spec);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Layout::parse("v(pane)").is_err());
+        assert!(Layout::parse("x(pane, pane)").is_err());
+        assert!(Layout::parse("v(1.5, pane, pane)").is_err());
+        assert!(Layout::parse("pane pane").is_err());
+    }
+}
@@ -0,0 +1,493 @@
+//! Markdown Rendering
+//!
+//! Renders markdown to ANSI-styled text for the output view: headings,
+//! emphasis, lists, quotes, tables, highlighted code blocks and links.
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+use crate::terminal::ansi::strip_ansi;
+use crate::terminal::syntax;
+
+const H1: &str = "1;95";
+const H2: &str = "1;96";
+const HEADING: &str = "1";
+const BOLD: &str = "1";
+const ITALIC: &str = "3";
+const STRIKE: &str = "9;90";
+const CODE: &str = "38;5;216";
+const LINK: &str = "4;94";
+const BULLET: &str = "36";
+const DIM: &str = "90";
+
+/// Width of horizontal rules
+const RULE_WIDTH: usize = 40;
+
+/// Render markdown as ANSI-styled text
+///
+/// Link targets follow the link text as their own segment so the output
+/// view can make them clickable.
+pub fn render(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let mut renderer = Renderer::default();
+    for event in Parser::new_ext(markdown, options) {
+        renderer.event(event);
+    }
+    renderer.out.trim_end_matches('\n').to_string()
+}
+
+/// A table being collected cell by cell
+#[derive(Default)]
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    row: Vec<String>,
+    cell_start: usize,
+}
+
+struct Renderer {
+    out: String,
+    /// Active SGR codes, re-applied at every line start
+    styles: Vec<&'static str>,
+    /// Quote bars and list indentation written before every line
+    prefixes: Vec<String>,
+    /// Open lists with their next item number (None = bulleted)
+    lists: Vec<Option<u64>>,
+    at_line_start: bool,
+    /// Whether the last line written was blank
+    blank: bool,
+    code: Option<(String, String)>,
+    link: Option<(String, usize)>,
+    heading: Option<(HeadingLevel, usize)>,
+    table: Option<Table>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            out: String::new(),
+            styles: Vec::new(),
+            prefixes: Vec::new(),
+            lists: Vec::new(),
+            at_line_start: true,
+            blank: true,
+            code: None,
+            link: None,
+            heading: None,
+            table: None,
+        }
+    }
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match self.code.as_mut() {
+                Some((_, body)) => body.push_str(&text),
+                None => self.write(&text),
+            },
+            Event::Code(code) => self.styled(CODE, &code),
+            Event::SoftBreak | Event::HardBreak => self.finish_line(),
+            Event::Rule => {
+                self.block_start();
+                self.styled(DIM, &"─".repeat(RULE_WIDTH));
+                self.finish_line();
+            }
+            Event::TaskListMarker(checked) => {
+                if checked {
+                    self.styled("32", "☑ ");
+                } else {
+                    self.write("☐ ");
+                }
+            }
+            Event::FootnoteReference(name) => self.styled(DIM, &format!("[^{}]", name)),
+            // Raw HTML has no terminal equivalent
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph if self.lists.is_empty() => {
+                self.blank_line();
+            }
+            Tag::Heading { level, .. } => {
+                self.block_start();
+                self.push_style(match level {
+                    HeadingLevel::H1 => H1,
+                    HeadingLevel::H2 => H2,
+                    _ => HEADING,
+                });
+                self.heading = Some((level, self.out.len()));
+            }
+            Tag::BlockQuote(_) => {
+                self.block_start();
+                self.prefixes.push(format!("\x1b[{}m▎\x1b[0m ", DIM));
+                self.push_style(ITALIC);
+            }
+            Tag::CodeBlock(kind) => {
+                self.block_start();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split([',', ' '])
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code = Some((lang, String::new()));
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.block_start();
+                } else {
+                    self.finish_line();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.finish_line();
+                let depth = self.lists.len();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => match depth {
+                        0 | 1 => "• ",
+                        2 => "◦ ",
+                        _ => "▪ ",
+                    }
+                    .to_string(),
+                };
+                self.styled(BULLET, &marker);
+                self.prefixes.push(" ".repeat(marker.chars().count()));
+            }
+            Tag::Table(alignments) => {
+                self.block_start();
+                self.table = Some(Table {
+                    alignments,
+                    ..Default::default()
+                });
+            }
+            Tag::TableCell => {
+                // Cells are rendered into the buffer, then moved into the table
+                self.at_line_start = false;
+                if let Some(table) = self.table.as_mut() {
+                    table.cell_start = self.out.len();
+                }
+            }
+            Tag::Emphasis => self.push_style(ITALIC),
+            Tag::Strong => self.push_style(BOLD),
+            Tag::Strikethrough => self.push_style(STRIKE),
+            Tag::Link { dest_url, .. } => {
+                self.push_style(LINK);
+                self.link = Some((dest_url.to_string(), self.out.len()));
+            }
+            Tag::Image { dest_url, .. } => {
+                self.styled(DIM, "🖼 ");
+                self.link = Some((dest_url.to_string(), self.out.len()));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.finish_line(),
+            TagEnd::Heading(_) => {
+                self.pop_style();
+                let Some((level, start)) = self.heading.take() else {
+                    return;
+                };
+                let width = visible_width(&self.out[start..]);
+                self.finish_line();
+                match level {
+                    HeadingLevel::H1 => self.styled(DIM, &"═".repeat(width)),
+                    HeadingLevel::H2 => self.styled(DIM, &"─".repeat(width)),
+                    _ => {}
+                }
+                self.finish_line();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.pop_style();
+                self.finish_line();
+                self.prefixes.pop();
+            }
+            TagEnd::CodeBlock => {
+                if let Some((lang, body)) = self.code.take() {
+                    self.code_block(&lang, &body);
+                }
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.finish_line();
+            }
+            TagEnd::Item => {
+                self.finish_line();
+                self.prefixes.pop();
+            }
+            TagEnd::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    let cell = self.out.split_off(table.cell_start);
+                    table.row.push(cell.trim().to_string());
+                }
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    let row = std::mem::take(&mut table.row);
+                    table.rows.push(row);
+                }
+            }
+            TagEnd::Table => {
+                self.at_line_start = true;
+                if let Some(table) = self.table.take() {
+                    self.render_table(table);
+                }
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => self.pop_style(),
+            TagEnd::Link => {
+                self.pop_style();
+                self.link_target();
+            }
+            TagEnd::Image => self.link_target(),
+            _ => {}
+        }
+    }
+
+    /// Show a link's target after its text, unless the text already is the target
+    fn link_target(&mut self) {
+        let Some((url, start)) = self.link.take() else {
+            return;
+        };
+        if url.is_empty() || url.starts_with('#') || strip_ansi(&self.out[start..]) == url {
+            return;
+        }
+        self.styled(DIM, " (");
+        self.styled(LINK, &url);
+        self.styled(DIM, ")");
+    }
+
+    fn code_block(&mut self, lang: &str, body: &str) {
+        let body = body.trim_end_matches('\n');
+        let highlighted = if lang.is_empty() {
+            None
+        } else {
+            syntax::highlight_to_ansi(body, lang)
+        };
+
+        self.prefixes.push(format!("\x1b[{}m│\x1b[0m ", DIM));
+        match highlighted {
+            Some(highlighted) => {
+                for line in highlighted.split('\n') {
+                    self.write(line);
+                    self.finish_line();
+                }
+            }
+            None => {
+                for line in body.split('\n') {
+                    self.styled(CODE, line);
+                    self.finish_line();
+                }
+            }
+        }
+        self.prefixes.pop();
+    }
+
+    fn render_table(&mut self, table: Table) {
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| visible_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for (index, row) in table.rows.iter().enumerate() {
+            for (column, width) in widths.iter().enumerate() {
+                if column > 0 {
+                    self.styled(DIM, " │ ");
+                }
+                let cell = row.get(column).map(String::as_str).unwrap_or("");
+                let padding = width - visible_width(cell);
+                let (left, right) = match table.alignments.get(column) {
+                    Some(Alignment::Right) => (padding, 0),
+                    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                    _ => (0, padding),
+                };
+                self.write(&" ".repeat(left));
+                if index == 0 {
+                    self.styled(BOLD, cell);
+                } else {
+                    self.write(cell);
+                }
+                if column + 1 < widths.len() {
+                    self.write(&" ".repeat(right));
+                }
+            }
+            self.finish_line();
+
+            if index == 0 {
+                let rule: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+                self.styled(DIM, &rule.join("─┼─"));
+                self.finish_line();
+            }
+        }
+    }
+
+    /// Write text, starting new lines with the current prefixes and styles
+    fn write(&mut self, text: &str) {
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.finish_line();
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if self.at_line_start {
+                self.at_line_start = false;
+                self.blank = false;
+                let prefix = self.prefixes.concat();
+                self.out.push_str(&prefix);
+                self.apply_styles();
+            }
+            self.out.push_str(line);
+        }
+    }
+
+    fn styled(&mut self, code: &'static str, text: &str) {
+        self.push_style(code);
+        self.write(text);
+        self.pop_style();
+    }
+
+    fn push_style(&mut self, code: &'static str) {
+        self.styles.push(code);
+        if !self.at_line_start {
+            self.out.push_str(&format!("\x1b[{}m", code));
+        }
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+        if !self.at_line_start {
+            self.out.push_str("\x1b[0m");
+            self.apply_styles();
+        }
+    }
+
+    fn apply_styles(&mut self) {
+        for code in &self.styles {
+            self.out.push_str(&format!("\x1b[{}m", code));
+        }
+    }
+
+    fn finish_line(&mut self) {
+        if !self.at_line_start {
+            if !self.styles.is_empty() {
+                self.out.push_str("\x1b[0m");
+            }
+            self.out.push('\n');
+            self.at_line_start = true;
+        }
+    }
+
+    /// Separate blocks with one blank line (keeping quote bars)
+    fn blank_line(&mut self) {
+        self.finish_line();
+        if !self.blank {
+            let prefix = self.prefixes.concat();
+            self.out.push_str(prefix.trim_end());
+            self.out.push('\n');
+            self.blank = true;
+        }
+    }
+
+    /// Blocks are spaced at the top level and packed inside list items
+    fn block_start(&mut self) {
+        if self.lists.is_empty() {
+            self.blank_line();
+        } else {
+            self.finish_line();
+        }
+    }
+}
+
+fn visible_width(text: &str) -> usize {
+    strip_ansi(text).chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(markdown: &str) -> String {
+        strip_ansi(&render(markdown))
+    }
+
+    #[test]
+    fn test_headings_and_emphasis() {
+        let rendered = render("# Title\n\nSome **bold** and *italic* text");
+        assert!(rendered.contains("\x1b[1;95mTitle"));
+        assert!(rendered.contains("\x1b[1mbold\x1b[0m"));
+        assert!(rendered.contains("\x1b[3mitalic\x1b[0m"));
+        assert_eq!(
+            strip_ansi(&rendered),
+            "Title\n═════\n\nSome bold and italic text"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            plain("- one\n- two\n  - nested\n\n1. first\n2. second\n\n- [x] done"),
+            "• one\n• two\n  ◦ nested\n\n1. first\n2. second\n\n• ☑ done"
+        );
+    }
+
+    #[test]
+    fn test_styles_reapplied_per_line() {
+        // Each output line is parsed on its own, so styles must not leak across
+        let rendered = render("> quoted\n> lines");
+        for line in rendered.lines() {
+            assert!(line.contains("\x1b[3m"), "{:?}", line);
+        }
+        assert_eq!(strip_ansi(&rendered), "▎ quoted\n▎ lines");
+    }
+
+    #[test]
+    fn test_table() {
+        let table = "| Name | Size |\n|------|-----:|\n| a.rs | 12 |\n| main.rs | 3 |";
+        assert_eq!(
+            plain(table),
+            "Name    │ Size\n────────┼─────\na.rs    │   12\nmain.rs │    3"
+        );
+    }
+
+    #[test]
+    fn test_link_target_is_own_segment() {
+        let rendered = render("See [the docs](https://example.com/docs) or <https://example.com>");
+        assert_eq!(
+            strip_ansi(&rendered),
+            "See the docs (https://example.com/docs) or https://example.com"
+        );
+        let segments = crate::terminal::ansi::parse_ansi(&rendered);
+        assert!(segments
+            .iter()
+            .any(|s| s.text == "https://example.com/docs" && s.underline));
+    }
+
+    #[test]
+    fn test_code_block() {
+        let rendered = render("```rust\nfn main() {}\n```\n\n```\nplain\n```");
+        assert_eq!(strip_ansi(&rendered), "│ fn main() {}\n\n│ plain");
+        assert!(rendered.contains("\x1b[38;2;"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Scrollback minimap
+//!
+//! A thin gutter beside the output that marks where command blocks start,
+//! where errors were printed, and where search matches are. Positions are
+//! proportional to the line index, so a click maps straight back to a
+//! scroll position.
+
+use std::collections::HashSet;
+
+use super::buffer::{LineType, OutputBuffer};
+
+/// What a minimap marker points at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarkerKind {
+    /// Start of a command block
+    Block,
+    /// Line printed as an error
+    Error,
+    /// Search or vi match
+    Match,
+    /// The match currently selected
+    CurrentMatch,
+}
+
+/// A marker at a buffer line
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Marker {
+    pub line: usize,
+    pub kind: MarkerKind,
+}
+
+/// Collect markers for a buffer. `matches` are buffer line indices;
+/// `current_match` is the line of the selected match, if any.
+pub fn collect_markers(
+    buffer: &OutputBuffer,
+    matches: &[usize],
+    current_match: Option<usize>,
+) -> Vec<Marker> {
+    let mut markers: Vec<Marker> = buffer
+        .blocks()
+        .iter()
+        .map(|block| Marker {
+            line: block.start_line,
+            kind: MarkerKind::Block,
+        })
+        .collect();
+
+    markers.extend(
+        buffer
+            .output_lines()
+            .enumerate()
+            .filter(|(_, line)| line.line_type == LineType::Error)
+            .map(|(line, _)| Marker {
+                line,
+                kind: MarkerKind::Error,
+            }),
+    );
+
+    markers.extend(matches.iter().map(|&line| Marker {
+        line,
+        kind: MarkerKind::Match,
+    }));
+
+    // Drawn last so it sits on top
+    if let Some(line) = current_match {
+        markers.push(Marker {
+            line,
+            kind: MarkerKind::CurrentMatch,
+        });
+    }
+
+    markers
+}
+
+/// Fraction (0.0 - 1.0) of the scrollback at which a line sits
+pub fn line_fraction(line: usize, total_lines: usize) -> f32 {
+    if total_lines <= 1 {
+        return 0.0;
+    }
+    (line as f32 / (total_lines - 1) as f32).clamp(0.0, 1.0)
+}
+
+/// Drop markers that would land on the same pixel row as an earlier marker
+/// of the same kind - long error logs would otherwise paint thousands of rects.
+pub fn dedupe_rows(markers: &[Marker], total_lines: usize, height: f32) -> Vec<(f32, MarkerKind)> {
+    let mut seen = HashSet::new();
+    markers
+        .iter()
+        .filter_map(|marker| {
+            let y = line_fraction(marker.line, total_lines) * height;
+            seen.insert((y as i32, marker.kind))
+                .then_some((y, marker.kind))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_markers() {
+        let mut buffer = OutputBuffer::new(100);
+        buffer.start_block("cargo build");
+        buffer.push_line("$ cargo build");
+        buffer.push_error("error: oops");
+        buffer.end_block(false);
+
+        let markers = collect_markers(&buffer, &[1], Some(1));
+        assert!(markers.contains(&Marker {
+            line: 0,
+            kind: MarkerKind::Block
+        }));
+        assert!(markers.contains(&Marker {
+            line: 1,
+            kind: MarkerKind::Error
+        }));
+        assert_eq!(markers.last().unwrap().kind, MarkerKind::CurrentMatch);
+    }
+
+    #[test]
+    fn test_dedupe_rows() {
+        let markers: Vec<Marker> = (0..1000)
+            .map(|line| Marker {
+                line,
+                kind: MarkerKind::Error,
+            })
+            .collect();
+        let rows = dedupe_rows(&markers, 1000, 100.0);
+        assert!(rows.len() <= 101);
+        assert_eq!(line_fraction(999, 1000), 1.0);
+    }
+}
@@ -3,15 +3,26 @@
 //! Handles terminal state, output buffering, input, and rendering.
 
 pub mod ansi;
+pub mod audit;
 pub mod autocomplete;
 pub mod buffer;
+pub mod digest;
+pub mod export;
+pub mod filter;
 pub mod format;
 pub mod fuzzy;
 pub mod hints;
 pub mod history;
 pub mod img;
 pub mod input;
+pub mod keybindings;
+pub mod layout;
+pub mod markdown;
+pub mod minimap;
+pub mod output_view;
+pub mod pager;
 pub mod palette;
+pub mod preview;
 pub mod project;
 pub mod render;
 pub mod session;
@@ -19,4 +30,6 @@ pub mod smart_history;
 pub mod split;
 pub mod state;
 pub mod syntax;
+pub mod tips;
+pub mod usage;
 pub mod vi_mode;
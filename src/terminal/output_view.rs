@@ -0,0 +1,366 @@
+//! Virtualized output rendering
+//!
+//! A pane can hold hundreds of thousands of lines, but only the few dozen
+//! in view need laying out each frame. `OutputView` remembers every line's
+//! height between frames - estimated from its length until it has been
+//! drawn, then measured - so the scroll area can place the visible lines
+//! without touching the others, and keeps the galleys of the lines in view
+//! so that an unchanged line is not laid out again.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+
+use eframe::egui;
+
+use super::ansi;
+use super::buffer::OutputBuffer;
+
+/// Something `OutputView::show` asks to have drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Row {
+    /// The header (collapse and copy buttons, duration) of the block that
+    /// starts at line `index`, drawn just above it
+    Header(usize),
+    /// The one-line summary standing in for the collapsed block that
+    /// starts at line `index`
+    Collapsed(usize),
+    /// Output line `index`
+    Line(usize),
+    /// Whatever follows the last line, such as a job's spinner
+    End,
+}
+
+/// Layout kept for one line while its `OutputLine::id` stays the same
+struct Entry {
+    id: u64,
+    /// Including item spacing
+    height: f32,
+    /// Laid out while the line is in view, with the wrap width and color
+    galley: Option<(f32, egui::Color32, Arc<egui::Galley>)>,
+}
+
+/// Row heights and galleys of a pane's output, kept between frames
+#[derive(Default)]
+pub struct OutputView {
+    entries: VecDeque<Entry>,
+    /// Font and width the heights were worked out for
+    font: Option<egui::FontId>,
+    width: f32,
+    /// Top of each line this frame, then the bottom of the last
+    tops: Vec<f32>,
+    /// Lines that start a block this frame, and whether it is collapsed
+    headers: Vec<(usize, bool)>,
+    /// Lines hidden in collapsed blocks this frame
+    collapsed: Vec<Range<usize>>,
+    header_height: f32,
+    summary_height: f32,
+    end_height: f32,
+    /// Lines drawn last frame, whose galleys are kept
+    shown: Range<usize>,
+}
+
+impl OutputView {
+    /// Show `buffer` in `scroll_area`, `margin` narrower than the space
+    /// given, calling `add` for just the rows in view. Lines where `mask`
+    /// is false are hidden, each block gets a header, and a collapsed block
+    /// shows only its summary.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        scroll_area: egui::ScrollArea,
+        buffer: &OutputBuffer,
+        mask: Option<&[bool]>,
+        margin: f32,
+        mut add: impl FnMut(&mut egui::Ui, Row, &mut Self),
+    ) -> egui::scroll_area::ScrollAreaOutput<()> {
+        scroll_area.show_viewport(ui, |ui, viewport| {
+            ui.set_max_width(ui.available_width() - margin);
+            self.sync(ui, buffer);
+            self.place(buffer, mask);
+
+            let lines = self.entries.len();
+            let bottom = self.tops[lines];
+            ui.set_height(bottom + self.end_height);
+            let range = self.visible(viewport);
+            let top = ui.max_rect().top() + self.tops[range.start];
+            let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), top..=f32::INFINITY);
+            ui.scope_builder(egui::UiBuilder::new().max_rect(rect), |ui| {
+                ui.skip_ahead_auto_ids(range.start);
+                for index in range.clone() {
+                    if let Ok(header) = self.headers.binary_search_by_key(&index, |h| h.0) {
+                        let top = ui.cursor().top();
+                        if self.headers[header].1 {
+                            add(ui, Row::Collapsed(index), self);
+                            self.summary_height = ui.cursor().top() - top;
+                        } else {
+                            add(ui, Row::Header(index), self);
+                            self.header_height = ui.cursor().top() - top;
+                        }
+                    }
+                    if self.is_hidden(index, mask) {
+                        continue;
+                    }
+                    let top = ui.cursor().top();
+                    add(ui, Row::Line(index), self);
+                    self.entries[index].height = ui.cursor().top() - top;
+                }
+                if range.end == lines {
+                    let top = ui.cursor().top();
+                    add(ui, Row::End, self);
+                    self.end_height = ui.cursor().top() - top;
+                }
+            });
+
+            // Galleys are only kept for the lines in view
+            for index in self.shown.clone() {
+                if !range.contains(&index) {
+                    if let Some(entry) = self.entries.get_mut(index) {
+                        entry.galley = None;
+                    }
+                }
+            }
+            self.shown = range;
+        })
+    }
+
+    /// Where line `index` starts, as a scroll offset (as of the last frame)
+    pub fn line_top(&self, index: usize) -> Option<f32> {
+        self.tops.get(index).copied()
+    }
+
+    /// Line `index` laid out as plain `text` in `color`, wrapped to the
+    /// space left in `ui`; kept until the line changes or leaves the view
+    pub fn galley(
+        &mut self,
+        ui: &egui::Ui,
+        index: usize,
+        text: &str,
+        color: egui::Color32,
+    ) -> Arc<egui::Galley> {
+        let width = ui.available_width();
+        if let Some((w, c, galley)) = self.entries.get(index).and_then(|e| e.galley.as_ref()) {
+            if *w == width && *c == color {
+                return galley.clone();
+            }
+        }
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let galley = ui.fonts(|fonts| fonts.layout(text.to_string(), font, color, width));
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.galley = Some((width, color, galley.clone()));
+        }
+        galley
+    }
+
+    /// Bring the entries in line with `buffer`, estimating new lines
+    fn sync(&mut self, ui: &egui::Ui, buffer: &OutputBuffer) {
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let width = ui.available_width();
+        if self.font.as_ref() != Some(&font) || (self.width - width).abs() >= 1.0 {
+            self.entries.clear();
+            self.shown = 0..0;
+            self.width = width;
+        }
+
+        // Lines that left the front of the scrollback
+        let first = buffer.get_line(0).map(|line| line.id);
+        let gone = self
+            .entries
+            .iter()
+            .take_while(|entry| first.is_none_or(|first| entry.id < first))
+            .count();
+        self.entries.drain(..gone);
+        self.shown = self.shown.start.saturating_sub(gone)..self.shown.end.saturating_sub(gone);
+        // ...and lines changed or removed at the end
+        let kept = self
+            .entries
+            .iter()
+            .zip(buffer.output_lines())
+            .take_while(|(entry, line)| entry.id == line.id)
+            .count();
+        self.entries.truncate(kept);
+
+        let (row_height, char_width) =
+            ui.fonts(|fonts| (fonts.row_height(&font), fonts.glyph_width(&font, 'M')));
+        let spacing = ui.spacing().item_spacing.y;
+        let per_row = ((width / char_width.max(1.0)) as usize).max(1);
+        for line in buffer.output_lines().skip(kept) {
+            // Styled lines are drawn unwrapped; plain ones wrap
+            let rows = if ansi::has_ansi(&line.text) || !line.urls.is_empty() {
+                1
+            } else {
+                line.text.chars().count().div_ceil(per_row).max(1)
+            };
+            self.entries.push_back(Entry {
+                id: line.id,
+                height: rows as f32 * row_height + spacing,
+                galley: None,
+            });
+        }
+        if self.font.is_none() {
+            self.header_height = ui.spacing().interact_size.y + spacing;
+            self.summary_height = row_height + spacing;
+            self.end_height = row_height + spacing;
+        }
+        self.font = Some(font);
+    }
+
+    /// Work out where each line goes this frame
+    fn place(&mut self, buffer: &OutputBuffer, mask: Option<&[bool]>) {
+        self.tops.clear();
+        self.headers.clear();
+        self.collapsed.clear();
+        // Blocks with lines, in order; a header goes above a block's first
+        // line when that is shown, a summary whatever the mask says
+        let mut blocks = buffer
+            .blocks()
+            .iter()
+            .filter(|block| block.end_line > block.start_line)
+            .peekable();
+        let mut top = 0.0;
+        for (index, entry) in self.entries.iter().enumerate() {
+            self.tops.push(top);
+            while blocks.next_if(|block| block.start_line < index).is_some() {}
+            if let Some(block) = blocks.next_if(|block| block.start_line == index) {
+                if block.collapsed {
+                    self.headers.push((index, true));
+                    self.collapsed.push(index..block.end_line);
+                    top += self.summary_height;
+                } else if mask_shows(mask, index) {
+                    self.headers.push((index, false));
+                    top += self.header_height;
+                }
+            }
+            if !self.is_hidden(index, mask) {
+                top += entry.height;
+            }
+        }
+        self.tops.push(top);
+    }
+
+    /// Whether line `index` is filtered out or in a collapsed block
+    fn is_hidden(&self, index: usize, mask: Option<&[bool]>) -> bool {
+        !mask_shows(mask, index) || self.collapsed.iter().any(|range| range.contains(&index))
+    }
+
+    /// Lines overlapping `viewport`
+    fn visible(&self, viewport: egui::Rect) -> Range<usize> {
+        let lines = self.entries.len();
+        let start = self
+            .tops
+            .partition_point(|top| *top < viewport.min.y)
+            .saturating_sub(1)
+            .min(lines);
+        let end = self
+            .tops
+            .partition_point(|top| *top < viewport.max.y)
+            .min(lines);
+        start..end.max(start)
+    }
+}
+
+fn mask_shows(mask: Option<&[bool]>, index: usize) -> bool {
+    mask.is_none_or(|mask| mask.get(index).copied().unwrap_or(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Show `buffer` in a 400x200 window, returning the rows asked for
+    fn shown(view: &mut OutputView, buffer: &OutputBuffer, offset: f32) -> Vec<Row> {
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(400.0, 200.0),
+            )),
+            ..Default::default()
+        };
+        let mut rows = Vec::new();
+        // The first pass loads the fonts
+        for _ in 0..2 {
+            rows.clear();
+            let _ = ctx.run(input.clone(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let scroll_area = egui::ScrollArea::vertical().vertical_scroll_offset(offset);
+                    view.show(ui, scroll_area, buffer, None, 0.0, |ui, row, view| {
+                        rows.push(row);
+                        match row {
+                            Row::Line(index) => {
+                                let text = &buffer.get_line(index).unwrap().text;
+                                ui.label(view.galley(ui, index, text, egui::Color32::WHITE));
+                            }
+                            Row::Collapsed(_) => {
+                                ui.label("▸ summary");
+                            }
+                            Row::Header(_) | Row::End => {}
+                        }
+                    });
+                });
+            });
+        }
+        rows
+    }
+
+    #[test]
+    fn test_only_visible_rows_are_drawn() {
+        let mut buffer = OutputBuffer::new(10_000);
+        buffer.start_block("find /");
+        for i in 0..5001 {
+            buffer.push_line(&format!("/usr/lib/file{}", i));
+        }
+        let mut view = OutputView::default();
+
+        let rows = shown(&mut view, &buffer, 0.0);
+        assert_eq!(rows[..2], [Row::Header(0), Row::Line(0)]);
+        assert!(rows.len() < 40, "{} rows drawn", rows.len());
+        assert!(!rows.contains(&Row::End));
+
+        // Far down, only lines around the offset
+        let top = view.line_top(3000).unwrap();
+        let rows = shown(&mut view, &buffer, top);
+        assert!(rows.contains(&Row::Line(3000)));
+        assert!(!rows.contains(&Row::Header(0)));
+        assert!(rows.len() < 40);
+        assert_eq!(
+            view.entries.iter().filter(|e| e.galley.is_some()).count(),
+            rows.len()
+        );
+
+        // New lines are picked up, old ones dropped with the scrollback
+        buffer.set_max_lines(100);
+        buffer.push_line("last");
+        shown(&mut view, &buffer, 0.0);
+        assert_eq!(view.entries.len(), 100);
+        let top = view.line_top(95).unwrap();
+        let rows = shown(&mut view, &buffer, top);
+        assert_eq!(rows.last(), Some(&Row::End));
+        assert!(rows.contains(&Row::Line(99)));
+    }
+
+    #[test]
+    fn test_collapsed_block_shows_summary() {
+        let mut buffer = OutputBuffer::new(100);
+        for command in ["ls", "pwd"] {
+            buffer.start_block(command);
+            buffer.push_line(&format!("$ {}", command));
+            buffer.push_line("out");
+            buffer.end_block(true);
+        }
+        buffer.collapse_previous();
+        let mut view = OutputView::default();
+
+        let rows = shown(&mut view, &buffer, 0.0);
+        assert_eq!(
+            rows,
+            [
+                Row::Collapsed(0),
+                Row::Header(2),
+                Row::Line(2),
+                Row::Line(3),
+                Row::End
+            ]
+        );
+    }
+}
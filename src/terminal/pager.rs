@@ -0,0 +1,301 @@
+//! Pager
+//!
+//! A `less`-style modal view of long output: opened by `less`/`more`, or
+//! for any built-in's output taller than its pane (`[terminal] pager`).
+//! Keys arrive as the same characters vi mode reads (see
+//! [`vi_mode::key_char`](super::vi_mode::key_char)).
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use super::ansi::strip_ansi;
+
+/// Pager controller
+#[derive(Default)]
+pub struct Pager {
+    /// Whether the pager is showing
+    pub active: bool,
+    /// What is being paged (a file name or command)
+    pub title: String,
+    /// Lines, with their ANSI colors
+    lines: Vec<String>,
+    /// First line shown
+    top: usize,
+    /// Lines that fit in the view, set by the renderer
+    height: usize,
+    /// Search being typed after `/`
+    search: Option<String>,
+    /// Last search, highlighted and repeated by n/N
+    pattern: Option<Regex>,
+    /// "Pattern not found" and the like, until the next key
+    pub message: Option<String>,
+    /// Count prefix (e.g. 5j = down 5 lines)
+    count: Option<usize>,
+}
+
+impl Pager {
+    /// Create a closed pager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `text` from its first line
+    pub fn open(&mut self, title: &str, text: &str) {
+        *self = Self {
+            active: true,
+            title: title.to_string(),
+            lines: text.lines().map(String::from).collect(),
+            height: self.height,
+            ..Self::default()
+        };
+    }
+
+    /// Close the pager, dropping its text
+    pub fn close(&mut self) {
+        *self = Self {
+            height: self.height,
+            ..Self::default()
+        };
+    }
+
+    /// Lines that fit in the view
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height.max(1);
+        self.top = self.top.min(self.max_top());
+    }
+
+    /// The lines in view, with their indices
+    pub fn visible(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(self.top)
+            .take(self.height.max(1))
+            .map(|(index, line)| (index, line.as_str()))
+    }
+
+    /// Lowest first line that still fills the view
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.height.max(1))
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.max_top());
+    }
+
+    /// Scroll down (or up, when negative) by `lines` (the mouse wheel)
+    pub fn scroll(&mut self, lines: isize) {
+        self.scroll_to(self.top.saturating_add_signed(lines));
+    }
+
+    /// Handle a key press (as vi mode reads it); Escape or q closes the pager
+    #[allow(clippy::collapsible_match)]
+    pub fn handle_key(&mut self, key: char) {
+        if let Some(query) = &mut self.search {
+            match key {
+                '\x1b' => self.search = None,
+                '\r' | '\n' => {
+                    let query = self.search.take().unwrap_or_default();
+                    self.find(&query);
+                }
+                '\x7f' | '\x08' => {
+                    if query.pop().is_none() {
+                        self.search = None;
+                    }
+                }
+                c if c.is_ascii_graphic() || c == ' ' => query.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        self.message = None;
+        if key.is_ascii_digit() && (key != '0' || self.count.is_some()) {
+            let digit = key.to_digit(10).unwrap_or(0) as usize;
+            self.count = Some(self.count.unwrap_or(0).saturating_mul(10) + digit);
+            return;
+        }
+        let count = self.count.take();
+        let times = count.unwrap_or(1);
+        let page = self.height.max(1);
+
+        match key {
+            'q' | 'Q' | '\x1b' => self.close(),
+            'j' | 'e' | '\n' | '\x0e' => self.scroll_to(self.top + times),
+            'k' | 'y' | '\x10' => self.scroll_to(self.top.saturating_sub(times)),
+            'f' | ' ' | '\x06' => self.scroll_to(self.top + page * times),
+            'b' | '\x02' => self.scroll_to(self.top.saturating_sub(page * times)),
+            'd' | '\x04' => self.scroll_to(self.top + page.div_ceil(2) * times),
+            'u' | '\x15' => self.scroll_to(self.top.saturating_sub(page.div_ceil(2) * times)),
+            // g and G take a line number, like less
+            'g' => self.scroll_to(count.map_or(0, |line| line.saturating_sub(1))),
+            'G' => self.scroll_to(count.map_or(usize::MAX, |line| line.saturating_sub(1))),
+            '/' => self.search = Some(String::new()),
+            'n' => self.next_match(true),
+            'N' => self.next_match(false),
+            _ => {}
+        }
+    }
+
+    /// Search for `query` (case-insensitive unless it has an uppercase
+    /// letter) from the line after the top one
+    fn find(&mut self, query: &str) {
+        if query.is_empty() {
+            // A bare `/` repeats the last search
+            self.next_match(true);
+            return;
+        }
+        match RegexBuilder::new(query)
+            .case_insensitive(!query.chars().any(char::is_uppercase))
+            .build()
+        {
+            Ok(pattern) => {
+                self.pattern = Some(pattern);
+                self.next_match(true);
+            }
+            Err(_) => self.message = Some(format!("Invalid pattern: {}", query)),
+        }
+    }
+
+    /// Bring the next (or previous) line matching the search to the top
+    fn next_match(&mut self, forward: bool) {
+        let Some(pattern) = &self.pattern else {
+            self.message = Some("No previous search".to_string());
+            return;
+        };
+        let matches = |index: &usize| pattern.is_match(&strip_ansi(&self.lines[*index]));
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(matches)
+        } else {
+            (0..self.top).rev().find(matches)
+        };
+        match found {
+            Some(line) => self.scroll_to(line),
+            None => self.message = Some("Pattern not found".to_string()),
+        }
+    }
+
+    /// Byte ranges of `text` (without colors) that the last search matches
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        self.pattern.as_ref().map_or_else(Vec::new, |pattern| {
+            pattern
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect()
+        })
+    }
+
+    /// How far through the text the bottom of the view is, like less
+    pub fn percent(&self) -> usize {
+        if self.lines.is_empty() {
+            return 100;
+        }
+        let bottom = (self.top + self.height.max(1)).min(self.lines.len());
+        bottom * 100 / self.lines.len()
+    }
+
+    /// Status line: the search being typed, a message, or the position
+    pub fn status_text(&self) -> String {
+        if let Some(query) = &self.search {
+            return format!("/{}", query);
+        }
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+        let bottom = (self.top + self.height.max(1)).min(self.lines.len());
+        let end = if bottom == self.lines.len() {
+            " (END)"
+        } else {
+            ""
+        };
+        format!(
+            "{}  lines {}-{} of {}  {}%{}",
+            self.title,
+            (self.top + 1).min(bottom),
+            bottom,
+            self.lines.len(),
+            self.percent(),
+            end
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pager: &mut Pager, keys: &str) {
+        for key in keys.chars() {
+            pager.handle_key(key);
+        }
+    }
+
+    fn numbered(count: usize) -> String {
+        (1..=count)
+            .map(|n| format!("line {}", n))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_pager_navigation() {
+        let mut pager = Pager::new();
+        pager.set_height(10);
+        pager.open("numbers", &numbered(100));
+        assert_eq!(pager.status_text(), "numbers  lines 1-10 of 100  10%");
+
+        keys(&mut pager, "jjj");
+        assert_eq!(pager.visible().next(), Some((3, "line 4")));
+        keys(&mut pager, "k ");
+        assert_eq!(pager.visible().next(), Some((12, "line 13")));
+        keys(&mut pager, "G");
+        assert_eq!(pager.percent(), 100);
+        assert!(pager.status_text().ends_with("(END)"));
+        keys(&mut pager, "g");
+        assert_eq!(pager.visible().next(), Some((0, "line 1")));
+        keys(&mut pager, "50g");
+        assert_eq!(pager.visible().next(), Some((49, "line 50")));
+        keys(&mut pager, "\x04");
+        assert_eq!(pager.visible().next(), Some((54, "line 55")));
+
+        // Short text never scrolls
+        pager.open("short", "one\ntwo");
+        keys(&mut pager, "jjG");
+        assert_eq!(pager.visible().count(), 2);
+        assert_eq!(pager.percent(), 100);
+
+        keys(&mut pager, "q");
+        assert!(!pager.active);
+    }
+
+    #[test]
+    fn test_pager_search() {
+        let mut pager = Pager::new();
+        pager.set_height(5);
+        pager.open(
+            "log",
+            "start\nok\nERROR one\nok\nok\nok\nok\nok\nerror two\nok\nok\nok\nok",
+        );
+
+        keys(&mut pager, "/err");
+        assert_eq!(pager.status_text(), "/err");
+        keys(&mut pager, "\n");
+        assert_eq!(pager.visible().next(), Some((2, "ERROR one")));
+        assert_eq!(pager.highlights("ERROR one, error two"), [0..3, 11..14]);
+        keys(&mut pager, "n");
+        assert_eq!(pager.visible().next(), Some((8, "error two")));
+        keys(&mut pager, "n");
+        assert_eq!(pager.status_text(), "Pattern not found");
+        keys(&mut pager, "N");
+        assert_eq!(pager.visible().next(), Some((2, "ERROR one")));
+
+        // Uppercase searches match case; Escape cancels a search being typed
+        keys(&mut pager, "g/Error\n");
+        assert_eq!(pager.status_text(), "Pattern not found");
+        keys(&mut pager, "/x\x1b");
+        assert!(pager.status_text().starts_with("log  lines 1-5"));
+        assert!(pager.active);
+    }
+}
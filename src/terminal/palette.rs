@@ -1,10 +1,35 @@
 //! Command Palette
 //!
 //! Ctrl+P quick access to all commands, similar to VS Code
+//!
+//! Besides the built-in commands and actions, `[[palette]]` tables in
+//! config.toml add entries of their own (listed under "Custom"), saved
+//! workspaces can be opened from it, and the entries picked most recently
+//! are listed first.
 
 #![allow(dead_code)]
 
+use std::path::{Path, PathBuf};
+
+use egui::InputState;
+
+use super::keybindings;
 use crate::commands::registry::CommandRegistry;
+use crate::config::keymap::Chord;
+use crate::config::settings::PaletteCommand;
+use crate::terminal::session::{SessionManager, Workspace};
+
+/// Category of the entries defined in config.toml
+pub const CUSTOM: &str = "Custom";
+
+/// Category of saved workspaces
+pub const WORKSPACES: &str = "Workspaces";
+
+/// Section heading of recently picked entries
+pub const RECENT: &str = "Recent";
+
+/// How many recently picked entries are remembered
+const RECENT_MAX: usize = 8;
 
 /// A command palette entry
 #[derive(Clone)]
@@ -33,19 +58,112 @@ pub struct CommandPalette {
     all_entries: Vec<PaletteEntry>,
     /// Selected index
     pub selected: usize,
+    /// Entries from config.toml
+    custom: Vec<CustomEntry>,
+    /// Names of the entries picked most recently, newest first
+    recent: Vec<String>,
+    /// Where each section of `entries` starts (only while not searching)
+    sections: Vec<(usize, String)>,
+}
+
+/// A `[[palette]]` entry from config.toml
+struct CustomEntry {
+    name: String,
+    /// Command line, before `{input}` and `{cwd}` are filled in
+    command: String,
+    chord: Option<Chord>,
 }
 
 impl CommandPalette {
     /// Create a new command palette
     pub fn new() -> Self {
-        let all_entries = Self::build_entries();
-        Self {
+        let mut palette = Self {
             is_open: false,
             query: String::new(),
-            entries: all_entries.clone(),
-            all_entries,
+            entries: Vec::new(),
+            all_entries: Self::build_entries(),
             selected: 0,
+            custom: Vec::new(),
+            recent: load_recent(),
+            sections: Vec::new(),
+        };
+        palette.update_search();
+        palette
+    }
+
+    /// Replace the entries defined in config.toml, returning a warning for
+    /// each one that was skipped or whose keys could not be read
+    pub fn set_custom(&mut self, commands: &[PaletteCommand]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.all_entries.retain(|e| e.category != CUSTOM);
+        self.custom.clear();
+        for command in commands {
+            let name = command.name.trim();
+            if name.is_empty() || command.command.trim().is_empty() {
+                warnings.push("[[palette]] entries need a name and a command".to_string());
+                continue;
+            }
+            let chord = match command.keys.as_deref().map(str::parse::<Chord>) {
+                Some(Ok(chord)) => Some(chord),
+                Some(Err(e)) => {
+                    warnings.push(format!("palette entry '{}': {}", name, e));
+                    None
+                }
+                None => None,
+            };
+            self.all_entries.push(PaletteEntry {
+                name: name.to_string(),
+                description: if command.description.is_empty() {
+                    command.command.clone()
+                } else {
+                    command.description.clone()
+                },
+                category: CUSTOM.to_string(),
+                shortcut: chord.as_ref().map(Chord::to_string),
+                score: 0,
+            });
+            self.custom.push(CustomEntry {
+                name: name.to_string(),
+                command: command.command.clone(),
+                chord,
+            });
         }
+        self.update_search();
+        warnings
+    }
+
+    /// Command line of a custom entry, before placeholders are filled in
+    pub fn custom_command(&self, name: &str) -> Option<&str> {
+        self.custom
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.command.as_str())
+    }
+
+    /// Name of the custom entry whose shortcut was just pressed
+    pub fn custom_pressed(&self, input: &InputState) -> Option<String> {
+        self.custom
+            .iter()
+            .find(|c| c.chord.as_ref().is_some_and(|chord| chord.pressed(input)))
+            .map(|c| c.name.clone())
+    }
+
+    /// Remember that an entry was picked, so it is listed first next time
+    pub fn record_use(&mut self, name: &str) {
+        self.recent.retain(|n| n != name);
+        self.recent.insert(0, name.to_string());
+        self.recent.truncate(RECENT_MAX);
+        if let Err(e) = save_recent(&self.recent) {
+            eprintln!("Failed to save recent palette entries: {}", e);
+        }
+    }
+
+    /// Section heading to draw above the entry at `index`, if one starts there
+    pub fn section_at(&self, index: usize) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|(start, _)| *start == index)
+            .map(|(_, name)| name.as_str())
     }
 
     /// Build all palette entries from the command registry
@@ -71,28 +189,63 @@ impl CommandPalette {
             name: "New Tab".to_string(),
             description: "Open a new terminal tab".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+T".to_string()),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "Close Tab".to_string(),
             description: "Close current terminal tab".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+W".to_string()),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "Search".to_string(),
             description: "Search in terminal output".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+F".to_string()),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Filter Output".to_string(),
+            description: "Show only errors, one block, or matching lines".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Collapse Previous Blocks".to_string(),
+            description: "Fold every command's output but the last into one line".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Expand All Blocks".to_string(),
+            description: "Show the output of every collapsed command again".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Copy Block as HTML".to_string(),
+            description: "Copy the last block with its colors for docs and chat".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Copy Block with ANSI".to_string(),
+            description: "Copy the last block with ANSI color codes".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "Clear".to_string(),
             description: "Clear the terminal screen".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+L".to_string()),
+            shortcut: None,
             score: 0,
         });
 
@@ -145,42 +298,176 @@ impl CommandPalette {
             name: "Split Horizontal".to_string(),
             description: "Split current pane horizontally".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+Shift+D".to_string()),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Run in New Pane".to_string(),
+            description: "Run the typed command in a new split pane (cmd |> pane)".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "Split Vertical".to_string(),
             description: "Split current pane vertically".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+Shift+E".to_string()),
+            shortcut: None,
             score: 0,
         });
+        for (name, description, _) in crate::terminal::layout::PRESETS {
+            entries.push(PaletteEntry {
+                name: format!("layout {}", name),
+                description: format!("Pane layout: {}", description),
+                category: "Actions".to_string(),
+                shortcut: None,
+                score: 0,
+            });
+        }
         entries.push(PaletteEntry {
             name: "Vi Mode".to_string(),
             description: "Toggle vim-style navigation mode".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+Shift+M".to_string()),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "Hints Mode".to_string(),
             description: "Extract URLs and paths from output".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+Shift+H".to_string()),
+            shortcut: None,
             score: 0,
         });
         entries.push(PaletteEntry {
             name: "History Search".to_string(),
             description: "Fuzzy search command history".to_string(),
             category: "Actions".to_string(),
-            shortcut: Some("Ctrl+R".to_string()),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Recent Files".to_string(),
+            description: "Fuzzy search files used by recent commands".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Jump to Directory".to_string(),
+            description: "Fuzzy search the directory stack and recent directories".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Git Panel".to_string(),
+            description: "Staged, unstaged, and untracked files with their diffs".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Pick Search Result".to_string(),
+            description: "Fuzzy search grep/find hits in the last output".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "New Window".to_string(),
+            description: "Open another Zaxiom window".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Copy Input".to_string(),
+            description: "Copy the command being typed".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Paste".to_string(),
+            description: "Paste into the input (newlines removed)".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Paste with Newlines".to_string(),
+            description: "Paste into the input as-is".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Zoom In".to_string(),
+            description: "Make the focused pane's text larger".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Zoom Out".to_string(),
+            description: "Make the focused pane's text smaller".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Reset Zoom".to_string(),
+            description: "Focused pane's text back to the default size".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Ask AI".to_string(),
+            description: "Start an AI question at the prompt".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Quit".to_string(),
+            description: "Close Zaxiom".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Restore Session".to_string(),
+            description: "Reopen the tabs of the last or a saved session".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
+            score: 0,
+        });
+        entries.push(PaletteEntry {
+            name: "Keyboard Shortcuts".to_string(),
+            description: "Show the shortcuts available right now".to_string(),
+            category: "Actions".to_string(),
+            shortcut: None,
             score: 0,
         });
 
-        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Self::set_shortcuts(&mut entries);
         entries
     }
 
+    /// Action shortcuts come from the keybinding registry
+    fn set_shortcuts(entries: &mut [PaletteEntry]) {
+        for entry in entries.iter_mut().filter(|e| e.category == "Actions") {
+            entry.shortcut = keybindings::shortcut_for(&entry.name);
+        }
+    }
+
+    /// Show the current keys after shortcuts were rebound
+    pub fn refresh_shortcuts(&mut self) {
+        Self::set_shortcuts(&mut self.all_entries);
+        Self::set_shortcuts(&mut self.entries);
+    }
+
     /// Get keyboard shortcut for a command
     fn get_shortcut(name: &str) -> Option<String> {
         match name {
@@ -194,23 +481,33 @@ impl CommandPalette {
     fn categorize_command(name: &str) -> String {
         match name {
             "ls" | "cd" | "pwd" | "tree" | "clear" | "help" => "Navigation",
-            "cat" | "touch" | "rm" | "mkdir" | "cp" | "mv" | "ln" | "stat" | "file" | "chmod"
-            | "readlink" | "mktemp" | "nano" | "vim" | "vi" | "edit" => "Files",
-            "echo" | "head" | "tail" | "wc" | "sort" | "uniq" | "grep" | "find" | "cut"
+            "cat" | "less" | "more" | "md" | "touch" | "rm" | "mkdir" | "cp" | "mv" | "ln"
+            | "stat" | "file" | "chmod" | "readlink" | "mktemp" | "nano" | "vim" | "vi"
+            | "edit" | "open" | "start" => "Files",
+            "echo" | "head" | "tail" | "wc" | "sort" | "uniq" | "grep" | "find" | "fd" | "cut"
             | "paste" | "diff" | "tr" | "sed" | "awk" | "rev" | "nl" | "printf" | "xargs"
-            | "column" | "strings" | "split" | "join" | "comm" => "Text",
+            | "column" | "strings" | "split" | "join" | "comm" | "str" | "jq" => "Text",
             "exit" | "which" | "du" | "df" | "ps" | "kill" | "whoami" | "hostname" | "uname"
             | "uptime" | "free" | "date" | "cal" | "id" | "neofetch" | "printenv" | "lscpu"
-            | "history" | "test" | "man" | "theme" => "System",
-            "curl" | "wget" | "ping" | "netstat" | "traceroute" | "nslookup" | "host"
-            | "ifconfig" => "Network",
+            | "history" | "fc" | "audit" | "test" | "man" | "theme" | "color" | "layout"
+            | "pane" | "zoom" | "keys" | "workspace" | "session" | "tips" | "timestamps"
+            | "buffer" | "paths" | "stats" | "digest" => "System",
+            "curl" | "wget" | "scrape" | "ping" | "lanscan" | "speedtest" | "netstat"
+            | "traceroute" | "nslookup" | "whois" | "tlscert" | "mock" | "host" | "ifconfig"
+            | "ssh" => "Network",
             "md5sum" | "sha1sum" | "sha224sum" | "sha256sum" | "sha384sum" | "sha512sum"
-            | "blake3sum" | "b3sum" | "crc32" | "base64" | "xxd" => "Hash",
+            | "blake3sum" | "b3sum" | "crc32" | "base64" | "xxd" | "hex" | "unhex"
+            | "urlencode" | "urldecode" => "Hash",
             "tar" | "zip" | "unzip" | "gzip" | "gunzip" => "Compress",
-            "alias" | "env" | "export" | "sleep" | "seq" | "yes" | "true" | "false" | "expr"
-            | "bc" | "tee" | "timeout" | "type" | "command" | "pushd" | "popd" | "dirs" => "Shell",
+            "alias" | "unalias" | "env" | "export" | "sleep" | "seq" | "yes" | "true" | "false"
+            | "expr" | "bc" | "tee" | "timeout" | "type" | "command" | "pushd" | "popd"
+            | "dirs" | "source" | "uuid" | "random" | "lorem" | "watch" | "jobs" | "fg" | "bg" => {
+                "Shell"
+            }
             "fortune" | "cowsay" | "coffee" | "matrix" | "pet" => "Fun",
-            "ai" | "ollama" => "AI",
+            "ai" | "ollama" | "summarize" => "AI",
+            "gs" | "gd" | "gl" | "gg" | "ga" | "gc" | "gp" | "gpl" | "gst" | "gtag" | "gremote"
+            | "gconflicts" | "gpr" | "gopen" => "Git",
             _ => "Other",
         }
         .to_string()
@@ -218,11 +515,10 @@ impl CommandPalette {
 
     /// Toggle the palette
     pub fn toggle(&mut self) {
-        self.is_open = !self.is_open;
         if self.is_open {
-            self.query.clear();
-            self.entries = self.all_entries.clone();
-            self.selected = 0;
+            self.close();
+        } else {
+            self.open();
         }
     }
 
@@ -230,8 +526,23 @@ impl CommandPalette {
     pub fn open(&mut self) {
         self.is_open = true;
         self.query.clear();
-        self.entries = self.all_entries.clone();
-        self.selected = 0;
+        self.set_workspaces(&SessionManager::new().list_workspaces());
+    }
+
+    /// List saved workspaces as `workspace load <name>` entries
+    fn set_workspaces(&mut self, workspaces: &[Workspace]) {
+        self.all_entries.retain(|e| e.category != WORKSPACES);
+        for workspace in workspaces {
+            let titles: Vec<&str> = workspace.tabs.iter().map(|t| t.title.as_str()).collect();
+            self.all_entries.push(PaletteEntry {
+                name: format!("workspace load {}", workspace.name),
+                description: format!("Open {}", titles.join(", ")),
+                category: WORKSPACES.to_string(),
+                shortcut: None,
+                score: 0,
+            });
+        }
+        self.update_search();
     }
 
     /// Close the palette
@@ -242,8 +553,9 @@ impl CommandPalette {
 
     /// Update search results based on query
     pub fn update_search(&mut self) {
+        self.sections.clear();
         if self.query.is_empty() {
-            self.entries = self.all_entries.clone();
+            self.list_by_section();
         } else {
             let query_lower = self.query.to_lowercase();
             self.entries = self
@@ -257,7 +569,7 @@ impl CommandPalette {
                     let score = Self::fuzzy_score(&query_lower, &name_lower, &desc_lower);
                     if score > 0 {
                         let mut e = entry.clone();
-                        e.score = score;
+                        e.score = score + self.recent_bonus(&entry.name);
                         Some(e)
                     } else {
                         None
@@ -266,13 +578,59 @@ impl CommandPalette {
                 .collect();
 
             // Sort by score (highest first)
-            self.entries.sort_by(|a, b| b.score.cmp(&a.score));
+            self.entries
+                .sort_by_key(|entry| std::cmp::Reverse(entry.score));
         }
 
         // Reset selection
         self.selected = 0;
     }
 
+    /// Recently picked entries first, then every category in turn
+    fn list_by_section(&mut self) {
+        let recent: Vec<PaletteEntry> = self
+            .recent
+            .iter()
+            .filter_map(|name| self.all_entries.iter().find(|e| &e.name == name))
+            .cloned()
+            .collect();
+        let mut rest: Vec<PaletteEntry> = self
+            .all_entries
+            .iter()
+            .filter(|e| !self.recent.contains(&e.name))
+            .cloned()
+            .collect();
+        rest.sort_by(|a, b| {
+            (category_rank(&a.category), &a.category, &a.name).cmp(&(
+                category_rank(&b.category),
+                &b.category,
+                &b.name,
+            ))
+        });
+
+        if !recent.is_empty() {
+            self.sections.push((0, RECENT.to_string()));
+        }
+        self.entries = recent;
+        let mut category: Option<String> = None;
+        for entry in rest {
+            if category.as_ref() != Some(&entry.category) {
+                category = Some(entry.category.clone());
+                self.sections
+                    .push((self.entries.len(), entry.category.clone()));
+            }
+            self.entries.push(entry);
+        }
+    }
+
+    /// Search bonus for entries picked recently, larger the more recent
+    fn recent_bonus(&self, name: &str) -> i32 {
+        self.recent
+            .iter()
+            .position(|n| n == name)
+            .map_or(0, |i| 40 - 4 * i as i32)
+    }
+
     /// Calculate fuzzy match score
     fn fuzzy_score(query: &str, name: &str, desc: &str) -> i32 {
         let mut score = 0;
@@ -336,8 +694,119 @@ impl CommandPalette {
     }
 }
 
+/// Fill in a custom entry's `{input}` and `{cwd}` placeholders
+pub fn expand_command(command: &str, input: &str, cwd: &Path) -> String {
+    command
+        .replace("{input}", input.trim())
+        .replace("{cwd}", &cwd.display().to_string())
+}
+
+/// Custom entries first, then workspaces and actions, then the rest
+/// alphabetically
+fn category_rank(category: &str) -> u8 {
+    match category {
+        CUSTOM => 0,
+        WORKSPACES => 1,
+        "Actions" => 2,
+        _ => 3,
+    }
+}
+
+fn recent_path() -> PathBuf {
+    crate::config::paths::data_dir().join("palette_recent.json")
+}
+
+/// Recently picked entries (empty if missing or unreadable)
+fn load_recent() -> Vec<String> {
+    std::fs::read_to_string(recent_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_recent(recent: &[String]) -> anyhow::Result<()> {
+    let path = recent_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string(recent)?)?;
+    Ok(())
+}
+
 impl Default for CommandPalette {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::session::WorkspaceTab;
+
+    #[test]
+    fn test_custom_entries_and_recent_first() {
+        let mut palette = CommandPalette::new();
+        palette.recent.clear();
+        let warnings = palette.set_custom(&[
+            PaletteCommand {
+                name: "Build".to_string(),
+                description: String::new(),
+                command: "cargo build".to_string(),
+                keys: Some("Ctrl+Shift+B".to_string()),
+            },
+            PaletteCommand {
+                name: "Grep Here".to_string(),
+                description: "Search this folder".to_string(),
+                command: "grep -r {input} {cwd}".to_string(),
+                keys: Some("Ctrl+Nope".to_string()),
+            },
+            PaletteCommand::default(),
+        ]);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(palette.custom_command("Build"), Some("cargo build"));
+        assert_eq!(palette.custom_command("New Tab"), None);
+
+        // Custom entries lead the list, under their own heading
+        assert_eq!(palette.section_at(0), Some(CUSTOM));
+        assert_eq!(palette.entries[0].name, "Build");
+        assert_eq!(palette.entries[0].description, "cargo build");
+        assert_eq!(palette.entries[0].shortcut.as_deref(), Some("Ctrl+Shift+B"));
+        assert_eq!(palette.entries[1].shortcut, None);
+        assert_eq!(palette.section_at(2), Some("Actions"));
+
+        palette.recent = vec!["Split Vertical".to_string()];
+        palette.update_search();
+        assert_eq!(palette.section_at(0), Some(RECENT));
+        assert_eq!(palette.entries[0].name, "Split Vertical");
+        assert_eq!(palette.section_at(1), Some(CUSTOM));
+        let listed = palette
+            .entries
+            .iter()
+            .filter(|e| e.name == "Split Vertical");
+        assert_eq!(listed.count(), 1);
+
+        let mut workspace = Workspace::new("ops");
+        workspace.tabs.push(WorkspaceTab {
+            title: "logs".to_string(),
+            layout: "pane".to_string(),
+            panes: Vec::new(),
+            focused: 0,
+        });
+        palette.set_workspaces(&[workspace]);
+        assert_eq!(palette.section_at(3), Some(WORKSPACES));
+        assert_eq!(palette.entries[3].name, "workspace load ops");
+        assert_eq!(palette.entries[3].description, "Open logs");
+
+        // Searching ranks recently picked entries above equal matches
+        palette.query = "split ".to_string();
+        palette.update_search();
+        assert_eq!(palette.entries[0].name, "Split Vertical");
+        assert_eq!(palette.section_at(0), None);
+
+        assert_eq!(
+            expand_command("grep -r {input} {cwd}", " TODO ", Path::new("/src")),
+            "grep -r TODO /src"
+        );
+    }
+}
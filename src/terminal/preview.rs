@@ -0,0 +1,398 @@
+//! File previews for paths in output
+//!
+//! Hovering a path in the output shows the first lines of a text file
+//! (syntax highlighted), an image's dimensions and thumbnail, or a
+//! binary's size and type. Files are read on a background thread with a
+//! byte cap, so hovering a huge log or binary never stalls the UI, and
+//! results are cached for a few seconds.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use super::syntax;
+
+/// Lines shown for text files and directories
+pub const MAX_LINES: usize = 12;
+
+/// Bytes read from a text file at most
+const MAX_BYTES: u64 = 16 * 1024;
+
+/// Larger images only show their dimensions
+const MAX_THUMBNAIL_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Thumbnail edge length in pixels
+const THUMBNAIL_SIZE: u32 = 128;
+
+/// How long a preview stays cached
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Previews kept before the cache is pruned
+const MAX_CACHED: usize = 64;
+
+/// Background reads allowed at once
+const MAX_LOADING: usize = 4;
+
+/// A text segment with an optional highlight color
+pub type PreviewSegment = (String, Option<egui::Color32>);
+
+/// What a hovered path looks like
+pub enum Preview {
+    /// First lines of a text file
+    Text {
+        lines: Vec<Vec<PreviewSegment>>,
+        size: u64,
+        truncated: bool,
+    },
+    /// Image dimensions and a small thumbnail
+    Image {
+        width: u32,
+        height: u32,
+        size: u64,
+        thumbnail: Option<egui::ColorImage>,
+    },
+    /// Anything that isn't text
+    Binary { size: u64, kind: &'static str },
+    /// First entries of a directory
+    Directory { entries: Vec<String>, total: usize },
+}
+
+/// Preview lookup result
+pub enum PreviewStatus<'a> {
+    /// Still being read
+    Loading,
+    /// Ready to show (with the thumbnail texture, if any)
+    Ready(&'a Preview, Option<&'a egui::TextureHandle>),
+    /// Not a file or directory
+    Missing,
+}
+
+struct CacheEntry {
+    loaded_at: Instant,
+    /// A background read is in flight
+    loading: bool,
+    /// None until the first read finishes; Some(None) if the path is missing
+    preview: Option<Option<Preview>>,
+    texture: Option<egui::TextureHandle>,
+}
+
+/// Loads previews in the background and caches them briefly
+pub struct PreviewCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    tx: Sender<(PathBuf, Option<Preview>)>,
+    rx: Receiver<(PathBuf, Option<Preview>)>,
+}
+
+impl Default for PreviewCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreviewCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        let (tx, rx) = channel();
+        Self {
+            entries: HashMap::new(),
+            tx,
+            rx,
+        }
+    }
+
+    /// Look up a preview, starting a background read if it's missing or
+    /// stale (a stale preview keeps showing until the new one arrives)
+    pub fn get(&mut self, ctx: &egui::Context, path: &Path) -> PreviewStatus<'_> {
+        while let Ok((loaded, preview)) = self.rx.try_recv() {
+            if let Some(entry) = self.entries.get_mut(&loaded) {
+                entry.loading = false;
+                entry.preview = Some(preview);
+                entry.texture = None;
+            }
+        }
+
+        let needs_read = self
+            .entries
+            .get(path)
+            .is_none_or(|entry| !entry.loading && entry.loaded_at.elapsed() > CACHE_TTL);
+        let loading = self.entries.values().filter(|e| e.loading).count();
+        if needs_read && loading < MAX_LOADING {
+            self.prune();
+            let entry = self
+                .entries
+                .entry(path.to_path_buf())
+                .or_insert_with(|| CacheEntry {
+                    loaded_at: Instant::now(),
+                    loading: false,
+                    preview: None,
+                    texture: None,
+                });
+            entry.loaded_at = Instant::now();
+            entry.loading = true;
+
+            let tx = self.tx.clone();
+            let path = path.to_path_buf();
+            let repaint = ctx.clone();
+            std::thread::spawn(move || {
+                let preview = load_preview(&path);
+                let _ = tx.send((path, preview));
+                repaint.request_repaint();
+            });
+        }
+
+        let Some(entry) = self.entries.get_mut(path) else {
+            return PreviewStatus::Loading;
+        };
+        match &entry.preview {
+            None => PreviewStatus::Loading,
+            Some(None) => PreviewStatus::Missing,
+            Some(Some(preview)) => {
+                if let Preview::Image {
+                    thumbnail: Some(image),
+                    ..
+                } = preview
+                {
+                    if entry.texture.is_none() {
+                        entry.texture = Some(ctx.load_texture(
+                            format!("preview:{}", path.display()),
+                            image.clone(),
+                            egui::TextureOptions::LINEAR,
+                        ));
+                    }
+                }
+                PreviewStatus::Ready(preview, entry.texture.as_ref())
+            }
+        }
+    }
+
+    /// Drop expired entries once the cache grows too large
+    fn prune(&mut self) {
+        if self.entries.len() >= MAX_CACHED {
+            self.entries
+                .retain(|_, entry| entry.loading || entry.loaded_at.elapsed() < CACHE_TTL);
+        }
+    }
+}
+
+/// Read a bounded preview of a path (None if it doesn't exist)
+fn load_preview(path: &Path) -> Option<Preview> {
+    let metadata = std::fs::metadata(path).ok()?;
+
+    if metadata.is_dir() {
+        let mut names: Vec<String> = std::fs::read_dir(path)
+            .ok()?
+            .flatten()
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    format!("{}/", name)
+                } else {
+                    name
+                }
+            })
+            .collect();
+        names.sort();
+        let total = names.len();
+        names.truncate(MAX_LINES);
+        return Some(Preview::Directory {
+            entries: names,
+            total,
+        });
+    }
+
+    let size = metadata.len();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if let Ok((width, height)) = image::image_dimensions(path) {
+        let thumbnail = (size <= MAX_THUMBNAIL_BYTES)
+            .then(|| image::open(path).ok())
+            .flatten()
+            .map(|img| {
+                let thumb = img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgba8();
+                egui::ColorImage::from_rgba_unmultiplied(
+                    [thumb.width() as usize, thumb.height() as usize],
+                    thumb.as_raw(),
+                )
+            });
+        return Some(Preview::Image {
+            width,
+            height,
+            size,
+            thumbnail,
+        });
+    }
+
+    let mut bytes = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(MAX_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+
+    if let Some(kind) = binary_kind(&bytes, &extension) {
+        return Some(Preview::Binary { size, kind });
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut head: String = text.lines().take(MAX_LINES).collect::<Vec<_>>().join("\n");
+    head.push('\n');
+    let truncated = text.lines().count() > MAX_LINES || size > MAX_BYTES;
+
+    let found = syntax::syntax_for_path(path, head.lines().next().unwrap_or_default());
+    let lines = match found {
+        Some(found) => {
+            let mut highlighter = syntax::LineHighlighter::new(found);
+            head.split_inclusive('\n')
+                .map(|line| {
+                    highlighter
+                        .line(line)
+                        .into_iter()
+                        .map(|seg| {
+                            let (r, g, b, _) = seg.fg;
+                            (
+                                seg.text.trim_end_matches('\n').to_string(),
+                                Some(egui::Color32::from_rgb(r, g, b)),
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+        None => head.lines().map(|l| vec![(l.to_string(), None)]).collect(),
+    };
+
+    Some(Preview::Text {
+        lines,
+        size,
+        truncated,
+    })
+}
+
+/// Describe non-text content, or None if it looks like text
+fn binary_kind(bytes: &[u8], extension: &str) -> Option<&'static str> {
+    let kind = if bytes.starts_with(b"MZ") {
+        "Windows executable"
+    } else if bytes.starts_with(b"\x7fELF") {
+        "ELF executable"
+    } else if bytes.starts_with(b"%PDF") {
+        "PDF document"
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        "ZIP archive"
+    } else if bytes.starts_with(&[0x1f, 0x8b]) {
+        "gzip archive"
+    } else if bytes.contains(&0) {
+        match extension {
+            "db" | "sqlite" | "sqlite3" => "SQLite database",
+            "wasm" => "WebAssembly module",
+            _ => "binary data",
+        }
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// The path-like word at a char index of a line, if any.
+/// Trailing `:line:col` and punctuation are dropped; URLs are ignored.
+pub fn path_at(text: &str, char_index: usize) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_boundary = |c: char| {
+        c.is_whitespace()
+            || matches!(
+                c,
+                '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '<' | '>' | ',' | ';'
+            )
+    };
+
+    if char_index >= chars.len() || is_boundary(chars[char_index]) {
+        return None;
+    }
+    let start = chars[..char_index]
+        .iter()
+        .rposition(|&c| is_boundary(c))
+        .map_or(0, |i| i + 1);
+    let end = chars[char_index..]
+        .iter()
+        .position(|&c| is_boundary(c))
+        .map_or(chars.len(), |i| char_index + i);
+
+    let word: String = chars[start..end].iter().collect();
+    if word.contains("://") {
+        return None;
+    }
+
+    // Drop :line or :line:col (but keep the colon of C:\)
+    let mut word = word.trim_end_matches(['.', ':']).to_string();
+    while let Some((head, tail)) = word.rsplit_once(':') {
+        if !tail.is_empty() && tail.chars().all(|c| c.is_ascii_digit()) {
+            word = head.to_string();
+        } else {
+            break;
+        }
+    }
+
+    let looks_like_path = word.contains(['/', '\\'])
+        || word
+            .rsplit_once('.')
+            .is_some_and(|(name, ext)| !name.is_empty() && ext.chars().any(|c| c.is_alphabetic()));
+    looks_like_path.then_some(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_at() {
+        let line = "error: src/app.rs:120:5 mismatched types";
+        assert_eq!(path_at(line, 10).as_deref(), Some("src/app.rs"));
+        assert_eq!(path_at(line, 2), None);
+        assert_eq!(
+            path_at("Cargo.toml  README.md", 3).as_deref(),
+            Some("Cargo.toml")
+        );
+        assert_eq!(
+            path_at("see C:\\logs\\out.txt.", 8).as_deref(),
+            Some("C:\\logs\\out.txt")
+        );
+        assert_eq!(path_at("open https://example.com/a.png", 10), None);
+        assert_eq!(path_at("version 1.2.3", 9), None);
+    }
+
+    #[test]
+    fn test_binary_kind() {
+        assert_eq!(
+            binary_kind(b"MZ\x90\x00", "exe"),
+            Some("Windows executable")
+        );
+        assert_eq!(binary_kind(b"\x00\x01\x02", "db"), Some("SQLite database"));
+        assert_eq!(binary_kind(b"fn main() {}", "rs"), None);
+    }
+
+    #[test]
+    fn test_load_text_preview_is_bounded() {
+        let path = std::env::temp_dir().join(format!("zaxiom-preview-{}.txt", std::process::id()));
+        let contents: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, contents).unwrap();
+
+        match load_preview(&path) {
+            Some(Preview::Text {
+                lines, truncated, ..
+            }) => {
+                assert_eq!(lines.len(), MAX_LINES);
+                assert!(truncated);
+            }
+            _ => panic!("expected a text preview"),
+        }
+        std::fs::remove_file(path).unwrap();
+        assert!(load_preview(Path::new("/definitely/not/here.txt")).is_none());
+    }
+}
@@ -1,11 +1,16 @@
 //! Terminal rendering utilities
 //!
-//! Helpers for rendering terminal output in egui.
+//! Helpers for rendering terminal output, the input line, and PTY cell
+//! grids in egui.
+
+use std::ops::Range;
 
 use eframe::egui;
 
+use super::ansi;
 use super::buffer::{LineType, OutputLine};
 use crate::config::theme::Theme;
+use crate::pty::grid::Cell;
 
 /// Render an output line with appropriate styling
 #[allow(dead_code)]
@@ -21,3 +26,346 @@ pub fn render_line(ui: &mut egui::Ui, line: &OutputLine, theme: &Theme) {
         egui::RichText::new(&line.text).monospace().color(color),
     ));
 }
+
+/// Theme colors a PTY grid row is drawn with
+pub struct GridColors {
+    /// Text without a color of its own
+    pub foreground: egui::Color32,
+    /// Terminal background (what inverse text shows as its color)
+    pub background: egui::Color32,
+    pub cursor: egui::Color32,
+}
+
+/// Lay out one PTY grid row with each cell's colors and attributes, drawing
+/// column `cursor` (if any) as a block cursor
+pub fn grid_row_job(
+    row: &[Cell],
+    font: &egui::FontId,
+    row_height: f32,
+    colors: &GridColors,
+    cursor: Option<usize>,
+) -> egui::text::LayoutJob {
+    let blank = Cell::default();
+    let used = row
+        .iter()
+        .rposition(|cell| !cell.is_blank())
+        .map_or(0, |i| i + 1);
+    let end = used.max(cursor.map_or(0, |col| col + 1)).max(1);
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut run = String::new();
+    let mut run_format: Option<egui::TextFormat> = None;
+    for col in 0..end {
+        let cell = row.get(col).unwrap_or(&blank);
+        let format = cell_format(cell, font, row_height, colors, cursor == Some(col));
+        if run_format.as_ref() != Some(&format) {
+            if let Some(previous) = run_format.replace(format) {
+                job.append(&std::mem::take(&mut run), 0.0, previous);
+            }
+        }
+        run.push(cell.ch);
+    }
+    if let Some(format) = run_format {
+        job.append(&run, 0.0, format);
+    }
+    job
+}
+
+/// Text format for one cell: bold brightens the 8 basic colors, dim fades,
+/// and inverse swaps foreground and background
+fn cell_format(
+    cell: &Cell,
+    font: &egui::FontId,
+    row_height: f32,
+    colors: &GridColors,
+    is_cursor: bool,
+) -> egui::TextFormat {
+    let rgb = |code: u32| {
+        let (r, g, b) = ansi::grid_color_to_rgb(code);
+        egui::Color32::from_rgb(r, g, b)
+    };
+    let mut fg = match cell.fg {
+        Some(code) if cell.bold && code < 8 => rgb(code + 8),
+        Some(code) => rgb(code),
+        None => colors.foreground,
+    };
+    let mut bg = cell.bg.map_or(egui::Color32::TRANSPARENT, rgb);
+    if cell.dim {
+        fg = fg.gamma_multiply(0.6);
+    }
+    if cell.inverse {
+        let back = if bg == egui::Color32::TRANSPARENT {
+            colors.background
+        } else {
+            bg
+        };
+        bg = fg;
+        fg = back;
+    }
+    if is_cursor {
+        bg = colors.cursor;
+        fg = colors.background;
+    }
+
+    let line = |on: bool| {
+        if on {
+            egui::Stroke::new(1.0, fg)
+        } else {
+            egui::Stroke::NONE
+        }
+    };
+    egui::TextFormat {
+        font_id: font.clone(),
+        line_height: Some(row_height),
+        color: fg,
+        background: bg,
+        italics: cell.italic,
+        underline: line(cell.underline),
+        strikethrough: line(cell.strikethrough),
+        ..Default::default()
+    }
+}
+
+/// Lay out a usage hint under the input: dimmed, with the `active`
+/// parameter in the accent color
+pub fn usage_hint_job(
+    usage: &str,
+    active: Option<Range<usize>>,
+    font: &egui::FontId,
+    color: egui::Color32,
+    accent: egui::Color32,
+) -> egui::text::LayoutJob {
+    let active = active.unwrap_or(0..0);
+    let mut job = egui::text::LayoutJob::default();
+    for (range, highlighted) in [
+        (0..active.start, false),
+        (active.clone(), true),
+        (active.end..usage.len(), false),
+    ] {
+        if range.is_empty() {
+            continue;
+        }
+        job.append(
+            &usage[range],
+            0.0,
+            egui::TextFormat {
+                font_id: font.clone(),
+                color: if highlighted { accent } else { color },
+                underline: if highlighted {
+                    egui::Stroke::new(1.0, accent)
+                } else {
+                    egui::Stroke::NONE
+                },
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// Lay out the input line: the first `prefix.0` bytes (the prefix Up is
+/// searching history for) in the accent color `prefix.1`, and the `error`
+/// range (a syntax error) underlined in its color
+pub fn input_job(
+    text: &str,
+    font: &egui::FontId,
+    color: egui::Color32,
+    prefix: (usize, egui::Color32),
+    error: Option<(Range<usize>, egui::Color32)>,
+) -> egui::text::LayoutJob {
+    let (prefix_len, accent) = prefix;
+    let mut bounds = vec![0, prefix_len, text.len()];
+    if let Some((range, _)) = &error {
+        bounds.extend([range.start, range.end]);
+    }
+    bounds.retain(|&b| b <= text.len() && text.is_char_boundary(b));
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut job = egui::text::LayoutJob::default();
+    for pair in bounds.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let in_prefix = end <= prefix_len;
+        let error_color = error
+            .as_ref()
+            .filter(|(range, _)| start >= range.start && end <= range.end)
+            .map(|(_, color)| *color);
+        let underline = match error_color {
+            Some(red) => egui::Stroke::new(2.0, red),
+            None if in_prefix => egui::Stroke::new(1.0, accent),
+            None => egui::Stroke::NONE,
+        };
+        job.append(
+            &text[start..end],
+            0.0,
+            egui::TextFormat {
+                font_id: font.clone(),
+                color: if in_prefix { accent } else { color },
+                underline,
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// Lay out a line of the pager in its ANSI colors (`color` where it has
+/// none), with `matches` (byte ranges of its text) drawn in `highlight.1`
+/// on `highlight.0`
+pub fn pager_line_job(
+    segments: &[ansi::StyledSegment],
+    matches: &[Range<usize>],
+    font: &egui::FontId,
+    color: egui::Color32,
+    highlight: (egui::Color32, egui::Color32),
+) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let mut offset = 0;
+    for segment in segments {
+        let end = offset + segment.text.len();
+        let mut bounds = vec![offset, end];
+        bounds.extend(matches.iter().flat_map(|m| [m.start, m.end]));
+        bounds
+            .retain(|&b| (offset..=end).contains(&b) && segment.text.is_char_boundary(b - offset));
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let segment_color = segment
+            .fg_color
+            .map_or(color, |(r, g, b)| egui::Color32::from_rgb(r, g, b));
+        for pair in bounds.windows(2) {
+            let (start, stop) = (pair[0], pair[1]);
+            let highlighted = matches.iter().any(|m| start >= m.start && stop <= m.end);
+            job.append(
+                &segment.text[start - offset..stop - offset],
+                0.0,
+                egui::TextFormat {
+                    font_id: font.clone(),
+                    color: if highlighted {
+                        highlight.1
+                    } else {
+                        segment_color
+                    },
+                    background: if highlighted {
+                        highlight.0
+                    } else {
+                        egui::Color32::TRANSPARENT
+                    },
+                    italics: segment.italic,
+                    underline: if segment.underline {
+                        egui::Stroke::new(1.0, segment_color)
+                    } else {
+                        egui::Stroke::NONE
+                    },
+                    ..Default::default()
+                },
+            );
+        }
+        offset = end;
+    }
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pty::TerminalGrid;
+
+    #[test]
+    fn test_grid_row_job() {
+        let mut grid = TerminalGrid::new(1, 20);
+        grid.process(b"ab\x1b[31mcd\x1b[0m  ");
+        let row = grid.all_rows().next().unwrap();
+        let colors = GridColors {
+            foreground: egui::Color32::WHITE,
+            background: egui::Color32::BLACK,
+            cursor: egui::Color32::GREEN,
+        };
+        let font = egui::FontId::monospace(14.0);
+
+        // One run per style; trailing blanks are dropped
+        let job = grid_row_job(row, &font, 18.0, &colors, None);
+        assert_eq!(job.text, "abcd");
+        assert_eq!(job.sections.len(), 2);
+        assert_eq!(
+            job.sections[1].format.color,
+            egui::Color32::from_rgb(205, 49, 49)
+        );
+
+        // The cursor past the text pads the row and gets its own block
+        let job = grid_row_job(row, &font, 18.0, &colors, Some(6));
+        assert_eq!(job.text, "abcd   ");
+        assert_eq!(
+            job.sections.last().unwrap().format.background,
+            egui::Color32::GREEN
+        );
+    }
+
+    #[test]
+    fn test_input_job() {
+        let font = egui::FontId::monospace(14.0);
+        let (white, accent, red) = (
+            egui::Color32::WHITE,
+            egui::Color32::BLUE,
+            egui::Color32::RED,
+        );
+
+        let job = input_job(
+            "git st | | wc",
+            &font,
+            white,
+            (3, accent),
+            Some((9..10, red)),
+        );
+        let pieces: Vec<_> = job
+            .sections
+            .iter()
+            .map(|s| (&job.text[s.byte_range.clone()], s.format.underline.color))
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                ("git", accent),
+                (" st | ", egui::Color32::TRANSPARENT),
+                ("|", red),
+                (" wc", egui::Color32::TRANSPARENT)
+            ]
+        );
+
+        // Ranges that don't fit the text are ignored
+        let job = input_job("é", &font, white, (1, accent), Some((5..9, red)));
+        assert_eq!(job.sections.len(), 1);
+    }
+
+    #[test]
+    fn test_pager_line_job() {
+        let segments = ansi::parse_ansi("src/\x1b[38;2;0;200;0mmain.rs\x1b[0m");
+        let font = egui::FontId::monospace(14.0);
+        let highlight = (egui::Color32::YELLOW, egui::Color32::BLACK);
+
+        // The matches split both colored runs
+        let job = pager_line_job(
+            &segments,
+            &[0..1, 2..6],
+            &font,
+            egui::Color32::WHITE,
+            highlight,
+        );
+        let runs: Vec<(&str, egui::Color32)> = job
+            .sections
+            .iter()
+            .map(|s| (&job.text[s.byte_range.clone()], s.format.color))
+            .collect();
+        assert_eq!(job.text, "src/main.rs");
+        assert_eq!(
+            runs,
+            [
+                ("s", egui::Color32::BLACK),
+                ("r", egui::Color32::WHITE),
+                ("c/", egui::Color32::BLACK),
+                ("ma", egui::Color32::BLACK),
+                ("in.rs", egui::Color32::from_rgb(0, 200, 0)),
+            ]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! Session persistence
 //!
-//! Saves and restores terminal sessions across restarts.
+//! Saves and restores terminal sessions across restarts, and named
+//! workspaces (`workspace save/load`): tabs with their split layout, and
+//! per pane a working directory and commands to run when it opens.
 
 #![allow(dead_code)]
 
@@ -8,6 +10,10 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+/// Name the previous window's autosave is kept under (see
+/// `SessionManager::keep_last_session`)
+pub const LAST_SESSION: &str = "last";
+
 /// Saved session data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedSession {
@@ -26,18 +32,90 @@ pub struct SavedSession {
 pub struct SavedTab {
     /// Tab title
     pub title: String,
-    /// Working directory
+    /// Working directory (of the focused pane)
     pub cwd: PathBuf,
     /// Command history (last N commands)
     pub history: Vec<String>,
     /// Scroll position (lines from top)
     pub scroll_position: usize,
+    /// Split arrangement as a layout spec (see `terminal::layout`)
+    #[serde(default = "single_pane")]
+    pub layout: String,
+    /// Panes in layout order (empty in sessions saved before splits were;
+    /// `cwd` and `history` then describe the only pane)
+    #[serde(default)]
+    pub panes: Vec<SavedPane>,
+    /// Index of the focused pane
+    #[serde(default)]
+    pub focused: usize,
+}
+
+/// Saved pane data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPane {
+    /// Working directory
+    pub cwd: PathBuf,
+    /// Name shown in the pane header (`pane rename`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Commands run in this pane (last N, oldest first)
+    #[serde(default)]
+    pub history: Vec<String>,
+}
+
+/// A named workspace profile (e.g. "rust-dev", "ops")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Workspace name
+    pub name: String,
+    /// Tabs it opens
+    pub tabs: Vec<WorkspaceTab>,
+    /// Tab to switch to once it is open
+    #[serde(default)]
+    pub active_tab: usize,
+    /// When the workspace was last saved
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// A tab of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    /// Tab title when it was saved
+    pub title: String,
+    /// Split arrangement as a layout spec (see `terminal::layout`)
+    #[serde(default = "single_pane")]
+    pub layout: String,
+    /// Panes in layout order
+    pub panes: Vec<WorkspacePane>,
+    /// Index of the focused pane
+    #[serde(default)]
+    pub focused: usize,
+}
+
+/// A pane of a workspace tab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacePane {
+    /// Working directory
+    pub cwd: PathBuf,
+    /// Name shown in the pane header (`pane rename`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Commands run one after another when the pane opens
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+fn single_pane() -> String {
+    "pane".to_string()
 }
 
 /// Session manager for persistence
 pub struct SessionManager {
     /// Directory to store session files
     session_dir: PathBuf,
+    /// Directory to store workspace files
+    workspace_dir: PathBuf,
     /// Maximum history entries to save per tab
     max_history: usize,
 }
@@ -48,16 +126,14 @@ impl SessionManager {
         let session_dir = Self::default_session_dir();
         Self {
             session_dir,
+            workspace_dir: crate::config::paths::data_dir().join("workspaces"),
             max_history: 100,
         }
     }
 
     /// Get the default session directory
-    fn default_session_dir() -> PathBuf {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zaxiom")
-            .join("sessions")
+    pub fn default_session_dir() -> PathBuf {
+        crate::config::paths::data_dir().join("sessions")
     }
 
     /// Ensure session directory exists
@@ -115,6 +191,32 @@ impl SessionManager {
         Err(anyhow::anyhow!("No saved sessions found"))
     }
 
+    /// Keep the previous window's autosave as the "last" session before this
+    /// window's autosave replaces it
+    pub fn keep_last_session(&self) -> anyhow::Result<()> {
+        let Ok(mut session) = self.load_autosave() else {
+            return Ok(());
+        };
+        session.name = LAST_SESSION.to_string();
+        self.save_session(&session)?;
+        self.clear_autosave()
+    }
+
+    /// Sessions that can be restored (everything but this window's
+    /// autosave), most recently saved first
+    pub fn restorable_sessions(&self) -> Vec<SavedSession> {
+        let Ok(names) = self.list_sessions() else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<SavedSession> = names
+            .iter()
+            .filter(|name| *name != "autosave")
+            .filter_map(|name| self.load_session(name).ok())
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        sessions
+    }
+
     /// List all saved sessions
     pub fn list_sessions(&self) -> anyhow::Result<Vec<String>> {
         self.ensure_dir()?;
@@ -162,6 +264,53 @@ impl SessionManager {
     pub fn clear_autosave(&self) -> anyhow::Result<()> {
         self.delete_session("autosave")
     }
+
+    fn workspace_path(&self, name: &str) -> PathBuf {
+        self.workspace_dir
+            .join(format!("{}.json", sanitize_filename(name)))
+    }
+
+    /// Save a workspace, replacing one with the same name
+    pub fn save_workspace(&self, workspace: &Workspace) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.workspace_dir)?;
+        let json = serde_json::to_string_pretty(workspace)?;
+        fs::write(self.workspace_path(&workspace.name), json)?;
+        Ok(())
+    }
+
+    /// Load a workspace by name
+    pub fn load_workspace(&self, name: &str) -> anyhow::Result<Workspace> {
+        let json = fs::read_to_string(self.workspace_path(name))
+            .map_err(|_| anyhow::anyhow!("no workspace named '{}'", name))?;
+        let workspace = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("workspace '{}' is invalid: {}", name, e))?;
+        Ok(workspace)
+    }
+
+    /// All saved workspaces, sorted by name (unreadable files are skipped)
+    pub fn list_workspaces(&self) -> Vec<Workspace> {
+        let Ok(entries) = fs::read_dir(&self.workspace_dir) else {
+            return Vec::new();
+        };
+        let mut workspaces: Vec<Workspace> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|s| s == "json"))
+            .filter_map(|e| fs::read_to_string(e.path()).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        workspaces.sort_by(|a: &Workspace, b| a.name.cmp(&b.name));
+        workspaces
+    }
+
+    /// Delete a workspace, returning whether it existed
+    pub fn delete_workspace(&self, name: &str) -> anyhow::Result<bool> {
+        let path = self.workspace_path(name);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok(true)
+    }
 }
 
 impl Default for SessionManager {
@@ -183,6 +332,25 @@ fn sanitize_filename(name: &str) -> String {
         .collect()
 }
 
+/// Local time of a `saved_at` timestamp, as "2024-05-01 18:30"
+pub fn saved_time(saved_at: u64) -> String {
+    chrono::DateTime::from_timestamp(saved_at as i64, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 impl SavedSession {
     /// Create a new session with a name
     pub fn new(name: &str) -> Self {
@@ -190,10 +358,7 @@ impl SavedSession {
             name: name.to_string(),
             tabs: Vec::new(),
             active_tab: 0,
-            saved_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
+            saved_at: now(),
         }
     }
 
@@ -201,6 +366,17 @@ impl SavedSession {
     pub fn add_tab(&mut self, tab: SavedTab) {
         self.tabs.push(tab);
     }
+
+    /// Number of panes over all tabs
+    pub fn pane_count(&self) -> usize {
+        self.tabs.iter().map(|tab| tab.panes.len().max(1)).sum()
+    }
+
+    /// Tab titles, comma separated
+    pub fn tab_titles(&self) -> String {
+        let titles: Vec<&str> = self.tabs.iter().map(|t| t.title.as_str()).collect();
+        titles.join(", ")
+    }
 }
 
 impl SavedTab {
@@ -211,6 +387,141 @@ impl SavedTab {
             cwd,
             history: Vec::new(),
             scroll_position: 0,
+            layout: single_pane(),
+            panes: Vec::new(),
+            focused: 0,
+        }
+    }
+
+    /// Panes to restore, in layout order
+    pub fn saved_panes(&self) -> Vec<SavedPane> {
+        if self.panes.is_empty() {
+            vec![SavedPane {
+                cwd: self.cwd.clone(),
+                name: None,
+                history: self.history.clone(),
+            }]
+        } else {
+            self.panes.clone()
+        }
+    }
+}
+
+impl Workspace {
+    /// Create an empty workspace with a name
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            tabs: Vec::new(),
+            active_tab: 0,
+            saved_at: now(),
         }
     }
+
+    /// Number of panes over all tabs
+    pub fn pane_count(&self) -> usize {
+        self.tabs.iter().map(|tab| tab.panes.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-workspaces-{}", std::process::id()));
+        let manager = SessionManager {
+            session_dir: dir.join("sessions"),
+            workspace_dir: dir.join("workspaces"),
+            max_history: 100,
+        };
+        assert!(manager.list_workspaces().is_empty());
+        assert!(manager.load_workspace("rust-dev").is_err());
+
+        let mut workspace = Workspace::new("rust-dev");
+        workspace.tabs.push(WorkspaceTab {
+            title: "zaxiom".to_string(),
+            layout: "v(0.65, pane, pane)".to_string(),
+            panes: vec![
+                WorkspacePane {
+                    cwd: PathBuf::from("/src/zaxiom"),
+                    name: None,
+                    commands: Vec::new(),
+                },
+                WorkspacePane {
+                    cwd: PathBuf::from("/src/zaxiom"),
+                    name: Some("tests".to_string()),
+                    commands: vec!["cargo watch -x test".to_string()],
+                },
+            ],
+            focused: 1,
+        });
+        manager.save_workspace(&workspace).unwrap();
+
+        let loaded = manager.load_workspace("rust-dev").unwrap();
+        assert_eq!(loaded.pane_count(), 2);
+        assert_eq!(loaded.tabs[0].panes[1].commands, ["cargo watch -x test"]);
+        assert_eq!(loaded.tabs[0].focused, 1);
+        assert_eq!(manager.list_workspaces().len(), 1);
+
+        // Hand-written files can leave out the layout and pane details
+        fs::write(
+            manager.workspace_path("ops"),
+            r#"{"name": "ops", "tabs": [{"title": "logs", "panes": [{"cwd": "/var/log"}]}]}"#,
+        )
+        .unwrap();
+        let names: Vec<String> = manager
+            .list_workspaces()
+            .into_iter()
+            .map(|w| w.name)
+            .collect();
+        assert_eq!(names, ["ops", "rust-dev"]);
+        assert_eq!(
+            manager.load_workspace("ops").unwrap().tabs[0].layout,
+            "pane"
+        );
+
+        assert!(manager.delete_workspace("ops").unwrap());
+        assert!(!manager.delete_workspace("ops").unwrap());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_last_session_is_kept() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-sessions-{}", std::process::id()));
+        let manager = SessionManager {
+            session_dir: dir.join("sessions"),
+            workspace_dir: dir.join("workspaces"),
+            max_history: 100,
+        };
+        assert!(manager.restorable_sessions().is_empty());
+
+        // A session file from before splits were saved
+        fs::create_dir_all(&manager.session_dir).unwrap();
+        fs::write(
+            manager.session_dir.join("autosave.json"),
+            r#"{"name": "autosave", "active_tab": 0, "saved_at": 100, "tabs": [
+                {"title": "src", "cwd": "/src", "history": ["ls"], "scroll_position": 0}]}"#,
+        )
+        .unwrap();
+        assert!(manager.restorable_sessions().is_empty());
+        manager.keep_last_session().unwrap();
+
+        let mut named = SavedSession::new("work");
+        named.saved_at = 200;
+        named.add_tab(SavedTab::new("docs", PathBuf::from("/docs")));
+        manager.save_session(&named).unwrap();
+        manager.autosave(&named).unwrap();
+
+        let sessions = manager.restorable_sessions();
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["work", LAST_SESSION]);
+        let last = manager.load_session(LAST_SESSION).unwrap();
+        let panes = last.tabs[0].saved_panes();
+        assert_eq!(panes.len(), 1);
+        assert_eq!(panes[0].history, ["ls"]);
+        assert_eq!(last.tabs[0].layout, "pane");
+        let _ = fs::remove_dir_all(dir);
+    }
 }
@@ -2,13 +2,25 @@
 //!
 //! Enhanced command history with context tracking, fuzzy search, and persistence.
 //! Inspired by Warp's AI-powered history and atuin shell history.
+//!
+//! Every pane appends the commands it runs to one JSONL file in the data
+//! directory (when, where, exit code, and run time) and picks up what other
+//! panes and windows have appended, so history outlives the app.
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime};
 
+use anyhow::Result;
+use chrono::{DateTime, Local, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::shell::parser::split_words;
 use crate::terminal::project::ProjectType;
 
 /// A command entry with full context
@@ -142,6 +154,61 @@ impl HistoryEntry {
     }
 }
 
+/// Default history file location
+pub fn default_path() -> PathBuf {
+    crate::config::paths::data_dir().join("history.jsonl")
+}
+
+/// One line of the history file
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryRecord {
+    command: String,
+    cwd: PathBuf,
+    /// When the command started (RFC 3339)
+    started: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// The pane that ran it, which skips its own lines when merging
+    session: u64,
+}
+
+impl HistoryRecord {
+    fn new(entry: &HistoryEntry) -> Self {
+        Self {
+            command: entry.command.clone(),
+            cwd: entry.cwd.clone(),
+            started: DateTime::<Local>::from(entry.timestamp).to_rfc3339(),
+            exit_code: entry.exit_code,
+            duration_ms: entry.duration.map(|d| d.as_millis() as u64),
+            session: entry.session_id,
+        }
+    }
+
+    fn into_entry(self) -> Option<HistoryEntry> {
+        let started = DateTime::parse_from_rfc3339(&self.started).ok()?;
+        let mut entry = HistoryEntry::new(self.command, self.cwd, self.session);
+        entry.timestamp = started.into();
+        entry.exit_code = self.exit_code;
+        entry.duration = self.duration_ms.map(Duration::from_millis);
+        Some(entry)
+    }
+}
+
+/// Append a line to the history file
+fn append(path: &Path, record: &HistoryRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Distinguishes panes opened in the same instant
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(0);
+
 /// Smart history manager
 pub struct SmartHistory {
     /// All history entries
@@ -160,6 +227,13 @@ pub struct SmartHistory {
     frequency: HashMap<String, usize>,
     /// Directory-specific command frequency
     dir_frequency: HashMap<PathBuf, HashMap<String, usize>>,
+    /// History file shared by every pane (None keeps history in memory)
+    file: Option<PathBuf>,
+    /// Bytes of the file already read
+    file_read: u64,
+    /// The last entry hasn't been written yet: it's held back until it
+    /// finishes so its exit code and run time go with it
+    unsaved: bool,
 }
 
 impl Default for SmartHistory {
@@ -176,14 +250,30 @@ impl SmartHistory {
             max_entries,
             session_id: SystemTime::now()
                 .duration_since(SystemTime::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+                .wrapping_add(NEXT_SESSION.fetch_add(1, Ordering::Relaxed)),
             position: None,
             filtered: Vec::new(),
             search_query: String::new(),
             frequency: HashMap::new(),
             dir_frequency: HashMap::new(),
+            file: None,
+            file_read: 0,
+            unsaved: false,
+        }
+    }
+
+    /// History kept in `path` (see `default_path`): loads what's there, and
+    /// appends each command run from now on
+    pub fn persistent(path: PathBuf, max_entries: usize) -> Self {
+        let mut history = Self::new(max_entries);
+        history.file = Some(path);
+        // Drop what no longer fits once the file is well past the limit
+        if history.sync() > max_entries * 2 {
+            history.rewrite();
         }
+        history
     }
 
     /// Add a command to history
@@ -192,29 +282,46 @@ impl SmartHistory {
         if command.is_empty() {
             return;
         }
+        self.save_pending();
+        self.sync();
+
+        let mut entry = HistoryEntry::new(command.to_string(), cwd, self.session_id);
+        entry.project_type = project_type;
+        self.unsaved = self.push(entry) && self.file.is_some();
+        self.position = None;
+        self.filtered.clear();
+    }
 
-        // Don't add if identical to last command
+    /// Put a restored pane's commands back at the end, so ↑ reaches them
+    /// first. They are already in the history file and aren't written again.
+    pub fn restore(&mut self, commands: &[String], cwd: &Path) {
+        for command in commands {
+            let entry = HistoryEntry::new(command.clone(), cwd.to_path_buf(), self.session_id);
+            self.push(entry);
+        }
+        self.position = None;
+        self.filtered.clear();
+    }
+
+    /// Append an entry, unless it repeats the last one (the same command in
+    /// the same directory). Returns whether it was added.
+    fn push(&mut self, mut entry: HistoryEntry) -> bool {
         if let Some(last) = self.entries.last() {
-            if last.command == command && last.cwd == cwd {
-                self.position = None;
-                return;
+            if last.command == entry.command && last.cwd == entry.cwd {
+                return false;
             }
         }
 
-        // Create entry
-        let mut entry = HistoryEntry::new(command.to_string(), cwd.clone(), self.session_id);
-        entry.project_type = project_type;
-
         // Auto-tag based on command
-        entry.tags = Self::auto_tag(command);
+        entry.tags = Self::auto_tag(&entry.command);
 
         // Update frequency maps
-        *self.frequency.entry(command.to_string()).or_insert(0) += 1;
+        *self.frequency.entry(entry.command.clone()).or_insert(0) += 1;
         *self
             .dir_frequency
-            .entry(cwd)
+            .entry(entry.cwd.clone())
             .or_default()
-            .entry(command.to_string())
+            .entry(entry.command.clone())
             .or_insert(0) += 1;
 
         // Remove oldest if at capacity
@@ -223,8 +330,7 @@ impl SmartHistory {
         }
 
         self.entries.push(entry);
-        self.position = None;
-        self.filtered.clear();
+        true
     }
 
     /// Update the last entry with completion info
@@ -235,6 +341,91 @@ impl SmartHistory {
                 entry.set_output(out);
             }
         }
+        self.save_pending();
+    }
+
+    /// Merge in the commands other panes and windows have written to the
+    /// history file since it was last read. Returns how many lines were read.
+    pub fn sync(&mut self) -> usize {
+        // Merged entries would land after the one still running
+        if self.unsaved {
+            return 0;
+        }
+        let Some(path) = &self.file else {
+            return 0;
+        };
+        let Ok(mut file) = File::open(path) else {
+            return 0;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.file_read {
+            // Rewritten by `history -c` or `-d` in another pane
+            self.file_read = len;
+            return 0;
+        }
+        let mut bytes = Vec::new();
+        if file.seek(SeekFrom::Start(self.file_read)).is_err()
+            || file.read_to_end(&mut bytes).is_err()
+        {
+            return 0;
+        }
+
+        // A line still being written is picked up next time
+        let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        self.file_read += complete as u64;
+        let text = String::from_utf8_lossy(&bytes[..complete]);
+        let mut read = 0;
+        for line in text.lines() {
+            read += 1;
+            let entry = serde_json::from_str::<HistoryRecord>(line)
+                .ok()
+                .and_then(HistoryRecord::into_entry);
+            if let Some(entry) = entry.filter(|e| e.session_id != self.session_id) {
+                self.push(entry);
+            }
+        }
+        read
+    }
+
+    /// Write the last entry if it's still held back
+    fn save_pending(&mut self) {
+        if !std::mem::take(&mut self.unsaved) {
+            return;
+        }
+        let (Some(path), Some(entry)) = (&self.file, self.entries.last()) else {
+            return;
+        };
+        if let Err(e) = append(path, &HistoryRecord::new(entry)) {
+            eprintln!("Failed to write history: {}", e);
+        }
+    }
+
+    /// Replace the history file with the entries held now
+    fn rewrite(&mut self) {
+        let Some(path) = &self.file else {
+            return;
+        };
+        let mut contents = String::new();
+        for entry in &self.entries {
+            if let Ok(line) = serde_json::to_string(&HistoryRecord::new(entry)) {
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+        }
+        // Written aside and renamed so a crash can't leave half a file
+        let temp = path.with_extension("jsonl.tmp");
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&temp, &contents))
+            .and_then(|_| fs::rename(&temp, path));
+        match written {
+            Ok(()) => {
+                self.file_read = contents.len() as u64;
+                self.unsaved = false;
+            }
+            Err(e) => eprintln!("Failed to write history: {}", e),
+        }
     }
 
     /// Auto-generate tags based on command content
@@ -309,7 +500,7 @@ impl SmartHistory {
                 .collect();
 
             // Sort by score (highest first)
-            scored.sort_by(|a, b| b.1.cmp(&a.1));
+            scored.sort_by_key(|s| std::cmp::Reverse(s.1));
 
             // Deduplicate by command (keep highest scored)
             let mut seen = std::collections::HashSet::new();
@@ -355,6 +546,47 @@ impl SmartHistory {
         list
     }
 
+    /// Get previous command starting with `prefix` (up arrow with text typed,
+    /// zsh-style). The prefix is fixed when navigation starts; an empty prefix
+    /// walks the full history.
+    pub fn previous_with_prefix(&mut self, prefix: &str) -> Option<&str> {
+        if self.position.is_none() {
+            self.reset_position();
+        }
+        if self.position.is_none() && !prefix.is_empty() {
+            let mut seen = HashSet::new();
+            let matches: Vec<usize> = self
+                .entries
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, e)| {
+                    e.command.starts_with(prefix)
+                        && e.command != prefix
+                        && seen.insert(e.command.as_str())
+                })
+                .map(|(i, _)| i)
+                .collect();
+            if matches.is_empty() {
+                return None;
+            }
+            self.filtered = matches;
+            self.search_query = prefix.to_string();
+        }
+        self.previous()
+    }
+
+    /// Whether Up/Down navigation is in progress
+    pub fn is_navigating(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Prefix the current Up/Down navigation is limited to, if any
+    pub fn navigation_prefix(&self) -> Option<&str> {
+        (self.position.is_some() && !self.filtered.is_empty() && !self.search_query.is_empty())
+            .then_some(self.search_query.as_str())
+    }
+
     /// Get next command (down arrow)
     pub fn next(&mut self) -> Option<&str> {
         match self.position {
@@ -422,7 +654,7 @@ impl SmartHistory {
         }
 
         // Sort by frequency
-        suggestions.sort_by(|a, b| b.1.cmp(&a.1));
+        suggestions.sort_by_key(|s| std::cmp::Reverse(s.1));
 
         suggestions
             .into_iter()
@@ -431,6 +663,41 @@ impl SmartHistory {
             .collect()
     }
 
+    /// Commands run most often and most recently in a directory, best first
+    /// (frecency: each run counts more the newer it is; failed runs are skipped)
+    pub fn frecent(&self, cwd: &PathBuf, limit: usize) -> Vec<String> {
+        let now = SystemTime::now();
+        let mut scores: HashMap<&str, (u64, usize)> = HashMap::new();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.cwd != *cwd || entry.exit_code.is_some_and(|c| c != 0) {
+                continue;
+            }
+            let age = now
+                .duration_since(entry.timestamp)
+                .unwrap_or_default()
+                .as_secs();
+            let weight = match age {
+                0..=3_599 => 8,
+                3_600..=86_399 => 4,
+                86_400..=604_799 => 2,
+                _ => 1,
+            };
+            let score = scores.entry(entry.command.as_str()).or_insert((0, 0));
+            score.0 += weight;
+            score.1 = index;
+        }
+
+        // Highest score first, the most recently run breaking ties
+        let mut ranked: Vec<_> = scores.into_iter().collect();
+        ranked.sort_by_key(|r| std::cmp::Reverse(r.1));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(cmd, _)| cmd.to_string())
+            .collect()
+    }
+
     /// Get most frequent commands overall
     pub fn top_commands(&self, limit: usize) -> Vec<(&str, usize)> {
         let mut freq: Vec<_> = self.frequency.iter().collect();
@@ -482,6 +749,92 @@ impl SmartHistory {
             .collect()
     }
 
+    /// Files named as arguments by recent commands that still exist, most
+    /// recent first, with the command that last used each one and when
+    pub fn recent_files(&self, limit: usize) -> Vec<(PathBuf, &HistoryEntry)> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+        for entry in self.entries.iter().rev() {
+            let words = split_words(&entry.command).unwrap_or_else(|_| {
+                entry
+                    .command
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect()
+            });
+            for arg in words.iter().skip(1).filter(|arg| !arg.starts_with('-')) {
+                // Lexically tidy the path so `./a` and `a` are one file
+                let path: PathBuf = entry
+                    .cwd
+                    .join(arg)
+                    .components()
+                    .filter(|c| *c != Component::CurDir)
+                    .collect();
+                if path.is_file() && seen.insert(path.clone()) {
+                    files.push((path, entry));
+                    if files.len() == limit {
+                        return files;
+                    }
+                }
+            }
+        }
+        files
+    }
+
+    /// Directories recent commands ran in that still exist, most recent first
+    pub fn recent_dirs(&self, limit: usize) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.cwd.is_dir() && seen.insert(entry.cwd.clone()))
+            .take(limit)
+            .map(|entry| entry.cwd.clone())
+            .collect()
+    }
+
+    /// Remove entries by index (0-based). Frequency counts are rebuilt so
+    /// removed commands stop showing up in suggestions, and the history
+    /// file is rewritten without them.
+    pub fn remove(&mut self, range: std::ops::Range<usize>) {
+        let end = range.end.min(self.entries.len());
+        if range.start >= end {
+            return;
+        }
+        self.entries.drain(range.start..end);
+        self.rebuild_frequency();
+        self.position = None;
+        self.filtered.clear();
+        // Keep what other panes wrote meanwhile
+        self.unsaved = false;
+        self.sync();
+        self.rewrite();
+    }
+
+    /// Remove every entry, from the history file too
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.frequency.clear();
+        self.dir_frequency.clear();
+        self.position = None;
+        self.filtered.clear();
+        self.rewrite();
+    }
+
+    fn rebuild_frequency(&mut self) {
+        self.frequency.clear();
+        self.dir_frequency.clear();
+        for entry in &self.entries {
+            *self.frequency.entry(entry.command.clone()).or_insert(0) += 1;
+            *self
+                .dir_frequency
+                .entry(entry.cwd.clone())
+                .or_default()
+                .entry(entry.command.clone())
+                .or_insert(0) += 1;
+        }
+    }
+
     /// Get all entries
     pub fn all(&self) -> impl Iterator<Item = &HistoryEntry> {
         self.entries.iter()
@@ -497,34 +850,103 @@ impl SmartHistory {
         self.entries.is_empty()
     }
 
-    /// Get statistics
-    pub fn stats(&self) -> HistoryStats<'_> {
-        let total = self.entries.len();
-        let successful = self.entries.iter().filter(|e| e.is_success()).count();
-        let failed = self
-            .entries
+    /// Usage figures for `history stats`, ranked lists holding at most
+    /// `limit` rows
+    pub fn stats(&self, limit: usize) -> HistoryStats {
+        let mut stats = HistoryStats {
+            total_commands: self.entries.len(),
+            ..Default::default()
+        };
+        // Runs, failures, and (count, total) of timed runs per command
+        let mut commands: HashMap<&str, (usize, usize, u32, Duration)> = HashMap::new();
+        let mut directories: HashMap<&PathBuf, usize> = HashMap::new();
+
+        for entry in &self.entries {
+            let command =
+                commands
+                    .entry(entry.command.as_str())
+                    .or_insert((0, 0, 0, Duration::ZERO));
+            command.0 += 1;
+            match entry.exit_code {
+                Some(0) => stats.successful += 1,
+                Some(_) => {
+                    stats.failed += 1;
+                    command.1 += 1;
+                }
+                None => {}
+            }
+            if let Some(duration) = entry.duration {
+                command.2 += 1;
+                command.3 += duration;
+            }
+            *directories.entry(&entry.cwd).or_insert(0) += 1;
+            let hour = DateTime::<Local>::from(entry.timestamp).hour();
+            stats.by_hour[hour as usize] += 1;
+        }
+        stats.unique_commands = commands.len();
+
+        // Ties go to the command name so the order is stable
+        let mut ranked: Vec<_> = commands.into_iter().collect();
+        ranked.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+        stats.most_used = ranked
             .iter()
-            .filter(|e| e.exit_code.is_some_and(|c| c != 0))
-            .count();
-        let unique = self.frequency.len();
+            .take(limit)
+            .map(|(command, (runs, ..))| (command.to_string(), *runs))
+            .collect();
 
-        HistoryStats {
-            total_commands: total,
-            unique_commands: unique,
-            successful,
-            failed,
-            most_used: self.top_commands(5),
-        }
+        let mut failing: Vec<_> = ranked.iter().filter(|(_, c)| c.1 > 0).collect();
+        failing.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then_with(|| a.0.cmp(b.0)));
+        stats.most_failed = failing
+            .into_iter()
+            .take(limit)
+            .map(|(command, (runs, failed, ..))| (command.to_string(), *failed, *runs))
+            .collect();
+
+        let mut timed: Vec<_> = ranked
+            .iter()
+            .filter(|(_, c)| c.2 > 0)
+            .map(|(command, (_, _, count, total))| {
+                (command.to_string(), *total / *count, *count as usize)
+            })
+            .collect();
+        timed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        timed.truncate(limit);
+        stats.slowest = timed;
+
+        let mut directories: Vec<_> = directories
+            .into_iter()
+            .map(|(dir, count)| (dir.clone(), count))
+            .collect();
+        directories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        directories.truncate(limit);
+        stats.directories = directories;
+        stats
+    }
+}
+
+impl Drop for SmartHistory {
+    fn drop(&mut self) {
+        self.save_pending();
     }
 }
 
-/// History statistics
-pub struct HistoryStats<'a> {
+/// History statistics, shared with the `history stats` command
+#[derive(Clone, Debug, Default)]
+pub struct HistoryStats {
     pub total_commands: usize,
     pub unique_commands: usize,
     pub successful: usize,
     pub failed: usize,
-    pub most_used: Vec<(&'a str, usize)>,
+    /// Commands and how often they ran, most first
+    pub most_used: Vec<(String, usize)>,
+    /// Commands with failures: (command, failures, runs), most failures first
+    pub most_failed: Vec<(String, usize, usize)>,
+    /// Average run time: (command, average, timed runs), slowest first
+    pub slowest: Vec<(String, Duration, usize)>,
+    /// Directories and how many commands ran there, most first
+    pub directories: Vec<(PathBuf, usize)>,
+    /// Commands started in each hour of the day
+    pub by_hour: [usize; 24],
 }
 
 #[cfg(test)]
@@ -543,6 +965,18 @@ mod tests {
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn test_restore() {
+        let mut history = SmartHistory::new(100);
+        let cwd = PathBuf::from("/home/user/project");
+        history.add("git status", cwd.clone(), None);
+        history.restore(&["cargo test".to_string(), "cargo run".to_string()], &cwd);
+
+        assert_eq!(history.previous(), Some("cargo run"));
+        assert_eq!(history.previous(), Some("cargo test"));
+        assert_eq!(history.current_session().len(), 3);
+    }
+
     #[test]
     fn test_search() {
         let mut history = SmartHistory::new(100);
@@ -557,6 +991,35 @@ mod tests {
         assert_eq!(results.len(), 3);
     }
 
+    #[test]
+    fn test_previous_with_prefix() {
+        let mut history = SmartHistory::new(100);
+        let cwd = PathBuf::from("/home/user/project");
+
+        history.add("git status", cwd.clone(), None);
+        history.add("cargo build", cwd.clone(), None);
+        history.add("git push", cwd.clone(), None);
+        history.add("git status", cwd.clone(), None);
+        history.add("git", cwd.clone(), None);
+
+        // Newest first, duplicates and the prefix itself skipped
+        assert_eq!(history.previous_with_prefix("git"), Some("git status"));
+        assert_eq!(history.navigation_prefix(), Some("git"));
+        assert_eq!(history.previous_with_prefix("git"), Some("git push"));
+        assert_eq!(history.previous_with_prefix("git"), Some("git push"));
+        assert_eq!(history.next(), Some("git status"));
+        assert_eq!(history.next(), None);
+        assert!(!history.is_navigating());
+
+        history.reset_position();
+        assert_eq!(history.previous_with_prefix("npm"), None);
+        assert!(!history.is_navigating());
+
+        // Empty input walks everything
+        assert_eq!(history.previous_with_prefix(""), Some("git"));
+        assert_eq!(history.navigation_prefix(), None);
+    }
+
     #[test]
     fn test_suggestions() {
         let mut history = SmartHistory::new(100);
@@ -571,4 +1034,171 @@ mod tests {
         assert!(!suggestions.is_empty());
         assert!(suggestions[0].contains("cargo"));
     }
+
+    #[test]
+    fn test_frecent() {
+        let mut history = SmartHistory::new(100);
+        let cwd = PathBuf::from("/home/user/project");
+        let other = PathBuf::from("/tmp");
+
+        history.add("cargo build", cwd.clone(), None);
+        history.add("cargo test", cwd.clone(), None);
+        history.add("gs", cwd.clone(), None);
+        history.add("cargo test", cwd.clone(), None);
+        history.add("make", other.clone(), None);
+        history.add("cargo tset", cwd.clone(), None);
+        history.complete_last(127, Duration::ZERO, None);
+
+        assert_eq!(
+            history.frecent(&cwd, 3),
+            vec!["cargo test", "gs", "cargo build"]
+        );
+        assert_eq!(history.frecent(&other, 3), vec!["make"]);
+        assert!(history.frecent(&PathBuf::from("/"), 3).is_empty());
+    }
+
+    #[test]
+    fn test_remove_and_clear() {
+        let mut history = SmartHistory::new(100);
+        let cwd = PathBuf::from("/home/user/project");
+
+        history.add("export TOKEN=secret", cwd.clone(), None);
+        history.add("git status", cwd.clone(), None);
+        history.add("cargo build", cwd.clone(), None);
+
+        history.remove(0..1);
+        assert_eq!(history.len(), 2);
+        assert!(history.suggest("export", &cwd, 5).is_empty());
+
+        history.clear();
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut history = SmartHistory::new(100);
+        let cwd = PathBuf::from("/home/user/project");
+        let runs = [
+            ("cargo test", 101, 4000),
+            ("git status", 0, 20),
+            ("cargo test", 0, 2000),
+            ("ls", 0, 5),
+            ("cargo test", 101, 3000),
+            ("make", 2, 100),
+        ];
+        for (command, exit_code, ms) in runs {
+            history.add(command, cwd.clone(), None);
+            history.complete_last(exit_code, Duration::from_millis(ms), None);
+        }
+        history.add("ls", PathBuf::from("/tmp"), None);
+
+        let stats = history.stats(2);
+        assert_eq!(stats.total_commands, 7);
+        assert_eq!(stats.unique_commands, 4);
+        assert_eq!((stats.successful, stats.failed), (3, 3));
+        assert_eq!(
+            stats.most_used,
+            [("cargo test".to_string(), 3), ("ls".to_string(), 2)]
+        );
+        assert_eq!(
+            stats.most_failed,
+            [("cargo test".to_string(), 2, 3), ("make".to_string(), 1, 1)]
+        );
+        assert_eq!(
+            stats.slowest[0],
+            ("cargo test".to_string(), Duration::from_millis(3000), 3)
+        );
+        assert_eq!(stats.directories, [(cwd, 6), (PathBuf::from("/tmp"), 1)]);
+        assert_eq!(stats.by_hour.iter().sum::<usize>(), 7);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let path =
+            std::env::temp_dir().join(format!("zaxiom-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cwd = PathBuf::from("/home/user/project");
+
+        // A command is written once it finishes, with how it went
+        let mut first = SmartHistory::persistent(path.clone(), 100);
+        first.add("cargo build", cwd.clone(), None);
+        first.complete_last(101, Duration::from_millis(1500), None);
+
+        // Another pane sees it; its own running command is written when it closes
+        let mut second = SmartHistory::persistent(path.clone(), 100);
+        second.add("cargo build", cwd.clone(), None);
+        second.add("ls", PathBuf::from("/tmp"), None);
+        drop(second);
+
+        // ...and the first pane merges that in before its next command
+        first.add("git status", cwd.clone(), None);
+        let commands: Vec<_> = first.all().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, ["cargo build", "ls", "git status"]);
+        drop(first);
+
+        // Restarting loads everything, repeats collapsed
+        let mut restored = SmartHistory::persistent(path.clone(), 100);
+        let entries: Vec<_> = restored.all().collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].exit_code, Some(101));
+        assert_eq!(entries[0].duration, Some(Duration::from_millis(1500)));
+        assert_eq!(entries[1].cwd, PathBuf::from("/tmp"));
+        assert!(entries[2].exit_code.is_none());
+
+        // Deleting rewrites the file
+        restored.remove(0..1);
+        drop(restored);
+        let mut restored = SmartHistory::persistent(path.clone(), 100);
+        assert_eq!(restored.len(), 2);
+        restored.clear();
+        assert!(SmartHistory::persistent(path.clone(), 100).is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_recent_files() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-recent-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        for file in ["a.txt", "src/b.rs", "my notes.md"] {
+            std::fs::write(dir.join(file), "x").unwrap();
+        }
+
+        let mut history = SmartHistory::new(100);
+        history.add("cat a.txt missing.txt", dir.clone(), None);
+        history.add("nano \"my notes.md\"", dir.clone(), None);
+        history.add("grep -n x ./src/b.rs a.txt src", dir.clone(), None);
+        history.add("cat b.rs", dir.join("src"), None);
+
+        let files: Vec<_> = history
+            .recent_files(10)
+            .into_iter()
+            .map(|(path, entry)| (path, entry.command.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                (dir.join("src").join("b.rs"), "cat b.rs"),
+                (dir.join("a.txt"), "grep -n x ./src/b.rs a.txt src"),
+                (dir.join("my notes.md"), "nano \"my notes.md\""),
+            ]
+        );
+        assert_eq!(history.recent_files(1).len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recent_dirs() {
+        let dir = std::env::temp_dir().join(format!("zaxiom-recent-dirs-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+
+        let mut history = SmartHistory::new(100);
+        history.add("ls", dir.clone(), None);
+        history.add("ls", dir.join("gone"), None);
+        history.add("cargo build", dir.join("src"), None);
+        history.add("git status", dir.clone(), None);
+
+        assert_eq!(history.recent_dirs(10), [dir.clone(), dir.join("src")]);
+        assert_eq!(history.recent_dirs(1).len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,11 +1,24 @@
 //! Split pane management
 //!
-//! Handles horizontal and vertical terminal splits.
+//! Handles horizontal and vertical terminal splits, and resizing them by
+//! dragging the divider between two panes or with Ctrl+Shift+Arrow.
 
 #![allow(dead_code)]
 
 use eframe::egui;
 
+use super::layout::Layout;
+
+/// Smallest share of a split either side can be resized to (matches what
+/// layout specs accept)
+pub const MIN_RATIO: f32 = 0.1;
+
+/// Largest share of a split either side can be resized to
+pub const MAX_RATIO: f32 = 0.9;
+
+/// How far one Ctrl+Shift+Arrow press moves a divider
+pub const RESIZE_STEP: f32 = 0.05;
+
 /// Direction of a split
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SplitDirection {
@@ -38,6 +51,29 @@ pub enum SplitNode {
     },
 }
 
+/// The divider between the two sides of a split, for dragging
+#[derive(Clone, Copy, Debug)]
+pub struct Divider {
+    /// Which split it belongs to (see `SplitManager::set_ratio`)
+    pub index: usize,
+    pub direction: SplitDirection,
+    /// Area shared by the two sides
+    pub area: egui::Rect,
+    /// Where the divider is drawn
+    pub line: egui::Rect,
+}
+
+impl Divider {
+    /// Ratio that puts the divider at `pos`
+    pub fn ratio_at(&self, pos: egui::Pos2) -> f32 {
+        let ratio = match self.direction {
+            SplitDirection::Horizontal => (pos.y - self.area.top()) / self.area.height(),
+            SplitDirection::Vertical => (pos.x - self.area.left()) / self.area.width(),
+        };
+        ratio.clamp(MIN_RATIO, MAX_RATIO)
+    }
+}
+
 /// Split pane manager
 pub struct SplitManager {
     /// Root of the split tree
@@ -242,6 +278,103 @@ impl SplitManager {
         }
     }
 
+    /// Move the divider nearest to the focused pane that splits in
+    /// `direction` by `delta` (positive moves it right or down). Returns
+    /// whether there was one to move.
+    pub fn resize_toward(&mut self, direction: SplitDirection, delta: f32) -> bool {
+        fn nudge(
+            node: &mut SplitNode,
+            target: usize,
+            direction: SplitDirection,
+            delta: f32,
+        ) -> bool {
+            let SplitNode::Split {
+                direction: split_direction,
+                ratio,
+                first,
+                second,
+            } = node
+            else {
+                return false;
+            };
+            let child = if contains(first, target) {
+                first
+            } else if contains(second, target) {
+                second
+            } else {
+                return false;
+            };
+            // The innermost matching split is the one beside the pane
+            if nudge(child, target, direction, delta) {
+                return true;
+            }
+            if *split_direction != direction {
+                return false;
+            }
+            *ratio = (*ratio + delta).clamp(MIN_RATIO, MAX_RATIO);
+            true
+        }
+        nudge(&mut self.root, self.focused_pane, direction, delta)
+    }
+
+    /// Set the ratio of the split a divider belongs to
+    pub fn set_ratio(&mut self, index: usize, ratio: f32) {
+        fn find(node: &mut SplitNode, index: &mut usize, value: f32) -> bool {
+            let SplitNode::Split {
+                ratio,
+                first,
+                second,
+                ..
+            } = node
+            else {
+                return false;
+            };
+            if *index == 0 {
+                *ratio = value.clamp(MIN_RATIO, MAX_RATIO);
+                return true;
+            }
+            *index -= 1;
+            find(first, index, value) || find(second, index, value)
+        }
+        find(&mut self.root, &mut { index }, ratio);
+    }
+
+    /// Dividers between panes laid out in `available`
+    pub fn dividers(&self, available: egui::Rect) -> Vec<Divider> {
+        fn collect(node: &SplitNode, rect: egui::Rect, dividers: &mut Vec<Divider>) {
+            if let SplitNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } = node
+            {
+                let (first_rect, second_rect) = split_rect(rect, *direction, *ratio);
+                let line = match direction {
+                    SplitDirection::Horizontal => egui::Rect::from_x_y_ranges(
+                        rect.x_range(),
+                        first_rect.bottom()..=first_rect.bottom(),
+                    ),
+                    SplitDirection::Vertical => egui::Rect::from_x_y_ranges(
+                        first_rect.right()..=first_rect.right(),
+                        rect.y_range(),
+                    ),
+                };
+                dividers.push(Divider {
+                    index: dividers.len(),
+                    direction: *direction,
+                    area: rect,
+                    line,
+                });
+                collect(first, first_rect, dividers);
+                collect(second, second_rect, dividers);
+            }
+        }
+        let mut dividers = Vec::new();
+        collect(&self.root, available, &mut dividers);
+        dividers
+    }
+
     /// Check if a node contains a pane
     fn contains_pane(&self, node: &SplitNode, pane_id: usize) -> bool {
         match node {
@@ -253,7 +386,7 @@ impl SplitManager {
     }
 
     /// Get all pane IDs in order
-    fn all_pane_ids(&self) -> Vec<usize> {
+    pub fn all_pane_ids(&self) -> Vec<usize> {
         let mut ids = Vec::new();
         self.collect_pane_ids(&self.root, &mut ids);
         ids
@@ -294,6 +427,83 @@ impl SplitManager {
         self.broadcast_mode
     }
 
+    /// Rearrange panes into a layout, reusing existing pane IDs in order.
+    /// Returns (added pane IDs, removed pane IDs) so the caller can create
+    /// or drop the matching sessions.
+    pub fn apply_layout(&mut self, layout: &Layout) -> (Vec<usize>, Vec<usize>) {
+        let mut existing = self.all_pane_ids().into_iter();
+        let mut added = Vec::new();
+        self.root = self.build_node(layout, &mut existing, &mut added);
+        let removed: Vec<usize> = existing.collect();
+
+        // Keep focus where it was if that pane survived
+        let focus = if self.contains_pane(&self.root, self.focused_pane) {
+            Some(self.focused_pane)
+        } else {
+            self.first_pane_id()
+        };
+        if let Some(id) = focus {
+            self.focus_pane(id);
+        }
+
+        (added, removed)
+    }
+
+    fn build_node(
+        &mut self,
+        layout: &Layout,
+        existing: &mut impl Iterator<Item = usize>,
+        added: &mut Vec<usize>,
+    ) -> SplitNode {
+        match layout {
+            Layout::Pane => {
+                let id = existing.next().unwrap_or_else(|| {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    added.push(id);
+                    id
+                });
+                SplitNode::Pane(Pane {
+                    id,
+                    ratio: 1.0,
+                    focused: false,
+                })
+            }
+            Layout::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => SplitNode::Split {
+                direction: *direction,
+                ratio: *ratio,
+                first: Box::new(self.build_node(first, existing, added)),
+                second: Box::new(self.build_node(second, existing, added)),
+            },
+        }
+    }
+
+    /// The current arrangement as a layout (for saving)
+    pub fn layout(&self) -> Layout {
+        fn convert(node: &SplitNode) -> Layout {
+            match node {
+                SplitNode::Pane(_) => Layout::Pane,
+                SplitNode::Split {
+                    direction,
+                    ratio,
+                    first,
+                    second,
+                } => Layout::Split {
+                    direction: *direction,
+                    ratio: *ratio,
+                    first: Box::new(convert(first)),
+                    second: Box::new(convert(second)),
+                },
+            }
+        }
+        convert(&self.root)
+    }
+
     /// Calculate layout rectangles for all panes
     pub fn calculate_layout(&self, available: egui::Rect) -> Vec<(usize, egui::Rect)> {
         let mut layouts = Vec::new();
@@ -317,23 +527,7 @@ impl SplitManager {
                 first,
                 second,
             } => {
-                let (first_rect, second_rect) = match direction {
-                    SplitDirection::Horizontal => {
-                        let split_y = rect.top() + rect.height() * ratio;
-                        (
-                            egui::Rect::from_min_max(rect.min, egui::pos2(rect.right(), split_y)),
-                            egui::Rect::from_min_max(egui::pos2(rect.left(), split_y), rect.max),
-                        )
-                    }
-                    SplitDirection::Vertical => {
-                        let split_x = rect.left() + rect.width() * ratio;
-                        (
-                            egui::Rect::from_min_max(rect.min, egui::pos2(split_x, rect.bottom())),
-                            egui::Rect::from_min_max(egui::pos2(split_x, rect.top()), rect.max),
-                        )
-                    }
-                };
-
+                let (first_rect, second_rect) = split_rect(rect, *direction, *ratio);
                 self.layout_node(first, first_rect, layouts);
                 self.layout_node(second, second_rect, layouts);
             }
@@ -341,8 +535,114 @@ impl SplitManager {
     }
 }
 
+/// Whether a node contains a pane
+fn contains(node: &SplitNode, pane_id: usize) -> bool {
+    match node {
+        SplitNode::Pane(p) => p.id == pane_id,
+        SplitNode::Split { first, second, .. } => {
+            contains(first, pane_id) || contains(second, pane_id)
+        }
+    }
+}
+
+/// The two sides of a split of `rect`
+fn split_rect(rect: egui::Rect, direction: SplitDirection, ratio: f32) -> (egui::Rect, egui::Rect) {
+    match direction {
+        SplitDirection::Horizontal => {
+            let split_y = rect.top() + rect.height() * ratio;
+            (
+                egui::Rect::from_min_max(rect.min, egui::pos2(rect.right(), split_y)),
+                egui::Rect::from_min_max(egui::pos2(rect.left(), split_y), rect.max),
+            )
+        }
+        SplitDirection::Vertical => {
+            let split_x = rect.left() + rect.width() * ratio;
+            (
+                egui::Rect::from_min_max(rect.min, egui::pos2(split_x, rect.bottom())),
+                egui::Rect::from_min_max(egui::pos2(split_x, rect.top()), rect.max),
+            )
+        }
+    }
+}
+
 impl Default for SplitManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_layout_reuses_and_adds_panes() {
+        let mut splits = SplitManager::new();
+        splits.split(SplitDirection::Vertical);
+        splits.focus_pane(0);
+
+        let (added, removed) = splits.apply_layout(&Layout::preset("grid").unwrap());
+        assert_eq!(added, vec![2, 3]);
+        assert!(removed.is_empty());
+        assert_eq!(splits.all_pane_ids(), vec![0, 1, 2, 3]);
+        assert_eq!(splits.focused_pane_id(), 0);
+
+        let (added, removed) = splits.apply_layout(&Layout::Pane);
+        assert!(added.is_empty());
+        assert_eq!(removed, vec![1, 2, 3]);
+        assert_eq!(splits.pane_count(), 1);
+    }
+
+    #[test]
+    fn test_resize() {
+        // Side by side, the right side stacked: v(pane 0, h(pane 1, pane 2))
+        let mut splits = SplitManager::new();
+        splits.apply_layout(&Layout::preset("main-stack").unwrap());
+        splits.focus_pane(2);
+
+        assert!(splits.resize_toward(SplitDirection::Vertical, RESIZE_STEP));
+        assert!(splits.resize_toward(SplitDirection::Horizontal, -RESIZE_STEP));
+        assert_eq!(
+            splits.layout().to_spec(),
+            "v(0.65, pane, h(0.45, pane, pane))"
+        );
+
+        // Pane 0 has no stacked neighbour to resize against
+        splits.focus_pane(0);
+        assert!(!splits.resize_toward(SplitDirection::Horizontal, RESIZE_STEP));
+        for _ in 0..20 {
+            splits.resize_toward(SplitDirection::Vertical, -RESIZE_STEP);
+        }
+        assert_eq!(
+            splits.layout().to_spec(),
+            "v(0.10, pane, h(0.45, pane, pane))"
+        );
+
+        // Dragging the divider between the stacked panes
+        let area = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1000.0, 500.0));
+        let dividers = splits.dividers(area);
+        assert_eq!(dividers.len(), 2);
+        assert_eq!(dividers[0].line.left(), 100.0);
+        let stacked = dividers[1];
+        assert_eq!(stacked.direction, SplitDirection::Horizontal);
+        let ratio = stacked.ratio_at(egui::pos2(500.0, 375.0));
+        splits.set_ratio(stacked.index, ratio);
+        assert_eq!(
+            splits.layout().to_spec(),
+            "v(0.10, pane, h(0.75, pane, pane))"
+        );
+
+        // The proportions survive a save and restore through the layout spec
+        let mut restored = SplitManager::new();
+        restored.apply_layout(&Layout::parse(&splits.layout().to_spec()).unwrap());
+        assert_eq!(restored.layout(), splits.layout());
+    }
+
+    #[test]
+    fn test_layout_roundtrip() {
+        let layout = Layout::preset("main-stack").unwrap();
+        let mut splits = SplitManager::new();
+        splits.apply_layout(&layout);
+        assert_eq!(splits.layout(), layout);
+    }
+}
@@ -1,6 +1,11 @@
 //! Terminal state management
 //!
 //! Tracks current working directory, environment variables, and other state.
+//! Each pane owns one, and everything a command can change - the working
+//! directory, exported variables, the pushd stack - lives here instead of
+//! in the process, so panes running commands at the same time can't change
+//! each other's. The process working directory and environment are never
+//! modified; programs are started with the pane's values instead.
 
 #![allow(dead_code)]
 
@@ -8,9 +13,17 @@ use std::collections::HashMap;
 use std::env;
 use std::path::PathBuf;
 
-use crate::config::settings::Config;
-use crate::config::theme::{icons, kawaii_icons, ThemeName};
+use crate::commands::net::MockServer;
+use crate::config::aliases::AliasConfig;
+use crate::config::keymap::Keymap;
+use crate::config::settings::{Config, FontConfig, KawaiiFeatures, KawaiiLevel, TimestampMode};
+use crate::config::theme::{icons, kawaii_icons, ColorVision, ThemeName, UserTheme};
 use crate::git::prompt::get_git_branch;
+use crate::shell::job::JobTable;
+use crate::terminal::buffer::BufferStats;
+use crate::terminal::layout::Layout;
+use crate::terminal::session::{SavedSession, Workspace};
+use crate::terminal::smart_history::HistoryStats;
 
 /// Terminal state
 pub struct TerminalState {
@@ -20,14 +33,201 @@ pub struct TerminalState {
     home: PathBuf,
     /// User-defined aliases
     aliases: HashMap<String, String>,
+    /// Shell functions defined by scripts or at the prompt (name -> body)
+    functions: HashMap<String, String>,
     /// Previous directory for `cd -`
     prev_cwd: Option<PathBuf>,
+    /// Variables set with `export` in this pane, on top of the process environment
+    env: HashMap<String, String>,
+    /// Directories saved by `pushd`, most recent last
+    pub dir_stack: Vec<PathBuf>,
+    /// How many `source`d scripts are running inside each other
+    pub script_depth: usize,
+    /// Jobs started with `&` or suspended with Ctrl+Z (shared with snapshots)
+    jobs: JobTable,
+    /// Job brought to the foreground by `fg` (checked by app after command execution)
+    pub requested_foreground: Option<usize>,
     /// Requested theme change (checked by app after command execution)
     pub requested_theme: Option<ThemeName>,
-    /// Current active theme
+    /// Current active theme (a user theme's base while one is in use)
     pub current_theme: ThemeName,
-    /// Kawaii mode - cuter UI elements
-    pub kawaii_mode: bool,
+    /// Requested switch to a theme file (checked by app after command execution)
+    pub requested_user_theme: Option<UserTheme>,
+    /// Name of the user theme in use, if any
+    pub current_user_theme: Option<String>,
+    /// Requested auto theme on/off (checked by app after command execution)
+    pub requested_auto_theme: Option<bool>,
+    /// Requested color vision mode (checked by app after command execution)
+    pub requested_color_vision: Option<ColorVision>,
+    /// Kawaii level - how much cute flair to show
+    pub kawaii_level: KawaiiLevel,
+    /// Kawaii features resolved from the level and config overrides
+    pub kawaii: KawaiiFeatures,
+    /// Output of the most recent command (for AI context)
+    pub last_output: Option<String>,
+    /// Another pane's output, attached by the app for `# --pane N` prompts
+    pub pane_context: Option<PaneContext>,
+    /// Requested pane layout change (checked by app after command execution)
+    pub requested_layout: Option<LayoutRequest>,
+    /// User-given pane name, shown in the split pane header
+    pub pane_name: Option<String>,
+    /// Size of this pane's text in points
+    pub font_size: f32,
+    /// Requested text size change (checked by app after command execution)
+    pub requested_zoom: Option<ZoomRequest>,
+    /// Requested tips change (checked by app after command execution)
+    pub requested_tips: Option<TipsRequest>,
+    /// This pane's command history, shared by the app for `history` and `fc`
+    pub history: Vec<String>,
+    /// Requested history change (checked by app after command execution)
+    pub requested_history: Option<HistoryRequest>,
+    /// Requested audit logging on/off (checked by app after command execution)
+    pub requested_audit: Option<bool>,
+    /// Requested timestamp gutter mode (checked by app after command execution)
+    pub requested_timestamps: Option<TimestampMode>,
+    /// Saved global/suffix aliases to apply (checked by app after command execution)
+    pub requested_aliases: Option<AliasConfig>,
+    /// Rebound shortcuts to apply (checked by app after command execution)
+    pub requested_keymap: Option<Keymap>,
+    /// Workspace to save or open (checked by app after command execution)
+    pub requested_workspace: Option<WorkspaceRequest>,
+    /// Session to save, restore, or pick (checked by app after command execution)
+    pub requested_session: Option<SessionRequest>,
+    /// Mock HTTP server started by `mock` in this pane
+    pub mock_server: Option<MockServer>,
+    /// This pane's scrollback size, shared by the app for `buffer stats`
+    pub buffer_stats: Option<BufferStats>,
+    /// Figures from this pane's history, shared by the app for `history stats`
+    pub history_stats: Option<HistoryStats>,
+    /// Requested scrollback trim to the newest N lines (checked by app after command execution)
+    pub requested_buffer_trim: Option<usize>,
+    /// Requested lines kept in memory by every pane (checked by app after command execution)
+    pub requested_scrollback: Option<usize>,
+    /// File to write the whole scrollback to (checked by app after command execution)
+    pub requested_buffer_export: Option<PathBuf>,
+    /// grep/find output to open in the fuzzy finder (`--pick`, checked by app after command execution)
+    pub requested_pick: Option<String>,
+    /// Text to show in the pager (`less`, checked by app after command execution)
+    pub requested_pager: Option<PagerRequest>,
+    /// Requested usage dashboard or recording change (checked by app after command execution)
+    pub requested_stats: Option<StatsRequest>,
+    /// Day whose digest was requested by the `digest` command (checked by app after command execution)
+    pub requested_digest: Option<chrono::NaiveDate>,
+    /// Set by `echo -n` and `printf` when their output has no final newline,
+    /// so `>`/`>>` write it as-is (reset before each command)
+    pub omit_newline: bool,
+    /// Set by the executor when a command's output goes to the next command
+    /// in a pipeline or to a file rather than the screen, so it can leave out
+    /// colors and decoration (as `cat` does with syntax highlighting)
+    pub output_piped: bool,
+}
+
+/// History changes requested by the `history` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryRequest {
+    /// Delete entries `start..=end` (1-based, as numbered by `history`)
+    Delete { start: usize, end: usize },
+    /// Forget the whole history
+    Clear,
+}
+
+/// Usage statistics changes requested by the `stats` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsRequest {
+    /// Open the dashboard
+    Show,
+    /// Start or stop recording
+    Record(bool),
+}
+
+/// Tips changes requested by the `tips` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TipsRequest {
+    /// Never show the most recent tip again
+    Dismiss,
+    /// Forget dismissed and shown tips
+    Reset,
+}
+
+/// Smallest pane text size, in points
+pub const MIN_FONT_SIZE: f32 = 8.0;
+/// Largest pane text size, in points
+pub const MAX_FONT_SIZE: f32 = 40.0;
+
+/// Pane text size changes from the `zoom` command or Ctrl+= / Ctrl+- / Ctrl+0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoomRequest {
+    /// One point larger
+    In,
+    /// One point smaller
+    Out,
+    /// Back to the default size
+    Reset,
+    /// A size in points
+    Size(f32),
+}
+
+impl ZoomRequest {
+    /// Text size after this change to `size`
+    pub fn apply(self, size: f32) -> f32 {
+        match self {
+            Self::In => size + 1.0,
+            Self::Out => size - 1.0,
+            Self::Reset => FontConfig::default().size,
+            Self::Size(size) => size,
+        }
+        .clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+    }
+}
+
+/// Pane layout changes requested by the `layout` command
+#[derive(Debug, Clone)]
+pub enum LayoutRequest {
+    /// Rearrange the current tab's panes
+    Apply(Layout),
+    /// Save the current tab's arrangement under a name
+    Save(String),
+}
+
+/// Workspace changes requested by the `workspace` command
+#[derive(Debug, Clone)]
+pub enum WorkspaceRequest {
+    /// Save the window's tabs and panes under a name
+    Save(String),
+    /// Open a saved workspace's tabs
+    Open(Workspace),
+}
+
+/// Session changes requested by the `session` command
+#[derive(Debug, Clone)]
+pub enum SessionRequest {
+    /// Save the window's tabs under a name
+    Save(String),
+    /// Reopen a saved session's tabs
+    Restore(SavedSession),
+    /// Show the saved sessions to pick one
+    Pick,
+}
+
+/// Text for the pager, requested by `less`
+#[derive(Debug, Clone)]
+pub struct PagerRequest {
+    /// File name (or what was piped in) for the status line
+    pub title: String,
+    pub text: String,
+}
+
+/// The last command and output of a pane, shared with AI on request
+#[derive(Debug, Clone)]
+pub struct PaneContext {
+    /// Pane number as shown in the pane header
+    pub label: usize,
+    /// The command that produced the output
+    pub command: String,
+    /// The command's output
+    pub output: String,
+    /// Whether the command succeeded
+    pub success: bool,
 }
 
 impl TerminalState {
@@ -36,30 +236,121 @@ impl TerminalState {
         let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         // Start in home directory like a normal terminal
         let cwd = home.clone();
-        // Set the process cwd as well
-        let _ = env::set_current_dir(&cwd);
 
-        // Load kawaii mode from config
+        // Load kawaii level from config
         let config = Config::load();
-        let kawaii_mode = config.kawaii_mode;
+        let kawaii_level = config.kawaii_level();
+        let kawaii = config.kawaii_features();
 
         Self {
             cwd,
             home,
             aliases: HashMap::new(),
+            functions: HashMap::new(),
             prev_cwd: None,
+            env: HashMap::new(),
+            dir_stack: Vec::new(),
+            script_depth: 0,
+            jobs: JobTable::default(),
+            requested_foreground: None,
             requested_theme: None,
             current_theme: ThemeName::CatppuccinMocha, // Default theme
-            kawaii_mode,
+            requested_user_theme: None,
+            current_user_theme: None,
+            requested_auto_theme: None,
+            requested_color_vision: None,
+            kawaii_level,
+            kawaii,
+            last_output: None,
+            pane_context: None,
+            requested_layout: None,
+            pane_name: None,
+            font_size: config.font.size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE),
+            requested_zoom: None,
+            requested_tips: None,
+            history: Vec::new(),
+            requested_history: None,
+            requested_audit: None,
+            requested_timestamps: None,
+            requested_aliases: None,
+            requested_keymap: None,
+            requested_workspace: None,
+            requested_session: None,
+            mock_server: None,
+            requested_pick: None,
+            requested_pager: None,
+            requested_stats: None,
+            requested_digest: None,
+            buffer_stats: None,
+            history_stats: None,
+            requested_buffer_trim: None,
+            requested_scrollback: None,
+            requested_buffer_export: None,
+            omit_newline: false,
+            output_piped: false,
         }
     }
 
-    /// Set kawaii mode and persist to config
-    pub fn set_kawaii_mode(&mut self, enabled: bool) {
-        self.kawaii_mode = enabled;
-        // Persist to config
+    /// A copy of what commands read from this state (directories, variables,
+    /// aliases, functions, history, kawaii settings) for a background job. Pending
+    /// requests and the mock server stay behind; the job table is shared.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            cwd: self.cwd.clone(),
+            home: self.home.clone(),
+            aliases: self.aliases.clone(),
+            functions: self.functions.clone(),
+            prev_cwd: self.prev_cwd.clone(),
+            env: self.env.clone(),
+            dir_stack: self.dir_stack.clone(),
+            script_depth: 0,
+            jobs: self.jobs.clone(),
+            requested_foreground: None,
+            requested_theme: None,
+            current_theme: self.current_theme,
+            requested_user_theme: None,
+            current_user_theme: self.current_user_theme.clone(),
+            requested_auto_theme: None,
+            requested_color_vision: None,
+            kawaii_level: self.kawaii_level,
+            kawaii: self.kawaii,
+            last_output: self.last_output.clone(),
+            pane_context: self.pane_context.clone(),
+            requested_layout: None,
+            pane_name: self.pane_name.clone(),
+            font_size: self.font_size,
+            requested_zoom: None,
+            requested_tips: None,
+            history: self.history.clone(),
+            requested_history: None,
+            requested_audit: None,
+            requested_timestamps: None,
+            requested_aliases: None,
+            requested_keymap: None,
+            requested_workspace: None,
+            requested_session: None,
+            mock_server: None,
+            requested_pick: None,
+            requested_pager: None,
+            requested_stats: None,
+            requested_digest: None,
+            buffer_stats: self.buffer_stats.clone(),
+            history_stats: self.history_stats.clone(),
+            requested_buffer_trim: None,
+            requested_scrollback: None,
+            requested_buffer_export: None,
+            omit_newline: false,
+            output_piped: false,
+        }
+    }
+
+    /// Set kawaii level and persist to config
+    pub fn set_kawaii_level(&mut self, level: KawaiiLevel) {
         let mut config = Config::load();
-        let _ = config.set_kawaii_mode(enabled);
+        self.kawaii_level = level;
+        self.kawaii = config.kawaii.features(level);
+        // Persist to config
+        let _ = config.set_kawaii_level(level);
     }
 
     /// Get current working directory
@@ -72,8 +363,38 @@ impl TerminalState {
         // Store current as previous before changing
         self.prev_cwd = Some(self.cwd.clone());
         self.cwd = path;
-        // Also update the actual process cwd
-        let _ = env::set_current_dir(&self.cwd);
+    }
+
+    /// A variable as this pane sees it (exported here, else inherited)
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.env.get(name).cloned().or_else(|| env::var(name).ok())
+    }
+
+    /// Export a variable in this pane
+    pub fn set_var(&mut self, name: &str, value: &str) {
+        self.env.insert(name.to_string(), value.to_string());
+    }
+
+    /// Every variable this pane sees, sorted by name
+    pub fn vars(&self) -> Vec<(String, String)> {
+        let mut vars: HashMap<String, String> = env::vars().collect();
+        vars.extend(self.env.clone());
+        let mut vars: Vec<_> = vars.into_iter().collect();
+        vars.sort();
+        vars
+    }
+
+    /// The directory stack as `dirs` lists it: the working directory, then
+    /// pushed directories from the most recent (entry `+n` is index n)
+    pub fn dirs(&self) -> Vec<PathBuf> {
+        std::iter::once(self.cwd.clone())
+            .chain(self.dir_stack.iter().rev().cloned())
+            .collect()
+    }
+
+    /// Variables exported in this pane, to pass to programs it starts
+    pub fn exported_vars(&self) -> &HashMap<String, String> {
+        &self.env
     }
 
     /// Get previous working directory
@@ -87,13 +408,17 @@ impl TerminalState {
     }
 
     /// Format prompt string with iTerm/Warp-style icons
-    /// Format:  ~/path/to/dir  branch ❯  (or ♡ in kawaii mode)
+    /// Format:  ~/path/to/dir  branch ❯  (or ♡ in kawaii mode), with the
+    /// directory stack depth after the path (`~/src +2`) while pushd is in use
     pub fn format_prompt(&self) -> String {
-        let (icon, display_path) = self.format_path_display();
+        let (icon, mut display_path) = self.format_path_display();
+        if !self.dir_stack.is_empty() {
+            display_path.push_str(&format!(" +{}", self.dir_stack.len()));
+        }
         let git_branch = get_git_branch(&self.cwd);
 
         // Use kawaii icons when kawaii mode is enabled
-        let (prompt_icon, git_icon) = if self.kawaii_mode {
+        let (prompt_icon, git_icon) = if self.kawaii.palette {
             (kawaii_icons::PROMPT, kawaii_icons::GIT_BRANCH)
         } else {
             (icons::PROMPT, icons::GIT_BRANCH)
@@ -200,6 +525,21 @@ impl TerminalState {
         aliases.sort_by_key(|(name, _)| *name);
         aliases
     }
+
+    /// The pane's numbered jobs
+    pub fn jobs(&self) -> &JobTable {
+        &self.jobs
+    }
+
+    /// Get a shell function's body by name
+    pub fn get_function(&self, name: &str) -> Option<String> {
+        self.functions.get(name).cloned()
+    }
+
+    /// Define (or redefine) a shell function
+    pub fn set_function(&mut self, name: String, body: String) {
+        self.functions.insert(name, body);
+    }
 }
 
 impl Default for TerminalState {
@@ -207,3 +547,8 @@ impl Default for TerminalState {
         Self::new()
     }
 }
+
+/// The index in a `+n` directory stack argument (`cd +2`, `popd +1`)
+pub fn stack_index(arg: &str) -> Option<usize> {
+    arg.strip_prefix('+')?.parse().ok()
+}
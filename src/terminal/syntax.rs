@@ -1,20 +1,149 @@
 //! Syntax Highlighting Module
 //!
-//! Provides syntax highlighting for source code files using syntect.
+//! The one highlighting engine behind cat, the nano editor overlay, file
+//! previews, markdown code blocks, and diffs. Parsing uses syntect's default
+//! grammars, plus aliases that give related languages (TypeScript, Kotlin,
+//! Elixir, TOML, Dockerfiles, ...) the closest grammar it ships. Colors
+//! come from the active app theme: `set_theme` maps its palette onto code
+//! scopes (strings, keywords, comments, ...).
 
 #![allow(dead_code)]
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use egui::Color32;
 use once_cell::sync::Lazy;
-use syntect::easy::HighlightLines;
-use syntect::highlighting::{Style, ThemeSet};
-use syntect::parsing::SyntaxSet;
+use syntect::highlighting::{
+    Color, FontStyle, HighlightIterator, HighlightState, Highlighter, ScopeSelectors, Style,
+    StyleModifier, Theme as CodeTheme, ThemeItem, ThemeSettings,
+};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
 use syntect::util::LinesWithEndings;
 
+use crate::config::theme::Theme;
+
 /// Lazy-loaded syntax set
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
 
-/// Lazy-loaded theme set
-static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+/// Code colors for the active app theme
+static CODE_THEME: Lazy<RwLock<Arc<CodeTheme>>> =
+    Lazy::new(|| RwLock::new(Arc::new(code_theme(&Theme::default()))));
+
+/// Bumped by every `set_theme`, so cached colors know to refresh
+static THEME_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Languages without a grammar of their own: (extension or name, language,
+/// grammar used for it)
+const ALIASES: &[(&str, &str, &str)] = &[
+    ("ts", "TypeScript", "JavaScript"),
+    ("mts", "TypeScript", "JavaScript"),
+    ("cts", "TypeScript", "JavaScript"),
+    ("typescript", "TypeScript", "JavaScript"),
+    ("tsx", "TSX", "JavaScript"),
+    ("jsx", "JSX", "JavaScript"),
+    ("json5", "JSON5", "JSON"),
+    ("jsonc", "JSONC", "JSON"),
+    ("kt", "Kotlin", "Java"),
+    ("kts", "Kotlin", "Java"),
+    ("kotlin", "Kotlin", "Java"),
+    ("dart", "Dart", "Java"),
+    ("swift", "Swift", "C#"),
+    ("zig", "Zig", "Rust"),
+    ("gleam", "Gleam", "Rust"),
+    ("wgsl", "WGSL", "Rust"),
+    ("odin", "Odin", "Go"),
+    ("nim", "Nim", "Python"),
+    ("nims", "Nim", "Python"),
+    ("mojo", "Mojo", "Python"),
+    ("pyx", "Cython", "Python"),
+    ("jl", "Julia", "Ruby"),
+    ("julia", "Julia", "Ruby"),
+    ("ex", "Elixir", "Ruby"),
+    ("exs", "Elixir", "Ruby"),
+    ("elixir", "Elixir", "Ruby"),
+    ("cr", "Crystal", "Ruby"),
+    ("tf", "HCL", "Ruby"),
+    ("tfvars", "HCL", "Ruby"),
+    ("hcl", "HCL", "Ruby"),
+    ("fs", "F#", "OCaml"),
+    ("fsi", "F#", "OCaml"),
+    ("fsx", "F#", "OCaml"),
+    ("elm", "Elm", "Haskell"),
+    ("purs", "PureScript", "Haskell"),
+    ("idr", "Idris", "Haskell"),
+    ("sol", "Solidity", "JavaScript"),
+    ("graphql", "GraphQL", "JavaScript"),
+    ("gql", "GraphQL", "JavaScript"),
+    ("prisma", "Prisma", "JavaScript"),
+    ("proto", "Protobuf", "C++"),
+    ("thrift", "Thrift", "C++"),
+    ("ino", "Arduino", "C++"),
+    ("cu", "CUDA", "C++"),
+    ("cuh", "CUDA", "C++"),
+    ("metal", "Metal", "C++"),
+    ("glsl", "GLSL", "C"),
+    ("vert", "GLSL", "C"),
+    ("frag", "GLSL", "C"),
+    ("hlsl", "HLSL", "C"),
+    ("hx", "Haxe", "ActionScript"),
+    ("toml", "TOML", "Java Properties"),
+    ("ini", "INI", "Java Properties"),
+    ("cfg", "INI", "Java Properties"),
+    ("conf", "Config", "Java Properties"),
+    ("env", "dotenv", "Bourne Again Shell (bash)"),
+    ("zsh", "Zsh", "Bourne Again Shell (bash)"),
+    ("fish", "Fish", "Bourne Again Shell (bash)"),
+    ("ksh", "KornShell", "Bourne Again Shell (bash)"),
+    ("dockerfile", "Dockerfile", "Bourne Again Shell (bash)"),
+    ("containerfile", "Dockerfile", "Bourne Again Shell (bash)"),
+    ("cmake", "CMake", "Bourne Again Shell (bash)"),
+    ("cmakelists.txt", "CMake", "Bourne Again Shell (bash)"),
+    ("nix", "Nix", "Bourne Again Shell (bash)"),
+    ("gitignore", "Gitignore", "Bourne Again Shell (bash)"),
+    ("dockerignore", "Gitignore", "Bourne Again Shell (bash)"),
+    ("ps1", "PowerShell", "Perl"),
+    ("psm1", "PowerShell", "Perl"),
+    ("psd1", "PowerShell", "Perl"),
+    ("raku", "Raku", "Perl"),
+    ("awk", "AWK", "Perl"),
+    ("scss", "SCSS", "CSS"),
+    ("sass", "Sass", "CSS"),
+    ("less", "Less", "CSS"),
+    ("styl", "Stylus", "CSS"),
+    ("vue", "Vue", "HTML"),
+    ("svelte", "Svelte", "HTML"),
+    ("astro", "Astro", "HTML"),
+    ("hbs", "Handlebars", "HTML"),
+    ("mustache", "Mustache", "HTML"),
+    ("njk", "Nunjucks", "HTML"),
+    ("jinja", "Jinja", "HTML"),
+    ("j2", "Jinja", "HTML"),
+    ("twig", "Twig", "HTML"),
+    ("liquid", "Liquid", "HTML"),
+    ("ejs", "EJS", "HTML"),
+    ("svg", "SVG", "XML"),
+    ("csproj", "MSBuild", "XML"),
+    ("fsproj", "MSBuild", "XML"),
+    ("xaml", "XAML", "XML"),
+    ("plist", "Property List", "XML"),
+    ("el", "Emacs Lisp", "Lisp"),
+    ("scm", "Scheme", "Lisp"),
+    ("rkt", "Racket", "Lisp"),
+    ("fnl", "Fennel", "Lisp"),
+    ("cljs", "ClojureScript", "Clojure"),
+    ("edn", "EDN", "Clojure"),
+    ("jenkinsfile", "Jenkinsfile", "Groovy"),
+    ("justfile", "Just", "Makefile"),
+    ("mdx", "MDX", "Markdown"),
+    ("adoc", "AsciiDoc", "Markdown"),
+    ("psql", "PostgreSQL", "SQL"),
+    ("mysql", "MySQL", "SQL"),
+];
 
 /// A highlighted line segment with color info
 #[derive(Clone, Debug)]
@@ -92,34 +221,292 @@ pub fn supported_extensions() -> Vec<&'static str> {
     ]
 }
 
+/// Use `theme`'s colors for code from now on (called whenever the app
+/// theme changes)
+pub fn set_theme(theme: &Theme) {
+    let code = Arc::new(code_theme(theme));
+    if let Ok(mut current) = CODE_THEME.write() {
+        *current = code;
+    }
+    THEME_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn current_theme() -> Arc<CodeTheme> {
+    match CODE_THEME.read() {
+        Ok(theme) => theme.clone(),
+        Err(_) => Arc::new(code_theme(&Theme::default())),
+    }
+}
+
+fn color(color: Color32) -> Color {
+    Color {
+        r: color.r(),
+        g: color.g(),
+        b: color.b(),
+        a: 0xff,
+    }
+}
+
+/// A syntect theme coloring code scopes from an app theme's palette
+fn code_theme(theme: &Theme) -> CodeTheme {
+    let plain = FontStyle::empty();
+    let rules = [
+        (
+            "comment, punctuation.definition.comment",
+            theme.comment_color,
+            FontStyle::ITALIC,
+        ),
+        (
+            "string, punctuation.definition.string",
+            theme.string_color,
+            plain,
+        ),
+        (
+            "constant.character.escape, string.regexp",
+            theme.accent_secondary,
+            plain,
+        ),
+        (
+            "constant.numeric, constant.language, constant.character",
+            theme.number_color,
+            plain,
+        ),
+        (
+            "constant.other, support.constant, variable.other.constant",
+            theme.number_color,
+            plain,
+        ),
+        ("keyword, storage.modifier", theme.command_color, plain),
+        (
+            "keyword.operator, punctuation.separator.key-value",
+            theme.flag_color,
+            plain,
+        ),
+        (
+            "storage.type, entity.name.type, entity.name.class, support.type, support.class",
+            theme.warning_color,
+            plain,
+        ),
+        (
+            "entity.other.inherited-class, entity.name.namespace",
+            theme.path_color,
+            plain,
+        ),
+        (
+            "entity.name.function, support.function, variable.function",
+            theme.info_color,
+            plain,
+        ),
+        (
+            "variable.parameter, variable.other.member",
+            theme.branch_color,
+            plain,
+        ),
+        ("variable.language", theme.error_color, FontStyle::ITALIC),
+        ("entity.name.tag", theme.command_color, plain),
+        ("entity.other.attribute-name", theme.warning_color, plain),
+        (
+            "meta.preprocessor, meta.annotation, meta.attribute",
+            theme.accent_secondary,
+            plain,
+        ),
+        ("punctuation", theme.foreground_dim, plain),
+        (
+            "markup.heading, entity.name.section",
+            theme.accent,
+            FontStyle::BOLD,
+        ),
+        ("markup.bold", theme.foreground, FontStyle::BOLD),
+        ("markup.italic", theme.foreground, FontStyle::ITALIC),
+        ("markup.inserted", theme.success_color, plain),
+        ("markup.deleted, invalid", theme.error_color, plain),
+        ("markup.underline.link, markup.raw", theme.link_color, plain),
+    ];
+    let scopes = rules
+        .into_iter()
+        .filter_map(|(selector, fg, font_style)| {
+            Some(ThemeItem {
+                scope: selector.parse::<ScopeSelectors>().ok()?,
+                style: StyleModifier {
+                    foreground: Some(color(fg)),
+                    background: None,
+                    font_style: Some(font_style),
+                },
+            })
+        })
+        .collect();
+    CodeTheme {
+        name: Some("zaxiom".to_string()),
+        author: None,
+        settings: ThemeSettings {
+            foreground: Some(color(theme.foreground)),
+            background: Some(color(theme.background)),
+            ..ThemeSettings::default()
+        },
+        scopes,
+    }
+}
+
+/// Grammar for a file extension, file name (`Makefile`, `Dockerfile`), or
+/// language name (a markdown fence tag)
+pub fn find_syntax(token: &str) -> Option<&'static SyntaxReference> {
+    let set = &*SYNTAX_SET;
+    let lower = token.to_lowercase();
+    set.find_syntax_by_extension(token)
+        .or_else(|| set.find_syntax_by_extension(&lower))
+        .or_else(|| {
+            ALIASES
+                .iter()
+                .find(|(alias, name, _)| *alias == lower || name.to_lowercase() == lower)
+                .and_then(|(_, _, grammar)| set.find_syntax_by_name(grammar))
+        })
+        .or_else(|| set.find_syntax_by_token(token))
+        .filter(|syntax| syntax.name != "Plain Text")
+}
+
+/// Grammar for a file: by extension, then by name, then by a shebang or
+/// similar first line
+pub fn syntax_for_path(path: &Path, first_line: &str) -> Option<&'static SyntaxReference> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(find_syntax)
+        .or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(find_syntax)
+        })
+        .or_else(|| SYNTAX_SET.find_syntax_by_first_line(first_line))
+        .filter(|syntax| syntax.name != "Plain Text")
+}
+
 /// Check if a file extension is supported for highlighting
 pub fn is_supported(extension: &str) -> bool {
-    let ext_lower = extension.to_lowercase();
-    SYNTAX_SET.find_syntax_by_extension(&ext_lower).is_some()
+    find_syntax(extension).is_some()
 }
 
-/// Highlight source code and return colored segments
-pub fn highlight_code(code: &str, extension: &str) -> Option<Vec<HighlightedLine>> {
-    let syntax = SYNTAX_SET.find_syntax_by_extension(extension)?;
+/// Highlights a file a line at a time, carrying open strings and comments
+/// from one line to the next
+pub struct LineHighlighter {
+    theme: Arc<CodeTheme>,
+    parse: ParseState,
+    highlight: HighlightState,
+}
+
+impl LineHighlighter {
+    /// Highlighter for a grammar from `find_syntax` or `syntax_for_path`
+    pub fn new(syntax: &SyntaxReference) -> Self {
+        let theme = current_theme();
+        let highlight = HighlightState::new(&Highlighter::new(&theme), ScopeStack::new());
+        Self {
+            theme,
+            parse: ParseState::new(syntax),
+            highlight,
+        }
+    }
+
+    /// Highlighter for an extension, file name, or language name
+    pub fn for_token(token: &str) -> Option<Self> {
+        find_syntax(token).map(Self::new)
+    }
+
+    /// Colored segments of the next line (which should end with its `\n`)
+    pub fn line(&mut self, line: &str) -> HighlightedLine {
+        let Ok(ops) = self.parse.parse_line(line, &SYNTAX_SET) else {
+            return vec![self.plain(line)];
+        };
+        let highlighter = Highlighter::new(&self.theme);
+        HighlightIterator::new(&mut self.highlight, &ops, line, &highlighter)
+            .map(|(style, text)| style_to_segment(style, text))
+            .collect()
+    }
 
-    // Use a dark theme for terminal look
-    let theme = &THEME_SET.themes["base16-ocean.dark"];
-    let mut highlighter = HighlightLines::new(syntax, theme);
+    /// The next line as ANSI-colored text, without its line ending
+    pub fn ansi(&mut self, line: &str) -> String {
+        let mut line = line.trim_end_matches(['\n', '\r']).to_string();
+        line.push('\n');
+        segments_to_ansi(&self.line(&line))
+    }
+
+    fn plain(&self, text: &str) -> HighlightedSegment {
+        let fg = self.theme.settings.foreground.unwrap_or(Color::WHITE);
+        HighlightedSegment {
+            text: text.to_string(),
+            fg: (fg.r, fg.g, fg.b, fg.a),
+            bold: false,
+            italic: false,
+        }
+    }
+}
 
-    let mut result = Vec::new();
+/// Highlights the code in one file's diff lines: removed lines continue the
+/// old file, added lines the new one, and context lines both
+pub struct DiffHighlighter {
+    old: LineHighlighter,
+    new: LineHighlighter,
+}
 
-    for line in LinesWithEndings::from(code) {
-        let ranges = highlighter.highlight_line(line, &SYNTAX_SET).ok()?;
-        let mut highlighted_line = Vec::new();
+impl DiffHighlighter {
+    /// Highlighter for a changed file, or None when it has no grammar
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let found = syntax_for_path(path, "")?;
+        Some(Self {
+            old: LineHighlighter::new(found),
+            new: LineHighlighter::new(found),
+        })
+    }
 
-        for (style, text) in ranges {
-            highlighted_line.push(style_to_segment(style, text));
+    /// A diff line's code (what follows its `+`, `-`, or space) as ANSI text
+    pub fn line(&mut self, origin: char, content: &str) -> String {
+        match origin {
+            '+' => self.new.ansi(content),
+            '-' => self.old.ansi(content),
+            _ => {
+                self.old.ansi(content);
+                self.new.ansi(content)
+            }
         }
+    }
+}
+
+/// Colors for a file open in the editor, redone only when its text or the
+/// theme changes rather than on every frame
+#[derive(Default)]
+pub struct HighlightCache {
+    key: Option<(u64, usize)>,
+    lines: Vec<HighlightedLine>,
+}
 
-        result.push(highlighted_line);
+impl HighlightCache {
+    /// Each line's segments, or nothing for a file without a grammar
+    pub fn lines(&mut self, path: &Path, content: &str) -> &[HighlightedLine] {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        content.hash(&mut hasher);
+        let key = (hasher.finish(), THEME_GENERATION.load(Ordering::Relaxed));
+        if self.key != Some(key) {
+            self.key = Some(key);
+            self.lines = syntax_for_path(path, content.lines().next().unwrap_or_default())
+                .map(|found| {
+                    let mut highlighter = LineHighlighter::new(found);
+                    content
+                        .lines()
+                        .map(|line| highlighter.line(&format!("{}\n", line)))
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+        &self.lines
     }
+}
 
-    Some(result)
+/// Highlight source code and return colored segments
+pub fn highlight_code(code: &str, extension: &str) -> Option<Vec<HighlightedLine>> {
+    let mut highlighter = LineHighlighter::for_token(extension)?;
+    Some(
+        LinesWithEndings::from(code)
+            .map(|line| highlighter.line(line))
+            .collect(),
+    )
 }
 
 /// Convert syntect Style to our HighlightedSegment
@@ -132,38 +519,46 @@ fn style_to_segment(style: Style, text: &str) -> HighlightedSegment {
             style.foreground.b,
             style.foreground.a,
         ),
-        bold: style
-            .font_style
-            .contains(syntect::highlighting::FontStyle::BOLD),
-        italic: style
-            .font_style
-            .contains(syntect::highlighting::FontStyle::ITALIC),
+        bold: style.font_style.contains(FontStyle::BOLD),
+        italic: style.font_style.contains(FontStyle::ITALIC),
     }
 }
 
-/// Highlight code and format as ANSI colored string (for terminal output)
-pub fn highlight_to_ansi(code: &str, extension: &str) -> Option<String> {
-    let syntax = SYNTAX_SET.find_syntax_by_extension(extension)?;
-    let theme = &THEME_SET.themes["base16-ocean.dark"];
-    let mut highlighter = HighlightLines::new(syntax, theme);
-
+/// One line's segments as 24-bit ANSI color, without the line ending
+pub fn segments_to_ansi(segments: &[HighlightedSegment]) -> String {
     let mut output = String::new();
-
-    for line in LinesWithEndings::from(code) {
-        let ranges = highlighter.highlight_line(line, &SYNTAX_SET).ok()?;
-
-        for (style, text) in ranges {
-            // Convert to ANSI escape codes
-            let r = style.foreground.r;
-            let g = style.foreground.g;
-            let b = style.foreground.b;
-
-            // Use 24-bit true color ANSI codes
-            output.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text));
+    for segment in segments {
+        let text = segment.text.trim_end_matches(['\n', '\r']);
+        if text.is_empty() {
+            continue;
         }
+        let (r, g, b, _) = segment.fg;
+        let style = if segment.bold {
+            "1;"
+        } else if segment.italic {
+            "3;"
+        } else {
+            ""
+        };
+        output.push_str(&format!(
+            "\x1b[{}38;2;{};{};{}m{}\x1b[0m",
+            style, r, g, b, text
+        ));
     }
+    output
+}
 
-    Some(output)
+/// Highlight code and format as ANSI colored string (for terminal output)
+///
+/// Accepts a file extension or a language name such as a markdown fence tag.
+pub fn highlight_to_ansi(code: &str, extension: &str) -> Option<String> {
+    let mut highlighter = LineHighlighter::for_token(extension)?;
+    Some(
+        LinesWithEndings::from(code)
+            .map(|line| highlighter.ansi(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
 }
 
 /// Highlight code and return as plain text with inline color markers
@@ -187,10 +582,16 @@ pub fn highlight_with_markers(code: &str, extension: &str) -> Option<String> {
 }
 
 /// Get syntax name for a file extension
+///
+/// An aliased language is named for itself, not the grammar it borrows.
 pub fn get_syntax_name(extension: &str) -> Option<String> {
-    SYNTAX_SET
-        .find_syntax_by_extension(extension)
-        .map(|s| s.name.clone())
+    let lower = extension.to_lowercase();
+    ALIASES
+        .iter()
+        .find(|(alias, _, _)| *alias == lower)
+        .filter(|_| SYNTAX_SET.find_syntax_by_extension(extension).is_none())
+        .map(|(_, name, _)| name.to_string())
+        .or_else(|| find_syntax(extension).map(|s| s.name.clone()))
 }
 
 /// List all available syntax names
@@ -202,6 +603,19 @@ pub fn list_syntaxes() -> Vec<String> {
         .collect()
 }
 
+/// How many languages can be highlighted: the grammars plus the languages
+/// aliased onto them
+pub fn language_count() -> usize {
+    let mut names: HashSet<&str> = SYNTAX_SET
+        .syntaxes()
+        .iter()
+        .map(|s| s.name.as_str())
+        .filter(|name| *name != "Plain Text")
+        .collect();
+    names.extend(ALIASES.iter().map(|(_, name, _)| *name));
+    names.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,11 +637,54 @@ mod tests {
         assert!(is_supported("rs"));
         assert!(is_supported("py"));
         assert!(is_supported("js"));
+        assert!(is_supported("ts"));
+        assert!(is_supported("Dockerfile"));
+        assert!(!is_supported("txt"));
     }
 
     #[test]
     fn test_get_syntax_name() {
         assert_eq!(get_syntax_name("rs"), Some("Rust".to_string()));
         assert_eq!(get_syntax_name("py"), Some("Python".to_string()));
+        assert_eq!(get_syntax_name("kt"), Some("Kotlin".to_string()));
+    }
+
+    #[test]
+    fn test_languages() {
+        for (alias, _, grammar) in ALIASES {
+            assert!(
+                SYNTAX_SET.find_syntax_by_name(grammar).is_some(),
+                "{} uses missing grammar {}",
+                alias,
+                grammar
+            );
+        }
+        assert!(language_count() >= 100);
+        assert_eq!(find_syntax("rust").unwrap().name, "Rust");
+        let script = syntax_for_path(Path::new("deploy"), "#!/usr/bin/env python3\n");
+        assert_eq!(script.unwrap().name, "Python");
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let theme = Theme::default();
+        set_theme(&theme);
+        let mut highlighter = LineHighlighter::for_token("py").unwrap();
+        let line = highlighter.line("x = 'hi'  # note\n");
+        let segment = |text: &str| line.iter().find(|s| s.text.contains(text)).unwrap();
+        let rgba = |c: Color32| (c.r(), c.g(), c.b(), 0xff);
+        assert_eq!(segment("hi").fg, rgba(theme.string_color));
+        assert_eq!(segment("note").fg, rgba(theme.comment_color));
+        assert!(segment("note").italic);
+
+        let ansi = highlighter.ansi("y = 1\n");
+        assert!(ansi.contains("\x1b[38;2;"));
+        assert!(!ansi.ends_with('\n'));
+
+        let mut diff = DiffHighlighter::for_path(Path::new("src/main.rs")).unwrap();
+        let added = diff.line('+', "let s = \"x\";");
+        let rgb = theme.string_color;
+        assert!(added.contains(&format!("38;2;{};{};{}m", rgb.r(), rgb.g(), rgb.b())));
+        assert!(DiffHighlighter::for_path(Path::new("notes.txt")).is_none());
     }
 }
@@ -0,0 +1,371 @@
+//! Contextual tips
+//!
+//! Suggests features the user hasn't tried yet, at the moment they would
+//! help (e.g. hints mode right after copying a URL by hand). Features the
+//! user already uses are never suggested, dismissed tips never come back,
+//! and tips are spaced out so they don't become noise.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Commands between two tips
+const COMMANDS_BETWEEN_TIPS: usize = 8;
+
+/// How many times a tip is shown before giving up on it
+const MAX_SHOWS: u32 = 2;
+
+/// Something that happened which might make a tip relevant
+#[derive(Debug, Clone, Copy)]
+pub enum TipEvent<'a> {
+    /// A command finished
+    Command {
+        command: &'a str,
+        success: bool,
+        output_lines: usize,
+    },
+    /// The user copied text containing a URL by hand
+    UrlCopied,
+    /// The user pressed ↑ this many times in a row
+    HistoryScrolled(usize),
+    /// A new tab was opened
+    TabOpened,
+    /// A feature was used (may unlock follow-up tips)
+    FeatureUsed(&'static str),
+}
+
+/// When a tip becomes relevant
+#[derive(Debug, Clone, Copy)]
+enum Trigger {
+    UrlCopied,
+    HistoryScrolled(usize),
+    TabOpened,
+    AfterFeature(&'static str),
+    CommandFailed,
+    LongOutput(usize),
+    CommandPrefix(&'static str),
+    AfterCommands(usize),
+}
+
+/// A tip about one feature
+#[derive(Debug)]
+pub struct Tip {
+    /// Stable ID (persisted when dismissed)
+    pub id: &'static str,
+    /// Feature that makes this tip unnecessary once used
+    pub feature: &'static str,
+    /// Tip text
+    pub text: &'static str,
+    trigger: Trigger,
+}
+
+/// All tips, most specific triggers first
+pub static TIPS: &[Tip] = &[
+    Tip {
+        id: "hints",
+        feature: "hints",
+        text: "Ctrl+Shift+H (hints mode) picks URLs and paths out of the output for you~",
+        trigger: Trigger::UrlCopied,
+    },
+    Tip {
+        id: "history-search",
+        feature: "history_search",
+        text: "Ctrl+R fuzzy-searches your history instead of pressing ↑ over and over",
+        trigger: Trigger::HistoryScrolled(5),
+    },
+    Tip {
+        id: "split",
+        feature: "split",
+        text: "Ctrl+Shift+D / Ctrl+Shift+E split this tab instead of opening another one",
+        trigger: Trigger::TabOpened,
+    },
+    Tip {
+        id: "layout",
+        feature: "layout",
+        text: "'layout grid' or 'layout main-side' arranges all your panes in one go",
+        trigger: Trigger::AfterFeature("split"),
+    },
+    Tip {
+        id: "ai-explain",
+        feature: "ai",
+        text: "Ask '# why did that fail?' and the AI will look at what went wrong",
+        trigger: Trigger::CommandFailed,
+    },
+    Tip {
+        id: "summarize",
+        feature: "summarize",
+        text: "Pipe long output into 'summarize' for a quick digest (e.g. cargo build 2>&1 | summarize)",
+        trigger: Trigger::LongOutput(200),
+    },
+    Tip {
+        id: "search",
+        feature: "search",
+        text: "Ctrl+F searches the output - matches also show on the minimap",
+        trigger: Trigger::LongOutput(100),
+    },
+    Tip {
+        id: "cat-syntax",
+        feature: "cat_syntax",
+        text: "'cat -s file.rs' shows the file with syntax highlighting",
+        trigger: Trigger::CommandPrefix("cat "),
+    },
+    Tip {
+        id: "palette",
+        feature: "palette",
+        text: "Ctrl+P opens the command palette with every command and action",
+        trigger: Trigger::AfterCommands(20),
+    },
+    Tip {
+        id: "themes",
+        feature: "theme",
+        text: "'theme list' shows all 20 themes~",
+        trigger: Trigger::AfterCommands(40),
+    },
+    Tip {
+        id: "pet",
+        feature: "pet",
+        text: "Try 'pet' to interact with your robot companion! (◕‿◕)",
+        trigger: Trigger::AfterCommands(60),
+    },
+];
+
+/// Look up a tip by ID
+pub fn find_tip(id: &str) -> Option<&'static Tip> {
+    TIPS.iter().find(|tip| tip.id == id)
+}
+
+/// Features a command line uses
+pub fn features_in_command(command: &str) -> Vec<&'static str> {
+    let command = command.trim();
+    let mut features = Vec::new();
+    if command.starts_with('#') {
+        features.push("ai");
+    }
+    for segment in command.split('|') {
+        let mut words = segment.split_whitespace();
+        let feature = match words.next() {
+            Some("summarize") => "summarize",
+            Some("layout") => "layout",
+            Some("theme") => "theme",
+            Some("pet") => "pet",
+            Some("cat")
+                if segment
+                    .split_whitespace()
+                    .any(|w| w == "-s" || w == "--syntax") =>
+            {
+                "cat_syntax"
+            }
+            _ => continue,
+        };
+        features.push(feature);
+    }
+    features
+}
+
+/// What the user has used, seen, and dismissed (persisted)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TipsState {
+    /// Features the user has used
+    pub used: BTreeSet<String>,
+    /// Tips the user dismissed
+    pub dismissed: BTreeSet<String>,
+    /// How often each tip has been shown
+    pub shown: BTreeMap<String, u32>,
+    /// The most recently shown tip
+    pub last_tip: Option<String>,
+}
+
+impl TipsState {
+    /// Load from disk (empty if missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save to disk
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Path of the tips file
+    pub fn path() -> PathBuf {
+        crate::config::paths::data_dir().join("tips.json")
+    }
+
+    /// Whether a tip can still be shown at all
+    pub fn is_pending(&self, tip: &Tip) -> bool {
+        !self.used.contains(tip.feature)
+            && !self.dismissed.contains(tip.id)
+            && self.shown.get(tip.id).copied().unwrap_or(0) < MAX_SHOWS
+    }
+}
+
+/// Decides when to show which tip
+pub struct TipsEngine {
+    /// Global switch - when off, usage is still tracked but nothing is suggested
+    pub enabled: bool,
+    state: TipsState,
+    /// Commands run this session
+    commands: usize,
+    /// Commands since the last tip
+    commands_since_tip: usize,
+    /// Tips already shown this session
+    shown_this_session: HashSet<&'static str>,
+}
+
+impl TipsEngine {
+    /// Create an engine from the saved state
+    pub fn load() -> Self {
+        Self::with_state(TipsState::load())
+    }
+
+    fn with_state(state: TipsState) -> Self {
+        Self {
+            enabled: true,
+            state,
+            commands: 0,
+            commands_since_tip: COMMANDS_BETWEEN_TIPS,
+            shown_this_session: HashSet::new(),
+        }
+    }
+
+    /// Record that a feature was used, so its tip is never suggested
+    pub fn mark_used(&mut self, feature: &str) {
+        if self.state.used.insert(feature.to_string()) {
+            let _ = self.state.save();
+        }
+    }
+
+    /// Feed an event; returns a tip to show, if one fits
+    pub fn on_event(&mut self, event: TipEvent) -> Option<&'static Tip> {
+        match event {
+            TipEvent::Command { command, .. } => {
+                self.commands += 1;
+                self.commands_since_tip += 1;
+                for feature in features_in_command(command) {
+                    self.mark_used(feature);
+                }
+            }
+            TipEvent::FeatureUsed(feature) => self.mark_used(feature),
+            _ => {}
+        }
+
+        if !self.enabled || self.commands_since_tip < COMMANDS_BETWEEN_TIPS {
+            return None;
+        }
+
+        let tip = TIPS.iter().find(|tip| {
+            self.state.is_pending(tip)
+                && !self.shown_this_session.contains(tip.id)
+                && self.matches(tip.trigger, &event)
+        })?;
+
+        self.commands_since_tip = 0;
+        self.shown_this_session.insert(tip.id);
+        *self.state.shown.entry(tip.id.to_string()).or_default() += 1;
+        self.state.last_tip = Some(tip.id.to_string());
+        let _ = self.state.save();
+        Some(tip)
+    }
+
+    fn matches(&self, trigger: Trigger, event: &TipEvent) -> bool {
+        match (trigger, event) {
+            (Trigger::UrlCopied, TipEvent::UrlCopied) => true,
+            (Trigger::TabOpened, TipEvent::TabOpened) => true,
+            (Trigger::HistoryScrolled(min), TipEvent::HistoryScrolled(n)) => *n >= min,
+            (Trigger::AfterFeature(feature), TipEvent::FeatureUsed(used)) => feature == *used,
+            (Trigger::CommandFailed, TipEvent::Command { success, .. }) => !success,
+            (Trigger::LongOutput(min), TipEvent::Command { output_lines, .. }) => {
+                *output_lines >= min
+            }
+            (
+                Trigger::CommandPrefix(prefix),
+                TipEvent::Command {
+                    command, success, ..
+                },
+            ) => *success && command.trim_start().starts_with(prefix),
+            (Trigger::AfterCommands(min), TipEvent::Command { success, .. }) => {
+                *success && self.commands >= min
+            }
+            _ => false,
+        }
+    }
+
+    /// Never show the most recent tip again. Returns the dismissed tip.
+    pub fn dismiss_last(&mut self) -> Option<&'static Tip> {
+        let tip = find_tip(self.state.last_tip.as_deref()?)?;
+        self.state.dismissed.insert(tip.id.to_string());
+        let _ = self.state.save();
+        Some(tip)
+    }
+
+    /// Forget dismissed and shown tips (used features are kept)
+    pub fn reset(&mut self) {
+        self.state.dismissed.clear();
+        self.state.shown.clear();
+        self.state.last_tip = None;
+        self.shown_this_session.clear();
+        let _ = self.state.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> TipsEngine {
+        TipsEngine::with_state(TipsState::default())
+    }
+
+    fn matching(engine: &TipsEngine, event: TipEvent) -> Vec<&'static str> {
+        TIPS.iter()
+            .filter(|tip| engine.state.is_pending(tip) && engine.matches(tip.trigger, &event))
+            .map(|tip| tip.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_features_in_command() {
+        assert_eq!(features_in_command("# what is dns"), vec!["ai"]);
+        assert_eq!(
+            features_in_command("cargo build 2>&1 | summarize"),
+            vec!["summarize"]
+        );
+        assert_eq!(features_in_command("cat -s main.rs"), vec!["cat_syntax"]);
+        assert!(features_in_command("cat main.rs").is_empty());
+    }
+
+    #[test]
+    fn test_contextual_triggers() {
+        let engine = engine();
+        assert_eq!(matching(&engine, TipEvent::UrlCopied), vec!["hints"]);
+        assert!(matching(&engine, TipEvent::HistoryScrolled(2)).is_empty());
+        assert_eq!(
+            matching(&engine, TipEvent::HistoryScrolled(5)),
+            vec!["history-search"]
+        );
+    }
+
+    #[test]
+    fn test_used_features_are_not_suggested() {
+        let mut engine = engine();
+        engine.state.used.insert("hints".to_string());
+        assert!(matching(&engine, TipEvent::UrlCopied).is_empty());
+    }
+
+    #[test]
+    fn test_dismissed_tip_never_repeats() {
+        let mut engine = engine();
+        engine.state.last_tip = Some("hints".to_string());
+        engine.state.dismissed.insert("hints".to_string());
+        assert!(matching(&engine, TipEvent::UrlCopied).is_empty());
+    }
+}
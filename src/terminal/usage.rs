@@ -0,0 +1,181 @@
+//! Local usage statistics
+//!
+//! One JSONL line per executed command - when, where, how long, and whether
+//! it was an AI prompt - kept in the data directory and only ever read by
+//! the `stats` dashboard. Command text isn't stored and nothing is sent
+//! anywhere; `stats off` stops recording and `stats clear` deletes the file.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// Days shown in the commands-per-day chart
+pub const DAYS_SHOWN: usize = 14;
+
+/// Directories listed as the busiest
+const TOP_DIRECTORIES: usize = 5;
+
+/// One executed command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEntry {
+    /// When the command started (RFC 3339)
+    pub started: String,
+    /// Directory it ran in
+    pub cwd: String,
+    /// Run time (None for programs still running in the PTY)
+    pub duration_ms: Option<u64>,
+    pub success: bool,
+    /// A `#` AI prompt
+    pub ai: bool,
+}
+
+/// Default file location
+pub fn default_path() -> PathBuf {
+    crate::config::paths::data_dir().join("usage.jsonl")
+}
+
+/// Append an entry
+pub fn record(path: &Path, entry: &UsageEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Every readable entry (a missing file is an empty history)
+pub fn load(path: &Path) -> Vec<UsageEntry> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Figures shown by the dashboard
+#[derive(Debug, Clone, Default)]
+pub struct UsageStats {
+    pub total: usize,
+    pub failed: usize,
+    pub ai_prompts: usize,
+    /// Commands on each of the last `DAYS_SHOWN` days, oldest first
+    pub per_day: Vec<(NaiveDate, usize)>,
+    /// Commands started in each hour of the day
+    pub by_hour: [usize; 24],
+    /// Busiest directories, most commands first
+    pub top_directories: Vec<(String, usize)>,
+    /// Mean run time of commands that finished
+    pub average_ms: Option<u64>,
+}
+
+impl UsageStats {
+    /// Summarize entries, with the per-day chart ending on `today`
+    pub fn from_entries(entries: &[UsageEntry], today: NaiveDate) -> Self {
+        let mut stats = Self {
+            total: entries.len(),
+            ..Default::default()
+        };
+        let mut days: HashMap<NaiveDate, usize> = HashMap::new();
+        let mut directories: HashMap<&str, usize> = HashMap::new();
+        let (mut timed, mut total_ms) = (0u64, 0u64);
+
+        for entry in entries {
+            stats.failed += usize::from(!entry.success);
+            stats.ai_prompts += usize::from(entry.ai);
+            *directories.entry(entry.cwd.as_str()).or_insert(0) += 1;
+            if let Some(ms) = entry.duration_ms {
+                timed += 1;
+                total_ms += ms;
+            }
+            if let Ok(started) = DateTime::parse_from_rfc3339(&entry.started) {
+                let started = started.with_timezone(&Local);
+                *days.entry(started.date_naive()).or_insert(0) += 1;
+                stats.by_hour[started.hour() as usize] += 1;
+            }
+        }
+
+        stats.per_day = (0..DAYS_SHOWN as u64)
+            .rev()
+            .filter_map(|back| today.checked_sub_days(chrono::Days::new(back)))
+            .map(|day| (day, days.get(&day).copied().unwrap_or(0)))
+            .collect();
+
+        let mut directories: Vec<_> = directories
+            .into_iter()
+            .map(|(dir, count)| (dir.to_string(), count))
+            .collect();
+        directories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        directories.truncate(TOP_DIRECTORIES);
+        stats.top_directories = directories;
+
+        stats.average_ms = (timed > 0).then(|| total_ms / timed);
+        stats
+    }
+
+    /// Load and summarize the log at `path`
+    pub fn load(path: &Path) -> Self {
+        Self::from_entries(&load(path), Local::now().date_naive())
+    }
+
+    /// Hour with the most commands
+    pub fn busiest_hour(&self) -> Option<usize> {
+        (0..24)
+            .filter(|&hour| self.by_hour[hour] > 0)
+            .max_by_key(|&hour| (self.by_hour[hour], std::cmp::Reverse(hour)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(day: u32, hour: u32, cwd: &str, duration_ms: Option<u64>, ai: bool) -> UsageEntry {
+        let started = Local.with_ymd_and_hms(2026, 3, day, hour, 15, 0).unwrap();
+        UsageEntry {
+            started: started.to_rfc3339(),
+            cwd: cwd.to_string(),
+            duration_ms,
+            success: !ai,
+            ai,
+        }
+    }
+
+    #[test]
+    fn test_from_entries() {
+        let entries = [
+            entry(9, 10, "/work", Some(100), false),
+            entry(9, 10, "/work", Some(300), false),
+            entry(10, 22, "/home", None, false),
+            entry(10, 10, "/work", Some(200), true),
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let stats = UsageStats::from_entries(&entries, today);
+
+        assert_eq!((stats.total, stats.failed, stats.ai_prompts), (4, 1, 1));
+        assert_eq!(stats.per_day.len(), DAYS_SHOWN);
+        assert_eq!(stats.per_day.last(), Some(&(today, 2)));
+        assert_eq!(stats.per_day[DAYS_SHOWN - 2].1, 2);
+        assert_eq!(stats.per_day[0].1, 0);
+        assert_eq!(stats.by_hour[10], 3);
+        assert_eq!(stats.busiest_hour(), Some(10));
+        assert_eq!(
+            stats.top_directories,
+            vec![("/work".to_string(), 3), ("/home".to_string(), 1)]
+        );
+        assert_eq!(stats.average_ms, Some(200));
+
+        let empty = UsageStats::from_entries(&[], today);
+        assert_eq!(empty.busiest_hour(), None);
+        assert_eq!(empty.average_ms, None);
+    }
+}
@@ -5,6 +5,10 @@
 
 #![allow(dead_code)]
 
+use eframe::egui;
+
+use super::export::ExportFormat;
+
 /// Vi mode state
 #[derive(Clone, Debug, PartialEq)]
 pub enum ViState {
@@ -457,6 +461,23 @@ impl ViMode {
                 action
             }
 
+            // Yank selected lines with their colors (as HTML / with ANSI codes)
+            'H' | 'A' => {
+                let Some(sel) = self.selection.take() else {
+                    return ViAction::None;
+                };
+                self.state = ViState::Normal;
+                ViAction::YankStyled {
+                    start: sel.start.line.min(sel.end.line),
+                    end: sel.start.line.max(sel.end.line),
+                    format: if key == 'H' {
+                        ExportFormat::Html
+                    } else {
+                        ExportFormat::Ansi
+                    },
+                }
+            }
+
             // Switch visual modes
             '\x16' => {
                 // Ctrl+V - toggle block mode
@@ -623,6 +644,12 @@ pub enum ViAction {
     YankSelection,
     /// Yank to end of line
     YankToEnd,
+    /// Yank lines `start..=end` keeping their colors
+    YankStyled {
+        start: usize,
+        end: usize,
+        format: ExportFormat,
+    },
     /// Set a mark
     SetMark,
     /// Go to a mark
@@ -633,6 +660,107 @@ pub enum ViAction {
     Paste,
 }
 
+/// The key pressed this frame as the character vi mode reads: letters
+/// (uppercase with Shift), digits, `$ ^ / ? -`, space, Escape, Enter, and
+/// Backspace, or Ctrl+F/B/D/U/O/I as control characters. The pager reads
+/// keys the same way.
+pub fn key_char(i: &egui::InputState) -> Option<char> {
+    use egui::Key;
+
+    if i.modifiers.alt {
+        return None;
+    }
+    if i.modifiers.ctrl {
+        // Page down/up, half page down/up, jump back/forward
+        return [
+            (Key::F, '\x06'),
+            (Key::B, '\x02'),
+            (Key::D, '\x04'),
+            (Key::U, '\x15'),
+            (Key::O, '\x0f'),
+            (Key::I, '\x09'),
+        ]
+        .into_iter()
+        .find(|&(key, _)| i.key_pressed(key))
+        .map(|(_, ch)| ch);
+    }
+
+    let mut pressed = None;
+    let letters = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+    ];
+    if let Some(index) = letters.iter().position(|&key| i.key_pressed(key)) {
+        let ch = (b'a' + index as u8) as char;
+        pressed = Some(if i.modifiers.shift {
+            ch.to_ascii_uppercase()
+        } else {
+            ch
+        });
+    }
+    let digits = [
+        Key::Num0,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+    if let Some(digit) = digits.iter().position(|&key| i.key_pressed(key)) {
+        pressed = char::from_digit(digit as u32, 10);
+    }
+    for (key, ch) in [
+        (Key::Escape, '\x1b'),
+        (Key::Enter, '\n'),
+        (Key::Backspace, '\x08'),
+        (Key::Minus, '-'),
+        (Key::Space, ' '),
+    ] {
+        if i.key_pressed(key) {
+            pressed = Some(ch);
+        }
+    }
+    // Shift+4 = $, Shift+6 = ^, Shift+/ = ?
+    if i.modifiers.shift {
+        for (key, ch) in [(Key::Num4, '$'), (Key::Num6, '^'), (Key::Slash, '?')] {
+            if i.key_pressed(key) {
+                pressed = Some(ch);
+            }
+        }
+    } else if i.key_pressed(Key::Slash) {
+        pressed = Some('/');
+    }
+    pressed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -687,4 +815,25 @@ mod tests {
         assert_eq!(vi.state, ViState::Normal);
         assert!(vi.selection.is_none());
     }
+
+    #[test]
+    fn test_yank_styled() {
+        let mut vi = ViMode::new();
+        vi.enter(100);
+        vi.max_col = 80;
+
+        vi.handle_key('3');
+        vi.handle_key('j');
+        vi.handle_key('V');
+        vi.handle_key('k');
+        vi.handle_key('k');
+        match vi.handle_key('H') {
+            ViAction::YankStyled { start, end, format } => {
+                assert_eq!((start, end, format), (1, 3, ExportFormat::Html));
+            }
+            other => panic!("expected YankStyled, got {:?}", other),
+        }
+        assert_eq!(vi.state, ViState::Normal);
+        assert!(vi.selection.is_none());
+    }
 }